@@ -6,6 +6,7 @@ use log::*;
 use nym_bandwidth_controller::acquire::state::State;
 use nym_bin_common::completions::ArgShell;
 use nym_credential_storage::persistent_storage::PersistentStorage;
+use nym_credential_storage::storage::Storage;
 use nym_validator_client::nyxd::traits::DkgQueryClient;
 
 use crate::error::Result;
@@ -16,6 +17,9 @@ pub(crate) enum Command {
     /// Run the binary to obtain a credential
     Run(Run),
 
+    /// List the unspent credentials and the bandwidth remaining at the gateways
+    Balance(Balance),
+
     /// Generate shell completions
     Completions(ArgShell),
 
@@ -43,6 +47,12 @@ pub(crate) struct Run {
     #[clap(long, default_value = "0")]
     pub(crate) amount: u64,
 
+    /// Split the deposit into multiple vouchers worth the specified amounts of utokens,
+    /// e.g. `--denominations 500000,250000,250000`. They have to add up to the deposited amount.
+    /// If not provided, a single voucher worth the whole amount is obtained
+    #[clap(long, value_delimiter = ',')]
+    pub(crate) denominations: Vec<u64>,
+
     /// Path to a directory used to store recovery files for unconsumed deposits
     #[clap(long)]
     pub(crate) recovery_dir: std::path::PathBuf,
@@ -52,12 +62,19 @@ pub(crate) struct Run {
     pub(crate) recovery_mode: bool,
 }
 
+#[derive(Args)]
+pub(crate) struct Balance {
+    /// Home directory of the client whose credentials are to be listed.
+    #[clap(long)]
+    pub(crate) client_home_directory: std::path::PathBuf,
+}
+
 pub(crate) async fn recover_credentials<C: DkgQueryClient + Send + Sync>(
     client: &C,
     recovery_storage: &RecoveryStorage,
     shared_storage: &PersistentStorage,
 ) -> Result<()> {
-    for voucher in recovery_storage.unconsumed_vouchers()? {
+    for (voucher_path, voucher) in recovery_storage.unconsumed_vouchers()? {
         let state = State::new(voucher);
         if let Err(e) =
             nym_bandwidth_controller::acquire::get_credential(&state, client, shared_storage).await
@@ -72,7 +89,7 @@ pub(crate) async fn recover_credentials<C: DkgQueryClient + Send + Sync>(
                 "Converted deposit {} to a credential, removing recovery data for it",
                 state.voucher.tx_hash()
             );
-            if let Err(e) = recovery_storage.remove_voucher(state.voucher.tx_hash().to_string()) {
+            if let Err(e) = recovery_storage.remove_voucher(&voucher_path) {
                 warn!("Could not remove recovery data - {:?}", e);
             }
         }
@@ -80,3 +97,41 @@ pub(crate) async fn recover_credentials<C: DkgQueryClient + Send + Sync>(
 
     Ok(())
 }
+
+pub(crate) async fn show_balance<St: Storage>(storage: &St) -> Result<()> {
+    let credentials = storage.get_all_coconut_credentials().await?;
+    let unspent = credentials
+        .iter()
+        .filter(|credential| !credential.consumed)
+        .collect::<Vec<_>>();
+    let total_value = unspent
+        .iter()
+        .filter_map(|credential| credential.value())
+        .sum::<u64>();
+
+    println!("Unspent credentials: {}", unspent.len());
+    for credential in &unspent {
+        println!(
+            "\t#{}: {} utokens (epoch {})",
+            credential.id, credential.voucher_value, credential.epoch_id
+        );
+    }
+    println!("Total value of the unspent credentials: {total_value} utokens");
+
+    let gateway_bandwidths = storage.get_gateway_bandwidths().await?;
+    if !gateway_bandwidths.is_empty() {
+        println!("Bandwidth remaining at gateways:");
+    }
+    for gateway in gateway_bandwidths {
+        let spent = credentials
+            .iter()
+            .filter(|credential| credential.gateway_id.as_ref() == Some(&gateway.gateway_id))
+            .count();
+        println!(
+            "\t{}: {} bytes ({} credential(s) spent)",
+            gateway.gateway_id, gateway.remaining_bandwidth, spent
+        );
+    }
+
+    Ok(())
+}
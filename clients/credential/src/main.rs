@@ -11,6 +11,7 @@ use log::*;
 use nym_bin_common::completions::fig_generate;
 use nym_config::{CRED_DB_FILE_NAME, DATA_DIR};
 use nym_network_defaults::{setup_env, NymNetworkDetails};
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::time::{Duration, SystemTime};
 
@@ -33,6 +34,10 @@ struct Cli {
     pub(crate) command: Command,
 }
 
+fn credentials_database(client_home_directory: &Path) -> PathBuf {
+    client_home_directory.join(DATA_DIR).join(CRED_DB_FILE_NAME)
+}
+
 async fn block_until_coconut_is_available<C: CosmWasmClient + Send + Sync>(
     client: &nym_validator_client::Client<C>,
 ) -> Result<()> {
@@ -71,12 +76,10 @@ async fn main() -> Result<()> {
 
     match args.command {
         Command::Run(r) => {
-            let db_path = r
-                .client_home_directory
-                .join(DATA_DIR)
-                .join(CRED_DB_FILE_NAME);
-            let shared_storage =
-                nym_credential_storage::initialise_persistent_storage(db_path).await;
+            let shared_storage = nym_credential_storage::initialise_persistent_storage(
+                credentials_database(&r.client_home_directory),
+            )
+            .await;
             let recovery_storage = recovery_storage::RecoveryStorage::new(r.recovery_dir)?;
 
             let network_details = NymNetworkDetails::new_from_env();
@@ -94,23 +97,38 @@ async fn main() -> Result<()> {
             info!("Starting depositing funds, don't kill the process");
 
             if !r.recovery_mode {
-                let state =
-                    nym_bandwidth_controller::acquire::deposit(&client.nyxd, amount).await?;
-                if nym_bandwidth_controller::acquire::get_credential(
-                    &state,
-                    &client,
-                    &shared_storage,
-                )
-                .await
-                .is_err()
-                {
-                    warn!("Failed to obtain credential. Dumping recovery data.",);
-                    match recovery_storage.insert_voucher(&state.voucher) {
-                        Ok(file_path) => {
-                            warn!("Dumped recovery data to {:?}. Try using recovery mode to convert it to a credential", file_path);
-                        }
-                        Err(e) => {
-                            error!("Could not dump recovery data to file system due to {:?}, the deposit will be lost!", e)
+                let states = if r.denominations.is_empty() {
+                    vec![nym_bandwidth_controller::acquire::deposit(&client.nyxd, amount).await?]
+                } else {
+                    let denominations = r
+                        .denominations
+                        .iter()
+                        .map(|denomination| *denomination as u128)
+                        .collect::<Vec<_>>();
+                    nym_bandwidth_controller::acquire::deposit_with_denominations(
+                        &client.nyxd,
+                        amount,
+                        &denominations,
+                    )
+                    .await?
+                };
+                for state in states {
+                    if nym_bandwidth_controller::acquire::get_credential(
+                        &state,
+                        &client,
+                        &shared_storage,
+                    )
+                    .await
+                    .is_err()
+                    {
+                        warn!("Failed to obtain credential. Dumping recovery data.",);
+                        match recovery_storage.insert_voucher(&state.voucher) {
+                            Ok(file_path) => {
+                                warn!("Dumped recovery data to {:?}. Try using recovery mode to convert it to a credential", file_path);
+                            }
+                            Err(e) => {
+                                error!("Could not dump recovery data to file system due to {:?}, the deposit will be lost!", e)
+                            }
                         }
                     }
                 }
@@ -118,6 +136,13 @@ async fn main() -> Result<()> {
                 recover_credentials(&client.nyxd, &recovery_storage, &shared_storage).await?;
             }
         }
+        Command::Balance(b) => {
+            let shared_storage = nym_credential_storage::initialise_persistent_storage(
+                credentials_database(&b.client_home_directory),
+            )
+            .await;
+            show_balance(&shared_storage).await?;
+        }
         Command::Completions(c) => c.generate(&mut Cli::command(), bin_name),
         Command::GenerateFigSpec => fig_generate(&mut Cli::command(), bin_name),
     }
@@ -4,7 +4,7 @@
 use nym_credentials::coconut::bandwidth::BandwidthVoucher;
 use std::fs::{create_dir_all, read_dir, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct RecoveryStorage {
     recovery_dir: PathBuf,
@@ -16,7 +16,9 @@ impl RecoveryStorage {
         Ok(Self { recovery_dir })
     }
 
-    pub fn unconsumed_vouchers(&self) -> std::io::Result<impl Iterator<Item = BandwidthVoucher>> {
+    pub fn unconsumed_vouchers(
+        &self,
+    ) -> std::io::Result<impl Iterator<Item = (PathBuf, BandwidthVoucher)>> {
         Ok(read_dir(&self.recovery_dir)?
             .filter_map(|entry| entry.ok())
             .filter_map(|entry| {
@@ -27,21 +29,32 @@ impl RecoveryStorage {
                     None
                 }
             })
-            .filter_map(|path| File::open(path).ok())
-            .filter_map(|mut f| {
+            .filter_map(|path| File::open(&path).ok().map(|f| (path, f)))
+            .filter_map(|(path, mut f)| {
                 let mut buff = Vec::new();
                 if f.read_to_end(&mut buff).is_ok() {
-                    Some(buff)
+                    Some((path, buff))
                 } else {
                     None
                 }
             })
-            .filter_map(|buff| BandwidthVoucher::try_from_bytes(&buff).ok()))
+            .filter_map(|(path, buff)| {
+                BandwidthVoucher::try_from_bytes(&buff)
+                    .ok()
+                    .map(|voucher| (path, voucher))
+            }))
     }
 
     pub fn insert_voucher(&self, voucher: &BandwidthVoucher) -> std::io::Result<PathBuf> {
-        let file_name = voucher.tx_hash().to_string();
-        let file_path = self.recovery_dir.join(file_name);
+        let tx_hash = voucher.tx_hash().to_string();
+        // a single deposit might have been split into multiple vouchers
+        let file_path = (0..)
+            .map(|i| match i {
+                0 => self.recovery_dir.join(&tx_hash),
+                i => self.recovery_dir.join(format!("{tx_hash}-{i}")),
+            })
+            .find(|path| !path.exists())
+            .expect("run out of recovery file names");
         let mut file = File::create(&file_path)?;
         let buff = voucher.to_bytes();
         file.write_all(&buff)?;
@@ -49,8 +62,7 @@ impl RecoveryStorage {
         Ok(file_path)
     }
 
-    pub fn remove_voucher(&self, file_name: String) -> std::io::Result<()> {
-        let file_path = self.recovery_dir.join(file_name);
+    pub fn remove_voucher(&self, file_path: &Path) -> std::io::Result<()> {
         std::fs::remove_file(file_path)
     }
 }
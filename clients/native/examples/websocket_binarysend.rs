@@ -1,6 +1,7 @@
 use futures::{SinkExt, StreamExt};
 use nym_client_websocket_requests::{requests::ClientRequest, responses::ServerResponse};
 use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::message::MessageCompression;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{
     connect_async, tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream,
@@ -93,6 +94,7 @@ async fn send_file_without_reply() {
         recipient,
         message: read_data,
         connection_id: Some(0),
+        compression: MessageCompression::None,
     };
 
     println!("sending content of 'dummy_file' over the mix network...");
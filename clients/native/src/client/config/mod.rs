@@ -123,6 +123,11 @@ impl Config {
         self
     }
 
+    pub fn with_metrics_port(mut self, port: u16) -> Self {
+        self.socket.metrics_port = port;
+        self
+    }
+
     // getters
     pub fn get_config_file_save_location(&self) -> PathBuf {
         self.config_directory().join(Self::config_file_name())
@@ -152,6 +157,10 @@ impl Config {
         self.socket.listening_port
     }
 
+    pub fn get_metrics_port(&self) -> u16 {
+        self.socket.metrics_port
+    }
+
     // poor man's 'builder' method
     pub fn with_base<F, T>(mut self, f: F, val: T) -> Self
     where
@@ -203,6 +212,9 @@ pub struct Socket {
     socket_type: SocketType,
     host: IpAddr,
     listening_port: u16,
+
+    /// Port on which the prometheus metrics are exposed. A value of 0 disables the metrics server.
+    metrics_port: u16,
 }
 
 impl Default for Socket {
@@ -211,6 +223,7 @@ impl Default for Socket {
             socket_type: SocketType::WebSocket,
             host: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             listening_port: DEFAULT_WEBSOCKET_LISTENING_PORT,
+            metrics_port: 0,
         }
     }
 }
@@ -80,6 +80,15 @@ gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
 # Address of the gateway listener to which all client requests should be sent.
 gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
 
+# Ranked list of additional gateways the client has registered with. If the client fails
+# to reconnect to its current gateway, it switches to the first reachable one out of those.
+{{#each client.fallback_gateway_endpoints }}
+[[client.fallback_gateway_endpoints]]
+gateway_id = '{{this.gateway_id}}'
+gateway_owner = '{{this.gateway_owner}}'
+gateway_listener = '{{this.gateway_listener}}'
+{{/each}}
+
 
 
 ##### socket config options #####
@@ -97,6 +106,10 @@ listening_port = {{ socket.listening_port }}
 # will be listening for incoming requests
 host = '{{ socket.host }}'
 
+# the port on which the prometheus metrics will be exposed (under the same ip address as above).
+# a value of 0 disables the metrics server
+metrics_port = {{ socket.metrics_port }}
+
 ##### logging configuration options #####
 
 [logging]
@@ -9,17 +9,20 @@ use log::*;
 use nym_bandwidth_controller::BandwidthController;
 use nym_client_core::client::base_client::{
     non_wasm_helpers, BaseClientBuilder, ClientInput, ClientOutput, ClientState,
+    GatewayConfigPersister,
 };
 use nym_client_core::client::inbound_messages::InputMessage;
 use nym_client_core::client::received_buffer::{
     ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
 };
 use nym_client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use nym_config::NymConfig;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_task::connections::TransmissionLane;
 use nym_task::TaskManager;
 use nym_validator_client::nyxd::QueryNyxdClient;
 use std::error::Error;
+use std::net::SocketAddr;
 use tokio::sync::watch::error::SendError;
 
 pub use nym_client_core::client::key_manager::KeyManager;
@@ -57,6 +60,22 @@ impl SocketClient {
         }
     }
 
+    // once the client fails over to a different gateway, make it the main one in the config file
+    // (rather than the one in memory that might include command line overrides)
+    fn gateway_config_persister(&self) -> GatewayConfigPersister {
+        let id = self.config.get_base().get_id();
+        Box::new(move |active_gateway, fallback_gateways| {
+            let mut config = Config::load_from_file(&id)?;
+            config
+                .get_base_mut()
+                .set_gateway_endpoint(active_gateway.clone());
+            config
+                .get_base_mut()
+                .set_fallback_gateway_endpoints(fallback_gateways.to_vec());
+            config.save_to_file(None)
+        })
+    }
+
     async fn create_bandwidth_controller(
         config: &Config,
     ) -> BandwidthController<Client<QueryNyxdClient>, PersistentStorage> {
@@ -92,7 +111,6 @@ impl SocketClient {
         client_input: ClientInput,
         client_output: ClientOutput,
         client_state: ClientState,
-        self_address: &Recipient,
         shutdown: nym_task::TaskClient,
     ) {
         info!("Starting websocket listener...");
@@ -109,6 +127,7 @@ impl SocketClient {
         let ClientState {
             shared_lane_queue_lengths,
             reply_controller_sender,
+            self_address,
             ..
         } = client_state;
 
@@ -139,6 +158,8 @@ impl SocketClient {
             return Err(ClientError::InvalidSocketMode);
         }
 
+        let gateway_config_persister = self.gateway_config_persister();
+
         // don't create bandwidth controller if credentials are disabled
         let bandwidth_controller = if self.config.get_base().get_disabled_credentials_mode() {
             None
@@ -155,7 +176,8 @@ impl SocketClient {
                 self.config.get_debug_settings(),
             )
             .await?,
-        );
+        )
+        .with_gateway_config_persister(gateway_config_persister);
 
         let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
@@ -168,10 +190,18 @@ impl SocketClient {
             client_input,
             client_output,
             client_state,
-            &self_address,
             started_client.task_manager.subscribe(),
         );
 
+        let metrics_port = self.config.get_metrics_port();
+        if metrics_port != 0 {
+            nym_metrics::MetricsServer::new(SocketAddr::new(
+                self.config.get_listening_ip(),
+                metrics_port,
+            ))
+            .start(started_client.task_manager.subscribe());
+        }
+
         info!("Client startup finished!");
         info!("The address of this client is: {}", self_address);
 
@@ -183,6 +213,8 @@ impl SocketClient {
             return Err(ClientError::InvalidSocketMode);
         }
 
+        let gateway_config_persister = self.gateway_config_persister();
+
         // don't create bandwidth controller if credentials are disabled
         let bandwidth_controller = if self.config.get_base().get_disabled_credentials_mode() {
             None
@@ -199,7 +231,8 @@ impl SocketClient {
                 self.config.get_debug_settings(),
             )
             .await?,
-        );
+        )
+        .with_gateway_config_persister(gateway_config_persister);
 
         let address = base_client.as_mix_recipient();
 
@@ -0,0 +1,36 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::config::Config;
+use crate::error::ClientError;
+use clap::Args;
+use log::error;
+use nym_config::NymConfig;
+use nym_pemstore::PASSPHRASE_ENV_VAR;
+
+#[derive(Args, Clone)]
+pub(crate) struct EncryptKeys {
+    /// Id of the nym-client whose keys should be encrypted
+    #[clap(long)]
+    id: String,
+}
+
+pub(crate) fn execute(args: &EncryptKeys) -> Result<(), ClientError> {
+    let config = match Config::load_from_file(&args.id) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!("Failed to load config for {}. Are you sure you have run `init` before? (Error was: {err})", args.id);
+            return Err(ClientError::FailedToLoadConfig(args.id.clone()));
+        }
+    };
+
+    let encrypted = nym_client_core::init::encrypt_stored_keys(config.get_base())?;
+    for path in &encrypted {
+        eprintln!("Encrypted {}", path.display());
+    }
+    eprintln!(
+        "Encrypted {} key(s). Remember to set {PASSPHRASE_ENV_VAR} whenever running the client",
+        encrypted.len()
+    );
+    Ok(())
+}
@@ -38,6 +38,11 @@ pub(crate) struct Init {
     #[clap(long)]
     force_register_gateway: bool,
 
+    /// Number of additional gateways to register with that the client is going to fail over to
+    /// if its main gateway becomes unreachable.
+    #[clap(long, default_value_t = 0)]
+    fallback_gateways: usize,
+
     /// Comma separated list of rest endpoints of the nyxd validators
     #[clap(long, alias = "nyxd_validators", value_delimiter = ',', hide = true)]
     nyxd_urls: Option<Vec<url::Url>>,
@@ -163,6 +168,21 @@ pub(crate) async fn execute(args: &Init) -> Result<(), ClientError> {
 
     config.get_base_mut().set_gateway_endpoint(gateway);
 
+    // Fallback gateways are only (re-)registered with alongside the main gateway
+    if register_gateway && args.fallback_gateways > 0 {
+        let fallback_gateways =
+            nym_client_core::init::setup_fallback_gateways::<_, PersistentStorage>(
+                config.get_base(),
+                args.fallback_gateways,
+                args.latency_based_selection,
+            )
+            .await
+            .tap_err(|err| eprintln!("Failed to setup fallback gateways\nError: {err}"))?;
+        config
+            .get_base_mut()
+            .set_fallback_gateway_endpoints(fallback_gateways);
+    }
+
     config.save_to_file(None).tap_err(|_| {
         log::error!("Failed to save the config file");
     })?;
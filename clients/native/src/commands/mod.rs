@@ -13,6 +13,7 @@ use nym_config::{NymConfig, OptionalSet};
 use std::error::Error;
 use std::net::IpAddr;
 
+pub(crate) mod encrypt_keys;
 pub(crate) mod init;
 pub(crate) mod run;
 pub(crate) mod upgrade;
@@ -42,6 +43,8 @@ pub(crate) struct Cli {
 pub(crate) enum Commands {
     /// Initialise a Nym client. Do this first!
     Init(init::Init),
+    /// Encrypt, in place, the private keys of the client using the passphrase from the environment
+    EncryptKeys(encrypt_keys::EncryptKeys),
     /// Run the Nym client with provided configuration client optionally overriding set parameters
     Run(run::Run),
     /// Try to upgrade the client
@@ -70,6 +73,7 @@ pub(crate) async fn execute(args: &Cli) -> Result<(), Box<dyn Error + Send + Syn
     let bin_name = "nym-native-client";
 
     match &args.command {
+        Commands::EncryptKeys(m) => encrypt_keys::execute(m)?,
         Commands::Init(m) => init::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Upgrade(m) => upgrade::execute(m),
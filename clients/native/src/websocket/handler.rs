@@ -5,6 +5,7 @@ use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
 use log::*;
 use nym_client_core::client::replies::reply_controller::requests::ReplyControllerSender;
+use nym_client_core::client::self_address::SelfAddressAccessor;
 use nym_client_core::client::{
     inbound_messages::{InputMessage, InputMessageSender},
     received_buffer::{
@@ -14,6 +15,7 @@ use nym_client_core::client::{
 use nym_client_websocket_requests::{requests::ClientRequest, responses::ServerResponse};
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::message::MessageCompression;
 use nym_sphinx::receiver::ReconstructedMessage;
 use nym_task::connections::{
     ConnectionCommand, ConnectionCommandSender, ConnectionId, LaneQueueLengths, TransmissionLane,
@@ -38,7 +40,7 @@ pub(crate) struct HandlerBuilder {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: SelfAddressAccessor,
     lane_queue_lengths: LaneQueueLengths,
     reply_controller_sender: ReplyControllerSender,
 }
@@ -48,7 +50,7 @@ impl HandlerBuilder {
         msg_input: InputMessageSender,
         client_connection_tx: ConnectionCommandSender,
         buffer_requester: ReceivedBufferRequestSender,
-        self_full_address: &Recipient,
+        self_full_address: SelfAddressAccessor,
         lane_queue_lengths: LaneQueueLengths,
         reply_controller_sender: ReplyControllerSender,
     ) -> Self {
@@ -56,7 +58,7 @@ impl HandlerBuilder {
             msg_input,
             client_connection_tx,
             buffer_requester,
-            self_full_address: *self_full_address,
+            self_full_address,
             lane_queue_lengths,
             reply_controller_sender,
         }
@@ -68,7 +70,7 @@ impl HandlerBuilder {
             msg_input: self.msg_input.clone(),
             client_connection_tx: self.client_connection_tx.clone(),
             buffer_requester: self.buffer_requester.clone(),
-            self_full_address: self.self_full_address,
+            self_full_address: self.self_full_address.clone(),
             socket: None,
             received_response_type: Default::default(),
             lane_queue_lengths: self.lane_queue_lengths.clone(),
@@ -81,7 +83,7 @@ pub(crate) struct Handler {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: SelfAddressAccessor,
     socket: Option<WebSocketStream<TcpStream>>,
     received_response_type: ReceivedResponseType,
     lane_queue_lengths: LaneQueueLengths,
@@ -148,6 +150,7 @@ impl Handler {
         recipient: Recipient,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     ) -> Option<ServerResponse> {
         info!(
             "Attempting to send {:.2} kiB message to {recipient} on connection_id {connection_id:?}",
@@ -160,7 +163,8 @@ impl Handler {
         });
 
         // the ack control is now responsible for chunking, etc.
-        let input_msg = InputMessage::new_regular(recipient, message, lane);
+        let input_msg =
+            InputMessage::new_regular(recipient, message, lane).with_compression(compression);
         self.msg_input
             .send(input_msg)
             .await
@@ -180,6 +184,7 @@ impl Handler {
         message: Vec<u8>,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     ) -> Option<ServerResponse> {
         info!(
             "Attempting to anonymously send {:.2} kiB message to {recipient} on connection_id {connection_id:?} while attaching {reply_surbs} replySURBs.",
@@ -191,7 +196,8 @@ impl Handler {
             TransmissionLane::ConnectionId(id)
         });
 
-        let input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane);
+        let input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane)
+            .with_compression(compression);
         self.msg_input
             .send(input_msg)
             .await
@@ -210,6 +216,7 @@ impl Handler {
         recipient_tag: AnonymousSenderTag,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     ) -> Option<ServerResponse> {
         info!("Attempting to send {:.2} kiB reply message to {recipient_tag} on connection_id {connection_id:?}", message.len() as f64 / 1024.0);
 
@@ -218,7 +225,8 @@ impl Handler {
             TransmissionLane::ConnectionId(id)
         });
 
-        let input_msg = InputMessage::new_reply(recipient_tag, message, lane);
+        let input_msg =
+            InputMessage::new_reply(recipient_tag, message, lane).with_compression(compression);
         self.msg_input
             .send(input_msg)
             .await
@@ -233,7 +241,7 @@ impl Handler {
     }
 
     fn handle_self_address(&self) -> ServerResponse {
-        ServerResponse::SelfAddress(Box::new(self.self_full_address))
+        ServerResponse::SelfAddress(Box::new(self.self_full_address.current()))
     }
 
     fn handle_closed_connection(&self, connection_id: u64) -> Option<ServerResponse> {
@@ -253,23 +261,38 @@ impl Handler {
                 recipient,
                 message,
                 connection_id,
-            } => self.handle_send(recipient, message, connection_id).await,
+                compression,
+            } => {
+                self.handle_send(recipient, message, connection_id, compression)
+                    .await
+            }
 
             ClientRequest::SendAnonymous {
                 recipient,
                 message,
                 reply_surbs,
                 connection_id,
+                compression,
             } => {
-                self.handle_send_anonymous(recipient, message, reply_surbs, connection_id)
-                    .await
+                self.handle_send_anonymous(
+                    recipient,
+                    message,
+                    reply_surbs,
+                    connection_id,
+                    compression,
+                )
+                .await
             }
 
             ClientRequest::Reply {
                 message,
                 sender_tag,
                 connection_id,
-            } => self.handle_reply(sender_tag, message, connection_id).await,
+                compression,
+            } => {
+                self.handle_reply(sender_tag, message, connection_id, compression)
+                    .await
+            }
 
             ClientRequest::SelfAddress => Some(self.handle_self_address()),
             ClientRequest::ClosedConnection(id) => self.handle_closed_connection(id),
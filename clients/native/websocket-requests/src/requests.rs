@@ -8,9 +8,14 @@ use crate::error::{self, ErrorKind};
 use crate::text::ClientRequestText;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE};
+use nym_sphinx::message::MessageCompression;
 use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 
+/// Bit set on the request tag of the sending requests to indicate the message should be compressed.
+/// In that case the tag is followed by a byte identifying the compression algorithm.
+const COMPRESSED_REQUEST_FLAG: u8 = 0b1000_0000;
+
 #[repr(u8)]
 enum ClientRequestTag {
     /// Value tag representing [`Send`] variant of the [`ClientRequest`]
@@ -62,6 +67,7 @@ pub enum ClientRequest {
         recipient: Recipient,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     },
 
     /// Create a message used for a duplex anonymous communication where the recipient
@@ -77,6 +83,7 @@ pub enum ClientRequest {
         message: Vec<u8>,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     },
 
     /// Attempt to use our internally received and stored `ReplySurb` to send the message back
@@ -87,6 +94,7 @@ pub enum ClientRequest {
         sender_tag: AnonymousSenderTag,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compression: MessageCompression,
     },
 
     SelfAddress,
@@ -166,6 +174,7 @@ impl ClientRequest {
             recipient,
             message: data.to_vec(),
             connection_id,
+            compression: MessageCompression::None,
         })
     }
 
@@ -245,6 +254,7 @@ impl ClientRequest {
             recipient,
             message: data.to_vec(),
             connection_id,
+            compression: MessageCompression::None,
         })
     }
 
@@ -312,6 +322,7 @@ impl ClientRequest {
             message: message.to_vec(),
             sender_tag,
             connection_id,
+            compression: MessageCompression::None,
         })
     }
 
@@ -382,26 +393,106 @@ impl ClientRequest {
         Ok(ClientRequest::GetLaneQueueLength(connection_id))
     }
 
+    // (REQUEST_TAG | COMPRESSED_REQUEST_FLAG) || compression || request content
+    fn attach_compression(mut request: Vec<u8>, compression: MessageCompression) -> Vec<u8> {
+        if compression.is_enabled() {
+            request[0] |= COMPRESSED_REQUEST_FLAG;
+            request.insert(1, compression as u8);
+        }
+        request
+    }
+
+    // (REQUEST_TAG | COMPRESSED_REQUEST_FLAG) || compression || request content
+    fn deserialize_compressed(b: &[u8]) -> Result<Self, error::Error> {
+        if b.len() < 2 {
+            return Err(error::Error::new(
+                ErrorKind::TooShortRequest,
+                "not enough data provided to recover the compression".to_string(),
+            ));
+        }
+
+        let compression = MessageCompression::try_from(b[1])
+            .map_err(|err| error::Error::new(ErrorKind::MalformedRequest, err.to_string()))?;
+
+        let uncompressed_request = std::iter::once(b[0] & !COMPRESSED_REQUEST_FLAG)
+            .chain(b[2..].iter().copied())
+            .collect::<Vec<_>>();
+
+        match Self::deserialize(&uncompressed_request)? {
+            ClientRequest::Send {
+                recipient,
+                message,
+                connection_id,
+                ..
+            } => Ok(ClientRequest::Send {
+                recipient,
+                message,
+                connection_id,
+                compression,
+            }),
+            ClientRequest::SendAnonymous {
+                recipient,
+                message,
+                reply_surbs,
+                connection_id,
+                ..
+            } => Ok(ClientRequest::SendAnonymous {
+                recipient,
+                message,
+                reply_surbs,
+                connection_id,
+                compression,
+            }),
+            ClientRequest::Reply {
+                sender_tag,
+                message,
+                connection_id,
+                ..
+            } => Ok(ClientRequest::Reply {
+                sender_tag,
+                message,
+                connection_id,
+                compression,
+            }),
+            _ => Err(error::Error::new(
+                ErrorKind::MalformedRequest,
+                "only the sending requests can specify message compression".to_string(),
+            )),
+        }
+    }
+
     pub fn serialize(self) -> Vec<u8> {
         match self {
             ClientRequest::Send {
                 recipient,
                 message,
                 connection_id,
-            } => Self::serialize_send(recipient, message, connection_id),
+                compression,
+            } => Self::attach_compression(
+                Self::serialize_send(recipient, message, connection_id),
+                compression,
+            ),
 
             ClientRequest::SendAnonymous {
                 recipient,
                 message,
                 reply_surbs,
                 connection_id,
-            } => Self::serialize_send_anonymous(recipient, message, reply_surbs, connection_id),
+                compression,
+            } => Self::attach_compression(
+                Self::serialize_send_anonymous(recipient, message, reply_surbs, connection_id),
+                compression,
+            ),
 
             ClientRequest::Reply {
                 message,
                 sender_tag,
                 connection_id,
-            } => Self::serialize_reply(message, sender_tag, connection_id),
+                compression,
+            } => Self::attach_compression(
+                Self::serialize_reply(message, sender_tag, connection_id),
+                compression,
+            ),
 
             ClientRequest::SelfAddress => Self::serialize_self_address(),
 
@@ -421,6 +512,10 @@ impl ClientRequest {
             ));
         }
 
+        if b[0] & COMPRESSED_REQUEST_FLAG != 0 {
+            return Self::deserialize_compressed(b);
+        }
+
         let request_tag = ClientRequestTag::try_from(b[0])?;
 
         // determine what kind of request that is and try to deserialize it
@@ -462,6 +557,7 @@ mod tests {
             recipient,
             message: b"foomp".to_vec(),
             connection_id: Some(42),
+            compression: MessageCompression::None,
         };
 
         let bytes = send_request.serialize();
@@ -471,10 +567,12 @@ mod tests {
                 recipient,
                 message,
                 connection_id,
+                compression,
             } => {
                 assert_eq!(recipient.to_string(), recipient_string);
                 assert_eq!(message, b"foomp".to_vec());
-                assert_eq!(connection_id, Some(42))
+                assert_eq!(connection_id, Some(42));
+                assert_eq!(compression, MessageCompression::None)
             }
             _ => unreachable!(),
         }
@@ -489,6 +587,7 @@ mod tests {
             message: b"foomp".to_vec(),
             reply_surbs: 666,
             connection_id: Some(42),
+            compression: MessageCompression::None,
         };
 
         let bytes = send_anonymous_request.serialize();
@@ -499,6 +598,7 @@ mod tests {
                 message,
                 reply_surbs,
                 connection_id,
+                ..
             } => {
                 assert_eq!(recipient, original_recipient);
                 assert_eq!(message, b"foomp".to_vec());
@@ -515,6 +615,7 @@ mod tests {
             sender_tag: [8u8; SENDER_TAG_SIZE].into(),
             message: b"foomp".to_vec(),
             connection_id: Some(42),
+            compression: MessageCompression::None,
         };
 
         let bytes = reply_request.serialize();
@@ -524,6 +625,7 @@ mod tests {
                 sender_tag,
                 message,
                 connection_id,
+                ..
             } => {
                 assert_eq!(sender_tag, [8u8; SENDER_TAG_SIZE].into());
                 assert_eq!(message, b"foomp".to_vec());
@@ -533,6 +635,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compressed_send_request_serialization_works() {
+        let original_recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+
+        let send_anonymous_request = ClientRequest::SendAnonymous {
+            recipient: original_recipient,
+            message: b"foomp".to_vec(),
+            reply_surbs: 666,
+            connection_id: Some(42),
+            compression: MessageCompression::Deflate,
+        };
+
+        let bytes = send_anonymous_request.serialize();
+        assert_eq!(
+            bytes[0],
+            ClientRequestTag::SendAnonymous as u8 | COMPRESSED_REQUEST_FLAG
+        );
+
+        let recovered = ClientRequest::deserialize(&bytes).unwrap();
+        match recovered {
+            ClientRequest::SendAnonymous {
+                recipient,
+                message,
+                reply_surbs,
+                connection_id,
+                compression,
+            } => {
+                assert_eq!(recipient, original_recipient);
+                assert_eq!(message, b"foomp".to_vec());
+                assert_eq!(connection_id, Some(42));
+                assert_eq!(reply_surbs, 666);
+                assert_eq!(compression, MessageCompression::Deflate)
+            }
+            _ => unreachable!(),
+        }
+
+        let flagged_self_address = vec![
+            ClientRequestTag::SelfAddress as u8 | COMPRESSED_REQUEST_FLAG,
+            MessageCompression::Deflate as u8,
+        ];
+        assert!(ClientRequest::deserialize(&flagged_self_address).is_err());
+    }
+
     #[test]
     fn self_address_request_serialization_works() {
         let self_address_request = ClientRequest::SelfAddress;
@@ -8,6 +8,7 @@ use crate::error::{self, ErrorKind};
 use crate::text::ServerResponseText;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE};
+use nym_sphinx::message::MessageFraming;
 use nym_sphinx::receiver::ReconstructedMessage;
 use std::convert::TryInto;
 use std::mem::size_of;
@@ -143,6 +144,7 @@ impl ServerResponse {
         Ok(ServerResponse::Received(ReconstructedMessage {
             message: message.to_vec(),
             sender_tag,
+            framing: MessageFraming::Standalone,
         }))
     }
 
@@ -325,6 +327,7 @@ mod tests {
         let received_with_sender_tag = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             sender_tag: Some([42u8; SENDER_TAG_SIZE].into()),
+            framing: MessageFraming::Standalone,
         });
         let bytes = received_with_sender_tag.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
@@ -342,6 +345,7 @@ mod tests {
         let received_without_sender_tag = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             sender_tag: None,
+            framing: MessageFraming::Standalone,
         });
         let bytes = received_without_sender_tag.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
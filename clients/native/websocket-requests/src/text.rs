@@ -6,6 +6,7 @@ use crate::requests::ClientRequest;
 use crate::responses::ServerResponse;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::message::{MessageCompression, NymMessageError};
 use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 
@@ -20,6 +21,8 @@ pub(super) enum ClientRequestText {
         message: String,
         recipient: String,
         connection_id: Option<u64>,
+        #[serde(default)]
+        compression: Option<String>,
     },
     #[serde(rename_all = "camelCase")]
     SendAnonymous {
@@ -27,12 +30,16 @@ pub(super) enum ClientRequestText {
         message: String,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        #[serde(default)]
+        compression: Option<String>,
     },
     #[serde(rename_all = "camelCase")]
     Reply {
         sender_tag: String,
         message: String,
         connection_id: Option<u64>,
+        #[serde(default)]
+        compression: Option<String>,
     },
     SelfAddress,
 }
@@ -45,6 +52,17 @@ impl TryFrom<String> for ClientRequestText {
     }
 }
 
+fn parse_compression(
+    compression: Option<String>,
+) -> Result<MessageCompression, crate::error::Error> {
+    match compression {
+        None => Ok(MessageCompression::None),
+        Some(compression) => compression.parse().map_err(|err: NymMessageError| {
+            crate::error::Error::new(ErrorKind::MalformedRequest, err.to_string())
+        }),
+    }
+}
+
 impl TryInto<ClientRequest> for ClientRequestText {
     type Error = crate::error::Error;
 
@@ -54,6 +72,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                 message,
                 recipient,
                 connection_id,
+                compression,
             } => {
                 let message_bytes = message.into_bytes();
                 let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
@@ -64,6 +83,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     message: message_bytes,
                     recipient,
                     connection_id,
+                    compression: parse_compression(compression)?,
                 })
             }
             ClientRequestText::SendAnonymous {
@@ -71,6 +91,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                 message,
                 reply_surbs,
                 connection_id,
+                compression,
             } => {
                 let message_bytes = message.into_bytes();
                 let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
@@ -81,6 +102,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     message: message_bytes,
                     reply_surbs,
                     connection_id,
+                    compression: parse_compression(compression)?,
                 })
             }
             ClientRequestText::SelfAddress => Ok(ClientRequest::SelfAddress),
@@ -88,6 +110,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                 sender_tag,
                 message,
                 connection_id,
+                compression,
             } => {
                 let message_bytes = message.into_bytes();
                 let sender_tag =
@@ -99,6 +122,7 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     sender_tag,
                     message: message_bytes,
                     connection_id,
+                    compression: parse_compression(compression)?,
                 })
             }
         }
@@ -36,6 +36,11 @@ pub(crate) struct Init {
     #[clap(long, alias = "use_anonymous_sender_tag")]
     use_reply_surbs: Option<bool>,
 
+    /// Specifies whether the messages sent to the service provider are going to be compressed.
+    /// Note that service providers running older versions are unable to read such messages.
+    #[clap(long)]
+    compress_messages: Option<bool>,
+
     /// Id of the gateway we are going to connect to.
     #[clap(long)]
     gateway: Option<identity::PublicKey>,
@@ -50,6 +55,11 @@ pub(crate) struct Init {
     #[clap(long)]
     force_register_gateway: bool,
 
+    /// Number of additional gateways to register with that the client is going to fail over to
+    /// if its main gateway becomes unreachable.
+    #[clap(long, default_value_t = 0)]
+    fallback_gateways: usize,
+
     /// Comma separated list of rest endpoints of the nyxd validators
     #[clap(long, alias = "nyxd_validators", value_delimiter = ',', hide = true)]
     nyxd_urls: Option<Vec<url::Url>>,
@@ -87,6 +97,7 @@ impl From<Init> for OverrideConfig {
             nym_apis: init_config.nym_apis,
             port: init_config.port,
             use_anonymous_replies: init_config.use_reply_surbs,
+            compress_messages: init_config.compress_messages,
             fastmode: init_config.fastmode,
             no_cover: init_config.no_cover,
             nyxd_urls: init_config.nyxd_urls,
@@ -169,6 +180,21 @@ pub(crate) async fn execute(args: &Init) -> Result<(), Socks5ClientError> {
 
     config.get_base_mut().set_gateway_endpoint(gateway);
 
+    // Fallback gateways are only (re-)registered with alongside the main gateway
+    if register_gateway && args.fallback_gateways > 0 {
+        let fallback_gateways =
+            nym_client_core::init::setup_fallback_gateways::<_, PersistentStorage>(
+                config.get_base(),
+                args.fallback_gateways,
+                args.latency_based_selection,
+            )
+            .await
+            .tap_err(|err| eprintln!("Failed to setup fallback gateways\nError: {err}"))?;
+        config
+            .get_base_mut()
+            .set_fallback_gateway_endpoints(fallback_gateways);
+    }
+
     // TODO: ask the service provider we specified for its interface version and set it in the config
 
     config.save_to_file(None).tap_err(|_| {
@@ -12,6 +12,7 @@ use nym_socks5_client_core::config::old_config_v1_1_13::OldConfigV1_1_13;
 use nym_socks5_client_core::config::{BaseConfig, Config};
 use std::error::Error;
 
+pub(crate) mod encrypt_keys;
 pub mod init;
 pub(crate) mod run;
 pub(crate) mod upgrade;
@@ -42,6 +43,9 @@ pub(crate) enum Commands {
     /// Initialise a Nym client. Do this first!
     Init(init::Init),
 
+    /// Encrypt, in place, the private keys of the client using the passphrase from the environment
+    EncryptKeys(encrypt_keys::EncryptKeys),
+
     /// Run the Nym client with provided configuration client optionally overriding set parameters
     Run(run::Run),
 
@@ -60,6 +64,7 @@ pub(crate) struct OverrideConfig {
     nym_apis: Option<Vec<url::Url>>,
     port: Option<u16>,
     use_anonymous_replies: Option<bool>,
+    compress_messages: Option<bool>,
     fastmode: bool,
     no_cover: bool,
     nyxd_urls: Option<Vec<url::Url>>,
@@ -70,6 +75,7 @@ pub(crate) async fn execute(args: &Cli) -> Result<(), Box<dyn Error + Send + Syn
     let bin_name = "nym-socks5-client";
 
     match &args.command {
+        Commands::EncryptKeys(m) => encrypt_keys::execute(m)?,
         Commands::Init(m) => init::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Upgrade(m) => upgrade::execute(m),
@@ -84,6 +90,7 @@ pub(crate) fn override_config(config: Config, args: OverrideConfig) -> Config {
         .with_base(BaseConfig::with_high_default_traffic_volume, args.fastmode)
         .with_base(BaseConfig::with_disabled_cover_traffic, args.no_cover)
         .with_optional(Config::with_anonymous_replies, args.use_anonymous_replies)
+        .with_optional(Config::with_message_compression, args.compress_messages)
         .with_optional(Config::with_port, args.port)
         .with_optional_custom_env_ext(
             BaseConfig::with_custom_nym_apis,
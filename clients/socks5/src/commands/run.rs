@@ -33,6 +33,11 @@ pub(crate) struct Run {
     #[clap(long, alias = "use_anonymous_sender_tag")]
     use_anonymous_replies: Option<bool>,
 
+    /// Specifies whether the messages sent to the service provider are going to be compressed.
+    /// Note that service providers running older versions are unable to read such messages.
+    #[clap(long)]
+    compress_messages: Option<bool>,
+
     /// Address of the socks5 provider to send messages to.
     #[clap(long)]
     provider: Option<Recipient>,
@@ -75,6 +80,7 @@ impl From<Run> for OverrideConfig {
             nym_apis: run_config.nym_apis,
             port: run_config.port,
             use_anonymous_replies: run_config.use_anonymous_replies,
+            compress_messages: run_config.compress_messages,
             fastmode: run_config.fastmode,
             no_cover: run_config.no_cover,
             nyxd_urls: run_config.nyxd_urls,
@@ -12,7 +12,7 @@ use nym_client_core::config::{
     GatewayEndpointConfig, ReplySurbs as ConfigReplySurbs, Topology as ConfigTopology,
     Traffic as ConfigTraffic,
 };
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::params::{PacketMode, PacketSize};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use url::Url;
@@ -77,6 +77,9 @@ pub struct Traffic {
 
     /// Controls whether the sent sphinx packet use the NON-DEFAULT bigger size.
     pub use_extended_packet_size: bool,
+
+    /// Controls whether the sent messages use the outfox packet format instead of sphinx.
+    pub use_outfox: bool,
 }
 
 impl From<Traffic> for ConfigTraffic {
@@ -94,6 +97,13 @@ impl From<Traffic> for ConfigTraffic {
                 .disable_main_poisson_packet_distribution,
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: use_extended_packet_size,
+            packet_mode: if traffic.use_outfox {
+                PacketMode::Outfox
+            } else {
+                PacketMode::Mix
+            },
+            message_redundancy: None,
+            recipients_support_erasure_coding: false,
         }
     }
 }
@@ -107,6 +117,7 @@ impl From<ConfigTraffic> for Traffic {
             disable_main_poisson_packet_distribution: traffic
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size: traffic.secondary_packet_size.is_some(),
+            use_outfox: traffic.packet_mode.is_outfox(),
         }
     }
 }
@@ -238,6 +249,7 @@ impl From<Topology> for ConfigTopology {
             topology_resolution_timeout: Duration::from_millis(
                 topology.topology_resolution_timeout_ms,
             ),
+            route_selection: Default::default(),
         }
     }
 }
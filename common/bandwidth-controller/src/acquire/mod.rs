@@ -4,7 +4,7 @@
 use crate::error::BandwidthControllerError;
 use nym_coconut_interface::{Base58, Parameters};
 use nym_credential_storage::storage::Storage;
-use nym_credentials::coconut::bandwidth::{BandwidthVoucher, TOTAL_ATTRIBUTES};
+use nym_credentials::coconut::bandwidth::{expiry_date_for, BandwidthVoucher, TOTAL_ATTRIBUTES};
 use nym_credentials::coconut::utils::obtain_aggregate_signature;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_network_defaults::VOUCHER_INFO;
@@ -23,11 +23,36 @@ pub async fn deposit<C>(client: &C, amount: Coin) -> Result<State, BandwidthCont
 where
     C: CoconutBandwidthSigningClient,
 {
+    let denominations = [amount.amount];
+    let mut states = deposit_with_denominations(client, amount, &denominations).await?;
+
+    // we've asked for exactly one voucher
+    Ok(states.remove(0))
+}
+
+/// Deposits the specified amount and prepares a separate voucher for each of the denominations,
+/// so that the smaller credentials could be spent independently of each other.
+/// The denominations have to add up to the deposited amount.
+pub async fn deposit_with_denominations<C>(
+    client: &C,
+    amount: Coin,
+    denominations: &[u128],
+) -> Result<Vec<State>, BandwidthControllerError>
+where
+    C: CoconutBandwidthSigningClient,
+{
+    if denominations.is_empty()
+        || denominations.contains(&0)
+        || denominations.iter().sum::<u128>() != amount.amount
+    {
+        return Err(BandwidthControllerError::InvalidDenominations {
+            amount: amount.amount,
+        });
+    }
+
     let mut rng = OsRng;
     let signing_keypair = KeyPair::from(identity::KeyPair::new(&mut rng));
     let encryption_keypair = KeyPair::from(encryption::KeyPair::new(&mut rng));
-    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
-    let voucher_value = amount.amount.to_string();
 
     let tx_hash = client
         .deposit(
@@ -40,19 +65,27 @@ where
         .await?
         .transaction_hash
         .to_string();
+    let tx_hash = Hash::from_str(&tx_hash).map_err(|_| BandwidthControllerError::InvalidTxHash)?;
 
-    let voucher = BandwidthVoucher::new(
-        &params,
-        voucher_value,
-        VOUCHER_INFO.to_string(),
-        Hash::from_str(&tx_hash).map_err(|_| BandwidthControllerError::InvalidTxHash)?,
-        identity::PrivateKey::from_base58_string(&signing_keypair.private_key)?,
-        encryption::PrivateKey::from_base58_string(&encryption_keypair.private_key)?,
-    );
+    // all the vouchers of the same deposit expire at the same time
+    let expiry_date = expiry_date_for(crate::current_unix_timestamp());
 
-    let state = State { voucher, params };
+    let mut states = Vec::with_capacity(denominations.len());
+    for denomination in denominations {
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
+        let voucher = BandwidthVoucher::new(
+            &params,
+            denomination.to_string(),
+            VOUCHER_INFO.to_string(),
+            expiry_date,
+            tx_hash,
+            identity::PrivateKey::from_base58_string(&signing_keypair.private_key)?,
+            encryption::PrivateKey::from_base58_string(&encryption_keypair.private_key)?,
+        );
+        states.push(State { voucher, params });
+    }
 
-    Ok(state)
+    Ok(states)
 }
 
 pub async fn get_credential<C: DkgQueryClient + Send + Sync, St: Storage>(
@@ -82,6 +115,7 @@ pub async fn get_credential<C: DkgQueryClient + Send + Sync, St: Storage>(
             state.voucher.get_private_attributes()[1].to_bs58(),
             signature.to_bs58(),
             epoch_id.to_string(),
+            state.voucher.expiry_date().to_string(),
         )
         .await?;
 
@@ -38,4 +38,7 @@ pub enum BandwidthControllerError {
 
     #[error("Threshold not set yet")]
     NoThreshold,
+
+    #[error("The voucher denominations have to be non-zero and add up to the deposited amount of {amount}")]
+    InvalidDenominations { amount: u128 },
 }
@@ -4,9 +4,13 @@
 use crate::error::BandwidthControllerError;
 
 use nym_credential_storage::error::StorageError;
+use nym_credential_storage::models::CoconutCredential;
 use nym_credential_storage::storage::Storage;
+use nym_network_defaults::BYTES_PER_UTOKEN;
 
 use std::str::FromStr;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
 use {
     nym_coconut_interface::Base58,
     nym_credentials::coconut::{
@@ -26,6 +30,21 @@ pub mod error;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm_mockups;
 
+/// Current unix timestamp used for determining validity of the stored credentials.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is set before the unix epoch")
+        .as_secs()
+}
+
+// `SystemTime` is not available in the browser and the credentials can't be spent there anyway
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn current_unix_timestamp() -> u64 {
+    0
+}
+
 pub struct BandwidthController<C, St: Storage> {
     storage: St,
     client: C,
@@ -47,6 +66,34 @@ impl<C, St: Storage> BandwidthController<C, St> {
         C: DkgQueryClient + Sync + Send,
     {
         let bandwidth_credential = self.storage.get_next_coconut_credential().await?;
+        self.prepare_stored_credential(bandwidth_credential).await
+    }
+
+    /// Prepares the stored credential that covers the required amount of bandwidth
+    /// with the least amount of waste. If none of them is big enough, the most valuable one is used.
+    /// Already expired credentials are never chosen.
+    pub async fn prepare_coconut_credential_for_bandwidth(
+        &self,
+        required_bandwidth: u64,
+    ) -> Result<(nym_coconut_interface::Credential, i64), BandwidthControllerError>
+    where
+        C: DkgQueryClient + Sync + Send,
+    {
+        let required_value = (required_bandwidth + BYTES_PER_UTOKEN - 1) / BYTES_PER_UTOKEN;
+        let bandwidth_credential = self
+            .storage
+            .get_coconut_credential_for_value(required_value, current_unix_timestamp())
+            .await?;
+        self.prepare_stored_credential(bandwidth_credential).await
+    }
+
+    async fn prepare_stored_credential(
+        &self,
+        bandwidth_credential: CoconutCredential,
+    ) -> Result<(nym_coconut_interface::Credential, i64), BandwidthControllerError>
+    where
+        C: DkgQueryClient + Sync + Send,
+    {
         let voucher_value = u64::from_str(&bandwidth_credential.voucher_value)
             .map_err(|_| StorageError::InconsistentData)?;
         let voucher_info = bandwidth_credential.voucher_info.clone();
@@ -58,6 +105,9 @@ impl<C, St: Storage> BandwidthController<C, St> {
             nym_coconut_interface::Signature::try_from_bs58(bandwidth_credential.signature)?;
         let epoch_id = u64::from_str(&bandwidth_credential.epoch_id)
             .map_err(|_| StorageError::InconsistentData)?;
+        let expiry_date = bandwidth_credential
+            .expiry_date()
+            .ok_or(StorageError::InconsistentData)?;
 
         #[cfg(not(target_arch = "wasm32"))]
         let coconut_api_clients =
@@ -72,6 +122,7 @@ impl<C, St: Storage> BandwidthController<C, St> {
             prepare_for_spending(
                 voucher_value,
                 voucher_info,
+                expiry_date,
                 serial_number,
                 binding_number,
                 epoch_id,
@@ -82,10 +133,30 @@ impl<C, St: Storage> BandwidthController<C, St> {
         ))
     }
 
-    pub async fn consume_credential(&self, id: i64) -> Result<(), BandwidthControllerError> {
+    pub async fn consume_credential(
+        &self,
+        id: i64,
+        gateway_id: &str,
+    ) -> Result<(), BandwidthControllerError> {
         // JS: shouldn't we send some contract/validator/gateway message here to actually, you know,
         // consume it?
-        Ok(self.storage.consume_coconut_credential(id).await?)
+        Ok(self
+            .storage
+            .consume_coconut_credential(id, gateway_id)
+            .await?)
+    }
+
+    /// Records how much bandwidth is still available at the gateway, so that the partially used
+    /// credentials could be accounted for.
+    pub async fn update_gateway_bandwidth(
+        &self,
+        gateway_id: &str,
+        remaining_bandwidth: i64,
+    ) -> Result<(), BandwidthControllerError> {
+        Ok(self
+            .storage
+            .update_gateway_bandwidth(gateway_id, remaining_bandwidth)
+            .await?)
     }
 }
 
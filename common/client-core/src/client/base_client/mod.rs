@@ -3,6 +3,7 @@
 
 use super::received_buffer::ReceivedBufferMessage;
 use crate::client::cover_traffic_stream::LoopCoverTrafficStream;
+use crate::client::gateway_failover::GatewayFailoverListener;
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver, InputMessageSender};
 use crate::client::key_manager::KeyManager;
 use crate::client::mix_traffic::{BatchMixMessageSender, MixTrafficController};
@@ -16,20 +17,23 @@ use crate::client::replies::reply_controller::{ReplyControllerReceiver, ReplyCon
 use crate::client::replies::reply_storage::{
     CombinedReplyStorage, PersistentReplyStorage, ReplyStorageBackend, SentReplyKeys,
 };
+use crate::client::self_address::SelfAddressAccessor;
 use crate::client::topology_control::nym_api_provider::NymApiTopologyProvider;
 use crate::client::topology_control::{
     TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
 };
+use crate::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use crate::config::{Config, DebugConfig, GatewayEndpointConfig};
 use crate::error::ClientCoreError;
 use crate::spawn_future;
 use futures::channel::mpsc;
 use log::{debug, info};
 use nym_bandwidth_controller::BandwidthController;
+use nym_config::NymConfig;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_gateway_client::{
-    AcknowledgementReceiver, AcknowledgementSender, GatewayClient, MixnetMessageReceiver,
-    MixnetMessageSender,
+    AcknowledgementReceiver, AcknowledgementSender, FallbackGateway, GatewayClient,
+    GatewayFailoverSender, MixnetMessageReceiver, MixnetMessageSender,
 };
 use nym_sphinx::acknowledgements::AckKey;
 use nym_sphinx::addressing::clients::Recipient;
@@ -38,6 +42,7 @@ use nym_sphinx::receiver::{ReconstructedMessage, SphinxMessageReceiver};
 use nym_task::connections::{ConnectionCommandReceiver, ConnectionCommandSender, LaneQueueLengths};
 use nym_task::{TaskClient, TaskManager};
 use nym_topology::provider_trait::TopologyProvider;
+use nym_topology::RouteSelectionStrategy;
 use std::sync::Arc;
 use std::time::Duration;
 use tap::TapFallible;
@@ -96,6 +101,7 @@ pub struct ClientState {
     pub shared_lane_queue_lengths: LaneQueueLengths,
     pub reply_controller_sender: ReplyControllerSender,
     pub topology_accessor: TopologyAccessor,
+    pub self_address: SelfAddressAccessor,
 }
 
 pub enum ClientInputStatus {
@@ -152,17 +158,26 @@ impl From<bool> for CredentialsToggle {
     }
 }
 
+/// Persists the gateway configuration of the client once it has failed over to a different gateway,
+/// given the new main gateway and the remaining fallback ones.
+pub type GatewayConfigPersister =
+    Box<dyn FnMut(&GatewayEndpointConfig, &[GatewayEndpointConfig]) -> std::io::Result<()> + Send>;
+
 pub struct BaseClientBuilder<'a, B, C, St: Storage> {
     // due to wasm limitations I had to split it like this : (
     gateway_config: &'a GatewayEndpointConfig,
+    fallback_gateways: Vec<GatewayEndpointConfig>,
     debug_config: &'a DebugConfig,
     disabled_credentials: bool,
     nym_api_endpoints: Vec<Url>,
     reply_storage_backend: B,
 
     custom_topology_provider: Option<Box<dyn TopologyProvider>>,
+    custom_route_selection: Option<RouteSelectionStrategy>,
     bandwidth_controller: Option<BandwidthController<C, St>>,
     key_manager: KeyManager,
+    key_pathfinder: Option<ClientKeyPathfinder>,
+    gateway_config_persister: Option<GatewayConfigPersister>,
 }
 
 impl<'a, B, C, St> BaseClientBuilder<'a, B, C, St>
@@ -173,19 +188,39 @@ where
 {
     pub fn new_from_base_config<T>(
         base_config: &'a Config<T>,
-        key_manager: KeyManager,
+        mut key_manager: KeyManager,
         bandwidth_controller: Option<BandwidthController<C, St>>,
         reply_storage_backend: B,
-    ) -> BaseClientBuilder<'a, B, C, St> {
+    ) -> BaseClientBuilder<'a, B, C, St>
+    where
+        T: NymConfig,
+    {
+        let fallback_gateways = base_config.get_fallback_gateway_endpoints().to_vec();
+        let key_pathfinder = ClientKeyPathfinder::new_from_config(base_config);
+
+        // if we can't load any of the keys, we'll just register with the gateway again during the failover
+        if let Err(err) = key_manager.load_fallback_gateway_keys(
+            &key_pathfinder,
+            fallback_gateways
+                .iter()
+                .map(|gateway| gateway.gateway_id.as_str()),
+        ) {
+            log::warn!("failed to load the fallback gateway keys - {err}")
+        }
+
         BaseClientBuilder {
             gateway_config: base_config.get_gateway_endpoint_config(),
+            fallback_gateways,
             debug_config: base_config.get_debug_config(),
             disabled_credentials: base_config.get_disabled_credentials_mode(),
             nym_api_endpoints: base_config.get_nym_api_endpoints(),
             bandwidth_controller,
             reply_storage_backend,
             key_manager,
+            key_pathfinder: Some(key_pathfinder),
+            gateway_config_persister: None,
             custom_topology_provider: None,
+            custom_route_selection: None,
         }
     }
 
@@ -200,13 +235,17 @@ where
     ) -> BaseClientBuilder<'a, B, C, St> {
         BaseClientBuilder {
             gateway_config,
+            fallback_gateways: Vec::new(),
             debug_config,
             disabled_credentials: credentials_toggle.is_disabled(),
             nym_api_endpoints,
             reply_storage_backend,
             custom_topology_provider: None,
+            custom_route_selection: None,
             bandwidth_controller,
             key_manager,
+            key_pathfinder: None,
+            gateway_config_persister: None,
         }
     }
 
@@ -215,6 +254,36 @@ where
         self
     }
 
+    /// Overrides the route selection strategy specified in the debug config,
+    /// for example in order to use a custom weighting function.
+    pub fn with_route_selection_strategy(mut self, strategy: RouteSelectionStrategy) -> Self {
+        self.custom_route_selection = Some(strategy);
+        self
+    }
+
+    /// Specifies the ranked list of gateways the client is going to fail over to if it can't
+    /// reconnect to its main gateway. Any gateways without a shared key present in the
+    /// `KeyManager` are going to be registered with during the failover.
+    pub fn with_fallback_gateways(mut self, fallback_gateways: Vec<GatewayEndpointConfig>) -> Self {
+        self.fallback_gateways = fallback_gateways;
+        self
+    }
+
+    /// Specifies location of the client keys so that any shared keys derived with
+    /// the fallback gateways during the failover would be persisted.
+    pub fn with_key_pathfinder(mut self, key_pathfinder: ClientKeyPathfinder) -> Self {
+        self.key_pathfinder = Some(key_pathfinder);
+        self
+    }
+
+    /// Specifies how the gateway configuration should be saved once the client fails over to
+    /// a different gateway, so that it would keep using it after restarting. The shared keys are
+    /// rearranged accordingly, so a key pathfinder has to be provided as well.
+    pub fn with_gateway_config_persister(mut self, persister: GatewayConfigPersister) -> Self {
+        self.gateway_config_persister = Some(persister);
+        self
+    }
+
     pub fn as_mix_recipient(&self) -> Recipient {
         Recipient::new(
             *self.key_manager.identity_keypair().public_key(),
@@ -230,7 +299,7 @@ where
     fn start_cover_traffic_stream(
         debug_config: &DebugConfig,
         ack_key: Arc<AckKey>,
-        self_address: Recipient,
+        self_address: SelfAddressAccessor,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
         shutdown: TaskClient,
@@ -303,10 +372,53 @@ where
         controller.start_with_shutdown(shutdown)
     }
 
+    fn start_gateway_failover_listener(
+        &mut self,
+        self_address: SelfAddressAccessor,
+        shutdown: TaskClient,
+    ) -> GatewayFailoverSender {
+        info!("Starting gateway failover listener...");
+        let (failover_sender, failover_receiver) = mpsc::unbounded();
+        GatewayFailoverListener::new(
+            failover_receiver,
+            self_address,
+            self.gateway_config.clone(),
+            self.fallback_gateways.clone(),
+            self.key_manager.clone(),
+            self.key_pathfinder.clone(),
+            self.gateway_config_persister.take(),
+        )
+        .start_with_shutdown(shutdown);
+        failover_sender
+    }
+
+    fn fallback_gateways(&self) -> Vec<FallbackGateway> {
+        self.fallback_gateways
+            .iter()
+            .filter_map(|gateway| {
+                let identity = identity::PublicKey::from_base58_string(&gateway.gateway_id)
+                    .tap_err(|err| {
+                        log::warn!(
+                            "fallback gateway {} has an invalid identity - {err}",
+                            gateway.gateway_id
+                        )
+                    })
+                    .ok()?;
+                Some(FallbackGateway::new(
+                    gateway.gateway_listener.clone(),
+                    identity,
+                    self.key_manager
+                        .fallback_gateway_shared_key(&gateway.gateway_id),
+                ))
+            })
+            .collect()
+    }
+
     async fn start_gateway_client(
         &mut self,
         mixnet_message_sender: MixnetMessageSender,
         ack_sender: AcknowledgementSender,
+        failover_sender: Option<GatewayFailoverSender>,
         shutdown: TaskClient,
     ) -> Result<GatewayClient<C, St>, ClientCoreError> {
         let gateway_id = self.gateway_config.gateway_id.clone();
@@ -344,6 +456,10 @@ where
         );
 
         gateway_client.set_disabled_credentials_mode(self.disabled_credentials);
+        if let Some(failover_sender) = failover_sender {
+            gateway_client.with_fallback_gateways(self.fallback_gateways());
+            gateway_client.with_failover_notifications(failover_sender);
+        }
 
         gateway_client
             .authenticate_and_start()
@@ -357,12 +473,14 @@ where
     fn setup_topology_provider(
         custom_provider: Option<Box<dyn TopologyProvider>>,
         nym_api_urls: Vec<Url>,
+        route_selection: &RouteSelectionStrategy,
     ) -> Box<dyn TopologyProvider> {
         // if no custom provider was ... provided ..., create one using nym-api
         custom_provider.unwrap_or_else(|| {
             Box::new(NymApiTopologyProvider::new(
                 nym_api_urls,
                 env!("CARGO_PKG_VERSION").to_string(),
+                route_selection.requires_performance(),
             ))
         })
     }
@@ -372,10 +490,11 @@ where
     async fn start_topology_refresher(
         topology_provider: Box<dyn TopologyProvider>,
         refresh_rate: Duration,
+        route_selection: RouteSelectionStrategy,
         topology_accessor: TopologyAccessor,
         shutdown: TaskClient,
     ) -> Result<(), ClientCoreError> {
-        let topology_refresher_config = TopologyRefresherConfig::new(refresh_rate);
+        let topology_refresher_config = TopologyRefresherConfig::new(refresh_rate, route_selection);
 
         let mut topology_refresher = TopologyRefresher::new(
             topology_refresher_config,
@@ -480,12 +599,26 @@ where
         let (reply_controller_sender, reply_controller_receiver) =
             reply_controller::requests::new_control_channels();
 
-        let self_address = self.as_mix_recipient();
+        let self_address = SelfAddressAccessor::new(self.as_mix_recipient());
 
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
+        let failover_sender =
+            if self.fallback_gateways.is_empty() {
+                None
+            } else {
+                Some(self.start_gateway_failover_listener(
+                    self_address.clone(),
+                    task_manager.subscribe(),
+                ))
+            };
         let gateway_client = self
-            .start_gateway_client(mixnet_messages_sender, ack_sender, task_manager.subscribe())
+            .start_gateway_client(
+                mixnet_messages_sender,
+                ack_sender,
+                failover_sender,
+                task_manager.subscribe(),
+            )
             .await?;
 
         let reply_storage = Self::setup_persistent_reply_storage(
@@ -494,13 +627,19 @@ where
         )
         .await?;
 
+        let route_selection = self
+            .custom_route_selection
+            .take()
+            .unwrap_or_else(|| self.debug_config.topology.route_selection.into());
         let topology_provider = Self::setup_topology_provider(
             self.custom_topology_provider.take(),
             self.nym_api_endpoints,
+            &route_selection,
         );
         Self::start_topology_refresher(
             topology_provider,
             self.debug_config.topology.topology_refresh_rate,
+            route_selection,
             shared_topology_accessor.clone(),
             task_manager.subscribe(),
         )
@@ -533,7 +672,7 @@ where
         let controller_config = real_messages_control::Config::new(
             self.debug_config,
             self.key_manager.ack_key(),
-            self_address,
+            self_address.clone(),
         );
 
         Self::start_real_traffic_controller(
@@ -558,7 +697,7 @@ where
             Self::start_cover_traffic_stream(
                 self.debug_config,
                 self.key_manager.ack_key(),
-                self_address,
+                self_address.clone(),
                 shared_topology_accessor.clone(),
                 sphinx_message_sender,
                 task_manager.subscribe(),
@@ -566,7 +705,7 @@ where
         }
 
         debug!("Core client startup finished!");
-        debug!("The address of this client is: {self_address}");
+        debug!("The address of this client is: {}", self_address.current());
 
         Ok(BaseClient {
             client_input: ClientInputStatus::AwaitingProducer {
@@ -584,6 +723,7 @@ where
                 shared_lane_queue_lengths,
                 reply_controller_sender,
                 topology_accessor: shared_topology_accessor,
+                self_address,
             },
             task_manager,
         })
@@ -0,0 +1,159 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::channel::oneshot;
+use futures::FutureExt;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use thiserror::Error;
+
+pub type DeliveryResult = Result<(), DeliveryError>;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum DeliveryError {
+    #[error("failed to prepare the message for sending: {reason}")]
+    PreparationFailure { reason: String },
+
+    #[error("{fragment} was not acknowledged after {retransmissions} retransmissions")]
+    RetransmissionsExhausted {
+        fragment: FragmentIdentifier,
+        retransmissions: u32,
+    },
+
+    #[error("delivery tracking is not supported for replies")]
+    UnsupportedReply,
+
+    #[error("the client has stopped tracking the delivery of this message")]
+    TrackingAborted,
+}
+
+/// Tracks the delivery of all fragments of a single message. It is attached to each of the
+/// message's `PendingAcknowledgement`s and resolves the associated [`DeliveryConfirmation`]
+/// once all of them got acknowledged or once any of them has exhausted its retransmissions.
+#[derive(Debug, Clone)]
+pub struct DeliveryTracker {
+    max_retransmissions: u32,
+    state: Arc<Mutex<DeliveryState>>,
+}
+
+#[derive(Debug)]
+struct DeliveryState {
+    remaining_fragments: usize,
+    notifier: Option<oneshot::Sender<DeliveryResult>>,
+}
+
+impl DeliveryTracker {
+    /// Creates a new tracker alongside the handle that is going to get resolved once the
+    /// delivery outcome of the message is known.
+    pub fn new(max_retransmissions: u32) -> (Self, DeliveryConfirmation) {
+        let (notifier, receiver) = oneshot::channel();
+        let tracker = DeliveryTracker {
+            max_retransmissions,
+            state: Arc::new(Mutex::new(DeliveryState {
+                remaining_fragments: 0,
+                notifier: Some(notifier),
+            })),
+        };
+        (tracker, DeliveryConfirmation { receiver })
+    }
+
+    pub(crate) fn max_retransmissions(&self) -> u32 {
+        self.max_retransmissions
+    }
+
+    // must be called before any of the fragments is sent to the mix network
+    pub(crate) fn expect_fragments(&self, fragments: usize) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("delivery tracker lock is poisoned");
+        state.remaining_fragments = fragments;
+        if fragments == 0 {
+            state.notify(Ok(()))
+        }
+    }
+
+    pub(crate) fn fragment_acknowledged(&self) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("delivery tracker lock is poisoned");
+        state.remaining_fragments = state.remaining_fragments.saturating_sub(1);
+        if state.remaining_fragments == 0 {
+            state.notify(Ok(()))
+        }
+    }
+
+    pub(crate) fn fail(&self, err: DeliveryError) {
+        self.state
+            .lock()
+            .expect("delivery tracker lock is poisoned")
+            .notify(Err(err))
+    }
+}
+
+impl DeliveryState {
+    fn notify(&mut self, result: DeliveryResult) {
+        // only the first outcome matters, i.e. if one fragment has failed, we don't care
+        // what happens to the remaining ones
+        if let Some(notifier) = self.notifier.take() {
+            // the receiver might have been dropped if nobody is waiting for the outcome anymore
+            let _ = notifier.send(result);
+        }
+    }
+}
+
+/// Handle resolving to the delivery outcome of a message sent with a [`DeliveryTracker`] attached.
+#[derive(Debug)]
+pub struct DeliveryConfirmation {
+    receiver: oneshot::Receiver<DeliveryResult>,
+}
+
+impl Future for DeliveryConfirmation {
+    type Output = DeliveryResult;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver
+            .poll_unpin(cx)
+            .map(|res| res.unwrap_or(Err(DeliveryError::TrackingAborted)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn resolves_once_all_fragments_are_acknowledged() {
+        let (tracker, confirmation) = DeliveryTracker::new(3);
+        tracker.expect_fragments(2);
+        tracker.fragment_acknowledged();
+        tracker.clone().fragment_acknowledged();
+
+        assert_eq!(confirmation.await, Ok(()))
+    }
+
+    #[tokio::test]
+    async fn reports_first_failure() {
+        let (tracker, confirmation) = DeliveryTracker::new(3);
+        tracker.expect_fragments(2);
+        tracker.fail(DeliveryError::UnsupportedReply);
+        tracker.fragment_acknowledged();
+        tracker.fragment_acknowledged();
+
+        assert_eq!(confirmation.await, Err(DeliveryError::UnsupportedReply))
+    }
+
+    #[tokio::test]
+    async fn reports_dropped_tracker() {
+        let (tracker, confirmation) = DeliveryTracker::new(3);
+        tracker.expect_fragments(2);
+        tracker.fragment_acknowledged();
+        drop(tracker);
+
+        assert_eq!(confirmation.await, Err(DeliveryError::TrackingAborted))
+    }
+}
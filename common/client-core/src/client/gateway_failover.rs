@@ -0,0 +1,365 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::base_client::GatewayConfigPersister;
+use crate::client::key_manager::KeyManager;
+use crate::client::self_address::SelfAddressAccessor;
+use crate::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use crate::config::GatewayEndpointConfig;
+use crate::spawn_future;
+use futures::StreamExt;
+use log::*;
+use nym_gateway_client::{GatewayFailover, GatewayFailoverReceiver};
+use nym_sphinx::addressing::clients::Recipient;
+
+/// Listens for the gateway client switching to one of the fallback gateways and updates
+/// the address of this client accordingly, so that all subsequently created packets
+/// would be routed via the new gateway.
+pub(crate) struct GatewayFailoverListener {
+    failover_receiver: GatewayFailoverReceiver,
+    self_address: SelfAddressAccessor,
+
+    /// Gateway the client is currently using.
+    active_gateway: GatewayEndpointConfig,
+
+    /// Remaining gateways, in the order the gateway client is going to attempt them.
+    fallback_gateways: Vec<GatewayEndpointConfig>,
+
+    /// Id of the gateway whose shared key is stored in the main gateway key file.
+    main_gateway_id: String,
+    key_manager: KeyManager,
+
+    /// If specified, newly derived fallback gateway keys are going to get persisted
+    /// so that the client would not have to register again after restarting.
+    key_pathfinder: Option<ClientKeyPathfinder>,
+
+    /// If specified, the gateway the client has switched to is going to become its main gateway
+    /// so that it would keep using it after restarting.
+    config_persister: Option<GatewayConfigPersister>,
+}
+
+impl GatewayFailoverListener {
+    pub(crate) fn new(
+        failover_receiver: GatewayFailoverReceiver,
+        self_address: SelfAddressAccessor,
+        active_gateway: GatewayEndpointConfig,
+        fallback_gateways: Vec<GatewayEndpointConfig>,
+        key_manager: KeyManager,
+        key_pathfinder: Option<ClientKeyPathfinder>,
+        config_persister: Option<GatewayConfigPersister>,
+    ) -> Self {
+        GatewayFailoverListener {
+            failover_receiver,
+            self_address,
+            main_gateway_id: active_gateway.gateway_id.clone(),
+            active_gateway,
+            fallback_gateways,
+            key_manager,
+            key_pathfinder,
+            config_persister,
+        }
+    }
+
+    // mirror the rotation performed by the gateway client: the new gateway is taken out of
+    // the fallback list and the one we've moved away from is put at its back
+    fn rotate_gateways(&mut self, failover: &GatewayFailover) -> GatewayEndpointConfig {
+        let gateway_id = failover.identity.to_base58_string();
+        let new_gateway = match self
+            .fallback_gateways
+            .iter()
+            .position(|gateway| gateway.gateway_id == gateway_id)
+        {
+            Some(position) => self.fallback_gateways.remove(position),
+            None => GatewayEndpointConfig::new(gateway_id, String::new(), failover.address.clone()),
+        };
+
+        let previous = std::mem::replace(&mut self.active_gateway, new_gateway);
+        self.fallback_gateways.push(previous.clone());
+        previous
+    }
+
+    fn on_failover(&mut self, failover: GatewayFailover) {
+        let gateway_id = failover.identity.to_base58_string();
+        let previous_address = self.self_address.current();
+        let new_address = Recipient::new(
+            *previous_address.identity(),
+            *previous_address.encryption_key(),
+            failover.identity,
+        );
+        self.self_address.update(new_address);
+        info!(
+            "The gateway has changed to {gateway_id} ({})",
+            failover.address
+        );
+        info!("The address of this client is now: {new_address}");
+
+        let previous_gateway = self.rotate_gateways(&failover);
+        if self.key_manager.is_gateway_key_set() {
+            let previous_key = self.key_manager.gateway_shared_key();
+            self.key_manager
+                .insert_fallback_gateway_shared_key(previous_gateway.gateway_id, previous_key);
+        }
+        self.key_manager
+            .insert_gateway_shared_key(failover.shared_key.clone());
+        self.key_manager
+            .insert_fallback_gateway_shared_key(gateway_id.clone(), failover.shared_key);
+
+        if self.config_persister.is_some() {
+            self.persist_active_gateway();
+        } else if gateway_id != self.main_gateway_id {
+            self.persist_fallback_gateway_key(&gateway_id);
+        }
+    }
+
+    fn persist_fallback_gateway_key(&self, gateway_id: &str) {
+        if let Some(pathfinder) = &self.key_pathfinder {
+            if let Err(err) = self
+                .key_manager
+                .store_fallback_gateway_key(pathfinder, gateway_id)
+            {
+                warn!("failed to persist the shared key of gateway {gateway_id}: {err}")
+            }
+        }
+    }
+
+    // the config is only updated once all the keys are in place, so that the client would
+    // never attempt to use a mismatched key with its main gateway after restarting
+    fn persist_active_gateway(&mut self) {
+        let Some(persister) = self.config_persister.as_mut() else {
+            return;
+        };
+
+        if let Some(pathfinder) = &self.key_pathfinder {
+            let known_gateways = self
+                .fallback_gateways
+                .iter()
+                .chain(std::iter::once(&self.active_gateway))
+                .map(|gateway| gateway.gateway_id.as_str())
+                .filter(|id| self.key_manager.fallback_gateway_shared_key(id).is_some());
+            for gateway_id in known_gateways {
+                if let Err(err) = self
+                    .key_manager
+                    .store_fallback_gateway_key(pathfinder, gateway_id)
+                {
+                    warn!("failed to persist the shared key of gateway {gateway_id}: {err}");
+                    return;
+                }
+            }
+            if let Err(err) = self.key_manager.store_gateway_key(pathfinder) {
+                warn!("failed to persist the shared key of the new main gateway: {err}");
+                return;
+            }
+        }
+
+        match persister(&self.active_gateway, &self.fallback_gateways) {
+            Ok(_) => self.main_gateway_id = self.active_gateway.gateway_id.clone(),
+            Err(err) => {
+                warn!("failed to persist the new gateway configuration: {err}");
+                self.restore_main_gateway_key();
+            }
+        }
+    }
+
+    fn restore_main_gateway_key(&self) {
+        let (Some(pathfinder), Some(main_key)) = (
+            &self.key_pathfinder,
+            self.key_manager
+                .fallback_gateway_shared_key(&self.main_gateway_id),
+        ) else {
+            return;
+        };
+        if let Err(err) = nym_pemstore::store_key_with_passphrase(
+            main_key.as_ref(),
+            pathfinder.gateway_shared_key(),
+            pathfinder.passphrase(),
+        ) {
+            warn!("failed to restore the shared key of the main gateway: {err}")
+        }
+    }
+
+    async fn run_with_shutdown(&mut self, mut shutdown: nym_task::TaskClient) {
+        debug!("Started GatewayFailoverListener with graceful shutdown support");
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    log::trace!("GatewayFailoverListener: Received shutdown");
+                }
+                failover = self.failover_receiver.next() => {
+                    if let Some(failover) = failover {
+                        self.on_failover(failover)
+                    } else {
+                        log::trace!("GatewayFailoverListener: Stopping since channel closed");
+                        break;
+                    }
+                },
+            }
+        }
+        shutdown.recv_timeout().await;
+        log::debug!("GatewayFailoverListener: Exiting");
+    }
+
+    pub(crate) fn start_with_shutdown(mut self, shutdown: nym_task::TaskClient) {
+        spawn_future(async move { self.run_with_shutdown(shutdown).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use nym_crypto::asymmetric::identity;
+    use nym_gateway_requests::generic_array::typenum::Unsigned;
+    use nym_gateway_requests::registration::handshake::{SharedKeySize, SharedKeys};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    type PersistedConfigs = Arc<Mutex<Vec<(String, Vec<String>)>>>;
+
+    fn gateway(id: &str) -> GatewayEndpointConfig {
+        GatewayEndpointConfig::new(id.to_string(), "owner".into(), format!("ws://{id}"))
+    }
+
+    fn shared_key(byte: u8) -> Arc<SharedKeys> {
+        Arc::new(SharedKeys::try_from_bytes(&vec![byte; SharedKeySize::to_usize()]).unwrap())
+    }
+
+    fn failover(identity: identity::PublicKey, key: u8) -> GatewayFailover {
+        GatewayFailover {
+            address: format!("ws://{}", identity.to_base58_string()),
+            identity,
+            shared_key: shared_key(key),
+        }
+    }
+
+    fn key_pathfinder(dir: &Path) -> ClientKeyPathfinder {
+        ClientKeyPathfinder {
+            identity_private_key: dir.join("private_identity.pem"),
+            identity_public_key: dir.join("public_identity.pem"),
+            encryption_private_key: dir.join("private_encryption.pem"),
+            encryption_public_key: dir.join("public_encryption.pem"),
+            gateway_shared_key: dir.join("gateway_shared.pem"),
+            ack_key: dir.join("ack_key.pem"),
+            passphrase: None,
+        }
+    }
+
+    fn stored_key(path: &Path) -> Vec<u8> {
+        nym_pemstore::load_key_with_passphrase::<SharedKeys>(path, None)
+            .unwrap()
+            .to_bytes()
+    }
+
+    struct TestSetup {
+        listener: GatewayFailoverListener,
+        gateways: Vec<identity::PublicKey>,
+        persisted: PersistedConfigs,
+    }
+
+    // the client starts with the first gateway, with the rest being its fallbacks
+    fn setup(num_gateways: usize, key_pathfinder: Option<ClientKeyPathfinder>) -> TestSetup {
+        let mut rng = rand::rngs::OsRng;
+        let gateways: Vec<_> = (0..num_gateways)
+            .map(|_| *identity::KeyPair::new(&mut rng).public_key())
+            .collect();
+        let configs: Vec<_> = gateways
+            .iter()
+            .map(|identity| gateway(&identity.to_base58_string()))
+            .collect();
+
+        let mut key_manager = KeyManager::new(&mut rng);
+        key_manager.insert_gateway_shared_key(shared_key(0));
+        let self_address = SelfAddressAccessor::new(Recipient::new(
+            *key_manager.identity_keypair().public_key(),
+            *key_manager.encryption_keypair().public_key(),
+            gateways[0],
+        ));
+
+        let persisted = PersistedConfigs::default();
+        let persisted_clone = Arc::clone(&persisted);
+        let persister: GatewayConfigPersister = Box::new(move |active, fallbacks| {
+            persisted_clone.lock().unwrap().push((
+                active.gateway_id.clone(),
+                fallbacks.iter().map(|g| g.gateway_id.clone()).collect(),
+            ));
+            Ok(())
+        });
+
+        let (_, failover_receiver) = mpsc::unbounded();
+        let listener = GatewayFailoverListener::new(
+            failover_receiver,
+            self_address,
+            configs[0].clone(),
+            configs[1..].to_vec(),
+            key_manager,
+            key_pathfinder,
+            Some(persister),
+        );
+
+        TestSetup {
+            listener,
+            gateways,
+            persisted,
+        }
+    }
+
+    #[test]
+    fn failover_updates_the_active_address() {
+        let TestSetup {
+            mut listener,
+            gateways,
+            ..
+        } = setup(3, None);
+        let initial = listener.self_address.current();
+
+        listener.on_failover(failover(gateways[2], 2));
+        let current = listener.self_address.current();
+        assert_eq!(current.gateway(), &gateways[2]);
+        assert_eq!(current.identity(), initial.identity());
+        assert_eq!(current.encryption_key(), initial.encryption_key());
+    }
+
+    #[test]
+    fn failover_rotates_and_persists_the_gateways() {
+        let TestSetup {
+            mut listener,
+            gateways,
+            persisted,
+        } = setup(3, None);
+        let ids: Vec<_> = gateways.iter().map(|g| g.to_base58_string()).collect();
+
+        listener.on_failover(failover(gateways[2], 2));
+        listener.on_failover(failover(gateways[1], 1));
+        listener.on_failover(failover(gateways[0], 0));
+
+        assert_eq!(
+            *persisted.lock().unwrap(),
+            vec![
+                (ids[2].clone(), vec![ids[1].clone(), ids[0].clone()]),
+                (ids[1].clone(), vec![ids[0].clone(), ids[2].clone()]),
+                (ids[0].clone(), vec![ids[2].clone(), ids[1].clone()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn failover_rearranges_the_stored_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let pathfinder = key_pathfinder(dir.path());
+        let TestSetup {
+            mut listener,
+            gateways,
+            ..
+        } = setup(2, Some(pathfinder.clone()));
+
+        listener.on_failover(failover(gateways[1], 1));
+        assert_eq!(
+            stored_key(pathfinder.gateway_shared_key()),
+            shared_key(1).to_bytes()
+        );
+        assert_eq!(
+            stored_key(&pathfinder.fallback_gateway_shared_key(&gateways[0].to_base58_string())),
+            shared_key(0).to_bytes()
+        );
+    }
+}
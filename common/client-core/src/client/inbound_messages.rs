@@ -1,5 +1,7 @@
+use crate::client::delivery_tracker::DeliveryTracker;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::message::{MessageCompression, MessageFraming};
 use nym_task::connections::TransmissionLane;
 
 pub type InputMessageSender = tokio::sync::mpsc::Sender<InputMessage>;
@@ -15,6 +17,8 @@ pub enum InputMessage {
         recipient: Recipient,
         data: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     },
 
     /// Creates a message used for a duplex anonymous communication where the recipient
@@ -30,6 +34,8 @@ pub enum InputMessage {
         data: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     },
 
     /// Attempt to use our internally received and stored `ReplySurb` to send the message back
@@ -40,6 +46,17 @@ pub enum InputMessage {
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
+    },
+
+    /// Wraps any of the other variants and attaches a `DeliveryTracker` to all of its fragments,
+    /// so that the sender could learn whether the message has actually reached its destination.
+    ///
+    /// Note that the delivery of replies is currently not being tracked.
+    Tracked {
+        message: Box<InputMessage>,
+        tracker: DeliveryTracker,
     },
 }
 
@@ -49,6 +66,8 @@ impl InputMessage {
             recipient,
             data,
             lane,
+            compression: MessageCompression::None,
+            framing: MessageFraming::Standalone,
         }
     }
 
@@ -63,6 +82,8 @@ impl InputMessage {
             data,
             reply_surbs,
             lane,
+            compression: MessageCompression::None,
+            framing: MessageFraming::Standalone,
         }
     }
 
@@ -75,6 +96,49 @@ impl InputMessage {
             recipient_tag,
             data,
             lane,
+            compression: MessageCompression::None,
+            framing: MessageFraming::Standalone,
+        }
+    }
+
+    pub fn new_tracked(message: InputMessage, tracker: DeliveryTracker) -> Self {
+        InputMessage::Tracked {
+            message: Box::new(message),
+            tracker,
+        }
+    }
+
+    /// Specifies the compression applied to the content of the message before it's split into
+    /// sphinx packets. The recipient is going to transparently decompress it.
+    #[must_use]
+    pub fn with_compression(mut self, compression: MessageCompression) -> Self {
+        self.set_compression(compression);
+        self
+    }
+
+    fn set_compression(&mut self, new_compression: MessageCompression) {
+        match self {
+            InputMessage::Regular { compression, .. }
+            | InputMessage::Anonymous { compression, .. }
+            | InputMessage::Reply { compression, .. } => *compression = new_compression,
+            InputMessage::Tracked { message, .. } => message.set_compression(new_compression),
+        }
+    }
+
+    /// Specifies how the recipient is meant to interpret the content of the message.
+    /// It's explicitly indicated in the message header.
+    #[must_use]
+    pub fn with_framing(mut self, framing: MessageFraming) -> Self {
+        self.set_framing(framing);
+        self
+    }
+
+    fn set_framing(&mut self, new_framing: MessageFraming) {
+        match self {
+            InputMessage::Regular { framing, .. }
+            | InputMessage::Anonymous { framing, .. }
+            | InputMessage::Reply { framing, .. } => *framing = new_framing,
+            InputMessage::Tracked { message, .. } => message.set_framing(new_framing),
         }
     }
 
@@ -83,6 +147,7 @@ impl InputMessage {
             InputMessage::Regular { lane, .. }
             | InputMessage::Anonymous { lane, .. }
             | InputMessage::Reply { lane, .. } => lane,
+            InputMessage::Tracked { message, .. } => message.lane(),
         }
     }
 }
@@ -7,6 +7,7 @@ use nym_crypto::asymmetric::{encryption, identity};
 use nym_gateway_requests::registration::handshake::SharedKeys;
 use nym_sphinx::acknowledgements::AckKey;
 use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
 use std::io;
 use std::sync::Arc;
 
@@ -28,6 +29,9 @@ pub struct KeyManager {
     /// shared key derived with the gateway during "registration handshake"
     gateway_shared_key: Option<Arc<SharedKeys>>,
 
+    /// shared keys derived with each of the fallback gateways, indexed by their identities.
+    fallback_gateway_shared_keys: HashMap<String, Arc<SharedKeys>>,
+
     /// key used for producing and processing acknowledgement packets.
     ack_key: Arc<AckKey>,
 }
@@ -51,6 +55,7 @@ impl KeyManager {
             identity_keypair: Arc::new(identity::KeyPair::new(rng)),
             encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
             gateway_shared_key: None,
+            fallback_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(AckKey::new(rng)),
         }
     }
@@ -65,29 +70,38 @@ impl KeyManager {
             identity_keypair: Arc::new(id_keypair),
             encryption_keypair: Arc::new(enc_keypair),
             gateway_shared_key: Some(Arc::new(gateway_shared_key)),
+            fallback_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(ack_key),
         }
     }
 
     /// Loads previously stored client keys from the disk.
     fn load_client_keys(client_pathfinder: &ClientKeyPathfinder) -> io::Result<Self> {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 client_pathfinder.private_identity_key().to_owned(),
                 client_pathfinder.public_identity_key().to_owned(),
-            ))?;
-        let encryption_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+            ),
+            client_pathfinder.passphrase(),
+        )?;
+        let encryption_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 client_pathfinder.private_encryption_key().to_owned(),
                 client_pathfinder.public_encryption_key().to_owned(),
-            ))?;
+            ),
+            client_pathfinder.passphrase(),
+        )?;
 
-        let ack_key: AckKey = nym_pemstore::load_key(client_pathfinder.ack_key())?;
+        let ack_key: AckKey = nym_pemstore::load_key_with_passphrase(
+            client_pathfinder.ack_key(),
+            client_pathfinder.passphrase(),
+        )?;
 
         Ok(KeyManager {
             identity_keypair: Arc::new(identity_keypair),
             encryption_keypair: Arc::new(encryption_keypair),
             gateway_shared_key: None,
+            fallback_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(ack_key),
         })
     }
@@ -97,8 +111,10 @@ impl KeyManager {
     pub fn load_keys(client_pathfinder: &ClientKeyPathfinder) -> io::Result<Self> {
         let mut key_manager = Self::load_client_keys(client_pathfinder)?;
 
-        let gateway_shared_key: SharedKeys =
-            nym_pemstore::load_key(client_pathfinder.gateway_shared_key())?;
+        let gateway_shared_key: SharedKeys = nym_pemstore::load_key_with_passphrase(
+            client_pathfinder.gateway_shared_key(),
+            client_pathfinder.passphrase(),
+        )?;
 
         key_manager.gateway_shared_key = Some(Arc::new(gateway_shared_key));
 
@@ -113,7 +129,10 @@ impl KeyManager {
         let mut key_manager = Self::load_client_keys(client_pathfinder)?;
 
         let gateway_shared_key: Result<SharedKeys, io::Error> =
-            nym_pemstore::load_key(client_pathfinder.gateway_shared_key());
+            nym_pemstore::load_key_with_passphrase(
+                client_pathfinder.gateway_shared_key(),
+                client_pathfinder.passphrase(),
+            );
 
         // It's ok if the gateway key was not found
         let gateway_shared_key = match gateway_shared_key {
@@ -127,33 +146,75 @@ impl KeyManager {
         Ok(key_manager)
     }
 
+    /// Loads previously stored shared keys of the specified fallback gateways.
+    /// Gateways whose keys could not be found are ignored, as the client is going to register
+    /// with them again if it ever has to fail over to them.
+    pub fn load_fallback_gateway_keys<'a, I>(
+        &mut self,
+        client_pathfinder: &ClientKeyPathfinder,
+        gateway_ids: I,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        for gateway_id in gateway_ids {
+            let path = client_pathfinder.fallback_gateway_shared_key(gateway_id);
+            match nym_pemstore::load_key_with_passphrase::<SharedKeys>(
+                &path,
+                client_pathfinder.passphrase(),
+            ) {
+                Ok(key) => {
+                    self.fallback_gateway_shared_keys
+                        .insert(gateway_id.to_owned(), Arc::new(key));
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    debug!("no shared key stored for fallback gateway {gateway_id}")
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
     /// Stores all available keys on the disk.
     // While perhaps there is no much point in storing the `AckKey` on the disk,
     // it is done so for the consistency sake so that you wouldn't require an rng instance
     // during `load_keys` to generate the said key.
     pub fn store_keys(&self, client_pathfinder: &ClientKeyPathfinder) -> io::Result<()> {
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             self.identity_keypair.as_ref(),
             &nym_pemstore::KeyPairPath::new(
                 client_pathfinder.private_identity_key().to_owned(),
                 client_pathfinder.public_identity_key().to_owned(),
             ),
+            client_pathfinder.passphrase(),
         )?;
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             self.encryption_keypair.as_ref(),
             &nym_pemstore::KeyPairPath::new(
                 client_pathfinder.private_encryption_key().to_owned(),
                 client_pathfinder.public_encryption_key().to_owned(),
             ),
+            client_pathfinder.passphrase(),
         )?;
 
-        nym_pemstore::store_key(self.ack_key.as_ref(), client_pathfinder.ack_key())?;
+        nym_pemstore::store_key_with_passphrase(
+            self.ack_key.as_ref(),
+            client_pathfinder.ack_key(),
+            client_pathfinder.passphrase(),
+        )?;
 
         match self.gateway_shared_key.as_ref() {
             None => debug!("No gateway shared key available to store!"),
-            Some(gate_key) => {
-                nym_pemstore::store_key(gate_key.as_ref(), client_pathfinder.gateway_shared_key())?
-            }
+            Some(gate_key) => nym_pemstore::store_key_with_passphrase(
+                gate_key.as_ref(),
+                client_pathfinder.gateway_shared_key(),
+                client_pathfinder.passphrase(),
+            )?,
+        }
+
+        for gateway_id in self.fallback_gateway_shared_keys.keys() {
+            self.store_fallback_gateway_key(client_pathfinder, gateway_id)?;
         }
 
         Ok(())
@@ -167,14 +228,34 @@ impl KeyManager {
                     "trying to store a non-existing key",
                 ))
             }
-            Some(gate_key) => {
-                nym_pemstore::store_key(gate_key.as_ref(), client_pathfinder.gateway_shared_key())?
-            }
+            Some(gate_key) => nym_pemstore::store_key_with_passphrase(
+                gate_key.as_ref(),
+                client_pathfinder.gateway_shared_key(),
+                client_pathfinder.passphrase(),
+            )?,
         }
 
         Ok(())
     }
 
+    pub fn store_fallback_gateway_key(
+        &self,
+        client_pathfinder: &ClientKeyPathfinder,
+        gateway_id: &str,
+    ) -> io::Result<()> {
+        match self.fallback_gateway_shared_keys.get(gateway_id) {
+            None => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "trying to store a non-existing key",
+            )),
+            Some(gate_key) => nym_pemstore::store_key_with_passphrase(
+                gate_key.as_ref(),
+                &client_pathfinder.fallback_gateway_shared_key(gateway_id),
+                client_pathfinder.passphrase(),
+            ),
+        }
+    }
+
     /// Overwrite the existing identity keypair
     pub fn set_identity_keypair(&mut self, id_keypair: identity::KeyPair) {
         self.identity_keypair = Arc::new(id_keypair);
@@ -224,4 +305,21 @@ impl KeyManager {
     pub fn is_gateway_key_set(&self) -> bool {
         self.gateway_shared_key.is_some()
     }
+
+    /// After shared key with one of the fallback gateways is derived, puts its ownership
+    /// to this instance of a [`KeyManager`].
+    pub fn insert_fallback_gateway_shared_key(
+        &mut self,
+        gateway_id: String,
+        gateway_shared_key: Arc<SharedKeys>,
+    ) {
+        self.fallback_gateway_shared_keys
+            .insert(gateway_id, gateway_shared_key);
+    }
+
+    /// Gets an atomically reference counted pointer to [`SharedKey`] derived with the specified
+    /// fallback gateway, if we have registered with it before.
+    pub fn fallback_gateway_shared_key(&self, gateway_id: &str) -> Option<Arc<SharedKeys>> {
+        self.fallback_gateway_shared_keys.get(gateway_id).cloned()
+    }
 }
@@ -0,0 +1,28 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use lazy_static::lazy_static;
+use nym_metrics::{register_int_counter, IntCounter};
+
+lazy_static! {
+    pub(crate) static ref PACKETS_SENT: IntCounter = register_int_counter(
+        "client_packets_sent_total",
+        "Number of sphinx packets (both real and cover) sent by the client to its gateway"
+    );
+    pub(crate) static ref PACKETS_DROPPED: IntCounter = register_int_counter(
+        "client_packets_dropped_total",
+        "Number of sphinx packets the client failed to send to its gateway"
+    );
+    pub(crate) static ref PACKETS_RECEIVED: IntCounter = register_int_counter(
+        "client_packets_received_total",
+        "Number of packets received by the client from its gateway"
+    );
+    pub(crate) static ref ACK_RETRANSMISSIONS: IntCounter = register_int_counter(
+        "client_ack_retransmissions_total",
+        "Number of packets that had to be retransmitted due to not receiving an ack in time"
+    );
+    pub(crate) static ref TOPOLOGY_REFRESH_FAILURES: IntCounter = register_int_counter(
+        "client_topology_refresh_failures_total",
+        "Number of failed attempts to obtain a new network topology"
+    );
+}
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::metrics;
 use crate::spawn_future;
 use log::*;
 use nym_gateway_client::GatewayClient;
@@ -53,6 +54,7 @@ where
 
     async fn on_messages(&mut self, mut mix_packets: Vec<MixPacket>) {
         debug_assert!(!mix_packets.is_empty());
+        let packets = mix_packets.len() as u64;
 
         let result = if mix_packets.len() == 1 {
             let mix_packet = mix_packets.pop().unwrap();
@@ -66,6 +68,7 @@ where
         match result {
             Err(err) => {
                 error!("Failed to send sphinx packet(s) to the gateway! - {err}");
+                metrics::PACKETS_DROPPED.inc_by(packets);
                 self.consecutive_gateway_failure_count += 1;
                 if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
                     // todo: in the future this should initiate a 'graceful' shutdown or try
@@ -75,6 +78,7 @@ where
             }
             Ok(_) => {
                 trace!("We *might* have managed to forward sphinx packet(s) to the gateway!");
+                metrics::PACKETS_SENT.inc_by(packets);
                 self.consecutive_gateway_failure_count = 0;
             }
         }
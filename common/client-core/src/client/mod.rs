@@ -3,12 +3,16 @@
 
 pub mod base_client;
 pub mod cover_traffic_stream;
+pub mod delivery_tracker;
+pub(crate) mod gateway_failover;
 pub(crate) mod helpers;
 pub mod inbound_messages;
 pub mod key_manager;
+pub(crate) mod metrics;
 pub mod mix_traffic;
 pub mod real_messages_control;
 pub mod received_buffer;
 pub mod replies;
+pub mod self_address;
 pub mod topology_control;
 pub(crate) mod transmission_buffer;
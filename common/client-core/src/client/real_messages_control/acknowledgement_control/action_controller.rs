@@ -2,22 +2,50 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::PendingAcknowledgement;
+use crate::client::delivery_tracker::DeliveryError;
 use crate::client::real_messages_control::acknowledgement_control::RetransmissionRequestSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
 use nym_nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue, QueueKey};
+use nym_sphinx::chunking::fec::can_recover_set;
 use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use nym_sphinx::Delay as SphinxDelay;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
 pub(crate) type AckActionSender = mpsc::UnboundedSender<Action>;
 pub(crate) type AckActionReceiver = mpsc::UnboundedReceiver<Action>;
 
-// The actual data being sent off as well as potential key to the delay queue
-type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>);
+// The actual data being sent off, potential key to the delay queue and the number of times
+// the data has already been retransmitted
+type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>, u32);
+
+/// Erasure coded set with some of its data or parity `Fragment`s not yet acknowledged.
+struct PendingErasureCodedSet {
+    total_data: u8,
+    total_parity: u8,
+    pending: HashSet<FragmentIdentifier>,
+}
+
+impl PendingErasureCodedSet {
+    fn can_be_recovered(&self, recipient_supports_erasure_coding: bool) -> bool {
+        if !recipient_supports_erasure_coding {
+            // recipients running older versions discard the parity fragments,
+            // so they have to receive every single data fragment
+            return self.pending.iter().all(|frag_id| frag_id.is_parity());
+        }
+
+        can_recover_set(
+            self.total_data,
+            self.total_parity,
+            self.pending
+                .iter()
+                .map(|frag_id| frag_id.fragment_position()),
+        )
+    }
+}
 
 // we can either:
 // - have a completely new set of packets we just sent and need to create entries for
@@ -69,6 +97,10 @@ pub(super) struct Config {
 
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
+
+    /// Specifies whether the recipients are known to be able to rebuild erasure coded sets.
+    /// If they're not, the data fragments are retransmitted until all of them get acknowledged.
+    recipients_support_erasure_coding: bool,
 }
 
 impl Config {
@@ -76,8 +108,17 @@ impl Config {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            recipients_support_erasure_coding: false,
         }
     }
+
+    pub(super) fn with_erasure_coding_support(
+        mut self,
+        recipients_support_erasure_coding: bool,
+    ) -> Self {
+        self.recipients_support_erasure_coding = recipients_support_erasure_coding;
+        self
+    }
 }
 
 pub(super) struct ActionController {
@@ -88,6 +129,12 @@ pub(super) struct ActionController {
     /// key to its `AckDelayQueue` entry if it was started.
     pending_acks_data: HashMap<FragmentIdentifier, PendingAckEntry>,
 
+    /// Erasure coded sets that are still waiting for some of their acknowledgements. Once enough
+    /// of their `Fragment`s got acknowledged for the recipient to be able to rebuild them,
+    /// the remaining ones are no longer retransmitted. Unless the recipients are known to support
+    /// erasure coding, that only happens once all of the data `Fragment`s got acknowledged.
+    erasure_coded_sets: HashMap<i32, PendingErasureCodedSet>,
+
     // This structure ensures that we will EITHER handle expired timer or a received action and NEVER both
     // at the same time hence getting rid of one possible race condition that we suffered from in the
     // previous version.
@@ -111,6 +158,7 @@ impl ActionController {
         ActionController {
             config,
             pending_acks_data: HashMap::new(),
+            erasure_coded_sets: HashMap::new(),
             pending_acks_timers: NonExhaustiveDelayQueue::new(),
             incoming_actions,
             retransmission_sender,
@@ -118,13 +166,31 @@ impl ActionController {
     }
 
     fn handle_insert(&mut self, pending_acks: Vec<PendingAcknowledgement>) {
+        // only the sets that got erasure coded have any parity fragments
+        for pending_ack in &pending_acks {
+            let fragment = &pending_ack.message_chunk;
+            if fragment.is_parity() {
+                self.erasure_coded_sets
+                    .entry(fragment.id())
+                    .or_insert_with(|| PendingErasureCodedSet {
+                        total_data: fragment.total_data_fragments(),
+                        total_parity: fragment.total_fragments(),
+                        pending: HashSet::new(),
+                    });
+            }
+        }
+
         for pending_ack in pending_acks {
             let frag_id = pending_ack.message_chunk.fragment_identifier();
             trace!("{} is inserted", frag_id);
 
+            if let Some(erasure_coded_set) = self.erasure_coded_sets.get_mut(&frag_id.set_id()) {
+                erasure_coded_set.pending.insert(frag_id);
+            }
+
             if self
                 .pending_acks_data
-                .insert(frag_id, (Arc::new(pending_ack), None))
+                .insert(frag_id, (Arc::new(pending_ack), None, 0))
                 .is_some()
             {
                 panic!("Tried to insert duplicate pending ack")
@@ -135,7 +201,7 @@ impl ActionController {
     fn handle_start_timer(&mut self, frag_id: FragmentIdentifier) {
         trace!("{} is starting its timer", frag_id);
 
-        if let Some((pending_ack_data, queue_key)) = self.pending_acks_data.get_mut(&frag_id) {
+        if let Some((pending_ack_data, queue_key, _)) = self.pending_acks_data.get_mut(&frag_id) {
             // the fact that this branch is now POSSIBLE is a sign of a need to refactor this whole
             // retransmission procedure
             //
@@ -170,7 +236,11 @@ impl ActionController {
                     frag_id
                 );
             }
-            Some((_, queue_key)) => {
+            Some((pending_ack_data, queue_key, _)) => {
+                if let Some(delivery_tracker) = &pending_ack_data.delivery_tracker {
+                    delivery_tracker.fragment_acknowledged()
+                }
+
                 if let Some(queue_key) = queue_key {
                     // there are no possible checks here, we must GUARANTEE that we NEVER try
                     // to remove an entry that doesn't exist (and we MUST GUARANTEE that
@@ -185,6 +255,45 @@ impl ActionController {
                         frag_id
                     );
                 }
+
+                self.handle_erasure_coded_ack(frag_id);
+            }
+        }
+    }
+
+    // once the recipient is able to rebuild the erasure coded set from the already acknowledged
+    // fragments, there's no point in retransmitting the remaining ones. note that recipients
+    // running older versions are only able to do so once they got all of the data fragments
+    fn handle_erasure_coded_ack(&mut self, frag_id: FragmentIdentifier) {
+        let set_id = frag_id.set_id();
+        let Some(erasure_coded_set) = self.erasure_coded_sets.get_mut(&set_id) else {
+            return;
+        };
+
+        erasure_coded_set.pending.remove(&frag_id);
+        if !erasure_coded_set.can_be_recovered(self.config.recipients_support_erasure_coding) {
+            return;
+        }
+
+        let Some(erasure_coded_set) = self.erasure_coded_sets.remove(&set_id) else {
+            return;
+        };
+        if !erasure_coded_set.pending.is_empty() {
+            debug!(
+                "set {set_id} can be rebuilt by the recipient - no longer waiting for {} of its fragments",
+                erasure_coded_set.pending.len()
+            );
+        }
+
+        for frag_id in erasure_coded_set.pending {
+            if let Some((pending_ack_data, queue_key, _)) = self.pending_acks_data.remove(&frag_id)
+            {
+                if let Some(delivery_tracker) = &pending_ack_data.delivery_tracker {
+                    delivery_tracker.fragment_acknowledged()
+                }
+                if let Some(queue_key) = queue_key {
+                    self.pending_acks_timers.remove(&queue_key);
+                }
             }
         }
     }
@@ -194,7 +303,9 @@ impl ActionController {
     fn handle_update_delay(&mut self, frag_id: FragmentIdentifier, delay: SphinxDelay) {
         trace!("{} is updating its delay", frag_id);
         // TODO: is it possible to solve this without either locking or temporarily removing the value?
-        if let Some((pending_ack_data, queue_key)) = self.pending_acks_data.remove(&frag_id) {
+        if let Some((pending_ack_data, queue_key, retransmissions)) =
+            self.pending_acks_data.remove(&frag_id)
+        {
             // this Action is triggered by `RetransmissionRequestListener` (for 'normal' packets)
             // or `ReplyController` (for 'reply' packets) which held the other potential
             // reference to this Arc. HOWEVER, before the Action was pushed onto the queue, the reference
@@ -203,7 +314,7 @@ impl ActionController {
             inner_data.update_delay(delay);
 
             self.pending_acks_data
-                .insert(frag_id, (Arc::new(inner_data), queue_key));
+                .insert(frag_id, (Arc::new(inner_data), queue_key, retransmissions));
         } else {
             debug!(
                 "Tried to UPDATE TIMER on pending ack that is already gone! - {}",
@@ -225,13 +336,32 @@ impl ActionController {
 
         trace!("{} has expired", frag_id);
 
-        if let Some((pending_ack_data, queue_key)) = self.pending_acks_data.get_mut(&frag_id) {
+        if let Some((pending_ack_data, queue_key, retransmissions)) =
+            self.pending_acks_data.get_mut(&frag_id)
+        {
             if queue_key.is_none() {
                 // this branch should be IMPOSSIBLE under ANY condition. It would imply the timeout
                 // happened before it even started.
                 panic!("Ack expired before it was even scheduled!")
             }
             *queue_key = None;
+
+            // if somebody is waiting on the delivery of this data, we can't keep retransmitting it forever
+            if let Some(delivery_tracker) = &pending_ack_data.delivery_tracker {
+                if *retransmissions >= delivery_tracker.max_retransmissions() {
+                    debug!("{frag_id} has exhausted all of its retransmissions");
+                    delivery_tracker.fail(DeliveryError::RetransmissionsExhausted {
+                        fragment: frag_id,
+                        retransmissions: *retransmissions,
+                    });
+                    self.pending_acks_data.remove(&frag_id);
+                    // the delivery has already failed, so there's nothing left to track
+                    self.erasure_coded_sets.remove(&frag_id.set_id());
+                    return;
+                }
+            }
+            *retransmissions += 1;
+
             // downgrading an arc and then upgrading vs cloning is difference of 30ns vs 15ns
             // so it's literally a NO difference while it might prevent us from unnecessarily
             // resending data (in maybe 1 in 1 million cases, but it's something)
@@ -290,3 +420,92 @@ impl ActionController {
         log::debug!("ActionController: Exiting");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_sphinx::addressing::clients::Recipient;
+    use nym_sphinx::chunking::{split_into_sets_with_redundancy, RedundancyRatio};
+    use rand::rngs::OsRng;
+
+    fn test_controller(recipients_support_erasure_coding: bool) -> ActionController {
+        let config = Config::new(Duration::from_secs(1), 1.5)
+            .with_erasure_coding_support(recipients_support_erasure_coding);
+        let (retransmission_sender, _) = mpsc::unbounded();
+        let (_, incoming_actions) = mpsc::unbounded();
+        ActionController::new(config, retransmission_sender, incoming_actions)
+    }
+
+    // inserts a single erasure coded set and returns the identifiers of its data and parity fragments
+    fn insert_erasure_coded_set(
+        controller: &mut ActionController,
+    ) -> (Vec<FragmentIdentifier>, Vec<FragmentIdentifier>) {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let redundancy = RedundancyRatio::new(0.5).unwrap();
+        let mut sets = split_into_sets_with_redundancy(&mut OsRng, &[42; 4000], 1000, redundancy);
+        assert_eq!(sets.len(), 1);
+
+        let fragments = sets.pop().unwrap();
+        let (parity, data): (Vec<_>, Vec<_>) = fragments
+            .iter()
+            .map(|fragment| fragment.fragment_identifier())
+            .partition(|frag_id| frag_id.is_parity());
+
+        controller.handle_insert(
+            fragments
+                .into_iter()
+                .map(|fragment| {
+                    PendingAcknowledgement::new_known(
+                        fragment,
+                        SphinxDelay::new_from_nanos(42),
+                        recipient,
+                    )
+                })
+                .collect(),
+        );
+        (data, parity)
+    }
+
+    #[test]
+    fn rebuildable_set_is_not_retransmitted_if_recipient_supports_erasure_coding() {
+        let mut controller = test_controller(true);
+        let (data, parity) = insert_erasure_coded_set(&mut controller);
+
+        // a single lost data fragment can be recovered from the parity fragments
+        for frag_id in parity.iter().chain(&data[1..]) {
+            controller.handle_remove(*frag_id);
+        }
+        assert!(controller.pending_acks_data.is_empty());
+        assert!(controller.erasure_coded_sets.is_empty());
+    }
+
+    #[test]
+    fn data_fragments_are_retransmitted_to_recipients_without_erasure_coding_support() {
+        let mut controller = test_controller(false);
+        let (data, parity) = insert_erasure_coded_set(&mut controller);
+
+        // the recipient would discard the parity fragments, so it needs the missing data fragment
+        for frag_id in parity.iter().chain(&data[1..]) {
+            controller.handle_remove(*frag_id);
+        }
+        assert_eq!(controller.pending_acks_data.len(), 1);
+        assert!(controller.pending_acks_data.contains_key(&data[0]));
+
+        controller.handle_remove(data[0]);
+        assert!(controller.pending_acks_data.is_empty());
+        assert!(controller.erasure_coded_sets.is_empty());
+    }
+
+    #[test]
+    fn parity_fragments_are_not_retransmitted_once_all_data_fragments_are_acknowledged() {
+        let mut controller = test_controller(false);
+        let (data, parity) = insert_erasure_coded_set(&mut controller);
+        assert!(!parity.is_empty());
+
+        for frag_id in &data {
+            controller.handle_remove(*frag_id);
+        }
+        assert!(controller.pending_acks_data.is_empty());
+        assert!(controller.erasure_coded_sets.is_empty());
+    }
+}
@@ -1,12 +1,14 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery_tracker::{DeliveryError, DeliveryTracker};
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver};
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
 use log::*;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::message::{MessageCompression, MessageFraming};
 use nym_task::connections::TransmissionLane;
 use rand::{CryptoRng, Rng};
 
@@ -46,10 +48,12 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) {
         // offload reply handling to the dedicated task
         self.reply_controller_sender
-            .send_reply(recipient_tag, data, lane)
+            .send_reply(recipient_tag, data, lane, compression, framing)
     }
 
     async fn handle_plain_message(
@@ -57,55 +61,128 @@ where
         recipient: Recipient,
         content: Vec<u8>,
         lane: TransmissionLane,
+        delivery_tracker: Option<DeliveryTracker>,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_plain_message(recipient, content, lane)
+            .try_send_plain_message(
+                recipient,
+                content,
+                lane,
+                delivery_tracker.clone(),
+                compression,
+                framing,
+            )
             .await
         {
-            warn!("failed to send a plain message - {err}")
+            warn!("failed to send a plain message - {err}");
+            if let Some(delivery_tracker) = delivery_tracker {
+                delivery_tracker.fail(DeliveryError::PreparationFailure {
+                    reason: err.to_string(),
+                })
+            }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_repliable_message(
         &mut self,
         recipient: Recipient,
         content: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+        delivery_tracker: Option<DeliveryTracker>,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_message_with_reply_surbs(recipient, content, reply_surbs, lane)
+            .try_send_message_with_reply_surbs(
+                recipient,
+                content,
+                reply_surbs,
+                lane,
+                delivery_tracker.clone(),
+                compression,
+                framing,
+            )
             .await
         {
-            warn!("failed to send a repliable message - {err}")
+            warn!("failed to send a repliable message - {err}");
+            if let Some(delivery_tracker) = delivery_tracker {
+                delivery_tracker.fail(DeliveryError::PreparationFailure {
+                    reason: err.to_string(),
+                })
+            }
         }
     }
 
-    async fn on_input_message(&mut self, msg: InputMessage) {
+    async fn on_input_message(&mut self, mut msg: InputMessage) {
+        let mut delivery_tracker = None;
+        // if the tracked messages were (for some reason) nested, only the innermost tracker is used
+        // and the outer ones are going to get notified about tracking being aborted
+        while let InputMessage::Tracked { message, tracker } = msg {
+            delivery_tracker = Some(tracker);
+            msg = *message;
+        }
+
         match msg {
             InputMessage::Regular {
                 recipient,
                 data,
                 lane,
-            } => self.handle_plain_message(recipient, data, lane).await,
+                compression,
+                framing,
+            } => {
+                self.handle_plain_message(
+                    recipient,
+                    data,
+                    lane,
+                    delivery_tracker,
+                    compression,
+                    framing,
+                )
+                .await
+            }
             InputMessage::Anonymous {
                 recipient,
                 data,
                 reply_surbs,
                 lane,
+                compression,
+                framing,
             } => {
-                self.handle_repliable_message(recipient, data, reply_surbs, lane)
-                    .await
+                self.handle_repliable_message(
+                    recipient,
+                    data,
+                    reply_surbs,
+                    lane,
+                    delivery_tracker,
+                    compression,
+                    framing,
+                )
+                .await
             }
             InputMessage::Reply {
                 recipient_tag,
                 data,
                 lane,
+                compression,
+                framing,
             } => {
-                self.handle_reply(recipient_tag, data, lane).await;
+                if let Some(delivery_tracker) = delivery_tracker {
+                    // replies are retransmitted by the reply controller whenever it gets hold of
+                    // enough reply surbs, so we have no sensible way of tracking them (yet)
+                    warn!("attempted to track the delivery of a reply - it's not going to be sent");
+                    delivery_tracker.fail(DeliveryError::UnsupportedReply);
+                    return;
+                }
+                self.handle_reply(recipient_tag, data, lane, compression, framing)
+                    .await;
             }
+            InputMessage::Tracked { .. } => unreachable!("tracked messages are unwrapped above"),
         };
     }
 
@@ -7,6 +7,7 @@ use self::{
     retransmission_request_listener::RetransmissionRequestListener,
     sent_notification_listener::SentNotificationListener,
 };
+use crate::client::delivery_tracker::DeliveryTracker;
 use crate::client::inbound_messages::InputMessageReceiver;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
@@ -69,6 +70,7 @@ pub(crate) struct PendingAcknowledgement {
     message_chunk: Fragment,
     delay: SphinxDelay,
     destination: PacketDestination,
+    delivery_tracker: Option<DeliveryTracker>,
 }
 
 impl PendingAcknowledgement {
@@ -82,6 +84,7 @@ impl PendingAcknowledgement {
             message_chunk,
             delay,
             destination: PacketDestination::KnownRecipient(recipient.into()),
+            delivery_tracker: None,
         }
     }
 
@@ -98,9 +101,15 @@ impl PendingAcknowledgement {
                 recipient_tag,
                 extra_surb_request,
             },
+            delivery_tracker: None,
         }
     }
 
+    pub(crate) fn with_delivery_tracker(mut self, delivery_tracker: DeliveryTracker) -> Self {
+        self.delivery_tracker = Some(delivery_tracker);
+        self
+    }
+
     pub(crate) fn inner_fragment_identifier(&self) -> FragmentIdentifier {
         self.message_chunk.fragment_identifier()
     }
@@ -164,6 +173,9 @@ pub(super) struct Config {
 
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
+
+    /// Specifies whether the recipients are known to be able to rebuild erasure coded sets.
+    recipients_support_erasure_coding: bool,
 }
 
 impl Config {
@@ -172,6 +184,7 @@ impl Config {
             ack_wait_addition,
             ack_wait_multiplier,
             packet_size: Default::default(),
+            recipients_support_erasure_coding: false,
         }
     }
 
@@ -179,6 +192,11 @@ impl Config {
         self.packet_size = packet_size;
         self
     }
+
+    pub fn with_erasure_coding_support(mut self, recipients_support_erasure_coding: bool) -> Self {
+        self.recipients_support_erasure_coding = recipients_support_erasure_coding;
+        self
+    }
 }
 
 pub(super) struct AcknowledgementController<R>
@@ -206,7 +224,8 @@ where
         let (retransmission_tx, retransmission_rx) = mpsc::unbounded();
 
         let action_config =
-            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier);
+            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier)
+                .with_erasure_coding_support(config.recipients_support_erasure_coding);
         let action_controller = ActionController::new(
             action_config,
             retransmission_tx,
@@ -5,6 +5,7 @@ use super::{
     action_controller::{AckActionSender, Action},
     PendingAcknowledgement, RetransmissionRequestReceiver,
 };
+use crate::client::metrics;
 use crate::client::real_messages_control::acknowledgement_control::PacketDestination;
 use crate::client::real_messages_control::message_handler::{MessageHandler, PreparationError};
 use crate::client::real_messages_control::real_traffic_stream::RealMessage;
@@ -67,6 +68,7 @@ where
                 return;
             }
         };
+        metrics::ACK_RETRANSMISSIONS.inc();
 
         let maybe_prepared_fragment = match &timed_out_ack.destination {
             PacketDestination::Anonymous {
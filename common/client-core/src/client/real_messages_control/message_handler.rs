@@ -1,12 +1,14 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery_tracker::DeliveryTracker;
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
 use crate::client::real_messages_control::real_traffic_stream::{
     BatchRealMessageSender, RealMessage,
 };
 use crate::client::real_messages_control::{AckActionSender, Action};
 use crate::client::replies::reply_storage::{ReceivedReplySurbsMap, SentReplyKeys, UsedSenderTags};
+use crate::client::self_address::SelfAddressAccessor;
 use crate::client::topology_control::{TopologyAccessor, TopologyReadPermit};
 use log::{debug, error, info, trace, warn};
 use nym_sphinx::acknowledgements::AckKey;
@@ -14,8 +16,9 @@ use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, RepliableMessage, ReplyMessage};
 use nym_sphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
 use nym_sphinx::chunking::fragment::{Fragment, FragmentIdentifier};
-use nym_sphinx::message::NymMessage;
-use nym_sphinx::params::{PacketSize, DEFAULT_NUM_MIX_HOPS};
+use nym_sphinx::chunking::RedundancyRatio;
+use nym_sphinx::message::{MessageCompression, MessageFraming, NymMessage};
+use nym_sphinx::params::{PacketMode, PacketSize, DEFAULT_NUM_MIX_HOPS};
 use nym_sphinx::preparer::{MessagePreparer, PreparedFragment};
 use nym_sphinx::Delay;
 use nym_task::connections::TransmissionLane;
@@ -89,7 +92,7 @@ pub(crate) struct Config {
 
     /// Address of this client which also represent an address to which all acknowledgements
     /// and surb-based are going to be sent.
-    sender_address: Recipient,
+    sender_address: SelfAddressAccessor,
 
     /// Average delay a data packet is going to get delay at a single mixnode.
     average_packet_delay: Duration,
@@ -106,12 +109,18 @@ pub(crate) struct Config {
 
     /// Optional secondary predefined packet size used for the encapsulated messages.
     secondary_packet_size: Option<PacketSize>,
+
+    /// Mode of the packets used for the encapsulated messages.
+    packet_mode: PacketMode,
+
+    /// Optional ratio of parity to data fragments attached to each fragment set of sent messages.
+    redundancy: Option<RedundancyRatio>,
 }
 
 impl Config {
     pub fn new(
         ack_key: Arc<AckKey>,
-        sender_address: Recipient,
+        sender_address: SelfAddressAccessor,
         average_packet_delay: Duration,
         average_ack_delay: Duration,
     ) -> Self {
@@ -123,6 +132,8 @@ impl Config {
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             primary_packet_size: PacketSize::default(),
             secondary_packet_size: None,
+            packet_mode: PacketMode::default(),
+            redundancy: None,
         }
     }
 
@@ -144,6 +155,18 @@ impl Config {
         self.secondary_packet_size = packet_size;
         self
     }
+
+    /// Allows setting non-default mode of the packets sent out.
+    pub fn with_custom_packet_mode(mut self, packet_mode: PacketMode) -> Self {
+        self.packet_mode = packet_mode;
+        self
+    }
+
+    /// Allows attaching erasure coded parity fragments to the messages sent out.
+    pub fn with_message_redundancy(mut self, redundancy: Option<RedundancyRatio>) -> Self {
+        self.redundancy = redundancy;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -176,11 +199,13 @@ where
     {
         let message_preparer = MessagePreparer::new(
             rng,
-            config.sender_address,
+            config.sender_address.current(),
             config.average_packet_delay,
             config.average_ack_delay,
         )
-        .with_mix_hops(config.num_mix_hops);
+        .with_mix_hops(config.num_mix_hops)
+        .with_packet_mode(config.packet_mode)
+        .with_redundancy(config.redundancy);
 
         MessageHandler {
             config,
@@ -207,11 +232,17 @@ where
         }
     }
 
+    // make sure all acks and reply SURBs we create are going to be routed via our current gateway
+    fn refresh_sender_address(&mut self) {
+        self.message_preparer
+            .set_sender_address(self.config.sender_address.current())
+    }
+
     fn get_topology<'a>(
         &self,
         permit: &'a TopologyReadPermit<'a>,
     ) -> Result<&'a NymTopology, PreparationError> {
-        match permit.try_get_valid_topology_ref(&self.config.sender_address, None) {
+        match permit.try_get_valid_topology_ref(&self.config.sender_address.current(), None) {
             Ok(topology_ref) => Ok(topology_ref),
             Err(err) => {
                 warn!("Could not process the packet - the network topology is invalid - {err}");
@@ -246,6 +277,7 @@ where
         &mut self,
         amount: usize,
     ) -> Result<(Vec<ReplySurb>, Vec<SurbEncryptionKey>), PreparationError> {
+        self.refresh_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -272,9 +304,12 @@ where
         let packet_size = self.optimal_packet_size(&msg);
         debug!("Using {packet_size} packets for {msg}");
 
-        let mut fragment = self
-            .message_preparer
-            .pad_and_split_message(msg, packet_size);
+        let mut fragment = self.message_preparer.pad_and_split_message(
+            msg,
+            packet_size,
+            MessageCompression::None,
+            MessageFraming::Standalone,
+        );
         if fragment.len() > 1 {
             // well, it's not a single surb message
             return Err(SurbWrappedPreparationError {
@@ -317,19 +352,24 @@ where
         debug!("requesting {amount} reply SURBs from {from}");
 
         let surbs_request =
-            ReplyMessage::new_surb_request_message(self.config.sender_address, amount);
+            ReplyMessage::new_surb_request_message(self.config.sender_address.current(), amount);
         self.try_send_single_surb_message(from, surbs_request, reply_surb, true)
             .await
     }
 
     // // TODO: this will require additional argument to make it use different variant of `ReplyMessage`
-    pub(crate) fn split_reply_message(&mut self, message: Vec<u8>) -> Vec<Fragment> {
+    pub(crate) fn split_reply_message(
+        &mut self,
+        message: Vec<u8>,
+        compression: MessageCompression,
+        framing: MessageFraming,
+    ) -> Vec<Fragment> {
         let msg = NymMessage::new_reply(ReplyMessage::new_data_message(message));
         let packet_size = self.optimal_packet_size(&msg);
         debug!("Using {packet_size} packets for {msg}");
 
         self.message_preparer
-            .pad_and_split_message(msg, packet_size)
+            .pad_and_split_message(msg, packet_size, compression, framing)
     }
 
     pub(crate) async fn send_retransmission_reply_chunks(
@@ -406,10 +446,20 @@ where
         recipient: Recipient,
         message: Vec<u8>,
         lane: TransmissionLane,
+        delivery_tracker: Option<DeliveryTracker>,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) -> Result<(), PreparationError> {
         let message = NymMessage::new_plain(message);
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            delivery_tracker,
+            compression,
+            framing,
+        )
+        .await
     }
 
     pub(crate) async fn try_split_and_send_non_reply_message(
@@ -417,19 +467,27 @@ where
         message: NymMessage,
         recipient: Recipient,
         lane: TransmissionLane,
+        delivery_tracker: Option<DeliveryTracker>,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) -> Result<(), PreparationError> {
         // TODO: I really dislike existence of this assertion, it implies code has to be re-organised
         debug_assert!(!matches!(message, NymMessage::Reply(_)));
 
         // TODO2: it's really annoying we have to get topology permit again here due to borrow-checker
+        self.refresh_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
         let packet_size = self.optimal_packet_size(&message);
         debug!("Using {packet_size} packets for {message}");
-        let fragments = self
-            .message_preparer
-            .pad_and_split_message(message, packet_size);
+        let fragments =
+            self.message_preparer
+                .pad_and_split_message(message, packet_size, compression, framing);
+
+        if let Some(delivery_tracker) = &delivery_tracker {
+            delivery_tracker.expect_fragments(fragments.len());
+        }
 
         let mut pending_acks = Vec::with_capacity(fragments.len());
         let mut real_messages = Vec::with_capacity(fragments.len());
@@ -447,7 +505,10 @@ where
             let real_message =
                 RealMessage::new(prepared_fragment.mix_packet, fragment.fragment_identifier());
             let delay = prepared_fragment.total_delay;
-            let pending_ack = PendingAcknowledgement::new_known(fragment, delay, recipient);
+            let mut pending_ack = PendingAcknowledgement::new_known(fragment, delay, recipient);
+            if let Some(delivery_tracker) = &delivery_tracker {
+                pending_ack = pending_ack.with_delivery_tracker(delivery_tracker.clone());
+            }
 
             real_messages.push(real_message);
             pending_acks.push(pending_ack);
@@ -477,6 +538,9 @@ where
             message,
             recipient,
             TransmissionLane::AdditionalReplySurbs,
+            None,
+            MessageCompression::None,
+            MessageFraming::Standalone,
         )
         .await?;
 
@@ -486,12 +550,16 @@ where
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_send_message_with_reply_surbs(
         &mut self,
         recipient: Recipient,
         message: Vec<u8>,
         num_reply_surbs: u32,
         lane: TransmissionLane,
+        delivery_tracker: Option<DeliveryTracker>,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) -> Result<(), SurbWrappedPreparationError> {
         let sender_tag = self.get_or_create_sender_tag(&recipient);
         let (reply_surbs, reply_keys) = self
@@ -501,8 +569,15 @@ where
         let message =
             NymMessage::new_repliable(RepliableMessage::new_data(message, sender_tag, reply_surbs));
 
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await?;
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            delivery_tracker,
+            compression,
+            framing,
+        )
+        .await?;
 
         log::trace!("storing {} reply keys", reply_keys.len());
         self.reply_key_storage.insert_multiple(reply_keys);
@@ -515,6 +590,7 @@ where
         recipient: Recipient,
         chunk: Fragment,
     ) -> Result<PreparedFragment, PreparationError> {
+        self.refresh_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -539,6 +615,7 @@ where
             reply_surbs.len()
         );
 
+        self.refresh_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
@@ -567,6 +644,7 @@ where
         reply_surb: ReplySurb,
         chunk: Fragment,
     ) -> Result<PreparedFragment, SurbWrappedPreparationError> {
+        self.refresh_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
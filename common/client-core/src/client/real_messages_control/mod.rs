@@ -25,12 +25,12 @@ use futures::channel::mpsc;
 use log::*;
 use nym_gateway_client::AcknowledgementReceiver;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_task::connections::{ConnectionCommandReceiver, LaneQueueLengths};
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::sync::Arc;
 
 use crate::client::replies::reply_controller;
+use crate::client::self_address::SelfAddressAccessor;
 use crate::config;
 pub(crate) use acknowledgement_control::{AckActionSender, Action};
 
@@ -44,7 +44,7 @@ pub struct Config {
     ack_key: Arc<AckKey>,
 
     /// Address of `this` client.
-    self_recipient: Recipient,
+    self_recipient: SelfAddressAccessor,
 
     /// Specifies all traffic related configuration options.
     traffic: config::Traffic,
@@ -66,6 +66,7 @@ impl<'a> From<&'a Config> for acknowledgement_control::Config {
             cfg.acks.ack_wait_multiplier,
         )
         .with_custom_packet_size(cfg.traffic.primary_packet_size)
+        .with_erasure_coding_support(cfg.traffic.recipients_support_erasure_coding)
     }
 }
 
@@ -73,7 +74,7 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
     fn from(cfg: &'a Config) -> Self {
         real_traffic_stream::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.acks.average_ack_delay,
             cfg.traffic,
             cfg.cover_traffic.cover_traffic_primary_size_ratio,
@@ -91,12 +92,14 @@ impl<'a> From<&'a Config> for message_handler::Config {
     fn from(cfg: &'a Config) -> Self {
         message_handler::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.traffic.average_packet_delay,
             cfg.acks.average_ack_delay,
         )
         .with_custom_primary_packet_size(cfg.traffic.primary_packet_size)
         .with_custom_secondary_packet_size(cfg.traffic.secondary_packet_size)
+        .with_custom_packet_mode(cfg.traffic.packet_mode)
+        .with_message_redundancy(cfg.traffic.message_redundancy)
     }
 }
 
@@ -104,7 +107,7 @@ impl Config {
     pub fn new(
         base_client_debug_config: &config::DebugConfig,
         ack_key: Arc<AckKey>,
-        self_recipient: Recipient,
+        self_recipient: SelfAddressAccessor,
     ) -> Self {
         Config {
             ack_key,
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::metrics;
 use crate::client::replies::reply_controller::ReplyControllerSender;
 use crate::client::replies::reply_storage::SentReplyKeys;
 use crate::spawn_future;
@@ -15,7 +16,7 @@ use nym_sphinx::anonymous_replies::requests::{
     RepliableMessage, RepliableMessageContent, ReplyMessage, ReplyMessageContent,
 };
 use nym_sphinx::anonymous_replies::{encryption_key::EncryptionKeyDigest, SurbEncryptionKey};
-use nym_sphinx::message::{NymMessage, PlainMessage};
+use nym_sphinx::message::{MessageFraming, NymMessage, PlainMessage};
 use nym_sphinx::params::ReplySurbKeyDigestAlgorithm;
 use nym_sphinx::receiver::{MessageReceiver, MessageRecoveryError, ReconstructedMessage};
 use std::collections::HashSet;
@@ -46,7 +47,10 @@ struct ReceivedMessagesBufferInner<R: MessageReceiver> {
 }
 
 impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
-    fn recover_from_fragment(&mut self, fragment_data: &[u8]) -> Option<NymMessage> {
+    fn recover_from_fragment(
+        &mut self,
+        fragment_data: &[u8],
+    ) -> Option<(NymMessage, MessageFraming)> {
         if nym_sphinx::cover::is_cover(fragment_data) {
             trace!("The message was a loop cover message! Skipping it");
             return None;
@@ -84,14 +88,14 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
                 ),
             },
             Ok(reconstruction_result) => match reconstruction_result {
-                Some((reconstructed_message, used_sets)) => {
+                Some((reconstructed_message, framing, used_sets)) => {
                     for set_id in used_sets {
                         if !self.recently_reconstructed.insert(set_id) {
                             // or perhaps we should even panic at this point?
                             error!("Reconstructed another message containing already used set id!")
                         }
                     }
-                    Some(reconstructed_message)
+                    Some((reconstructed_message, framing))
                 }
                 None => None,
             },
@@ -102,7 +106,7 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
         &mut self,
         reply_ciphertext: &mut [u8],
         reply_key: SurbEncryptionKey,
-    ) -> Result<Option<NymMessage>, MessageRecoveryError> {
+    ) -> Result<Option<(NymMessage, MessageFraming)>, MessageRecoveryError> {
         // note: this performs decryption IN PLACE without extra allocation
         self.message_receiver
             .recover_plaintext_from_reply(reply_ciphertext, reply_key)?;
@@ -111,7 +115,10 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
         Ok(self.recover_from_fragment(fragment_data))
     }
 
-    fn process_received_regular_packet(&mut self, mut raw_fragment: Vec<u8>) -> Option<NymMessage> {
+    fn process_received_regular_packet(
+        &mut self,
+        mut raw_fragment: Vec<u8>,
+    ) -> Option<(NymMessage, MessageFraming)> {
         let fragment_data = match self.message_receiver.recover_plaintext_from_regular_packet(
             self.local_encryption_keypair.private_key(),
             &mut raw_fragment,
@@ -195,17 +202,19 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
 
     fn handle_reconstructed_plain_messages(
         &mut self,
-        msgs: Vec<PlainMessage>,
+        msgs: Vec<(PlainMessage, MessageFraming)>,
     ) -> Vec<ReconstructedMessage> {
-        msgs.into_iter().map(Into::into).collect()
+        msgs.into_iter()
+            .map(|(msg, framing)| ReconstructedMessage::from(msg).with_framing(framing))
+            .collect()
     }
 
     fn handle_reconstructed_repliable_messages(
         &mut self,
-        msgs: Vec<RepliableMessage>,
+        msgs: Vec<(RepliableMessage, MessageFraming)>,
     ) -> Vec<ReconstructedMessage> {
         let mut reconstructed = Vec::new();
-        for msg in msgs {
+        for (msg, framing) in msgs {
             let (reply_surbs, from_surb_request) = match msg.content {
                 RepliableMessageContent::Data {
                     message,
@@ -217,7 +226,9 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
                         msg.sender_tag
                     );
 
-                    reconstructed.push(ReconstructedMessage::new(message, msg.sender_tag));
+                    reconstructed.push(
+                        ReconstructedMessage::new(message, msg.sender_tag).with_framing(framing),
+                    );
 
                     (reply_surbs, false)
                 }
@@ -248,12 +259,14 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
 
     fn handle_reconstructed_reply_messages(
         &mut self,
-        msgs: Vec<ReplyMessage>,
+        msgs: Vec<(ReplyMessage, MessageFraming)>,
     ) -> Vec<ReconstructedMessage> {
         let mut reconstructed = Vec::new();
-        for msg in msgs {
+        for (msg, framing) in msgs {
             match msg.content {
-                ReplyMessageContent::Data { message } => reconstructed.push(message.into()),
+                ReplyMessageContent::Data { message } => {
+                    reconstructed.push(ReconstructedMessage::from(message).with_framing(framing))
+                }
                 ReplyMessageContent::SurbRequest { recipient, amount } => {
                     debug!("received request for {amount} additional reply SURBs from {recipient}");
                     self.reply_controller_sender
@@ -264,7 +277,7 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         reconstructed
     }
 
-    async fn handle_reconstructed_messages(&mut self, msgs: Vec<NymMessage>) {
+    async fn handle_reconstructed_messages(&mut self, msgs: Vec<(NymMessage, MessageFraming)>) {
         if msgs.is_empty() {
             return;
         }
@@ -273,11 +286,11 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         let mut repliable_messages = Vec::new();
         let mut reply_messages = Vec::new();
 
-        for msg in msgs {
+        for (msg, framing) in msgs {
             match msg {
-                NymMessage::Plain(plain) => plain_messages.push(plain),
-                NymMessage::Repliable(repliable) => repliable_messages.push(repliable),
-                NymMessage::Reply(reply) => reply_messages.push(reply),
+                NymMessage::Plain(plain) => plain_messages.push((plain, framing)),
+                NymMessage::Repliable(repliable) => repliable_messages.push((repliable, framing)),
+                NymMessage::Reply(reply) => reply_messages.push((reply, framing)),
             }
         }
 
@@ -336,6 +349,7 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
             "Processing {:?} new message that might get added to the buffer!",
             msgs.len()
         );
+        metrics::PACKETS_RECEIVED.inc_by(msgs.len() as u64);
 
         let mut completed_messages = Vec::new();
         let mut inner_guard = self.inner.lock().await;
@@ -353,7 +367,7 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
                 };
 
             if let Some(completed) = completed_message {
-                info!("received {completed}");
+                info!("received {}", completed.0);
                 completed_messages.push(completed)
             }
         }
@@ -11,6 +11,7 @@ use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::anonymous_replies::ReplySurb;
 use nym_sphinx::chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx::message::{MessageCompression, MessageFraming};
 use nym_task::connections::{ConnectionId, TransmissionLane};
 use rand::{CryptoRng, Rng};
 use std::cmp::{max, min};
@@ -185,6 +186,8 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) {
         if !self
             .full_reply_storage
@@ -196,7 +199,9 @@ where
         }
 
         trace!("handling reply to {:?}", recipient_tag);
-        let mut fragments = self.message_handler.split_reply_message(data);
+        let mut fragments = self
+            .message_handler
+            .split_reply_message(data, compression, framing);
         let total_size = fragments.len();
         trace!("This reply requires {:?} SURBs", total_size);
 
@@ -653,7 +658,12 @@ where
                 recipient,
                 message,
                 lane,
-            } => self.handle_send_reply(recipient, message, lane).await,
+                compression,
+                framing,
+            } => {
+                self.handle_send_reply(recipient, message, lane, compression, framing)
+                    .await
+            }
             ReplyControllerMessage::AdditionalSurbs {
                 sender_tag,
                 reply_surbs,
@@ -7,6 +7,7 @@ use log::error;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::anonymous_replies::ReplySurb;
+use nym_sphinx::message::{MessageCompression, MessageFraming};
 use nym_task::connections::{ConnectionId, TransmissionLane};
 use std::sync::Weak;
 
@@ -45,12 +46,16 @@ impl ReplyControllerSender {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) {
         self.0
             .unbounded_send(ReplyControllerMessage::SendReply {
                 recipient,
                 message,
                 lane,
+                compression,
+                framing,
             })
             .expect("ReplyControllerReceiver has died!")
     }
@@ -131,6 +136,8 @@ pub(crate) enum ReplyControllerMessage {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        compression: MessageCompression,
+        framing: MessageFraming,
     },
 
     AdditionalSurbs {
@@ -0,0 +1,29 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_sphinx::addressing::clients::Recipient;
+use std::sync::{Arc, RwLock};
+
+/// Shared view of the address of this client. It only ever changes when the client fails over
+/// to a different gateway, at which point all components are going to start using the new address
+/// for any acks, reply SURBs and loop cover traffic they create.
+#[derive(Clone, Debug)]
+pub struct SelfAddressAccessor {
+    inner: Arc<RwLock<Recipient>>,
+}
+
+impl SelfAddressAccessor {
+    pub fn new(address: Recipient) -> Self {
+        SelfAddressAccessor {
+            inner: Arc::new(RwLock::new(address)),
+        }
+    }
+
+    pub fn current(&self) -> Recipient {
+        *self.inner.read().expect("self address lock is poisoned")
+    }
+
+    pub(crate) fn update(&self, address: Recipient) {
+        *self.inner.write().expect("self address lock is poisoned") = address;
+    }
+}
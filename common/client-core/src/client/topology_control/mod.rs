@@ -1,12 +1,13 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::metrics;
 use crate::spawn_future;
 pub(crate) use accessor::{TopologyAccessor, TopologyReadPermit};
 use futures::StreamExt;
 use log::*;
 use nym_topology::provider_trait::TopologyProvider;
-use nym_topology::NymTopologyError;
+use nym_topology::{NymTopologyError, RouteSelectionStrategy};
 use std::time::Duration;
 
 mod accessor;
@@ -17,11 +18,15 @@ const MAX_FAILURE_COUNT: usize = 10;
 
 pub struct TopologyRefresherConfig {
     refresh_rate: Duration,
+    route_selection: RouteSelectionStrategy,
 }
 
 impl TopologyRefresherConfig {
-    pub fn new(refresh_rate: Duration) -> Self {
-        TopologyRefresherConfig { refresh_rate }
+    pub fn new(refresh_rate: Duration, route_selection: RouteSelectionStrategy) -> Self {
+        TopologyRefresherConfig {
+            refresh_rate,
+            route_selection,
+        }
     }
 }
 
@@ -30,6 +35,7 @@ pub struct TopologyRefresher {
     topology_accessor: TopologyAccessor,
 
     refresh_rate: Duration,
+    route_selection: RouteSelectionStrategy,
     consecutive_failure_count: usize,
 }
 
@@ -43,6 +49,7 @@ impl TopologyRefresher {
             topology_provider,
             topology_accessor,
             refresh_rate: cfg.refresh_rate,
+            route_selection: cfg.route_selection,
             consecutive_failure_count: 0,
         }
     }
@@ -61,8 +68,13 @@ impl TopologyRefresher {
                 .await;
         }
 
-        let new_topology = self.topology_provider.get_new_topology().await;
+        let new_topology = self
+            .topology_provider
+            .get_new_topology()
+            .await
+            .map(|topology| topology.with_route_selection(self.route_selection.clone()));
         if new_topology.is_none() {
+            metrics::TOPOLOGY_REFRESH_FAILURES.inc();
             warn!("failed to obtain new network topology");
         }
 
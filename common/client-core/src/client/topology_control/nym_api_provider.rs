@@ -3,10 +3,12 @@
 
 use async_trait::async_trait;
 use log::{error, warn};
+use nym_topology::mix::{MixId, Performance};
 use nym_topology::provider_trait::TopologyProvider;
 use nym_topology::{nym_topology_from_detailed, NymTopology, NymTopologyError};
 use rand::prelude::SliceRandom;
 use rand::thread_rng;
+use std::collections::HashMap;
 use url::Url;
 
 pub(crate) struct NymApiTopologyProvider {
@@ -15,10 +17,18 @@ pub(crate) struct NymApiTopologyProvider {
 
     client_version: String,
     currently_used_api: usize,
+
+    // whether the provider should also retrieve the performance of the mixnodes,
+    // which requires querying the more expensive 'detailed' endpoint
+    include_performance: bool,
 }
 
 impl NymApiTopologyProvider {
-    pub(crate) fn new(mut nym_api_urls: Vec<Url>, client_version: String) -> Self {
+    pub(crate) fn new(
+        mut nym_api_urls: Vec<Url>,
+        client_version: String,
+        include_performance: bool,
+    ) -> Self {
         nym_api_urls.shuffle(&mut thread_rng());
 
         NymApiTopologyProvider {
@@ -28,6 +38,7 @@ impl NymApiTopologyProvider {
             nym_api_urls,
             client_version,
             currently_used_api: 0,
+            include_performance,
         }
     }
 
@@ -61,12 +72,33 @@ impl NymApiTopologyProvider {
     }
 
     async fn get_current_compatible_topology(&mut self) -> Option<NymTopology> {
-        let mixnodes = match self.validator_client.get_cached_active_mixnodes().await {
-            Err(err) => {
-                error!("failed to get network mixnodes - {err}");
-                return None;
+        let (mixnodes, performance) = if self.include_performance {
+            match self
+                .validator_client
+                .get_cached_active_mixnodes_detailed()
+                .await
+            {
+                Err(err) => {
+                    error!("failed to get detailed network mixnodes - {err}");
+                    return None;
+                }
+                Ok(mixes) => {
+                    let performance: HashMap<MixId, Performance> = mixes
+                        .iter()
+                        .map(|mix| (mix.mix_id(), mix.node_performance.most_recent))
+                        .collect();
+                    let mixnodes = mixes.into_iter().map(|mix| mix.mixnode_details).collect();
+                    (mixnodes, Some(performance))
+                }
+            }
+        } else {
+            match self.validator_client.get_cached_active_mixnodes().await {
+                Err(err) => {
+                    error!("failed to get network mixnodes - {err}");
+                    return None;
+                }
+                Ok(mixes) => (mixes, None),
             }
-            Ok(mixes) => mixes,
         };
 
         let gateways = match self.validator_client.get_cached_gateways().await {
@@ -77,9 +109,13 @@ impl NymApiTopologyProvider {
             Ok(gateways) => gateways,
         };
 
-        let topology = nym_topology_from_detailed(mixnodes, gateways)
+        let mut topology = nym_topology_from_detailed(mixnodes, gateways)
             .filter_system_version(&self.client_version);
 
+        if let Some(performance) = performance {
+            topology.update_mix_performance(&performance)
+        }
+
         if let Err(err) = self.check_layer_distribution(&topology) {
             warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used: {err}");
             self.use_next_nym_api();
@@ -3,7 +3,9 @@
 
 use nym_config::defaults::NymNetworkDetails;
 use nym_config::{NymConfig, OptionalSet, CRED_DB_FILE_NAME};
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::chunking::RedundancyRatio;
+use nym_sphinx::params::{PacketMode, PacketSize};
+use nym_topology::RouteSelectionStrategy;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -188,6 +190,18 @@ impl<T> Config<T> {
         self
     }
 
+    pub fn set_fallback_gateway_endpoints(&mut self, endpoints: Vec<GatewayEndpointConfig>) {
+        self.client.fallback_gateway_endpoints = endpoints;
+    }
+
+    pub fn with_fallback_gateway_endpoints(
+        mut self,
+        endpoints: Vec<GatewayEndpointConfig>,
+    ) -> Self {
+        self.client.fallback_gateway_endpoints = endpoints;
+        self
+    }
+
     pub fn with_gateway_id<S: Into<String>>(&mut self, id: S) {
         self.client.gateway_endpoint.gateway_id = id.into();
     }
@@ -302,6 +316,10 @@ impl<T> Config<T> {
         &self.client.gateway_endpoint
     }
 
+    pub fn get_fallback_gateway_endpoints(&self) -> &[GatewayEndpointConfig] {
+        &self.client.fallback_gateway_endpoints
+    }
+
     pub fn get_database_path(&self) -> PathBuf {
         self.client.database_path.clone()
     }
@@ -502,6 +520,11 @@ pub struct Client<T> {
     /// Information regarding how the client should send data to gateway.
     pub gateway_endpoint: GatewayEndpointConfig,
 
+    /// Ranked list of additional gateways the client has registered with. If the client fails
+    /// to reconnect to its current gateway, it switches to the first reachable one out of those.
+    #[serde(default)]
+    pub fallback_gateway_endpoints: Vec<GatewayEndpointConfig>,
+
     /// Path to the database containing bandwidth credentials of this client.
     pub database_path: PathBuf,
 
@@ -552,6 +575,7 @@ impl<T: NymConfig> Default for Client<T> {
             gateway_shared_key_file: Default::default(),
             ack_key_file: Default::default(),
             gateway_endpoint: Default::default(),
+            fallback_gateway_endpoints: Vec::new(),
             database_path: Default::default(),
             reply_surb_database_path: Default::default(),
             nym_root_directory: T::default_root_directory(),
@@ -627,10 +651,33 @@ pub struct Traffic {
     /// Note that its use decreases overall anonymity.
     /// Do not set it it unless you understand the consequences of that change.
     pub secondary_packet_size: Option<PacketSize>,
+
+    /// Specifies the format of the packets used for sent messages, i.e. either sphinx ("mix")
+    /// or outfox ("outfox"). Acknowledgements, replies and cover traffic always use sphinx.
+    /// Note that outfox packets are delayed by the mix nodes by their own average delay
+    /// rather than the configured `average_packet_delay`.
+    pub packet_mode: PacketMode,
+
+    /// If set, every fragment set of a sent message is going to be accompanied by Reed-Solomon
+    /// parity fragments, so that the recipient could reconstruct it despite losing some packets
+    /// without waiting for retransmission. The value specifies the ratio of parity to data fragments,
+    /// e.g. `0.25` results in one additional packet for every four data packets.
+    /// Note that recipients running older versions are going to discard the parity fragments.
+    pub message_redundancy: Option<RedundancyRatio>,
+
+    /// Indicates that the recipients of the sent messages are known to run versions capable
+    /// of rebuilding erasure coded fragment sets. Only then the client stops retransmitting
+    /// the data fragments of a set once enough of its other fragments got acknowledged.
+    /// Otherwise all of the data fragments are retransmitted until they're acknowledged.
+    pub recipients_support_erasure_coding: bool,
 }
 
 impl Traffic {
     pub fn validate(&self) -> bool {
+        // the 'vpn' mode is deprecated and its packets are rejected by the mix nodes
+        if self.packet_mode.is_old_vpn() {
+            return false;
+        }
         if let Some(secondary_packet_size) = self.secondary_packet_size {
             if secondary_packet_size == PacketSize::AckPacket
                 || secondary_packet_size == self.primary_packet_size
@@ -650,6 +697,9 @@ impl Default for Traffic {
             disable_main_poisson_packet_distribution: false,
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: None,
+            packet_mode: PacketMode::Mix,
+            message_redundancy: None,
+            recipients_support_erasure_coding: false,
         }
     }
 }
@@ -743,6 +793,9 @@ pub struct Topology {
     /// did not reach its destination.
     #[serde(with = "humantime_serde")]
     pub topology_resolution_timeout: Duration,
+
+    /// Specifies how mixnodes are chosen on each layer when constructing packet routes.
+    pub route_selection: RouteSelection,
 }
 
 impl Default for Topology {
@@ -750,6 +803,32 @@ impl Default for Topology {
         Topology {
             topology_refresh_rate: DEFAULT_TOPOLOGY_REFRESH_RATE,
             topology_resolution_timeout: DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
+            route_selection: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteSelection {
+    /// Every mixnode on given layer is equally likely to be chosen.
+    #[default]
+    Uniform,
+
+    /// Mixnodes are chosen with probability proportional to their total stake.
+    StakeWeighted,
+
+    /// Mixnodes are chosen with probability proportional to their performance,
+    /// as reported by the nym-api.
+    PerformanceWeighted,
+}
+
+impl From<RouteSelection> for RouteSelectionStrategy {
+    fn from(value: RouteSelection) -> Self {
+        match value {
+            RouteSelection::Uniform => RouteSelectionStrategy::Uniform,
+            RouteSelection::StakeWeighted => RouteSelectionStrategy::StakeWeighted,
+            RouteSelection::PerformanceWeighted => RouteSelectionStrategy::PerformanceWeighted,
         }
     }
 }
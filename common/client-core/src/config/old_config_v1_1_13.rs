@@ -14,7 +14,7 @@ use crate::config::{
     DEFAULT_TOPOLOGY_REFRESH_RATE, DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
 };
 use nym_config::NymConfig;
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::params::{PacketMode, PacketSize};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::time::Duration;
@@ -125,6 +125,9 @@ impl From<OldDebugConfigV1_1_13> for DebugConfig {
                     .disable_main_poisson_packet_distribution,
                 primary_packet_size: PacketSize::RegularPacket,
                 secondary_packet_size: value.use_extended_packet_size.map(Into::into),
+                packet_mode: PacketMode::Mix,
+                message_redundancy: None,
+                recipients_support_erasure_coding: false,
             },
             cover_traffic: CoverTraffic {
                 loop_cover_traffic_average_delay: value.loop_cover_traffic_average_delay,
@@ -142,6 +145,7 @@ impl From<OldDebugConfigV1_1_13> for DebugConfig {
             topology: Topology {
                 topology_refresh_rate: value.topology_refresh_rate,
                 topology_resolution_timeout: value.topology_resolution_timeout,
+                route_selection: Default::default(),
             },
             reply_surbs: ReplySurbs {
                 minimum_reply_surb_storage_threshold: value.minimum_reply_surb_storage_threshold,
@@ -206,6 +210,7 @@ impl<T, U> From<OldConfigV1_1_13<T>> for Config<U> {
                 gateway_shared_key_file: value.client.gateway_shared_key_file,
                 ack_key_file: value.client.ack_key_file,
                 gateway_endpoint: value.client.gateway_endpoint,
+                fallback_gateway_endpoints: Vec::new(),
                 database_path: value.client.database_path,
                 reply_surb_database_path: value.client.reply_surb_database_path,
                 nym_root_directory: value.client.nym_root_directory,
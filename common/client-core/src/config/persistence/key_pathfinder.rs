@@ -3,9 +3,10 @@
 
 use crate::config::Config;
 use nym_config::NymConfig;
+use nym_pemstore::Passphrase;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientKeyPathfinder {
     pub identity_private_key: PathBuf,
     pub identity_public_key: PathBuf,
@@ -13,6 +14,10 @@ pub struct ClientKeyPathfinder {
     pub encryption_public_key: PathBuf,
     pub gateway_shared_key: PathBuf,
     pub ack_key: PathBuf,
+
+    /// Passphrase used for encrypting the private and shared keys. If not set, the keys are
+    /// stored in plaintext.
+    pub passphrase: Option<Passphrase>,
 }
 
 impl ClientKeyPathfinder {
@@ -26,6 +31,7 @@ impl ClientKeyPathfinder {
             encryption_public_key: config_dir.join("public_encryption.pem"),
             gateway_shared_key: config_dir.join("gateway_shared.pem"),
             ack_key: config_dir.join("ack_key.pem"),
+            passphrase: Passphrase::from_env(),
         }
     }
 
@@ -37,9 +43,25 @@ impl ClientKeyPathfinder {
             encryption_public_key: config.get_public_encryption_key_file(),
             gateway_shared_key: config.get_gateway_shared_key_file(),
             ack_key: config.get_ack_key_file(),
+            passphrase: Passphrase::from_env(),
         }
     }
 
+    #[must_use]
+    pub fn with_passphrase(mut self, passphrase: Option<Passphrase>) -> Self {
+        self.passphrase = passphrase;
+        self
+    }
+
+    pub fn passphrase(&self) -> Option<&Passphrase> {
+        self.passphrase.as_ref()
+    }
+
+    /// Directory containing all the keys of the client.
+    pub fn keys_dir(&self) -> Option<&Path> {
+        self.identity_private_key.parent()
+    }
+
     pub fn any_file_exists(&self) -> bool {
         matches!(self.identity_public_key.try_exists(), Ok(true))
             || matches!(self.identity_private_key.try_exists(), Ok(true))
@@ -82,6 +104,13 @@ impl ClientKeyPathfinder {
         &self.gateway_shared_key
     }
 
+    /// Path to the shared key derived with one of the fallback gateways.
+    /// It is stored alongside the key of the main gateway.
+    pub fn fallback_gateway_shared_key(&self, gateway_id: &str) -> PathBuf {
+        self.gateway_shared_key
+            .with_file_name(format!("gateway_shared_{gateway_id}.pem"))
+    }
+
     pub fn ack_key(&self) -> &Path {
         &self.ack_key
     }
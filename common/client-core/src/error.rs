@@ -86,6 +86,12 @@ pub enum ClientCoreError {
 
     #[error("Unexpected exit")]
     UnexpectedExit,
+
+    #[error(
+        "The keys passphrase has to be provided via the {} environment variable",
+        nym_pemstore::PASSPHRASE_ENV_VAR
+    )]
+    MissingKeysPassphrase,
 }
 
 /// Set of messages that the client can send to listeners via the task manager
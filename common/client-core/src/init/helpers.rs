@@ -224,6 +224,42 @@ pub(super) async fn query_gateway_details(
     }
 }
 
+/// Returns all available gateways, apart from the excluded ones, ordered by their preference,
+/// i.e. either by their latency or randomly.
+pub(super) async fn query_ranked_gateways(
+    validator_servers: Vec<Url>,
+    excluded: &[identity::PublicKey],
+    by_latency: bool,
+) -> Result<Vec<gateway::Node>, ClientCoreError> {
+    let mut rng = thread_rng();
+    let mut gateways = current_gateways(&mut rng, validator_servers)
+        .await?
+        .into_iter()
+        .filter(|gateway| !excluded.contains(&gateway.identity_key))
+        .collect::<Vec<_>>();
+
+    if !by_latency {
+        gateways.shuffle(&mut rng);
+        return Ok(gateways);
+    }
+
+    info!("ranking gateways by latency...");
+    let mut gateways_with_latency = Vec::new();
+    for gateway in gateways {
+        let id = *gateway.identity();
+        match measure_latency(gateway).await {
+            Ok(res) => gateways_with_latency.push(res),
+            Err(err) => warn!("failed to measure {id}: {err}"),
+        }
+    }
+    gateways_with_latency.sort_by_key(|item| item.latency);
+
+    Ok(gateways_with_latency
+        .into_iter()
+        .map(|item| item.gateway)
+        .collect())
+}
+
 pub(super) async fn register_with_gateway<St: Storage>(
     gateway: &gateway::Node,
     our_identity: Arc<identity::KeyPair>,
@@ -4,6 +4,7 @@
 //! Collection of initialization steps used by client implementations
 
 use std::fmt::Display;
+use std::path::PathBuf;
 
 use nym_sphinx::addressing::{clients::Recipient, nodes::NodeIdentity};
 use rand::rngs::OsRng;
@@ -152,6 +153,68 @@ where
     Ok(gateway.into())
 }
 
+/// Register with up to `count` additional gateways, other than the main one specified in the
+/// provided `Config`, that the client is going to fail over to if its main gateway becomes
+/// unreachable. The derived shared keys are stored alongside the existing client keys and
+/// the endpoints of the gateways are returned in the order of their preference.
+pub async fn setup_fallback_gateways<T, St>(
+    config: &Config<T>,
+    count: usize,
+    by_latency: bool,
+) -> Result<Vec<GatewayEndpointConfig>, ClientCoreError>
+where
+    T: NymConfig,
+    St: Storage,
+{
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let pathfinder = ClientKeyPathfinder::new_from_config(config);
+    let mut key_manager = KeyManager::load_keys_but_gateway_is_optional(&pathfinder)
+        .tap_err(|err| log::error!("Failed to load the client keys: {err}"))?;
+    let our_identity = key_manager.identity_keypair();
+
+    let main_gateway = identity::PublicKey::from_base58_string(config.get_gateway_id())
+        .map_err(ClientCoreError::UnableToCreatePublicKeyFromGatewayId)?;
+    let candidates =
+        helpers::query_ranked_gateways(config.get_nym_api_endpoints(), &[main_gateway], by_latency)
+            .await?;
+
+    let mut fallback_gateways = Vec::with_capacity(count);
+    for gateway in candidates {
+        if fallback_gateways.len() == count {
+            break;
+        }
+
+        let gateway_id = gateway.identity_key.to_base58_string();
+        eprintln!("Registering with fallback gateway {gateway_id}");
+        let shared_keys =
+            match helpers::register_with_gateway::<St>(&gateway, our_identity.clone()).await {
+                Ok(shared_keys) => shared_keys,
+                Err(err) => {
+                    log::warn!("Failed to register with fallback gateway {gateway_id}: {err}");
+                    continue;
+                }
+            };
+
+        key_manager.insert_fallback_gateway_shared_key(gateway_id.clone(), shared_keys);
+        key_manager
+            .store_fallback_gateway_key(&pathfinder, &gateway_id)
+            .tap_err(|err| log::error!("Failed to store the gateway key: {err}"))?;
+        fallback_gateways.push(gateway.into());
+    }
+
+    if fallback_gateways.len() < count {
+        log::warn!(
+            "Managed to register with only {} out of {count} requested fallback gateways",
+            fallback_gateways.len()
+        );
+    }
+
+    Ok(fallback_gateways)
+}
+
 /// Read and reuse the existing gateway configuration from a file that was generate earlier.
 pub fn load_existing_gateway_config<T>(id: &str) -> Result<GatewayEndpointConfig, ClientCoreError>
 where
@@ -185,6 +248,26 @@ pub fn get_client_address(
     )
 }
 
+/// Encrypt, in place, all the private and shared keys of the client using the passphrase
+/// provided via the environment. Returns paths to all the keys that got encrypted.
+pub fn encrypt_stored_keys<T>(config: &Config<T>) -> Result<Vec<PathBuf>, ClientCoreError>
+where
+    T: NymConfig,
+{
+    let pathfinder = ClientKeyPathfinder::new_from_config(config);
+    let passphrase = pathfinder
+        .passphrase()
+        .ok_or(ClientCoreError::MissingKeysPassphrase)?;
+    let keys_dir = pathfinder.keys_dir().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "could not determine the keys directory",
+        )
+    })?;
+
+    Ok(nym_pemstore::encrypt_pem_directory(keys_dir, passphrase)?)
+}
+
 /// Get the client address by loading the keys from stored files.
 pub fn get_client_address_from_stored_keys<T>(
     config: &Config<T>,
@@ -195,24 +278,28 @@ where
     fn load_identity_keys(
         pathfinder: &ClientKeyPathfinder,
     ) -> Result<identity::KeyPair, ClientCoreError> {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .tap_err(|_| log::error!("Failed to read stored identity key files"))?;
+            ),
+            pathfinder.passphrase(),
+        )
+        .tap_err(|_| log::error!("Failed to read stored identity key files"))?;
         Ok(identity_keypair)
     }
 
     fn load_sphinx_keys(
         pathfinder: &ClientKeyPathfinder,
     ) -> Result<encryption::KeyPair, ClientCoreError> {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .tap_err(|_| log::error!("Failed to read stored sphinx key files"))?;
+            ),
+            pathfinder.passphrase(),
+        )
+        .tap_err(|_| log::error!("Failed to read stored sphinx key files"))?;
         Ok(sphinx_keypair)
     }
 
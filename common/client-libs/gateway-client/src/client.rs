@@ -8,6 +8,7 @@ pub use crate::packet_router::{
 };
 use crate::socket_state::{PartiallyDelegated, SocketState};
 use crate::{cleanup_socket_message, try_decrypt_binary_message};
+use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
 use log::*;
 use nym_bandwidth_controller::BandwidthController;
@@ -21,6 +22,7 @@ use nym_network_defaults::{REMAINING_BANDWIDTH_THRESHOLD, TOKENS_TO_BURN};
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_task::TaskClient;
 use rand::rngs::OsRng;
+use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::sync::Arc;
 use std::time::Duration;
@@ -42,6 +44,41 @@ use wasm_utils::websocket::JSWebsocket;
 const DEFAULT_RECONNECTION_ATTEMPTS: usize = 10;
 const DEFAULT_RECONNECTION_BACKOFF: Duration = Duration::from_secs(5);
 
+/// Gateway the client is going to switch to once it fails to reconnect to its current gateway.
+#[derive(Clone)]
+pub struct FallbackGateway {
+    pub address: String,
+    pub identity: identity::PublicKey,
+    /// Keys derived during a prior registration. If not provided, the client is going to
+    /// register with the gateway during the failover.
+    pub shared_key: Option<Arc<SharedKeys>>,
+}
+
+impl FallbackGateway {
+    pub fn new(
+        address: String,
+        identity: identity::PublicKey,
+        shared_key: Option<Arc<SharedKeys>>,
+    ) -> Self {
+        FallbackGateway {
+            address,
+            identity,
+            shared_key,
+        }
+    }
+}
+
+/// Information about a gateway the client has switched to after failing to reconnect to the previous one.
+#[derive(Clone)]
+pub struct GatewayFailover {
+    pub address: String,
+    pub identity: identity::PublicKey,
+    pub shared_key: Arc<SharedKeys>,
+}
+
+pub type GatewayFailoverSender = mpsc::UnboundedSender<GatewayFailover>;
+pub type GatewayFailoverReceiver = mpsc::UnboundedReceiver<GatewayFailover>;
+
 pub struct GatewayClient<C, St: Storage> {
     authenticated: bool,
     disabled_credentials_mode: bool,
@@ -64,6 +101,13 @@ pub struct GatewayClient<C, St: Storage> {
     /// Delay between each subsequent reconnection attempt.
     reconnection_backoff: Duration,
 
+    // failover related variables
+    /// Ranked gateways the client is going to attempt to use, in order, once it has failed to reconnect
+    /// to its current gateway. Gateways it has moved away from are put at the back of the queue.
+    fallback_gateways: VecDeque<FallbackGateway>,
+    /// Channel used for announcing the gateway the client has failed over to.
+    failover_sender: Option<GatewayFailoverSender>,
+
     /// Listen to shutdown messages.
     shutdown: TaskClient,
 }
@@ -101,6 +145,8 @@ where
             should_reconnect_on_failure: true,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
+            fallback_gateways: VecDeque::new(),
+            failover_sender: None,
             shutdown,
         }
     }
@@ -122,14 +168,20 @@ where
         self.reconnection_backoff = backoff
     }
 
+    pub fn with_fallback_gateways(&mut self, fallback_gateways: Vec<FallbackGateway>) {
+        self.fallback_gateways = fallback_gateways.into()
+    }
+
+    pub fn with_failover_notifications(&mut self, failover_sender: GatewayFailoverSender) {
+        self.failover_sender = Some(failover_sender)
+    }
+
     pub fn new_init(
         gateway_address: String,
         gateway_identity: identity::PublicKey,
         local_identity: Arc<identity::KeyPair>,
         response_timeout_duration: Duration,
     ) -> Self {
-        use futures::channel::mpsc;
-
         // note: this packet_router is completely invalid in normal circumstances, but "works"
         // perfectly fine here, because it's not meant to be used
         let (ack_tx, _) = mpsc::unbounded();
@@ -152,6 +204,8 @@ where
             should_reconnect_on_failure: false,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
+            fallback_gateways: VecDeque::new(),
+            failover_sender: None,
             shutdown,
         }
     }
@@ -257,7 +311,98 @@ where
                     "failed to reconnect after {} attempts",
                     self.reconnection_attempts
                 );
-                Err(err)
+                if self.fallback_gateways.is_empty() {
+                    Err(err)
+                } else {
+                    self.attempt_failover().await
+                }
+            }
+        }
+    }
+
+    // try each of the fallback gateways, in order, until we manage to connect to one of them.
+    // note: the same requirements as for `attempt_reconnection` apply
+    async fn attempt_failover(&mut self) -> Result<(), GatewayClientError> {
+        let mut last_err = GatewayClientError::ConnectionNotEstablished;
+
+        for _ in 0..self.fallback_gateways.len() {
+            let Some(fallback) = self.fallback_gateways.pop_front() else {
+                break;
+            };
+
+            info!(
+                "Attempting to fail over to gateway {} at {}...",
+                fallback.identity.to_base58_string(),
+                fallback.address
+            );
+
+            // put the gateway we're moving away from at the back of the queue so that
+            // we could come back to it if everything else fails
+            let previous = FallbackGateway {
+                address: std::mem::replace(&mut self.gateway_address, fallback.address),
+                identity: std::mem::replace(&mut self.gateway_identity, fallback.identity),
+                shared_key: std::mem::replace(&mut self.shared_key, fallback.shared_key),
+            };
+            self.fallback_gateways.push_back(previous);
+            self.connection = SocketState::NotConnected;
+            self.authenticated = false;
+            self.bandwidth_remaining = 0;
+
+            match self.try_failover().await {
+                Ok(shared_key) => {
+                    info!(
+                        "managed to fail over to gateway {}!",
+                        self.gateway_identity.to_base58_string()
+                    );
+                    self.notify_failover(shared_key);
+                    return Ok(());
+                }
+                Err(err) => {
+                    warn!(
+                        "failed to fail over to gateway {} - {err}",
+                        self.gateway_identity.to_base58_string()
+                    );
+                    last_err = err
+                }
+            }
+        }
+
+        error!("failed to fail over to any of the fallback gateways");
+        Err(last_err)
+    }
+
+    async fn try_failover(&mut self) -> Result<Arc<SharedKeys>, GatewayClientError> {
+        self.establish_connection().await?;
+        let shared_key = self.perform_initial_authentication().await?;
+
+        if self.bandwidth_remaining < REMAINING_BANDWIDTH_THRESHOLD {
+            if self.disabled_credentials_mode {
+                self.try_claim_testnet_bandwidth().await?;
+            } else {
+                // credentials can only be spent explicitly (via `claim_bandwidth`),
+                // so we can't do it automatically in the middle of sending packets
+                warn!(
+                    "the new gateway reports only {} bandwidth remaining. You might have to claim more of it",
+                    self.bandwidth_remaining
+                );
+            }
+        }
+
+        // this call is NON-blocking
+        self.start_listening_for_mixnet_messages()?;
+
+        Ok(shared_key)
+    }
+
+    fn notify_failover(&self, shared_key: Arc<SharedKeys>) {
+        if let Some(failover_sender) = &self.failover_sender {
+            let failover = GatewayFailover {
+                address: self.gateway_address.clone(),
+                identity: self.gateway_identity,
+                shared_key,
+            };
+            if failover_sender.unbounded_send(failover).is_err() {
+                warn!("failed to announce the gateway failover - the receiver has been dropped")
             }
         }
     }
@@ -585,27 +730,47 @@ where
             return self.try_claim_testnet_bandwidth().await;
         }
 
+        // only spend as much as it takes to get above the threshold again
+        let required_bandwidth = REMAINING_BANDWIDTH_THRESHOLD
+            .saturating_sub(self.bandwidth_remaining)
+            .max(1) as u64;
         let (credential, credential_id) = self
             .bandwidth_controller
             .as_ref()
             .unwrap()
-            .prepare_coconut_credential()
+            .prepare_coconut_credential_for_bandwidth(required_bandwidth)
             .await?;
 
         self.claim_coconut_bandwidth(credential).await?;
+        let gateway_id = self.gateway_identity.to_base58_string();
         self.bandwidth_controller
             .as_ref()
             .unwrap()
-            .consume_credential(credential_id)
+            .consume_credential(credential_id, &gateway_id)
             .await?;
+        self.record_remaining_bandwidth().await;
 
         Ok(())
     }
 
+    /// Stores the bandwidth reported by the gateway, so that the credentials spent at it
+    /// would still be accounted for in the balance.
+    async fn record_remaining_bandwidth(&self) {
+        if let Some(bandwidth_controller) = &self.bandwidth_controller {
+            let gateway_id = self.gateway_identity.to_base58_string();
+            if let Err(err) = bandwidth_controller
+                .update_gateway_bandwidth(&gateway_id, self.bandwidth_remaining)
+                .await
+            {
+                warn!("failed to store the remaining bandwidth of gateway {gateway_id}: {err}");
+            }
+        }
+    }
+
     fn estimate_required_bandwidth(&self, packets: &[MixPacket]) -> i64 {
         packets
             .iter()
-            .map(|packet| packet.sphinx_packet().len())
+            .map(|packet| packet.packet().len())
             .sum::<usize>() as i64
     }
 
@@ -686,9 +851,9 @@ where
         if !self.authenticated {
             return Err(GatewayClientError::NotAuthenticated);
         }
-        if (mix_packet.sphinx_packet().len() as i64) > self.bandwidth_remaining {
+        if (mix_packet.packet().len() as i64) > self.bandwidth_remaining {
             return Err(GatewayClientError::NotEnoughBandwidth(
-                mix_packet.sphinx_packet().len() as i64,
+                mix_packet.packet().len() as i64,
                 self.bandwidth_remaining,
             ));
         }
@@ -777,6 +942,7 @@ where
             self.establish_connection().await?;
         }
         let shared_key = self.perform_initial_authentication().await?;
+        self.record_remaining_bandwidth().await;
 
         if self.bandwidth_remaining < REMAINING_BANDWIDTH_THRESHOLD {
             info!("Claiming more bandwidth for your tokens. This will use {} token(s) from your wallet. \
@@ -790,3 +956,92 @@ where
         Ok(shared_key)
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use nym_credential_storage::ephemeral_storage::EphemeralStorage;
+
+    // nothing is listening on that port, so all connection attempts fail immediately
+    const UNREACHABLE_ADDRESS: &str = "ws://127.0.0.1:1";
+
+    fn random_identity() -> identity::PublicKey {
+        *identity::KeyPair::new(&mut OsRng).public_key()
+    }
+
+    fn test_client(
+        fallback_gateways: &[identity::PublicKey],
+    ) -> GatewayClient<(), EphemeralStorage> {
+        let mut client = GatewayClient::new_init(
+            UNREACHABLE_ADDRESS.to_string(),
+            random_identity(),
+            Arc::new(identity::KeyPair::new(&mut OsRng)),
+            Duration::from_secs(1),
+        );
+        client.with_fallback_gateways(
+            fallback_gateways
+                .iter()
+                .map(|identity| {
+                    FallbackGateway::new(UNREACHABLE_ADDRESS.to_string(), *identity, None)
+                })
+                .collect(),
+        );
+        client
+    }
+
+    fn queued_identities(client: &GatewayClient<(), EphemeralStorage>) -> Vec<identity::PublicKey> {
+        client
+            .fallback_gateways
+            .iter()
+            .map(|gateway| gateway.identity)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn failover_gives_up_once_all_fallback_gateways_got_attempted() {
+        let fallbacks = [random_identity(), random_identity()];
+        let mut client = test_client(&fallbacks);
+        let main = client.gateway_identity();
+
+        assert!(client.attempt_failover().await.is_err());
+
+        // every fallback got attempted exactly once, in order, and the gateways we've moved
+        // away from ended up at the back of the queue
+        assert_eq!(client.gateway_identity(), fallbacks[1]);
+        assert_eq!(queued_identities(&client), vec![main, fallbacks[0]]);
+    }
+
+    #[tokio::test]
+    async fn failover_rotates_through_the_fallback_gateways() {
+        let fallbacks = [random_identity(), random_identity(), random_identity()];
+        let mut client = test_client(&fallbacks);
+        let main = client.gateway_identity();
+
+        assert!(client.attempt_failover().await.is_err());
+        assert_eq!(client.gateway_identity(), fallbacks[2]);
+        assert_eq!(
+            queued_identities(&client),
+            vec![main, fallbacks[0], fallbacks[1]]
+        );
+
+        // the subsequent failover starts from the gateway we've originally moved away from
+        assert!(client.attempt_failover().await.is_err());
+        assert_eq!(client.gateway_identity(), fallbacks[1]);
+        assert_eq!(
+            queued_identities(&client),
+            vec![fallbacks[2], main, fallbacks[0]]
+        );
+    }
+
+    #[tokio::test]
+    async fn failover_fails_without_fallback_gateways() {
+        let mut client = test_client(&[]);
+        let main = client.gateway_identity();
+
+        assert!(matches!(
+            client.attempt_failover().await,
+            Err(GatewayClientError::ConnectionNotEstablished)
+        ));
+        assert_eq!(client.gateway_identity(), main);
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::GatewayClientError;
-pub use client::GatewayClient;
+pub use client::{
+    FallbackGateway, GatewayClient, GatewayFailover, GatewayFailoverReceiver, GatewayFailoverSender,
+};
 use log::warn;
 use nym_gateway_requests::registration::handshake::SharedKeys;
 use nym_gateway_requests::BinaryResponse;
@@ -0,0 +1,246 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for constructing `x/authz` messages, which allow an account (the grantee)
+//! to execute a restricted set of messages on behalf of another account (the granter).
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::Coin;
+use cosmrs::proto::cosmos::authz::v1beta1::{
+    Grant as ProtoGrant, MsgExec as ProtoMsgExec, MsgGrant as ProtoMsgGrant,
+    MsgRevoke as ProtoMsgRevoke,
+};
+use cosmrs::tx::Msg;
+use cosmrs::{cosmwasm, AccountId, Any};
+use prost::Message;
+use std::time::SystemTime;
+
+pub const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+const MSG_GRANT_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgGrant";
+const MSG_EXEC_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgExec";
+const MSG_REVOKE_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgRevoke";
+
+const CONTRACT_EXECUTION_AUTHORIZATION_TYPE_URL: &str =
+    "/cosmwasm.wasm.v1.ContractExecutionAuthorization";
+const MAX_CALLS_LIMIT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MaxCallsLimit";
+const ACCEPTED_MESSAGE_KEYS_FILTER_TYPE_URL: &str = "/cosmwasm.wasm.v1.AcceptedMessageKeysFilter";
+
+// the wasmd authorization types are not (yet) available in cosmrs, so we have to define them ourselves.
+// they're equivalent to the definitions from `cosmwasm/wasm/v1/authz.proto`
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoContractExecutionAuthorization {
+    #[prost(message, repeated, tag = "1")]
+    grants: Vec<ProtoContractGrant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoContractGrant {
+    #[prost(string, tag = "1")]
+    contract: String,
+    #[prost(message, optional, tag = "2")]
+    limit: Option<Any>,
+    #[prost(message, optional, tag = "3")]
+    filter: Option<Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoMaxCallsLimit {
+    #[prost(uint64, tag = "1")]
+    remaining: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoAcceptedMessageKeysFilter {
+    #[prost(string, repeated, tag = "1")]
+    keys: Vec<String>,
+}
+
+fn to_any<M: Message>(type_url: &str, msg: &M) -> Any {
+    Any {
+        type_url: type_url.to_owned(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+/// Permission to execute the specified messages of a particular contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractExecutionGrant {
+    pub contract: AccountId,
+
+    /// Top-level keys of the json-encoded `ExecuteMsg`s that are allowed to be executed,
+    /// for example `update_mixnode_config`.
+    pub allowed_messages: Vec<String>,
+
+    /// Number of executions after which the grant is exhausted.
+    pub max_calls: u64,
+}
+
+impl ContractExecutionGrant {
+    pub fn new(contract: AccountId, allowed_messages: Vec<String>, max_calls: u64) -> Self {
+        ContractExecutionGrant {
+            contract,
+            allowed_messages,
+            max_calls,
+        }
+    }
+
+    fn to_proto(&self) -> ProtoContractGrant {
+        ProtoContractGrant {
+            contract: self.contract.to_string(),
+            limit: Some(to_any(
+                MAX_CALLS_LIMIT_TYPE_URL,
+                &ProtoMaxCallsLimit {
+                    remaining: self.max_calls,
+                },
+            )),
+            filter: Some(to_any(
+                ACCEPTED_MESSAGE_KEYS_FILTER_TYPE_URL,
+                &ProtoAcceptedMessageKeysFilter {
+                    keys: self.allowed_messages.clone(),
+                },
+            )),
+        }
+    }
+}
+
+/// Creates `ContractExecutionAuthorization` out of the provided grants.
+pub fn contract_execution_authorization(grants: &[ContractExecutionGrant]) -> Any {
+    to_any(
+        CONTRACT_EXECUTION_AUTHORIZATION_TYPE_URL,
+        &ProtoContractExecutionAuthorization {
+            grants: grants
+                .iter()
+                .map(ContractExecutionGrant::to_proto)
+                .collect(),
+        },
+    )
+}
+
+pub(crate) fn msg_grant(
+    granter: &AccountId,
+    grantee: &AccountId,
+    authorization: Any,
+    expiration: Option<SystemTime>,
+) -> Any {
+    to_any(
+        MSG_GRANT_TYPE_URL,
+        &ProtoMsgGrant {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+            grant: Some(ProtoGrant {
+                authorization: Some(authorization),
+                expiration: expiration.map(Into::into),
+            }),
+        },
+    )
+}
+
+pub(crate) fn msg_exec(grantee: &AccountId, msgs: Vec<Any>) -> Any {
+    to_any(
+        MSG_EXEC_TYPE_URL,
+        &ProtoMsgExec {
+            grantee: grantee.to_string(),
+            msgs,
+        },
+    )
+}
+
+/// Wraps the contract execution in `MsgExec` so that the grantee could execute it
+/// with the granter being the sender of the message.
+pub(crate) fn msg_exec_contract(
+    grantee: &AccountId,
+    granter: &AccountId,
+    contract: &AccountId,
+    msg: Vec<u8>,
+    funds: Vec<Coin>,
+) -> Result<Any, NyxdError> {
+    let execute_msg = cosmwasm::MsgExecuteContract {
+        sender: granter.clone(),
+        contract: contract.clone(),
+        msg,
+        funds: funds.into_iter().map(Into::into).collect(),
+    }
+    .to_any()
+    .map_err(|_| NyxdError::SerializationError("MsgExecuteContract".to_owned()))?;
+
+    Ok(msg_exec(grantee, vec![execute_msg]))
+}
+
+pub(crate) fn msg_revoke(granter: &AccountId, grantee: &AccountId, msg_type_url: String) -> Any {
+    to_any(
+        MSG_REVOKE_TYPE_URL,
+        &ProtoMsgRevoke {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+            msg_type_url,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_execution_authorization_encoding() {
+        let contract: AccountId = "n17n9flp6jflljg6fp05dsy07wcprf2uuu8g40rf".parse().unwrap();
+        let grant = ContractExecutionGrant::new(
+            contract.clone(),
+            vec!["update_mixnode_config".to_string()],
+            42,
+        );
+
+        let authorization = contract_execution_authorization(&[grant]);
+        assert_eq!(
+            authorization.type_url,
+            CONTRACT_EXECUTION_AUTHORIZATION_TYPE_URL
+        );
+
+        let decoded =
+            ProtoContractExecutionAuthorization::decode(authorization.value.as_ref()).unwrap();
+        assert_eq!(decoded.grants.len(), 1);
+        assert_eq!(decoded.grants[0].contract, contract.to_string());
+
+        let limit = decoded.grants[0].limit.as_ref().unwrap();
+        assert_eq!(limit.type_url, MAX_CALLS_LIMIT_TYPE_URL);
+        assert_eq!(
+            ProtoMaxCallsLimit::decode(limit.value.as_ref())
+                .unwrap()
+                .remaining,
+            42
+        );
+
+        let filter = decoded.grants[0].filter.as_ref().unwrap();
+        assert_eq!(filter.type_url, ACCEPTED_MESSAGE_KEYS_FILTER_TYPE_URL);
+        assert_eq!(
+            ProtoAcceptedMessageKeysFilter::decode(filter.value.as_ref())
+                .unwrap()
+                .keys,
+            vec!["update_mixnode_config".to_string()]
+        );
+    }
+
+    #[test]
+    fn contract_execution_is_sent_on_behalf_of_granter() {
+        let grantee: AccountId = "n1jw6mp7d5xqc7w6xm79lha27glmd0vdt3l9artf".parse().unwrap();
+        let granter: AccountId = "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es".parse().unwrap();
+        let contract: AccountId = "n17n9flp6jflljg6fp05dsy07wcprf2uuu8g40rf".parse().unwrap();
+        let msg = br#"{"update_mixnode_config":{}}"#.to_vec();
+
+        let exec = msg_exec_contract(&grantee, &granter, &contract, msg.clone(), vec![]).unwrap();
+        assert_eq!(exec.type_url, MSG_EXEC_TYPE_URL);
+
+        let decoded = ProtoMsgExec::decode(exec.value.as_ref()).unwrap();
+        assert_eq!(decoded.grantee, grantee.to_string());
+        assert_eq!(decoded.msgs.len(), 1);
+        assert_eq!(decoded.msgs[0].type_url, MSG_EXECUTE_CONTRACT_TYPE_URL);
+
+        let execute_msg = cosmwasm::MsgExecuteContract::from_any(&decoded.msgs[0]).unwrap();
+        assert_eq!(execute_msg.sender, granter);
+        assert_eq!(execute_msg.contract, contract);
+        assert_eq!(execute_msg.msg, msg);
+        assert!(execute_msg.funds.is_empty());
+    }
+}
@@ -6,6 +6,7 @@ use crate::nyxd::GasPrice;
 use cosmrs::rpc::{Error as TendermintRpcError, HttpClient, HttpClientUrl};
 use std::convert::TryInto;
 
+pub mod authz;
 pub mod client;
 mod helpers;
 pub mod logs;
@@ -1,6 +1,7 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::nyxd::cosmwasm_client::authz::{self, ContractExecutionGrant};
 use crate::nyxd::cosmwasm_client::client::CosmWasmClient;
 use crate::nyxd::cosmwasm_client::helpers::{compress_wasm_code, CheckResponse};
 use crate::nyxd::cosmwasm_client::logs::{self, parse_raw_logs};
@@ -480,6 +481,93 @@ pub trait SigningCosmWasmClient: CosmWasmClient {
             .check_response()
     }
 
+    /// Grant the specified authorization to the grantee, allowing it to execute messages
+    /// on behalf of the granter.
+    async fn grant_authorization(
+        &self,
+        granter: &AccountId,
+        grantee: &AccountId,
+        authorization: Any,
+        expiration: Option<SystemTime>,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<TxResponse, NyxdError> {
+        let grant_msg = authz::msg_grant(granter, grantee, authorization, expiration);
+
+        self.sign_and_broadcast(granter, vec![grant_msg], fee, memo)
+            .await?
+            .check_response()
+    }
+
+    /// Grant the permission to execute the specified contract messages on behalf of the granter.
+    async fn grant_contract_execution(
+        &self,
+        granter: &AccountId,
+        grantee: &AccountId,
+        grants: Vec<ContractExecutionGrant>,
+        expiration: Option<SystemTime>,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<TxResponse, NyxdError> {
+        let authorization = authz::contract_execution_authorization(&grants);
+        self.grant_authorization(granter, grantee, authorization, expiration, fee, memo)
+            .await
+    }
+
+    async fn revoke_authorization(
+        &self,
+        granter: &AccountId,
+        grantee: &AccountId,
+        msg_type_url: String,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<TxResponse, NyxdError> {
+        let revoke_msg = authz::msg_revoke(granter, grantee, msg_type_url);
+
+        self.sign_and_broadcast(granter, vec![revoke_msg], fee, memo)
+            .await?
+            .check_response()
+    }
+
+    /// Execute the contract using the authorization previously granted by the granter,
+    /// i.e. the contract is going to see the granter as the sender of the message.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_as_grantee<M>(
+        &self,
+        grantee_address: &AccountId,
+        granter_address: &AccountId,
+        contract_address: &AccountId,
+        msg: &M,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+        funds: Vec<Coin>,
+    ) -> Result<ExecuteResult, NyxdError>
+    where
+        M: ?Sized + Serialize + Sync,
+    {
+        let exec_msg = authz::msg_exec_contract(
+            grantee_address,
+            granter_address,
+            contract_address,
+            serde_json::to_vec(msg)?,
+            funds,
+        )?;
+
+        let tx_res = self
+            .sign_and_broadcast(grantee_address, vec![exec_msg], fee, memo)
+            .await?
+            .check_response()?;
+
+        let gas_info = GasInfo::new(tx_res.tx_result.gas_wanted, tx_res.tx_result.gas_used);
+
+        Ok(ExecuteResult {
+            logs: parse_raw_logs(tx_res.tx_result.log)?,
+            data: tx_res.tx_result.data,
+            transaction_hash: tx_res.hash,
+            gas_info,
+        })
+    }
+
     async fn delegate_tokens(
         &self,
         delegator_address: &AccountId,
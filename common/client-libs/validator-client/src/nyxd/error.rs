@@ -140,6 +140,15 @@ pub enum NyxdError {
 
     #[error("Account had an unexpected bech32 prefix. Expected: {expected}, got: {got}")]
     UnexpectedBech32Prefix { got: String, expected: String },
+
+    #[error("Event {event_type} is malformed: {reason}")]
+    MalformedEvent { event_type: String, reason: String },
+
+    #[error("The event subscription has been closed")]
+    EventSubscriptionClosed,
+
+    #[error("Could not derive a valid websocket endpoint from {0}")]
+    InvalidWebsocketEndpoint(String),
 }
 
 // The purpose of parsing the abci query result is that we want to generate the `pretty_log` if
@@ -0,0 +1,95 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::EventAttributes;
+use cosmrs::AccountId;
+use nym_coconut_dkg_common::event_attributes::{
+    DKG_DISQUALIFIED_DEALERS, DKG_EPOCH_ID, DKG_EPOCH_STATE, DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE,
+};
+use nym_coconut_dkg_common::types::{EpochId, EpochState};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DkgEvent {
+    EpochStateAdvanced {
+        epoch_id: EpochId,
+        state: EpochState,
+        /// Dealers that got disqualified as a result of the complaints submitted during this epoch.
+        disqualified_dealers: Vec<AccountId>,
+    },
+
+    /// Any other event emitted by the DKG contract that does not have a typed representation.
+    Other(cosmwasm_std::Event),
+}
+
+pub(super) fn decode(event: &cosmwasm_std::Event) -> Result<DkgEvent, NyxdError> {
+    let attributes = EventAttributes::new(event);
+
+    let decoded = match event.ty.as_str() {
+        DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE => {
+            let state = serde_json::from_str(attributes.required(DKG_EPOCH_STATE)?)
+                .map_err(|_| attributes.malformed("the epoch state could not be deserialized"))?;
+
+            let disqualified_dealers = match attributes.optional(DKG_DISQUALIFIED_DEALERS) {
+                Some(raw) => raw
+                    .split(',')
+                    .map(|dealer| dealer.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| {
+                        attributes.malformed("one of the disqualified dealers is invalid")
+                    })?,
+                None => Vec::new(),
+            };
+
+            DkgEvent::EpochStateAdvanced {
+                epoch_id: attributes.parse(DKG_EPOCH_ID)?,
+                state,
+                disqualified_dealers,
+            }
+        }
+        _ => DkgEvent::Other(event.clone()),
+    };
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEALER1: &str = "n10yyd98e2tuwu0f7ypz9dy3hhjw7v772q6287gy";
+    const DEALER2: &str = "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es";
+
+    #[test]
+    fn decoding_epoch_state_advancement() {
+        let event = cosmwasm_std::Event::new(DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE)
+            .add_attribute(DKG_EPOCH_ID, "3")
+            .add_attribute(
+                DKG_EPOCH_STATE,
+                r#"{"dealing_exchange":{"resharing":true}}"#,
+            );
+
+        assert_eq!(
+            decode(&event).unwrap(),
+            DkgEvent::EpochStateAdvanced {
+                epoch_id: 3,
+                state: EpochState::DealingExchange { resharing: true },
+                disqualified_dealers: vec![],
+            }
+        );
+
+        let event = cosmwasm_std::Event::new(DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE)
+            .add_attribute(DKG_EPOCH_ID, "3")
+            .add_attribute(DKG_EPOCH_STATE, r#""in_progress""#)
+            .add_attribute(DKG_DISQUALIFIED_DEALERS, format!("{DEALER1},{DEALER2}"));
+
+        assert_eq!(
+            decode(&event).unwrap(),
+            DkgEvent::EpochStateAdvanced {
+                epoch_id: 3,
+                state: EpochState::InProgress,
+                disqualified_dealers: vec![DEALER1.parse().unwrap(), DEALER2.parse().unwrap()],
+            }
+        );
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::EventAttributes;
+use crate::nyxd::Coin;
+use cosmrs::AccountId;
+use nym_mixnet_contract_common::events::{
+    MixnetEventType, AMOUNT_KEY, CURRENT_EPOCH_KEY, DELEGATION_TARGET_KEY, DELEGATOR_KEY,
+    EVENT_CREATION_HEIGHT_KEY, MIX_ID_KEY, NEW_CURRENT_EPOCH_KEY, NODE_IDENTITY_KEY, OWNER_KEY,
+    PROXY_KEY, REDELEGATION_FAILURE_KEY, REDELEGATION_SOURCE_KEY, REWARDED_SET_NODES_KEY,
+};
+use nym_mixnet_contract_common::{BlockHeight, EpochId, IdentityKey, MixId};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MixnetEvent {
+    MixnodeBonding {
+        mix_id: MixId,
+        identity: IdentityKey,
+        owner: AccountId,
+        proxy: Option<AccountId>,
+        amount: Coin,
+    },
+    PendingMixnodeUnbonding {
+        mix_id: MixId,
+        identity: IdentityKey,
+        owner: AccountId,
+        proxy: Option<AccountId>,
+    },
+    MixnodeUnbonding {
+        mix_id: MixId,
+        created_at: BlockHeight,
+    },
+    GatewayBonding {
+        identity: IdentityKey,
+        owner: AccountId,
+        proxy: Option<AccountId>,
+        amount: Coin,
+    },
+    GatewayUnbonding {
+        identity: IdentityKey,
+        owner: AccountId,
+        proxy: Option<AccountId>,
+        amount: Coin,
+    },
+    PendingDelegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        mix_id: MixId,
+        amount: Coin,
+    },
+    /// Note: the amount is not going to be present if the delegation was made towards
+    /// a node that has unbonded in the meantime (the funds are returned to the delegator instead).
+    Delegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        mix_id: MixId,
+        amount: Option<Coin>,
+    },
+    PendingUndelegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        mix_id: MixId,
+    },
+    Undelegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        mix_id: MixId,
+        created_at: BlockHeight,
+    },
+    PendingRedelegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        source_mix_id: MixId,
+        target_mix_id: MixId,
+        amount: Coin,
+    },
+    Redelegation {
+        delegator: AccountId,
+        proxy: Option<AccountId>,
+        source_mix_id: MixId,
+        target_mix_id: MixId,
+        amount: Option<Coin>,
+        target_not_bonded: bool,
+        created_at: BlockHeight,
+    },
+    EpochTransitionStart {
+        current_epoch: EpochId,
+    },
+    EpochAdvanced {
+        new_current_epoch: EpochId,
+        rewarded_set_nodes: u32,
+    },
+
+    /// Any other event emitted by the mixnet contract that does not have a typed representation.
+    Other(cosmwasm_std::Event),
+}
+
+pub(super) fn decode(event: &cosmwasm_std::Event) -> Result<MixnetEvent, NyxdError> {
+    let is = |typ: MixnetEventType| event.ty == typ.to_string();
+    let attributes = EventAttributes::new(event);
+
+    let decoded = if is(MixnetEventType::MixnodeBonding) {
+        MixnetEvent::MixnodeBonding {
+            mix_id: attributes.parse(MIX_ID_KEY)?,
+            identity: attributes.required(NODE_IDENTITY_KEY)?.to_owned(),
+            owner: attributes.account(OWNER_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::PendingMixnodeUnbonding) {
+        MixnetEvent::PendingMixnodeUnbonding {
+            mix_id: attributes.parse(MIX_ID_KEY)?,
+            identity: attributes.required(NODE_IDENTITY_KEY)?.to_owned(),
+            owner: attributes.account(OWNER_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+        }
+    } else if is(MixnetEventType::MixnodeUnbonding) {
+        MixnetEvent::MixnodeUnbonding {
+            mix_id: attributes.parse(MIX_ID_KEY)?,
+            created_at: attributes.parse(EVENT_CREATION_HEIGHT_KEY)?,
+        }
+    } else if is(MixnetEventType::GatewayBonding) {
+        MixnetEvent::GatewayBonding {
+            identity: attributes.required(NODE_IDENTITY_KEY)?.to_owned(),
+            owner: attributes.account(OWNER_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::GatewayUnbonding) {
+        MixnetEvent::GatewayUnbonding {
+            identity: attributes.required(NODE_IDENTITY_KEY)?.to_owned(),
+            owner: attributes.account(OWNER_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::PendingDelegation) {
+        MixnetEvent::PendingDelegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            mix_id: attributes.parse(DELEGATION_TARGET_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::Delegation) {
+        MixnetEvent::Delegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            mix_id: attributes.parse(DELEGATION_TARGET_KEY)?,
+            amount: attributes.optional_coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::PendingUndelegation) {
+        MixnetEvent::PendingUndelegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            mix_id: attributes.parse(MIX_ID_KEY)?,
+        }
+    } else if is(MixnetEventType::Undelegation) {
+        MixnetEvent::Undelegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            mix_id: attributes.parse(MIX_ID_KEY)?,
+            created_at: attributes.parse(EVENT_CREATION_HEIGHT_KEY)?,
+        }
+    } else if is(MixnetEventType::PendingRedelegation) {
+        MixnetEvent::PendingRedelegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            source_mix_id: attributes.parse(REDELEGATION_SOURCE_KEY)?,
+            target_mix_id: attributes.parse(DELEGATION_TARGET_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+        }
+    } else if is(MixnetEventType::Redelegation) {
+        MixnetEvent::Redelegation {
+            delegator: attributes.account(DELEGATOR_KEY)?,
+            proxy: attributes.optional_account(PROXY_KEY)?,
+            source_mix_id: attributes.parse(REDELEGATION_SOURCE_KEY)?,
+            target_mix_id: attributes.parse(DELEGATION_TARGET_KEY)?,
+            amount: attributes.optional_coin(AMOUNT_KEY)?,
+            target_not_bonded: attributes.optional(REDELEGATION_FAILURE_KEY).is_some(),
+            created_at: attributes.parse(EVENT_CREATION_HEIGHT_KEY)?,
+        }
+    } else if is(MixnetEventType::BeginEpochTransition) {
+        MixnetEvent::EpochTransitionStart {
+            current_epoch: attributes.parse(CURRENT_EPOCH_KEY)?,
+        }
+    } else if is(MixnetEventType::AdvanceEpoch) {
+        MixnetEvent::EpochAdvanced {
+            new_current_epoch: attributes.parse(NEW_CURRENT_EPOCH_KEY)?,
+            rewarded_set_nodes: attributes.parse(REWARDED_SET_NODES_KEY)?,
+        }
+    } else {
+        MixnetEvent::Other(event.clone())
+    };
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Addr, Decimal};
+    use nym_mixnet_contract_common::events::{
+        new_delegation_event, new_delegation_on_unbonded_node_event, new_mixnode_bonding_event,
+        new_redelegation_to_unbonded_node_event,
+    };
+    use nym_mixnet_contract_common::Layer;
+
+    const OWNER: &str = "n10yyd98e2tuwu0f7ypz9dy3hhjw7v772q6287gy";
+    const PROXY: &str = "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es";
+
+    #[test]
+    fn decoding_mixnode_bonding() {
+        let event = new_mixnode_bonding_event(
+            &Addr::unchecked(OWNER),
+            &Some(Addr::unchecked(PROXY)),
+            &coin(100_000_000, "unym"),
+            "identity",
+            42,
+            Layer::Two,
+        );
+
+        assert_eq!(
+            decode(&event).unwrap(),
+            MixnetEvent::MixnodeBonding {
+                mix_id: 42,
+                identity: "identity".to_string(),
+                owner: OWNER.parse().unwrap(),
+                proxy: Some(PROXY.parse().unwrap()),
+                amount: Coin::new(100_000_000, "unym"),
+            }
+        );
+    }
+
+    #[test]
+    fn decoding_delegations() {
+        let event = new_delegation_event(
+            123,
+            &Addr::unchecked(OWNER),
+            &None,
+            &coin(1234, "unym"),
+            42,
+            Decimal::one(),
+        );
+        assert_eq!(
+            decode(&event).unwrap(),
+            MixnetEvent::Delegation {
+                delegator: OWNER.parse().unwrap(),
+                proxy: None,
+                mix_id: 42,
+                amount: Some(Coin::new(1234, "unym")),
+            }
+        );
+
+        let event = new_delegation_on_unbonded_node_event(&Addr::unchecked(OWNER), &None, 42);
+        assert_eq!(
+            decode(&event).unwrap(),
+            MixnetEvent::Delegation {
+                delegator: OWNER.parse().unwrap(),
+                proxy: None,
+                mix_id: 42,
+                amount: None,
+            }
+        );
+
+        let event =
+            new_redelegation_to_unbonded_node_event(123, &Addr::unchecked(OWNER), &None, 1, 2);
+        assert_eq!(
+            decode(&event).unwrap(),
+            MixnetEvent::Redelegation {
+                delegator: OWNER.parse().unwrap(),
+                proxy: None,
+                source_mix_id: 1,
+                target_mix_id: 2,
+                amount: None,
+                target_not_bonded: true,
+                created_at: 123,
+            }
+        );
+    }
+
+    #[test]
+    fn decoding_event_with_invalid_address_fails() {
+        let event = new_delegation_on_unbonded_node_event(&Addr::unchecked("foomp"), &None, 42);
+        assert!(decode(&event).is_err())
+    }
+
+    #[test]
+    fn unknown_events_are_not_decoded() {
+        let event = cosmwasm_std::Event::new("v2_foomp").add_attribute("foo", "bar");
+        assert_eq!(decode(&event).unwrap(), MixnetEvent::Other(event))
+    }
+}
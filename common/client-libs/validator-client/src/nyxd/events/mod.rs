@@ -0,0 +1,309 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed representation of the events emitted by the Nym contracts alongside
+//! a websocket-based subscription for receiving them as they're included on chain.
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::{Coin, Config};
+use cosmrs::tendermint::abci;
+use cosmrs::tendermint::abci::transaction::Hash as TxHash;
+use cosmrs::AccountId;
+use log::warn;
+use std::str::FromStr;
+
+pub use coconut_dkg::DkgEvent;
+pub use mixnet::MixnetEvent;
+pub use subscription::{subscribe, EventSubscription, SubscriptionConfig};
+pub use vesting::VestingEvent;
+
+mod coconut_dkg;
+mod mixnet;
+mod subscription;
+mod vesting;
+
+// all custom events emitted by cosmwasm contracts get this prefix attached to their types
+const WASM_EVENT_PREFIX: &str = "wasm-";
+// and are always tagged with the address of the emitting contract
+const CONTRACT_ADDRESS_KEY: &str = "_contract_address";
+
+/// Decoded contract event alongside the details of the transaction that has emitted it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainEvent {
+    pub height: u64,
+    pub tx_hash: TxHash,
+    pub event: ContractEvent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractEvent {
+    Mixnet(MixnetEvent),
+    Vesting(VestingEvent),
+    CoconutDkg(DkgEvent),
+}
+
+/// Addresses of the contracts whose events should be decoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchedContracts {
+    pub mixnet: Option<AccountId>,
+    pub vesting: Option<AccountId>,
+    pub coconut_dkg: Option<AccountId>,
+}
+
+impl WatchedContracts {
+    pub fn new_from_config(config: &Config) -> Self {
+        WatchedContracts {
+            mixnet: config.mixnet_contract_address.clone(),
+            vesting: config.vesting_contract_address.clone(),
+            coconut_dkg: config.coconut_dkg_contract_address.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_mixnet_contract(mut self, address: Option<AccountId>) -> Self {
+        self.mixnet = address;
+        self
+    }
+
+    #[must_use]
+    pub fn with_vesting_contract(mut self, address: Option<AccountId>) -> Self {
+        self.vesting = address;
+        self
+    }
+
+    #[must_use]
+    pub fn with_coconut_dkg_contract(mut self, address: Option<AccountId>) -> Self {
+        self.coconut_dkg = address;
+        self
+    }
+
+    pub(crate) fn addresses(&self) -> impl Iterator<Item = &AccountId> {
+        self.mixnet
+            .iter()
+            .chain(self.vesting.iter())
+            .chain(self.coconut_dkg.iter())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.addresses().next().is_none()
+    }
+
+    fn decode(&self, contract: &str, event: &cosmwasm_std::Event) -> Option<ContractEvent> {
+        let is = |watched: &Option<AccountId>| {
+            watched
+                .as_ref()
+                .map(|address| address.as_ref() == contract)
+                .unwrap_or_default()
+        };
+
+        // we don't want to lose the event just because we failed to fully understand it,
+        // so in that case just pass it on in its raw form
+        let or_raw = |err: NyxdError| {
+            warn!("{err}. It's going to be passed on in its raw form");
+            event.clone()
+        };
+
+        let decoded = if is(&self.mixnet) {
+            ContractEvent::Mixnet(
+                mixnet::decode(event).unwrap_or_else(|err| MixnetEvent::Other(or_raw(err))),
+            )
+        } else if is(&self.vesting) {
+            ContractEvent::Vesting(
+                vesting::decode(event).unwrap_or_else(|err| VestingEvent::Other(or_raw(err))),
+            )
+        } else if is(&self.coconut_dkg) {
+            ContractEvent::CoconutDkg(
+                coconut_dkg::decode(event).unwrap_or_else(|err| DkgEvent::Other(or_raw(err))),
+            )
+        } else {
+            return None;
+        };
+
+        Some(decoded)
+    }
+
+    /// Decodes all events of the watched contracts that were emitted by a single transaction,
+    /// preserving their original ordering.
+    pub fn decode_tx_events(&self, events: &[abci::Event]) -> Vec<ContractEvent> {
+        events
+            .iter()
+            .filter_map(|event| {
+                let ty = event.type_str.strip_prefix(WASM_EVENT_PREFIX)?;
+                let contract = event
+                    .attributes
+                    .iter()
+                    .find(|tag| tag.key.as_ref() == CONTRACT_ADDRESS_KEY)?
+                    .value
+                    .as_ref();
+
+                let mut wasm_event = cosmwasm_std::Event::new(ty);
+                for tag in &event.attributes {
+                    if tag.key.as_ref() != CONTRACT_ADDRESS_KEY {
+                        wasm_event = wasm_event.add_attribute(tag.key.as_ref(), tag.value.as_ref());
+                    }
+                }
+                self.decode(contract, &wasm_event)
+            })
+            .collect()
+    }
+}
+
+// helper for extracting typed attribute values out of contract events
+struct EventAttributes<'a> {
+    event: &'a cosmwasm_std::Event,
+}
+
+impl<'a> EventAttributes<'a> {
+    fn new(event: &'a cosmwasm_std::Event) -> Self {
+        EventAttributes { event }
+    }
+
+    fn malformed<S: Into<String>>(&self, reason: S) -> NyxdError {
+        NyxdError::MalformedEvent {
+            event_type: self.event.ty.clone(),
+            reason: reason.into(),
+        }
+    }
+
+    fn optional(&self, key: &str) -> Option<&'a str> {
+        self.event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    }
+
+    fn required(&self, key: &str) -> Result<&'a str, NyxdError> {
+        self.optional(key)
+            .ok_or_else(|| self.malformed(format!("the '{key}' attribute is missing")))
+    }
+
+    fn parse<T: FromStr>(&self, key: &str) -> Result<T, NyxdError> {
+        self.required(key)?
+            .parse()
+            .map_err(|_| self.malformed(format!("the '{key}' attribute has an invalid value")))
+    }
+
+    fn account(&self, key: &str) -> Result<AccountId, NyxdError> {
+        self.parse(key)
+    }
+
+    fn optional_account(&self, key: &str) -> Result<Option<AccountId>, NyxdError> {
+        self.optional(key).map(|_| self.account(key)).transpose()
+    }
+
+    fn coin(&self, key: &str) -> Result<Coin, NyxdError> {
+        parse_coin(self.required(key)?)
+            .ok_or_else(|| self.malformed(format!("the '{key}' attribute is not a valid coin")))
+    }
+
+    fn optional_coin(&self, key: &str) -> Result<Option<Coin>, NyxdError> {
+        self.optional(key).map(|_| self.coin(key)).transpose()
+    }
+}
+
+// coins are attached to the events using their `Display` implementation, i.e. `<amount><denom>`
+fn parse_coin(raw: &str) -> Option<Coin> {
+    let denom_start = raw.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, denom) = raw.split_at(denom_start);
+    Some(Coin::new(amount.parse().ok()?, denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmrs::tendermint::abci::tag::Tag;
+
+    const MIXNET_CONTRACT: &str = "n17n9flp6jflljg6fp05dsy07wcprf2uuu8g40rf";
+    const VESTING_CONTRACT: &str = "n19kdst4srf76xgwe55jg32mpcpcyf6aqgp6qrdk";
+
+    fn watched() -> WatchedContracts {
+        WatchedContracts::default()
+            .with_mixnet_contract(Some(MIXNET_CONTRACT.parse().unwrap()))
+            .with_vesting_contract(Some(VESTING_CONTRACT.parse().unwrap()))
+    }
+
+    fn abci_event(ty: &str, attributes: &[(&str, &str)]) -> abci::Event {
+        abci::Event {
+            type_str: ty.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| Tag {
+                    key: key.parse().unwrap(),
+                    value: value.parse().unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn coin_parsing() {
+        assert_eq!(parse_coin("1234unym"), Some(Coin::new(1234, "unym")));
+        assert_eq!(parse_coin("0unyx"), Some(Coin::new(0, "unyx")));
+        assert!(parse_coin("unym").is_none());
+        assert!(parse_coin("1234").is_none());
+        assert!(parse_coin("").is_none());
+    }
+
+    #[test]
+    fn only_events_of_watched_contracts_are_decoded() {
+        let events = vec![
+            abci_event(
+                "message",
+                &[("action", "/cosmwasm.wasm.v1.MsgExecuteContract")],
+            ),
+            abci_event("execute", &[(CONTRACT_ADDRESS_KEY, MIXNET_CONTRACT)]),
+            abci_event(
+                "wasm-v2_mixnode_unbonding",
+                &[
+                    (CONTRACT_ADDRESS_KEY, MIXNET_CONTRACT),
+                    ("created_at", "123"),
+                    ("mix_id", "42"),
+                ],
+            ),
+            abci_event(
+                "wasm-v2_mixnode_unbonding",
+                &[
+                    (
+                        CONTRACT_ADDRESS_KEY,
+                        "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es",
+                    ),
+                    ("created_at", "123"),
+                    ("mix_id", "42"),
+                ],
+            ),
+            abci_event(
+                "wasm-vesting_delegation",
+                &[(CONTRACT_ADDRESS_KEY, VESTING_CONTRACT)],
+            ),
+        ];
+
+        let decoded = watched().decode_tx_events(&events);
+        assert_eq!(
+            decoded,
+            vec![
+                ContractEvent::Mixnet(MixnetEvent::MixnodeUnbonding {
+                    mix_id: 42,
+                    created_at: 123,
+                }),
+                ContractEvent::Vesting(VestingEvent::Delegation),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_events_are_passed_in_raw_form() {
+        let events = vec![abci_event(
+            "wasm-v2_mixnode_unbonding",
+            &[(CONTRACT_ADDRESS_KEY, MIXNET_CONTRACT), ("mix_id", "foo")],
+        )];
+
+        let decoded = watched().decode_tx_events(&events);
+        assert_eq!(
+            decoded,
+            vec![ContractEvent::Mixnet(MixnetEvent::Other(
+                cosmwasm_std::Event::new("v2_mixnode_unbonding").add_attribute("mix_id", "foo")
+            ))]
+        );
+    }
+}
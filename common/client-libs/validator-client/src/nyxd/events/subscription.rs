@@ -0,0 +1,438 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::cosmwasm_client::client::CosmWasmClient;
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{ChainEvent, WatchedContracts};
+use crate::nyxd::QueryNyxdClient;
+use cosmrs::rpc::query::{EventType, Query};
+use cosmrs::tendermint::abci;
+use cosmrs::tendermint::abci::transaction::Hash as TxHash;
+use cosmrs::AccountId;
+use futures::channel::mpsc;
+use futures::stream::{self, Stream, StreamExt};
+use futures::SinkExt;
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tendermint_rpc::event::EventData;
+use tendermint_rpc::{SubscriptionClient, WebSocketClient};
+use tokio::task::JoinHandle;
+use url::Url;
+
+const DEFAULT_INITIAL_RECONNECTION_BACKOFF: Duration = Duration::from_secs(1);
+const DEFAULT_MAXIMUM_RECONNECTION_BACKOFF: Duration = Duration::from_secs(60);
+const DEFAULT_EVENT_BUFFER_SIZE: usize = 1000;
+
+// number of blocks, behind the latest processed one, for which we remember the seen transactions
+const DEDUPLICATION_WINDOW: u64 = 10;
+
+// wasmd emits this event (with the contract address attached) for every single contract execution,
+// including the ones triggered by other contracts, which makes it ideal for filtering
+const EXECUTE_CONTRACT_ADDRESS_KEY: &str = "execute._contract_address";
+const TX_HEIGHT_KEY: &str = "tx.height";
+
+const TENDERMINT_WEBSOCKET_PATH: &str = "websocket";
+
+#[derive(Debug, Clone)]
+pub struct SubscriptionConfig {
+    rpc_endpoint: Url,
+    websocket_endpoint: Option<Url>,
+    contracts: WatchedContracts,
+    start_height: Option<u64>,
+    initial_reconnection_backoff: Duration,
+    maximum_reconnection_backoff: Duration,
+    event_buffer_size: usize,
+}
+
+impl SubscriptionConfig {
+    pub fn new(rpc_endpoint: Url, contracts: WatchedContracts) -> Self {
+        SubscriptionConfig {
+            rpc_endpoint,
+            websocket_endpoint: None,
+            contracts,
+            start_height: None,
+            initial_reconnection_backoff: DEFAULT_INITIAL_RECONNECTION_BACKOFF,
+            maximum_reconnection_backoff: DEFAULT_MAXIMUM_RECONNECTION_BACKOFF,
+            event_buffer_size: DEFAULT_EVENT_BUFFER_SIZE,
+        }
+    }
+
+    /// Explicitly sets the websocket endpoint of the node. If not specified, it's derived from
+    /// the rpc endpoint, i.e. `https://rpc.nymtech.net` becomes `wss://rpc.nymtech.net/websocket`.
+    #[must_use]
+    pub fn with_websocket_endpoint(mut self, websocket_endpoint: Url) -> Self {
+        self.websocket_endpoint = Some(websocket_endpoint);
+        self
+    }
+
+    /// Height (inclusive) from which the events should be delivered. All events emitted between
+    /// that height and the current one are going to be retrieved before any of the live events.
+    #[must_use]
+    pub fn with_start_height(mut self, start_height: u64) -> Self {
+        self.start_height = Some(start_height);
+        self
+    }
+
+    #[must_use]
+    pub fn with_initial_reconnection_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_reconnection_backoff = backoff;
+        self
+    }
+
+    #[must_use]
+    pub fn with_maximum_reconnection_backoff(mut self, backoff: Duration) -> Self {
+        self.maximum_reconnection_backoff = backoff;
+        self
+    }
+
+    /// Number of decoded events that can be buffered before the listener stops processing
+    /// new transactions until some of them are consumed.
+    #[must_use]
+    pub fn with_event_buffer_size(mut self, event_buffer_size: usize) -> Self {
+        self.event_buffer_size = event_buffer_size;
+        self
+    }
+
+    fn websocket_endpoint(&self) -> Result<Url, NyxdError> {
+        if let Some(endpoint) = &self.websocket_endpoint {
+            return Ok(endpoint.clone());
+        }
+
+        let invalid = || NyxdError::InvalidWebsocketEndpoint(self.rpc_endpoint.to_string());
+
+        let mut endpoint = self.rpc_endpoint.clone();
+        let scheme = match endpoint.scheme() {
+            "http" => "ws",
+            "https" => "wss",
+            _ => return Err(invalid()),
+        };
+        endpoint.set_scheme(scheme).map_err(|_| invalid())?;
+        endpoint
+            .path_segments_mut()
+            .map_err(|_| invalid())?
+            .pop_if_empty()
+            .push(TENDERMINT_WEBSOCKET_PATH);
+
+        Ok(endpoint)
+    }
+}
+
+/// Stream of decoded contract events.
+///
+/// Upon losing connection to the node, it's going to keep attempting to re-establish it
+/// (with an exponential backoff) and resume from the last seen height so that no events are missed.
+/// If the events are not consumed fast enough, the listener stops processing new ones
+/// until there's space in the buffer.
+/// The background task is stopped once the subscription is dropped.
+pub struct EventSubscription {
+    receiver: mpsc::Receiver<ChainEvent>,
+    listener: JoinHandle<()>,
+}
+
+impl Stream for EventSubscription {
+    type Item = ChainEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.listener.abort()
+    }
+}
+
+/// Subscribes to the events emitted by the specified contracts.
+/// Note: it must be called from within a tokio runtime as the listener is spawned as a separate task.
+pub fn subscribe(config: SubscriptionConfig) -> Result<EventSubscription, NyxdError> {
+    if config.contracts.is_empty() {
+        return Err(NyxdError::NoContractAddressAvailable);
+    }
+
+    let (sender, receiver) = mpsc::channel(config.event_buffer_size);
+    let listener = EventListener {
+        http_client: QueryNyxdClient::new(config.rpc_endpoint.as_str())?,
+        websocket_endpoint: config.websocket_endpoint()?,
+        initial_reconnection_backoff: config.initial_reconnection_backoff,
+        maximum_reconnection_backoff: config.maximum_reconnection_backoff,
+        contracts: config.contracts,
+        processed: ProcessedTransactions::new(config.start_height),
+        sender,
+    };
+
+    Ok(EventSubscription {
+        receiver,
+        listener: tokio::spawn(listener.run()),
+    })
+}
+
+/// Keeps track of the recently processed transactions so that the subscription could be resumed
+/// from the right height without delivering any of them twice.
+struct ProcessedTransactions {
+    /// Height from which the events are going to be retrieved upon (re)establishing the connection.
+    resume_height: Option<u64>,
+
+    /// (height, index) of the recently processed transactions.
+    seen: BTreeSet<(u64, u32)>,
+}
+
+impl ProcessedTransactions {
+    fn new(start_height: Option<u64>) -> Self {
+        ProcessedTransactions {
+            resume_height: start_height,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Marks the transaction as processed. Returns `false` if it has already been seen.
+    fn mark(&mut self, height: u64, index: u32) -> bool {
+        // the same transaction might be received multiple times, for example if it involved
+        // multiple watched contracts or if it was in both the historical and the live results
+        if !self.seen.insert((height, index)) {
+            return false;
+        }
+
+        if self
+            .resume_height
+            .map(|resume| height > resume)
+            .unwrap_or(true)
+        {
+            self.resume_height = Some(height);
+            let threshold = height.saturating_sub(DEDUPLICATION_WINDOW);
+            self.seen = self.seen.split_off(&(threshold, 0));
+        }
+
+        true
+    }
+}
+
+struct EventListener {
+    http_client: QueryNyxdClient,
+    websocket_endpoint: Url,
+    initial_reconnection_backoff: Duration,
+    maximum_reconnection_backoff: Duration,
+    contracts: WatchedContracts,
+    processed: ProcessedTransactions,
+    sender: mpsc::Sender<ChainEvent>,
+}
+
+impl EventListener {
+    fn live_query(contract: &AccountId) -> Query {
+        Query::from(EventType::Tx).and_eq(EXECUTE_CONTRACT_ADDRESS_KEY, contract.to_string())
+    }
+
+    fn historical_query(contract: &AccountId, from_height: u64) -> Query {
+        Query::eq(EXECUTE_CONTRACT_ADDRESS_KEY, contract.to_string())
+            .and_gte(TX_HEIGHT_KEY, from_height)
+    }
+
+    async fn run(mut self) {
+        let mut backoff = self.initial_reconnection_backoff;
+
+        loop {
+            if let Err(err) = self.run_session(&mut backoff).await {
+                if self.sender.is_closed() {
+                    debug!("the event subscription got dropped - stopping the listener");
+                    return;
+                }
+                warn!("the event subscription has failed: {err}");
+            }
+
+            info!("attempting to re-establish the event subscription in {backoff:?}");
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.maximum_reconnection_backoff);
+        }
+    }
+
+    async fn run_session(&mut self, backoff: &mut Duration) -> Result<(), NyxdError> {
+        let (client, driver) = WebSocketClient::new(self.websocket_endpoint.as_str()).await?;
+        let driver_handle = tokio::spawn(driver.run());
+
+        let res = self.process_events(&client, backoff).await;
+
+        // there's not much we can do if we fail to cleanly close the connection
+        let _ = client.close();
+        let _ = driver_handle.await;
+
+        res
+    }
+
+    async fn process_events(
+        &mut self,
+        client: &WebSocketClient,
+        backoff: &mut Duration,
+    ) -> Result<(), NyxdError> {
+        let mut subscriptions = Vec::new();
+        for contract in self.contracts.addresses() {
+            subscriptions.push(client.subscribe(Self::live_query(contract)).await?);
+        }
+        let mut live_events = stream::select_all(subscriptions);
+        *backoff = self.initial_reconnection_backoff;
+
+        // note: we're retrieving the historical events only after subscribing so that
+        // nothing could slip through in between
+        self.catch_up().await?;
+
+        while let Some(event) = live_events.next().await {
+            if let EventData::Tx { tx_result } = event?.data {
+                let tx_hash = TxHash::new(Sha256::digest(&tx_result.tx).into());
+                self.process_tx(
+                    tx_result.height as u64,
+                    tx_result.index.unwrap_or_default() as u32,
+                    tx_hash,
+                    &tx_result.result.events,
+                )
+                .await?;
+            }
+        }
+
+        Err(NyxdError::EventSubscriptionClosed)
+    }
+
+    async fn catch_up(&mut self) -> Result<(), NyxdError> {
+        let Some(from_height) = self.processed.resume_height else {
+            // there's nothing to catch up on, but remember where we've started
+            // in case we have to reconnect
+            self.processed.resume_height = Some(self.http_client.get_height().await?.value());
+            return Ok(());
+        };
+
+        let mut txs = Vec::new();
+        for contract in self.contracts.addresses() {
+            let mut contract_txs = self
+                .http_client
+                .search_tx(Self::historical_query(contract, from_height))
+                .await?;
+            txs.append(&mut contract_txs);
+        }
+        txs.sort_by_key(|tx| (tx.height.value(), tx.index));
+
+        debug!(
+            "retrieved {} historical transactions since height {from_height}",
+            txs.len()
+        );
+        for tx in txs {
+            self.process_tx(tx.height.value(), tx.index, tx.hash, &tx.tx_result.events)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn process_tx(
+        &mut self,
+        height: u64,
+        index: u32,
+        tx_hash: TxHash,
+        events: &[abci::Event],
+    ) -> Result<(), NyxdError> {
+        if !self.processed.mark(height, index) {
+            return Ok(());
+        }
+
+        for event in self.contracts.decode_tx_events(events) {
+            self.sender
+                .send(ChainEvent {
+                    height,
+                    tx_hash,
+                    event,
+                })
+                .await
+                .map_err(|_| NyxdError::EventSubscriptionClosed)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deriving_websocket_endpoint() {
+        let config =
+            |rpc: &str| SubscriptionConfig::new(rpc.parse().unwrap(), WatchedContracts::default());
+
+        assert_eq!(
+            config("https://rpc.nymtech.net")
+                .websocket_endpoint()
+                .unwrap()
+                .as_str(),
+            "wss://rpc.nymtech.net/websocket"
+        );
+        assert_eq!(
+            config("http://localhost:26657/")
+                .websocket_endpoint()
+                .unwrap()
+                .as_str(),
+            "ws://localhost:26657/websocket"
+        );
+        assert_eq!(
+            config("https://example.com/nyxd/rpc")
+                .websocket_endpoint()
+                .unwrap()
+                .as_str(),
+            "wss://example.com/nyxd/rpc/websocket"
+        );
+        assert!(config("ftp://example.com").websocket_endpoint().is_err());
+
+        let explicit: Url = "ws://localhost:1234/foo".parse().unwrap();
+        assert_eq!(
+            config("https://rpc.nymtech.net")
+                .with_websocket_endpoint(explicit.clone())
+                .websocket_endpoint()
+                .unwrap(),
+            explicit
+        );
+    }
+
+    #[test]
+    fn transactions_are_processed_only_once() {
+        let mut processed = ProcessedTransactions::new(Some(100));
+
+        // historical results
+        assert!(processed.mark(100, 0));
+        assert!(processed.mark(101, 0));
+        assert!(processed.mark(101, 1));
+
+        // live results overlapping with the historical ones
+        assert!(!processed.mark(101, 1));
+        assert!(processed.mark(101, 2));
+        assert!(!processed.mark(101, 2));
+    }
+
+    #[test]
+    fn resume_height_only_moves_forward() {
+        let mut processed = ProcessedTransactions::new(None);
+        assert!(processed.mark(105, 0));
+        assert_eq!(processed.resume_height, Some(105));
+
+        assert!(processed.mark(103, 0));
+        assert_eq!(processed.resume_height, Some(105));
+
+        assert!(processed.mark(107, 0));
+        assert_eq!(processed.resume_height, Some(107));
+
+        let mut processed = ProcessedTransactions::new(Some(200));
+        assert!(processed.mark(150, 0));
+        assert_eq!(processed.resume_height, Some(200));
+    }
+
+    #[test]
+    fn entries_outside_deduplication_window_are_pruned() {
+        let mut processed = ProcessedTransactions::new(None);
+        assert!(processed.mark(100, 0));
+        assert!(processed.mark(100 + DEDUPLICATION_WINDOW, 0));
+        assert!(processed.seen.contains(&(100, 0)));
+
+        assert!(processed.mark(101 + DEDUPLICATION_WINDOW, 0));
+        assert!(!processed.seen.contains(&(100, 0)));
+        assert!(processed.seen.contains(&(100 + DEDUPLICATION_WINDOW, 0)));
+    }
+}
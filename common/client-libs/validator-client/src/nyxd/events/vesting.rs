@@ -0,0 +1,120 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::EventAttributes;
+use crate::nyxd::Coin;
+use cosmrs::AccountId;
+use nym_vesting_contract_common::events::{
+    AMOUNT_KEY, FROM_ACCOUNT_KEY, NO_VALUE_VALUE, OWNERSHIP_TRANSFER_EVENT_TYPE, OWNER_KEY,
+    REMAINING_SPENDABLE_KEY, TO_ACCOUNT_KEY, VESTING_DECREASE_PLEDGE_EVENT_TYPE,
+    VESTING_DELEGATION_EVENT_TYPE, VESTING_GATEWAY_BONDING_EVENT_TYPE,
+    VESTING_GATEWAY_UNBONDING_EVENT_TYPE, VESTING_MIXNODE_BONDING_EVENT_TYPE,
+    VESTING_MIXNODE_UNBONDING_EVENT_TYPE, VESTING_PLEDGE_MORE_EVENT_TYPE,
+    VESTING_REDELEGATION_EVENT_TYPE, VESTING_UNDELEGATION_EVENT_TYPE, WITHDRAW_EVENT_TYPE,
+};
+
+// note: most of the vesting events do not carry any attributes as the details are
+// already included in the event emitted by the mixnet contract within the same transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum VestingEvent {
+    VestedCoinsWithdraw {
+        owner: AccountId,
+        amount: Coin,
+        remaining_spendable: Coin,
+    },
+    /// Transfer of either the account ownership or of its staking address.
+    OwnershipTransfer {
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+    },
+    MixnodeBonding,
+    MixnodeUnbonding,
+    PledgeMore,
+    DecreasePledge,
+    GatewayBonding,
+    GatewayUnbonding,
+    Delegation,
+    Undelegation,
+    Redelegation,
+
+    /// Any other event emitted by the vesting contract that does not have a typed representation.
+    Other(cosmwasm_std::Event),
+}
+
+pub(super) fn decode(event: &cosmwasm_std::Event) -> Result<VestingEvent, NyxdError> {
+    let attributes = EventAttributes::new(event);
+    let optional_account = |key: &str| -> Result<Option<AccountId>, NyxdError> {
+        if attributes.required(key)? == NO_VALUE_VALUE {
+            Ok(None)
+        } else {
+            attributes.account(key).map(Some)
+        }
+    };
+
+    let decoded = match event.ty.as_str() {
+        WITHDRAW_EVENT_TYPE => VestingEvent::VestedCoinsWithdraw {
+            owner: attributes.account(OWNER_KEY)?,
+            amount: attributes.coin(AMOUNT_KEY)?,
+            remaining_spendable: attributes.coin(REMAINING_SPENDABLE_KEY)?,
+        },
+        OWNERSHIP_TRANSFER_EVENT_TYPE => VestingEvent::OwnershipTransfer {
+            from: optional_account(FROM_ACCOUNT_KEY)?,
+            to: optional_account(TO_ACCOUNT_KEY)?,
+        },
+        VESTING_MIXNODE_BONDING_EVENT_TYPE => VestingEvent::MixnodeBonding,
+        VESTING_MIXNODE_UNBONDING_EVENT_TYPE => VestingEvent::MixnodeUnbonding,
+        VESTING_PLEDGE_MORE_EVENT_TYPE => VestingEvent::PledgeMore,
+        VESTING_DECREASE_PLEDGE_EVENT_TYPE => VestingEvent::DecreasePledge,
+        VESTING_GATEWAY_BONDING_EVENT_TYPE => VestingEvent::GatewayBonding,
+        VESTING_GATEWAY_UNBONDING_EVENT_TYPE => VestingEvent::GatewayUnbonding,
+        VESTING_DELEGATION_EVENT_TYPE => VestingEvent::Delegation,
+        VESTING_UNDELEGATION_EVENT_TYPE => VestingEvent::Undelegation,
+        VESTING_REDELEGATION_EVENT_TYPE => VestingEvent::Redelegation,
+        _ => VestingEvent::Other(event.clone()),
+    };
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Addr};
+    use nym_vesting_contract_common::events::{
+        new_staking_address_update_event, new_vested_coins_withdraw_event,
+    };
+
+    const OWNER: &str = "n10yyd98e2tuwu0f7ypz9dy3hhjw7v772q6287gy";
+
+    #[test]
+    fn decoding_withdraw() {
+        let event = new_vested_coins_withdraw_event(
+            &Addr::unchecked(OWNER),
+            &coin(100, "unym"),
+            &coin(42, "unym"),
+        );
+
+        assert_eq!(
+            decode(&event).unwrap(),
+            VestingEvent::VestedCoinsWithdraw {
+                owner: OWNER.parse().unwrap(),
+                amount: Coin::new(100, "unym"),
+                remaining_spendable: Coin::new(42, "unym"),
+            }
+        );
+    }
+
+    #[test]
+    fn decoding_ownership_transfer_with_missing_accounts() {
+        let event = new_staking_address_update_event(&None, &Some(Addr::unchecked(OWNER)));
+
+        assert_eq!(
+            decode(&event).unwrap(),
+            VestingEvent::OwnershipTransfer {
+                from: None,
+                to: Some(OWNER.parse().unwrap()),
+            }
+        );
+    }
+}
@@ -22,6 +22,9 @@ use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::time::SystemTime;
 
+pub use crate::nyxd::cosmwasm_client::authz::{
+    ContractExecutionGrant, MSG_EXECUTE_CONTRACT_TYPE_URL,
+};
 pub use crate::nyxd::cosmwasm_client::client::CosmWasmClient;
 pub use crate::nyxd::cosmwasm_client::signing_client::SigningCosmWasmClient;
 pub use crate::nyxd::fee::Fee;
@@ -51,6 +54,7 @@ pub type DirectSigningNyxdClient = SigningNyxdClient<DirectSecp256k1HdWallet>;
 pub mod coin;
 pub mod cosmwasm_client;
 pub mod error;
+pub mod events;
 pub mod fee;
 pub mod traits;
 
@@ -616,6 +620,74 @@ impl<C> NyxdClient<C> {
             .await
     }
 
+    /// Grant another address the permission to execute the specified contract messages on our behalf
+    pub async fn grant_contract_execution(
+        &self,
+        grantee: &AccountId,
+        grants: Vec<ContractExecutionGrant>,
+        expiration: Option<SystemTime>,
+        memo: impl Into<String> + Send + 'static,
+        fee: Option<Fee>,
+    ) -> Result<TxResponse, NyxdError>
+    where
+        C: SigningCosmWasmClient + Sync,
+    {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        self.client
+            .grant_contract_execution(self.address(), grantee, grants, expiration, fee, memo)
+            .await
+    }
+
+    /// Revoke the contract execution permission previously granted to another address
+    pub async fn revoke_contract_execution(
+        &self,
+        grantee: &AccountId,
+        memo: impl Into<String> + Send + 'static,
+        fee: Option<Fee>,
+    ) -> Result<TxResponse, NyxdError>
+    where
+        C: SigningCosmWasmClient + Sync,
+    {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        self.client
+            .revoke_authorization(
+                self.address(),
+                grantee,
+                MSG_EXECUTE_CONTRACT_TYPE_URL.to_owned(),
+                fee,
+                memo,
+            )
+            .await
+    }
+
+    /// Execute the contract on behalf of the granter using the permission it has previously granted us
+    pub async fn execute_as_grantee<M>(
+        &self,
+        granter: &AccountId,
+        contract_address: &AccountId,
+        msg: &M,
+        fee: Option<Fee>,
+        memo: impl Into<String> + Send + 'static,
+        funds: Vec<Coin>,
+    ) -> Result<ExecuteResult, NyxdError>
+    where
+        C: SigningCosmWasmClient + Sync,
+        M: ?Sized + Serialize + Sync,
+    {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        self.client
+            .execute_as_grantee(
+                self.address(),
+                granter,
+                contract_address,
+                msg,
+                fee,
+                memo,
+                funds,
+            )
+            .await
+    }
+
     pub async fn execute<M>(
         &self,
         contract_address: &AccountId,
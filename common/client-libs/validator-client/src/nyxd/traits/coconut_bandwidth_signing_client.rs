@@ -25,6 +25,7 @@ pub trait CoconutBandwidthSigningClient {
         funds: Coin,
         blinded_serial_number: String,
         gateway_cosmos_address: String,
+        expiry_date: u64,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError>;
 }
@@ -59,6 +60,7 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
         funds: Coin,
         blinded_serial_number: String,
         gateway_cosmos_address: String,
+        expiry_date: u64,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError> {
         let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
@@ -67,6 +69,7 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
                 funds.into(),
                 blinded_serial_number,
                 gateway_cosmos_address,
+                expiry_date,
             ),
         };
         self.client
@@ -5,6 +5,9 @@ use crate::nyxd::error::NyxdError;
 use crate::nyxd::{CosmWasmClient, NyxdClient};
 use async_trait::async_trait;
 use cosmrs::AccountId;
+use nym_coconut_dkg_common::complaint::{
+    Complaint, DisqualifiedDealer, PagedComplaintsResponse, PagedDisqualifiedDealersResponse,
+};
 use nym_coconut_dkg_common::dealer::{
     ContractDealing, DealerDetailsResponse, PagedDealerResponse, PagedDealingsResponse,
 };
@@ -95,6 +98,36 @@ pub trait DkgQueryClient {
         self.query_dkg_contract(request).await
     }
 
+    async fn get_complaints_paged(
+        &self,
+        epoch_id: EpochId,
+        dealer: String,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedComplaintsResponse, NyxdError> {
+        let request = DkgQueryMsg::GetComplaints {
+            epoch_id,
+            dealer,
+            limit: page_limit,
+            start_after,
+        };
+        self.query_dkg_contract(request).await
+    }
+
+    async fn get_disqualified_dealers_paged(
+        &self,
+        epoch_id: EpochId,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedDisqualifiedDealersResponse, NyxdError> {
+        let request = DkgQueryMsg::GetDisqualifiedDealers {
+            epoch_id,
+            limit: page_limit,
+            start_after,
+        };
+        self.query_dkg_contract(request).await
+    }
+
     async fn get_all_current_dealers(&self) -> Result<Vec<DealerDetails>, NyxdError> {
         let mut dealers = Vec::new();
         let mut start_after = None;
@@ -173,6 +206,51 @@ pub trait DkgQueryClient {
 
         Ok(shares)
     }
+
+    async fn get_all_complaints(
+        &self,
+        epoch_id: EpochId,
+        dealer: String,
+    ) -> Result<Vec<Complaint>, NyxdError> {
+        let mut complaints = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = self
+                .get_complaints_paged(epoch_id, dealer.clone(), start_after.take(), None)
+                .await?;
+            complaints.append(&mut paged_response.complaints);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res.into_string())
+            } else {
+                break;
+            }
+        }
+
+        Ok(complaints)
+    }
+
+    async fn get_all_disqualified_dealers(
+        &self,
+        epoch_id: EpochId,
+    ) -> Result<Vec<DisqualifiedDealer>, NyxdError> {
+        let mut dealers = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = self
+                .get_disqualified_dealers_paged(epoch_id, start_after.take(), None)
+                .await?;
+            dealers.append(&mut paged_response.dealers);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res.into_string())
+            } else {
+                break;
+            }
+        }
+
+        Ok(dealers)
+    }
 }
 
 #[async_trait]
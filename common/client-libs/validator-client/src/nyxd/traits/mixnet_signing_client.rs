@@ -26,6 +26,16 @@ pub trait MixnetSigningClient {
         funds: Vec<Coin>,
     ) -> Result<ExecuteResult, NyxdError>;
 
+    /// Executes the mixnet contract on behalf of the granter using the `x/authz` permission
+    /// it has previously granted to us.
+    async fn execute_mixnet_contract_as_grantee(
+        &self,
+        granter: &AccountId,
+        fee: Option<Fee>,
+        msg: MixnetExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> Result<ExecuteResult, NyxdError>;
+
     // state/sys-params-related
 
     async fn update_rewarding_validator_address(
@@ -443,6 +453,36 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_mixnode_cost_params_as_grantee(
+        &self,
+        owner: &AccountId,
+        new_costs: MixNodeCostParams,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract_as_grantee(
+            owner,
+            fee,
+            MixnetExecuteMsg::UpdateMixnodeCostParams { new_costs },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_mixnode_config_as_grantee(
+        &self,
+        owner: &AccountId,
+        new_config: MixNodeConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract_as_grantee(
+            owner,
+            fee,
+            MixnetExecuteMsg::UpdateMixnodeConfig { new_config },
+            vec![],
+        )
+        .await
+    }
+
     // gateway-related:
 
     async fn bond_gateway(
@@ -533,6 +573,21 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_gateway_config_as_grantee(
+        &self,
+        owner: &AccountId,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract_as_grantee(
+            owner,
+            fee,
+            MixnetExecuteMsg::UpdateGatewayConfig { new_config },
+            vec![],
+        )
+        .await
+    }
+
     // delegation-related:
 
     async fn delegate_to_mixnode(
@@ -597,6 +652,46 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateMixnode {
+                from_mix_id,
+                to_mix_id,
+                amount: amount.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn redelegate_mixnode_on_behalf(
+        &self,
+        delegate: AccountId,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateMixnodeOnBehalf {
+                from_mix_id,
+                to_mix_id,
+                amount: amount.into(),
+                delegate: delegate.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     // reward-related
 
     async fn reward_mixnode(
@@ -691,6 +786,28 @@ where
             )
             .await
     }
+
+    async fn execute_mixnet_contract_as_grantee(
+        &self,
+        granter: &AccountId,
+        fee: Option<Fee>,
+        msg: MixnetExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let memo = msg.default_memo();
+        self.client
+            .execute_as_grantee(
+                self.address(),
+                granter,
+                self.mixnet_contract_address(),
+                &msg,
+                fee,
+                memo,
+                funds,
+            )
+            .await
+    }
 }
 
 #[async_trait]
@@ -706,4 +823,16 @@ where
     ) -> Result<ExecuteResult, NyxdError> {
         self.nyxd.execute_mixnet_contract(fee, msg, funds).await
     }
+
+    async fn execute_mixnet_contract_as_grantee(
+        &self,
+        granter: &AccountId,
+        fee: Option<Fee>,
+        msg: MixnetExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.nyxd
+            .execute_mixnet_contract_as_grantee(granter, fee, msg, funds)
+            .await
+    }
 }
@@ -5,6 +5,7 @@ pub mod error;
 
 use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use error::CoconutInterfaceError;
 
@@ -18,6 +19,7 @@ pub struct Credential {
     theta: Theta,
     voucher_value: u64,
     voucher_info: String,
+    expiry_date: u64,
     #[getset(get = "pub")]
     epoch_id: u64,
 }
@@ -27,6 +29,7 @@ impl Credential {
         theta: Theta,
         voucher_value: u64,
         voucher_info: String,
+        expiry_date: u64,
         epoch_id: u64,
     ) -> Credential {
         Credential {
@@ -34,6 +37,7 @@ impl Credential {
             theta,
             voucher_value,
             voucher_info,
+            expiry_date,
             epoch_id,
         }
     }
@@ -55,32 +59,93 @@ impl Credential {
         self.voucher_value
     }
 
-    pub fn verify(&self, verification_key: &VerificationKey) -> bool {
-        let params = Parameters::new(self.n_params).unwrap();
-        let public_attributes = vec![
+    /// The unix timestamp after which the credential can no longer be spent.
+    pub fn expiry_date(&self) -> u64 {
+        self.expiry_date
+    }
+
+    fn public_attributes(&self) -> Vec<Attribute> {
+        vec![
             self.voucher_value.to_string().as_bytes(),
             self.voucher_info.as_bytes(),
+            self.expiry_date.to_string().as_bytes(),
         ]
         .iter()
         .map(hash_to_scalar)
-        .collect::<Vec<Attribute>>();
+        .collect()
+    }
+
+    pub fn verify(&self, verification_key: &VerificationKey) -> bool {
+        let params = match Parameters::new(self.n_params) {
+            Ok(params) => params,
+            Err(_) => return false,
+        };
+        let public_attributes = self.public_attributes();
         nym_coconut::verify_credential(&params, verification_key, &self.theta, &public_attributes)
     }
 
+    /// Verifies all the provided credentials, issued under the same verification key, at once.
+    /// Returns the (sorted) indices of the credentials that failed to verify.
+    pub fn batch_verify(
+        credentials: &[&Credential],
+        verification_key: &VerificationKey,
+    ) -> Vec<usize> {
+        // credentials can only be batched together if they were created with the same parameters
+        let mut by_params: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+        for (index, credential) in credentials.iter().enumerate() {
+            by_params
+                .entry(credential.n_params)
+                .or_default()
+                .push(index);
+        }
+
+        let mut invalid = Vec::new();
+        for (n_params, indices) in by_params {
+            // the number of parameters is provided by the client, so it might be invalid
+            let params = match Parameters::new(n_params) {
+                Ok(params) => params,
+                Err(_) => {
+                    invalid.extend(indices);
+                    continue;
+                }
+            };
+            let public_attributes = indices
+                .iter()
+                .map(|&index| credentials[index].public_attributes())
+                .collect::<Vec<_>>();
+            let batch = indices
+                .iter()
+                .zip(public_attributes.iter())
+                .map(|(&index, attributes)| (&credentials[index].theta, attributes.as_slice()))
+                .collect::<Vec<_>>();
+
+            invalid.extend(
+                nym_coconut::batch_verify_credentials(&params, verification_key, &batch)
+                    .into_iter()
+                    .map(|batch_index| indices[batch_index]),
+            );
+        }
+
+        invalid.sort_unstable();
+        invalid
+    }
+
     pub fn as_bytes(&self) -> Vec<u8> {
         let n_params_bytes = self.n_params.to_be_bytes();
         let theta_bytes = self.theta.to_bytes();
         let theta_bytes_len = theta_bytes.len();
         let voucher_value_bytes = self.voucher_value.to_be_bytes();
+        let expiry_date_bytes = self.expiry_date.to_be_bytes();
         let epoch_id_bytes = self.epoch_id.to_be_bytes();
         let voucher_info_bytes = self.voucher_info.as_bytes();
         let voucher_info_len = voucher_info_bytes.len();
 
-        let mut bytes = Vec::with_capacity(28 + theta_bytes_len + voucher_info_len);
+        let mut bytes = Vec::with_capacity(36 + theta_bytes_len + voucher_info_len);
         bytes.extend_from_slice(&n_params_bytes);
         bytes.extend_from_slice(&(theta_bytes_len as u64).to_be_bytes());
         bytes.extend_from_slice(&theta_bytes);
         bytes.extend_from_slice(&voucher_value_bytes);
+        bytes.extend_from_slice(&expiry_date_bytes);
         bytes.extend_from_slice(&epoch_id_bytes);
         bytes.extend_from_slice(voucher_info_bytes);
 
@@ -88,7 +153,7 @@ impl Credential {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, CoconutError> {
-        if bytes.len() < 28 {
+        if bytes.len() < 36 {
             return Err(CoconutError::Deserialization(String::from(
                 "To few bytes in credential",
             )));
@@ -100,7 +165,7 @@ impl Credential {
         let n_params = u32::from_be_bytes(four_byte);
         eight_byte.copy_from_slice(&bytes[4..12]);
         let theta_len = u64::from_be_bytes(eight_byte);
-        if bytes.len() < 28 + theta_len as usize {
+        if bytes.len() < 36 + theta_len as usize {
             return Err(CoconutError::Deserialization(String::from(
                 "To few bytes in credential",
             )));
@@ -110,8 +175,10 @@ impl Credential {
         eight_byte.copy_from_slice(&bytes[12 + theta_len as usize..20 + theta_len as usize]);
         let voucher_value = u64::from_be_bytes(eight_byte);
         eight_byte.copy_from_slice(&bytes[20 + theta_len as usize..28 + theta_len as usize]);
+        let expiry_date = u64::from_be_bytes(eight_byte);
+        eight_byte.copy_from_slice(&bytes[28 + theta_len as usize..36 + theta_len as usize]);
         let epoch_id = u64::from_be_bytes(eight_byte);
-        let voucher_info = String::from_utf8(bytes[28 + theta_len as usize..].to_vec())
+        let voucher_info = String::from_utf8(bytes[36 + theta_len as usize..].to_vec())
             .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
 
         Ok(Credential {
@@ -119,6 +186,7 @@ impl Credential {
             theta,
             voucher_value,
             voucher_info,
+            expiry_date,
             epoch_id,
         })
     }
@@ -140,8 +208,7 @@ impl Base58 for Credential {}
 mod tests {
     use super::*;
 
-    #[test]
-    fn serde_coconut_credential() {
+    fn credential_fixture(n_params: u32) -> (Credential, VerificationKey) {
         let voucher_value = 1000000u64;
         let voucher_info = String::from("BandwidthVoucher");
         let serial_number =
@@ -175,11 +242,35 @@ mod tests {
             binding_number,
         )
         .unwrap();
-        let credential = Credential::new(4, theta, voucher_value, voucher_info, 42);
+        let credential = Credential::new(
+            n_params,
+            theta,
+            voucher_value,
+            voucher_info,
+            1_682_899_200,
+            42,
+        );
+        (credential, verification_key)
+    }
+
+    #[test]
+    fn serde_coconut_credential() {
+        let (credential, _) = credential_fixture(4);
 
         let serialized_credential = credential.as_bytes();
         let deserialized_credential = Credential::from_bytes(&serialized_credential).unwrap();
 
         assert_eq!(credential, deserialized_credential);
     }
+
+    #[test]
+    fn credentials_with_invalid_params_are_rejected() {
+        let (credential, verification_key) = credential_fixture(0);
+
+        assert!(!credential.verify(&verification_key));
+        assert_eq!(
+            Credential::batch_verify(&[&credential], &verification_key),
+            vec![0]
+        );
+    }
 }
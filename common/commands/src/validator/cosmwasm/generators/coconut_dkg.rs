@@ -23,6 +23,9 @@ pub struct Args {
     #[clap(long)]
     pub dealing_exchange_time_secs: Option<u64>,
 
+    #[clap(long)]
+    pub complaint_submission_time_secs: Option<u64>,
+
     #[clap(long)]
     pub verification_key_submission_time_secs: Option<u64>,
 
@@ -68,6 +71,9 @@ pub async fn generate(args: Args) {
     if let Some(dealing_exchange_time_secs) = args.dealing_exchange_time_secs {
         time_configuration.dealing_exchange_time_secs = dealing_exchange_time_secs;
     }
+    if let Some(complaint_submission_time_secs) = args.complaint_submission_time_secs {
+        time_configuration.complaint_submission_time_secs = complaint_submission_time_secs;
+    }
     if let Some(verification_key_submission_time_secs) = args.verification_key_submission_time_secs
     {
         time_configuration.verification_key_submission_time_secs =
@@ -0,0 +1,40 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use nym_mixnet_contract_common::{Coin, MixId};
+use nym_validator_client::nyxd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Id of the mixnode the stake is going to be moved away from
+    #[clap(long)]
+    pub from_mix_id: MixId,
+
+    /// Id of the mixnode the stake is going to be moved to
+    #[clap(long)]
+    pub to_mix_id: MixId,
+
+    #[clap(long)]
+    pub amount: u128,
+}
+
+pub async fn redelegate_mixnode(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+
+    info!(
+        "Starting redelegation from mixnode {} to mixnode {}",
+        args.from_mix_id, args.to_mix_id
+    );
+
+    let coin = Coin::new(args.amount, denom);
+
+    let res = client
+        .redelegate_mixnode(args.from_mix_id, args.to_mix_id, coin.into(), None)
+        .await
+        .expect("failed to redelegate!");
+
+    info!("redelegating between mixnodes: {:?}", res);
+}
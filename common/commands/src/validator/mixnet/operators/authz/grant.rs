@@ -0,0 +1,90 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::{info, warn};
+use nym_validator_client::nyxd::{AccountId, ContractExecutionGrant};
+use std::time::{Duration, SystemTime};
+
+// mixnet contract messages that only change the settings of an already bonded node
+const OPERATOR_SETTINGS_MESSAGES: &[&str] = &[
+    "update_mixnode_config",
+    "update_mixnode_cost_params",
+    "update_gateway_config",
+];
+
+const DEFAULT_MAX_CALLS: u64 = 100;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Address of the account that is going to be allowed to execute the messages on your behalf
+    #[clap(long)]
+    pub grantee: AccountId,
+
+    /// Mixnet contract messages the grantee is allowed to execute. If not provided, the grantee
+    /// is only allowed to update the configuration and cost parameters of your node.
+    #[clap(long, value_delimiter = ',')]
+    pub allowed_messages: Option<Vec<String>>,
+
+    /// Allow granting messages other than the ones updating the settings of your node,
+    /// for example `unbond_mixnode` or `withdraw_operator_reward`
+    #[clap(long)]
+    pub allow_any: bool,
+
+    /// Maximum number of messages the grantee is allowed to execute
+    #[clap(long, default_value_t = DEFAULT_MAX_CALLS)]
+    pub max_calls: u64,
+
+    /// Number of seconds after which the grant expires. If not provided, it never expires.
+    #[clap(long)]
+    pub valid_for_secs: Option<u64>,
+}
+
+pub async fn grant(args: Args, client: SigningClient) {
+    info!("Granting mixnet contract permissions to {}", args.grantee);
+
+    let allowed_messages = args.allowed_messages.unwrap_or_else(|| {
+        OPERATOR_SETTINGS_MESSAGES
+            .iter()
+            .map(|msg| msg.to_string())
+            .collect()
+    });
+
+    let disallowed = allowed_messages
+        .iter()
+        .filter(|msg| !OPERATOR_SETTINGS_MESSAGES.contains(&msg.as_str()))
+        .collect::<Vec<_>>();
+    if !disallowed.is_empty() && !args.allow_any {
+        warn!("{disallowed:?} are not operator settings messages and would give the grantee control over more than just the configuration of your node. Are you sure that's what you want? If so, run with `--allow-any` flag");
+        return;
+    }
+
+    let expiration = args
+        .valid_for_secs
+        .map(|secs| SystemTime::now() + Duration::from_secs(secs));
+
+    info!(
+        "{} is going to be allowed to execute {allowed_messages:?} at most {} times",
+        args.grantee, args.max_calls
+    );
+
+    let grant = ContractExecutionGrant::new(
+        client.mixnet_contract_address().clone(),
+        allowed_messages,
+        args.max_calls,
+    );
+
+    let res = client
+        .grant_contract_execution(
+            &args.grantee,
+            vec![grant],
+            expiration,
+            "granting mixnet contract permissions via nym-cli",
+            None,
+        )
+        .await
+        .expect("failed to grant the permissions!");
+
+    info!("Grant result: {:?}", res)
+}
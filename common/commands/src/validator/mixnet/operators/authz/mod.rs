@@ -0,0 +1,22 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::{Args, Subcommand};
+
+pub mod grant;
+pub mod revoke;
+
+#[derive(Debug, Args)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_required = true)]
+pub struct MixnetOperatorsAuthz {
+    #[clap(subcommand)]
+    pub command: MixnetOperatorsAuthzCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MixnetOperatorsAuthzCommands {
+    /// Allow another account to update the settings of your node on your behalf
+    Grant(grant::Args),
+    /// Revoke the permissions previously granted to another account
+    Revoke(revoke::Args),
+}
@@ -0,0 +1,29 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use nym_validator_client::nyxd::AccountId;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Address of the account whose permissions should be revoked
+    #[clap(long)]
+    pub grantee: AccountId,
+}
+
+pub async fn revoke(args: Args, client: SigningClient) {
+    info!("Revoking contract permissions of {}", args.grantee);
+
+    let res = client
+        .revoke_contract_execution(
+            &args.grantee,
+            "revoking contract permissions via nym-cli",
+            None,
+        )
+        .await
+        .expect("failed to revoke the permissions!");
+
+    info!("Revoke result: {:?}", res)
+}
@@ -6,6 +6,7 @@ use clap::Parser;
 use log::info;
 use nym_mixnet_contract_common::GatewayConfigUpdate;
 use nym_validator_client::nyxd::traits::{MixnetQueryClient, MixnetSigningClient};
+use nym_validator_client::nyxd::AccountId;
 
 #[derive(Debug, Parser)]
 pub struct Args {
@@ -23,13 +24,20 @@ pub struct Args {
 
     #[clap(long)]
     pub version: Option<String>,
+
+    /// Address of the gateway owner that has granted this account the permission to update the config
+    /// (see `operators authz grant`). If not provided, the config of our own gateway is updated.
+    #[clap(long)]
+    pub granter: Option<AccountId>,
 }
 
 pub async fn update_config(args: Args, client: SigningClient) {
     info!("Update gateway config!");
 
+    let owner = args.granter.as_ref().unwrap_or(client.address());
+
     let current_details = match client
-        .get_owned_gateway(client.address())
+        .get_owned_gateway(owner)
         .await
         .expect("failed to query the chain for gateway details")
         .gateway
@@ -51,10 +59,15 @@ pub async fn update_config(args: Args, client: SigningClient) {
         version: args.version.unwrap_or(current_details.gateway.version),
     };
 
-    let res = client
-        .update_gateway_config(update, None)
-        .await
-        .expect("updating gateway config");
+    let res = match &args.granter {
+        Some(granter) => {
+            client
+                .update_gateway_config_as_grantee(granter, update, None)
+                .await
+        }
+        None => client.update_gateway_config(update, None).await,
+    }
+    .expect("updating gateway config");
 
     info!("gateway config updated: {:?}", res)
 }
@@ -6,6 +6,7 @@ use clap::Parser;
 use log::info;
 use nym_mixnet_contract_common::MixNodeConfigUpdate;
 use nym_validator_client::nyxd::traits::{MixnetQueryClient, MixnetSigningClient};
+use nym_validator_client::nyxd::AccountId;
 
 #[derive(Debug, Parser)]
 pub struct Args {
@@ -23,13 +24,20 @@ pub struct Args {
 
     #[clap(long)]
     pub version: Option<String>,
+
+    /// Address of the mixnode owner that has granted this account the permission to update the config
+    /// (see `operators authz grant`). If not provided, the config of our own mixnode is updated.
+    #[clap(long)]
+    pub granter: Option<AccountId>,
 }
 
 pub async fn update_config(args: Args, client: SigningClient) {
     info!("Update mix node config!");
 
+    let owner = args.granter.as_ref().unwrap_or(client.address());
+
     let current_details = match client
-        .get_owned_mixnode(client.address())
+        .get_owned_mixnode(owner)
         .await
         .expect("failed to query the chain for mixnode details")
         .mixnode_details
@@ -59,10 +67,15 @@ pub async fn update_config(args: Args, client: SigningClient) {
             .unwrap_or(current_details.bond_information.mix_node.version),
     };
 
-    let res = client
-        .update_mixnode_config(update, None)
-        .await
-        .expect("updating mix-node config");
+    let res = match &args.granter {
+        Some(granter) => {
+            client
+                .update_mixnode_config_as_grantee(granter, update, None)
+                .await
+        }
+        None => client.update_mixnode_config(update, None).await,
+    }
+    .expect("updating mix-node config");
 
     info!("mixnode config updated: {:?}", res)
 }
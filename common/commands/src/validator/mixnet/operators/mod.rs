@@ -3,6 +3,7 @@
 
 use clap::{Args, Subcommand};
 
+pub mod authz;
 pub mod gateway;
 pub mod mixnode;
 
@@ -19,4 +20,6 @@ pub enum MixnetOperatorsCommands {
     Mixnode(mixnode::MixnetOperatorsMixnode),
     /// Manage your gateway
     Gateway(gateway::MixnetOperatorsGateway),
+    /// Delegate the management of your node to another account
+    Authz(authz::MixnetOperatorsAuthz),
 }
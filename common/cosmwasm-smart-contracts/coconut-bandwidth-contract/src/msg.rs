@@ -20,6 +20,7 @@ pub enum ExecuteMsg {
     DepositFunds { data: DepositData },
     SpendCredential { data: SpendCredentialData },
     ReleaseFunds { funds: Coin },
+    PruneExpiredCredentials { limit: Option<u32> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
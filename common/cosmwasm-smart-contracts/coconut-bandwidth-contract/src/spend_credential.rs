@@ -13,14 +13,21 @@ pub struct SpendCredentialData {
     funds: Coin,
     blinded_serial_number: String,
     gateway_cosmos_address: String,
+    expiry_date: u64,
 }
 
 impl SpendCredentialData {
-    pub fn new(funds: Coin, blinded_serial_number: String, gateway_cosmos_address: String) -> Self {
+    pub fn new(
+        funds: Coin,
+        blinded_serial_number: String,
+        gateway_cosmos_address: String,
+        expiry_date: u64,
+    ) -> Self {
         SpendCredentialData {
             funds,
             blinded_serial_number,
             gateway_cosmos_address,
+            expiry_date,
         }
     }
 
@@ -35,6 +42,10 @@ impl SpendCredentialData {
     pub fn gateway_cosmos_address(&self) -> &str {
         &self.gateway_cosmos_address
     }
+
+    pub fn expiry_date(&self) -> u64 {
+        self.expiry_date
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
@@ -49,15 +60,24 @@ pub struct SpendCredential {
     blinded_serial_number: String,
     gateway_cosmos_address: Addr,
     status: SpendCredentialStatus,
+    // entries stored before the credentials had expiry dates are treated as already expired
+    #[serde(default)]
+    expiry_date: u64,
 }
 
 impl SpendCredential {
-    pub fn new(funds: Coin, blinded_serial_number: String, gateway_cosmos_address: Addr) -> Self {
+    pub fn new(
+        funds: Coin,
+        blinded_serial_number: String,
+        gateway_cosmos_address: Addr,
+        expiry_date: u64,
+    ) -> Self {
         SpendCredential {
             funds,
             blinded_serial_number,
             gateway_cosmos_address,
             status: SpendCredentialStatus::InProgress,
+            expiry_date,
         }
     }
 
@@ -65,6 +85,10 @@ impl SpendCredential {
         &self.blinded_serial_number
     }
 
+    pub fn expiry_date(&self) -> u64 {
+        self.expiry_date
+    }
+
     pub fn status(&self) -> SpendCredentialStatus {
         self.status
     }
@@ -0,0 +1,86 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::EpochId;
+use cosmwasm_std::Addr;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// note: the variants are not renamed to snake_case as the reason is also part of the state
+// persisted by the nym-apis
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Hash, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum ComplaintReason {
+    MalformedBTEPublicKey,
+    InvalidBTEPublicKey,
+    MissingDealing,
+    MalformedDealing,
+    DealingVerificationError,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Complaint {
+    pub complainer: Addr,
+    pub dealer: Addr,
+    pub epoch_id: EpochId,
+    pub reason: ComplaintReason,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DisqualifiedDealer {
+    pub dealer: Addr,
+    pub epoch_id: EpochId,
+
+    /// Number of dealers that complained about this dealer.
+    pub complaints: u32,
+
+    /// The most common reason given by the complainers.
+    pub reason: ComplaintReason,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PagedComplaintsResponse {
+    pub complaints: Vec<Complaint>,
+    pub per_page: usize,
+    pub start_next_after: Option<Addr>,
+}
+
+impl PagedComplaintsResponse {
+    pub fn new(
+        complaints: Vec<Complaint>,
+        per_page: usize,
+        start_next_after: Option<Addr>,
+    ) -> Self {
+        PagedComplaintsResponse {
+            complaints,
+            per_page,
+            start_next_after,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PagedDisqualifiedDealersResponse {
+    pub dealers: Vec<DisqualifiedDealer>,
+    pub per_page: usize,
+    pub start_next_after: Option<Addr>,
+}
+
+impl PagedDisqualifiedDealersResponse {
+    pub fn new(
+        dealers: Vec<DisqualifiedDealer>,
+        per_page: usize,
+        start_next_after: Option<Addr>,
+    ) -> Self {
+        PagedDisqualifiedDealersResponse {
+            dealers,
+            per_page,
+            start_next_after,
+        }
+    }
+}
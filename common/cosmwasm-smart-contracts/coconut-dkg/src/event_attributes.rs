@@ -3,3 +3,8 @@
 
 pub const NODE_INDEX: &str = "node_index";
 pub const DKG_PROPOSAL_ID: &str = "proposal_id";
+
+pub const DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE: &str = "dkg_epoch_state_advance";
+pub const DKG_EPOCH_ID: &str = "epoch_id";
+pub const DKG_EPOCH_STATE: &str = "epoch_state";
+pub const DKG_DISQUALIFIED_DEALERS: &str = "disqualified_dealers";
@@ -1,3 +1,4 @@
+pub mod complaint;
 pub mod dealer;
 pub mod event_attributes;
 pub mod msg;
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::complaint::ComplaintReason;
 use crate::types::{ContractSafeBytes, EncodedBTEPublicKeyWithProof, EpochId, TimeConfiguration};
 use crate::verification_key::VerificationKeyShare;
 use cosmwasm_std::Addr;
@@ -29,6 +30,12 @@ pub enum ExecuteMsg {
         resharing: bool,
     },
 
+    SubmitComplaint {
+        dealer: String,
+        reason: ComplaintReason,
+        resharing: bool,
+    },
+
     CommitVerificationKeyShare {
         share: VerificationKeyShare,
         resharing: bool,
@@ -71,6 +78,17 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<String>,
     },
+    GetComplaints {
+        epoch_id: EpochId,
+        dealer: String,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetDisqualifiedDealers {
+        epoch_id: EpochId,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -15,8 +15,8 @@ pub type EncodedBTEPublicKeyWithProofRef<'a> = &'a str;
 pub type NodeIndex = u64;
 pub type EpochId = u64;
 
-// 2 public attributes, 2 private attributes, 1 fixed for coconut credential
-pub const TOTAL_DEALINGS: usize = 2 + 2 + 1;
+// 3 public attributes, 2 private attributes, 1 fixed for coconut credential
+pub const TOTAL_DEALINGS: usize = 3 + 2 + 1;
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq, Ord, PartialOrd)]
 pub struct InitialReplacementData {
@@ -31,6 +31,9 @@ pub struct TimeConfiguration {
     // The time sign-up is open for dealers to join
     pub public_key_submission_time_secs: u64,
     pub dealing_exchange_time_secs: u64,
+    // The time dealers have for complaining about misbehaving dealers
+    #[serde(default = "default_complaint_submission_time_secs")]
+    pub complaint_submission_time_secs: u64,
     pub verification_key_submission_time_secs: u64,
     pub verification_key_validation_time_secs: u64,
     pub verification_key_finalization_time_secs: u64,
@@ -47,26 +50,32 @@ impl FromStr for TimeConfiguration {
             .map(|t| t.parse())
             .collect::<Result<Vec<u64>, _>>()
             .map_err(|_| String::from("Could not parse string"))?;
-        if times.len() != 6 {
+        if times.len() != 7 {
             Err(String::from("Not enough time specified"))
         } else {
             Ok(TimeConfiguration {
                 public_key_submission_time_secs: times[0],
                 dealing_exchange_time_secs: times[1],
-                verification_key_submission_time_secs: times[2],
-                verification_key_validation_time_secs: times[3],
-                verification_key_finalization_time_secs: times[4],
-                in_progress_time_secs: times[5],
+                complaint_submission_time_secs: times[2],
+                verification_key_submission_time_secs: times[3],
+                verification_key_validation_time_secs: times[4],
+                verification_key_finalization_time_secs: times[5],
+                in_progress_time_secs: times[6],
             })
         }
     }
 }
 
+fn default_complaint_submission_time_secs() -> u64 {
+    60 * 5 // 5 minutes
+}
+
 impl Default for TimeConfiguration {
     fn default() -> Self {
         Self {
-            public_key_submission_time_secs: 60 * 10,      // 10 minutes
-            dealing_exchange_time_secs: 60 * 5,            // 5 minutes
+            public_key_submission_time_secs: 60 * 10, // 10 minutes
+            dealing_exchange_time_secs: 60 * 5,       // 5 minutes
+            complaint_submission_time_secs: default_complaint_submission_time_secs(),
             verification_key_submission_time_secs: 60 * 5, // 5 minutes
             verification_key_validation_time_secs: 60,     // 1 minute
             verification_key_finalization_time_secs: 60,   // 1 minute
@@ -96,6 +105,9 @@ impl Epoch {
                 time_configuration.public_key_submission_time_secs
             }
             EpochState::DealingExchange { .. } => time_configuration.dealing_exchange_time_secs,
+            EpochState::ComplaintSubmission { .. } => {
+                time_configuration.complaint_submission_time_secs
+            }
             EpochState::VerificationKeySubmission { .. } => {
                 time_configuration.verification_key_submission_time_secs
             }
@@ -126,6 +138,9 @@ impl Epoch {
                     time_configuration.public_key_submission_time_secs
                 }
                 EpochState::DealingExchange { .. } => time_configuration.dealing_exchange_time_secs,
+                EpochState::ComplaintSubmission { .. } => {
+                    time_configuration.complaint_submission_time_secs
+                }
                 EpochState::VerificationKeySubmission { .. } => {
                     time_configuration.verification_key_submission_time_secs
                 }
@@ -147,12 +162,12 @@ impl Epoch {
 // the epoch can be in the following states (in order):
 // 1. PublicKeySubmission -> potential dealers are submitting their BTE and ed25519 public keys to participate in dealing exchange
 // 2. DealingExchange -> the actual (off-chain) dealing exchange is happening
-// 3. ComplaintSubmission -> receivers submitting evidence of other dealers sending malformed data
-// 4. ComplaintVoting -> (if any complaints were submitted) receivers voting on the validity of the evidence provided
-// 5. VerificationKeySubmission -> receivers submitting their partial (and master) verification keys
-// 6. VerificationKeyMismatchSubmission -> receivers / watchers raising issue that the submitted VK are mismatched with their local derivations
-// 7. VerificationKeyMismatchVoting -> (if any complaints were submitted) receivers voting on received mismatches
-// 8. InProgress -> all receivers have all their secrets derived and all is good
+// 3. ComplaintSubmission -> receivers complaining about dealers that sent malformed data (or no data at all).
+//    Dealers with at least `threshold` complaints are disqualified once the state is advanced
+// 4. VerificationKeySubmission -> receivers submitting their partial (and master) verification keys
+// 5. VerificationKeyValidation -> receivers voting on the validity of the submitted verification keys
+// 6. VerificationKeyFinalization -> receivers executing the proposals of their verification keys
+// 7. InProgress -> all receivers have all their secrets derived and all is good
 //
 // Note: It's important that the variant ordering is not changed otherwise it would mess up the derived `PartialOrd`
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd)]
@@ -160,6 +175,7 @@ impl Epoch {
 pub enum EpochState {
     PublicKeySubmission { resharing: bool },
     DealingExchange { resharing: bool },
+    ComplaintSubmission { resharing: bool },
     VerificationKeySubmission { resharing: bool },
     VerificationKeyValidation { resharing: bool },
     VerificationKeyFinalization { resharing: bool },
@@ -179,6 +195,9 @@ impl Display for EpochState {
                 write!(f, "PublicKeySubmission with resharing {resharing}")
             }
             EpochState::DealingExchange { resharing } => write!(f, "DealingExchange {resharing}"),
+            EpochState::ComplaintSubmission { resharing } => {
+                write!(f, "ComplaintSubmission with resharing {resharing}")
+            }
             EpochState::VerificationKeySubmission { resharing } => {
                 write!(f, "VerificationKeySubmission with resharing {resharing}")
             }
@@ -200,6 +219,9 @@ impl EpochState {
                 Some(EpochState::DealingExchange { resharing })
             }
             EpochState::DealingExchange { resharing } => {
+                Some(EpochState::ComplaintSubmission { resharing })
+            }
+            EpochState::ComplaintSubmission { resharing } => {
                 Some(EpochState::VerificationKeySubmission { resharing })
             }
             EpochState::VerificationKeySubmission { resharing } => {
@@ -138,6 +138,9 @@ pub enum MixnetContractError {
         proxy: Option<String>,
     },
 
+    #[error("Attempted to redelegate from mixnode {mix_id} back to itself")]
+    RedelegationToSameMixnode { mix_id: MixId },
+
     #[error("Provided message to update rewarding params did not contain any updates")]
     EmptyParamsChangeMsg,
 
@@ -227,6 +227,17 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         delegate: String,
     },
+    RedelegateMixnode {
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+    },
+    RedelegateMixnodeOnBehalf {
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        delegate: String,
+    },
 
     // reward-related
     RewardMixnode {
@@ -343,6 +354,19 @@ impl ExecuteMsg {
             ExecuteMsg::UndelegateFromMixnodeOnBehalf { mix_id, .. } => {
                 format!("removing delegation from mixnode {mix_id} on behalf")
             }
+            ExecuteMsg::RedelegateMixnode {
+                from_mix_id,
+                to_mix_id,
+                amount,
+            } => format!("redelegating {amount} from mixnode {from_mix_id} to mixnode {to_mix_id}"),
+            ExecuteMsg::RedelegateMixnodeOnBehalf {
+                from_mix_id,
+                to_mix_id,
+                amount,
+                ..
+            } => format!(
+                "redelegating {amount} from mixnode {from_mix_id} to mixnode {to_mix_id} on behalf"
+            ),
             ExecuteMsg::RewardMixnode {
                 mix_id,
                 performance,
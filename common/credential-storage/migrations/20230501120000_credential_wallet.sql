@@ -0,0 +1,14 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- identity of the gateway at which the credential got spent
+ALTER TABLE coconut_credentials ADD COLUMN gateway_id TEXT;
+
+-- bandwidth still available at gateways, bought with the already spent credentials
+CREATE TABLE gateway_bandwidth
+(
+    gateway_id          TEXT    NOT NULL PRIMARY KEY,
+    remaining_bandwidth INTEGER NOT NULL
+);
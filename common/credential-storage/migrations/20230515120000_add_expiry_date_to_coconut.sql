@@ -0,0 +1,8 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- unix timestamp after which the credential can no longer be spent.
+-- credentials issued before the expiry attribute got introduced are marked as expired right away
+ALTER TABLE coconut_credentials ADD COLUMN expiry_date TEXT NOT NULL DEFAULT '0';
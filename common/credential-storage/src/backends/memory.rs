@@ -1,13 +1,15 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::models::CoconutCredential;
+use crate::models::{CoconutCredential, GatewayBandwidth};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 #[derive(Clone)]
 pub struct CoconutCredentialManager {
     inner: Arc<RwLock<Vec<CoconutCredential>>>,
+    gateway_bandwidth: Arc<RwLock<HashMap<String, i64>>>,
 }
 
 impl CoconutCredentialManager {
@@ -15,6 +17,7 @@ impl CoconutCredentialManager {
     pub fn new() -> Self {
         CoconutCredentialManager {
             inner: Arc::new(RwLock::new(Vec::new())),
+            gateway_bandwidth: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -27,6 +30,9 @@ impl CoconutCredentialManager {
     /// * `serial_number`: Base58 representation of the serial number attribute.
     /// * `binding_number`: Base58 representation of the binding number attribute.
     /// * `signature`: Coconut credential in the form of a signature.
+    /// * `epoch_id`: The epoch when it was signed.
+    /// * `expiry_date`: Unix timestamp after which the credential can no longer be spent.
+    #[allow(clippy::too_many_arguments)]
     pub async fn insert_coconut_credential(
         &self,
         voucher_value: String,
@@ -35,6 +41,7 @@ impl CoconutCredentialManager {
         binding_number: String,
         signature: String,
         epoch_id: String,
+        expiry_date: String,
     ) {
         let mut creds = self.inner.write().await;
         let id = creds.len() as i64;
@@ -47,6 +54,8 @@ impl CoconutCredentialManager {
             signature,
             epoch_id,
             consumed: false,
+            gateway_id: None,
+            expiry_date,
         });
     }
 
@@ -56,15 +65,54 @@ impl CoconutCredentialManager {
         creds.iter().find(|c| !c.consumed).cloned()
     }
 
+    /// Retrieves all the stored, unused credentials.
+    pub async fn get_unconsumed_coconut_credentials(&self) -> Vec<CoconutCredential> {
+        let creds = self.inner.read().await;
+        creds.iter().filter(|c| !c.consumed).cloned().collect()
+    }
+
+    /// Retrieves all the stored credentials, including the already consumed ones.
+    pub async fn get_all_coconut_credentials(&self) -> Vec<CoconutCredential> {
+        self.inner.read().await.clone()
+    }
+
     /// Consumes in the database the specified credential.
     ///
     /// # Arguments
     ///
     /// * `id`: Database id.
-    pub async fn consume_coconut_credential(&self, id: i64) {
+    /// * `gateway_id`: Base58 representation of the identity of the gateway the credential got spent at.
+    pub async fn consume_coconut_credential(&self, id: i64, gateway_id: &str) {
         let mut creds = self.inner.write().await;
         if let Some(cred) = creds.get_mut(id as usize) {
             cred.consumed = true;
+            cred.gateway_id = Some(gateway_id.to_string());
         }
     }
+
+    /// Sets the amount of bandwidth still available at the specified gateway.
+    ///
+    /// # Arguments
+    ///
+    /// * `gateway_id`: Base58 representation of the identity of the gateway.
+    /// * `remaining_bandwidth`: Bandwidth remaining at the gateway.
+    pub async fn update_gateway_bandwidth(&self, gateway_id: &str, remaining_bandwidth: i64) {
+        self.gateway_bandwidth
+            .write()
+            .await
+            .insert(gateway_id.to_string(), remaining_bandwidth);
+    }
+
+    /// Retrieves the bandwidth remaining at all the gateways the credentials got spent at.
+    pub async fn get_gateway_bandwidths(&self) -> Vec<GatewayBandwidth> {
+        self.gateway_bandwidth
+            .read()
+            .await
+            .iter()
+            .map(|(gateway_id, remaining_bandwidth)| GatewayBandwidth {
+                gateway_id: gateway_id.clone(),
+                remaining_bandwidth: *remaining_bandwidth,
+            })
+            .collect()
+    }
 }
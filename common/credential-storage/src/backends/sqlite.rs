@@ -1,7 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::models::CoconutCredential;
+use crate::models::{CoconutCredential, GatewayBandwidth};
 
 #[derive(Clone)]
 pub struct CoconutCredentialManager {
@@ -27,6 +27,9 @@ impl CoconutCredentialManager {
     /// * `serial_number`: Base58 representation of the serial number attribute.
     /// * `binding_number`: Base58 representation of the binding number attribute.
     /// * `signature`: Coconut credential in the form of a signature.
+    /// * `epoch_id`: The epoch when it was signed.
+    /// * `expiry_date`: Unix timestamp after which the credential can no longer be spent.
+    #[allow(clippy::too_many_arguments)]
     pub async fn insert_coconut_credential(
         &self,
         voucher_value: String,
@@ -35,10 +38,11 @@ impl CoconutCredentialManager {
         binding_number: String,
         signature: String,
         epoch_id: String,
+        expiry_date: String,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "INSERT INTO coconut_credentials(voucher_value, voucher_info, serial_number, binding_number, signature, epoch_id, consumed) VALUES (?, ?, ?, ?, ?, ?, ?)",
-            voucher_value, voucher_info, serial_number, binding_number, signature, epoch_id, false
+            "INSERT INTO coconut_credentials(voucher_value, voucher_info, serial_number, binding_number, signature, epoch_id, consumed, expiry_date) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            voucher_value, voucher_info, serial_number, binding_number, signature, epoch_id, false, expiry_date
         )
         .execute(&self.connection_pool)
         .await?;
@@ -57,18 +61,71 @@ impl CoconutCredentialManager {
         .await
     }
 
+    /// Retrieves all the stored, unused credentials.
+    pub async fn get_unconsumed_coconut_credentials(
+        &self,
+    ) -> Result<Vec<CoconutCredential>, sqlx::Error> {
+        sqlx::query_as!(
+            CoconutCredential,
+            "SELECT * FROM coconut_credentials WHERE NOT consumed"
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Retrieves all the stored credentials, including the already consumed ones.
+    pub async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, sqlx::Error> {
+        sqlx::query_as!(CoconutCredential, "SELECT * FROM coconut_credentials")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+
     /// Consumes in the database the specified credential.
     ///
     /// # Arguments
     ///
     /// * `id`: Database id.
-    pub async fn consume_coconut_credential(&self, id: i64) -> Result<(), sqlx::Error> {
+    /// * `gateway_id`: Base58 representation of the identity of the gateway the credential got spent at.
+    pub async fn consume_coconut_credential(
+        &self,
+        id: i64,
+        gateway_id: &str,
+    ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE coconut_credentials SET consumed = TRUE WHERE id = ?",
+            "UPDATE coconut_credentials SET consumed = TRUE, gateway_id = ? WHERE id = ?",
+            gateway_id,
             id
         )
         .execute(&self.connection_pool)
         .await?;
         Ok(())
     }
+
+    /// Sets the amount of bandwidth still available at the specified gateway.
+    ///
+    /// # Arguments
+    ///
+    /// * `gateway_id`: Base58 representation of the identity of the gateway.
+    /// * `remaining_bandwidth`: Bandwidth remaining at the gateway.
+    pub async fn update_gateway_bandwidth(
+        &self,
+        gateway_id: &str,
+        remaining_bandwidth: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR REPLACE INTO gateway_bandwidth(gateway_id, remaining_bandwidth) VALUES (?, ?)",
+            gateway_id,
+            remaining_bandwidth
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Retrieves the bandwidth remaining at all the gateways the credentials got spent at.
+    pub async fn get_gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, sqlx::Error> {
+        sqlx::query_as!(GatewayBandwidth, "SELECT * FROM gateway_bandwidth")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
 }
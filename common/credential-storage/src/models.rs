@@ -12,4 +12,144 @@ pub struct CoconutCredential {
     pub signature: String,
     pub epoch_id: String,
     pub consumed: bool,
+    pub gateway_id: Option<String>,
+    pub expiry_date: String,
+}
+
+impl CoconutCredential {
+    /// Parses the plaintext value of the credential. Returns `None` if it's malformed.
+    pub fn value(&self) -> Option<u64> {
+        self.voucher_value.parse().ok()
+    }
+
+    /// Parses the expiry date of the credential. Returns `None` if it's malformed.
+    pub fn expiry_date(&self) -> Option<u64> {
+        self.expiry_date.parse().ok()
+    }
+
+    /// Checks whether the credential could still be spent at the provided unix timestamp.
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.expiry_date()
+            .map_or(false, |expiry_date| expiry_date > timestamp)
+    }
+}
+
+#[derive(Clone)]
+pub struct GatewayBandwidth {
+    pub gateway_id: String,
+    pub remaining_bandwidth: i64,
+}
+
+/// Chooses the credential that covers the required value with the least amount of waste,
+/// i.e. the smallest one worth at least `required_value`. If none of them is worth enough,
+/// the most valuable credential is chosen instead. Credentials that are no longer valid
+/// at `valid_at` are never chosen.
+pub(crate) fn best_fitting_credential<I>(
+    credentials: I,
+    required_value: u64,
+    valid_at: u64,
+) -> Option<CoconutCredential>
+where
+    I: IntoIterator<Item = CoconutCredential>,
+{
+    let mut smallest_sufficient: Option<(u64, CoconutCredential)> = None;
+    let mut largest: Option<(u64, CoconutCredential)> = None;
+
+    for credential in credentials {
+        // skip the expired credentials and the ones with malformed values
+        // as we wouldn't be able to spend them anyway
+        if !credential.is_valid_at(valid_at) {
+            continue;
+        }
+        let Some(value) = credential.value() else {
+            continue;
+        };
+
+        if value >= required_value
+            && smallest_sufficient
+                .as_ref()
+                .map_or(true, |(best, _)| value < *best)
+        {
+            smallest_sufficient = Some((value, credential.clone()));
+        }
+        if largest.as_ref().map_or(true, |(best, _)| value > *best) {
+            largest = Some((value, credential));
+        }
+    }
+
+    smallest_sufficient
+        .or(largest)
+        .map(|(_, credential)| credential)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(id: i64, voucher_value: &str) -> CoconutCredential {
+        expiring_credential(id, voucher_value, "2000")
+    }
+
+    fn expiring_credential(id: i64, voucher_value: &str, expiry_date: &str) -> CoconutCredential {
+        CoconutCredential {
+            id,
+            voucher_value: voucher_value.to_string(),
+            voucher_info: String::new(),
+            serial_number: String::new(),
+            binding_number: String::new(),
+            signature: String::new(),
+            epoch_id: String::new(),
+            consumed: false,
+            gateway_id: None,
+            expiry_date: expiry_date.to_string(),
+        }
+    }
+
+    #[test]
+    fn best_fitting_credential_is_chosen() {
+        let credentials = vec![
+            credential(0, "1000"),
+            credential(1, "50"),
+            credential(2, "malformed"),
+            credential(3, "200"),
+        ];
+
+        let chosen = |required| {
+            best_fitting_credential(credentials.clone(), required, 1000)
+                .map(|credential| credential.id)
+        };
+
+        assert_eq!(chosen(0), Some(1));
+        assert_eq!(chosen(50), Some(1));
+        assert_eq!(chosen(51), Some(3));
+        assert_eq!(chosen(500), Some(0));
+        // nothing is worth enough, so use the most valuable one
+        assert_eq!(chosen(5000), Some(0));
+        assert_eq!(
+            best_fitting_credential(Vec::new(), 10, 1000).map(|c| c.id),
+            None
+        );
+    }
+
+    #[test]
+    fn expired_credentials_are_never_chosen() {
+        let credentials = vec![
+            expiring_credential(0, "100", "1000"),
+            expiring_credential(1, "1000", "999"),
+            expiring_credential(2, "50", "malformed"),
+            expiring_credential(3, "10", "1001"),
+        ];
+
+        let chosen = |required| {
+            best_fitting_credential(credentials.clone(), required, 1000)
+                .map(|credential| credential.id)
+        };
+
+        assert_eq!(chosen(10), Some(3));
+        assert_eq!(chosen(100), Some(3));
+        assert_eq!(
+            best_fitting_credential(credentials.clone(), 10, 1001).map(|c| c.id),
+            None
+        );
+    }
 }
@@ -5,7 +5,7 @@ use crate::backends::sqlite::CoconutCredentialManager;
 use crate::error::StorageError;
 use crate::storage::Storage;
 
-use crate::models::CoconutCredential;
+use crate::models::{best_fitting_credential, CoconutCredential, GatewayBandwidth};
 use async_trait::async_trait;
 use log::{debug, error};
 use sqlx::ConnectOptions;
@@ -64,6 +64,7 @@ impl Storage for PersistentStorage {
         binding_number: String,
         signature: String,
         epoch_id: String,
+        expiry_date: String,
     ) -> Result<(), StorageError> {
         self.coconut_credential_manager
             .insert_coconut_credential(
@@ -73,6 +74,7 @@ impl Storage for PersistentStorage {
                 binding_number,
                 signature,
                 epoch_id,
+                expiry_date,
             )
             .await?;
 
@@ -89,11 +91,59 @@ impl Storage for PersistentStorage {
         Ok(credential)
     }
 
-    async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError> {
+    async fn get_coconut_credential_for_value(
+        &self,
+        required_value: u64,
+        valid_at: u64,
+    ) -> Result<CoconutCredential, StorageError> {
+        let credentials = self
+            .coconut_credential_manager
+            .get_unconsumed_coconut_credentials()
+            .await?;
+
+        best_fitting_credential(credentials, required_value, valid_at)
+            .ok_or(StorageError::NoCredential)
+    }
+
+    async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, StorageError> {
+        let credentials = self
+            .coconut_credential_manager
+            .get_all_coconut_credentials()
+            .await?;
+
+        Ok(credentials)
+    }
+
+    async fn consume_coconut_credential(
+        &self,
+        id: i64,
+        gateway_id: &str,
+    ) -> Result<(), StorageError> {
         self.coconut_credential_manager
-            .consume_coconut_credential(id)
+            .consume_coconut_credential(id, gateway_id)
             .await?;
 
         Ok(())
     }
+
+    async fn update_gateway_bandwidth(
+        &self,
+        gateway_id: &str,
+        remaining_bandwidth: i64,
+    ) -> Result<(), StorageError> {
+        self.coconut_credential_manager
+            .update_gateway_bandwidth(gateway_id, remaining_bandwidth)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, StorageError> {
+        let bandwidths = self
+            .coconut_credential_manager
+            .get_gateway_bandwidths()
+            .await?;
+
+        Ok(bandwidths)
+    }
 }
@@ -4,7 +4,7 @@
 use async_trait::async_trait;
 
 use crate::error::StorageError;
-use crate::models::CoconutCredential;
+use crate::models::{CoconutCredential, GatewayBandwidth};
 
 #[async_trait]
 pub trait Storage: Send + Sync {
@@ -18,6 +18,8 @@ pub trait Storage: Send + Sync {
     /// * `binding_number`: Binding number of the credential.
     /// * `signature`: Coconut credential in the form of a signature.
     /// * `epoch_id`: The epoch when it was signed.
+    /// * `expiry_date`: Unix timestamp after which the credential can no longer be spent.
+    #[allow(clippy::too_many_arguments)]
     async fn insert_coconut_credential(
         &self,
         voucher_value: String,
@@ -26,15 +28,54 @@ pub trait Storage: Send + Sync {
         binding_number: String,
         signature: String,
         epoch_id: String,
+        expiry_date: String,
     ) -> Result<(), StorageError>;
 
     /// Tries to retrieve one of the stored, unused credentials.
     async fn get_next_coconut_credential(&self) -> Result<CoconutCredential, StorageError>;
 
+    /// Tries to retrieve the unused credential best matching the required value, i.e. the smallest
+    /// one worth at least that much or, if there's no such credential, the most valuable one.
+    /// Credentials that have already expired are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `required_value`: The value the credential should ideally be worth.
+    /// * `valid_at`: Unix timestamp at which the credential has to still be valid.
+    async fn get_coconut_credential_for_value(
+        &self,
+        required_value: u64,
+        valid_at: u64,
+    ) -> Result<CoconutCredential, StorageError>;
+
+    /// Retrieves all the stored credentials, including the already consumed ones.
+    async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, StorageError>;
+
     /// Marks as consumed in the database the specified credential.
     ///
     /// # Arguments
     ///
     /// * `id`: Id of the credential to be consumed.
-    async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError>;
+    /// * `gateway_id`: Identity of the gateway the credential got spent at.
+    async fn consume_coconut_credential(
+        &self,
+        id: i64,
+        gateway_id: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Records how much bandwidth, bought with the already spent credentials,
+    /// is still available at the specified gateway.
+    ///
+    /// # Arguments
+    ///
+    /// * `gateway_id`: Identity of the gateway.
+    /// * `remaining_bandwidth`: Bandwidth remaining at the gateway.
+    async fn update_gateway_bandwidth(
+        &self,
+        gateway_id: &str,
+        remaining_bandwidth: i64,
+    ) -> Result<(), StorageError>;
+
+    /// Retrieves the bandwidth remaining at all the gateways the credentials got spent at.
+    async fn get_gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, StorageError>;
 }
@@ -17,10 +17,32 @@ use cosmrs::tx::Hash;
 use super::utils::prepare_credential_for_spending;
 use crate::error::Error;
 
-pub const PUBLIC_ATTRIBUTES: u32 = 2;
+pub const PUBLIC_ATTRIBUTES: u32 = 3;
 pub const PRIVATE_ATTRIBUTES: u32 = 2;
 pub const TOTAL_ATTRIBUTES: u32 = PUBLIC_ATTRIBUTES + PRIVATE_ATTRIBUTES;
 
+/// Expiry dates are always rounded up to the midnight (UTC), so that the date revealed when
+/// spending the credential couldn't be used for linking it to the moment it got issued at.
+pub const EXPIRY_DATE_GRANULARITY_SECS: u64 = 24 * 60 * 60;
+
+/// For how long, in addition to the remainder of the day it got issued at, the credential remains valid.
+pub const CREDENTIAL_VALIDITY_SECS: u64 = 30 * EXPIRY_DATE_GRANULARITY_SECS;
+
+/// Returns the expiry date of a credential issued at the provided unix timestamp.
+pub fn expiry_date_for(issued_at: u64) -> u64 {
+    (issued_at / EXPIRY_DATE_GRANULARITY_SECS + 1) * EXPIRY_DATE_GRANULARITY_SECS
+        + CREDENTIAL_VALIDITY_SECS
+}
+
+/// Checks whether a credential with the provided expiry date could have been legitimately
+/// requested at `now` (unix timestamp), i.e. it hasn't expired yet and doesn't remain valid
+/// for longer than it's allowed to.
+pub fn is_valid_expiry_date(expiry_date: u64, now: u64) -> bool {
+    expiry_date % EXPIRY_DATE_GRANULARITY_SECS == 0
+        && expiry_date > now
+        && expiry_date <= expiry_date_for(now)
+}
+
 pub struct BandwidthVoucher {
     // a random secret value generated by the client used for double-spending detection
     serial_number: PrivateAttribute,
@@ -34,6 +56,10 @@ pub struct BandwidthVoucher {
     voucher_info: PublicAttribute,
     // the plain text information
     voucher_info_plain: String,
+    // the date (unix timestamp) after which the voucher can no longer be spent
+    expiry_date: PublicAttribute,
+    // the plain value of the expiry date
+    expiry_date_plain: u64,
     // the hash of the deposit transaction
     tx_hash: Hash,
     // base58 encoded private key ensuring the depositer requested these attributes
@@ -49,6 +75,7 @@ impl BandwidthVoucher {
         params: &Parameters,
         voucher_value: String,
         voucher_info: String,
+        expiry_date: u64,
         tx_hash: Hash,
         signing_key: identity::PrivateKey,
         encryption_key: encryption::PrivateKey,
@@ -59,10 +86,12 @@ impl BandwidthVoucher {
         let voucher_info_plain = voucher_info.clone();
         let voucher_value = hash_to_scalar(voucher_value.as_bytes());
         let voucher_info = hash_to_scalar(voucher_info.as_bytes());
+        let expiry_date_plain = expiry_date;
+        let expiry_date = hash_to_scalar(expiry_date_plain.to_string().as_bytes());
         let (pedersen_commitments_openings, blind_sign_request) = prepare_blind_sign(
             params,
             &[serial_number, binding_number],
-            &[voucher_value, voucher_info],
+            &[voucher_value, voucher_info, expiry_date],
         )
         .unwrap();
         BandwidthVoucher {
@@ -72,6 +101,8 @@ impl BandwidthVoucher {
             voucher_value_plain,
             voucher_info,
             voucher_info_plain,
+            expiry_date,
+            expiry_date_plain,
             tx_hash,
             signing_key,
             encryption_key,
@@ -101,6 +132,7 @@ impl BandwidthVoucher {
         ret.extend_from_slice(&(voucher_info_plain_b.len() as u64).to_be_bytes());
         ret.extend_from_slice(&(blind_sign_request_b.len() as u64).to_be_bytes());
         ret.extend_from_slice(&(self.pedersen_commitments_openings.len() as u64).to_be_bytes());
+        ret.extend_from_slice(&self.expiry_date_plain.to_be_bytes());
         ret.extend_from_slice(voucher_value_plain_b);
         ret.extend_from_slice(voucher_info_plain_b);
         ret.extend_from_slice(&blind_sign_request_b);
@@ -112,10 +144,10 @@ impl BandwidthVoucher {
     }
 
     pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        if bytes.len() < 32 * 5 + 4 * 8 {
+        if bytes.len() < 32 * 5 + 5 * 8 {
             return Err(Error::BandwidthVoucherDeserializationError(format!(
                 "Less then {} bytes needed",
-                32 * 5 + 4 * 8
+                32 * 5 + 5 * 8
             )));
         }
         let mut buff = [0u8; 32];
@@ -146,9 +178,12 @@ impl BandwidthVoucher {
         let blind_sign_request_no = u64::from_be_bytes(small_buff) as usize;
         small_buff.copy_from_slice(&bytes[5 * 32 + 3 * 8..5 * 32 + 4 * 8]);
         let pedersen_commitments_openings_no = u64::from_be_bytes(small_buff) as usize;
+        small_buff.copy_from_slice(&bytes[5 * 32 + 4 * 8..5 * 32 + 5 * 8]);
+        let expiry_date_plain = u64::from_be_bytes(small_buff);
+        let expiry_date = hash_to_scalar(expiry_date_plain.to_string().as_bytes());
 
         let total_length = 32 * 5
-            + 4 * 8
+            + 5 * 8
             + voucher_value_plain_no
             + voucher_info_plain_no
             + blind_sign_request_no
@@ -164,7 +199,7 @@ impl BandwidthVoucher {
                 "Invalid UTF8 string",
             )))
         };
-        let mut var_length_pointer = 5 * 32 + 4 * 8;
+        let mut var_length_pointer = 5 * 32 + 5 * 8;
         let voucher_value_plain = String::from_utf8(
             bytes[var_length_pointer..var_length_pointer + voucher_value_plain_no].to_vec(),
         )
@@ -198,6 +233,8 @@ impl BandwidthVoucher {
             voucher_value_plain,
             voucher_info,
             voucher_info_plain,
+            expiry_date,
+            expiry_date_plain,
             tx_hash,
             signing_key,
             encryption_key,
@@ -208,10 +245,12 @@ impl BandwidthVoucher {
 
     /// Check if the plain values correspond to the PublicAttributes
     pub fn verify_against_plain(values: &[PublicAttribute], plain_values: &[String]) -> bool {
-        values.len() == 2
-            && plain_values.len() == 2
-            && values[0] == hash_to_scalar(&plain_values[0])
-            && values[1] == hash_to_scalar(&plain_values[1])
+        values.len() == PUBLIC_ATTRIBUTES as usize
+            && plain_values.len() == PUBLIC_ATTRIBUTES as usize
+            && values
+                .iter()
+                .zip(plain_values)
+                .all(|(value, plain_value)| *value == hash_to_scalar(plain_value))
     }
 
     pub fn tx_hash(&self) -> &Hash {
@@ -219,7 +258,7 @@ impl BandwidthVoucher {
     }
 
     pub fn get_public_attributes(&self) -> Vec<PublicAttribute> {
-        vec![self.voucher_value, self.voucher_info]
+        vec![self.voucher_value, self.voucher_info, self.expiry_date]
     }
 
     pub fn encryption_key(&self) -> &encryption::PrivateKey {
@@ -238,10 +277,15 @@ impl BandwidthVoucher {
         self.voucher_value_plain.clone()
     }
 
+    pub fn expiry_date(&self) -> u64 {
+        self.expiry_date_plain
+    }
+
     pub fn get_public_attributes_plain(&self) -> Vec<String> {
         vec![
             self.voucher_value_plain.clone(),
             self.voucher_info_plain.clone(),
+            self.expiry_date_plain.to_string(),
         ]
     }
 
@@ -256,9 +300,11 @@ impl BandwidthVoucher {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_for_spending(
     voucher_value: u64,
     voucher_info: String,
+    expiry_date: u64,
     serial_number: PrivateAttribute,
     binding_number: PrivateAttribute,
     epoch_id: u64,
@@ -271,6 +317,7 @@ pub fn prepare_for_spending(
         &params,
         voucher_value,
         voucher_info,
+        expiry_date,
         serial_number,
         binding_number,
         epoch_id,
@@ -286,12 +333,13 @@ mod test {
     use rand::rngs::OsRng;
 
     fn voucher_fixture() -> BandwidthVoucher {
-        let params = Parameters::new(4).unwrap();
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
         let mut rng = OsRng;
         BandwidthVoucher::new(
             &params,
             "1234".to_string(),
             "voucher info".to_string(),
+            expiry_date_for(1_680_000_000),
             Hash::new([0; 32]),
             identity::PrivateKey::from_base58_string(
                 identity::KeyPair::new(&mut rng)
@@ -323,6 +371,11 @@ mod test {
             voucher.voucher_info_plain,
             deserialized_voucher.voucher_info_plain
         );
+        assert_eq!(voucher.expiry_date, deserialized_voucher.expiry_date);
+        assert_eq!(
+            voucher.expiry_date_plain,
+            deserialized_voucher.expiry_date_plain
+        );
         assert_eq!(voucher.tx_hash, deserialized_voucher.tx_hash);
         assert_eq!(
             voucher.signing_key.to_string(),
@@ -345,39 +398,51 @@ mod test {
     #[test]
     fn voucher_consistency() {
         let voucher = voucher_fixture();
+        let attributes = voucher.get_public_attributes();
+        let plain = voucher.get_public_attributes_plain();
+        assert!(!BandwidthVoucher::verify_against_plain(&[], &plain));
+        assert!(!BandwidthVoucher::verify_against_plain(&attributes, &[]));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &[],
-            &voucher.get_public_attributes_plain()
+            &attributes,
+            &[plain[0].clone(), String::new(), plain[2].clone()]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &voucher.get_public_attributes(),
-            &[],
+            &attributes,
+            &[String::new(), plain[1].clone(), plain[2].clone()]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &voucher.get_public_attributes(),
-            &[
-                voucher.get_public_attributes_plain()[0].clone(),
-                String::new()
-            ]
+            &attributes,
+            &[plain[0].clone(), plain[1].clone(), String::new()]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &voucher.get_public_attributes(),
-            &[
-                String::new(),
-                voucher.get_public_attributes_plain()[1].clone()
-            ]
+            &attributes[..2],
+            &plain[..2]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &[voucher.get_public_attributes()[0], Attribute::one()],
-            &voucher.get_public_attributes_plain()
+            &[attributes[0], Attribute::one(), attributes[2]],
+            &plain
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &[Attribute::one(), voucher.get_public_attributes()[1]],
-            &voucher.get_public_attributes_plain()
-        ));
-        assert!(BandwidthVoucher::verify_against_plain(
-            &voucher.get_public_attributes(),
-            &voucher.get_public_attributes_plain()
+            &[Attribute::one(), attributes[1], attributes[2]],
+            &plain
         ));
+        assert!(BandwidthVoucher::verify_against_plain(&attributes, &plain));
+    }
+
+    #[test]
+    fn expiry_dates() {
+        let day = EXPIRY_DATE_GRANULARITY_SECS;
+        let issued_at = 1_680_000_000;
+        let expiry_date = expiry_date_for(issued_at);
+        assert_eq!(expiry_date % day, 0);
+        assert!(expiry_date > issued_at + CREDENTIAL_VALIDITY_SECS);
+        assert!(expiry_date <= issued_at + CREDENTIAL_VALIDITY_SECS + day);
+
+        assert!(is_valid_expiry_date(expiry_date, issued_at));
+        // it's still valid if it's checked slightly later, e.g. after crossing the midnight
+        assert!(is_valid_expiry_date(expiry_date, issued_at + day));
+        assert!(!is_valid_expiry_date(expiry_date, expiry_date));
+        assert!(!is_valid_expiry_date(expiry_date + day, issued_at));
+        assert!(!is_valid_expiry_date(expiry_date - 1, issued_at));
     }
 }
@@ -0,0 +1,113 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::bandwidth::TOTAL_ATTRIBUTES;
+use crate::error::Error;
+use nym_coconut_interface::{Credential, VerificationKey};
+use tokio::sync::{mpsc, oneshot};
+
+/// The default maximum number of credentials verified together in a single batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 64;
+
+struct VerificationRequest {
+    credential: Credential,
+    verification_key: VerificationKey,
+    response: oneshot::Sender<bool>,
+}
+
+/// Handle to a background task verifying the submitted credentials in batches.
+///
+/// The verification doesn't wait for the batch to fill up. Instead, every batch consists of all
+/// the requests that got submitted whilst the previous one was being verified, so that under
+/// light load each credential is effectively verified on its own, while under heavy load
+/// the pairing work gets shared across many of them.
+#[derive(Clone)]
+pub struct BatchCredentialVerifier {
+    requests: mpsc::UnboundedSender<VerificationRequest>,
+}
+
+impl BatchCredentialVerifier {
+    /// Spawns the verification task. It must be called from within a tokio runtime.
+    /// The task stops once all the handles to it are dropped.
+    pub fn new(max_batch_size: usize) -> Self {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run(receiver, max_batch_size.max(1)));
+        BatchCredentialVerifier { requests }
+    }
+
+    /// Verifies the credential against the provided verification key, possibly alongside
+    /// credentials submitted by other callers.
+    /// Credentials that weren't created for the bandwidth credential attributes are rejected
+    /// straight away, so that they'd never reach (and slow down) the shared verification task.
+    pub async fn verify(
+        &self,
+        credential: Credential,
+        verification_key: VerificationKey,
+    ) -> Result<bool, Error> {
+        if *credential.n_params() != TOTAL_ATTRIBUTES {
+            return Ok(false);
+        }
+
+        let (response, receiver) = oneshot::channel();
+        self.requests
+            .send(VerificationRequest {
+                credential,
+                verification_key,
+                response,
+            })
+            .map_err(|_| Error::BatchVerifierUnavailable)?;
+
+        receiver.await.map_err(|_| Error::BatchVerifierUnavailable)
+    }
+}
+
+impl Default for BatchCredentialVerifier {
+    fn default() -> Self {
+        BatchCredentialVerifier::new(DEFAULT_MAX_BATCH_SIZE)
+    }
+}
+
+async fn run(mut requests: mpsc::UnboundedReceiver<VerificationRequest>, max_batch_size: usize) {
+    while let Some(request) = requests.recv().await {
+        let mut batch = vec![request];
+        while batch.len() < max_batch_size {
+            match requests.try_recv() {
+                Ok(request) => batch.push(request),
+                Err(_) => break,
+            }
+        }
+
+        // if the verification panicked, the requesters are going to get notified about it
+        // when their response channels get dropped
+        let _ = tokio::task::spawn_blocking(move || verify_batch(batch)).await;
+    }
+}
+
+fn verify_batch(batch: Vec<VerificationRequest>) {
+    // credentials can only be verified together if they were issued under the same key
+    let mut groups: Vec<(VerificationKey, Vec<VerificationRequest>)> = Vec::new();
+    for request in batch {
+        match groups
+            .iter_mut()
+            .find(|(verification_key, _)| verification_key == &request.verification_key)
+        {
+            Some((_, requests)) => requests.push(request),
+            None => groups.push((request.verification_key.clone(), vec![request])),
+        }
+    }
+
+    for (verification_key, requests) in groups {
+        let credentials = requests
+            .iter()
+            .map(|request| &request.credential)
+            .collect::<Vec<_>>();
+        let invalid = Credential::batch_verify(&credentials, &verification_key);
+
+        for (index, request) in requests.into_iter().enumerate() {
+            // the requester might have given up on waiting for the result
+            let _ = request
+                .response
+                .send(invalid.binary_search(&index).is_err());
+        }
+    }
+}
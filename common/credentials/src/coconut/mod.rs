@@ -2,5 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod bandwidth;
+#[cfg(feature = "batch-verifier")]
+pub mod batch_verifier;
 pub mod params;
 pub mod utils;
@@ -137,6 +137,7 @@ pub fn prepare_credential_for_spending(
     params: &Parameters,
     voucher_value: u64,
     voucher_info: String,
+    expiry_date: u64,
     serial_number: Attribute,
     binding_number: Attribute,
     epoch_id: u64,
@@ -156,6 +157,7 @@ pub fn prepare_credential_for_spending(
         theta,
         voucher_value,
         voucher_info,
+        expiry_date,
         epoch_id,
     ))
 }
@@ -41,4 +41,7 @@ pub enum Error {
 
     #[error("Could not deserialize bandwidth voucher - {0}")]
     BandwidthVoucherDeserializationError(String),
+
+    #[error("The credential batch verifier is no longer running")]
+    BatchVerifierUnavailable,
 }
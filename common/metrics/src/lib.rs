@@ -0,0 +1,123 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prometheus metrics shared by all Nym binaries.
+//!
+//! Every component defines its own metrics (usually inside a `lazy_static!` block) using the
+//! `register_*` helpers, which puts them in a single, process-wide registry. The contents of that
+//! registry can then be exposed in the Prometheus text format either through an existing http server
+//! (via [`encode_metrics`]) or through the standalone [`MetricsServer`].
+
+use lazy_static::lazy_static;
+use log::warn;
+use prometheus::core::Collector;
+use prometheus::{Encoder, Opts, Registry, TextEncoder};
+
+pub use prometheus::{IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::MetricsServer;
+
+/// Prefix attached to the names of all registered metrics.
+pub const METRICS_NAMESPACE: &str = "nym";
+
+/// Path under which the metrics are expected to be exposed.
+pub const METRICS_ROUTE: &str = "/metrics";
+
+lazy_static! {
+    static ref REGISTRY: Registry = Registry::new();
+}
+
+fn register<C: Collector + Clone + 'static>(metric: C) -> C {
+    // failing to register a metric should never bring the whole process down
+    if let Err(err) = REGISTRY.register(Box::new(metric.clone())) {
+        warn!("failed to register a metric: {err}");
+    }
+    metric
+}
+
+fn opts(name: &str, help: &str) -> Opts {
+    Opts::new(name, help).namespace(METRICS_NAMESPACE)
+}
+
+/// Creates and registers a new counter.
+///
+/// # Panics
+///
+/// Panics if the provided name or help message is not a valid Prometheus metric definition.
+pub fn register_int_counter(name: &str, help: &str) -> IntCounter {
+    register(IntCounter::with_opts(opts(name, help)).expect("invalid counter definition"))
+}
+
+/// Creates and registers a new counter partitioned by the provided labels.
+///
+/// # Panics
+///
+/// Panics if the provided name, help message or labels are not a valid Prometheus metric definition.
+pub fn register_int_counter_vec(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    register(IntCounterVec::new(opts(name, help), labels).expect("invalid counter definition"))
+}
+
+/// Creates and registers a new gauge.
+///
+/// # Panics
+///
+/// Panics if the provided name or help message is not a valid Prometheus metric definition.
+pub fn register_int_gauge(name: &str, help: &str) -> IntGauge {
+    register(IntGauge::with_opts(opts(name, help)).expect("invalid gauge definition"))
+}
+
+/// Creates and registers a new gauge partitioned by the provided labels.
+///
+/// # Panics
+///
+/// Panics if the provided name, help message or labels are not a valid Prometheus metric definition.
+pub fn register_int_gauge_vec(name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+    register(IntGaugeVec::new(opts(name, help), labels).expect("invalid gauge definition"))
+}
+
+/// Content type of the data returned by [`encode_metrics`].
+pub fn metrics_content_type() -> String {
+    TextEncoder::new().format_type().to_string()
+}
+
+/// Encodes all registered metrics using the Prometheus text format.
+pub fn encode_metrics() -> String {
+    let mut buffer = Vec::new();
+    if let Err(err) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+        warn!("failed to encode the metrics: {err}");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_metrics_are_encoded() {
+        let counter = register_int_counter("test_counter_total", "some test counter");
+        let gauge_vec = register_int_gauge_vec("test_gauge", "some test gauge", &["label"]);
+
+        counter.inc_by(42);
+        gauge_vec.with_label_values(&["foo"]).set(7);
+
+        let encoded = encode_metrics();
+        assert!(encoded.contains("# HELP nym_test_counter_total some test counter"));
+        assert!(encoded.contains("nym_test_counter_total 42"));
+        assert!(encoded.contains("nym_test_gauge{label=\"foo\"} 7"));
+    }
+
+    #[test]
+    fn duplicate_registration_does_not_panic() {
+        let first = register_int_counter("duplicate_total", "first");
+        let second = register_int_counter("duplicate_total", "second");
+
+        first.inc();
+        second.inc();
+        assert_eq!(first.get(), 1);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{encode_metrics, metrics_content_type, METRICS_ROUTE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, Server, StatusCode};
+use log::{error, info};
+use nym_task::TaskClient;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+/// Minimal http server exposing the registered metrics for binaries that don't run
+/// any other http server of their own.
+pub struct MetricsServer {
+    address: SocketAddr,
+}
+
+impl MetricsServer {
+    pub fn new(address: SocketAddr) -> Self {
+        MetricsServer { address }
+    }
+
+    async fn handle_request(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let response = if request.method() == Method::GET && request.uri().path() == METRICS_ROUTE {
+            Response::builder()
+                .header(header::CONTENT_TYPE, metrics_content_type())
+                .body(Body::from(encode_metrics()))
+        } else {
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+        };
+
+        // the response is always constructed out of valid parts
+        Ok(response.expect("failed to construct the http response"))
+    }
+
+    async fn run(self, mut shutdown: TaskClient) {
+        let make_service = make_service_fn(|_conn| async {
+            Ok::<_, Infallible>(service_fn(Self::handle_request))
+        });
+
+        let server = match Server::try_bind(&self.address) {
+            Ok(builder) => builder.serve(make_service),
+            Err(err) => {
+                // metrics are not critical for the operation of the node, so don't bring it down
+                error!(
+                    "failed to bind the metrics server to {}: {err}",
+                    self.address
+                );
+                shutdown.mark_as_success();
+                return;
+            }
+        };
+
+        info!("Serving metrics on http://{}{METRICS_ROUTE}", self.address);
+        let graceful = server.with_graceful_shutdown(async move { shutdown.recv().await });
+        if let Err(err) = graceful.await {
+            error!("the metrics server has failed: {err}");
+        }
+    }
+
+    pub fn start(self, shutdown: TaskClient) {
+        tokio::spawn(self.run(shutdown));
+    }
+}
@@ -3,15 +3,16 @@
 
 use crate::measure;
 use crate::packet_processor::error::MixProcessingError;
+use crate::packet_processor::replay_cache::{ReplayCache, ReplayTag};
 use log::*;
 use nym_sphinx_acknowledgements::surb_ack::SurbAck;
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_forwarding::packet::MixPacket;
 use nym_sphinx_framing::packet::FramedSphinxPacket;
-use nym_sphinx_params::{PacketMode, PacketSize};
+use nym_sphinx_params::{PacketMode, PacketSize, OUTFOX_AVERAGE_PACKET_DELAY};
 use nym_sphinx_types::{
-    Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, Payload, PrivateKey,
-    ProcessedPacket, SphinxPacket,
+    delays, Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, NymPacket,
+    OutfoxPacket, OutfoxProcessedPacket, Payload, PrivateKey, ProcessedPacket, SphinxPacket,
 };
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -20,6 +21,11 @@ use tracing::instrument;
 
 type ForwardAck = MixPacket;
 
+enum UnwrappedPacket {
+    Sphinx(ProcessedPacket),
+    Outfox(OutfoxProcessedPacket),
+}
+
 pub struct ProcessedFinalHop {
     pub destination: DestinationAddressBytes,
     pub forward_ack: Option<ForwardAck>,
@@ -38,16 +44,35 @@ pub enum MixProcessingResult {
 pub struct SphinxPacketProcessor {
     /// Private sphinx key of this node required to unwrap received sphinx packet.
     sphinx_key: Arc<PrivateKey>,
+
+    /// Cache of tags of recently processed packets used to reject any replayed packets.
+    replay_cache: ReplayCache,
 }
 
 impl SphinxPacketProcessor {
-    /// Creates new instance of `CachedPacketProcessor`
+    /// Creates new instance of `SphinxPacketProcessor` with the default replay cache.
     pub fn new(sphinx_key: PrivateKey) -> Self {
+        Self::new_with_replay_cache(sphinx_key, ReplayCache::default())
+    }
+
+    /// Creates new instance of `SphinxPacketProcessor` using the provided replay cache.
+    pub fn new_with_replay_cache(sphinx_key: PrivateKey, replay_cache: ReplayCache) -> Self {
         SphinxPacketProcessor {
             sphinx_key: Arc::new(sphinx_key),
+            replay_cache,
         }
     }
 
+    pub fn replay_cache(&self) -> &ReplayCache {
+        &self.replay_cache
+    }
+
+    /// Derives the replay tag of the packet, i.e. the shared secret included in its header.
+    /// It is unique for every packet at every hop and thus a repeated value implies a replay.
+    fn replay_tag(packet: &SphinxPacket) -> ReplayTag {
+        *packet.header.shared_secret.as_bytes()
+    }
+
     /// Performs a fresh sphinx unwrapping using no cache.
     #[cfg_attr(
         feature = "cpucycles",
@@ -58,14 +83,58 @@ impl SphinxPacketProcessor {
         packet: SphinxPacket,
     ) -> Result<ProcessedPacket, MixProcessingError> {
         measure!({
-            packet.process(&self.sphinx_key).map_err(|err| {
+            let replay_tag = Self::replay_tag(&packet);
+
+            // cheap check to avoid the expensive unwrapping of packets we have already seen
+            if self.replay_cache.contains(&replay_tag) {
+                self.replay_cache.report_rejected_replay();
+                return Err(MixProcessingError::ReplayedPacket);
+            }
+
+            let processed = packet.process(&self.sphinx_key).map_err(|err| {
                 debug!("Failed to unwrap Sphinx packet: {err}");
                 MixProcessingError::SphinxProcessingError(err)
-            })
+            })?;
+
+            // only remember the tags of valid packets so that the cache couldn't be filled with garbage.
+            // note that the insertion also guards against the same packet being processed concurrently
+            if !self.replay_cache.insert(replay_tag) {
+                return Err(MixProcessingError::ReplayedPacket);
+            }
+
+            Ok(processed)
         })
     }
 
-    /// Takes the received framed packet and tries to unwrap it from the sphinx encryption.
+    /// Performs a fresh outfox unwrapping of the outermost layer of the packet.
+    #[cfg_attr(
+        feature = "cpucycles",
+        instrument(skip(self, packet), fields(cpucycles))
+    )]
+    fn perform_initial_outfox_packet_processing(
+        &self,
+        packet: OutfoxPacket,
+    ) -> Result<OutfoxProcessedPacket, MixProcessingError> {
+        measure!({
+            let processed = packet
+                .decode_next_layer(&self.sphinx_key.to_bytes())
+                .map_err(|err| {
+                    debug!("Failed to unwrap Outfox packet: {err}");
+                    MixProcessingError::OutfoxProcessingError(err)
+                })?;
+
+            // unlike sphinx, the replay tag of outfox packets, i.e. the shared secret, is only
+            // known after performing the key exchange
+            if !self.replay_cache.insert(*processed.shared_secret()) {
+                self.replay_cache.report_rejected_replay();
+                return Err(MixProcessingError::ReplayedPacket);
+            }
+
+            Ok(processed)
+        })
+    }
+
+    /// Takes the received framed packet and tries to unwrap it from the sphinx (or outfox) encryption.
     #[cfg_attr(
         feature = "cpucycles",
         instrument(skip(self, received), fields(cpucycles))
@@ -73,16 +142,23 @@ impl SphinxPacketProcessor {
     fn perform_initial_unwrapping(
         &self,
         received: FramedSphinxPacket,
-    ) -> Result<ProcessedPacket, MixProcessingError> {
+    ) -> Result<UnwrappedPacket, MixProcessingError> {
         measure!({
             let packet_mode = received.packet_mode();
-            let sphinx_packet = received.into_inner();
+            let packet = received.into_inner();
 
             if packet_mode.is_old_vpn() {
                 return Err(MixProcessingError::ReceivedOldTypeVpnPacket);
             }
 
-            self.perform_initial_sphinx_packet_processing(sphinx_packet)
+            match packet {
+                NymPacket::Sphinx(packet) => self
+                    .perform_initial_sphinx_packet_processing(packet)
+                    .map(UnwrappedPacket::Sphinx),
+                NymPacket::Outfox(packet) => self
+                    .perform_initial_outfox_packet_processing(packet)
+                    .map(UnwrappedPacket::Outfox),
+            }
         })
     }
 
@@ -97,7 +173,7 @@ impl SphinxPacketProcessor {
     ) -> Result<MixProcessingResult, MixProcessingError> {
         let next_hop_address = NymNodeRoutingAddress::try_from(forward_address)?;
 
-        let mix_packet = MixPacket::new(next_hop_address, packet, packet_mode);
+        let mix_packet = MixPacket::new(next_hop_address, NymPacket::Sphinx(packet), packet_mode);
         Ok(MixProcessingResult::ForwardHop(mix_packet, Some(delay)))
     }
 
@@ -138,7 +214,8 @@ impl SphinxPacketProcessor {
                 trace!("received a normal packet!");
                 let (ack_data, message) = self.split_hop_data_into_ack_and_message(data)?;
                 let (ack_first_hop, ack_packet) = SurbAck::try_recover_first_hop_packet(&ack_data)?;
-                let forward_ack = MixPacket::new(ack_first_hop, ack_packet, packet_mode);
+                let forward_ack =
+                    MixPacket::new(ack_first_hop, NymPacket::Sphinx(ack_packet), packet_mode);
                 Ok((Some(forward_ack), message))
             }
         }
@@ -166,23 +243,61 @@ impl SphinxPacketProcessor {
         }))
     }
 
+    /// Processes the unwrapped outfox packet. The format does not include any delays, so forward
+    /// hops get a delay sampled from the same exponential distribution clients use for sphinx
+    /// packets, while the payload of the final hop is the exact message data.
+    fn process_outfox_packet(
+        &self,
+        packet: OutfoxProcessedPacket,
+        packet_size: PacketSize,
+    ) -> Result<MixProcessingResult, MixProcessingError> {
+        let routing_information = *packet.routing_information();
+
+        if !packet.is_final_hop() {
+            let next_hop_address =
+                NymNodeRoutingAddress::try_from(NodeAddressBytes::from_bytes(routing_information))?;
+            let mix_packet = MixPacket::new(
+                next_hop_address,
+                NymPacket::Outfox(packet.into_packet()),
+                PacketMode::Outfox,
+            );
+            let delay =
+                delays::generate_from_average_duration(1, OUTFOX_AVERAGE_PACKET_DELAY).pop();
+            return Ok(MixProcessingResult::ForwardHop(mix_packet, delay));
+        }
+
+        let destination = DestinationAddressBytes::from_bytes(routing_information);
+        let packet_message = packet.into_packet().into_payload();
+
+        // SURB-Acks are always sphinx packets
+        let (forward_ack, message) =
+            self.split_into_ack_and_message(packet_message, packet_size, PacketMode::Mix)?;
+
+        Ok(MixProcessingResult::FinalHop(ProcessedFinalHop {
+            destination,
+            forward_ack,
+            message,
+        }))
+    }
+
     /// Performs final processing for the unwrapped packet based on whether it was a forward hop
     /// or a final hop.
     fn perform_final_processing(
         &self,
-        packet: ProcessedPacket,
+        packet: UnwrappedPacket,
         packet_size: PacketSize,
         packet_mode: PacketMode,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         match packet {
-            ProcessedPacket::ForwardHop(packet, address, delay) => {
+            UnwrappedPacket::Sphinx(ProcessedPacket::ForwardHop(packet, address, delay)) => {
                 self.process_forward_hop(*packet, address, delay, packet_mode)
             }
             // right now there's no use for the surb_id included in the header - probably it should get removed from the
             // sphinx all together?
-            ProcessedPacket::FinalHop(destination, _, payload) => {
+            UnwrappedPacket::Sphinx(ProcessedPacket::FinalHop(destination, _, payload)) => {
                 self.process_final_hop(destination, payload, packet_size, packet_mode)
             }
+            UnwrappedPacket::Outfox(packet) => self.process_outfox_packet(packet, packet_size),
         }
     }
 
@@ -199,7 +314,7 @@ impl SphinxPacketProcessor {
             let packet_size = received.packet_size();
             let packet_mode = received.packet_mode();
 
-            // unwrap the sphinx packet and if possible and appropriate, cache keys
+            // unwrap the sphinx (or outfox) packet and if possible and appropriate, cache keys
             let processed_packet = self.perform_initial_unwrapping(received)?;
 
             // for forward packets, extract next hop and set delay (but do NOT delay here)
@@ -213,13 +328,114 @@ impl SphinxPacketProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nym_sphinx_types::builder::SphinxPacketBuilder;
     use nym_sphinx_types::crypto::keygen;
+    use nym_sphinx_types::{
+        Destination, Node, OutfoxHop, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+        NODE_ADDRESS_LENGTH,
+    };
+    use std::convert::TryInto;
+    use std::net::SocketAddr;
 
     fn fixture() -> SphinxPacketProcessor {
         let local_keys = keygen();
         SphinxPacketProcessor::new(local_keys.0)
     }
 
+    fn make_sphinx_packet_through(first_hop_key: &nym_sphinx_types::PublicKey) -> SphinxPacket {
+        let (_, node2_pk) = keygen();
+        let route = [
+            Node::new(
+                NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+                *first_hop_key,
+            ),
+            Node::new(
+                NodeAddressBytes::from_bytes([4u8; NODE_ADDRESS_LENGTH]),
+                node2_pk,
+            ),
+        ];
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::default().payload_size())
+            .build_packet(b"foomp", &route, &destination, &delays)
+            .unwrap()
+    }
+
+    #[test]
+    fn replayed_packets_are_rejected() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+
+        let packet = make_sphinx_packet_through(&public_key);
+        let packet_bytes = packet.to_bytes();
+
+        assert!(processor
+            .perform_initial_sphinx_packet_processing(packet)
+            .is_ok());
+
+        let replayed = SphinxPacket::from_bytes(&packet_bytes).unwrap();
+        assert!(matches!(
+            processor.perform_initial_sphinx_packet_processing(replayed),
+            Err(MixProcessingError::ReplayedPacket)
+        ));
+        assert_eq!(processor.replay_cache().rejected_replays(), 1);
+
+        // but a fresh packet is still processed correctly
+        let fresh = make_sphinx_packet_through(&public_key);
+        assert!(processor
+            .perform_initial_sphinx_packet_processing(fresh)
+            .is_ok());
+    }
+
+    #[test]
+    fn outfox_packets_are_forwarded_with_delay() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+
+        let next_hop: SocketAddr = "1.2.3.4:1789".parse().unwrap();
+        let next_hop_address = NymNodeRoutingAddress::from(next_hop);
+        let next_hop_bytes: NodeAddressBytes = next_hop_address.try_into().unwrap();
+        let route = [
+            OutfoxHop {
+                public_key: *public_key.as_bytes(),
+                routing_information: next_hop_bytes.as_bytes(),
+            },
+            OutfoxHop {
+                public_key: *keygen().1.as_bytes(),
+                routing_information: [3u8; DESTINATION_ADDRESS_LENGTH],
+            },
+        ];
+        let payload = vec![42u8; PacketSize::default().plaintext_size()];
+        let packet =
+            OutfoxPacket::build_for_route(&mut rand_07::rngs::OsRng, &payload, &route).unwrap();
+        let packet_bytes = packet.to_bytes();
+
+        let framed = FramedSphinxPacket::new(packet.into(), PacketMode::Outfox, false).unwrap();
+        match processor.process_received(framed).unwrap() {
+            MixProcessingResult::ForwardHop(mix_packet, delay) => {
+                assert!(delay.is_some());
+                assert_eq!(mix_packet.next_hop(), next_hop_address);
+                assert_eq!(mix_packet.packet_mode(), PacketMode::Outfox);
+                assert!(mix_packet.packet().is_outfox());
+            }
+            MixProcessingResult::FinalHop(_) => panic!("expected a forward hop"),
+        }
+
+        let replayed = NymPacket::outfox_from_bytes(&packet_bytes).unwrap();
+        let framed = FramedSphinxPacket::new(replayed, PacketMode::Outfox, false).unwrap();
+        assert!(matches!(
+            processor.process_received(framed),
+            Err(MixProcessingError::ReplayedPacket)
+        ));
+    }
+
     #[tokio::test]
     async fn splitting_hop_data_works_for_sufficiently_long_payload() {
         let processor = fixture();
@@ -0,0 +1,182 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// with ~100 bytes per entry (including the overhead of the underlying collections),
+// the cache is going to use at most ~50MB of memory
+pub const DEFAULT_REPLAY_CACHE_CAPACITY: usize = 500_000;
+pub const DEFAULT_REPLAY_CACHE_ENTRY_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub type ReplayTag = [u8; 32];
+
+/// Bounded cache of tags of all recently processed sphinx packets used for detecting replayed packets.
+/// Entries are removed once they're older than the specified ttl or, if the cache is full,
+/// in the order of their insertion.
+#[derive(Clone)]
+pub struct ReplayCache {
+    inner: Arc<ReplayCacheInner>,
+}
+
+struct ReplayCacheInner {
+    capacity: usize,
+    entry_ttl: Duration,
+    entries: Mutex<ReplayCacheEntries>,
+
+    rejected_replays: AtomicU64,
+    evicted_entries: AtomicU64,
+}
+
+#[derive(Default)]
+struct ReplayCacheEntries {
+    seen: HashSet<ReplayTag>,
+    insertion_order: VecDeque<(ReplayTag, Instant)>,
+}
+
+impl ReplayCacheEntries {
+    fn remove_expired(&mut self, now: Instant, entry_ttl: Duration) {
+        while let Some((tag, inserted)) = self.insertion_order.front() {
+            if now.saturating_duration_since(*inserted) < entry_ttl {
+                break;
+            }
+            self.seen.remove(tag);
+            self.insertion_order.pop_front();
+        }
+    }
+
+    fn evict_oldest(&mut self) -> bool {
+        if let Some((tag, _)) = self.insertion_order.pop_front() {
+            self.seen.remove(&tag);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        ReplayCache::new(
+            DEFAULT_REPLAY_CACHE_CAPACITY,
+            DEFAULT_REPLAY_CACHE_ENTRY_TTL,
+        )
+    }
+}
+
+impl ReplayCache {
+    pub fn new(capacity: usize, entry_ttl: Duration) -> Self {
+        ReplayCache {
+            inner: Arc::new(ReplayCacheInner {
+                capacity,
+                entry_ttl,
+                entries: Mutex::new(Default::default()),
+                rejected_replays: AtomicU64::new(0),
+                evicted_entries: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Checks whether the provided tag has already been seen without inserting it.
+    pub fn contains(&self, tag: &ReplayTag) -> bool {
+        let mut entries = self.inner.entries.lock().unwrap();
+        entries.remove_expired(Instant::now(), self.inner.entry_ttl);
+
+        entries.seen.contains(tag)
+    }
+
+    /// Attempts to insert the provided tag into the cache. It returns `false` if the tag was already
+    /// present, i.e. the packet was replayed.
+    pub fn insert(&self, tag: ReplayTag) -> bool {
+        self.insert_at(tag, Instant::now())
+    }
+
+    fn insert_at(&self, tag: ReplayTag, now: Instant) -> bool {
+        let mut entries = self.inner.entries.lock().unwrap();
+        entries.remove_expired(now, self.inner.entry_ttl);
+
+        if !entries.seen.insert(tag) {
+            self.inner.rejected_replays.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        entries.insertion_order.push_back((tag, now));
+
+        while entries.seen.len() > self.inner.capacity && entries.evict_oldest() {
+            self.inner.evicted_entries.fetch_add(1, Ordering::Relaxed);
+        }
+
+        true
+    }
+
+    /// Reports a replay that was detected before the tag got inserted into the cache.
+    pub(crate) fn report_rejected_replay(&self) {
+        self.inner.rejected_replays.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of packets rejected as replays since this cache got created.
+    pub fn rejected_replays(&self) -> u64 {
+        self.inner.rejected_replays.load(Ordering::Relaxed)
+    }
+
+    /// Number of entries removed before their expiry due to the cache being at its capacity.
+    pub fn evicted_entries(&self) -> u64 {
+        self.inner.evicted_entries.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.entries.lock().unwrap().seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_replayed_tags() {
+        let cache = ReplayCache::default();
+        assert!(cache.insert([1; 32]));
+        assert!(cache.insert([2; 32]));
+        assert!(!cache.insert([1; 32]));
+        assert!(cache.contains(&[2; 32]));
+        assert!(!cache.contains(&[3; 32]));
+        assert_eq!(cache.rejected_replays(), 1);
+    }
+
+    #[test]
+    fn never_exceeds_its_capacity() {
+        let cache = ReplayCache::new(3, DEFAULT_REPLAY_CACHE_ENTRY_TTL);
+        for i in 0..10 {
+            assert!(cache.insert([i; 32]));
+        }
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.evicted_entries(), 7);
+
+        // the oldest entries got evicted
+        assert!(!cache.contains(&[0; 32]));
+        assert!(cache.contains(&[9; 32]));
+    }
+
+    #[test]
+    fn removes_expired_entries() {
+        let ttl = Duration::from_secs(10);
+        let cache = ReplayCache::new(100, ttl);
+        let start = Instant::now();
+
+        assert!(cache.insert_at([1; 32], start));
+        assert!(cache.insert_at([2; 32], start + Duration::from_secs(5)));
+
+        assert!(!cache.insert_at([1; 32], start + Duration::from_secs(9)));
+
+        // the first entry has expired, but the second one is still there
+        assert!(cache.insert_at([1; 32], start + Duration::from_secs(11)));
+        assert!(!cache.insert_at([2; 32], start + Duration::from_secs(12)));
+        assert_eq!(cache.len(), 2);
+    }
+}
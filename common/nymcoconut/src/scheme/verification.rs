@@ -5,7 +5,7 @@ use core::ops::Neg;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 
-use bls12_381::{multi_miller_loop, G1Affine, G2Prepared, G2Projective, Scalar};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Prepared, G2Projective, Scalar};
 use group::{Curve, Group};
 
 use crate::error::{CoconutError, Result};
@@ -245,20 +245,27 @@ pub fn check_vk_pairing(
     true
 }
 
-pub fn verify_credential(
+/// Performs all the checks of the credential apart from the final pairing equation.
+/// If they all pass, the kappa (i.e. the blinded message including the public attributes)
+/// required for that pairing is returned.
+fn credential_kappa(
     params: &Parameters,
     verification_key: &VerificationKey,
     theta: &Theta,
     public_attributes: &[Attribute],
-) -> bool {
+) -> Option<G2Projective> {
     if public_attributes.len() + theta.pi_v.private_attributes_len()
         > verification_key.beta_g2.len()
     {
-        return false;
+        return None;
+    }
+
+    if bool::from(theta.credential.0.is_identity()) {
+        return None;
     }
 
     if !theta.verify_proof(params, verification_key) {
-        return false;
+        return None;
     }
 
     let kappa = if public_attributes.is_empty() {
@@ -278,12 +285,109 @@ pub fn verify_credential(
         theta.blinded_message + signed_public_attributes
     };
 
+    Some(kappa)
+}
+
+pub fn verify_credential(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    theta: &Theta,
+    public_attributes: &[Attribute],
+) -> bool {
+    let Some(kappa) = credential_kappa(params, verification_key, theta, public_attributes) else {
+        return false;
+    };
+
     check_bilinear_pairing(
         &theta.credential.0.to_affine(),
         &G2Prepared::from(kappa.to_affine()),
         &(theta.credential.1).to_affine(),
         params.prepared_miller_g2(),
-    ) && !bool::from(theta.credential.0.is_identity())
+    )
+}
+
+/// Credential whose pairing equation is still to be checked as part of a batch.
+struct BatchCandidate<'a> {
+    index: usize,
+    theta: &'a Theta,
+    kappa: G2Prepared,
+}
+
+// for credentials (h_i, s_i) with their respective kappa_i and random r_i, checks
+// prod_i e(r_i * h_i, kappa_i) * e(-sum_i(r_i * s_i), g2) == id
+// which holds (with overwhelming probability) only if e(h_i, kappa_i) == e(s_i, g2) for all i
+fn check_batched_pairings(params: &Parameters, candidates: &[BatchCandidate]) -> bool {
+    let randomisers = params.n_random_scalars(candidates.len());
+
+    let mut scaled_sigmas = Vec::with_capacity(candidates.len() + 1);
+    let mut combined_sigma = G1Projective::identity();
+    for (candidate, r) in candidates.iter().zip(randomisers.iter()) {
+        scaled_sigmas.push((candidate.theta.credential.0 * r).to_affine());
+        combined_sigma += candidate.theta.credential.1 * r;
+    }
+    scaled_sigmas.push(combined_sigma.neg().to_affine());
+
+    let mut terms = candidates
+        .iter()
+        .zip(scaled_sigmas.iter())
+        .map(|(candidate, sigma)| (sigma, &candidate.kappa))
+        .collect::<Vec<_>>();
+    terms.push((scaled_sigmas.last().unwrap(), params.prepared_miller_g2()));
+
+    let multi_miller = multi_miller_loop(&terms);
+    multi_miller.final_exponentiation().is_identity().into()
+}
+
+// recursively splits the batch in halves until all the invalid credentials are found
+fn find_invalid_pairings(
+    params: &Parameters,
+    candidates: &[BatchCandidate],
+    invalid: &mut Vec<usize>,
+) {
+    if candidates.is_empty() || check_batched_pairings(params, candidates) {
+        return;
+    }
+
+    if candidates.len() == 1 {
+        invalid.push(candidates[0].index);
+        return;
+    }
+
+    let (left, right) = candidates.split_at(candidates.len() / 2);
+    find_invalid_pairings(params, left, invalid);
+    find_invalid_pairings(params, right, invalid);
+}
+
+/// Verifies multiple credentials issued under the same verification key at once.
+///
+/// Rather than checking the pairing equation of every credential separately, they are combined
+/// using random linear combinations so that the (expensive) final exponentiation has to be
+/// performed only once for the whole batch. If the batch fails to verify, it is bisected
+/// in order to determine the offending credentials.
+///
+/// Returns the (sorted) indices of all the credentials that failed to verify.
+pub fn batch_verify_credentials(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    credentials: &[(&Theta, &[Attribute])],
+) -> Vec<usize> {
+    let mut invalid = Vec::new();
+    let mut candidates = Vec::with_capacity(credentials.len());
+
+    for (index, (theta, public_attributes)) in credentials.iter().enumerate() {
+        match credential_kappa(params, verification_key, theta, public_attributes) {
+            Some(kappa) => candidates.push(BatchCandidate {
+                index,
+                theta,
+                kappa: G2Prepared::from(kappa.to_affine()),
+            }),
+            None => invalid.push(index),
+        }
+    }
+
+    find_invalid_pairings(params, &candidates, &mut invalid);
+    invalid.sort_unstable();
+    invalid
 }
 
 // Used in tests only
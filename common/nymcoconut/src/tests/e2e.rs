@@ -3,8 +3,8 @@
 
 use crate::tests::helpers::tests::generate_dkg_keys;
 use crate::{
-    aggregate_verification_keys, setup, tests::helpers::*, ttp_keygen, verify_credential,
-    CoconutError, VerificationKey,
+    aggregate_verification_keys, batch_verify_credentials, setup, tests::helpers::*, ttp_keygen,
+    verify_credential, CoconutError, VerificationKey,
 };
 
 #[test]
@@ -81,3 +81,49 @@ fn dkg() -> Result<(), CoconutError> {
 
     Ok(())
 }
+
+#[test]
+fn batch_verification() -> Result<(), CoconutError> {
+    let params = setup(5)?;
+    let node_indices = vec![15u64, 248, 33521];
+
+    let coconut_keypairs = ttp_keygen(&params, 2, 3)?;
+    let verification_keys: Vec<VerificationKey> = coconut_keypairs
+        .iter()
+        .map(|keypair| keypair.verification_key())
+        .collect();
+    let verification_key = aggregate_verification_keys(&verification_keys, Some(&node_indices))?;
+
+    let mut public_attributes = Vec::new();
+    let mut thetas = Vec::new();
+    for _ in 0..5 {
+        let attributes = params.n_random_scalars(2);
+        thetas.push(theta_from_keys_and_attributes(
+            &params,
+            &coconut_keypairs,
+            &node_indices,
+            &attributes,
+        )?);
+        public_attributes.push(attributes);
+    }
+
+    let credentials = thetas
+        .iter()
+        .zip(public_attributes.iter())
+        .map(|(theta, attributes)| (theta, attributes.as_slice()))
+        .collect::<Vec<_>>();
+    assert!(batch_verify_credentials(&params, &verification_key, &credentials).is_empty());
+    assert!(batch_verify_credentials(&params, &verification_key, &[]).is_empty());
+
+    // claim wrong public attributes for some of the credentials
+    let wrong_attributes = params.n_random_scalars(2);
+    let mut credentials = credentials;
+    credentials[1].1 = &wrong_attributes;
+    credentials[4].1 = &wrong_attributes;
+    assert_eq!(
+        batch_verify_credentials(&params, &verification_key, &credentials),
+        vec![1, 4]
+    );
+
+    Ok(())
+}
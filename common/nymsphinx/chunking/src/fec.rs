@@ -0,0 +1,389 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::fragment::Fragment;
+use crate::ChunkingError;
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of shards, i.e. data and parity `Fragment`s, encoded together by a single
+/// Reed-Solomon code. The underlying field only has 256 elements, so bigger sets are interleaved
+/// into multiple stripes that are encoded independently. The value is kept slightly below
+/// the size of the field to account for uneven distribution of `Fragment`s between the stripes.
+const MAX_STRIPE_SHARDS: usize = 254;
+
+/// Ratio of parity `Fragment`s to data `Fragment`s generated for each `FragmentSet` when
+/// the message is erasure coded. For example, the ratio of 0.5 results in 5 parity `Fragment`s
+/// being created for a set of 10 data `Fragment`s, thus allowing the receiver to recover it
+/// from any 10 out of the 15 sent `Fragment`s without having to wait for any retransmissions.
+///
+/// Note that each set always gets at least a single, and at most 255, parity `Fragment`s.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct RedundancyRatio(f64);
+
+impl RedundancyRatio {
+    pub fn new(ratio: f64) -> Result<Self, ChunkingError> {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(ChunkingError::InvalidRedundancyRatio);
+        }
+        Ok(RedundancyRatio(ratio))
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Determines number of parity `Fragment`s that should be created for a set containing
+    /// the specified number of data `Fragment`s.
+    pub(crate) fn parity_fragments(&self, data_fragments: usize) -> usize {
+        let parity_fragments = (data_fragments as f64 * self.0).ceil() as usize;
+        parity_fragments.clamp(1, u8::max_value() as usize)
+    }
+}
+
+impl TryFrom<f64> for RedundancyRatio {
+    type Error = ChunkingError;
+
+    fn try_from(ratio: f64) -> Result<Self, Self::Error> {
+        RedundancyRatio::new(ratio)
+    }
+}
+
+impl From<RedundancyRatio> for f64 {
+    fn from(ratio: RedundancyRatio) -> Self {
+        ratio.0
+    }
+}
+
+/// Determines number of independently encoded stripes the set is going to be divided into.
+/// Data and parity `Fragment`s are assigned to the stripes in a round-robin fashion.
+fn number_of_stripes(data_fragments: usize, parity_fragments: usize) -> usize {
+    let required = (data_fragments + parity_fragments + MAX_STRIPE_SHARDS - 1) / MAX_STRIPE_SHARDS;
+
+    // each stripe must contain at least a single data and a single parity fragment
+    required.min(data_fragments).min(parity_fragments)
+}
+
+/// Checks whether the receiver is able to recover the whole erasure coded set with the provided
+/// number of data and parity `Fragment`s if it does not receive the `Fragment`s at the provided
+/// positions (of either kind), i.e. whether none of the set stripes lost more `Fragment`s
+/// than the number of parity `Fragment`s assigned to it.
+pub fn can_recover_set<I>(total_data: u8, total_parity: u8, lost_positions: I) -> bool
+where
+    I: IntoIterator<Item = u8>,
+{
+    if total_data == 0 || total_parity == 0 {
+        return false;
+    }
+
+    let stripes = number_of_stripes(total_data as usize, total_parity as usize);
+    let mut lost_per_stripe = vec![0; stripes];
+    for position in lost_positions {
+        // both data and parity fragments are assigned to the stripes in the round-robin fashion
+        lost_per_stripe[(position as usize).saturating_sub(1) % stripes] += 1;
+    }
+
+    lost_per_stripe
+        .into_iter()
+        .enumerate()
+        .all(|(stripe, lost)| lost <= (stripe..total_parity as usize).step_by(stripes).count())
+}
+
+/// Creates erasure coding shard out of the data `Fragment`, making sure it has the expected length.
+fn data_shard(fragment: &Fragment, shard_len: usize) -> Result<Vec<u8>, ChunkingError> {
+    let mut shard = fragment.to_data_shard();
+    if shard.len() > shard_len {
+        return Err(ChunkingError::ErasureCodingFailure {
+            reason: format!(
+                "data shard of fragment {} is longer than the parity shards",
+                fragment.fragment_identifier()
+            ),
+        });
+    }
+    shard.resize(shard_len, 0);
+    Ok(shard)
+}
+
+/// Generates parity `Fragment`s for the provided `FragmentSet` that allow the receiver to recover
+/// any of its lost data `Fragment`s as long as sufficient number of other `Fragment`s was received.
+/// Each parity `Fragment` is exactly `PARITY_FRAGMENT_HEADER_LEN - DATA_SHARD_OFFSET` bytes longer
+/// than the longest data `Fragment` of the set.
+pub(crate) fn generate_parity_fragments(
+    set: &[Fragment],
+    redundancy: RedundancyRatio,
+) -> Vec<Fragment> {
+    debug_assert!(!set.is_empty() && set.len() <= u8::max_value() as usize);
+
+    let set_id = set[0].id();
+    let data_fragments = set.len();
+    let parity_fragments = redundancy.parity_fragments(data_fragments);
+    let stripes = number_of_stripes(data_fragments, parity_fragments);
+
+    let shard_len = set
+        .iter()
+        .map(|fragment| fragment.to_data_shard().len())
+        .max()
+        .unwrap_or_default();
+
+    // the shards are created with the maximum length, so this can't fail
+    let data_shards = set
+        .iter()
+        .map(|fragment| data_shard(fragment, shard_len).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut parity_shards = vec![Vec::new(); parity_fragments];
+    for stripe in 0..stripes {
+        let mut shards = data_shards
+            .iter()
+            .skip(stripe)
+            .step_by(stripes)
+            .cloned()
+            .collect::<Vec<_>>();
+        let stripe_data = shards.len();
+        let stripe_parity = (stripe..parity_fragments).step_by(stripes).count();
+        shards.resize(stripe_data + stripe_parity, vec![0; shard_len]);
+
+        // with the bounded number of non-empty shards of equal length, the encoding can't fail
+        ReedSolomon::new(stripe_data, stripe_parity)
+            .and_then(|encoder| encoder.encode(&mut shards))
+            .expect("failed to erasure code the fragment set");
+
+        for (i, parity_shard) in shards.into_iter().skip(stripe_data).enumerate() {
+            parity_shards[stripe + i * stripes] = parity_shard;
+        }
+    }
+
+    parity_shards
+        .into_iter()
+        .enumerate()
+        .map(|(i, shard)| {
+            Fragment::try_new_parity(
+                shard,
+                set_id,
+                data_fragments as u8,
+                parity_fragments as u8,
+                (i + 1) as u8,
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Attempts to recover the missing data `Fragment`s of the set with the provided id using
+/// its received parity `Fragment`s. Both slices must be ordered by the `Fragment` positions.
+///
+/// It returns all `Fragment`s that got recovered. Note that some of them might still be missing
+/// if not enough `Fragment`s belonging to the same stripe were received.
+pub(crate) fn recover_data_fragments(
+    set_id: i32,
+    data_fragments: &[Option<Fragment>],
+    parity_fragments: &[Option<Fragment>],
+) -> Result<Vec<Fragment>, ChunkingError> {
+    let shard_len = match parity_fragments.iter().flatten().next() {
+        Some(parity_fragment) => parity_fragment.payload_size(),
+        None => return Ok(Vec::new()),
+    };
+
+    let total_data = data_fragments.len();
+    let stripes = number_of_stripes(total_data, parity_fragments.len());
+    let mut recovered = Vec::new();
+
+    for stripe in 0..stripes {
+        let stripe_data = data_fragments
+            .iter()
+            .enumerate()
+            .skip(stripe)
+            .step_by(stripes)
+            .collect::<Vec<_>>();
+        let stripe_parity = parity_fragments
+            .iter()
+            .skip(stripe)
+            .step_by(stripes)
+            .collect::<Vec<_>>();
+
+        let missing = stripe_data.iter().filter(|(_, f)| f.is_none()).count();
+        let received_parity = stripe_parity.iter().filter(|f| f.is_some()).count();
+        if missing == 0 || received_parity < missing {
+            // either there's nothing to recover or we don't have enough data to do it (yet)
+            continue;
+        }
+
+        let mut shards = Vec::with_capacity(stripe_data.len() + stripe_parity.len());
+        for (_, fragment) in &stripe_data {
+            shards.push(match fragment {
+                Some(fragment) => Some(data_shard(fragment, shard_len)?),
+                None => None,
+            });
+        }
+        shards.extend(
+            stripe_parity
+                .iter()
+                .map(|fragment| fragment.as_ref().map(|f| f.payload().to_vec())),
+        );
+
+        ReedSolomon::new(stripe_data.len(), stripe_parity.len())
+            .and_then(|decoder| decoder.reconstruct_data(&mut shards))
+            .map_err(|err| ChunkingError::ErasureCodingFailure {
+                reason: format!("{err:?}"),
+            })?;
+
+        for ((i, fragment), shard) in stripe_data.into_iter().zip(shards) {
+            if let (None, Some(shard)) = (fragment, shard) {
+                recovered.push(Fragment::try_from_data_shard(
+                    &shard,
+                    set_id,
+                    total_data as u8,
+                    (i + 1) as u8,
+                )?);
+            }
+        }
+    }
+
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fragment::{erasure_coded_plaintext_size, unlinked_fragment_payload_max_len};
+    use crate::set::split_into_sets;
+    use rand::seq::SliceRandom;
+    use rand::{thread_rng, RngCore};
+
+    // just some arbitrary value to use in tests
+    const AVAILABLE_PLAINTEXT_SIZE: usize = 1024;
+
+    fn full_set_message(fragments: usize) -> Vec<u8> {
+        let mut message =
+            vec![0u8; unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE) * fragments];
+        thread_rng().fill_bytes(&mut message);
+        message
+    }
+
+    #[test]
+    fn redundancy_ratio_must_be_positive() {
+        assert!(RedundancyRatio::new(0.0).is_err());
+        assert!(RedundancyRatio::new(-1.0).is_err());
+        assert!(RedundancyRatio::new(f64::NAN).is_err());
+        assert!(RedundancyRatio::new(f64::INFINITY).is_err());
+        assert!(RedundancyRatio::new(0.1).is_ok());
+    }
+
+    #[test]
+    fn number_of_parity_fragments_is_bounded() {
+        let ratio = RedundancyRatio::new(0.01).unwrap();
+        assert_eq!(ratio.parity_fragments(10), 1);
+
+        let ratio = RedundancyRatio::new(0.5).unwrap();
+        assert_eq!(ratio.parity_fragments(10), 5);
+        assert_eq!(ratio.parity_fragments(11), 6);
+
+        let ratio = RedundancyRatio::new(10.0).unwrap();
+        assert_eq!(ratio.parity_fragments(255), 255);
+    }
+
+    #[test]
+    fn stripes_never_exceed_the_field_size() {
+        for data in 1..=255 {
+            for parity in 1..=255 {
+                let stripes = number_of_stripes(data, parity);
+                let largest_stripe =
+                    (data + stripes - 1) / stripes + (parity + stripes - 1) / stripes;
+                assert!(largest_stripe <= 256);
+            }
+        }
+    }
+
+    #[test]
+    fn parity_fragments_fit_in_the_same_packets_as_data_fragments() {
+        let mut rng = thread_rng();
+        let data_plaintext_size = erasure_coded_plaintext_size(AVAILABLE_PLAINTEXT_SIZE);
+        let mut message = vec![0u8; unlinked_fragment_payload_max_len(data_plaintext_size) * 10];
+        rng.fill_bytes(&mut message);
+        let set = split_into_sets(&mut rng, &message, data_plaintext_size).remove(0);
+
+        let parity = generate_parity_fragments(&set, RedundancyRatio::new(0.3).unwrap());
+        assert_eq!(parity.len(), 3);
+        for fragment in parity {
+            assert!(fragment.is_parity());
+            assert_eq!(fragment.id(), set[0].id());
+            assert_eq!(fragment.total_data_fragments(), 10);
+            assert_eq!(fragment.serialized_size(), AVAILABLE_PLAINTEXT_SIZE);
+        }
+    }
+
+    #[test]
+    fn missing_fragments_can_be_recovered_from_any_sufficiently_large_subset() {
+        let mut rng = thread_rng();
+        for (set_size, ratio) in [(1, 1.0), (10, 0.5), (100, 0.2), (255, 1.0)] {
+            let message = full_set_message(set_size);
+            let set = split_into_sets(&mut rng, &message, AVAILABLE_PLAINTEXT_SIZE).remove(0);
+            let parity = generate_parity_fragments(&set, RedundancyRatio::new(ratio).unwrap());
+
+            // lose fragments at random, but no more than there are parity fragments in each stripe
+            let stripes = number_of_stripes(set.len(), parity.len());
+            let mut data = set.iter().cloned().map(Some).collect::<Vec<_>>();
+            let mut parity = parity.into_iter().map(Some).collect::<Vec<_>>();
+            for stripe in 0..stripes {
+                let mut positions = (stripe..set.len())
+                    .step_by(stripes)
+                    .chain(
+                        (stripe..parity.len())
+                            .step_by(stripes)
+                            .map(|i| set.len() + i),
+                    )
+                    .collect::<Vec<_>>();
+                positions.shuffle(&mut rng);
+                let stripe_parity = (stripe..parity.len()).step_by(stripes).count();
+                for position in positions.into_iter().take(stripe_parity) {
+                    if position < set.len() {
+                        data[position] = None;
+                    } else {
+                        parity[position - set.len()] = None;
+                    }
+                }
+            }
+            let lost = data.iter().filter(|f| f.is_none()).count();
+
+            let recovered = recover_data_fragments(set[0].id(), &data, &parity).unwrap();
+            assert_eq!(recovered.len(), lost);
+            for fragment in recovered {
+                assert_eq!(
+                    Some(&fragment),
+                    set.get(fragment.current_fragment() as usize - 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn set_is_recoverable_while_each_stripe_has_enough_parity_fragments() {
+        assert!(can_recover_set(10, 5, []));
+        assert!(can_recover_set(10, 5, [1, 2, 3, 4, 5]));
+        assert!(!can_recover_set(10, 5, [1, 2, 3, 4, 5, 6]));
+
+        // 255 data and 255 parity fragments are split into 3 stripes with 85 parity fragments each,
+        // with the first stripe consisting of data and parity fragments at positions 1, 4, 7, ...
+        let first_stripe_data = (0..85).map(|i| i * 3 + 1);
+        assert!(can_recover_set(255, 255, first_stripe_data.clone()));
+        assert!(!can_recover_set(255, 255, first_stripe_data.chain([1])));
+    }
+
+    #[test]
+    fn nothing_is_recovered_without_enough_fragments() {
+        let mut rng = thread_rng();
+        let message = full_set_message(10);
+        let set = split_into_sets(&mut rng, &message, AVAILABLE_PLAINTEXT_SIZE).remove(0);
+        let parity = generate_parity_fragments(&set, RedundancyRatio::new(0.2).unwrap());
+
+        let mut data = set.into_iter().map(Some).collect::<Vec<_>>();
+        data[0] = None;
+        data[1] = None;
+        data[2] = None;
+        let parity = parity.into_iter().map(Some).collect::<Vec<_>>();
+
+        let recovered = recover_data_fragments(42, &data, &parity).unwrap();
+        assert!(recovered.is_empty());
+    }
+}
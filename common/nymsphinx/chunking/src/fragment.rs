@@ -15,10 +15,8 @@ use std::fmt::{self, Debug, Formatter};
 // need to use the tail byte to indicate lack of linking as it can be implied from the fragment
 // position.
 
-// TODO for later: with the removal of 'unfragmented' fragments, the first bit of each header
-// is completely useless, we should then think how to make the set_id become u32 instead of i32.
-// (the current limitation for making the seemingly trivial change is "linked id" which
-// has to have same amount of space available and right now it only has 31 bits available)
+// Note: with the removal of 'unfragmented' fragments, the first bit of each header is now used
+// to distinguish data fragments from parity fragments of erasure coded messages.
 
 /// When the underlying message has to be split into multiple Fragments, but still manages to fit
 /// into a single `FragmentSet`, each `FragmentHeader` needs to hold additional information to allow
@@ -34,6 +32,27 @@ pub const UNLINKED_FRAGMENTED_HEADER_LEN: usize = 7;
 /// `Fragment` in a `FragmentSet`.
 pub const LINKED_FRAGMENTED_HEADER_LEN: usize = 10;
 
+/// Parity `Fragment`s, created when the message is erasure coded, use the otherwise unused
+/// fragmentation flag to distinguish themselves from the data `Fragment`s. Their header consists
+/// of 4 bytes for the id of the set they protect, 1 byte to represent total number of data
+/// fragments of the set, 1 byte to represent total number of parity fragments of the set
+/// and 1 byte to represent position of the current parity fragment.
+pub const PARITY_FRAGMENT_HEADER_LEN: usize = 7;
+
+/// Number of leading bytes of every data `Fragment` header that are fully determined by the id of
+/// its set, the total number of fragments and its position, i.e. everything apart from the linking
+/// information. They are omitted from the shards used for erasure coding so that the parity
+/// `Fragment`s would fit in the same sphinx packets as the data `Fragment`s.
+pub(crate) const DATA_SHARD_OFFSET: usize = 6;
+
+/// Parity `Fragment` header is longer than the part of the data `Fragment` headers omitted from
+/// the erasure coding shards. Hence, when the message is erasure coded, its data `Fragment`s
+/// must leave that many bytes of each sphinx packet unused so that the parity `Fragment`s
+/// would still fit in the same packets.
+pub const fn erasure_coded_plaintext_size(max_plaintext_size: usize) -> usize {
+    max_plaintext_size - (PARITY_FRAGMENT_HEADER_LEN - DATA_SHARD_OFFSET)
+}
+
 /// Maximum size of payload of each fragment is always the maximum amount of plaintext data
 /// we can put into a sphinx packet minus length of respective fragment header.
 pub const fn unlinked_fragment_payload_max_len(max_plaintext_size: usize) -> usize {
@@ -52,6 +71,7 @@ pub const fn linked_fragment_payload_max_len(max_plaintext_size: usize) -> usize
 pub const COVER_FRAG_ID: FragmentIdentifier = FragmentIdentifier {
     set_id: 0,
     fragment_position: 0,
+    is_parity: false,
 };
 
 /// Identifier to uniquely identify a fragment. It represents 31bit ID of given `FragmentSet`
@@ -62,6 +82,10 @@ pub const COVER_FRAG_ID: FragmentIdentifier = FragmentIdentifier {
 pub struct FragmentIdentifier {
     set_id: i32,
     fragment_position: u8,
+
+    /// Indicates whether the identifier refers to one of the parity `Fragment`s of the set
+    /// rather than to one of its data `Fragment`s.
+    is_parity: bool,
 }
 
 impl fmt::Display for FragmentIdentifier {
@@ -70,15 +94,41 @@ impl fmt::Display for FragmentIdentifier {
             f,
             "Fragment Identifier: id: {} position: {}",
             self.set_id, self.fragment_position
-        )
+        )?;
+        if self.is_parity {
+            write!(f, " (parity)")?;
+        }
+        Ok(())
     }
 }
 
 impl FragmentIdentifier {
+    /// Extracts id of the `FragmentSet` to which the identified `Fragment` belongs.
+    pub fn set_id(&self) -> i32 {
+        self.set_id
+    }
+
+    /// Extracts position of the identified `Fragment` among either the data or the parity
+    /// `Fragment`s of its set.
+    pub fn fragment_position(&self) -> u8 {
+        self.fragment_position
+    }
+
+    /// Indicates whether the identified `Fragment` is one of the parity `Fragment`s of its set.
+    pub fn is_parity(&self) -> bool {
+        self.is_parity
+    }
+
     pub fn to_bytes(self) -> SerializedFragmentIdentifier {
         debug_assert_eq!(FRAG_ID_LEN, 5);
 
-        let set_id_bytes = self.set_id.to_be_bytes();
+        // the otherwise unused sign bit is used to mark identifiers of parity fragments
+        let flagged_set_id = if self.is_parity {
+            self.set_id | (1 << 31)
+        } else {
+            self.set_id
+        };
+        let set_id_bytes = flagged_set_id.to_be_bytes();
         [
             set_id_bytes[0],
             set_id_bytes[1],
@@ -91,15 +141,21 @@ impl FragmentIdentifier {
     pub fn try_from_bytes(b: SerializedFragmentIdentifier) -> Result<Self, ChunkingError> {
         debug_assert_eq!(FRAG_ID_LEN, 5);
 
-        let set_id = i32::from_be_bytes([b[0], b[1], b[2], b[3]]);
-        // set_id == 0 is valid for COVER_FRAG_ID and replies
-        if set_id < 0 {
-            return Err(ChunkingError::MalformedFragmentIdentifier { received: set_id });
+        let flagged_set_id = i32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        let is_parity = flagged_set_id < 0;
+        let set_id = flagged_set_id & !(1 << 31);
+
+        // set_id == 0 is valid for COVER_FRAG_ID and replies, but never for parity fragments
+        if is_parity && set_id == 0 {
+            return Err(ChunkingError::MalformedFragmentIdentifier {
+                received: flagged_set_id,
+            });
         }
 
         Ok(FragmentIdentifier {
             set_id,
             fragment_position: b[4],
+            is_parity,
         })
     }
 }
@@ -191,6 +247,54 @@ impl Fragment {
         })
     }
 
+    /// Creates a parity `Fragment` holding erasure coded data of the `FragmentSet` with the
+    /// provided id.
+    pub(crate) fn try_new_parity(
+        payload: Vec<u8>,
+        id: i32,
+        total_data_fragments: u8,
+        total_parity_fragments: u8,
+        current_parity_fragment: u8,
+    ) -> Result<Self, ChunkingError> {
+        let header = FragmentHeader::try_new_parity(
+            id,
+            total_data_fragments,
+            total_parity_fragments,
+            current_parity_fragment,
+        )?;
+
+        Ok(Fragment { header, payload })
+    }
+
+    /// Recovers data `Fragment` from its erasure coding shard (as created with `to_data_shard`)
+    /// and the metadata describing its position in the `FragmentSet`.
+    /// Note that if the original `Fragment` was shorter than the shard, its payload is going
+    /// to be zero-padded.
+    pub(crate) fn try_from_data_shard(
+        shard: &[u8],
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+    ) -> Result<Self, ChunkingError> {
+        // the linking information is part of the shard, so we only need the common header prefix
+        let mut bytes =
+            FragmentHeader::try_new(id, total_fragments, current_fragment, None, None)?.to_bytes();
+        bytes.truncate(DATA_SHARD_OFFSET);
+        bytes.extend_from_slice(shard);
+
+        Self::try_from_bytes(&bytes)
+    }
+
+    /// Serializes this data `Fragment` without the part of its header that can be derived
+    /// from its position in the `FragmentSet`. The result is used as the erasure coding shard.
+    pub(crate) fn to_data_shard(&self) -> Vec<u8> {
+        debug_assert!(!self.is_parity());
+
+        let mut shard = self.header.to_bytes().split_off(DATA_SHARD_OFFSET);
+        shard.extend_from_slice(&self.payload);
+        shard
+    }
+
     /// based on the size of the embedded data, determines which predefined `PacketSize`
     /// was used for construction of this `Fragment`
     pub fn serialized_size(&self) -> usize {
@@ -213,21 +317,37 @@ impl Fragment {
         FragmentIdentifier {
             set_id: self.header.id,
             fragment_position: self.header.current_fragment,
+            is_parity: self.header.is_parity(),
         }
     }
 
+    /// Checks whether this is a parity `Fragment` holding erasure coded data of its set,
+    /// rather than part of the original message.
+    pub fn is_parity(&self) -> bool {
+        self.header.is_parity()
+    }
+
+    /// Extracts total number of data `Fragment`s of the set protected by this parity `Fragment`.
+    /// For data `Fragment`s it is the same as `total_fragments`.
+    pub fn total_data_fragments(&self) -> u8 {
+        self.header
+            .protected_fragments
+            .unwrap_or(self.header.total_fragments)
+    }
+
     /// Gets the size of payload contained in this `Fragment`.
     pub fn payload_size(&self) -> usize {
         self.payload.len()
     }
 
-    /// Extracts id of this `Fragment`.
+    /// Extracts id of this `Fragment`. Note that parity `Fragment`s share the id of
+    /// the set they protect.
     pub fn id(&self) -> i32 {
         self.header.id
     }
 
     /// Extracts total number of fragments associated with this particular `Fragment` (belonging to
-    /// the same `FragmentSet`).
+    /// the same `FragmentSet`). For parity `Fragment`s it is the total number of parity fragments.
     pub fn total_fragments(&self) -> u8 {
         self.header.total_fragments
     }
@@ -253,11 +373,22 @@ impl Fragment {
         self.payload
     }
 
+    /// Gets reference to the payload associated with this `Fragment`.
+    pub(crate) fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
     /// Tries to recover `Fragment` from slice of bytes extracted from received sphinx packet.
     /// It can fail if payload would not fully fit in a single `Fragment` or some of the metadata
     /// is malformed or self-contradictory, for example if current_fragment > total_fragments.
     pub fn try_from_bytes(b: &[u8]) -> Result<Self, ChunkingError> {
-        let (header, n) = FragmentHeader::try_from_bytes(b)?;
+        // parity fragments are distinguished by having the fragmentation flag cleared
+        let is_parity = b.first().map(|byte| byte >> 7 == 0).unwrap_or_default();
+        let (header, n) = if is_parity {
+            FragmentHeader::try_parity_from_bytes(b)?
+        } else {
+            FragmentHeader::try_from_bytes(b)?
+        };
 
         // there's no sane way to decide if payload has correct range anymore as
         // it's no longer fixed
@@ -297,6 +428,10 @@ impl Fragment {
 /// there is 7 bytes of overhead inside each sphinx packet sent
 /// and for the longest messages, without upper bound, there is usually also only 7 bytes
 /// of overhead apart from first and last fragments in each set that instead have 10 bytes of overhead.
+///
+/// Finally, if the message was erasure coded, each set is accompanied by parity `Fragment`s
+/// with the following 7 byte header:
+/// '0'bit || 31-bit ID || 1-byte TF || 1-byte total parity fragments || 1-byte current parity fragment
 #[derive(PartialEq, Clone, Debug)]
 pub(crate) struct FragmentHeader {
     /// ID associated with `FragmentSet` to which this particular `Fragment` belongs.
@@ -321,6 +456,11 @@ pub(crate) struct FragmentHeader {
     /// Optional ID of next `FragmentSet` into which the original message was split.
     /// Note, this option is only valid of `current_fragment == total_fragments == u8::max_value()`
     next_fragments_set_id: Option<i32>,
+
+    /// Total number of data `Fragment`s in the `FragmentSet` protected by the parity `Fragment`.
+    /// It is only set for the headers of parity `Fragment`s, in which case `total_fragments`
+    /// and `current_fragment` refer to the parity fragments of the set identified by `id`.
+    protected_fragments: Option<u8>,
 }
 
 impl FragmentHeader {
@@ -363,9 +503,58 @@ impl FragmentHeader {
             current_fragment,
             previous_fragments_set_id,
             next_fragments_set_id,
+            protected_fragments: None,
         })
     }
 
+    /// Checks whether this is a header of a parity `Fragment`.
+    fn is_parity(&self) -> bool {
+        self.protected_fragments.is_some()
+    }
+
+    /// Tries to create a new `FragmentHeader` of a parity `Fragment` protecting set with
+    /// the provided id.
+    fn try_new_parity(
+        id: i32,
+        total_data_fragments: u8,
+        total_parity_fragments: u8,
+        current_parity_fragment: u8,
+    ) -> Result<Self, ChunkingError> {
+        if total_data_fragments == 0 {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+        let mut header = Self::try_new(
+            id,
+            total_parity_fragments,
+            current_parity_fragment,
+            None,
+            None,
+        )?;
+        header.protected_fragments = Some(total_data_fragments);
+        Ok(header)
+    }
+
+    /// Tries to recover parity `FragmentHeader` from slice of bytes extracted from received
+    /// sphinx packet. If successful, returns `Self` and number of bytes used.
+    fn try_parity_from_bytes(b: &[u8]) -> Result<(Self, usize), ChunkingError> {
+        if b.len() < PARITY_FRAGMENT_HEADER_LEN {
+            return Err(ChunkingError::TooShortFragmentHeader {
+                received: b.len(),
+                expected: PARITY_FRAGMENT_HEADER_LEN,
+            });
+        }
+        let id = i32::from_be_bytes(b[0..4].try_into().unwrap());
+        // sanity check for the (cleared) fragmentation flag
+        if ((id >> 31) & 1) != 0 {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+
+        Ok((
+            Self::try_new_parity(id, b[4], b[5], b[6])?,
+            PARITY_FRAGMENT_HEADER_LEN,
+        ))
+    }
+
     /// Tries to recover `FragmentHeader` from slice of bytes extracted from received sphinx packet.
     /// If successful, returns `Self` and number of bytes used, as those can differ based on the
     /// type of header (unlinked or linked).
@@ -439,6 +628,17 @@ impl FragmentHeader {
 
     /// Marshal this `FragmentHeader` into vector of bytes which can be put into a sphinx packet.
     fn to_bytes(&self) -> Vec<u8> {
+        if let Some(protected_fragments) = self.protected_fragments {
+            return self
+                .id
+                .to_be_bytes()
+                .into_iter()
+                .chain(std::iter::once(protected_fragments))
+                .chain(std::iter::once(self.total_fragments))
+                .chain(std::iter::once(self.current_fragment))
+                .collect();
+        }
+
         let frag_id = self.id | (1 << 31);
         let frag_id_bytes = frag_id.to_be_bytes();
         let bytes_prefix_iter = frag_id_bytes
@@ -913,6 +1113,68 @@ mod fragment_tests {
         )
         .is_err());
     }
+
+    #[test]
+    fn parity_fragment_can_be_converted_to_and_from_bytes() {
+        let mut rng = thread_rng();
+
+        let mut shard = vec![0u8; max_plaintext_size() - PARITY_FRAGMENT_HEADER_LEN];
+        rng.fill_bytes(&mut shard);
+
+        let parity_fragment = Fragment::try_new_parity(shard, 12345, 20, 10, 3).unwrap();
+        assert!(parity_fragment.is_parity());
+        assert_eq!(20, parity_fragment.total_data_fragments());
+        assert_eq!(max_plaintext_size(), parity_fragment.serialized_size());
+
+        let packet_bytes = parity_fragment.clone().into_bytes();
+        assert_eq!(
+            parity_fragment,
+            Fragment::try_from_bytes(&packet_bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn data_fragment_can_be_recovered_from_its_shard() {
+        let mut rng = thread_rng();
+
+        let mut msg = vec![0u8; linked_fragment_payload_max_len(max_plaintext_size())];
+        rng.fill_bytes(&mut msg);
+
+        let unlinked =
+            Fragment::try_new(&msg[..10], 12345, 10, 10, None, None, max_plaintext_size()).unwrap();
+        let pre_linked =
+            Fragment::try_new(&msg, 12345, 10, 1, Some(1234), None, max_plaintext_size()).unwrap();
+
+        for fragment in [unlinked, pre_linked] {
+            let shard = fragment.to_data_shard();
+            let recovered = Fragment::try_from_data_shard(
+                &shard,
+                fragment.id(),
+                fragment.total_fragments(),
+                fragment.current_fragment(),
+            )
+            .unwrap();
+            assert_eq!(fragment, recovered);
+        }
+    }
+
+    #[test]
+    fn fragment_identifier_can_be_converted_to_and_from_bytes() {
+        let data_fragment =
+            Fragment::try_new(&[1, 2, 3], 12345, 10, 10, None, None, max_plaintext_size()).unwrap();
+        let parity_fragment = Fragment::try_new_parity(vec![1, 2, 3], 12345, 10, 5, 5).unwrap();
+
+        let data_id = data_fragment.fragment_identifier();
+        let parity_id = parity_fragment.fragment_identifier();
+        assert_ne!(data_id, parity_id);
+
+        for id in [data_id, parity_id, COVER_FRAG_ID] {
+            assert_eq!(
+                id,
+                FragmentIdentifier::try_from_bytes(id.to_bytes()).unwrap()
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1142,4 +1404,38 @@ mod fragment_header {
             assert_eq!(LINKED_FRAGMENTED_HEADER_LEN, bytes_used);
         }
     }
+
+    #[cfg(test)]
+    mod parity_payload {
+        use super::*;
+
+        #[test]
+        fn can_be_converted_to_and_from_bytes() {
+            let parity_header = FragmentHeader::try_new_parity(12345, 20, 10, 5).unwrap();
+            let header_bytes = parity_header.to_bytes();
+            assert_eq!(PARITY_FRAGMENT_HEADER_LEN, header_bytes.len());
+
+            let (recovered_header, bytes_used) =
+                FragmentHeader::try_parity_from_bytes(&header_bytes).unwrap();
+            assert_eq!(parity_header, recovered_header);
+            assert_eq!(PARITY_FRAGMENT_HEADER_LEN, bytes_used);
+        }
+
+        #[test]
+        fn retrieval_from_bytes_fail_for_set_fragmentation_flag() {
+            let header_bytes = FragmentHeader::try_new(12345, 10, 5, None, None)
+                .unwrap()
+                .to_bytes();
+            assert!(FragmentHeader::try_parity_from_bytes(&header_bytes).is_err());
+        }
+
+        #[test]
+        fn creation_of_header_fails_for_invalid_positions() {
+            assert!(FragmentHeader::try_new_parity(12345, 20, 10, 11).is_err());
+            assert!(FragmentHeader::try_new_parity(12345, 20, 10, 0).is_err());
+            assert!(FragmentHeader::try_new_parity(12345, 20, 0, 0).is_err());
+            assert!(FragmentHeader::try_new_parity(12345, 0, 10, 5).is_err());
+            assert!(FragmentHeader::try_new_parity(0, 20, 10, 5).is_err());
+        }
+    }
 }
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::fragment::{linked_fragment_payload_max_len, unlinked_fragment_payload_max_len};
-pub use set::split_into_sets;
+pub use fec::RedundancyRatio;
+pub use set::{split_into_sets, split_into_sets_with_redundancy};
 use thiserror::Error;
 
 pub const MIN_PADDING_OVERHEAD: usize = 1;
@@ -18,6 +19,7 @@ pub const MIN_PADDING_OVERHEAD: usize = 1;
 // they should definitely be revisited.
 // For instance there are not tests for the cases when we are padding the message
 
+pub mod fec;
 pub mod fragment;
 pub mod reconstruction;
 pub mod set;
@@ -47,6 +49,10 @@ pub mod set;
 ///
 /// Both of those concepts as well as their structures, i.e. `Set` and `Fragment`
 /// are further explained in the respective files.
+///
+/// Optionally, each `Set` can also be erasure coded, in which case it is accompanied by
+/// additional parity `Fragment`s allowing the receiver to recover the `Set` without having
+/// to wait for retransmission of any lost `Fragment`s. This is further explained in `fec.rs` file.
 
 #[derive(PartialEq, Eq, Debug, Error)]
 pub enum ChunkingError {
@@ -68,6 +74,12 @@ pub enum ChunkingError {
 
     #[error("Received fragment identifier ({received}) is not a valid value!")]
     MalformedFragmentIdentifier { received: i32 },
+
+    #[error("The redundancy ratio must be a positive, finite, number")]
+    InvalidRedundancyRatio,
+
+    #[error("Failed to erasure code the fragment set: {reason}")]
+    ErasureCodingFailure { reason: String },
 }
 
 /// Returns number of fragments the message will be split to as well as number of available
@@ -1,5 +1,6 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
+use crate::fec::recover_data_fragments;
 use crate::fragment::Fragment;
 use crate::ChunkingError;
 use log::*;
@@ -147,6 +148,10 @@ pub struct MessageReconstructor {
     // maximum sized sets but without one of required fragments. All of the received
     // data will be kept on the heap indefinitely in the current implementation.
     reconstructed_sets: HashMap<i32, ReconstructionBuffer>,
+
+    /// Parity `Fragment`s received for the erasure coded sets that are not yet complete,
+    /// ordered by their positions.
+    parity_fragments: HashMap<i32, Vec<Option<Fragment>>>,
 }
 
 impl MessageReconstructor {
@@ -237,6 +242,7 @@ impl MessageReconstructor {
     /// Note, before you call this method, you *must* ensure set was fully received
     fn extract_set_payload(&mut self, set_id: i32) -> Vec<u8> {
         debug_assert!(self.is_set_fully_received(set_id));
+        self.parity_fragments.remove(&set_id);
         self.reconstructed_sets
             .remove(&set_id)
             .unwrap()
@@ -263,20 +269,99 @@ impl MessageReconstructor {
         (message_content, set_id_sequence)
     }
 
+    /// Stores received parity `Fragment` so that it could be used for recovering any missing
+    /// data `Fragment`s of its set.
+    fn insert_parity_fragment(&mut self, fragment: Fragment) {
+        let set_id = fragment.id();
+        if self.is_set_fully_received(set_id) {
+            // there's nothing left to recover
+            return;
+        }
+
+        let total_parity = fragment.total_fragments() as usize;
+        let parity = self
+            .parity_fragments
+            .entry(set_id)
+            .or_insert_with(|| vec![None; total_parity]);
+
+        if parity.len() != total_parity {
+            warn!(
+                "received parity fragment with inconsistent number of total parity fragments (set id: {set_id})"
+            );
+            return;
+        }
+
+        let fragment_index = fragment.current_fragment() as usize - 1;
+        if parity[fragment_index].is_some() {
+            warn!(
+                "duplicate parity fragment received! - frag - {} (set id: {set_id})",
+                fragment.current_fragment(),
+            );
+        }
+        parity[fragment_index] = Some(fragment);
+    }
+
+    /// Attempts to recover missing data `Fragment`s of the set of given `id` using its
+    /// already received parity `Fragment`s.
+    fn try_recover_set(&mut self, id: i32) {
+        let (buf, parity) = match (
+            self.reconstructed_sets.get_mut(&id),
+            self.parity_fragments.get(&id),
+        ) {
+            (Some(buf), Some(parity)) if !buf.is_complete => (buf, parity),
+            _ => return,
+        };
+
+        match recover_data_fragments(id, &buf.fragments, parity) {
+            Ok(recovered) => {
+                if !recovered.is_empty() {
+                    debug!(
+                        "recovered {} fragment(s) of set {id} using parity data",
+                        recovered.len()
+                    );
+                }
+                for fragment in recovered {
+                    buf.insert_fragment(fragment)
+                }
+            }
+            Err(err) => warn!("failed to recover fragments of set {id} - {err}"),
+        }
+
+        if buf.is_complete {
+            self.parity_fragments.remove(&id);
+        }
+    }
+
     /// Given recovered `Fragment`, tries to insert it into an appropriate `ReconstructionBuffer`.
-    /// If a buffer does not exist, a new instance is created.
+    /// If a buffer does not exist, a new instance is created. Note that parity `Fragment`s
+    /// also create the buffer as they carry the total number of data `Fragment`s of their set.
+    /// If the `Fragment` is a parity `Fragment` (or the set has already received some of them),
+    /// attempts to recover any missing `Fragment`s of the set.
     /// If it was last remaining `Fragment` for the original message, the message is reconstructed
     /// and returned alongside all (if applicable) set ids used in the message.
     pub fn insert_new_fragment(&mut self, fragment: Fragment) -> Option<ReconstructedMessage> {
         let set_id = fragment.id();
-        let set_len = fragment.total_fragments();
+        let set_len = fragment.total_data_fragments();
 
         let buf = self
             .reconstructed_sets
             .entry(set_id)
             .or_insert_with(|| ReconstructionBuffer::new(set_len));
 
-        buf.insert_fragment(fragment);
+        if buf.fragments.len() != set_len as usize {
+            warn!(
+                "received fragment with inconsistent number of total fragments (set id: {set_id})"
+            );
+            return None;
+        }
+
+        if fragment.is_parity() {
+            self.insert_parity_fragment(fragment);
+        } else {
+            buf.insert_fragment(fragment);
+        }
+
+        self.try_recover_set(set_id);
         if self.is_message_fully_received(set_id) {
             Some(self.reconstruct_message(set_id))
         } else {
@@ -1847,4 +1932,145 @@ mod message_reconstruction {
             }
         }
     }
+
+    #[cfg(test)]
+    mod erasure_coded_split {
+        use super::*;
+        use crate::fragment::{
+            erasure_coded_plaintext_size, linked_fragment_payload_max_len,
+            unlinked_fragment_payload_max_len,
+        };
+        use crate::set::max_one_way_linked_set_payload_length;
+        use crate::RedundancyRatio;
+
+        const DATA_PLAINTEXT_SIZE: usize = erasure_coded_plaintext_size(AVAILABLE_PLAINTEXT_SIZE);
+
+        fn reconstruct(fragments: Vec<Fragment>) -> Option<ReconstructedMessage> {
+            let mut message_reconstructor = MessageReconstructor::default();
+            for fragment in fragments {
+                if let Some(msg) = message_reconstructor.insert_new_fragment(
+                    message_reconstructor
+                        .recover_fragment(fragment.into_bytes())
+                        .unwrap(),
+                ) {
+                    return Some(msg);
+                }
+            }
+            None
+        }
+
+        #[test]
+        fn it_reconstructs_single_set_message_with_lost_fragments() {
+            let mut rng = thread_rng();
+
+            let mut message =
+                vec![0u8; unlinked_fragment_payload_max_len(DATA_PLAINTEXT_SIZE) * 20];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments: Vec<_> = crate::split_into_sets_with_redundancy(
+                &mut rand::rngs::OsRng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                RedundancyRatio::new(0.25).unwrap(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .collect();
+            assert_eq!(fragments.len(), 25);
+
+            // lose as many fragments as there are parity fragments
+            fragments.shuffle(&mut rng);
+            fragments.truncate(20);
+
+            let msg = reconstruct(fragments).unwrap();
+            assert_eq!(msg.0, message);
+            assert_eq!(msg.1.len(), 1);
+        }
+
+        #[test]
+        fn it_reconstructs_message_split_into_two_sets_with_lost_linked_fragments() {
+            let mut rng = thread_rng();
+
+            let mut message = vec![
+                0u8;
+                max_one_way_linked_set_payload_length(DATA_PLAINTEXT_SIZE)
+                    + linked_fragment_payload_max_len(DATA_PLAINTEXT_SIZE)
+                    + unlinked_fragment_payload_max_len(DATA_PLAINTEXT_SIZE) * 40
+            ];
+            rng.fill_bytes(&mut message);
+
+            let sets = crate::split_into_sets_with_redundancy(
+                &mut rand::rngs::OsRng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                RedundancyRatio::new(0.1).unwrap(),
+            );
+            assert_eq!(sets.len(), 2);
+
+            let mut fragments: Vec<_> = sets
+                .into_iter()
+                .flat_map(|fragment_set| fragment_set.into_iter())
+                // lose all the fragments holding the linking information
+                .filter(|fragment| {
+                    fragment.is_parity()
+                        || (fragment.previous_fragments_set_id().is_none()
+                            && fragment.next_fragments_set_id().is_none())
+                })
+                .collect();
+            fragments.shuffle(&mut rng);
+
+            let msg = reconstruct(fragments).unwrap();
+            assert_eq!(msg.0, message);
+            assert_eq!(msg.1.len(), 2);
+        }
+
+        #[test]
+        fn it_reconstructs_single_set_message_from_parity_fragments_only() {
+            let mut rng = thread_rng();
+
+            let mut message =
+                vec![0u8; unlinked_fragment_payload_max_len(DATA_PLAINTEXT_SIZE) * 10];
+            rng.fill_bytes(&mut message);
+
+            let fragments: Vec<_> = crate::split_into_sets_with_redundancy(
+                &mut rand::rngs::OsRng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                RedundancyRatio::new(1.0).unwrap(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .filter(|fragment| fragment.is_parity())
+            .collect();
+            assert_eq!(fragments.len(), 10);
+
+            let msg = reconstruct(fragments).unwrap();
+            assert_eq!(msg.0, message);
+            assert_eq!(msg.1.len(), 1);
+        }
+
+        #[test]
+        fn it_does_not_reconstruct_message_with_too_many_lost_fragments() {
+            let mut rng = thread_rng();
+
+            let mut message =
+                vec![0u8; unlinked_fragment_payload_max_len(DATA_PLAINTEXT_SIZE) * 20];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments: Vec<_> = crate::split_into_sets_with_redundancy(
+                &mut rand::rngs::OsRng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                RedundancyRatio::new(0.25).unwrap(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .collect();
+
+            fragments.shuffle(&mut rng);
+            fragments.truncate(19);
+
+            assert!(reconstruct(fragments).is_none());
+        }
+    }
 }
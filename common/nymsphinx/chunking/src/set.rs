@@ -1,9 +1,11 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::fec::{generate_parity_fragments, RedundancyRatio};
 use crate::fragment::{
-    linked_fragment_payload_max_len, unlinked_fragment_payload_max_len, Fragment,
-    LINKED_FRAGMENTED_HEADER_LEN, UNLINKED_FRAGMENTED_HEADER_LEN,
+    erasure_coded_plaintext_size, linked_fragment_payload_max_len,
+    unlinked_fragment_payload_max_len, Fragment, LINKED_FRAGMENTED_HEADER_LEN,
+    UNLINKED_FRAGMENTED_HEADER_LEN,
 };
 use rand::Rng;
 
@@ -295,6 +297,29 @@ pub fn split_into_sets<R: Rng>(
     }
 }
 
+/// Similarly to `split_into_sets`, splits the whole message into possibly multiple [`Set`]s,
+/// but each of them is further extended with parity `Fragment`s, as determined by the provided
+/// redundancy ratio, that allow the receiver to recover any lost data `Fragment`s.
+/// Note that the parity `Fragment`s are always placed at the end of their [`Set`]s and that
+/// the data `Fragment`s are limited to `erasure_coded_plaintext_size(max_plaintext_size)`
+/// so that all of them would fit in `max_plaintext_size`.
+pub fn split_into_sets_with_redundancy<R: Rng>(
+    rng: &mut R,
+    message: &[u8],
+    max_plaintext_size: usize,
+    redundancy: RedundancyRatio,
+) -> Vec<FragmentSet> {
+    let data_plaintext_size = erasure_coded_plaintext_size(max_plaintext_size);
+    split_into_sets(rng, message, data_plaintext_size)
+        .into_iter()
+        .map(|mut fragment_set| {
+            let parity_fragments = generate_parity_fragments(&fragment_set, redundancy);
+            fragment_set.extend(parity_fragments);
+            fragment_set
+        })
+        .collect()
+}
+
 // reason for top level tests module is to be able to use the helper functions to verify sets payloads
 #[cfg(test)]
 mod tests {
@@ -797,6 +822,39 @@ mod tests {
                 &message[..max_one_way_linked_set_payload_length(max_plaintext_size())],
             );
         }
+
+        #[test]
+        fn appends_parity_fragments_to_each_set_when_requested() {
+            let mut rng = thread_rng();
+            let mut message =
+                vec![0u8; max_one_way_linked_set_payload_length(max_plaintext_size()) + 123];
+            rng.fill_bytes(&mut message);
+
+            let redundancy = RedundancyRatio::new(0.1).unwrap();
+            let sets = split_into_sets_with_redundancy(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                redundancy,
+            );
+            assert_eq!(2, sets.len());
+
+            for set in sets {
+                let data_fragments = set.iter().filter(|f| !f.is_parity()).count();
+                let parity_fragments = set.iter().filter(|f| f.is_parity()).count();
+                assert_eq!(
+                    parity_fragments,
+                    redundancy.parity_fragments(data_fragments)
+                );
+
+                // parity fragments are placed after all data fragments
+                assert!(set[data_fragments..].iter().all(|f| f.is_parity()));
+                assert!(set.iter().all(|f| f.id() == set[0].id()));
+                assert!(set
+                    .iter()
+                    .all(|f| f.serialized_size() <= max_plaintext_size()));
+            }
+        }
     }
 
     #[cfg(test)]
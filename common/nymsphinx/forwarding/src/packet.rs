@@ -3,7 +3,7 @@
 
 use nym_sphinx_addressing::nodes::{NymNodeRoutingAddress, NymNodeRoutingAddressError};
 use nym_sphinx_params::{PacketMode, PacketSize};
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_types::{NymPacket, OutfoxPacket};
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 
@@ -14,6 +14,8 @@ pub enum MixPacketFormattingError {
     InvalidPacketSize(usize),
     InvalidAddress,
     MalformedSphinxPacket,
+    MalformedOutfoxPacket,
+    InvalidOutfoxPayloadSize(usize),
 }
 
 impl Display for MixPacketFormattingError {
@@ -31,6 +33,15 @@ impl Display for MixPacketFormattingError {
                     PacketSize::ExtendedPacket32.size()
                 ),
             MalformedSphinxPacket => write!(f, "received sphinx packet was malformed"),
+            MalformedOutfoxPacket => write!(f, "received outfox packet was malformed"),
+            InvalidOutfoxPayloadSize(actual) =>
+                write!(
+                    f,
+                    "received outfox packet had invalid payload size. (actual: {}, but expected one of: {} (ACK), {} (REGULAR), {}, {}, {} (EXTENDED))",
+                    actual, PacketSize::AckPacket.plaintext_size(), PacketSize::RegularPacket.plaintext_size(),
+                    PacketSize::ExtendedPacket8.plaintext_size(), PacketSize::ExtendedPacket16.plaintext_size(),
+                    PacketSize::ExtendedPacket32.plaintext_size()
+                ),
             InvalidPacketMode => write!(f, "provided packet mode is invalid")
         }
     }
@@ -46,32 +57,42 @@ impl From<NymNodeRoutingAddressError> for MixPacketFormattingError {
 
 pub struct MixPacket {
     next_hop: NymNodeRoutingAddress,
-    sphinx_packet: SphinxPacket,
+    packet: NymPacket,
     packet_mode: PacketMode,
 }
 
 impl Debug for MixPacket {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "MixPacket to {:?} with packet_mode {:?}. Sphinx header: {:?}, payload length: {}",
-            self.next_hop,
-            self.packet_mode,
-            self.sphinx_packet.header,
-            self.sphinx_packet.payload.len()
-        )
+        match &self.packet {
+            NymPacket::Sphinx(sphinx_packet) => write!(
+                f,
+                "MixPacket to {:?} with packet_mode {:?}. Sphinx header: {:?}, payload length: {}",
+                self.next_hop,
+                self.packet_mode,
+                sphinx_packet.header,
+                sphinx_packet.payload.len()
+            ),
+            NymPacket::Outfox(outfox_packet) => write!(
+                f,
+                "MixPacket to {:?} with packet_mode {:?}. Outfox layers: {}, length: {}",
+                self.next_hop,
+                self.packet_mode,
+                outfox_packet.layers(),
+                outfox_packet.serialized_len()
+            ),
+        }
     }
 }
 
 impl MixPacket {
     pub fn new(
         next_hop: NymNodeRoutingAddress,
-        sphinx_packet: SphinxPacket,
+        packet: NymPacket,
         packet_mode: PacketMode,
     ) -> Self {
         MixPacket {
             next_hop,
-            sphinx_packet,
+            packet,
             packet_mode,
         }
     }
@@ -80,12 +101,12 @@ impl MixPacket {
         self.next_hop
     }
 
-    pub fn sphinx_packet(&self) -> &SphinxPacket {
-        &self.sphinx_packet
+    pub fn packet(&self) -> &NymPacket {
+        &self.packet
     }
 
-    pub fn into_sphinx_packet(self) -> SphinxPacket {
-        self.sphinx_packet
+    pub fn into_packet(self) -> NymPacket {
+        self.packet
     }
 
     pub fn packet_mode(&self) -> PacketMode {
@@ -93,7 +114,7 @@ impl MixPacket {
     }
 
     // the message is formatted as follows:
-    // PACKET_MODE || FIRST_HOP || SPHINX_PACKET
+    // PACKET_MODE || FIRST_HOP || PACKET
     pub fn try_from_bytes(b: &[u8]) -> Result<Self, MixPacketFormattingError> {
         let packet_mode = match PacketMode::try_from(b[0]) {
             Ok(mode) => mode,
@@ -103,19 +124,39 @@ impl MixPacket {
         let next_hop = NymNodeRoutingAddress::try_from_bytes(&b[1..])?;
         let addr_offset = next_hop.bytes_min_len();
 
-        let sphinx_packet_data = &b[addr_offset + 1..];
-        let packet_size = sphinx_packet_data.len();
+        let packet_data = &b[addr_offset + 1..];
+        if packet_mode.is_outfox() {
+            // outfox packets are self-describing and their size depends on the number of layers
+            let packet = OutfoxPacket::try_from(packet_data)
+                .map_err(|_| MixPacketFormattingError::MalformedOutfoxPacket)?;
+
+            // but their payload must still be of one of the known sizes
+            let payload_size = packet.mix_params().payload_length_bytes;
+            if PacketSize::get_type_from_plaintext(payload_size).is_err() {
+                return Err(MixPacketFormattingError::InvalidOutfoxPayloadSize(
+                    payload_size,
+                ));
+            }
+
+            return Ok(MixPacket {
+                next_hop,
+                packet: packet.into(),
+                packet_mode,
+            });
+        }
+
+        let packet_size = packet_data.len();
         if PacketSize::get_type(packet_size).is_err() {
             Err(MixPacketFormattingError::InvalidPacketSize(packet_size))
         } else {
-            let sphinx_packet = match SphinxPacket::from_bytes(sphinx_packet_data) {
+            let packet = match NymPacket::sphinx_from_bytes(packet_data) {
                 Ok(packet) => packet,
                 Err(_) => return Err(MixPacketFormattingError::MalformedSphinxPacket),
             };
 
             Ok(MixPacket {
                 next_hop,
-                sphinx_packet,
+                packet,
                 packet_mode,
             })
         }
@@ -124,9 +165,44 @@ impl MixPacket {
     pub fn into_bytes(self) -> Vec<u8> {
         std::iter::once(self.packet_mode as u8)
             .chain(self.next_hop.as_bytes().into_iter())
-            .chain(self.sphinx_packet.to_bytes().into_iter())
+            .chain(self.packet.to_bytes().into_iter())
             .collect()
     }
 }
 
-// TODO: test for serialization and errors!
+// TODO: test for sphinx serialization and errors!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_outfox::packet::LAYER_OVERHEAD;
+    use std::net::SocketAddr;
+
+    fn outfox_mix_packet_bytes(payload_size: usize) -> Vec<u8> {
+        let next_hop: NymNodeRoutingAddress = "1.2.3.4:1789".parse::<SocketAddr>().unwrap().into();
+        let layers = 3;
+
+        std::iter::once(PacketMode::Outfox as u8)
+            .chain(next_hop.as_bytes())
+            .chain(std::iter::once(layers as u8))
+            .chain(std::iter::repeat(0).take(layers * LAYER_OVERHEAD + payload_size))
+            .collect()
+    }
+
+    #[test]
+    fn outfox_packet_with_known_payload_size_is_accepted() {
+        let bytes = outfox_mix_packet_bytes(PacketSize::RegularPacket.plaintext_size());
+        let packet = MixPacket::try_from_bytes(&bytes).unwrap();
+        assert!(packet.packet().is_outfox());
+        assert!(packet.packet_mode().is_outfox());
+    }
+
+    #[test]
+    fn outfox_packet_with_unknown_payload_size_is_rejected() {
+        let payload_size = PacketSize::RegularPacket.plaintext_size() + 1;
+        let bytes = outfox_mix_packet_bytes(payload_size);
+        assert!(matches!(
+            MixPacket::try_from_bytes(&bytes),
+            Err(MixPacketFormattingError::InvalidOutfoxPayloadSize(size)) if size == payload_size
+        ));
+    }
+}
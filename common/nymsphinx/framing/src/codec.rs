@@ -6,7 +6,7 @@ use bytes::{Buf, BufMut, BytesMut};
 use nym_sphinx_params::packet_modes::InvalidPacketMode;
 use nym_sphinx_params::packet_sizes::{InvalidPacketSize, PacketSize};
 use nym_sphinx_types::Error as SphinxError;
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_types::{NymPacket, OutfoxError, OutfoxPacket};
 use std::io;
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
@@ -22,6 +22,9 @@ pub enum SphinxCodecError {
     #[error("the actual sphinx packet was malformed - {0}")]
     MalformedSphinxPacket(#[from] SphinxError),
 
+    #[error("the actual outfox packet was malformed - {0}")]
+    MalformedOutfoxPacket(#[from] OutfoxError),
+
     #[error("encountered an IO error - {0}")]
     IoError(#[from] io::Error),
 }
@@ -38,6 +41,9 @@ impl From<SphinxCodecError> for io::Error {
             SphinxCodecError::MalformedSphinxPacket(source) => {
                 io::Error::new(io::ErrorKind::InvalidData, source)
             }
+            SphinxCodecError::MalformedOutfoxPacket(source) => {
+                io::Error::new(io::ErrorKind::InvalidData, source)
+            }
             SphinxCodecError::IoError(err) => err,
         }
     }
@@ -76,7 +82,22 @@ impl Decoder for SphinxCodec {
             None => return Ok(None), // we have some data but not enough to get header back
         };
 
-        let sphinx_packet_size = header.packet_size.size();
+        let sphinx_packet_size = if header.packet_mode.is_outfox() {
+            // outfox packets get shorter at every hop, so their size depends on the number
+            // of the remaining layers, which is encoded in the first byte of the packet
+            match src.get(header.size()) {
+                Some(layers) => OutfoxPacket::expected_serialized_len(
+                    header.packet_size.plaintext_size(),
+                    *layers as usize,
+                ),
+                None => {
+                    src.reserve(1);
+                    return Ok(None);
+                }
+            }
+        } else {
+            header.packet_size.size()
+        };
         let frame_len = header.size() + sphinx_packet_size;
 
         if src.len() < frame_len {
@@ -91,7 +112,11 @@ impl Decoder for SphinxCodec {
 
         // here it could be debatable whether stream is corrupt or not,
         // but let's go with the safer approach and assume it is.
-        let packet = SphinxPacket::from_bytes(&sphinx_packet_bytes)?;
+        let packet = if header.packet_mode.is_outfox() {
+            NymPacket::outfox_from_bytes(&sphinx_packet_bytes)?
+        } else {
+            NymPacket::sphinx_from_bytes(&sphinx_packet_bytes)?
+        };
         let nymsphinx_packet = FramedSphinxPacket { header, packet };
 
         // As per docs:
@@ -128,13 +153,28 @@ impl Decoder for SphinxCodec {
 #[cfg(test)]
 mod packet_encoding {
     use super::*;
+    use nym_sphinx_params::PacketMode;
     use nym_sphinx_types::builder::SphinxPacketBuilder;
     use nym_sphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
-        DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        OutfoxHop, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
     };
 
-    fn make_valid_sphinx_packet(size: PacketSize) -> SphinxPacket {
+    fn make_valid_outfox_packet(size: PacketSize, layers: usize) -> NymPacket {
+        let route = (0..layers)
+            .map(|i| OutfoxHop {
+                public_key: *crypto::keygen().1.as_bytes(),
+                routing_information: [i as u8; NODE_ADDRESS_LENGTH],
+            })
+            .collect::<Vec<_>>();
+
+        let payload = vec![42u8; size.plaintext_size()];
+        OutfoxPacket::build_for_route(&mut rand::rngs::OsRng, &payload, &route)
+            .unwrap()
+            .into()
+    }
+
+    fn make_valid_sphinx_packet(size: PacketSize) -> NymPacket {
         let (_, node1_pk) = crypto::keygen();
         let node1 = Node::new(
             NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
@@ -165,6 +205,7 @@ mod packet_encoding {
             .with_payload_size(size.payload_size())
             .build_packet(b"foomp", &route, &destination, &delays)
             .unwrap()
+            .into()
     }
 
     #[test]
@@ -352,6 +393,37 @@ mod packet_encoding {
         }
     }
 
+    #[test]
+    fn outfox_packets_can_be_decoded_regardless_of_remaining_layers() {
+        for layers in 1..=4 {
+            let header = Header {
+                packet_mode: PacketMode::Outfox,
+                ..Default::default()
+            };
+
+            let outfox_packet = make_valid_outfox_packet(PacketSize::default(), layers);
+            let outfox_bytes = outfox_packet.to_bytes();
+            let packet = FramedSphinxPacket {
+                header,
+                packet: outfox_packet,
+            };
+
+            let mut bytes = BytesMut::new();
+            SphinxCodec.encode(packet, &mut bytes).unwrap();
+
+            // make sure partial frames are not decoded
+            let mut partial = bytes.split_to(header.size() + 1);
+            assert!(SphinxCodec.decode(&mut partial).unwrap().is_none());
+            partial.unsplit(bytes);
+
+            let decoded = SphinxCodec.decode(&mut partial).unwrap().unwrap();
+            assert_eq!(decoded.header, header);
+            assert!(decoded.packet.is_outfox());
+            assert_eq!(decoded.packet.to_bytes(), outfox_bytes);
+            assert!(partial.is_empty());
+        }
+    }
+
     #[test]
     fn can_decode_two_packets_immediately() {
         let packet1 = FramedSphinxPacket {
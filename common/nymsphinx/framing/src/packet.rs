@@ -6,31 +6,39 @@ use bytes::{BufMut, BytesMut};
 use nym_sphinx_params::packet_sizes::PacketSize;
 use nym_sphinx_params::packet_version::PacketVersion;
 use nym_sphinx_params::PacketMode;
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_types::NymPacket;
 use std::convert::TryFrom;
 
 pub struct FramedSphinxPacket {
     /// Contains any metadata helping receiver to handle the underlying packet.
     pub(crate) header: Header,
 
-    /// The actual SphinxPacket being sent.
-    pub(crate) packet: SphinxPacket,
+    /// The actual packet being sent.
+    pub(crate) packet: NymPacket,
 }
 
 impl FramedSphinxPacket {
-    pub fn new(packet: SphinxPacket, packet_mode: PacketMode, use_legacy_version: bool) -> Self {
-        // If this fails somebody is using the library in a super incorrect way, because they
-        // already managed to somehow create a sphinx packet
-        let packet_size = PacketSize::get_type(packet.len()).unwrap();
+    pub fn new(
+        packet: NymPacket,
+        packet_mode: PacketMode,
+        use_legacy_version: bool,
+    ) -> Result<Self, SphinxCodecError> {
+        let packet_size = match &packet {
+            NymPacket::Sphinx(packet) => PacketSize::get_type(packet.len())?,
+            // outfox packets shrink at every hop, but their payload always remains the same
+            NymPacket::Outfox(packet) => {
+                PacketSize::get_type_from_plaintext(packet.mix_params().payload_length_bytes)?
+            }
+        };
 
-        FramedSphinxPacket {
+        Ok(FramedSphinxPacket {
             header: Header {
                 packet_version: PacketVersion::new(use_legacy_version),
                 packet_size,
                 packet_mode,
             },
             packet,
-        }
+        })
     }
 
     pub fn packet_size(&self) -> PacketSize {
@@ -41,7 +49,7 @@ impl FramedSphinxPacket {
         self.header.packet_mode
     }
 
-    pub fn into_inner(self) -> SphinxPacket {
+    pub fn into_inner(self) -> NymPacket {
         self.packet
     }
 }
@@ -64,8 +72,9 @@ pub struct Header {
     ///
     /// TODO: ask @AP whether this can be sent like this - could it introduce some anonymity issues?
     /// (note: this will be behind some encryption, either something implemented by us or some SSL action)
-    // Note: currently packet_mode is deprecated but is still left as a concept behind to not break
-    // compatibility with existing network
+    // Note: currently the 'vpn' packet_mode is deprecated but is still left as a concept behind to not break
+    // compatibility with existing network. However, the mode is also used to indicate packets using
+    // the Outfox format.
     pub(crate) packet_mode: PacketMode,
 }
 
@@ -123,6 +132,35 @@ impl Header {
     }
 }
 
+#[cfg(test)]
+mod packet_framing {
+    use super::*;
+    use nym_sphinx_types::OutfoxPacket;
+
+    // LAYERS || ENCODED_LAYERS || PAYLOAD
+    fn outfox_packet(payload_size: usize) -> NymPacket {
+        let layers = 3;
+        let len = OutfoxPacket::expected_serialized_len(payload_size, layers);
+        let bytes: Vec<_> = std::iter::once(layers as u8)
+            .chain(std::iter::repeat(0).take(len - 1))
+            .collect();
+        NymPacket::outfox_from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn outfox_packet_with_known_payload_size_can_be_framed() {
+        let packet = outfox_packet(PacketSize::ExtendedPacket8.plaintext_size());
+        let framed = FramedSphinxPacket::new(packet, PacketMode::Outfox, false).unwrap();
+        assert_eq!(framed.packet_size(), PacketSize::ExtendedPacket8);
+    }
+
+    #[test]
+    fn outfox_packet_with_unknown_payload_size_cant_be_framed() {
+        let packet = outfox_packet(PacketSize::RegularPacket.plaintext_size() - 1);
+        assert!(FramedSphinxPacket::new(packet, PacketMode::Outfox, false).is_err());
+    }
+}
+
 #[cfg(test)]
 mod header_encoding {
     use super::*;
@@ -4,6 +4,7 @@
 use nym_crypto::aes::Aes128;
 use nym_crypto::blake3;
 use nym_crypto::ctr;
+use std::time::Duration;
 
 type Aes128Ctr = ctr::Ctr64BE<Aes128>;
 
@@ -19,6 +20,11 @@ pub mod packet_version;
 // I will change this to [`usize`]
 pub const DEFAULT_NUM_MIX_HOPS: u8 = 3;
 
+/// Average delay applied by mixnodes to forward hops of outfox packets. Unlike sphinx, the outfox
+/// header does not carry any delays, so they are sampled by the nodes themselves.
+/// It matches the default average packet delay used by clients when constructing sphinx packets.
+pub const OUTFOX_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(50);
+
 // TODO: not entirely sure how to feel about those being defined here, ideally it'd be where [`Fragment`]
 // is defined, but that'd introduce circular dependencies as the acknowledgements crate also needs
 // access to that
@@ -1,6 +1,7 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use thiserror::Error;
 
@@ -11,16 +12,23 @@ pub struct InvalidPacketMode {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PacketMode {
     /// Represents 'normal' packet sent through the network that should be delayed by an appropriate
     /// value at each hop.
     #[default]
+    #[serde(rename = "mix")]
     Mix = 0,
 
     /// Represents a VPN packet that should not be delayed and ideally cached pre-computed keys
     /// should be used for unwrapping data. Note that it does not offer the same level of anonymity.
+    #[serde(rename = "vpn")]
     Vpn = 1,
+
+    /// Represents a packet using the Outfox format rather than Sphinx. It has a lower per-hop
+    /// processing cost. It does not carry any delays, so the mixnodes sample them on their own.
+    #[serde(rename = "outfox")]
+    Outfox = 2,
 }
 
 impl PacketMode {
@@ -31,6 +39,10 @@ impl PacketMode {
     pub fn is_old_vpn(self) -> bool {
         self == PacketMode::Vpn
     }
+
+    pub fn is_outfox(self) -> bool {
+        self == PacketMode::Outfox
+    }
 }
 
 impl TryFrom<u8> for PacketMode {
@@ -40,6 +52,7 @@ impl TryFrom<u8> for PacketMode {
         match value {
             _ if value == (PacketMode::Mix as u8) => Ok(Self::Mix),
             _ if value == (PacketMode::Vpn as u8) => Ok(Self::Vpn),
+            _ if value == (PacketMode::Outfox as u8) => Ok(Self::Outfox),
             v => Err(InvalidPacketMode { received: v }),
         }
     }
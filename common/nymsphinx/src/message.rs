@@ -14,10 +14,24 @@ use nym_sphinx_chunking::fragment::Fragment;
 use nym_sphinx_params::{PacketSize, ReplySurbKeyDigestAlgorithm};
 use rand::Rng;
 use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
 use thiserror::Error;
 
 pub(crate) const ACK_OVERHEAD: usize = MAX_NODE_ADDRESS_UNPADDED_LEN + PacketSize::AckPacket.size();
 
+/// Bit set on the message type tag to indicate the content has been compressed.
+/// In that case the tag is followed by a byte identifying the used compression algorithm.
+const COMPRESSED_MESSAGE_FLAG: u8 = 0b1000_0000;
+
+/// Bit set on the message type tag to indicate the content is a frame of a stream multiplexed
+/// over the mixnet rather than a standalone message.
+const STREAM_MESSAGE_FLAG: u8 = 0b0100_0000;
+
+/// Maximum size a compressed message is allowed to expand to, so that a malicious sender
+/// would not be able to exhaust our memory with a tiny decompression bomb.
+pub const MAX_DECOMPRESSED_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
 #[derive(Debug, Error)]
 pub enum NymMessageError {
     #[error("{received} is not a valid type tag for a NymMessage")]
@@ -31,6 +45,141 @@ pub enum NymMessageError {
 
     #[error("Received empty message for deserialization")]
     EmptyMessage,
+
+    #[error("{received} is not a supported message compression algorithm")]
+    UnsupportedCompression { received: u8 },
+
+    #[error("'{name}' is not a supported message compression algorithm")]
+    UnknownCompression { name: String },
+
+    #[error("failed to decompress the received message: {source}")]
+    DecompressionFailure {
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("the decompressed message exceeds the maximum allowed size of {MAX_DECOMPRESSED_MESSAGE_SIZE} bytes")]
+    DecompressedMessageTooLarge,
+}
+
+/// Compression applied to the content of a [`NymMessage`] before it gets padded and split into
+/// fragments. It's indicated in the message header so that the recipient could transparently
+/// decompress it. Note that recipients running older versions are going to reject such messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageCompression {
+    #[default]
+    None = 0,
+    Deflate = 1,
+}
+
+impl TryFrom<u8> for MessageCompression {
+    type Error = NymMessageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (MessageCompression::None as u8) => Ok(Self::None),
+            _ if value == (MessageCompression::Deflate as u8) => Ok(Self::Deflate),
+            val => Err(NymMessageError::UnsupportedCompression { received: val }),
+        }
+    }
+}
+
+impl FromStr for MessageCompression {
+    type Err = NymMessageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(MessageCompression::None),
+            "deflate" => Ok(MessageCompression::Deflate),
+            _ => Err(NymMessageError::UnknownCompression {
+                name: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl Display for MessageCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageCompression::None => write!(f, "none"),
+            MessageCompression::Deflate => write!(f, "deflate"),
+        }
+    }
+}
+
+impl MessageCompression {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, MessageCompression::None)
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            MessageCompression::None => Ok(data.to_vec()),
+            MessageCompression::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(
+                    Vec::with_capacity(data.len()),
+                    flate2::Compression::default(),
+                );
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, NymMessageError> {
+        let mut decompressed = Vec::new();
+        match self {
+            MessageCompression::None => decompressed.extend_from_slice(data),
+            MessageCompression::Deflate => {
+                // read at most a single byte above the limit to detect whether it got exceeded
+                flate2::read::DeflateDecoder::new(data)
+                    .take(MAX_DECOMPRESSED_MESSAGE_SIZE as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|source| NymMessageError::DecompressionFailure { source })?;
+            }
+        }
+
+        if decompressed.len() > MAX_DECOMPRESSED_MESSAGE_SIZE {
+            return Err(NymMessageError::DecompressedMessageTooLarge);
+        }
+        Ok(decompressed)
+    }
+}
+
+/// Indicates how the recipient is meant to interpret the content of a [`NymMessage`]. It's
+/// explicitly tagged in the message header, so that the frames of multiplexed streams are never
+/// confused with regular messages. Note that recipients running older versions are going to reject
+/// any stream frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageFraming {
+    /// The message is a self-contained piece of application data.
+    #[default]
+    Standalone,
+
+    /// The message carries a frame of a stream multiplexed over the mixnet.
+    Stream,
+}
+
+impl MessageFraming {
+    pub fn is_stream(&self) -> bool {
+        matches!(self, MessageFraming::Stream)
+    }
+
+    fn flag(&self) -> u8 {
+        match self {
+            MessageFraming::Standalone => 0,
+            MessageFraming::Stream => STREAM_MESSAGE_FLAG,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Self {
+        if tag & STREAM_MESSAGE_FLAG != 0 {
+            MessageFraming::Stream
+        } else {
+            MessageFraming::Standalone
+        }
+    }
 }
 
 #[repr(u8)]
@@ -147,20 +296,89 @@ impl NymMessage {
             .collect()
     }
 
-    fn try_from_bytes(bytes: &[u8], num_mix_hops: u8) -> Result<Self, NymMessageError> {
+    // the tagged message is in the format of:
+    // (typ | framing) || msg
+    // or, if the content got compressed:
+    // (typ | framing | COMPRESSED_MESSAGE_FLAG) || compression || compressed(msg)
+    // if compressing the content would not reduce its size, the message is left uncompressed
+    fn into_tagged_bytes(
+        self,
+        compression: MessageCompression,
+        framing: MessageFraming,
+    ) -> Vec<u8> {
+        if !compression.is_enabled() {
+            let mut bytes = self.into_bytes();
+            bytes[0] |= framing.flag();
+            return bytes;
+        }
+
+        let tag = self.typ() as u8 | framing.flag();
+        let inner_bytes = self.inner_bytes();
+
+        let compressed = match compression.compress(&inner_bytes) {
+            Ok(compressed) => compressed,
+            Err(err) => {
+                log::warn!(
+                    "failed to compress the message ({err}). It's going to be sent uncompressed"
+                );
+                return std::iter::once(tag).chain(inner_bytes).collect();
+            }
+        };
+
+        if compressed.len() + 1 >= inner_bytes.len() {
+            log::trace!("compressing the message would not reduce its size - it's going to be sent uncompressed");
+            return std::iter::once(tag).chain(inner_bytes).collect();
+        }
+
+        log::trace!(
+            "compressed the message content from {} to {} bytes",
+            inner_bytes.len(),
+            compressed.len()
+        );
+        [tag | COMPRESSED_MESSAGE_FLAG, compression as u8]
+            .into_iter()
+            .chain(compressed)
+            .collect()
+    }
+
+    fn try_from_bytes(
+        bytes: &[u8],
+        num_mix_hops: u8,
+    ) -> Result<(Self, MessageFraming), NymMessageError> {
         if bytes.is_empty() {
             return Err(NymMessageError::EmptyMessage);
         }
 
-        let typ_tag = NymMessageType::try_from(bytes[0])?;
+        let framing = MessageFraming::from_tag(bytes[0]);
+        let tag = bytes[0] & !STREAM_MESSAGE_FLAG;
+
+        let message = if tag & COMPRESSED_MESSAGE_FLAG != 0 {
+            let compression_tag = bytes.get(1).ok_or(NymMessageError::EmptyMessage)?;
+            let compression = MessageCompression::try_from(*compression_tag)?;
+            let decompressed = compression.decompress(&bytes[2..])?;
+
+            // note that we do not attempt to decompress the content again, even if it was
+            // (maliciously) compressed multiple times
+            Self::try_from_content(tag & !COMPRESSED_MESSAGE_FLAG, &decompressed, num_mix_hops)?
+        } else {
+            Self::try_from_content(tag, &bytes[1..], num_mix_hops)?
+        };
+
+        Ok((message, framing))
+    }
+
+    fn try_from_content(
+        typ: u8,
+        content: &[u8],
+        num_mix_hops: u8,
+    ) -> Result<Self, NymMessageError> {
+        let typ_tag = NymMessageType::try_from(typ)?;
         match typ_tag {
-            NymMessageType::Plain => Ok(NymMessage::Plain(bytes[1..].to_vec())),
+            NymMessageType::Plain => Ok(NymMessage::Plain(content.to_vec())),
             NymMessageType::Repliable => Ok(NymMessage::Repliable(
-                RepliableMessage::try_from_bytes(&bytes[1..], num_mix_hops)?,
+                RepliableMessage::try_from_bytes(content, num_mix_hops)?,
             )),
-            NymMessageType::Reply => Ok(NymMessage::Reply(ReplyMessage::try_from_bytes(
-                &bytes[1..],
-            )?)),
+            NymMessageType::Reply => Ok(NymMessage::Reply(ReplyMessage::try_from_bytes(content)?)),
         }
     }
 
@@ -216,10 +434,16 @@ impl NymMessage {
 
     /// Pads the message so that after it gets chunked, it will occupy exactly N sphinx packets.
     /// Produces new_message = message || 1 || 0000....
-    pub fn pad_to_full_packet_lengths(self, plaintext_per_packet: usize) -> PaddedMessage {
+    /// If compression is enabled, it's applied to the message content before the padding.
+    pub fn pad_to_full_packet_lengths(
+        self,
+        plaintext_per_packet: usize,
+        compression: MessageCompression,
+        framing: MessageFraming,
+    ) -> PaddedMessage {
         let self_display = self.to_string();
 
-        let bytes = self.into_bytes();
+        let bytes = self.into_tagged_bytes(compression, framing);
 
         // 1 (chunking::MIN_PADDING_OVERHEAD) is added as there will always have to be at least a single byte of padding (1) added
         // to be able to later distinguish the actual padding from the underlying message
@@ -262,8 +486,26 @@ impl PaddedMessage {
             .collect()
     }
 
+    /// Splits the padded message into [`Fragment`]s alongside additional parity [`Fragment`]s,
+    /// as determined by the redundancy ratio, allowing the recipient to recover the message
+    /// even if some of them got lost.
+    pub fn split_into_fragments_with_redundancy<R: Rng>(
+        self,
+        rng: &mut R,
+        plaintext_per_packet: usize,
+        redundancy: chunking::RedundancyRatio,
+    ) -> Vec<Fragment> {
+        chunking::split_into_sets_with_redundancy(rng, &self.0, plaintext_per_packet, redundancy)
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .collect()
+    }
+
     // reverse of NymMessage::pad_to_full_packet_lengths
-    pub fn remove_padding(self, num_mix_hops: u8) -> Result<NymMessage, NymMessageError> {
+    pub fn remove_padding(
+        self,
+        num_mix_hops: u8,
+    ) -> Result<(NymMessage, MessageFraming), NymMessageError> {
         // we are looking for first occurrence of 1 in the tail and we get its index
         if let Some(padding_end) = self.0.iter().rposition(|b| *b == 1) {
             // and now we only take bytes until that point (but not including it)
@@ -302,4 +544,118 @@ mod tests {
         let reply = NymMessage::new_reply(ReplyMessage::new_data_message(vec![1, 2, 3, 4, 5]));
         assert_eq!(reply.serialized_size(3), reply.into_bytes().len());
     }
+
+    #[test]
+    fn compressed_message_can_be_recovered() {
+        let content = b"hello world! ".repeat(100);
+        let plain = NymMessage::new_plain(content.clone());
+        let uncompressed_len = plain.serialized_size(3);
+
+        let bytes =
+            plain.into_tagged_bytes(MessageCompression::Deflate, MessageFraming::Standalone);
+        assert!(bytes.len() < uncompressed_len);
+        assert_eq!(
+            bytes[0],
+            NymMessageType::Plain as u8 | COMPRESSED_MESSAGE_FLAG
+        );
+        assert_eq!(bytes[1], MessageCompression::Deflate as u8);
+
+        match NymMessage::try_from_bytes(&bytes, 3).unwrap() {
+            (NymMessage::Plain(recovered), MessageFraming::Standalone) => {
+                assert_eq!(recovered, content)
+            }
+            _ => panic!("unexpected message variant"),
+        }
+
+        let repliable = NymMessage::new_repliable(RepliableMessage::new_data(
+            content.clone(),
+            [42u8; 16].into(),
+            vec![],
+        ));
+        let padded = repliable.pad_to_full_packet_lengths(
+            1000,
+            MessageCompression::Deflate,
+            MessageFraming::Standalone,
+        );
+        let (recovered, _) = padded.remove_padding(3).unwrap();
+        assert!(matches!(recovered, NymMessage::Repliable(_)));
+        assert_eq!(recovered.into_inner_data(), content);
+    }
+
+    #[test]
+    fn incompressible_message_is_left_uncompressed() {
+        let plain = NymMessage::new_plain(vec![1, 2, 3, 4, 5]);
+        let expected = NymMessage::new_plain(vec![1, 2, 3, 4, 5]).into_bytes();
+
+        assert_eq!(
+            plain.into_tagged_bytes(MessageCompression::Deflate, MessageFraming::Standalone),
+            expected
+        );
+    }
+
+    #[test]
+    fn message_with_unknown_compression_is_rejected() {
+        let bytes = vec![
+            NymMessageType::Plain as u8 | COMPRESSED_MESSAGE_FLAG,
+            42,
+            1,
+            2,
+            3,
+        ];
+        assert!(matches!(
+            NymMessage::try_from_bytes(&bytes, 3),
+            Err(NymMessageError::UnsupportedCompression { received: 42 })
+        ));
+    }
+
+    #[test]
+    fn oversized_decompressed_message_is_rejected() {
+        // a tiny payload that inflates to just above the limit
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        let chunk = vec![0u8; 1024 * 1024];
+        for _ in 0..MAX_DECOMPRESSED_MESSAGE_SIZE / chunk.len() {
+            encoder.write_all(&chunk).unwrap();
+        }
+        encoder.write_all(&[0]).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(compressed.len() < MAX_DECOMPRESSED_MESSAGE_SIZE / 100);
+
+        let bytes: Vec<_> = [
+            NymMessageType::Plain as u8 | COMPRESSED_MESSAGE_FLAG,
+            MessageCompression::Deflate as u8,
+        ]
+        .into_iter()
+        .chain(compressed)
+        .collect();
+        assert!(matches!(
+            NymMessage::try_from_bytes(&bytes, 3),
+            Err(NymMessageError::DecompressedMessageTooLarge)
+        ));
+    }
+
+    #[test]
+    fn stream_framing_is_indicated_in_the_header() {
+        let content = b"hello world! ".repeat(100);
+
+        for compression in [MessageCompression::None, MessageCompression::Deflate] {
+            let bytes = NymMessage::new_plain(content.clone())
+                .into_tagged_bytes(compression, MessageFraming::Stream);
+            assert_ne!(bytes[0] & STREAM_MESSAGE_FLAG, 0);
+
+            match NymMessage::try_from_bytes(&bytes, 3).unwrap() {
+                (NymMessage::Plain(recovered), MessageFraming::Stream) => {
+                    assert_eq!(recovered, content)
+                }
+                _ => panic!("unexpected message variant or framing"),
+            }
+        }
+
+        // regular messages are never treated as stream frames, regardless of their content
+        let bytes = NymMessage::new_reply(ReplyMessage::new_data_message(content.clone()))
+            .into_tagged_bytes(MessageCompression::None, MessageFraming::Standalone);
+        let (recovered, framing) = NymMessage::try_from_bytes(&bytes, 3).unwrap();
+        assert_eq!(framing, MessageFraming::Standalone);
+        assert_eq!(recovered.into_inner_data(), content);
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::message::{NymMessage, ACK_OVERHEAD};
+use crate::message::{MessageCompression, MessageFraming, NymMessage, ACK_OVERHEAD};
 use crate::NymsphinxPayloadBuilder;
 use nym_crypto::asymmetric::encryption;
 use nym_crypto::Digest;
@@ -10,12 +10,15 @@ use nym_sphinx_acknowledgements::AckKey;
 use nym_sphinx_addressing::clients::Recipient;
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_anonymous_replies::reply_surb::ReplySurb;
-use nym_sphinx_chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx_chunking::fragment::{erasure_coded_plaintext_size, Fragment, FragmentIdentifier};
+use nym_sphinx_chunking::RedundancyRatio;
 use nym_sphinx_forwarding::packet::MixPacket;
 use nym_sphinx_params::packet_sizes::PacketSize;
-use nym_sphinx_params::{ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS};
+use nym_sphinx_params::{
+    PacketMode, ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS, OUTFOX_AVERAGE_PACKET_DELAY,
+};
 use nym_sphinx_types::builder::SphinxPacketBuilder;
-use nym_sphinx_types::{delays, Delay};
+use nym_sphinx_types::{delays, Delay, NymPacket, OutfoxHop, OutfoxPacket};
 use nym_topology::{NymTopology, NymTopologyError};
 use rand::{CryptoRng, Rng};
 use std::convert::TryFrom;
@@ -60,6 +63,15 @@ pub struct MessagePreparer<R> {
     /// Number of mix hops each packet ('real' message, ack, reply) is expected to take.
     /// Note that it does not include gateway hops.
     num_mix_hops: u8,
+
+    /// Mode of the packets created for the 'real' messages. Note that acks and replies
+    /// are always sent as sphinx packets.
+    packet_mode: PacketMode,
+
+    /// If specified, ratio of parity fragments that are going to be attached to each
+    /// (non-reply) message so that the recipient could recover it without waiting for
+    /// retransmissions of any lost fragments.
+    redundancy: Option<RedundancyRatio>,
 }
 
 impl<R> MessagePreparer<R>
@@ -78,6 +90,8 @@ where
             average_packet_delay,
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
+            packet_mode: PacketMode::default(),
+            redundancy: None,
         }
     }
 
@@ -87,6 +101,18 @@ where
         self
     }
 
+    /// Allows setting non-default mode of the packets sent out.
+    pub fn with_packet_mode(mut self, packet_mode: PacketMode) -> Self {
+        self.packet_mode = packet_mode;
+        self
+    }
+
+    /// Allows enabling erasure coding of the sent messages.
+    pub fn with_redundancy(mut self, redundancy: Option<RedundancyRatio>) -> Self {
+        self.redundancy = redundancy;
+        self
+    }
+
     /// Overwrites existing sender address with the provided value.
     pub fn set_sender_address(&mut self, sender_address: Recipient) {
         self.sender_address = sender_address;
@@ -162,7 +188,11 @@ where
             // well as the total delay of the ack packet.
             // we don't know the delays inside the reply surbs so we use best-effort estimation from our poisson distribution
             total_delay: expected_forward_delay + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(
+                first_hop_address,
+                NymPacket::Sphinx(sphinx_packet),
+                Default::default(),
+            ),
             fragment_identifier,
         })
     }
@@ -219,6 +249,49 @@ where
         )?;
         let destination = packet_recipient.as_sphinx_destination();
 
+        // from the constructed route extract the first hop
+        let first_hop_address =
+            NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
+
+        if self.packet_mode.is_outfox() {
+            // every hop learns the address of the next one, with the gateway learning the destination
+            let outfox_route = route
+                .iter()
+                .enumerate()
+                .map(|(i, node)| OutfoxHop {
+                    public_key: *node.pub_key.as_bytes(),
+                    routing_information: match route.get(i + 1) {
+                        Some(next_hop) => next_hop.address.as_bytes(),
+                        None => destination.address.as_bytes(),
+                    },
+                })
+                .collect::<Vec<_>>();
+
+            // with valid route and correct payload size, there's absolutely no reason for this call to fail.
+            let outfox_packet = OutfoxPacket::build_for_route(
+                &mut self.rng,
+                packet_payload.as_ref(),
+                &outfox_route,
+            )
+            .unwrap();
+
+            // outfox packets are delayed by every mixnode on the route (but not the gateway)
+            // by an amount of their own choosing, so only the expected value is known here
+            let expected_forward_delay = Delay::new_from_nanos(
+                OUTFOX_AVERAGE_PACKET_DELAY.as_nanos() as u64 * (route.len() as u64 - 1),
+            );
+
+            return Ok(PreparedFragment {
+                total_delay: expected_forward_delay + ack_delay,
+                mix_packet: MixPacket::new(
+                    first_hop_address,
+                    NymPacket::Outfox(outfox_packet),
+                    PacketMode::Outfox,
+                ),
+                fragment_identifier,
+            });
+        }
+
         // including set of delays
         let delays = delays::generate_from_average_duration(route.len(), self.average_packet_delay);
 
@@ -229,16 +302,16 @@ where
             .build_packet(packet_payload, &route, &destination, &delays)
             .unwrap();
 
-        // from the previously constructed route extract the first hop
-        let first_hop_address =
-            NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
-
         Ok(PreparedFragment {
             // the round-trip delay is the sum of delays of all hops on the forward route as
             // well as the total delay of the ack packet.
             // note that the last hop of the packet is a gateway that does not do any delays
             total_delay: delays.iter().take(delays.len() - 1).sum::<Delay>() + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(
+                first_hop_address,
+                NymPacket::Sphinx(sphinx_packet),
+                Default::default(),
+            ),
             fragment_identifier,
         })
     }
@@ -264,12 +337,34 @@ where
         &mut self,
         message: NymMessage,
         packet_size: PacketSize,
+        compression: MessageCompression,
+        framing: MessageFraming,
     ) -> Vec<Fragment> {
         let plaintext_per_packet = message.available_sphinx_plaintext_per_packet(packet_size);
 
-        message
-            .pad_to_full_packet_lengths(plaintext_per_packet)
-            .split_into_fragments(&mut self.rng, plaintext_per_packet)
+        // replies are never erasure coded as each of their fragments uses up a reply SURB
+        let redundancy = match message {
+            NymMessage::Reply(_) => None,
+            _ => self.redundancy,
+        };
+
+        match redundancy {
+            Some(redundancy) => {
+                // the data fragments of erasure coded messages are slightly shorter
+                // so that the parity fragments could fit in the same packets
+                let data_plaintext_per_packet = erasure_coded_plaintext_size(plaintext_per_packet);
+                message
+                    .pad_to_full_packet_lengths(data_plaintext_per_packet, compression, framing)
+                    .split_into_fragments_with_redundancy(
+                        &mut self.rng,
+                        plaintext_per_packet,
+                        redundancy,
+                    )
+            }
+            None => message
+                .pad_to_full_packet_lengths(plaintext_per_packet, compression, framing)
+                .split_into_fragments(&mut self.rng, plaintext_per_packet),
+        }
     }
 }
 
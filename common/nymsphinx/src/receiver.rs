@@ -1,7 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::message::{NymMessage, NymMessageError, PaddedMessage, PlainMessage};
+use crate::message::{MessageFraming, NymMessage, NymMessageError, PaddedMessage, PlainMessage};
 use nym_crypto::aes::cipher::{KeyIvInit, StreamCipher};
 use nym_crypto::asymmetric::encryption;
 use nym_crypto::shared_key::recompute_shared_key;
@@ -29,6 +29,10 @@ pub struct ReconstructedMessage {
     /// Optional ephemeral sender tag indicating pseudo-identity of the party who sent us the message
     /// (alongside any reply SURBs)
     pub sender_tag: Option<AnonymousSenderTag>,
+
+    /// Indicates whether the message is a standalone piece of data or a frame of a stream
+    /// multiplexed over the mixnet.
+    pub framing: MessageFraming,
 }
 
 impl From<ReconstructedMessage> for (Vec<u8>, Option<AnonymousSenderTag>) {
@@ -42,9 +46,16 @@ impl ReconstructedMessage {
         Self {
             message,
             sender_tag: Some(sender_tag),
+            framing: MessageFraming::Standalone,
         }
     }
 
+    #[must_use]
+    pub fn with_framing(mut self, framing: MessageFraming) -> Self {
+        self.framing = framing;
+        self
+    }
+
     pub fn into_inner(self) -> (Vec<u8>, Option<AnonymousSenderTag>) {
         self.into()
     }
@@ -55,6 +66,7 @@ impl From<PlainMessage> for ReconstructedMessage {
         ReconstructedMessage {
             message,
             sender_tag: None,
+            framing: MessageFraming::Standalone,
         }
     }
 }
@@ -186,10 +198,10 @@ pub trait MessageReceiver {
     fn insert_new_fragment(
         &mut self,
         fragment: Fragment,
-    ) -> Result<Option<(NymMessage, Vec<i32>)>, MessageRecoveryError> {
+    ) -> Result<Option<(NymMessage, MessageFraming, Vec<i32>)>, MessageRecoveryError> {
         if let Some((message, used_sets)) = self.reconstructor().insert_new_fragment(fragment) {
             match PaddedMessage::new_reconstructed(message).remove_padding(self.num_mix_hops()) {
-                Ok(message) => Ok(Some((message, used_sets))),
+                Ok((message, framing)) => Ok(Some((message, framing, used_sets))),
                 Err(err) => Err(MessageRecoveryError::MalformedReconstructedMessage {
                     source: err,
                     used_sets,
@@ -289,6 +301,8 @@ mod message_receiver {
                 .unwrap(),
                 layer: Layer::One,
                 version: "0.8.0-dev".to_string(),
+                total_stake: 0,
+                performance: None,
             }],
         );
 
@@ -309,6 +323,8 @@ mod message_receiver {
                 .unwrap(),
                 layer: Layer::Two,
                 version: "0.8.0-dev".to_string(),
+                total_stake: 0,
+                performance: None,
             }],
         );
 
@@ -329,6 +345,8 @@ mod message_receiver {
                 .unwrap(),
                 layer: Layer::Three,
                 version: "0.8.0-dev".to_string(),
+                total_stake: 0,
+                performance: None,
             }],
         );
 
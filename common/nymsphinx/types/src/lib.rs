@@ -1,6 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use std::convert::TryFrom;
+
+// re-exporting types and constants available in outfox
+pub use nym_outfox::{
+    error::OutfoxError,
+    packet::{OutfoxHop, OutfoxPacket, OutfoxProcessedPacket},
+};
+
 // re-exporting types and constants available in sphinx
 pub use sphinx_packet::{
     constants::{
@@ -15,3 +23,53 @@ pub use sphinx_packet::{
     surb::{SURBMaterial, SURB},
     Error, ProcessedPacket, Result, SphinxPacket,
 };
+
+/// Packet of either of the formats supported by the mix network.
+pub enum NymPacket {
+    Sphinx(SphinxPacket),
+    Outfox(OutfoxPacket),
+}
+
+impl NymPacket {
+    pub fn sphinx_from_bytes(bytes: &[u8]) -> Result<NymPacket> {
+        SphinxPacket::from_bytes(bytes).map(NymPacket::Sphinx)
+    }
+
+    pub fn outfox_from_bytes(bytes: &[u8]) -> std::result::Result<NymPacket, OutfoxError> {
+        OutfoxPacket::try_from(bytes).map(NymPacket::Outfox)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            NymPacket::Sphinx(packet) => packet.len(),
+            NymPacket::Outfox(packet) => packet.serialized_len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_outfox(&self) -> bool {
+        matches!(self, NymPacket::Outfox(_))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            NymPacket::Sphinx(packet) => packet.to_bytes(),
+            NymPacket::Outfox(packet) => packet.to_bytes(),
+        }
+    }
+}
+
+impl From<SphinxPacket> for NymPacket {
+    fn from(packet: SphinxPacket) -> Self {
+        NymPacket::Sphinx(packet)
+    }
+}
+
+impl From<OutfoxPacket> for NymPacket {
+    fn from(packet: OutfoxPacket) -> Self {
+        NymPacket::Outfox(packet)
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Name of the environment variable from which the keystore passphrase is read.
+pub const PASSPHRASE_ENV_VAR: &str = "NYM_KEYS_PASSPHRASE";
+
+/// Prefix of the PEM tag of every encrypted key, i.e. an encrypted "ED25519 PRIVATE KEY"
+/// is stored as "ENCRYPTED ED25519 PRIVATE KEY".
+const ENCRYPTED_TAG_PREFIX: &str = "ENCRYPTED ";
+
+const CURRENT_VERSION: u8 = 1;
+
+const MEMORY_COST: u32 = 16 * 1024;
+const ITERATIONS: u32 = 3;
+const PARALLELISM: u32 = 1;
+const OUTPUT_LENGTH: usize = 32;
+
+// as per Argon2 recommendation
+const SALT_LEN: usize = 16;
+
+// AES256GCM Nonce is 96 bit long.
+const IV_LEN: usize = 12;
+
+/// | version (1) | salt (16) | iv (12) |
+const HEADER_LEN: usize = 1 + SALT_LEN + IV_LEN;
+
+/// Passphrase used for encrypting the private keys stored on the disk.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Passphrase(String);
+
+impl Passphrase {
+    pub fn new<S: Into<String>>(passphrase: S) -> Self {
+        Passphrase(passphrase.into())
+    }
+
+    /// Attempts to read the passphrase from the [`PASSPHRASE_ENV_VAR`] environment variable.
+    /// Unset or empty variable results in `None`.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(PASSPHRASE_ENV_VAR)
+            .ok()
+            .filter(|passphrase| !passphrase.is_empty())
+            .map(Passphrase)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl Debug for Passphrase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Passphrase(<redacted>)")
+    }
+}
+
+impl From<String> for Passphrase {
+    fn from(passphrase: String) -> Self {
+        Passphrase(passphrase)
+    }
+}
+
+pub(crate) fn encrypted_tag(tag: &str) -> String {
+    format!("{ENCRYPTED_TAG_PREFIX}{tag}")
+}
+
+/// Returns the tag of the underlying key if the provided tag belongs to an encrypted key.
+pub(crate) fn decrypted_tag(tag: &str) -> Option<&str> {
+    tag.strip_prefix(ENCRYPTED_TAG_PREFIX)
+}
+
+fn derive_cipher(passphrase: &Passphrase, salt: &[u8]) -> io::Result<Aes256Gcm> {
+    // this can only fail if output length is either smaller than 4 or larger than 2^32 - 1 which is not the case here
+    let params = Params::new(MEMORY_COST, ITERATIONS, PARALLELISM, Some(OUTPUT_LENGTH)).unwrap();
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+    let mut key = [0u8; OUTPUT_LENGTH];
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+    // this can't fail as the key has the exact length required by AES256
+    let cipher = Aes256Gcm::new_from_slice(&key).unwrap();
+    key.zeroize();
+    Ok(cipher)
+}
+
+/// Encrypts the key bytes. The (unencrypted) pem tag is bound to the ciphertext so that
+/// one type of key could not be substituted for another.
+pub(crate) fn encrypt(data: &[u8], tag: &str, passphrase: &Passphrase) -> io::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let mut iv = [0u8; IV_LEN];
+    OsRng.fill_bytes(&mut iv);
+
+    let cipher = derive_cipher(passphrase, &salt)?;
+    let payload = Payload {
+        msg: data,
+        aad: tag.as_bytes(),
+    };
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&iv), payload)
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to encrypt the key"))?;
+
+    let mut encrypted = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    encrypted.push(CURRENT_VERSION);
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&iv);
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+pub(crate) fn decrypt(encrypted: &[u8], tag: &str, passphrase: &Passphrase) -> io::Result<Vec<u8>> {
+    if encrypted.len() < HEADER_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the encrypted key is too short",
+        ));
+    }
+    if encrypted[0] != CURRENT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported encrypted key version {}", encrypted[0]),
+        ));
+    }
+
+    let salt = &encrypted[1..1 + SALT_LEN];
+    let iv = &encrypted[1 + SALT_LEN..HEADER_LEN];
+    let ciphertext = &encrypted[HEADER_LEN..];
+
+    let cipher = derive_cipher(passphrase, salt)?;
+    let payload = Payload {
+        msg: ciphertext,
+        aad: tag.as_bytes(),
+    };
+    cipher.decrypt(Nonce::from_slice(iv), payload).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "failed to decrypt the key - is the passphrase correct?",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_can_be_decrypted_with_the_same_passphrase_and_tag() {
+        let passphrase = Passphrase::new("my-secret-passphrase");
+        let data = b"some very secret key bytes";
+
+        let encrypted = encrypt(data, "TEST KEY", &passphrase).unwrap();
+        assert_ne!(&encrypted[HEADER_LEN..], data);
+
+        let decrypted = decrypt(&encrypted, "TEST KEY", &passphrase).unwrap();
+        assert_eq!(decrypted, data);
+
+        assert!(decrypt(&encrypted, "TEST KEY", &Passphrase::new("wrong")).is_err());
+        assert!(decrypt(&encrypted, "OTHER KEY", &passphrase).is_err());
+    }
+
+    #[test]
+    fn tags_of_encrypted_keys_can_be_recovered() {
+        let tag = encrypted_tag("X25519 PRIVATE KEY");
+        assert_eq!(tag, "ENCRYPTED X25519 PRIVATE KEY");
+        assert_eq!(decrypted_tag(&tag), Some("X25519 PRIVATE KEY"));
+        assert_eq!(decrypted_tag("X25519 PRIVATE KEY"), None);
+    }
+}
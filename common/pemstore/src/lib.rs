@@ -3,10 +3,14 @@
 
 use crate::traits::{PemStorableKey, PemStorableKeyPair};
 use pem::{self, Pem};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use zeroize::Zeroize;
 
+pub use encryption::{Passphrase, PASSPHRASE_ENV_VAR};
+
+mod encryption;
 pub mod traits;
 
 pub struct KeyPairPath {
@@ -27,45 +31,165 @@ pub fn load_keypair<T>(paths: &KeyPairPath) -> io::Result<T>
 where
     T: PemStorableKeyPair,
 {
-    let private = load_key::<T::PrivatePemKey>(&paths.private_key_path)?;
+    load_keypair_with_passphrase(paths, None)
+}
+
+/// Loads the keypair whose private key might have been encrypted with the provided passphrase.
+pub fn load_keypair_with_passphrase<T>(
+    paths: &KeyPairPath,
+    passphrase: Option<&Passphrase>,
+) -> io::Result<T>
+where
+    T: PemStorableKeyPair,
+{
+    let private =
+        load_key_with_passphrase::<T::PrivatePemKey>(&paths.private_key_path, passphrase)?;
     let public = load_key::<T::PublicPemKey>(&paths.public_key_path)?;
     Ok(T::from_keys(private, public))
 }
 
 pub fn store_keypair<T>(keypair: &T, paths: &KeyPairPath) -> io::Result<()>
+where
+    T: PemStorableKeyPair,
+{
+    store_keypair_with_passphrase(keypair, paths, None)
+}
+
+/// Stores the keypair, encrypting its private key if the passphrase was provided.
+/// The public key is always stored in plaintext.
+pub fn store_keypair_with_passphrase<T>(
+    keypair: &T,
+    paths: &KeyPairPath,
+    passphrase: Option<&Passphrase>,
+) -> io::Result<()>
 where
     T: PemStorableKeyPair,
 {
     store_key(keypair.public_key(), &paths.public_key_path)?;
-    store_key(keypair.private_key(), &paths.private_key_path)
+    store_key_with_passphrase(keypair.private_key(), &paths.private_key_path, passphrase)
 }
 
 pub fn load_key<T>(path: &Path) -> io::Result<T>
 where
     T: PemStorableKey,
 {
-    let key_pem = read_pem_file(path)?;
+    load_key_with_passphrase(path, None)
+}
 
-    if T::pem_type() != key_pem.tag {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "unexpected key pem tag",
-        ));
-    }
+/// Loads the key that might have been encrypted with the provided passphrase.
+/// Unencrypted keys are loaded regardless of whether the passphrase was provided.
+pub fn load_key_with_passphrase<T>(path: &Path, passphrase: Option<&Passphrase>) -> io::Result<T>
+where
+    T: PemStorableKey,
+{
+    let key_pem = read_pem_file(path)?;
 
-    let key = match T::from_bytes(&key_pem.contents) {
-        Ok(key) => key,
-        Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string())),
+    let mut key_bytes = match encryption::decrypted_tag(&key_pem.tag) {
+        Some(tag) => {
+            if T::pem_type() != tag {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "unexpected key pem tag",
+                ));
+            }
+            let Some(passphrase) = passphrase else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "the key at {} is encrypted, but no passphrase was provided",
+                        path.display()
+                    ),
+                ));
+            };
+            encryption::decrypt(&key_pem.contents, tag, passphrase)?
+        }
+        None => {
+            if T::pem_type() != key_pem.tag {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "unexpected key pem tag",
+                ));
+            }
+            key_pem.contents
+        }
     };
 
-    Ok(key)
+    let key = T::from_bytes(&key_bytes)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()));
+    key_bytes.zeroize();
+    key
 }
 
 pub fn store_key<T>(key: &T, path: &Path) -> io::Result<()>
 where
     T: PemStorableKey,
 {
-    write_pem_file(path, key.to_bytes(), T::pem_type())
+    store_key_with_passphrase(key, path, None)
+}
+
+/// Stores the key, encrypting it if the passphrase was provided.
+pub fn store_key_with_passphrase<T>(
+    key: &T,
+    path: &Path,
+    passphrase: Option<&Passphrase>,
+) -> io::Result<()>
+where
+    T: PemStorableKey,
+{
+    let pem = Pem {
+        tag: T::pem_type().to_string(),
+        contents: key.to_bytes(),
+    };
+    let pem = match passphrase {
+        Some(passphrase) => encrypt_pem(pem, passphrase)?,
+        None => pem,
+    };
+    write_pem_file(path, &pem)
+}
+
+/// Encrypts, in place, the key stored at the provided path. Public keys and keys that are
+/// already encrypted are left untouched. Returns whether the file got encrypted.
+pub fn encrypt_pem_file(path: &Path, passphrase: &Passphrase) -> io::Result<bool> {
+    let pem = read_pem_file(path)?;
+    if encryption::decrypted_tag(&pem.tag).is_some() || pem.tag.contains("PUBLIC") {
+        return Ok(false);
+    }
+
+    let encrypted = encrypt_pem(pem, passphrase)?;
+
+    // write to a temporary file first so that we'd never end up with a partially written key
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    write_pem_file(&tmp_path, &encrypted)?;
+    fs::rename(&tmp_path, path)?;
+
+    Ok(true)
+}
+
+/// Encrypts, in place, all the private and symmetric keys stored in `.pem` files
+/// directly inside the provided directory. Returns paths to all the files that got encrypted.
+pub fn encrypt_pem_directory(dir: &Path, passphrase: &Passphrase) -> io::Result<Vec<PathBuf>> {
+    let mut encrypted = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+            continue;
+        }
+        if encrypt_pem_file(&path, passphrase)? {
+            encrypted.push(path)
+        }
+    }
+    Ok(encrypted)
+}
+
+fn encrypt_pem(mut pem: Pem, passphrase: &Passphrase) -> io::Result<Pem> {
+    let contents = encryption::encrypt(&pem.contents, &pem.tag, passphrase);
+    pem.contents.zeroize();
+    Ok(Pem {
+        tag: encryption::encrypted_tag(&pem.tag),
+        contents: contents?,
+    })
 }
 
 fn read_pem_file(filepath: &Path) -> io::Result<Pem> {
@@ -75,16 +199,12 @@ fn read_pem_file(filepath: &Path) -> io::Result<Pem> {
     pem::parse(&buf).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
 }
 
-fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
+fn write_pem_file(filepath: &Path, pem: &Pem) -> io::Result<()> {
     // ensure the whole directory structure exists
     if let Some(parent_dir) = filepath.parent() {
-        std::fs::create_dir_all(parent_dir)?;
+        fs::create_dir_all(parent_dir)?;
     }
-    let pem = Pem {
-        tag: tag.to_string(),
-        contents: data,
-    };
-    let key = pem::encode(&pem);
+    let key = pem::encode(pem);
 
     let mut file = File::create(filepath)?;
     file.write_all(key.as_bytes())?;
@@ -96,7 +216,6 @@ fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
     // which I feel is too open.
     #[cfg(target_family = "unix")]
     {
-        use std::fs;
         use std::os::unix::fs::PermissionsExt;
 
         let mut permissions = file.metadata()?.permissions();
@@ -106,3 +225,94 @@ fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct DummyKey<const PUBLIC: bool>(Vec<u8>);
+
+    impl<const PUBLIC: bool> PemStorableKey for DummyKey<PUBLIC> {
+        type Error = io::Error;
+
+        fn pem_type() -> &'static str {
+            if PUBLIC {
+                "DUMMY PUBLIC KEY"
+            } else {
+                "DUMMY PRIVATE KEY"
+            }
+        }
+
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error> {
+            Ok(DummyKey(bytes.to_vec()))
+        }
+    }
+
+    #[test]
+    fn encrypted_keys_can_only_be_loaded_with_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("private.pem");
+        let key = DummyKey::<false>(vec![1, 2, 3, 4]);
+        let passphrase = Passphrase::new("foomp");
+
+        store_key_with_passphrase(&key, &path, Some(&passphrase)).unwrap();
+        assert!(read_pem_file(&path).unwrap().tag.starts_with("ENCRYPTED"));
+
+        assert!(load_key::<DummyKey<false>>(&path).is_err());
+        assert!(
+            load_key_with_passphrase::<DummyKey<false>>(&path, Some(&Passphrase::new("bar")))
+                .is_err()
+        );
+        assert_eq!(
+            load_key_with_passphrase::<DummyKey<false>>(&path, Some(&passphrase)).unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn plaintext_keys_can_be_loaded_with_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("private.pem");
+        let key = DummyKey::<false>(vec![1, 2, 3, 4]);
+
+        store_key(&key, &path).unwrap();
+        let passphrase = Passphrase::new("foomp");
+        assert_eq!(
+            load_key_with_passphrase::<DummyKey<false>>(&path, Some(&passphrase)).unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn encrypting_directory_only_affects_non_public_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let private_path = dir.path().join("private.pem");
+        let public_path = dir.path().join("public.pem");
+        let private = DummyKey::<false>(vec![1, 2, 3, 4]);
+        let public = DummyKey::<true>(vec![5, 6, 7, 8]);
+        store_key(&private, &private_path).unwrap();
+        store_key(&public, &public_path).unwrap();
+        std::fs::write(dir.path().join("config.toml"), "foo").unwrap();
+
+        let passphrase = Passphrase::new("foomp");
+        let encrypted = encrypt_pem_directory(dir.path(), &passphrase).unwrap();
+        assert_eq!(encrypted, vec![private_path.clone()]);
+
+        assert!(load_key::<DummyKey<false>>(&private_path).is_err());
+        assert_eq!(load_key::<DummyKey<true>>(&public_path).unwrap(), public);
+        assert_eq!(
+            load_key_with_passphrase::<DummyKey<false>>(&private_path, Some(&passphrase)).unwrap(),
+            private
+        );
+
+        // running it again doesn't do anything
+        assert!(encrypt_pem_directory(dir.path(), &passphrase)
+            .unwrap()
+            .is_empty());
+    }
+}
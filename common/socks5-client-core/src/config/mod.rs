@@ -10,6 +10,7 @@ use nym_config::{NymConfig, OptionalSet};
 use nym_service_providers_common::interface::ProviderInterfaceVersion;
 use nym_socks5_requests::Socks5ProtocolVersion;
 use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::message::MessageCompression;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::path::PathBuf;
@@ -126,6 +127,11 @@ impl Config {
         self
     }
 
+    pub fn with_message_compression(mut self, compress_messages: bool) -> Self {
+        self.socks5.with_message_compression(compress_messages);
+        self
+    }
+
     // helper methods to use `OptionalSet` trait. Those are defined due to very... ehm. 'specific' structure of this config
     // (plz, lets refactor it)
     pub fn with_optional_ext<F, T>(mut self, f: F, val: Option<T>) -> Self
@@ -168,6 +174,10 @@ pub struct Socks5 {
     /// The port on which the client will be listening for incoming requests
     listening_port: u16,
 
+    /// The port on which the prometheus metrics are exposed. A value of 0 disables the metrics server.
+    #[serde(default)]
+    metrics_port: u16,
+
     /// The mix address of the provider to which all requests are going to be sent.
     provider_mix_address: String,
 
@@ -188,6 +198,13 @@ pub struct Socks5 {
     #[serde(default)]
     send_anonymously: bool,
 
+    /// Specifies whether the messages sent to the service provider are going to be compressed,
+    /// reducing the number of sphinx packets required for (compressible) requests.
+    ///
+    /// Note that service providers running older versions are unable to read such messages.
+    #[serde(default)]
+    compress_messages: bool,
+
     #[serde(default)]
     socks5_debug: Socks5Debug,
 }
@@ -196,10 +213,12 @@ impl Socks5 {
     pub fn new<S: Into<String>>(provider_mix_address: S) -> Self {
         Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
+            metrics_port: 0,
             provider_mix_address: provider_mix_address.into(),
             provider_interface_version: ProviderInterfaceVersion::Legacy,
             socks5_protocol_version: Socks5ProtocolVersion::Legacy,
             send_anonymously: false,
+            compress_messages: false,
             socks5_debug: Default::default(),
         }
     }
@@ -208,6 +227,10 @@ impl Socks5 {
         self.listening_port = port;
     }
 
+    pub fn with_metrics_port(&mut self, port: u16) {
+        self.metrics_port = port;
+    }
+
     pub fn with_provider_mix_address(&mut self, address: String) {
         self.provider_mix_address = address;
     }
@@ -224,6 +247,10 @@ impl Socks5 {
         self.send_anonymously = anonymous_replies;
     }
 
+    pub fn with_message_compression(&mut self, compress_messages: bool) {
+        self.compress_messages = compress_messages;
+    }
+
     pub fn get_provider_mix_address(&self) -> Recipient {
         Recipient::try_from_base58_string(&self.provider_mix_address)
             .expect("malformed provider address")
@@ -241,10 +268,22 @@ impl Socks5 {
         self.send_anonymously
     }
 
+    pub fn get_message_compression(&self) -> MessageCompression {
+        if self.compress_messages {
+            MessageCompression::Deflate
+        } else {
+            MessageCompression::None
+        }
+    }
+
     pub fn get_listening_port(&self) -> u16 {
         self.listening_port
     }
 
+    pub fn get_metrics_port(&self) -> u16 {
+        self.metrics_port
+    }
+
     pub fn get_connection_start_surbs(&self) -> u32 {
         self.socks5_debug.connection_start_surbs
     }
@@ -258,10 +297,12 @@ impl Default for Socks5 {
     fn default() -> Self {
         Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
+            metrics_port: 0,
             provider_mix_address: "".into(),
             provider_interface_version: ProviderInterfaceVersion::Legacy,
             socks5_protocol_version: Socks5ProtocolVersion::Legacy,
             send_anonymously: false,
+            compress_messages: false,
             socks5_debug: Default::default(),
         }
     }
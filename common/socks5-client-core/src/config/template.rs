@@ -80,6 +80,15 @@ gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
 # Address of the gateway listener to which all client requests should be sent.
 gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
 
+# Ranked list of additional gateways the client has registered with. If the client fails
+# to reconnect to its current gateway, it switches to the first reachable one out of those.
+{{#each client.fallback_gateway_endpoints }}
+[[client.fallback_gateway_endpoints]]
+gateway_id = '{{this.gateway_id}}'
+gateway_owner = '{{this.gateway_owner}}'
+gateway_listener = '{{this.gateway_listener}}'
+{{/each}}
+
 
 ##### socket config options #####
 
@@ -91,6 +100,9 @@ provider_mix_address = '{{ socks5.provider_mix_address }}'
 # The port on which the client will be listening for incoming requests
 listening_port = {{ socks5.listening_port }}
 
+# The port on which the prometheus metrics will be exposed. A value of 0 disables the metrics server.
+metrics_port = {{ socks5.metrics_port }}
+
 # Specifies whether this client is going to use an anonymous sender tag for communication with the service provider.
 # While this is going to hide its actual address information, it will make the actual communication
 # slower and consume nearly double the bandwidth as it will require sending reply SURBs.
@@ -98,6 +110,12 @@ listening_port = {{ socks5.listening_port }}
 # Note that some service providers might not support this.
 send_anonymously = {{ socks5.send_anonymously }}
 
+# Specifies whether the messages sent to the service provider are going to be compressed,
+# reducing the number of sphinx packets required for (compressible) requests.
+#
+# Note that service providers running older versions are unable to read such messages.
+compress_messages = {{ socks5.compress_messages }}
+
 ##### logging configuration options #####
 
 [logging]
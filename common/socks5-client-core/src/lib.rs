@@ -17,16 +17,17 @@ use nym_client_core::client::base_client::helpers::setup_empty_reply_surb_backen
 #[cfg(not(target_os = "android"))]
 use nym_client_core::client::base_client::non_wasm_helpers;
 use nym_client_core::client::base_client::{
-    BaseClientBuilder, ClientInput, ClientOutput, ClientState,
+    BaseClientBuilder, ClientInput, ClientOutput, ClientState, GatewayConfigPersister,
 };
 use nym_client_core::client::key_manager::KeyManager;
 use nym_client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use nym_config::NymConfig;
 use nym_credential_storage::storage::Storage;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_task::{TaskClient, TaskManager};
 use nym_validator_client::nyxd::QueryNyxdClient;
 use nym_validator_client::Client;
 use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 pub mod config;
 pub mod error;
@@ -74,6 +75,22 @@ impl NymClient {
         }
     }
 
+    // once the client fails over to a different gateway, make it the main one in the config file
+    // (rather than the one in memory that might include command line overrides)
+    fn gateway_config_persister(&self) -> GatewayConfigPersister {
+        let id = self.config.get_base().get_id();
+        Box::new(move |active_gateway, fallback_gateways| {
+            let mut config = Config::load_from_file(&id)?;
+            config
+                .get_base_mut()
+                .set_gateway_endpoint(active_gateway.clone());
+            config
+                .get_base_mut()
+                .set_fallback_gateway_endpoints(fallback_gateways.to_vec());
+            config.save_to_file(None)
+        })
+    }
+
     async fn create_bandwidth_controller<St: Storage>(
         config: &Config,
         storage: St,
@@ -105,7 +122,6 @@ impl NymClient {
         client_input: ClientInput,
         client_output: ClientOutput,
         client_status: ClientState,
-        self_address: Recipient,
         shutdown: TaskClient,
     ) {
         info!("Starting socks5 listener...");
@@ -123,6 +139,7 @@ impl NymClient {
 
         let ClientState {
             shared_lane_queue_lengths,
+            self_address,
             ..
         } = client_status;
 
@@ -139,6 +156,7 @@ impl NymClient {
                 socks5_config.get_send_anonymously(),
                 socks5_config.get_connection_start_surbs(),
                 socks5_config.get_per_request_surbs(),
+                socks5_config.get_message_compression(),
             ),
             shutdown.clone(),
         );
@@ -212,6 +230,8 @@ impl NymClient {
     }
 
     pub async fn start(self) -> Result<TaskManager, Socks5ClientCoreError> {
+        let gateway_config_persister = self.gateway_config_persister();
+
         #[cfg(not(target_os = "android"))]
         let base_builder = BaseClientBuilder::new_from_base_config(
             self.config.get_base(),
@@ -231,21 +251,24 @@ impl NymClient {
                 self.config.get_debug_settings(),
             )
             .await?,
-        );
+        )
+        .with_gateway_config_persister(gateway_config_persister);
 
         #[cfg(target_os = "android")]
-        let base_builder = BaseClientBuilder::<_, Client<QueryNyxdClient>, _>::new_from_base_config(
-            self.config.get_base(),
-            self.key_manager,
-            Some(
-                Self::create_bandwidth_controller(
-                    &self.config,
-                    nym_credential_storage::initialise_ephemeral_storage(),
-                )
-                .await,
-            ),
-            setup_empty_reply_surb_backend(self.config.get_debug_settings()),
-        );
+        let base_builder =
+            BaseClientBuilder::<_, Client<QueryNyxdClient>, _>::new_from_base_config(
+                self.config.get_base(),
+                self.key_manager,
+                Some(
+                    Self::create_bandwidth_controller(
+                        &self.config,
+                        nym_credential_storage::initialise_ephemeral_storage(),
+                    )
+                    .await,
+                ),
+                setup_empty_reply_surb_backend(self.config.get_debug_settings()),
+            )
+            .with_gateway_config_persister(gateway_config_persister);
 
         let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
@@ -258,10 +281,18 @@ impl NymClient {
             client_input,
             client_output,
             client_state,
-            self_address,
             started_client.task_manager.subscribe(),
         );
 
+        let metrics_port = self.config.get_socks5().get_metrics_port();
+        if metrics_port != 0 {
+            nym_metrics::MetricsServer::new(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                metrics_port,
+            ))
+            .start(started_client.task_manager.subscribe());
+        }
+
         info!("Client startup finished!");
         info!("The address of this client is: {}", self_address);
 
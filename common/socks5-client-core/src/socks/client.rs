@@ -3,20 +3,23 @@
 use super::authentication::{AuthenticationMethods, Authenticator, User};
 use super::request::{SocksCommand, SocksRequest};
 use super::types::{ResponseCodeV4, ResponseCodeV5, SocksProxyError};
+use super::udp;
 use super::{SocksVersion, RESERVED, SOCKS4_VERSION, SOCKS5_VERSION};
 use futures::channel::mpsc;
 use futures::task::{Context, Poll};
+use futures::StreamExt;
 use log::*;
 use nym_client_core::client::inbound_messages::{InputMessage, InputMessageSender};
 use nym_service_providers_common::interface::{ProviderInterfaceVersion, RequestVersion};
 use nym_socks5_proxy_helpers::connection_controller::{
-    ConnectionReceiver, ControllerCommand, ControllerSender,
+    ConnectionReceiver, ConnectionSender, ControllerCommand, ControllerSender,
 };
 use nym_socks5_proxy_helpers::proxy_runner::ProxyRunner;
 use nym_socks5_requests::{
     ConnectionId, RemoteAddress, Socks5ProtocolVersion, Socks5ProviderRequest, Socks5Request,
 };
 use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::message::MessageCompression;
 use nym_task::connections::{LaneQueueLengths, TransmissionLane};
 use nym_task::TaskClient;
 use pin_project::pin_project;
@@ -25,7 +28,10 @@ use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
-use tokio::{self, net::TcpStream};
+use tokio::{
+    self,
+    net::{TcpStream, UdpSocket},
+};
 
 #[pin_project(project = StateProject)]
 enum StreamState {
@@ -66,6 +72,17 @@ impl StreamState {
         }
     }
 
+    /// Returns the local address that this stream is bound to.
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            StreamState::RunningProxy => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "stream is being used to run the proxy",
+            )),
+            StreamState::Available(ref stream) => stream.local_addr(),
+        }
+    }
+
     async fn shutdown(&mut self) -> io::Result<()> {
         // shutdown should only be called if proxy is not being run. If it is, there's some bug
         // somewhere
@@ -136,6 +153,7 @@ pub(crate) struct Config {
     use_surbs_for_responses: bool,
     connection_start_surbs: u32,
     per_request_surbs: u32,
+    message_compression: MessageCompression,
 }
 
 impl Config {
@@ -145,6 +163,7 @@ impl Config {
         use_surbs_for_responses: bool,
         connection_start_surbs: u32,
         per_request_surbs: u32,
+        message_compression: MessageCompression,
     ) -> Self {
         Self {
             provider_interface_version,
@@ -152,6 +171,7 @@ impl Config {
             use_surbs_for_responses,
             connection_start_surbs,
             per_request_surbs,
+            message_compression,
         }
     }
 
@@ -345,7 +365,8 @@ impl SocksClient {
             msg.into_bytes(),
             self.config.connection_start_surbs,
             TransmissionLane::ConnectionId(self.connection_id),
-        );
+        )
+        .with_compression(self.config.message_compression);
         self.input_sender
             .send(input_message)
             .await
@@ -367,7 +388,8 @@ impl SocksClient {
             self.service_provider,
             msg.into_bytes(),
             TransmissionLane::ConnectionId(self.connection_id),
-        );
+        )
+        .with_compression(self.config.message_compression);
         self.input_sender
             .send(input_message)
             .await
@@ -402,6 +424,7 @@ impl SocksClient {
         let anonymous = self.config.use_surbs_for_responses;
         let per_request_surbs = self.config.per_request_surbs;
         let request_version = self.config.request_version();
+        let message_compression = self.config.message_compression;
 
         let recipient = self.service_provider;
         let (stream, _) = ProxyRunner::new(
@@ -426,7 +449,7 @@ impl SocksClient {
                 provider_request,
             );
             let lane = TransmissionLane::ConnectionId(conn_id);
-            if anonymous {
+            let input_message = if anonymous {
                 InputMessage::new_anonymous(
                     recipient,
                     provider_message.into_bytes(),
@@ -435,7 +458,8 @@ impl SocksClient {
                 )
             } else {
                 InputMessage::new_regular(recipient, provider_message.into_bytes(), lane)
-            }
+            };
+            input_message.with_compression(message_compression)
         })
         .await
         .into_inner();
@@ -491,13 +515,208 @@ impl SocksClient {
                 );
             }
 
+            SocksCommand::UdpAssociate => {
+                // UDP ASSOCIATE does not exist in SOCKS4
+                if version == &SocksVersion::V4 {
+                    return Err(ResponseCodeV5::CommandNotSupported.into());
+                }
+
+                self.run_udp_association(&request, mix_sender, mix_receiver)
+                    .await?;
+            }
+
             SocksCommand::Bind => unimplemented!(), // not handled
-            SocksCommand::UdpAssociate => unimplemented!(), // not handled
         };
 
         Ok(())
     }
 
+    /// Wraps the request for the service provider into an `InputMessage` in the same way
+    /// the proxied data is, i.e. either by attaching reply SURBs or our own address.
+    fn provider_input_message(&self, request: Socks5Request, reply_surbs: u32) -> InputMessage {
+        let provider_message = Socks5ProviderRequest::new_provider_data(
+            self.config.provider_interface_version,
+            request,
+        );
+        let lane = TransmissionLane::ConnectionId(self.connection_id);
+        let input_message = if self.config.use_surbs_for_responses {
+            InputMessage::new_anonymous(
+                self.service_provider,
+                provider_message.into_bytes(),
+                reply_surbs,
+                lane,
+            )
+        } else {
+            InputMessage::new_regular(self.service_provider, provider_message.into_bytes(), lane)
+        };
+        input_message.with_compression(self.config.message_compression)
+    }
+
+    async fn send_to_mixnet(&mut self, input_message: InputMessage) {
+        self.input_sender
+            .send(input_message)
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    async fn send_udp_associate_to_mixnet(&mut self) {
+        let return_address = if self.config.use_surbs_for_responses {
+            None
+        } else {
+            Some(self.self_address)
+        };
+        let req = Socks5Request::new_udp_associate(
+            self.config.socks5_protocol_version,
+            self.connection_id,
+            return_address,
+        );
+        let input_message = self.provider_input_message(req, self.config.connection_start_surbs);
+        self.send_to_mixnet(input_message).await
+    }
+
+    async fn send_datagram_to_mixnet(&mut self, remote_address: RemoteAddress, data: Vec<u8>) {
+        let req = Socks5Request::new_send_datagram(
+            self.config.socks5_protocol_version,
+            self.connection_id,
+            remote_address,
+            data,
+        );
+        let input_message = self.provider_input_message(req, self.config.per_request_surbs);
+        self.send_to_mixnet(input_message).await
+    }
+
+    async fn send_close_association_to_mixnet(&mut self) {
+        let req = Socks5Request::new_close_association(
+            self.config.socks5_protocol_version,
+            self.connection_id,
+        );
+        // there's no point in sending any more surbs, since nothing is going to be sent back
+        let input_message = self.provider_input_message(req, 0);
+        self.send_to_mixnet(input_message).await
+    }
+
+    /// Relays datagrams between the local UDP socket and the service provider for as long as
+    /// the TCP connection the UDP ASSOCIATE request arrived on stays open.
+    async fn run_udp_association(
+        &mut self,
+        request: &SocksRequest,
+        mix_sender: ConnectionSender,
+        mut mix_receiver: ConnectionReceiver,
+    ) -> Result<(), SocksProxyError> {
+        let peer_addr = self
+            .stream
+            .peer_addr()
+            .map_err(|source| SocksProxyError::PeerAddrExtractionFailure { source })?;
+        let local_addr = self
+            .stream
+            .local_addr()
+            .map_err(|source| SocksProxyError::UdpRelayBindFailure { source })?;
+
+        let socket = UdpSocket::bind(SocketAddr::new(local_addr.ip(), 0))
+            .await
+            .map_err(|source| SocksProxyError::UdpRelayBindFailure { source })?;
+        let relay_addr = socket
+            .local_addr()
+            .map_err(|source| SocksProxyError::UdpRelayBindFailure { source })?;
+
+        self.acknowledge_udp_associate(relay_addr).await?;
+
+        self.started_proxy = true;
+        self.controller_sender
+            .unbounded_send(ControllerCommand::Insert {
+                connection_id: self.connection_id,
+                connection_sender: mix_sender,
+            })
+            .unwrap();
+        self.send_udp_associate_to_mixnet().await;
+
+        info!(
+            "Starting UDP association via {relay_addr} (id: {})",
+            self.connection_id
+        );
+
+        // the client might not know the address it's going to send the datagrams from,
+        // in which case we only learn it once we receive the first one
+        let mut expected_source = udp::ExpectedSource::new(
+            &request.addr_type,
+            &request.addr,
+            request.port,
+            peer_addr.ip(),
+        );
+        let mut client_udp_addr = None;
+        let mut buf = vec![0u8; udp::MAX_DATAGRAM_SIZE];
+        let mut control_buf = [0u8; 64];
+        let mut shutdown = self.shutdown_listener.clone();
+
+        loop {
+            tokio::select! {
+                // the association terminates when the TCP connection it arrived on terminates
+                read = self.stream.read(&mut control_buf) => match read {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => trace!("ignoring data received on the UDP association control connection"),
+                },
+                received = socket.recv_from(&mut buf) => {
+                    let (n, source) = match received {
+                        Ok(received) => received,
+                        Err(err) => {
+                            debug!("failed to receive datagram on the UDP relay: {err}");
+                            continue;
+                        }
+                    };
+                    // only accept datagrams coming from the client that requested the association
+                    if !expected_source.accept(source) {
+                        debug!("dropping datagram from unexpected source {source}");
+                        continue;
+                    }
+                    client_udp_addr = Some(source);
+
+                    match udp::parse_datagram(&buf[..n]) {
+                        Ok((remote_address, data)) => {
+                            self.send_datagram_to_mixnet(remote_address, data.to_vec()).await
+                        }
+                        Err(err) => debug!("dropping datagram: {err}"),
+                    }
+                },
+                datagram = mix_receiver.next() => {
+                    let Some(datagram) = datagram else {
+                        break;
+                    };
+                    let Some(client_udp_addr) = client_udp_addr else {
+                        debug!("received a datagram before the client has sent anything - dropping it");
+                        continue;
+                    };
+                    if let Err(err) = socket.send_to(&datagram.payload, client_udp_addr).await {
+                        debug!("failed to send datagram to {client_udp_addr}: {err}");
+                    }
+                },
+                _ = shutdown.recv() => {
+                    log::trace!("UDP association: Received shutdown");
+                    break;
+                }
+            }
+        }
+
+        self.send_close_association_to_mixnet().await;
+        info!("UDP association is finished (id: {})", self.connection_id);
+        Ok(())
+    }
+
+    /// Writes a Socks5 reply containing the address of the UDP relay back to the requesting
+    /// client's TCP stream.
+    async fn acknowledge_udp_associate(
+        &mut self,
+        relay_addr: SocketAddr,
+    ) -> Result<(), SocksProxyError> {
+        let reply: Vec<u8> = [SOCKS5_VERSION, ResponseCodeV5::Success as u8, RESERVED]
+            .into_iter()
+            .chain(udp::encode_address(relay_addr))
+            .collect();
+        self.stream
+            .write_all(&reply)
+            .await
+            .map_err(|source| SocksProxyError::SocketWriteError { source })
+    }
+
     /// Writes a Socks5 header back to the requesting client's TCP stream,
     /// basically saying "I acknowledge your request and am dealing with it".
     async fn acknowledge_socks5(&mut self) {
@@ -7,12 +7,14 @@ use nym_client_core::client::received_buffer::{
     ReceivedBufferMessage, ReceivedBufferRequestSender,
 };
 use nym_service_providers_common::interface::{ControlResponse, ResponseContent};
-use nym_socks5_proxy_helpers::connection_controller::ControllerSender;
+use nym_socks5_proxy_helpers::connection_controller::{ControllerCommand, ControllerSender};
 use nym_socks5_requests::{Socks5ProviderResponse, Socks5Response, Socks5ResponseContent};
 use nym_sphinx::receiver::ReconstructedMessage;
 use nym_task::TaskClient;
+use std::net::SocketAddr;
 
 use crate::error::Socks5ClientCoreError;
+use crate::socks::udp;
 
 pub(crate) struct MixnetResponseListener {
     buffer_requester: ReceivedBufferRequestSender,
@@ -85,6 +87,22 @@ impl MixnetResponseListener {
                     .unwrap();
                 Ok(())
             }
+            Socks5ResponseContent::Datagram(response) => {
+                let Ok(source) = response.source_addr.parse::<SocketAddr>() else {
+                    warn!(
+                        "received a datagram with invalid source address {} - dropping it",
+                        response.source_addr
+                    );
+                    return Ok(());
+                };
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::SendDatagram {
+                        connection_id: response.connection_id,
+                        data: udp::encode_datagram(source, &response.data),
+                    })
+                    .unwrap();
+                Ok(())
+            }
         }
     }
 
@@ -7,6 +7,7 @@ use crate::socks::client;
 use log::*;
 use nym_client_core::client::{
     inbound_messages::InputMessageSender, received_buffer::ReceivedBufferRequestSender,
+    self_address::SelfAddressAccessor,
 };
 use nym_socks5_proxy_helpers::connection_controller::Controller;
 use nym_sphinx::addressing::clients::Recipient;
@@ -21,7 +22,7 @@ pub struct SphinxSocksServer {
     authenticator: Authenticator,
     listening_address: SocketAddr,
     service_provider: Recipient,
-    self_address: Recipient,
+    self_address: SelfAddressAccessor,
     client_config: client::Config,
     lane_queue_lengths: LaneQueueLengths,
     shutdown: TaskClient,
@@ -33,7 +34,7 @@ impl SphinxSocksServer {
         port: u16,
         authenticator: Authenticator,
         service_provider: Recipient,
-        self_address: Recipient,
+        self_address: SelfAddressAccessor,
         lane_queue_lengths: LaneQueueLengths,
         client_config: client::Config,
         shutdown: TaskClient,
@@ -101,7 +102,7 @@ impl SphinxSocksServer {
                         input_sender.clone(),
                         &self.service_provider,
                         controller_sender.clone(),
-                        &self.self_address,
+                        &self.self_address.current(),
                         self.lane_queue_lengths.clone(),
                         self.shutdown.clone(),
                     );
@@ -75,6 +75,15 @@ pub enum SocksProxyError {
         source: FromUtf8Error,
     },
 
+    #[error("failed to bind the UDP relay socket: {source}")]
+    UdpRelayBindFailure {
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("received malformed UDP datagram: {reason}")]
+    MalformedDatagram { reason: &'static str },
+
     #[error(transparent)]
     Socks5ResponseFailure(#[from] ResponseCodeV5),
 
@@ -0,0 +1,237 @@
+#![forbid(unsafe_code)]
+
+use super::types::{AddrType, SocksProxyError};
+use super::utils as socks_utils;
+use nym_socks5_requests::RemoteAddress;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Size of the largest possible UDP payload.
+pub(crate) const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Parse a UDP request datagram sent by the SOCKS5 client to the relay.
+/// From: https://www.rfc-editor.org/rfc/rfc1928#section-7
+///
+/// +----+------+------+----------+----------+----------+
+/// |RSV | FRAG | ATYP | DST.ADDR | DST.PORT |   DATA   |
+/// +----+------+------+----------+----------+----------+
+/// | 2  |  1   |  1   | Variable |    2     | Variable |
+/// +----+------+------+----------+----------+----------+
+///
+/// Returns the destination address alongside the actual payload.
+pub(crate) fn parse_datagram(b: &[u8]) -> Result<(RemoteAddress, &[u8]), SocksProxyError> {
+    if b.len() < 4 {
+        return Err(SocksProxyError::MalformedDatagram {
+            reason: "the header is too short",
+        });
+    }
+
+    // we don't support reassembling fragmented datagrams, which the RFC explicitly allows
+    if b[2] != 0 {
+        return Err(SocksProxyError::MalformedDatagram {
+            reason: "fragmentation is not supported",
+        });
+    }
+
+    let Some(addr_type) = AddrType::from(b[3] as usize) else {
+        return Err(SocksProxyError::MalformedDatagram {
+            reason: "unknown address type",
+        });
+    };
+
+    let (addr, rest) = match addr_type {
+        AddrType::V4 => split_checked(&b[4..], 4)?,
+        AddrType::V6 => split_checked(&b[4..], 16)?,
+        AddrType::Domain => {
+            let Some((domain_length, rest)) = b[4..].split_first() else {
+                return Err(SocksProxyError::MalformedDatagram {
+                    reason: "missing domain length",
+                });
+            };
+            split_checked(rest, *domain_length as usize)?
+        }
+    };
+    let (port, data) = split_checked(rest, 2)?;
+    let port = u16::from_be_bytes([port[0], port[1]]);
+
+    let remote_address = match addr_type {
+        AddrType::V4 => {
+            let ip = Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]);
+            SocketAddr::new(IpAddr::V4(ip), port).to_string()
+        }
+        AddrType::V6 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(addr);
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::from(octets)), port).to_string()
+        }
+        AddrType::Domain => format!(
+            "{}:{}",
+            socks_utils::pretty_print_addr(&addr_type, addr),
+            port
+        ),
+    };
+
+    Ok((remote_address, data))
+}
+
+/// Wraps the datagram received from `source` with the header expected by the SOCKS5 client.
+pub(crate) fn encode_datagram(source: SocketAddr, data: &[u8]) -> Vec<u8> {
+    let mut datagram = vec![0, 0, 0];
+    datagram.extend_from_slice(&encode_address(source));
+    datagram.extend_from_slice(data);
+    datagram
+}
+
+/// Encodes the address in the `ATYP || ADDR || PORT` format used by both the replies
+/// and the UDP request headers.
+pub(crate) fn encode_address(address: SocketAddr) -> Vec<u8> {
+    let mut encoded = match address.ip() {
+        IpAddr::V4(ip) => std::iter::once(AddrType::V4 as u8)
+            .chain(ip.octets())
+            .collect::<Vec<_>>(),
+        IpAddr::V6(ip) => std::iter::once(AddrType::V6 as u8)
+            .chain(ip.octets())
+            .collect::<Vec<_>>(),
+    };
+    encoded.extend_from_slice(&address.port().to_be_bytes());
+    encoded
+}
+
+/// Source of the datagrams the relay is willing to accept, as declared by the client
+/// in its UDP ASSOCIATE request.
+/// From: https://www.rfc-editor.org/rfc/rfc1928#section-7
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExpectedSource {
+    ip: IpAddr,
+    port: Option<u16>,
+}
+
+impl ExpectedSource {
+    /// If the client did not specify its address (i.e. it sent all zeros or a domain),
+    /// we expect the datagrams to come from the host of the control connection instead.
+    /// Similarly, if the port is unspecified, it gets pinned to the one of the first accepted datagram.
+    pub(crate) fn new(addr_type: &AddrType, addr: &[u8], port: u16, peer_ip: IpAddr) -> Self {
+        let declared = match addr_type {
+            AddrType::V4 if addr.len() == 4 => Some(IpAddr::V4(Ipv4Addr::new(
+                addr[0], addr[1], addr[2], addr[3],
+            ))),
+            AddrType::V6 if addr.len() == 16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(addr);
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        };
+
+        ExpectedSource {
+            ip: declared
+                .filter(|ip| !ip.is_unspecified())
+                .unwrap_or(peer_ip),
+            port: (port != 0).then_some(port),
+        }
+    }
+
+    /// Checks whether the datagram received from `source` should be accepted and, if so,
+    /// binds the association to its port (if it wasn't known yet).
+    pub(crate) fn accept(&mut self, source: SocketAddr) -> bool {
+        if source.ip() != self.ip {
+            return false;
+        }
+        match self.port {
+            Some(port) => port == source.port(),
+            None => {
+                self.port = Some(source.port());
+                true
+            }
+        }
+    }
+}
+
+fn split_checked(b: &[u8], at: usize) -> Result<(&[u8], &[u8]), SocksProxyError> {
+    if b.len() < at {
+        return Err(SocksProxyError::MalformedDatagram {
+            reason: "the header is too short",
+        });
+    }
+    Ok(b.split_at(at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_ipv4_datagram() {
+        let datagram = [0, 0, 0, 1, 1, 1, 1, 1, 0, 53, 42, 42];
+        let (address, data) = parse_datagram(&datagram).unwrap();
+        assert_eq!("1.1.1.1:53", address);
+        assert_eq!(&[42, 42], data);
+    }
+
+    #[test]
+    fn parsing_ipv6_datagram() {
+        let mut datagram = vec![0, 0, 0, 4];
+        datagram.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        datagram.extend_from_slice(&[0, 53, 42]);
+
+        let (address, data) = parse_datagram(&datagram).unwrap();
+        assert_eq!("[::1]:53", address);
+        assert_eq!(&[42], data);
+    }
+
+    #[test]
+    fn parsing_domain_datagram() {
+        let mut datagram = vec![0, 0, 0, 3, 7];
+        datagram.extend_from_slice(b"foo.com");
+        datagram.extend_from_slice(&[1, 187]);
+
+        let (address, data) = parse_datagram(&datagram).unwrap();
+        assert_eq!("foo.com:443", address);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn parsing_rejects_malformed_datagrams() {
+        // too short
+        assert!(parse_datagram(&[0, 0, 0]).is_err());
+        // fragmented
+        assert!(parse_datagram(&[0, 0, 1, 1, 1, 1, 1, 1, 0, 53]).is_err());
+        // unknown address type
+        assert!(parse_datagram(&[0, 0, 0, 2, 1, 1, 1, 1, 0, 53]).is_err());
+        // missing port
+        assert!(parse_datagram(&[0, 0, 0, 1, 1, 1, 1, 1, 0]).is_err());
+        // truncated domain
+        assert!(parse_datagram(&[0, 0, 0, 3, 7, 102, 111]).is_err());
+    }
+
+    #[test]
+    fn expected_source_is_enforced() {
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // unspecified address and port
+        let mut expected = ExpectedSource::new(&AddrType::V4, &[0, 0, 0, 0], 0, peer);
+        assert!(!expected.accept("10.0.0.2:1234".parse().unwrap()));
+        assert!(expected.accept("10.0.0.1:1234".parse().unwrap()));
+        // the port got pinned
+        assert!(!expected.accept("10.0.0.1:1235".parse().unwrap()));
+        assert!(expected.accept("10.0.0.1:1234".parse().unwrap()));
+
+        // explicit address and port
+        let mut expected = ExpectedSource::new(&AddrType::V4, &[10, 0, 0, 3], 4321, peer);
+        assert!(!expected.accept("10.0.0.1:4321".parse().unwrap()));
+        assert!(!expected.accept("10.0.0.3:1234".parse().unwrap()));
+        assert!(expected.accept("10.0.0.3:4321".parse().unwrap()));
+
+        // domains fall back to the address of the control connection
+        let mut expected = ExpectedSource::new(&AddrType::Domain, b"foo.com", 0, peer);
+        assert!(expected.accept("10.0.0.1:1234".parse().unwrap()));
+    }
+
+    #[test]
+    fn encoded_datagram_can_be_parsed() {
+        let source: SocketAddr = "8.8.8.8:53".parse().unwrap();
+        let encoded = encode_datagram(source, &[1, 2, 3]);
+        let (address, data) = parse_datagram(&encoded).unwrap();
+        assert_eq!(source.to_string(), address);
+        assert_eq!(&[1, 2, 3], data);
+    }
+}
@@ -41,6 +41,15 @@ impl OrderedMessageBuffer {
         self.messages.insert(message.index, message);
     }
 
+    /// Returns the number of messages stored in the buffer that haven't been read yet.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
     /// Returns `Option<Vec<u8>>` where it's `Some(bytes)` if there is gapless
     /// ordered data in the buffer, and `None` if the buffer is empty or has
     /// gaps in the contained data.
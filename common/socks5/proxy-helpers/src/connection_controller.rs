@@ -39,11 +39,22 @@ pub enum ControllerCommand {
     Remove {
         connection_id: ConnectionId,
     },
+    /// Closes the local socket of the connection straight away,
+    /// discarding any data that is still waiting to be put in order.
+    Close {
+        connection_id: ConnectionId,
+    },
     Send {
         connection_id: ConnectionId,
         data: Vec<u8>,
         is_closed: bool,
     },
+    /// Unlike `Send`, the data is not subject to any reordering and is immediately forwarded
+    /// to the connection, which is what UDP associations expect.
+    SendDatagram {
+        connection_id: ConnectionId,
+        data: Vec<u8>,
+    },
 }
 
 impl From<NetworkData> for ControllerCommand {
@@ -184,8 +195,31 @@ impl Controller {
         }
     }
 
+    fn close_connection(&mut self, conn_id: ConnectionId) {
+        let Some(active_connection) = self.active_connections.get_mut(&conn_id) else {
+            debug!("Tried to close non-existing connection {conn_id}");
+            return;
+        };
+        active_connection.is_closed = true;
+        if let Some(connection_sender) = active_connection.connection_sender.take() {
+            if let Err(err) = connection_sender.unbounded_send(ConnectionMessage {
+                payload: Vec::new(),
+                socket_closed: true,
+            }) {
+                debug!("Failed to close connection {conn_id}: {err}");
+            }
+        }
+    }
+
     fn send_to_connection(&mut self, conn_id: ConnectionId, payload: Vec<u8>, is_closed: bool) {
         if let Some(active_connection) = self.active_connections.get_mut(&conn_id) {
+            if active_connection.connection_sender.is_none() {
+                debug!(
+                    "Connection {conn_id} got closed - dropping {} bytes",
+                    payload.len()
+                );
+                return;
+            }
             if !payload.is_empty() {
                 active_connection.write_to_buf(payload, is_closed);
             } else if !is_closed {
@@ -239,6 +273,25 @@ impl Controller {
         }
     }
 
+    fn send_datagram_to_connection(&mut self, conn_id: ConnectionId, payload: Vec<u8>) {
+        let Some(connection_sender) = self
+            .active_connections
+            .get_mut(&conn_id)
+            .and_then(|active_connection| active_connection.connection_sender.as_mut())
+        else {
+            // datagrams are allowed to get lost, so there's no point in buffering them
+            debug!("Received a datagram for unknown association {conn_id} - dropping it");
+            return;
+        };
+
+        if let Err(err) = connection_sender.unbounded_send(ConnectionMessage {
+            payload,
+            socket_closed: false,
+        }) {
+            debug!("Failed to forward datagram to association {conn_id}: {err}");
+        }
+    }
+
     pub async fn run(&mut self) {
         loop {
             tokio::select! {
@@ -250,6 +303,10 @@ impl Controller {
                         self.insert_connection(connection_id, connection_sender)
                     }
                     Some(ControllerCommand::Remove{ connection_id }) => self.remove_connection(connection_id),
+                    Some(ControllerCommand::Close{ connection_id }) => self.close_connection(connection_id),
+                    Some(ControllerCommand::SendDatagram{ connection_id, data }) => {
+                        self.send_datagram_to_connection(connection_id, data)
+                    }
                     None => {
                         log::trace!("SOCKS5 Controller: Stopping since channel closed");
                         break;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::MixProxySender;
+use super::ReadThrottle;
 use super::SHUTDOWN_TIMEOUT;
 use crate::available_reader::AvailableReader;
 use bytes::Bytes;
@@ -45,6 +46,7 @@ async fn deal_with_data<F, S>(
     mix_sender: &MixProxySender<S>,
     adapter_fn: F,
     lane_queue_lengths: Option<LaneQueueLengths>,
+    read_throttle: Option<&ReadThrottle>,
 ) -> bool
 where
     F: Fn(ConnectionId, Vec<u8>, bool) -> S,
@@ -70,6 +72,8 @@ where
         is_finished
     );
 
+    let read_len = read_data.len();
+
     // if we're sending through the mixnet increase the sequence number...
     let ordered_msg = message_sender.wrap_message(read_data.to_vec()).into_bytes();
     log::trace!(
@@ -89,6 +93,15 @@ where
         .await
         .expect("InputMessageReceiver has stopped receiving!");
 
+    // Don't read anything more from the socket until we're allowed to send it
+    if let Some(read_throttle) = read_throttle {
+        let delay = read_throttle.delay(connection_id, read_len);
+        if !is_finished && !delay.is_zero() {
+            trace!("({connection_id}) pausing reading for {delay:?}");
+            sleep(delay).await;
+        }
+    }
+
     if is_finished {
         // After sending, if this is the last message, wait until we've actually transmitted the data
         // in the `OutQueueControl` and the lane is empty.
@@ -170,6 +183,7 @@ pub(super) async fn run_inbound<F, S>(
     adapter_fn: F,
     shutdown_notify: Arc<Notify>,
     lane_queue_lengths: Option<LaneQueueLengths>,
+    read_throttle: Option<ReadThrottle>,
     mut shutdown_listener: TaskClient,
 ) -> OwnedReadHalf
 where
@@ -193,7 +207,8 @@ where
                     &mut message_sender,
                     &mix_sender,
                     &adapter_fn,
-                    lane_queue_lengths.clone()
+                    lane_queue_lengths.clone(),
+                    read_throttle.as_ref(),
                 ).await {
                     break
                 }
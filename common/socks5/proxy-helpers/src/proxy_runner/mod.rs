@@ -33,6 +33,29 @@ impl From<(Vec<u8>, bool)> for ProxyMessage {
 pub type MixProxySender<S> = tokio::sync::mpsc::Sender<S>;
 pub type MixProxyReader<S> = tokio::sync::mpsc::Receiver<S>;
 
+/// Decides how long to pause reading from the local socket after given amount of data was read.
+#[derive(Clone)]
+pub struct ReadThrottle(Arc<dyn Fn(ConnectionId, usize) -> Duration + Send + Sync>);
+
+impl ReadThrottle {
+    pub fn new<F>(throttle: F) -> Self
+    where
+        F: Fn(ConnectionId, usize) -> Duration + Send + Sync + 'static,
+    {
+        ReadThrottle(Arc::new(throttle))
+    }
+
+    pub(crate) fn delay(&self, connection_id: ConnectionId, read: usize) -> Duration {
+        (self.0)(connection_id, read)
+    }
+}
+
+impl Debug for ReadThrottle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ReadThrottle")
+    }
+}
+
 // TODO: when we finally get to implementing graceful shutdown,
 // on Drop this guy should tell the remote that it's closed now
 #[derive(Debug)]
@@ -48,6 +71,7 @@ pub struct ProxyRunner<S> {
     remote_source_address: String,
     connection_id: ConnectionId,
     lane_queue_lengths: Option<LaneQueueLengths>,
+    read_throttle: Option<ReadThrottle>,
 
     // Listens to shutdown commands from higher up
     shutdown_listener: TaskClient,
@@ -76,10 +100,18 @@ where
             remote_source_address,
             connection_id,
             lane_queue_lengths,
+            read_throttle: None,
             shutdown_listener,
         }
     }
 
+    /// Delays reading more data from the local socket according to the provided throttle.
+    #[must_use]
+    pub fn with_read_throttle(mut self, read_throttle: ReadThrottle) -> Self {
+        self.read_throttle = Some(read_throttle);
+        self
+    }
+
     // The `adapter_fn` is used to transform whatever was read into appropriate
     // request/response as required by entity running particular side of the proxy.
     pub async fn run<F>(mut self, adapter_fn: F) -> Self
@@ -99,6 +131,7 @@ where
             adapter_fn,
             Arc::clone(&shutdown_notify),
             self.lane_queue_lengths.clone(),
+            self.read_throttle.clone(),
             self.shutdown_listener.clone(),
         );
 
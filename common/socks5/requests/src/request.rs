@@ -15,6 +15,9 @@ pub type RemoteAddress = String;
 pub enum RequestFlag {
     Connect = 0,
     Send = 1,
+    UdpAssociate = 2,
+    SendDatagram = 3,
+    CloseAssociation = 4,
 }
 
 impl TryFrom<u8> for RequestFlag {
@@ -24,6 +27,9 @@ impl TryFrom<u8> for RequestFlag {
         match value {
             _ if value == (RequestFlag::Connect as u8) => Ok(Self::Connect),
             _ if value == (RequestFlag::Send as u8) => Ok(Self::Send),
+            _ if value == (RequestFlag::UdpAssociate as u8) => Ok(Self::UdpAssociate),
+            _ if value == (RequestFlag::SendDatagram as u8) => Ok(Self::SendDatagram),
+            _ if value == (RequestFlag::CloseAssociation as u8) => Ok(Self::CloseAssociation),
             value => Err(RequestDeserializationError::UnknownRequestFlag { value }),
         }
     }
@@ -74,6 +80,19 @@ pub struct SendRequest {
     pub local_closed: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct UdpAssociateRequest {
+    pub conn_id: ConnectionId,
+    pub return_address: Option<Recipient>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatagramRequest {
+    pub conn_id: ConnectionId,
+    pub remote_addr: RemoteAddress,
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Socks5Request {
     pub protocol_version: Socks5ProtocolVersion,
@@ -89,6 +108,7 @@ impl Serializable for Socks5Request {
     // the updated formats use
     // 3 (Socks5ProtocolVersion) || 0 (RequestFlag::Connect) || <data> for connect requests
     // 3 (Socks5ProtocolVersion) || 1 (RequestFlag::Send) || <data> for send requests
+    // (and analogously for the udp requests which have never existed in the legacy format)
     // in both cases, the actual data is serialized the same way, so the process is quite straight forward
     fn into_bytes(self) -> Vec<u8> {
         if let Some(version) = self.protocol_version.as_u8() {
@@ -166,6 +186,39 @@ impl Socks5Request {
             content: Socks5RequestContent::new_send(conn_id, data, local_closed),
         }
     }
+
+    pub fn new_udp_associate(
+        protocol_version: Socks5ProtocolVersion,
+        conn_id: ConnectionId,
+        return_address: Option<Recipient>,
+    ) -> Socks5Request {
+        Socks5Request {
+            protocol_version,
+            content: Socks5RequestContent::new_udp_associate(conn_id, return_address),
+        }
+    }
+
+    pub fn new_send_datagram(
+        protocol_version: Socks5ProtocolVersion,
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5Request {
+        Socks5Request {
+            protocol_version,
+            content: Socks5RequestContent::new_send_datagram(conn_id, remote_addr, data),
+        }
+    }
+
+    pub fn new_close_association(
+        protocol_version: Socks5ProtocolVersion,
+        conn_id: ConnectionId,
+    ) -> Socks5Request {
+        Socks5Request {
+            protocol_version,
+            content: Socks5RequestContent::CloseAssociation(conn_id),
+        }
+    }
 }
 
 /// A request from a SOCKS5 client that a Nym Socks5 service provider should
@@ -179,6 +232,16 @@ pub enum Socks5RequestContent {
 
     /// Re-use an existing TCP connection, sending more request data up it.
     Send(SendRequest),
+
+    /// Start a new UDP association, i.e. bind a fresh UDP socket for relaying datagrams.
+    /// All datagrams received on this `ConnectionId` should come back to the specified `Recipient`
+    UdpAssociate(Box<UdpAssociateRequest>),
+
+    /// Send a single datagram to the specified `RemoteAddress` via an existing UDP association.
+    SendDatagram(DatagramRequest),
+
+    /// Close the UDP association with the given `ConnectionId`.
+    CloseAssociation(ConnectionId),
 }
 
 impl Socks5RequestContent {
@@ -208,6 +271,30 @@ impl Socks5RequestContent {
         })
     }
 
+    /// Construct a new Request::UdpAssociate instance
+    pub fn new_udp_associate(
+        conn_id: ConnectionId,
+        return_address: Option<Recipient>,
+    ) -> Socks5RequestContent {
+        Socks5RequestContent::UdpAssociate(Box::new(UdpAssociateRequest {
+            conn_id,
+            return_address,
+        }))
+    }
+
+    /// Construct a new Request::SendDatagram instance
+    pub fn new_send_datagram(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5RequestContent {
+        Socks5RequestContent::SendDatagram(DatagramRequest {
+            conn_id,
+            remote_addr,
+            data,
+        })
+    }
+
     /// Deserialize the request type, connection id, destination address and port,
     /// and the request body from bytes.
     ///
@@ -234,43 +321,8 @@ impl Socks5RequestContent {
         let conn_id = u64::from_be_bytes([b[1], b[2], b[3], b[4], b[5], b[6], b[7], b[8]]);
         match RequestFlag::try_from(b[0])? {
             RequestFlag::Connect => {
-                let connect_request_bytes = &b[9..];
-
-                // we need to be able to read at least 2 bytes that specify address length
-                if connect_request_bytes.len() < 2 {
-                    return Err(RequestDeserializationError::AddressLengthTooShort);
-                }
-
-                let address_length =
-                    u16::from_be_bytes([connect_request_bytes[0], connect_request_bytes[1]])
-                        as usize;
-
-                if connect_request_bytes.len() < 2 + address_length {
-                    return Err(RequestDeserializationError::AddressTooShort);
-                }
-
-                let address_start = 2;
-                let address_end = address_start + address_length;
-                let address_bytes = &connect_request_bytes[address_start..address_end];
-                let remote_address = String::from_utf8_lossy(address_bytes).to_string();
-
-                // just a temporary reference to mid-slice for ease of use
-                let recipient_data_bytes = &connect_request_bytes[address_end..];
-
-                let return_address = if recipient_data_bytes.is_empty() {
-                    None
-                } else {
-                    if recipient_data_bytes.len() != Recipient::LEN {
-                        return Err(RequestDeserializationError::ReturnAddressTooShort);
-                    }
-
-                    let mut return_bytes = [0u8; Recipient::LEN];
-                    return_bytes.copy_from_slice(&recipient_data_bytes[..Recipient::LEN]);
-                    Some(
-                        Recipient::try_from_bytes(return_bytes)
-                            .map_err(RequestDeserializationError::MalformedReturnAddress)?,
-                    )
-                };
+                let (remote_address, recipient_data_bytes) = parse_remote_address(&b[9..])?;
+                let return_address = parse_return_address(recipient_data_bytes)?;
 
                 Ok(Socks5RequestContent::new_connect(
                     conn_id,
@@ -288,6 +340,22 @@ impl Socks5RequestContent {
                     local_closed,
                 }))
             }
+            RequestFlag::UdpAssociate => {
+                let return_address = parse_return_address(&b[9..])?;
+                Ok(Socks5RequestContent::new_udp_associate(
+                    conn_id,
+                    return_address,
+                ))
+            }
+            RequestFlag::SendDatagram => {
+                let (remote_address, data) = parse_remote_address(&b[9..])?;
+                Ok(Socks5RequestContent::new_send_datagram(
+                    conn_id,
+                    remote_address,
+                    data.to_vec(),
+                ))
+            }
+            RequestFlag::CloseAssociation => Ok(Socks5RequestContent::CloseAssociation(conn_id)),
         }
     }
 
@@ -317,10 +385,75 @@ impl Socks5RequestContent {
                 .chain(std::iter::once(req.local_closed as u8))
                 .chain(req.data.into_iter())
                 .collect(),
+            // udp associate is: ASSOCIATE_FLAG || CONN_ID || RETURN
+            Socks5RequestContent::UdpAssociate(req) => {
+                let iter = std::iter::once(RequestFlag::UdpAssociate as u8)
+                    .chain(req.conn_id.to_be_bytes().into_iter());
+
+                if let Some(return_address) = req.return_address {
+                    iter.chain(return_address.to_bytes().into_iter()).collect()
+                } else {
+                    iter.collect()
+                }
+            }
+            // datagram is: DATAGRAM_FLAG || CONN_ID || REMOTE_LEN || REMOTE || DATA
+            Socks5RequestContent::SendDatagram(req) => {
+                let remote_address_bytes = req.remote_addr.into_bytes();
+                let remote_address_bytes_len = remote_address_bytes.len() as u16;
+
+                std::iter::once(RequestFlag::SendDatagram as u8)
+                    .chain(req.conn_id.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes_len.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes.into_iter())
+                    .chain(req.data.into_iter())
+                    .collect()
+            }
+            Socks5RequestContent::CloseAssociation(conn_id) => {
+                std::iter::once(RequestFlag::CloseAssociation as u8)
+                    .chain(conn_id.to_be_bytes().into_iter())
+                    .collect()
+            }
         }
     }
 }
 
+// reads REMOTE_LEN || REMOTE and returns the remaining bytes
+fn parse_remote_address(b: &[u8]) -> Result<(RemoteAddress, &[u8]), RequestDeserializationError> {
+    // we need to be able to read at least 2 bytes that specify address length
+    if b.len() < 2 {
+        return Err(RequestDeserializationError::AddressLengthTooShort);
+    }
+
+    let address_length = u16::from_be_bytes([b[0], b[1]]) as usize;
+
+    if b.len() < 2 + address_length {
+        return Err(RequestDeserializationError::AddressTooShort);
+    }
+
+    let address_start = 2;
+    let address_end = address_start + address_length;
+    let address_bytes = &b[address_start..address_end];
+    let remote_address = String::from_utf8_lossy(address_bytes).to_string();
+
+    Ok((remote_address, &b[address_end..]))
+}
+
+fn parse_return_address(b: &[u8]) -> Result<Option<Recipient>, RequestDeserializationError> {
+    if b.is_empty() {
+        return Ok(None);
+    }
+
+    if b.len() != Recipient::LEN {
+        return Err(RequestDeserializationError::ReturnAddressTooShort);
+    }
+
+    let mut return_bytes = [0u8; Recipient::LEN];
+    return_bytes.copy_from_slice(&b[..Recipient::LEN]);
+    Recipient::try_from_bytes(return_bytes)
+        .map(Some)
+        .map_err(RequestDeserializationError::MalformedReturnAddress)
+}
+
 #[cfg(test)]
 mod request_deserialization_tests {
     use super::*;
@@ -553,6 +686,82 @@ mod request_deserialization_tests {
         }
     }
 
+    #[cfg(test)]
+    mod udp_requests {
+        use super::*;
+
+        #[test]
+        fn udp_associate_request_serialization_works() {
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+
+            for return_address in [None, Some(recipient)] {
+                let bytes =
+                    Socks5RequestContent::new_udp_associate(42, return_address).into_bytes();
+                match Socks5RequestContent::try_from_bytes(&bytes).unwrap() {
+                    Socks5RequestContent::UdpAssociate(req) => {
+                        assert_eq!(42, req.conn_id);
+                        assert_eq!(
+                            return_address.map(|r| r.to_bytes().to_vec()),
+                            req.return_address.map(|r| r.to_bytes().to_vec())
+                        );
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        #[test]
+        fn datagram_request_serialization_works() {
+            let bytes = Socks5RequestContent::new_send_datagram(
+                42,
+                "1.1.1.1:53".to_string(),
+                vec![1, 2, 3],
+            )
+            .into_bytes();
+
+            match Socks5RequestContent::try_from_bytes(&bytes).unwrap() {
+                Socks5RequestContent::SendDatagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("1.1.1.1:53", req.remote_addr);
+                    assert_eq!(vec![1, 2, 3], req.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn datagram_request_with_truncated_address_is_rejected() {
+            let request_bytes = [
+                RequestFlag::SendDatagram as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+            ]
+            .to_vec();
+            match Socks5RequestContent::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestDeserializationError::AddressTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn close_association_request_serialization_works() {
+            let bytes = Socks5RequestContent::CloseAssociation(42).into_bytes();
+            match Socks5RequestContent::try_from_bytes(&bytes).unwrap() {
+                Socks5RequestContent::CloseAssociation(conn_id) => assert_eq!(42, conn_id),
+                _ => unreachable!(),
+            }
+        }
+    }
+
     #[cfg(test)]
     mod sending_additional_data_over_an_existing_connection {
         use super::*;
@@ -1,7 +1,7 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ConnectionId, Socks5ProtocolVersion, Socks5RequestError};
+use crate::{ConnectionId, RemoteAddress, Socks5ProtocolVersion, Socks5RequestError};
 use nym_service_providers_common::interface::{Serializable, ServiceProviderResponse};
 use thiserror::Error;
 
@@ -13,6 +13,7 @@ use thiserror::Error;
 pub enum ResponseFlag {
     NetworkData = 1,
     ConnectionError = 2,
+    Datagram = 3,
 }
 
 impl TryFrom<u8> for ResponseFlag {
@@ -22,6 +23,7 @@ impl TryFrom<u8> for ResponseFlag {
         match value {
             _ if value == (ResponseFlag::NetworkData as u8) => Ok(Self::NetworkData),
             _ if value == (ResponseFlag::ConnectionError as u8) => Ok(Self::ConnectionError),
+            _ if value == (ResponseFlag::Datagram as u8) => Ok(Self::Datagram),
             value => Err(ResponseDeserializationError::UnknownResponseFlag { value }),
         }
     }
@@ -32,6 +34,9 @@ pub enum ResponseDeserializationError {
     #[error("not enough bytes to recover the connection id")]
     ConnectionIdTooShort,
 
+    #[error("not enough bytes to recover the source address of the datagram")]
+    SourceAddressTooShort,
+
     #[error("{value} is not a valid response flag")]
     UnknownResponseFlag { value: u8 },
 
@@ -135,12 +140,25 @@ impl Socks5Response {
             content: Socks5ResponseContent::new_connection_error(connection_id, error_message),
         }
     }
+
+    pub fn new_datagram(
+        protocol_version: Socks5ProtocolVersion,
+        connection_id: ConnectionId,
+        source_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5Response {
+        Socks5Response {
+            protocol_version,
+            content: Socks5ResponseContent::new_datagram(connection_id, source_addr, data),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Socks5ResponseContent {
     NetworkData(NetworkData),
     ConnectionError(ConnectionError),
+    Datagram(DatagramData),
 }
 
 impl Socks5ResponseContent {
@@ -163,6 +181,14 @@ impl Socks5ResponseContent {
         Socks5ResponseContent::ConnectionError(ConnectionError::new(connection_id, error_message))
     }
 
+    pub fn new_datagram(
+        connection_id: ConnectionId,
+        source_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5ResponseContent {
+        Socks5ResponseContent::Datagram(DatagramData::new(connection_id, source_addr, data))
+    }
+
     pub fn into_bytes(self) -> Vec<u8> {
         match self {
             Socks5ResponseContent::NetworkData(res) => {
@@ -175,6 +201,9 @@ impl Socks5ResponseContent {
                     .chain(res.into_bytes().into_iter())
                     .collect()
             }
+            Socks5ResponseContent::Datagram(res) => std::iter::once(ResponseFlag::Datagram as u8)
+                .chain(res.into_bytes().into_iter())
+                .collect(),
         }
     }
 
@@ -193,6 +222,9 @@ impl Socks5ResponseContent {
             ResponseFlag::ConnectionError => Ok(Socks5ResponseContent::ConnectionError(
                 ConnectionError::try_from_bytes(&b[1..])?,
             )),
+            ResponseFlag::Datagram => Ok(Socks5ResponseContent::Datagram(
+                DatagramData::try_from_bytes(&b[1..])?,
+            )),
         }
     }
 }
@@ -318,6 +350,66 @@ impl ConnectionError {
     }
 }
 
+/// A datagram received by the Socks5 service provider on one of its UDP associations.
+#[derive(Debug)]
+pub struct DatagramData {
+    pub connection_id: ConnectionId,
+    pub source_addr: RemoteAddress,
+    pub data: Vec<u8>,
+}
+
+impl DatagramData {
+    pub fn new(connection_id: ConnectionId, source_addr: RemoteAddress, data: Vec<u8>) -> Self {
+        DatagramData {
+            connection_id,
+            source_addr,
+            data,
+        }
+    }
+
+    // the format is: CONN_ID || SOURCE_LEN || SOURCE || DATA
+    pub fn try_from_bytes(b: &[u8]) -> Result<DatagramData, ResponseDeserializationError> {
+        if b.is_empty() {
+            return Err(ResponseDeserializationError::NoData);
+        }
+
+        if b.len() < 8 {
+            return Err(ResponseDeserializationError::ConnectionIdTooShort);
+        }
+
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+
+        if b.len() < 10 {
+            return Err(ResponseDeserializationError::SourceAddressTooShort);
+        }
+        let source_length = u16::from_be_bytes([b[8], b[9]]) as usize;
+        if b.len() < 10 + source_length {
+            return Err(ResponseDeserializationError::SourceAddressTooShort);
+        }
+        let source_addr = String::from_utf8_lossy(&b[10..10 + source_length]).to_string();
+        let data = b[10 + source_length..].to_vec();
+
+        Ok(DatagramData {
+            connection_id,
+            source_addr,
+            data,
+        })
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        let source_bytes = self.source_addr.into_bytes();
+        let source_bytes_len = source_bytes.len() as u16;
+
+        self.connection_id
+            .to_be_bytes()
+            .into_iter()
+            .chain(source_bytes_len.to_be_bytes().into_iter())
+            .chain(source_bytes.into_iter())
+            .chain(self.data.into_iter())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,4 +506,38 @@ mod tests {
             ));
         }
     }
+
+    #[cfg(test)]
+    mod datagram_response_serde_tests {
+        use super::*;
+
+        #[test]
+        fn simple_serde() {
+            let response = DatagramData::new(42, "1.1.1.1:53".to_string(), vec![1, 2, 3]);
+            let bytes = response.into_bytes();
+            let deserialized_response = DatagramData::try_from_bytes(&bytes).unwrap();
+
+            assert_eq!(42, deserialized_response.connection_id);
+            assert_eq!("1.1.1.1:53", deserialized_response.source_addr);
+            assert_eq!(vec![1, 2, 3], deserialized_response.data);
+        }
+
+        #[test]
+        fn deserialization_errors() {
+            let err = DatagramData::try_from_bytes(&[]).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::NoData);
+
+            let bytes: [u8; 5] = [1, 2, 3, 4, 5];
+            let err = DatagramData::try_from_bytes(&bytes).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::ConnectionIdTooShort);
+
+            let bytes: Vec<u8> = 42u64
+                .to_be_bytes()
+                .into_iter()
+                .chain([0, 10, 49, 46].into_iter())
+                .collect();
+            let err = DatagramData::try_from_bytes(&bytes).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::SourceAddressTooShort);
+        }
+    }
 }
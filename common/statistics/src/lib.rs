@@ -36,6 +36,14 @@ pub enum StatsData {
 pub struct StatsGatewayData {
     pub gateway_id: String,
     pub inbox_count: u32,
+
+    /// Number of stored messages removed for exceeding the maximum age.
+    #[serde(default)]
+    pub expired_messages: u64,
+
+    /// Number of messages dropped because the inbox of the client was full.
+    #[serde(default)]
+    pub dropped_messages: u64,
 }
 
 impl StatsGatewayData {
@@ -43,8 +51,16 @@ impl StatsGatewayData {
         StatsGatewayData {
             gateway_id,
             inbox_count,
+            expired_messages: 0,
+            dropped_messages: 0,
         }
     }
+
+    pub fn with_inbox_drops(mut self, expired_messages: u64, dropped_messages: u64) -> Self {
+        self.expired_messages = expired_messages;
+        self.dropped_messages = dropped_messages;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -52,6 +68,10 @@ pub struct StatsServiceData {
     pub requested_service: String,
     pub request_bytes: u32,
     pub response_bytes: u32,
+
+    /// Number of requests rejected because the client exceeded its rate limits.
+    #[serde(default)]
+    pub throttled_requests: u32,
 }
 
 impl StatsServiceData {
@@ -60,6 +80,12 @@ impl StatsServiceData {
             requested_service,
             request_bytes,
             response_bytes,
+            throttled_requests: 0,
         }
     }
+
+    pub fn with_throttled_requests(mut self, throttled_requests: u32) -> Self {
+        self.throttled_requests = throttled_requests;
+        self
+    }
 }
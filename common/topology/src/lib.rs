@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::filter::VersionFilterable;
+use crate::route_selection::decimal_to_f64;
 use log::warn;
 use nym_mixnet_contract_common::mixnode::MixNodeDetails;
-use nym_mixnet_contract_common::GatewayBond;
+use nym_mixnet_contract_common::reward_params::Performance;
+use nym_mixnet_contract_common::{GatewayBond, MixId};
 use nym_sphinx_addressing::nodes::NodeIdentity;
 use nym_sphinx_types::Node as SphinxNode;
 use rand::{CryptoRng, Rng};
@@ -19,10 +21,13 @@ use thiserror::Error;
 pub mod filter;
 pub mod gateway;
 pub mod mix;
+pub mod route_selection;
 
 #[cfg(feature = "provider-trait")]
 pub mod provider_trait;
 
+pub use route_selection::RouteSelectionStrategy;
+
 #[derive(Debug, Clone, Error)]
 pub enum NymTopologyError {
     #[error("The provided network topology is empty - there are no mixnodes and no gateways on it - the network request(s) probably failed")]
@@ -98,11 +103,38 @@ pub type MixLayer = u8;
 pub struct NymTopology {
     mixes: HashMap<MixLayer, Vec<mix::Node>>,
     gateways: Vec<gateway::Node>,
+    route_selection: RouteSelectionStrategy,
 }
 
 impl NymTopology {
     pub fn new(mixes: HashMap<MixLayer, Vec<mix::Node>>, gateways: Vec<gateway::Node>) -> Self {
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            route_selection: Default::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_route_selection(mut self, route_selection: RouteSelectionStrategy) -> Self {
+        self.route_selection = route_selection;
+        self
+    }
+
+    pub fn set_route_selection(&mut self, route_selection: RouteSelectionStrategy) {
+        self.route_selection = route_selection
+    }
+
+    pub fn route_selection(&self) -> &RouteSelectionStrategy {
+        &self.route_selection
+    }
+
+    /// Updates the performance information of the mixnodes present in the topology
+    /// that is used for the performance-weighted route selection.
+    pub fn update_mix_performance(&mut self, performance: &HashMap<MixId, Performance>) {
+        for node in self.mixes.values_mut().flatten() {
+            node.performance = performance.get(&node.mix_id).copied();
+        }
     }
 
     pub fn mixes(&self) -> &HashMap<MixLayer, Vec<mix::Node>> {
@@ -146,7 +178,8 @@ impl NymTopology {
     }
 
     /// Returns a vec of size of `num_mix_hops` of mixnodes, such that each subsequent node is on
-    /// next layer, starting from layer 1
+    /// next layer, starting from layer 1. The nodes are chosen according to the
+    /// currently set [RouteSelectionStrategy].
     pub fn random_mix_route<R>(
         &self,
         rng: &mut R,
@@ -156,8 +189,6 @@ impl NymTopology {
         // I don't think there's a need for this RNG to be crypto-secure
         R: Rng + ?Sized,
     {
-        use rand::seq::SliceRandom;
-
         if self.mixes.len() < num_mix_hops as usize {
             return Err(NymTopologyError::InvalidNumberOfHopsError {
                 available: self.mixes.len(),
@@ -176,8 +207,9 @@ impl NymTopology {
 
             // choose a random mix from the above list
             // this can return a 'None' only if slice is empty
-            let random_mix = layer_mixes
-                .choose(rng)
+            let random_mix = self
+                .route_selection
+                .choose(rng, layer_mixes)
                 .ok_or(NymTopologyError::EmptyMixLayer { layer })?;
             route.push(random_mix.into());
         }
@@ -300,6 +332,7 @@ impl NymTopology {
         NymTopology {
             mixes: self.mixes.filter_by_version(expected_mix_version),
             gateways: self.gateways.clone(),
+            route_selection: self.route_selection.clone(),
         }
     }
 }
@@ -309,10 +342,9 @@ pub fn nym_topology_from_detailed(
     gateway_bonds: Vec<GatewayBond>,
 ) -> NymTopology {
     let mut mixes = HashMap::new();
-    for bond in mix_details
-        .into_iter()
-        .map(|details| details.bond_information)
-    {
+    for details in mix_details {
+        let total_stake = decimal_to_f64(details.total_stake()) as u128;
+        let bond = details.bond_information;
         let layer = bond.layer as MixLayer;
         if layer == 0 || layer > 3 {
             warn!(
@@ -325,8 +357,8 @@ pub fn nym_topology_from_detailed(
         let mix_identity = bond.mix_node.identity_key.clone();
 
         let layer_entry = mixes.entry(layer).or_insert_with(Vec::new);
-        match bond.try_into() {
-            Ok(mix) => layer_entry.push(mix),
+        match mix::Node::try_from(bond) {
+            Ok(mix) => layer_entry.push(mix::Node { total_stake, ..mix }),
             Err(err) => {
                 warn!("Mix {} / {} is malformed - {err}", mix_id, mix_identity);
                 continue;
@@ -377,6 +409,8 @@ mod converting_mixes_to_vec {
                 .unwrap(),
                 layer: Layer::One,
                 version: "0.x.0".to_string(),
+                total_stake: 0,
+                performance: None,
             };
 
             let node2 = mix::Node {
@@ -3,8 +3,9 @@
 
 use crate::{filter, NetworkAddress};
 use nym_crypto::asymmetric::{encryption, identity};
-pub use nym_mixnet_contract_common::Layer;
-use nym_mixnet_contract_common::{MixId, MixNodeBond};
+pub use nym_mixnet_contract_common::reward_params::Performance;
+use nym_mixnet_contract_common::MixNodeBond;
+pub use nym_mixnet_contract_common::{Layer, MixId};
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_types::Node as SphinxNode;
 use std::convert::{TryFrom, TryInto};
@@ -40,6 +41,10 @@ pub struct Node {
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
     pub layer: Layer,
     pub version: String,
+    // total stake (pledge and delegations) backing this node, used for weighted route selection
+    pub total_stake: u128,
+    // most recent performance of this node as reported by the nym-api, if known
+    pub performance: Option<Performance>,
 }
 
 impl filter::Versioned for Node {
@@ -89,6 +94,9 @@ impl<'a> TryFrom<&'a MixNodeBond> for Node {
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.mix_node.sphinx_key)?,
             layer: bond.layer,
             version: bond.mix_node.version.clone(),
+            // the bond on its own has no information about the delegations
+            total_stake: bond.original_pledge.amount.u128(),
+            performance: None,
         })
     }
 }
@@ -0,0 +1,197 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::mix;
+use nym_mixnet_contract_common::{Decimal, Fraction};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+pub type CustomMixWeight = dyn Fn(&mix::Node) -> f64 + Send + Sync;
+
+/// Determines how a mixnode is chosen out of all nodes available on a given layer
+/// when constructing a route through the mixnet.
+#[derive(Clone, Default)]
+pub enum RouteSelectionStrategy {
+    /// Every node on the layer is equally likely to be chosen.
+    #[default]
+    Uniform,
+
+    /// Nodes are chosen with probability proportional to their total stake,
+    /// i.e. the operator pledge and all delegations.
+    StakeWeighted,
+
+    /// Nodes are chosen with probability proportional to their most recent performance
+    /// as reported by the nym-api. Nodes without any known performance are never chosen,
+    /// unless no node on the layer has any performance data.
+    PerformanceWeighted,
+
+    /// Nodes are chosen with probability proportional to the weight returned by the provided closure.
+    Custom(Arc<CustomMixWeight>),
+}
+
+impl Debug for RouteSelectionStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteSelectionStrategy::Uniform => write!(f, "Uniform"),
+            RouteSelectionStrategy::StakeWeighted => write!(f, "StakeWeighted"),
+            RouteSelectionStrategy::PerformanceWeighted => write!(f, "PerformanceWeighted"),
+            RouteSelectionStrategy::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl RouteSelectionStrategy {
+    pub fn custom<F>(weight: F) -> Self
+    where
+        F: Fn(&mix::Node) -> f64 + Send + Sync + 'static,
+    {
+        RouteSelectionStrategy::Custom(Arc::new(weight))
+    }
+
+    /// Specifies whether this strategy relies on the performance information being present
+    /// on the nodes in the topology.
+    pub fn requires_performance(&self) -> bool {
+        matches!(self, RouteSelectionStrategy::PerformanceWeighted)
+    }
+
+    fn node_weight(&self, node: &mix::Node) -> f64 {
+        let weight = match self {
+            RouteSelectionStrategy::Uniform => 1.0,
+            RouteSelectionStrategy::StakeWeighted => node.total_stake as f64,
+            RouteSelectionStrategy::PerformanceWeighted => node
+                .performance
+                .map(|performance| decimal_to_f64(performance.value()))
+                .unwrap_or_default(),
+            RouteSelectionStrategy::Custom(weight) => weight(node),
+        };
+
+        // make sure we never attempt to use negative or otherwise invalid weights
+        if weight.is_finite() && weight > 0.0 {
+            weight
+        } else {
+            0.0
+        }
+    }
+
+    /// Chooses a node out of the provided slice according to this strategy.
+    /// It only returns a `None` if the slice is empty.
+    pub fn choose<'a, R>(&self, rng: &mut R, nodes: &'a [mix::Node]) -> Option<&'a mix::Node>
+    where
+        R: Rng + ?Sized,
+    {
+        if matches!(self, RouteSelectionStrategy::Uniform) {
+            return nodes.choose(rng);
+        }
+
+        // if we failed to choose a weighted node, for example because all weights were zero,
+        // fallback to the uniform distribution so that we could still construct a route
+        nodes
+            .choose_weighted(rng, |node| self.node_weight(node))
+            .ok()
+            .or_else(|| nodes.choose(rng))
+    }
+}
+
+pub(crate) fn decimal_to_f64(dec: Decimal) -> f64 {
+    let num = dec.numerator().u128() as f64;
+    let den = dec.denominator().u128() as f64;
+    num / den
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use nym_mixnet_contract_common::{Layer, Percent};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn test_node(mix_id: u32, total_stake: u128, performance: Option<u64>) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: "3.3.3.3".parse().unwrap(),
+            mix_host: "3.3.3.3:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            layer: Layer::One,
+            version: "0.x.0".to_string(),
+            total_stake,
+            performance: performance.map(|p| Percent::from_percentage_value(p).unwrap()),
+        }
+    }
+
+    fn test_rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([42u8; 32])
+    }
+
+    #[test]
+    fn uniform_selection_returns_none_for_empty_layer() {
+        let mut rng = test_rng();
+        assert!(RouteSelectionStrategy::Uniform
+            .choose(&mut rng, &[])
+            .is_none());
+        assert!(RouteSelectionStrategy::StakeWeighted
+            .choose(&mut rng, &[])
+            .is_none());
+    }
+
+    #[test]
+    fn stake_weighted_selection_never_chooses_unstaked_nodes() {
+        let mut rng = test_rng();
+        let nodes = vec![test_node(1, 0, None), test_node(2, 1000, None)];
+        for _ in 0..100 {
+            let chosen = RouteSelectionStrategy::StakeWeighted
+                .choose(&mut rng, &nodes)
+                .unwrap();
+            assert_eq!(chosen.mix_id, 2)
+        }
+    }
+
+    #[test]
+    fn performance_weighted_selection_prefers_performing_nodes() {
+        let mut rng = test_rng();
+        let nodes = vec![
+            test_node(1, 1000, Some(0)),
+            test_node(2, 1000, None),
+            test_node(3, 1000, Some(90)),
+        ];
+        for _ in 0..100 {
+            let chosen = RouteSelectionStrategy::PerformanceWeighted
+                .choose(&mut rng, &nodes)
+                .unwrap();
+            assert_eq!(chosen.mix_id, 3)
+        }
+    }
+
+    #[test]
+    fn weighted_selection_falls_back_to_uniform_if_all_weights_are_zero() {
+        let mut rng = test_rng();
+        let nodes = vec![test_node(1, 0, None), test_node(2, 0, None)];
+        assert!(RouteSelectionStrategy::PerformanceWeighted
+            .choose(&mut rng, &nodes)
+            .is_some());
+
+        let invalid = RouteSelectionStrategy::custom(|_| f64::NAN);
+        assert!(invalid.choose(&mut rng, &nodes).is_some());
+    }
+
+    #[test]
+    fn custom_selection_uses_provided_weights() {
+        let mut rng = test_rng();
+        let nodes = vec![test_node(1, 1000, None), test_node(2, 1000, None)];
+        let strategy =
+            RouteSelectionStrategy::custom(|node| if node.mix_id == 1 { 1.0 } else { -5.0 });
+        for _ in 0..100 {
+            assert_eq!(strategy.choose(&mut rng, &nodes).unwrap().mix_id, 1)
+        }
+    }
+}
@@ -55,6 +55,9 @@ pub fn execute(
             transactions::spend_credential(deps, env, info, data)
         }
         ExecuteMsg::ReleaseFunds { funds } => transactions::release_funds(deps, env, info, funds),
+        ExecuteMsg::PruneExpiredCredentials { limit } => {
+            transactions::prune_expired_credentials(deps, env, limit)
+        }
     }
 }
 
@@ -29,6 +29,9 @@ pub enum ContractError {
     #[error("Credential already spent or in process of spending")]
     DuplicateBlindedSerialNumber,
 
+    #[error("Credential has expired at {expiry_date}")]
+    CredentialExpired { expiry_date: u64 },
+
     #[error(transparent)]
     Admin(#[from] AdminError),
 }
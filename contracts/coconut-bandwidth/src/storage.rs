@@ -1,17 +1,31 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use cw_storage_plus::{Index, IndexList, IndexedMap, UniqueIndex};
+use cosmwasm_std::Empty;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, UniqueIndex};
 use nym_coconut_bandwidth_contract_common::spend_credential::SpendCredential;
 
 // storage prefixes
 const SPEND_CREDENTIAL_PK_NAMESPACE: &str = "sc";
 const SPEND_CREDENTIAL_BLINDED_SERIAL_NO_IDX_NAMESPACE: &str = "scn";
+const SPEND_CREDENTIAL_EXPIRY_NAMESPACE: &str = "sce";
 
 // paged retrieval limits for all queries and transactions
 pub(crate) const SPEND_CREDENTIAL_PAGE_MAX_LIMIT: u32 = 75;
 pub(crate) const SPEND_CREDENTIAL_PAGE_DEFAULT_LIMIT: u32 = 50;
 
+// how many expired credentials get pruned alongside every spending
+pub(crate) const SPEND_CREDENTIAL_PRUNE_ON_SPEND_LIMIT: u32 = 5;
+
+// how long past the expiry date the spent credential is kept around,
+// so that any proposal to release its funds could still be voted on
+pub(crate) const SPEND_CREDENTIAL_RETENTION_GRACE_SECS: u64 = 24 * 60 * 60;
+
+// spent credentials ordered by their expiry date, so that the ones that can no longer
+// be spent again (and thus don't need to be remembered) could be cheaply found and pruned
+pub(crate) const SPENT_CREDENTIALS_BY_EXPIRY: Map<'_, (u64, &str), Empty> =
+    Map::new(SPEND_CREDENTIAL_EXPIRY_NAMESPACE);
+
 pub(crate) struct SpendCredentialIndex<'a> {
     pub(crate) blinded_serial_number: UniqueIndex<'a, String, SpendCredential>,
 }
@@ -89,6 +103,7 @@ mod tests {
             funds.clone(),
             blind_serial_number.to_string(),
             gateway_cosmos_address.clone(),
+            1_682_899_200,
         );
         spend_credential.mark_as_spent();
 
@@ -8,11 +8,15 @@ use nym_coconut_bandwidth_contract_common::spend_credential::{
 
 pub const TEST_MIX_DENOM: &str = "unym";
 
+// expiry date far enough in the future for the `mock_env()` block time
+pub const TEST_EXPIRY_DATE: u64 = 1_700_000_000;
+
 pub fn spend_credential_fixture(blinded_serial_number: &str) -> SpendCredential {
     SpendCredential::new(
         Coin::new(100, TEST_MIX_DENOM),
         blinded_serial_number.to_string(),
         Addr::unchecked("gateway_owner_addr"),
+        TEST_EXPIRY_DATE,
     )
 }
 
@@ -21,5 +25,6 @@ pub fn spend_credential_data_fixture(blinded_serial_number: &str) -> SpendCreden
         Coin::new(100, TEST_MIX_DENOM),
         blinded_serial_number.to_string(),
         "gateway_owner_addr".to_string(),
+        TEST_EXPIRY_DATE,
     )
 }
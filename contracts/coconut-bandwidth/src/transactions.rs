@@ -1,19 +1,26 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, Event, MessageInfo, Response};
+use cosmwasm_std::{
+    BankMsg, Coin, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
 use nym_coconut_bandwidth_contract_common::spend_credential::{
     to_cosmos_msg, SpendCredential, SpendCredentialData,
 };
 
 use crate::error::ContractError;
 use crate::state::{ADMIN, CONFIG};
-use crate::storage;
+use crate::storage::{
+    self, SPEND_CREDENTIAL_PAGE_DEFAULT_LIMIT, SPEND_CREDENTIAL_PAGE_MAX_LIMIT,
+    SPEND_CREDENTIAL_PRUNE_ON_SPEND_LIMIT, SPEND_CREDENTIAL_RETENTION_GRACE_SECS,
+    SPENT_CREDENTIALS_BY_EXPIRY,
+};
 
 use nym_coconut_bandwidth_contract_common::deposit::DepositData;
 use nym_coconut_bandwidth_contract_common::events::{
     DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
-    DEPOSIT_VALUE,
+    DEPOSIT_VALUE, PRUNED_COUNT, PRUNED_SPEND_CREDENTIALS_EVENT_TYPE,
 };
 
 pub(crate) fn deposit_funds(
@@ -53,6 +60,11 @@ pub(crate) fn spend_credential(
     if data.funds().denom != mix_denom {
         return Err(ContractError::WrongDenom { mix_denom });
     }
+    if data.expiry_date() <= env.block.time.seconds() {
+        return Err(ContractError::CredentialExpired {
+            expiry_date: data.expiry_date(),
+        });
+    }
     if storage::spent_credentials().has(deps.storage, data.blinded_serial_number()) {
         return Err(ContractError::DuplicateBlindedSerialNumber);
     }
@@ -66,8 +78,17 @@ pub(crate) fn spend_credential(
             data.funds().to_owned(),
             data.blinded_serial_number().to_owned(),
             gateway_cosmos_address,
+            data.expiry_date(),
         ),
     )?;
+    SPENT_CREDENTIALS_BY_EXPIRY.save(
+        deps.storage,
+        (data.expiry_date(), data.blinded_serial_number()),
+        &Empty {},
+    )?;
+
+    // keep the storage from growing indefinitely by removing some of the expired entries
+    let pruned = prune_expired(deps.storage, &env, SPEND_CREDENTIAL_PRUNE_ON_SPEND_LIMIT)?;
 
     let msg = to_cosmos_msg(
         data.funds().clone(),
@@ -76,7 +97,50 @@ pub(crate) fn spend_credential(
         cfg.multisig_addr.into_string(),
     )?;
 
-    Ok(Response::new().add_message(msg))
+    Ok(Response::new()
+        .add_message(msg)
+        .add_event(pruned_event(pruned)))
+}
+
+pub(crate) fn prune_expired_credentials(
+    deps: DepsMut<'_>,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit
+        .unwrap_or(SPEND_CREDENTIAL_PAGE_DEFAULT_LIMIT)
+        .min(SPEND_CREDENTIAL_PAGE_MAX_LIMIT);
+    let pruned = prune_expired(deps.storage, &env, limit)?;
+
+    Ok(Response::new().add_event(pruned_event(pruned)))
+}
+
+// Removes up to `limit` spent credentials that expired long enough ago for them to no longer
+// be needed for preventing double spending, as the contract would reject them anyway.
+fn prune_expired(storage: &mut dyn Storage, env: &Env, limit: u32) -> StdResult<usize> {
+    let cutoff = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(SPEND_CREDENTIAL_RETENTION_GRACE_SECS);
+
+    // every entry with the expiry date below the cutoff, regardless of its serial number
+    let end = Bound::exclusive((cutoff, ""));
+    let expired = SPENT_CREDENTIALS_BY_EXPIRY
+        .keys(storage, None, Some(end), Order::Ascending)
+        .take(limit as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (expiry_date, blinded_serial_number) in &expired {
+        storage::spent_credentials().remove(storage, blinded_serial_number)?;
+        SPENT_CREDENTIALS_BY_EXPIRY.remove(storage, (*expiry_date, blinded_serial_number));
+    }
+
+    Ok(expired.len())
+}
+
+fn pruned_event(pruned: usize) -> Event {
+    Event::new(PRUNED_SPEND_CREDENTIALS_EVENT_TYPE).add_attribute(PRUNED_COUNT, pruned.to_string())
 }
 
 pub(crate) fn release_funds(
@@ -111,7 +175,7 @@ pub(crate) fn release_funds(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::support::tests::fixtures::spend_credential_data_fixture;
+    use crate::support::tests::fixtures::{spend_credential_data_fixture, TEST_EXPIRY_DATE};
     use crate::support::tests::helpers::{self, MULTISIG_CONTRACT, POOL_CONTRACT};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{from_binary, Coin, CosmosMsg, WasmMsg};
@@ -337,6 +401,7 @@ mod tests {
             Coin::new(1, "invalid_denom".to_string()),
             String::new(),
             String::new(),
+            TEST_EXPIRY_DATE,
         );
         let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), invalid_data);
         assert_eq!(
@@ -350,6 +415,7 @@ mod tests {
             Coin::new(1, crate::support::tests::fixtures::TEST_MIX_DENOM),
             String::new(),
             "Blinded Serial Number".to_string(),
+            TEST_EXPIRY_DATE,
         );
         let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), invalid_data);
         assert_eq!(
@@ -365,10 +431,81 @@ mod tests {
             invalid_data.clone(),
         )
         .unwrap();
-        let ret = spend_credential(deps.as_mut(), env, info, invalid_data);
+        let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), invalid_data);
         assert_eq!(
             ret.unwrap_err(),
             ContractError::DuplicateBlindedSerialNumber
         );
+
+        let expired_data = SpendCredentialData::new(
+            Coin::new(1, crate::support::tests::fixtures::TEST_MIX_DENOM),
+            "expired_serial_number".to_string(),
+            "gateway_owner_addr".to_string(),
+            env.block.time.seconds(),
+        );
+        let ret = spend_credential(deps.as_mut(), env.clone(), info, expired_data);
+        assert_eq!(
+            ret.unwrap_err(),
+            ContractError::CredentialExpired {
+                expiry_date: env.block.time.seconds()
+            }
+        );
+    }
+
+    #[test]
+    fn expired_credentials_are_pruned() {
+        let mut deps = helpers::init_contract();
+        let mut env = mock_env();
+        let info = mock_info("requester", &[]);
+
+        let now = env.block.time.seconds();
+        for (serial_number, expiry_date) in [("first", now + 10), ("second", now + 20)] {
+            let data = SpendCredentialData::new(
+                Coin::new(1, crate::support::tests::fixtures::TEST_MIX_DENOM),
+                serial_number.to_string(),
+                "gateway_owner_addr".to_string(),
+                expiry_date,
+            );
+            spend_credential(deps.as_mut(), env.clone(), info.clone(), data).unwrap();
+        }
+
+        // expired, but still within the grace period
+        env.block.time = env.block.time.plus_seconds(15);
+        prune_expired_credentials(deps.as_mut(), env.clone(), None).unwrap();
+        assert!(storage::spent_credentials().has(&deps.storage, "first"));
+
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(SPEND_CREDENTIAL_RETENTION_GRACE_SECS);
+        prune_expired_credentials(deps.as_mut(), env.clone(), None).unwrap();
+        assert!(!storage::spent_credentials().has(&deps.storage, "first"));
+        assert!(storage::spent_credentials().has(&deps.storage, "second"));
+
+        // the already pruned credential can't be spent again as it has expired
+        let data = SpendCredentialData::new(
+            Coin::new(1, crate::support::tests::fixtures::TEST_MIX_DENOM),
+            "first".to_string(),
+            "gateway_owner_addr".to_string(),
+            now + 10,
+        );
+        let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), data);
+        assert_eq!(
+            ret.unwrap_err(),
+            ContractError::CredentialExpired {
+                expiry_date: now + 10
+            }
+        );
+
+        // pruning also happens alongside spending
+        env.block.time = env.block.time.plus_seconds(10);
+        spend_credential(
+            deps.as_mut(),
+            env,
+            info,
+            spend_credential_data_fixture("third"),
+        )
+        .unwrap();
+        assert!(!storage::spent_credentials().has(&deps.storage, "second"));
     }
 }
@@ -0,0 +1,135 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::complaints::storage;
+use crate::complaints::storage::{COMPLAINTS, DISQUALIFIED_DEALERS};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+use nym_coconut_dkg_common::complaint::{
+    PagedComplaintsResponse, PagedDisqualifiedDealersResponse,
+};
+use nym_coconut_dkg_common::types::EpochId;
+
+pub fn query_complaints_paged(
+    deps: Deps<'_>,
+    epoch_id: EpochId,
+    dealer: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedComplaintsResponse> {
+    let limit = limit
+        .unwrap_or(storage::COMPLAINTS_PAGE_DEFAULT_LIMIT)
+        .min(storage::COMPLAINTS_PAGE_MAX_LIMIT) as usize;
+
+    let dealer = deps.api.addr_validate(&dealer)?;
+    let addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let complaints = COMPLAINTS
+        .prefix((epoch_id, &dealer))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, complaint)| complaint))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = complaints
+        .last()
+        .map(|complaint| complaint.complainer.clone());
+
+    Ok(PagedComplaintsResponse::new(
+        complaints,
+        limit,
+        start_next_after,
+    ))
+}
+
+pub fn query_disqualified_dealers_paged(
+    deps: Deps<'_>,
+    epoch_id: EpochId,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedDisqualifiedDealersResponse> {
+    let limit = limit
+        .unwrap_or(storage::DISQUALIFIED_DEALERS_PAGE_DEFAULT_LIMIT)
+        .min(storage::DISQUALIFIED_DEALERS_PAGE_MAX_LIMIT) as usize;
+
+    let addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let dealers = DISQUALIFIED_DEALERS
+        .prefix(epoch_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, dealer)| dealer))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = dealers.last().map(|dealer| dealer.dealer.clone());
+
+    Ok(PagedDisqualifiedDealersResponse::new(
+        dealers,
+        limit,
+        start_next_after,
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::helpers::init_contract;
+    use cosmwasm_std::Addr;
+    use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason};
+
+    #[test]
+    fn complaints_paged_retrieval_obeys_limits() {
+        let mut deps = init_contract();
+        let dealer = Addr::unchecked("dealer");
+        for n in 0..100 {
+            let complainer = Addr::unchecked(format!("owner{}", n));
+            let complaint = Complaint {
+                complainer: complainer.clone(),
+                dealer: dealer.clone(),
+                epoch_id: 1,
+                reason: ComplaintReason::MissingDealing,
+            };
+            COMPLAINTS
+                .save(&mut deps.storage, (1, &dealer, &complainer), &complaint)
+                .unwrap();
+        }
+
+        let page1 =
+            query_complaints_paged(deps.as_ref(), 1, dealer.to_string(), None, Some(2)).unwrap();
+        assert_eq!(2, page1.complaints.len());
+
+        let page2 = query_complaints_paged(
+            deps.as_ref(),
+            1,
+            dealer.to_string(),
+            page1.start_next_after.map(String::from),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            storage::COMPLAINTS_PAGE_DEFAULT_LIMIT,
+            page2.complaints.len() as u32
+        );
+        assert_ne!(page1.complaints[1], page2.complaints[0]);
+
+        // complaints are separated by epochs
+        let other_epoch =
+            query_complaints_paged(deps.as_ref(), 2, dealer.to_string(), None, None).unwrap();
+        assert!(other_epoch.complaints.is_empty());
+    }
+
+    #[test]
+    fn disqualified_dealers_empty_on_init() {
+        let deps = init_contract();
+        let response = query_disqualified_dealers_paged(deps.as_ref(), 0, None, None).unwrap();
+        assert!(response.dealers.is_empty());
+    }
+}
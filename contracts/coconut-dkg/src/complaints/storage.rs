@@ -0,0 +1,154 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::ContractError;
+use cosmwasm_std::{Addr, Order, Storage};
+use cw_storage_plus::Map;
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason, DisqualifiedDealer};
+use nym_coconut_dkg_common::types::EpochId;
+use std::collections::BTreeMap;
+
+pub(crate) const COMPLAINTS_PAGE_MAX_LIMIT: u32 = 75;
+pub(crate) const COMPLAINTS_PAGE_DEFAULT_LIMIT: u32 = 50;
+
+pub(crate) const DISQUALIFIED_DEALERS_PAGE_MAX_LIMIT: u32 = 75;
+pub(crate) const DISQUALIFIED_DEALERS_PAGE_DEFAULT_LIMIT: u32 = 50;
+
+// (epoch, dealer the complaint is about, complainer)
+type ComplaintKey<'a> = (EpochId, &'a Addr, &'a Addr);
+type DisqualifiedDealerKey<'a> = (EpochId, &'a Addr);
+
+pub(crate) const COMPLAINTS: Map<'_, ComplaintKey<'_>, Complaint> = Map::new("cmpl");
+pub(crate) const DISQUALIFIED_DEALERS: Map<'_, DisqualifiedDealerKey<'_>, DisqualifiedDealer> =
+    Map::new("dsqd");
+
+pub(crate) fn is_disqualified(storage: &dyn Storage, epoch_id: EpochId, dealer: &Addr) -> bool {
+    DISQUALIFIED_DEALERS.has(storage, (epoch_id, dealer))
+}
+
+/// Disqualifies all dealers that received at least `threshold` complaints during the provided epoch.
+/// Returns the addresses of the disqualified dealers.
+pub(crate) fn resolve_complaints(
+    storage: &mut dyn Storage,
+    epoch_id: EpochId,
+    threshold: u64,
+) -> Result<Vec<Addr>, ContractError> {
+    let mut reasons: BTreeMap<Addr, Vec<ComplaintReason>> = BTreeMap::new();
+    for complaint in COMPLAINTS
+        .sub_prefix(epoch_id)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (_, complaint) = complaint?;
+        reasons
+            .entry(complaint.dealer)
+            .or_default()
+            .push(complaint.reason);
+    }
+
+    let mut disqualified = Vec::new();
+    for (dealer, reasons) in reasons {
+        if (reasons.len() as u64) < threshold {
+            continue;
+        }
+
+        let mut occurrences: BTreeMap<ComplaintReason, u32> = BTreeMap::new();
+        for reason in &reasons {
+            *occurrences.entry(*reason).or_default() += 1;
+        }
+        // in case of a tie, the reason is chosen deterministically based on the variant ordering
+        let reason = occurrences
+            .into_iter()
+            .max_by(|(reason_a, count_a), (reason_b, count_b)| {
+                count_a.cmp(count_b).then(reason_b.cmp(reason_a))
+            })
+            .map(|(reason, _)| reason)
+            .expect("there is at least a single complaint");
+
+        DISQUALIFIED_DEALERS.save(
+            storage,
+            (epoch_id, &dealer),
+            &DisqualifiedDealer {
+                dealer: dealer.clone(),
+                epoch_id,
+                complaints: reasons.len() as u32,
+                reason,
+            },
+        )?;
+        disqualified.push(dealer);
+    }
+
+    Ok(disqualified)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::helpers::init_contract;
+
+    fn complain(
+        storage: &mut dyn Storage,
+        dealer: &str,
+        complainer: &str,
+        reason: ComplaintReason,
+    ) {
+        let dealer = Addr::unchecked(dealer);
+        let complainer = Addr::unchecked(complainer);
+        let complaint = Complaint {
+            complainer: complainer.clone(),
+            dealer: dealer.clone(),
+            epoch_id: 0,
+            reason,
+        };
+        COMPLAINTS
+            .save(storage, (0, &dealer, &complainer), &complaint)
+            .unwrap();
+    }
+
+    #[test]
+    fn only_dealers_with_enough_complaints_are_disqualified() {
+        let mut deps = init_contract();
+        complain(
+            &mut deps.storage,
+            "owner1",
+            "owner2",
+            ComplaintReason::MissingDealing,
+        );
+        complain(
+            &mut deps.storage,
+            "owner1",
+            "owner3",
+            ComplaintReason::MalformedDealing,
+        );
+        complain(
+            &mut deps.storage,
+            "owner1",
+            "owner4",
+            ComplaintReason::MalformedDealing,
+        );
+        complain(
+            &mut deps.storage,
+            "owner2",
+            "owner1",
+            ComplaintReason::MissingDealing,
+        );
+
+        let disqualified = resolve_complaints(&mut deps.storage, 0, 3).unwrap();
+        assert_eq!(disqualified, vec![Addr::unchecked("owner1")]);
+
+        let details = DISQUALIFIED_DEALERS
+            .load(&deps.storage, (0, &Addr::unchecked("owner1")))
+            .unwrap();
+        assert_eq!(details.complaints, 3);
+        assert_eq!(details.reason, ComplaintReason::MalformedDealing);
+        assert!(!is_disqualified(
+            &deps.storage,
+            0,
+            &Addr::unchecked("owner2")
+        ));
+        assert!(!is_disqualified(
+            &deps.storage,
+            1,
+            &Addr::unchecked("owner1")
+        ));
+    }
+}
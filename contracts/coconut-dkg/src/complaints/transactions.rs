@@ -0,0 +1,162 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::complaints::storage::COMPLAINTS;
+use crate::dealers::storage as dealers_storage;
+use crate::epoch_state::storage::CURRENT_EPOCH;
+use crate::epoch_state::utils::check_epoch_state;
+use crate::error::ContractError;
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason};
+use nym_coconut_dkg_common::types::EpochState;
+
+pub fn try_submit_complaint(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    dealer: String,
+    reason: ComplaintReason,
+    resharing: bool,
+) -> Result<Response, ContractError> {
+    check_epoch_state(deps.storage, EpochState::ComplaintSubmission { resharing })?;
+    // ensure the sender is a dealer
+    if dealers_storage::current_dealers()
+        .may_load(deps.storage, &info.sender)?
+        .is_none()
+    {
+        return Err(ContractError::NotADealer);
+    }
+
+    let dealer = deps.api.addr_validate(&dealer)?;
+    if dealer == info.sender {
+        return Err(ContractError::SelfComplaint);
+    }
+    if dealers_storage::current_dealers()
+        .may_load(deps.storage, &dealer)?
+        .is_none()
+    {
+        return Err(ContractError::UnknownDealer {
+            dealer: dealer.into_string(),
+        });
+    }
+
+    let epoch_id = CURRENT_EPOCH.load(deps.storage)?.epoch_id;
+    if COMPLAINTS.has(deps.storage, (epoch_id, &dealer, &info.sender)) {
+        return Err(ContractError::AlreadyComplained {
+            dealer: dealer.into_string(),
+        });
+    }
+
+    let complaint = Complaint {
+        complainer: info.sender.clone(),
+        dealer: dealer.clone(),
+        epoch_id,
+        reason,
+    };
+    COMPLAINTS.save(deps.storage, (epoch_id, &dealer, &info.sender), &complaint)?;
+
+    Ok(Response::default())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::fixtures::dealer_details_fixture;
+    use crate::support::tests::helpers;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use nym_coconut_dkg_common::types::{Epoch, TimeConfiguration};
+
+    #[test]
+    fn invalid_complaints() {
+        let mut deps = helpers::init_contract();
+        let env = mock_env();
+        let info = mock_info("owner1", &[]);
+        let reason = ComplaintReason::MissingDealing;
+
+        let ret = try_submit_complaint(
+            deps.as_mut(),
+            info.clone(),
+            "owner2".to_string(),
+            reason,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            ret,
+            ContractError::IncorrectEpochState {
+                current_state: EpochState::default().to_string(),
+                expected_state: EpochState::ComplaintSubmission { resharing: false }.to_string()
+            }
+        );
+
+        CURRENT_EPOCH
+            .save(
+                deps.as_mut().storage,
+                &Epoch::new(
+                    EpochState::ComplaintSubmission { resharing: false },
+                    0,
+                    TimeConfiguration::default(),
+                    env.block.time,
+                ),
+            )
+            .unwrap();
+
+        let ret = try_submit_complaint(
+            deps.as_mut(),
+            info.clone(),
+            "owner2".to_string(),
+            reason,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(ret, ContractError::NotADealer);
+
+        for idx in 1..3 {
+            let details = dealer_details_fixture(idx);
+            dealers_storage::current_dealers()
+                .save(deps.as_mut().storage, &details.address, &details)
+                .unwrap();
+        }
+
+        let ret = try_submit_complaint(
+            deps.as_mut(),
+            info.clone(),
+            "owner1".to_string(),
+            reason,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(ret, ContractError::SelfComplaint);
+
+        let ret = try_submit_complaint(
+            deps.as_mut(),
+            info.clone(),
+            "owner3".to_string(),
+            reason,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            ret,
+            ContractError::UnknownDealer {
+                dealer: "owner3".to_string()
+            }
+        );
+
+        try_submit_complaint(
+            deps.as_mut(),
+            info.clone(),
+            "owner2".to_string(),
+            reason,
+            false,
+        )
+        .unwrap();
+        let ret = try_submit_complaint(deps.as_mut(), info, "owner2".to_string(), reason, false)
+            .unwrap_err();
+        assert_eq!(
+            ret,
+            ContractError::AlreadyComplained {
+                dealer: "owner2".to_string()
+            }
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::complaints::queries::{query_complaints_paged, query_disqualified_dealers_paged};
+use crate::complaints::transactions::try_submit_complaint;
 use crate::dealers::queries::{
     query_current_dealers_paged, query_dealer_details, query_past_dealers_paged,
 };
@@ -83,6 +85,11 @@ pub fn execute(
             dealing_bytes,
             resharing,
         } => try_commit_dealings(deps, info, dealing_bytes, resharing),
+        ExecuteMsg::SubmitComplaint {
+            dealer,
+            reason,
+            resharing,
+        } => try_submit_complaint(deps, info, dealer, reason, resharing),
         ExecuteMsg::CommitVerificationKeyShare { share, resharing } => {
             try_commit_verification_key_share(deps, env, info, share, resharing)
         }
@@ -121,6 +128,28 @@ pub fn query(deps: Deps<'_>, _env: Env, msg: QueryMsg) -> Result<QueryResponse,
             limit,
             start_after,
         } => to_binary(&query_vk_shares_paged(deps, epoch_id, start_after, limit)?)?,
+        QueryMsg::GetComplaints {
+            epoch_id,
+            dealer,
+            limit,
+            start_after,
+        } => to_binary(&query_complaints_paged(
+            deps,
+            epoch_id,
+            dealer,
+            start_after,
+            limit,
+        )?)?,
+        QueryMsg::GetDisqualifiedDealers {
+            epoch_id,
+            limit,
+            start_after,
+        } => to_binary(&query_disqualified_dealers_paged(
+            deps,
+            epoch_id,
+            start_after,
+            limit,
+        )?)?,
     };
 
     Ok(response)
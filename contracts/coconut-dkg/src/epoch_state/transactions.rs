@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::complaints::storage::resolve_complaints;
 use crate::dealers::storage::{current_dealers, past_dealers};
 use crate::dealings::storage::DEALINGS_BYTES;
 use crate::epoch_state::storage::{CURRENT_EPOCH, INITIAL_REPLACEMENT_DATA, THRESHOLD};
@@ -8,7 +9,10 @@ use crate::epoch_state::utils::check_epoch_state;
 use crate::error::ContractError;
 use crate::state::STATE;
 use crate::verification_key_shares::storage::verified_dealers;
-use cosmwasm_std::{Addr, Deps, DepsMut, Env, Order, Response, Storage};
+use cosmwasm_std::{to_vec, Addr, Deps, DepsMut, Env, Event, Order, Response, Storage};
+use nym_coconut_dkg_common::event_attributes::{
+    DKG_DISQUALIFIED_DEALERS, DKG_EPOCH_ID, DKG_EPOCH_STATE, DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE,
+};
 use nym_coconut_dkg_common::types::{Epoch, EpochState, InitialReplacementData};
 
 fn reset_epoch_state(storage: &mut dyn Storage) -> Result<(), ContractError> {
@@ -92,6 +96,7 @@ pub(crate) fn advance_epoch_state(deps: DepsMut<'_>, env: Env) -> Result<Respons
     }
 
     let current_epoch = CURRENT_EPOCH.load(deps.storage)?;
+    let mut disqualified = Vec::new();
     let next_epoch = if let Some(state) = current_epoch.state.next() {
         // We are during DKG process
         let mut new_state = state;
@@ -114,6 +119,11 @@ pub(crate) fn advance_epoch_state(deps: DepsMut<'_>, env: Env) -> Result<Respons
                 THRESHOLD.save(deps.storage, &threshold)?;
             }
         };
+        if let EpochState::VerificationKeySubmission { .. } = state {
+            // exclude the dealers that enough of the other participants complained about
+            let threshold = THRESHOLD.load(deps.storage)?;
+            disqualified = resolve_complaints(deps.storage, current_epoch.epoch_id, threshold)?;
+        }
         Epoch::new(
             new_state,
             current_epoch.epoch_id,
@@ -162,7 +172,32 @@ pub(crate) fn advance_epoch_state(deps: DepsMut<'_>, env: Env) -> Result<Respons
     };
     CURRENT_EPOCH.save(deps.storage, &next_epoch)?;
 
-    Ok(Response::default())
+    Ok(Response::new().add_event(new_epoch_state_advance_event(&next_epoch, &disqualified)?))
+}
+
+fn new_epoch_state_advance_event(
+    epoch: &Epoch,
+    disqualified: &[Addr],
+) -> Result<Event, ContractError> {
+    // the state is serialized the same way it's returned by the contract queries
+    // so that the clients could just deserialize it back
+    let state = String::from_utf8(to_vec(&epoch.state)?)
+        .expect("serde-json-wasm produced invalid utf8 output");
+
+    let mut event = Event::new(DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE)
+        .add_attribute(DKG_EPOCH_ID, epoch.epoch_id.to_string())
+        .add_attribute(DKG_EPOCH_STATE, state);
+
+    if !disqualified.is_empty() {
+        let dealers = disqualified
+            .iter()
+            .map(|dealer| dealer.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        event = event.add_attribute(DKG_DISQUALIFIED_DEALERS, dealers);
+    }
+
+    Ok(event)
 }
 
 pub(crate) fn try_surpassed_threshold(
@@ -175,7 +210,7 @@ pub(crate) fn try_surpassed_threshold(
     let dealers = verified_dealers(deps.storage)?;
     if dealers_still_active(&deps.as_ref(), dealers.into_iter())? < threshold as usize {
         reset_epoch_state(deps.storage)?;
-        CURRENT_EPOCH.update::<_, ContractError>(deps.storage, |epoch| {
+        let next_epoch = CURRENT_EPOCH.update::<_, ContractError>(deps.storage, |epoch| {
             Ok(Epoch::new(
                 EpochState::default(),
                 epoch.epoch_id + 1,
@@ -183,6 +218,7 @@ pub(crate) fn try_surpassed_threshold(
                 env.block.time,
             ))
         })?;
+        return Ok(Response::new().add_event(new_epoch_state_advance_event(&next_epoch, &[])?));
     }
 
     Ok(Response::default())
@@ -464,6 +500,29 @@ pub(crate) mod tests {
                 EarlyEpochStateAdvancement(2)
             );
 
+            env.block.time = env.block.time.plus_seconds(3);
+            advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+            let epoch = CURRENT_EPOCH.load(deps.as_mut().storage).unwrap();
+            assert_eq!(
+                epoch.state,
+                EpochState::ComplaintSubmission { resharing: false }
+            );
+            assert_eq!(
+                epoch.finish_timestamp,
+                env.block
+                    .time
+                    .plus_seconds(epoch.time_configuration.complaint_submission_time_secs)
+            );
+
+            env.block.time = env
+                .block
+                .time
+                .plus_seconds(epoch.time_configuration.complaint_submission_time_secs - 2);
+            assert_eq!(
+                advance_epoch_state(deps.as_mut(), env.clone()).unwrap_err(),
+                EarlyEpochStateAdvancement(2)
+            );
+
             env.block.time = env.block.time.plus_seconds(3);
             advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
             let epoch = CURRENT_EPOCH.load(deps.as_mut().storage).unwrap();
@@ -622,6 +681,7 @@ pub(crate) mod tests {
             for times in [
                 epoch.time_configuration.public_key_submission_time_secs,
                 epoch.time_configuration.dealing_exchange_time_secs,
+                epoch.time_configuration.complaint_submission_time_secs,
                 epoch.time_configuration.verification_key_submission_time_secs,
                 epoch.time_configuration.verification_key_validation_time_secs,
                 epoch.time_configuration.verification_key_finalization_time_secs,
@@ -729,6 +789,7 @@ pub(crate) mod tests {
             for times in [
                 time_configuration.public_key_submission_time_secs,
                 time_configuration.dealing_exchange_time_secs,
+                time_configuration.complaint_submission_time_secs,
                 time_configuration.verification_key_submission_time_secs,
                 time_configuration.verification_key_validation_time_secs,
                 time_configuration.verification_key_finalization_time_secs,
@@ -740,9 +801,10 @@ pub(crate) mod tests {
             assert_eq!(THRESHOLD.load(&deps.storage).unwrap(), 2);
 
             // epoch hasn't advanced as we are still in the threshold range
-            try_surpassed_threshold(deps.as_mut(), env.clone()).unwrap();
+            let res = try_surpassed_threshold(deps.as_mut(), env.clone()).unwrap();
             assert_eq!(THRESHOLD.load(&deps.storage).unwrap(), 2);
             assert_eq!(CURRENT_EPOCH.load(&deps.storage).unwrap(), curr_epoch);
+            assert!(res.events.is_empty());
 
             *GROUP_MEMBERS.lock().unwrap().first_mut().unwrap() = (
                 Member {
@@ -763,7 +825,7 @@ pub(crate) mod tests {
                 },
                 1,
             );
-            try_surpassed_threshold(deps.as_mut(), env.clone()).unwrap();
+            let res = try_surpassed_threshold(deps.as_mut(), env.clone()).unwrap();
             assert!(THRESHOLD.may_load(&deps.storage).unwrap().is_none());
             let next_epoch = CURRENT_EPOCH.load(&deps.storage).unwrap();
             assert_eq!(
@@ -775,6 +837,10 @@ pub(crate) mod tests {
                     env.block.time,
                 )
             );
+            assert_eq!(
+                res.events,
+                vec![new_epoch_state_advance_event(&next_epoch, &[]).unwrap()]
+            );
         }
     }
 
@@ -822,6 +888,31 @@ pub(crate) mod tests {
         }
     }
 
+    #[test]
+    fn epoch_state_advance_event() {
+        let env = mock_env();
+        let epoch = Epoch::new(
+            EpochState::DealingExchange { resharing: true },
+            3,
+            TimeConfiguration::default(),
+            env.block.time,
+        );
+
+        let event = new_epoch_state_advance_event(&epoch, &[]).unwrap();
+        assert_eq!(event.ty, DKG_EPOCH_STATE_ADVANCE_EVENT_TYPE);
+        assert_eq!(event.attributes.len(), 2);
+        assert_eq!(event.attributes[0].value, "3");
+        assert_eq!(
+            event.attributes[1].value,
+            r#"{"dealing_exchange":{"resharing":true}}"#
+        );
+
+        let disqualified = [Addr::unchecked("owner1"), Addr::unchecked("owner2")];
+        let event = new_epoch_state_advance_event(&epoch, &disqualified).unwrap();
+        assert_eq!(event.attributes[2].key, DKG_DISQUALIFIED_DEALERS);
+        assert_eq!(event.attributes[2].value, "owner1,owner2");
+    }
+
     #[test]
     fn verify_threshold() {
         let mut deps = init_contract();
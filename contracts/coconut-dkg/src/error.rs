@@ -48,4 +48,16 @@ pub enum ContractError {
 
     #[error("No verification key committed for owner {owner}")]
     NoCommitForOwner { owner: String },
+
+    #[error("Dealers can't complain about themselves")]
+    SelfComplaint,
+
+    #[error("{dealer} is not a dealer for the current epoch")]
+    UnknownDealer { dealer: String },
+
+    #[error("This sender has already complained about {dealer}")]
+    AlreadyComplained { dealer: String },
+
+    #[error("This dealer has been disqualified for the current epoch")]
+    DisqualifiedDealer,
 }
@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::complaints::storage::is_disqualified;
 use crate::constants::BLOCK_TIME_FOR_VERIFICATION_SECS;
 use crate::dealers::storage as dealers_storage;
 use crate::epoch_state::storage::CURRENT_EPOCH;
@@ -30,6 +31,9 @@ pub fn try_commit_verification_key_share(
         .load(deps.storage, &info.sender)
         .map_err(|_| ContractError::NotADealer)?;
     let epoch_id = CURRENT_EPOCH.load(deps.storage)?.epoch_id;
+    if is_disqualified(deps.storage, epoch_id, &info.sender) {
+        return Err(ContractError::DisqualifiedDealer);
+    }
     if vk_shares()
         .may_load(deps.storage, (&info.sender, epoch_id))?
         .is_some()
@@ -91,13 +95,15 @@ pub fn try_verify_verification_key_share(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::complaints::storage::DISQUALIFIED_DEALERS;
     use crate::epoch_state::transactions::advance_epoch_state;
     use crate::support::tests::helpers;
     use crate::support::tests::helpers::{add_fixture_dealer, MULTISIG_CONTRACT};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cw_controllers::AdminError;
+    use nym_coconut_dkg_common::complaint::{ComplaintReason, DisqualifiedDealer};
     use nym_coconut_dkg_common::dealer::DealerDetails;
-    use nym_coconut_dkg_common::types::{EpochState, TimeConfiguration};
+    use nym_coconut_dkg_common::types::{Epoch, EpochState, TimeConfiguration};
 
     #[test]
     fn current_epoch_id() {
@@ -117,6 +123,11 @@ mod tests {
             .time
             .plus_seconds(TimeConfiguration::default().dealing_exchange_time_secs);
         advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(TimeConfiguration::default().complaint_submission_time_secs);
+        advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
         let dealer = Addr::unchecked("requester");
         let announce_address = String::from("localhost");
         let dealer_details = DealerDetails {
@@ -185,6 +196,11 @@ mod tests {
             .time
             .plus_seconds(TimeConfiguration::default().dealing_exchange_time_secs);
         advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(TimeConfiguration::default().complaint_submission_time_secs);
+        advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
         let ret = try_commit_verification_key_share(
             deps.as_mut(),
             env.clone(),
@@ -231,6 +247,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn disqualified_dealer_cannot_commit_vk_share() {
+        let mut deps = helpers::init_contract();
+        let env = mock_env();
+        let info = mock_info("requester", &[]);
+        let dealer = Addr::unchecked("requester");
+
+        CURRENT_EPOCH
+            .save(
+                deps.as_mut().storage,
+                &Epoch::new(
+                    EpochState::VerificationKeySubmission { resharing: false },
+                    0,
+                    TimeConfiguration::default(),
+                    env.block.time,
+                ),
+            )
+            .unwrap();
+        dealers_storage::current_dealers()
+            .save(
+                deps.as_mut().storage,
+                &dealer,
+                &DealerDetails {
+                    address: dealer.clone(),
+                    bte_public_key_with_proof: String::new(),
+                    announce_address: String::new(),
+                    assigned_index: 1,
+                },
+            )
+            .unwrap();
+        DISQUALIFIED_DEALERS
+            .save(
+                deps.as_mut().storage,
+                (0, &dealer),
+                &DisqualifiedDealer {
+                    dealer: dealer.clone(),
+                    epoch_id: 0,
+                    complaints: 1,
+                    reason: ComplaintReason::MissingDealing,
+                },
+            )
+            .unwrap();
+
+        let ret =
+            try_commit_verification_key_share(deps.as_mut(), env, info, "share".to_string(), false)
+                .unwrap_err();
+        assert_eq!(ret, ContractError::DisqualifiedDealer);
+    }
+
     #[test]
     fn invalid_verify_vk_share() {
         let mut deps = helpers::init_contract();
@@ -262,6 +327,11 @@ mod tests {
             .time
             .plus_seconds(TimeConfiguration::default().dealing_exchange_time_secs);
         advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(TimeConfiguration::default().complaint_submission_time_secs);
+        advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
         env.block.time = env
             .block
             .time
@@ -308,6 +378,11 @@ mod tests {
             .time
             .plus_seconds(TimeConfiguration::default().dealing_exchange_time_secs);
         advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(TimeConfiguration::default().complaint_submission_time_secs);
+        advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
 
         let dealer_details = DealerDetails {
             address: owner.clone(),
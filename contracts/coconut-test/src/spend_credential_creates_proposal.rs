@@ -13,6 +13,7 @@ use nym_group_contract_common::msg::InstantiateMsg as GroupInstantiateMsg;
 use nym_multisig_contract_common::msg::InstantiateMsg as MultisigInstantiateMsg;
 
 pub const TEST_COIN_DENOM: &str = "unym";
+pub const TEST_EXPIRY_DATE: u64 = 1_700_000_000;
 pub const TEST_COCONUT_BANDWIDTH_CONTRACT_ADDRESS: &str =
     "n19lc9u84cz0yz3fww5283nucc9yvr8gsjmgeul0";
 pub const TEST_COCONUT_DKG_CONTRACT_ADDRESS: &str = "n19lc9u84cz0yz3fww5283nucc9yvr8gsjmgeul0";
@@ -94,6 +95,7 @@ fn spend_credential_creates_proposal() {
             Coin::new(1, TEST_COIN_DENOM),
             String::from("blinded_serial_number"),
             String::from("gateway_cosmos_address"),
+            TEST_EXPIRY_DATE,
         ),
     };
     let res = app
@@ -137,6 +139,7 @@ fn spend_credential_creates_proposal() {
             Coin::new(1, TEST_COIN_DENOM),
             String::from("blinded_serial_number2"),
             String::from("gateway_cosmos_address"),
+            TEST_EXPIRY_DATE,
         ),
     };
     let res = app
@@ -345,6 +345,32 @@ pub fn execute(
                 deps, env, info, mix_id, delegate,
             )
         }
+        ExecuteMsg::RedelegateMixnode {
+            from_mix_id,
+            to_mix_id,
+            amount,
+        } => crate::delegations::transactions::try_redelegate_mixnode(
+            deps,
+            env,
+            info,
+            from_mix_id,
+            to_mix_id,
+            amount,
+        ),
+        ExecuteMsg::RedelegateMixnodeOnBehalf {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate,
+        } => crate::delegations::transactions::try_redelegate_mixnode_on_behalf(
+            deps,
+            env,
+            info,
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate,
+        ),
 
         // reward-related
         ExecuteMsg::RewardMixnode {
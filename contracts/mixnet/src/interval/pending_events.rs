@@ -1,15 +1,16 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use cosmwasm_std::{Addr, Coin, DepsMut, Env, Response};
+use cosmwasm_std::{Addr, Coin, Decimal, DepsMut, Env, Response, Storage};
 
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_active_set_update_event, new_delegation_event, new_delegation_on_unbonded_node_event,
     new_mixnode_cost_params_update_event, new_mixnode_unbonding_event, new_pledge_decrease_event,
-    new_pledge_increase_event, new_rewarding_params_update_event, new_undelegation_event,
+    new_pledge_increase_event, new_redelegation_event, new_redelegation_to_unbonded_node_event,
+    new_rewarding_params_update_event, new_undelegation_event,
 };
-use mixnet_contract_common::mixnode::MixNodeCostParams;
+use mixnet_contract_common::mixnode::{MixNodeCostParams, MixNodeRewarding};
 use mixnet_contract_common::pending_events::{
     PendingEpochEventData, PendingEpochEventKind, PendingIntervalEventData,
     PendingIntervalEventKind,
@@ -75,9 +76,33 @@ pub(crate) fn delegate(
         }
     };
 
-    let new_delegation_amount = amount.clone();
-    let mut mix_rewarding = mixnode_details.rewarding_details;
+    let unit_reward = increase_delegation(
+        deps.storage,
+        env,
+        owner.clone(),
+        mix_id,
+        mixnode_details.rewarding_details,
+        amount.clone(),
+        proxy.clone(),
+    )?;
+
+    let cosmos_event =
+        new_delegation_event(created_at, &owner, &proxy, &amount, mix_id, unit_reward);
+
+    Ok(Response::new().add_event(cosmos_event))
+}
 
+// adds the provided amount to the delegation of the owner towards the particular mixnode
+// (creating a fresh one if it didn't exist before) and returns the updated unit reward of the node
+fn increase_delegation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: Addr,
+    mix_id: MixId,
+    mut mix_rewarding: MixNodeRewarding,
+    amount: Coin,
+    proxy: Option<Addr>,
+) -> Result<Decimal, MixnetContractError> {
     // the delegation_amount might get increased if there's already a pre-existing delegation on this mixnode
     // (in that case we just create a fresh delegation with the sum of both)
     let mut stored_delegation_amount = amount;
@@ -86,7 +111,7 @@ pub(crate) fn delegate(
     // with the sum of both
     let storage_key = Delegation::generate_storage_key(mix_id, &owner, proxy.as_ref());
     let old_delegation = if let Some(existing_delegation) =
-        delegations_storage::delegations().may_load(deps.storage, storage_key.clone())?
+        delegations_storage::delegations().may_load(storage, storage_key.clone())?
     {
         // completely remove the delegation from the node
         let og_with_reward = mix_rewarding.undelegate(&existing_delegation)?;
@@ -103,15 +128,6 @@ pub(crate) fn delegate(
     // add the amount we're intending to delegate (whether it's fresh or we're adding to the existing one)
     mix_rewarding.add_base_delegation(stored_delegation_amount.amount)?;
 
-    let cosmos_event = new_delegation_event(
-        created_at,
-        &owner,
-        &proxy,
-        &new_delegation_amount,
-        mix_id,
-        mix_rewarding.total_unit_reward,
-    );
-
     let delegation = Delegation::new(
         owner,
         mix_id,
@@ -123,14 +139,14 @@ pub(crate) fn delegate(
 
     // save on reading since `.save()` would have attempted to read old data that we already have on hand
     delegations_storage::delegations().replace(
-        deps.storage,
+        storage,
         storage_key,
         Some(&delegation),
         old_delegation.as_ref(),
     )?;
-    rewards_storage::MIXNODE_REWARDING.save(deps.storage, mix_id, &mix_rewarding)?;
+    rewards_storage::MIXNODE_REWARDING.save(storage, mix_id, &mix_rewarding)?;
 
-    Ok(Response::new().add_event(cosmos_event))
+    Ok(mix_rewarding.total_unit_reward)
 }
 
 pub(crate) fn undelegate(
@@ -171,6 +187,114 @@ pub(crate) fn undelegate(
     Ok(response)
 }
 
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn redelegate(
+    deps: DepsMut<'_>,
+    env: &Env,
+    created_at: BlockHeight,
+    owner: Addr,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    // see if the delegation still exists (it might have been removed by an undelegation
+    // or another redelegation executed earlier in this epoch)
+    let source_key = Delegation::generate_storage_key(from_mix_id, &owner, proxy.as_ref());
+    let source_delegation =
+        match delegations_storage::delegations().may_load(deps.storage, source_key.clone())? {
+            None => return Ok(Response::default()),
+            Some(delegation) => delegation,
+        };
+
+    // check if the target node still exists and is not unbonding. if that's not the case,
+    // keep the delegation where it currently is, so that the delegator wouldn't lose any rewards
+    let target_rewarding = match get_mixnode_details_by_id(deps.storage, to_mix_id)? {
+        Some(details)
+            if details.rewarding_details.still_bonded()
+                && !details.bond_information.is_unbonding =>
+        {
+            details.rewarding_details
+        }
+        _ => {
+            return Ok(
+                Response::new().add_event(new_redelegation_to_unbonded_node_event(
+                    created_at,
+                    &owner,
+                    &proxy,
+                    from_mix_id,
+                    to_mix_id,
+                )),
+            )
+        }
+    };
+
+    let mut source_rewarding = rewards_storage::MIXNODE_REWARDING
+        .may_load(deps.storage, from_mix_id)?
+        .ok_or(MixnetContractError::inconsistent_state(
+            "mixnode rewarding got removed from the storage whilst there's still an existing delegation",
+        ))?;
+
+    // withdraw the entire delegation alongside all of its rewards and put back whatever is not being moved
+    let withdrawn = source_rewarding.undelegate(&source_delegation)?;
+    let moved = Coin {
+        denom: withdrawn.denom,
+        amount: amount.amount.min(withdrawn.amount),
+    };
+    let leftover = withdrawn.amount - moved.amount;
+
+    let remaining_delegation = if leftover.is_zero() {
+        None
+    } else {
+        source_rewarding.add_base_delegation(leftover)?;
+        Some(Delegation::new(
+            owner.clone(),
+            from_mix_id,
+            source_rewarding.total_unit_reward,
+            Coin::new(leftover.u128(), &moved.denom),
+            env.block.height,
+            proxy.clone(),
+        ))
+    };
+    delegations_storage::delegations().replace(
+        deps.storage,
+        source_key,
+        remaining_delegation.as_ref(),
+        Some(&source_delegation),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, from_mix_id, &source_rewarding)?;
+
+    increase_delegation(
+        deps.storage,
+        env,
+        owner.clone(),
+        to_mix_id,
+        target_rewarding,
+        moved.clone(),
+        proxy.clone(),
+    )?;
+
+    let response = Response::new()
+        .add_event(new_redelegation_event(
+            created_at,
+            &owner,
+            &proxy,
+            &moved,
+            from_mix_id,
+            to_mix_id,
+        ))
+        .maybe_add_track_vesting_redelegation_message(
+            deps.storage,
+            proxy,
+            owner.into_string(),
+            from_mix_id,
+            to_mix_id,
+            moved,
+        )?;
+
+    Ok(response)
+}
+
 pub(crate) fn unbond_mixnode(
     deps: DepsMut<'_>,
     env: &Env,
@@ -356,6 +480,22 @@ impl ContractExecutableEvent for PendingEpochEventData {
                 mix_id,
                 proxy,
             } => undelegate(deps, self.created_at, owner, mix_id, proxy),
+            PendingEpochEventKind::Redelegate {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            } => redelegate(
+                deps,
+                env,
+                self.created_at,
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            ),
             PendingEpochEventKind::PledgeMore { mix_id, amount } => {
                 increase_pledge(deps, self.created_at, mix_id, amount)
             }
@@ -1112,6 +1252,223 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod redelegating {
+        use cosmwasm_std::{coin, to_binary, CosmosMsg, WasmMsg};
+
+        use mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+
+        use crate::support::tests::fixtures::TEST_COIN_DENOM;
+        use crate::support::tests::test_helpers::get_bank_send_msg;
+
+        use super::*;
+
+        #[test]
+        fn doesnt_do_anything_if_delegation_doesnt_exist() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let env = test.env();
+            let owner = Addr::unchecked("delegator");
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                owner.clone(),
+                mix_id1,
+                mix_id2,
+                coin(100_000_000, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+            assert_eq!(res, Response::default());
+
+            let storage_key = Delegation::generate_storage_key(mix_id2, &owner, None);
+            assert!(delegations_storage::delegations()
+                .may_load(test.deps().storage, storage_key)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn keeps_the_delegation_if_target_mixnode_has_unbonded() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let delegation = 120_000_000u128;
+            let owner = "delegator";
+            test.add_immediate_delegation(owner, delegation, mix_id1);
+
+            let env = test.env();
+            unbond_mixnode(test.deps_mut(), &env, 123, mix_id2).unwrap();
+
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                coin(delegation, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+            assert!(get_bank_send_msg(&res).is_none());
+
+            let source = test.delegation(mix_id1, owner, &None);
+            assert_eq!(source.amount, coin(delegation, TEST_COIN_DENOM));
+
+            let storage_key =
+                Delegation::generate_storage_key(mix_id2, &Addr::unchecked(owner), None);
+            assert!(delegations_storage::delegations()
+                .may_load(test.deps().storage, storage_key)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn moves_the_requested_amount_and_keeps_the_rest() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let owner = "delegator";
+            test.add_immediate_delegation(owner, 120_000_000u128, mix_id1);
+
+            let env = test.env();
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                coin(50_000_000, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+
+            // tokens never leave the contract
+            assert!(get_bank_send_msg(&res).is_none());
+
+            let source = test.delegation(mix_id1, owner, &None);
+            assert_eq!(source.amount, coin(70_000_000, TEST_COIN_DENOM));
+            let target = test.delegation(mix_id2, owner, &None);
+            assert_eq!(target.amount, coin(50_000_000, TEST_COIN_DENOM));
+
+            let source_rewarding = test.mix_rewarding(mix_id1);
+            assert_eq!(
+                source_rewarding.delegates,
+                Decimal::from_atomics(70_000_000u128, 0).unwrap()
+            );
+            assert_eq!(source_rewarding.unique_delegations, 1);
+            let target_rewarding = test.mix_rewarding(mix_id2);
+            assert_eq!(
+                target_rewarding.delegates,
+                Decimal::from_atomics(50_000_000u128, 0).unwrap()
+            );
+            assert_eq!(target_rewarding.unique_delegations, 1);
+        }
+
+        #[test]
+        fn moves_entire_delegation_with_earned_rewards_if_requested_amount_exceeds_it() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", Some(100_000_000_000u128.into()));
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let owner = "delegator";
+            let delegation = 120_000_000u128;
+            test.add_immediate_delegation(owner, delegation, mix_id1);
+
+            test.force_change_rewarded_set(vec![mix_id1]);
+            test.skip_to_next_epoch_end();
+            let dist = test.reward_with_distribution_with_state_bypass(
+                mix_id1,
+                test_helpers::performance(100.0),
+            );
+            let expected_amount = delegation + truncate_reward_amount(dist.delegates).u128();
+
+            let env = test.env();
+            redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                coin(delegation * 10, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+
+            let storage_key =
+                Delegation::generate_storage_key(mix_id1, &Addr::unchecked(owner), None);
+            assert!(delegations_storage::delegations()
+                .may_load(test.deps().storage, storage_key)
+                .unwrap()
+                .is_none());
+            assert_eq!(test.mix_rewarding(mix_id1).unique_delegations, 0);
+
+            let target = test.delegation(mix_id2, owner, &None);
+            assert_eq!(target.amount, coin(expected_amount, TEST_COIN_DENOM));
+        }
+
+        #[test]
+        fn attaches_vesting_contract_track_message() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let delegation = 120_000_000u128;
+            let moved = coin(20_000_000, TEST_COIN_DENOM);
+            let owner = "delegator";
+
+            let vesting_contract = test.vesting_contract();
+            test.add_immediate_delegation_with_legal_proxy(owner, delegation, mix_id1);
+
+            let env = test.env();
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                moved.clone(),
+                Some(vesting_contract.clone()),
+            )
+            .unwrap();
+
+            let target = test.delegation(mix_id2, owner, &Some(vesting_contract.clone()));
+            assert_eq!(target.amount, moved);
+
+            let mut found_track = false;
+            for msg in &res.messages {
+                if let CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) = &msg.msg
+                {
+                    found_track = true;
+                    assert_eq!(contract_addr, vesting_contract.as_str());
+                    let expected_msg = to_binary(&VestingContractExecuteMsg::TrackRedelegation {
+                        owner: owner.to_string(),
+                        from_mix_id: mix_id1,
+                        to_mix_id: mix_id2,
+                        amount: moved.clone(),
+                    })
+                    .unwrap();
+                    assert_eq!(&expected_msg, msg);
+                    assert!(funds.is_empty())
+                }
+            }
+            assert!(found_track);
+        }
+    }
+
     #[cfg(test)]
     mod mixnode_unbonding {
         use cosmwasm_std::{coin, to_binary, CosmosMsg, Uint128, WasmMsg};
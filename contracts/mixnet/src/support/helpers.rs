@@ -40,6 +40,16 @@ where
         amount: Coin,
     ) -> Result<Self, MixnetContractError>;
 
+    fn maybe_add_track_vesting_redelegation_message(
+        self,
+        storage: &dyn Storage,
+        proxy: Option<Addr>,
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+    ) -> Result<Self, MixnetContractError>;
+
     fn maybe_add_track_vesting_unbond_mixnode_message(
         self,
         storage: &dyn Storage,
@@ -97,6 +107,40 @@ impl VestingTracking for Response {
         }
     }
 
+    fn maybe_add_track_vesting_redelegation_message(
+        self,
+        storage: &dyn Storage,
+        proxy: Option<Addr>,
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+    ) -> Result<Self, MixnetContractError> {
+        if let Some(proxy) = proxy {
+            let vesting_contract = mixnet_params_storage::vesting_contract_address(storage)?;
+
+            // exactly the same possible halting behaviour as in `maybe_add_track_vesting_undelegation_message`.
+            if proxy != vesting_contract {
+                return Err(MixnetContractError::ProxyIsNotVestingContract {
+                    received: proxy,
+                    vesting_contract,
+                });
+            }
+
+            let msg = VestingContractExecuteMsg::TrackRedelegation {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+            };
+            let track_redelegation_message = wasm_execute(proxy, &msg, vec![])?;
+            Ok(self.add_message(track_redelegation_message))
+        } else {
+            // there's no proxy so nothing to do
+            Ok(self)
+        }
+    }
+
     fn maybe_add_track_vesting_unbond_mixnode_message(
         self,
         storage: &dyn Storage,
@@ -123,6 +123,12 @@ pub fn execute(
             mix_id,
             on_behalf_of,
         } => try_undelegate_from_mixnode(mix_id, on_behalf_of, info, deps),
+        ExecuteMsg::RedelegateMixnode {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            on_behalf_of,
+        } => try_redelegate_mixnode(from_mix_id, to_mix_id, amount, on_behalf_of, info, deps),
         ExecuteMsg::CreateAccount {
             owner_address,
             staking_address,
@@ -145,6 +151,12 @@ pub fn execute(
             mix_id,
             amount,
         } => try_track_undelegation(&owner, mix_id, amount, info, deps),
+        ExecuteMsg::TrackRedelegation {
+            owner,
+            from_mix_id,
+            to_mix_id,
+            amount,
+        } => try_track_redelegation(&owner, from_mix_id, to_mix_id, amount, info, deps),
         ExecuteMsg::BondMixnode {
             mix_node,
             cost_params,
@@ -1,13 +1,13 @@
 use crate::contract::MAX_PER_MIX_DELEGATIONS;
 use crate::errors::ContractError;
-use crate::storage::save_delegation;
 use crate::storage::MIXNET_CONTRACT_ADDRESS;
+use crate::storage::{remove_delegation, save_delegation, DELEGATIONS};
 use crate::traits::DelegatingAccount;
-use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
+use cosmwasm_std::{wasm_execute, Coin, Env, Order, Response, Storage, Uint128};
 use mixnet_contract_common::ExecuteMsg as MixnetExecuteMsg;
 use mixnet_contract_common::MixId;
 use vesting_contract_common::events::{
-    new_vesting_delegation_event, new_vesting_undelegation_event,
+    new_vesting_delegation_event, new_vesting_redelegation_event, new_vesting_undelegation_event,
 };
 
 use super::Account;
@@ -95,6 +95,48 @@ impl DelegatingAccount for Account {
             .add_event(new_vesting_undelegation_event()))
     }
 
+    fn try_redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if !self.any_delegation_for_mix(from_mix_id, storage) {
+            return Err(ContractError::NoSuchDelegation(
+                self.owner_address(),
+                from_mix_id,
+            ));
+        }
+
+        // in the worst case all of the existing subdelegations are going to be moved
+        // to the target node
+        let num_subdelegations = self.num_subdelegations_for_mix(from_mix_id, storage)
+            + self.num_subdelegations_for_mix(to_mix_id, storage);
+        if num_subdelegations > MAX_PER_MIX_DELEGATIONS {
+            return Err(ContractError::TooManyDelegations {
+                address: self.owner_address.clone(),
+                acc_id: self.storage_key(),
+                mix_id: to_mix_id,
+                num: num_subdelegations,
+                cap: MAX_PER_MIX_DELEGATIONS,
+            });
+        }
+
+        let msg = MixnetExecuteMsg::RedelegateMixnodeOnBehalf {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate: self.owner_address().into_string(),
+        };
+        let redelegate_mixnode =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(redelegate_mixnode)
+            .add_event(new_vesting_redelegation_event()))
+    }
+
     fn track_delegation(
         &self,
         block_timestamp_secs: u64,
@@ -124,4 +166,47 @@ impl DelegatingAccount for Account {
         self.save_balance(new_balance, storage)?;
         Ok(())
     }
+
+    fn track_redelegation(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        // note that the limit is implicitly set to `MAX_PER_MIX_DELEGATIONS`
+        // as it should be impossible to create more delegations than that.
+        let subdelegations = DELEGATIONS
+            .prefix((self.storage_key(), from_mix_id))
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // move the oldest subdelegations first while preserving their timestamps,
+        // so that the vesting calculations would remain unaffected.
+        // the moved amount might include rewards, which were never tracked in the first place,
+        // so we can't move more than what's been recorded
+        let mut remaining = amount.amount;
+        for (block_timestamp, subdelegation) in subdelegations {
+            if remaining.is_zero() {
+                break;
+            }
+            let moved = remaining.min(subdelegation);
+            remaining -= moved;
+
+            remove_delegation((self.storage_key(), from_mix_id, block_timestamp), storage)?;
+            if moved < subdelegation {
+                save_delegation(
+                    (self.storage_key(), from_mix_id, block_timestamp),
+                    subdelegation - moved,
+                    storage,
+                )?;
+            }
+            save_delegation(
+                (self.storage_key(), to_mix_id, block_timestamp),
+                moved,
+                storage,
+            )?;
+        }
+        Ok(())
+    }
 }
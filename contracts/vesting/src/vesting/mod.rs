@@ -647,6 +647,90 @@ mod tests {
         assert_eq!(Uint128::new(90_000_000_000), total_delegations);
     }
 
+    #[test]
+    fn test_redelegations() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        let account = vesting_account_new_fixture(&mut deps.storage, &env);
+
+        let err = account.try_redelegate_mixnode(
+            1,
+            2,
+            coin(1_000_000_000, TEST_COIN_DENOM),
+            &deps.storage,
+        );
+        assert!(matches!(err, Err(ContractError::NoSuchDelegation(..))));
+
+        let balance = account.load_balance(&deps.storage).unwrap();
+        account
+            .track_delegation(
+                env.block.time.seconds(),
+                1,
+                balance,
+                coin(10_000_000_000, TEST_COIN_DENOM),
+                &mut deps.storage,
+            )
+            .unwrap();
+        env.block.time = env.block.time.plus_seconds(42);
+        let balance = account.load_balance(&deps.storage).unwrap();
+        account
+            .track_delegation(
+                env.block.time.seconds(),
+                1,
+                balance,
+                coin(5_000_000_000, TEST_COIN_DENOM),
+                &mut deps.storage,
+            )
+            .unwrap();
+        let balance = account.load_balance(&deps.storage).unwrap();
+
+        let ok = account.try_redelegate_mixnode(
+            1,
+            2,
+            coin(12_000_000_000, TEST_COIN_DENOM),
+            &deps.storage,
+        );
+        assert!(ok.is_ok());
+
+        // the oldest subdelegation is moved first
+        account
+            .track_redelegation(
+                1,
+                2,
+                coin(12_000_000_000, TEST_COIN_DENOM),
+                &mut deps.storage,
+            )
+            .unwrap();
+        assert_eq!(
+            account.total_delegations_for_mix(1, &deps.storage).unwrap(),
+            Uint128::new(3_000_000_000)
+        );
+        assert_eq!(
+            account.total_delegations_for_mix(2, &deps.storage).unwrap(),
+            Uint128::new(12_000_000_000)
+        );
+        assert_eq!(account.num_subdelegations_for_mix(1, &deps.storage), 1);
+        assert_eq!(account.num_subdelegations_for_mix(2, &deps.storage), 2);
+
+        // moving more than what's tracked (i.e. including rewards) only moves what's been recorded
+        account
+            .track_redelegation(
+                1,
+                2,
+                coin(100_000_000_000, TEST_COIN_DENOM),
+                &mut deps.storage,
+            )
+            .unwrap();
+        assert!(!account.any_delegation_for_mix(1, &deps.storage));
+        assert_eq!(
+            account.total_delegations_for_mix(2, &deps.storage).unwrap(),
+            Uint128::new(15_000_000_000)
+        );
+
+        // the tokens never left the mixnet contract
+        assert_eq!(account.load_balance(&deps.storage).unwrap(), balance);
+    }
+
     #[test]
     fn test_mixnode_bonds() {
         let mut deps = init_contract();
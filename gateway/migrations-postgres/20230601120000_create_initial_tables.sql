@@ -0,0 +1,30 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- equivalent of all the sqlite migrations (located in the `migrations` directory) applied in order
+
+CREATE TABLE shared_keys
+(
+    client_address_bs58                      TEXT NOT NULL PRIMARY KEY,
+    derived_aes128_ctr_blake3_hmac_keys_bs58 TEXT NOT NULL
+);
+
+CREATE TABLE message_store
+(
+    id                  BIGSERIAL NOT NULL PRIMARY KEY,
+    client_address_bs58 TEXT      NOT NULL,
+    content             BYTEA     NOT NULL,
+    -- unix timestamp (in seconds) of when the message got stored.
+    timestamp           BIGINT    NOT NULL DEFAULT 0
+);
+
+CREATE TABLE available_bandwidth
+(
+    client_address_bs58 TEXT   NOT NULL PRIMARY KEY,
+    available           BIGINT NOT NULL
+);
+
+CREATE INDEX message_store_timestamp_index ON message_store (timestamp);
+CREATE INDEX message_store_client_index ON message_store (client_address_bs58, id);
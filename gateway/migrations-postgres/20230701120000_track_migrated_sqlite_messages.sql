@@ -0,0 +1,14 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- messages copied over from the sqlite databases of individual gateways, identified by the identity key
+-- of the gateway and the id the message had in its database, so that re-running the migration
+-- would not duplicate (or redeliver) any of them
+CREATE TABLE migrated_sqlite_messages
+(
+    gateway_identity_bs58 TEXT   NOT NULL,
+    sqlite_id             BIGINT NOT NULL,
+    PRIMARY KEY (gateway_identity_bs58, sqlite_id)
+);
@@ -0,0 +1,10 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- clients banned by the gateway operator, which are not allowed to (re)connect until the ban is lifted
+CREATE TABLE banned_clients
+(
+    client_address_bs58 TEXT NOT NULL PRIMARY KEY
+);
@@ -0,0 +1,13 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- unix timestamp (in seconds) of when the message got stored.
+-- sqlite does not allow non-constant defaults when altering tables, so the existing messages
+-- are treated as if they were stored at the time of running the migration
+ALTER TABLE message_store ADD COLUMN timestamp INTEGER NOT NULL DEFAULT 0;
+UPDATE message_store SET timestamp = CAST(strftime('%s', 'now') AS INTEGER);
+
+CREATE INDEX `message_store_timestamp_index` ON `message_store` (`timestamp`);
+CREATE INDEX `message_store_client_index` ON `message_store` (`client_address_bs58`, `id`);
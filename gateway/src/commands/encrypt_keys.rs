@@ -0,0 +1,42 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::commands::OverrideConfig;
+use crate::config::persistence::pathfinder::GatewayPathfinder;
+use crate::support::config::build_config;
+use clap::Args;
+use nym_pemstore::PASSPHRASE_ENV_VAR;
+use std::error::Error;
+
+#[derive(Args, Clone)]
+pub struct EncryptKeys {
+    /// The id of the gateway whose keys should be encrypted
+    #[clap(long)]
+    id: String,
+}
+
+pub fn execute(args: EncryptKeys) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = build_config(args.id, OverrideConfig::default())?;
+    let pathfinder = GatewayPathfinder::new_from_config(&config);
+
+    let Some(passphrase) = pathfinder.passphrase() else {
+        return Err(format!(
+            "the passphrase must be provided via the {PASSPHRASE_ENV_VAR} environment variable"
+        )
+        .into());
+    };
+    let Some(keys_dir) = pathfinder.keys_dir() else {
+        return Err("could not determine the keys directory".into());
+    };
+
+    let encrypted = nym_pemstore::encrypt_pem_directory(keys_dir, passphrase)?;
+    for path in &encrypted {
+        eprintln!("Encrypted {}", path.display());
+    }
+    eprintln!(
+        "Encrypted {} key(s) in {}. Remember to set {PASSPHRASE_ENV_VAR} whenever running the gateway",
+        encrypted.len(),
+        keys_dir.display()
+    );
+    Ok(())
+}
@@ -129,21 +129,23 @@ pub async fn execute(args: Init) -> Result<(), Box<dyn Error + Send + Sync>> {
         let identity_keys = identity::KeyPair::new(&mut rng);
         let sphinx_keys = encryption::KeyPair::new(&mut rng);
         let pathfinder = GatewayPathfinder::new_from_config(&config);
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &sphinx_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
             ),
+            pathfinder.passphrase(),
         )
         .expect("Failed to save sphinx keys");
 
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &identity_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
             ),
+            pathfinder.passphrase(),
         )
         .expect("Failed to save identity keys");
 
@@ -193,6 +195,11 @@ mod tests {
 
         let config = Config::new(&args.id);
         let config = override_config(config, OverrideConfig::from(args.clone())).unwrap();
+        let storage = InMemStorage::new(
+            config.get_message_retrieval_limit(),
+            config.get_max_client_inbox_size(),
+            config.get_inbox_drop_policy(),
+        );
 
         let (identity_keys, sphinx_keys) = {
             let mut rng = rand::rngs::OsRng;
@@ -204,7 +211,6 @@ mod tests {
 
         // The test is really if this instantiates with InMemStorage without panics
         let _gateway =
-            Gateway::new_from_keys_and_storage(config, identity_keys, sphinx_keys, InMemStorage)
-                .await;
+            Gateway::new_from_keys_and_storage(config, identity_keys, sphinx_keys, storage).await;
     }
 }
@@ -0,0 +1,47 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::commands::OverrideConfig;
+use crate::node::storage::postgres::migrate_from_sqlite;
+use crate::support::config::build_config;
+use clap::Args;
+use nym_crypto::asymmetric::identity;
+use std::error::Error;
+
+#[derive(Args, Clone)]
+pub struct MigrateStorage {
+    /// The id of the gateway whose sqlite database is going to be migrated
+    #[clap(long)]
+    id: String,
+
+    /// Connection string of the target PostgreSQL database.
+    /// If not provided, the `postgres_url` from the gateway config is used.
+    #[clap(long)]
+    postgres_url: Option<String>,
+}
+
+pub async fn execute(args: MigrateStorage) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = build_config(args.id.clone(), OverrideConfig::default())?;
+
+    let Some(postgres_url) = args
+        .postgres_url
+        .or_else(|| config.get_postgres_url().map(ToOwned::to_owned))
+    else {
+        return Err("the url of the target PostgreSQL database has not been provided".into());
+    };
+
+    // the identity distinguishes the messages of this gateway from the ones migrated from other gateways
+    let identity: identity::PublicKey =
+        nym_pemstore::load_key(&config.get_public_identity_key_file())?;
+
+    let sqlite_path = config.get_persistent_store_path();
+    eprintln!(
+        "Migrating the data of gateway {} from {} to the PostgreSQL database...",
+        args.id,
+        sqlite_path.display()
+    );
+    migrate_from_sqlite(sqlite_path, &identity.to_base58_string(), &postgres_url).await?;
+
+    eprintln!("Migration complete. Set `postgres_url` in the gateway config in order to start using the new database");
+    Ok(())
+}
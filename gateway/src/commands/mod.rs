@@ -15,7 +15,10 @@ use std::error::Error;
 use std::net::IpAddr;
 use std::path::PathBuf;
 
+pub(crate) mod encrypt_keys;
 pub(crate) mod init;
+#[cfg(feature = "postgres")]
+pub(crate) mod migrate_storage;
 pub(crate) mod node_details;
 pub(crate) mod run;
 pub(crate) mod sign;
@@ -23,9 +26,16 @@ pub(crate) mod upgrade;
 
 #[derive(Subcommand)]
 pub(crate) enum Commands {
+    /// Encrypt, in place, the private keys of the gateway using the passphrase from the environment
+    EncryptKeys(encrypt_keys::EncryptKeys),
+
     /// Initialise the gateway
     Init(init::Init),
 
+    /// Copy the data of the gateway from its sqlite database into a PostgreSQL database
+    #[cfg(feature = "postgres")]
+    MigrateStorage(migrate_storage::MigrateStorage),
+
     /// Show details of this gateway
     NodeDetails(node_details::NodeDetails),
 
@@ -66,7 +76,10 @@ pub(crate) async fn execute(args: Cli) -> Result<(), Box<dyn Error + Send + Sync
     let bin_name = "nym-gateway";
 
     match args.command {
+        Commands::EncryptKeys(m) => encrypt_keys::execute(m)?,
         Commands::Init(m) => init::execute(m).await?,
+        #[cfg(feature = "postgres")]
+        Commands::MigrateStorage(m) => migrate_storage::execute(m).await?,
         Commands::NodeDetails(m) => node_details::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Sign(m) => sign::execute(m)?,
@@ -2,6 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::commands::{ensure_config_version_compatibility, OverrideConfig};
+#[cfg(not(feature = "postgres"))]
+use crate::error::GatewayError;
+use crate::node::storage::Storage;
+use crate::node::Gateway;
 use crate::support::config::build_config;
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
@@ -125,7 +129,37 @@ pub async fn execute(args: Run) -> Result<(), Box<dyn Error + Send + Sync>> {
         show_binding_warning(config.get_listening_address().to_string());
     }
 
-    let mut gateway = crate::node::create_gateway(config).await;
+    if config.get_ephemeral_storage() {
+        let gateway = crate::node::create_ephemeral_gateway(config).await;
+        return run_gateway(gateway, output).await;
+    }
+
+    if let Some(database_url) = config.get_postgres_url() {
+        #[cfg(feature = "postgres")]
+        {
+            let database_url = database_url.to_owned();
+            let gateway = crate::node::create_postgres_gateway(config, &database_url).await;
+            return run_gateway(gateway, output).await;
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let _ = database_url;
+            return Err(GatewayError::PostgresNotSupported.into());
+        }
+    }
+
+    let gateway = crate::node::create_gateway(config).await;
+    run_gateway(gateway, output).await
+}
+
+async fn run_gateway<St>(
+    mut gateway: Gateway<St>,
+    output: OutputFormat,
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    St: Storage + Clone + 'static,
+{
     eprintln!(
         "\nTo bond your gateway you will need to install the Nym wallet, go to https://nymtech.net/get-involved and select the Download button.\n\
          Select the correct version and install it to your machine. You will need to provide the following: \n ");
@@ -66,12 +66,14 @@ impl TryFrom<Sign> for SignedTarget {
 }
 
 pub fn load_identity_keys(pathfinder: &GatewayPathfinder) -> identity::KeyPair {
-    let identity_keypair: identity::KeyPair =
-        nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+    let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+        &nym_pemstore::KeyPairPath::new(
             pathfinder.private_identity_key().to_owned(),
             pathfinder.public_identity_key().to_owned(),
-        ))
-        .expect("Failed to read stored identity key files");
+        ),
+        pathfinder.passphrase(),
+    )
+    .expect("Failed to read stored identity key files");
     identity_keypair
 }
 
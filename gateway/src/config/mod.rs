@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::template::config_template;
-use nym_config::defaults::{DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT};
+use nym_config::defaults::{
+    DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_HTTP_API_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT,
+};
 use nym_config::NymConfig;
 use nym_network_defaults::mainnet::{NYM_API, NYXD_URL, STATISTICS_SERVICE_DOMAIN_ADDRESS};
 use nym_validator_client::nyxd;
@@ -28,6 +30,12 @@ const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
 
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
+const DEFAULT_MESSAGE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_INBOX_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_MAX_CLIENT_INBOX_SIZE: u64 = 64 * 1024 * 1024;
+
+const DEFAULT_METRICS_PORT: u16 = 9101;
+const DEFAULT_ADMIN_API_PORT: u16 = 8001;
 
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
@@ -37,6 +45,10 @@ fn bind_all_address() -> IpAddr {
     "0.0.0.0".parse().unwrap()
 }
 
+fn localhost_address() -> IpAddr {
+    "127.0.0.1".parse().unwrap()
+}
+
 fn default_mix_port() -> u16 {
     DEFAULT_MIX_LISTENING_PORT
 }
@@ -45,6 +57,18 @@ fn default_clients_port() -> u16 {
     DEFAULT_CLIENT_LISTENING_PORT
 }
 
+fn default_metrics_port() -> u16 {
+    DEFAULT_METRICS_PORT
+}
+
+fn default_http_api_port() -> u16 {
+    DEFAULT_HTTP_API_LISTENING_PORT
+}
+
+fn default_admin_api_port() -> u16 {
+    DEFAULT_ADMIN_API_PORT
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Config {
     gateway: Gateway,
@@ -259,10 +283,54 @@ impl Config {
         self.gateway.clients_port
     }
 
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.gateway.enable_metrics
+    }
+
+    pub fn get_metrics_address(&self) -> IpAddr {
+        self.gateway.metrics_address
+    }
+
+    pub fn get_metrics_port(&self) -> u16 {
+        self.gateway.metrics_port
+    }
+
+    pub fn get_http_api_port(&self) -> u16 {
+        self.gateway.http_api_port
+    }
+
+    pub fn get_admin_api_address(&self) -> IpAddr {
+        self.gateway.admin_api_address
+    }
+
+    pub fn get_admin_api_port(&self) -> u16 {
+        self.gateway.admin_api_port
+    }
+
+    pub fn get_admin_api_token(&self) -> Option<&str> {
+        if self.gateway.admin_api_token.is_empty() {
+            None
+        } else {
+            Some(&self.gateway.admin_api_token)
+        }
+    }
+
     pub fn get_persistent_store_path(&self) -> PathBuf {
         self.gateway.persistent_storage.clone()
     }
 
+    pub fn get_postgres_url(&self) -> Option<&str> {
+        if self.gateway.postgres_url.is_empty() {
+            None
+        } else {
+            Some(&self.gateway.postgres_url)
+        }
+    }
+
+    pub fn get_ephemeral_storage(&self) -> bool {
+        self.gateway.ephemeral_storage
+    }
+
     pub fn get_packet_forwarding_initial_backoff(&self) -> Duration {
         self.debug.packet_forwarding_initial_backoff
     }
@@ -287,6 +355,22 @@ impl Config {
         self.debug.message_retrieval_limit
     }
 
+    pub fn get_message_max_age(&self) -> Duration {
+        self.debug.message_max_age
+    }
+
+    pub fn get_inbox_pruning_interval(&self) -> Duration {
+        self.debug.inbox_pruning_interval
+    }
+
+    pub fn get_max_client_inbox_size(&self) -> u64 {
+        self.debug.max_client_inbox_size
+    }
+
+    pub fn get_inbox_drop_policy(&self) -> InboxDropPolicy {
+        self.debug.inbox_drop_policy
+    }
+
     pub fn get_version(&self) -> &str {
         &self.gateway.version
     }
@@ -331,6 +415,42 @@ pub struct Gateway {
     #[serde(default = "default_clients_port")]
     clients_port: u16,
 
+    /// Whether the gateway metrics should be exposed in the Prometheus format.
+    #[serde(default)]
+    enable_metrics: bool,
+
+    /// Address the metrics server will bind to.
+    /// (default: 127.0.0.1)
+    #[serde(default = "localhost_address")]
+    metrics_address: IpAddr,
+
+    /// Port used for exposing the gateway metrics in the Prometheus format.
+    /// (default: 9101)
+    #[serde(default = "default_metrics_port")]
+    metrics_port: u16,
+
+    /// Port used for the public http api exposing information about the gateway.
+    /// (default: 8000)
+    #[serde(default = "default_http_api_port")]
+    http_api_port: u16,
+
+    /// Address the administrative http api, used for listing, disconnecting or banning clients,
+    /// binds to. Note that the api is served over plain http, so unless it's placed behind
+    /// a TLS-terminating proxy, it should not be reachable from outside of the machine.
+    /// (default: 127.0.0.1)
+    #[serde(default = "localhost_address")]
+    admin_api_address: IpAddr,
+
+    /// Port used for the administrative http api.
+    /// (default: 8001)
+    #[serde(default = "default_admin_api_port")]
+    admin_api_port: u16,
+
+    /// Bearer token required by the administrative http api.
+    /// If left empty, the administrative api is disabled.
+    #[serde(default)]
+    admin_api_token: String,
+
     /// Path to file containing private identity key.
     private_identity_key_file: PathBuf,
 
@@ -368,6 +488,18 @@ pub struct Gateway {
     /// derived shared keys and available client bandwidths.
     persistent_storage: PathBuf,
 
+    /// Optional connection string of a PostgreSQL database to use instead of the sqlite `persistent_storage`.
+    /// It allows multiple gateway processes to share the client state.
+    /// Requires the gateway to be compiled with the `postgres` feature.
+    #[serde(default)]
+    postgres_url: String,
+
+    /// Specifies whether the gateway should keep all of its client state (shared keys, inboxes and bandwidth)
+    /// only in memory, i.e. nothing is going to be persisted on the disk and everything is lost upon restart.
+    /// It takes precedence over both `persistent_storage` and `postgres_url`.
+    #[serde(default)]
+    ephemeral_storage: bool,
+
     /// The Cosmos wallet address that will control this gateway
     // the only reason this is an Option is because of the lack of existence of a sane default value
     wallet_address: Option<nyxd::AccountId>,
@@ -405,6 +537,13 @@ impl Default for Gateway {
             announce_address: "127.0.0.1".to_string(),
             mix_port: DEFAULT_MIX_LISTENING_PORT,
             clients_port: DEFAULT_CLIENT_LISTENING_PORT,
+            enable_metrics: false,
+            metrics_address: localhost_address(),
+            metrics_port: DEFAULT_METRICS_PORT,
+            http_api_port: DEFAULT_HTTP_API_LISTENING_PORT,
+            admin_api_address: localhost_address(),
+            admin_api_port: DEFAULT_ADMIN_API_PORT,
+            admin_api_token: "".to_string(),
             private_identity_key_file: Default::default(),
             public_identity_key_file: Default::default(),
             private_sphinx_key_file: Default::default(),
@@ -417,6 +556,8 @@ impl Default for Gateway {
             cosmos_mnemonic: bip39::Mnemonic::generate(24).unwrap(),
             nym_root_directory: Config::default_root_directory(),
             persistent_storage: Default::default(),
+            postgres_url: "".to_string(),
+            ephemeral_storage: false,
             wallet_address: None,
         }
     }
@@ -426,6 +567,18 @@ impl Default for Gateway {
 #[serde(deny_unknown_fields)]
 struct Logging {}
 
+/// Specifies which messages get dropped once the inbox of an offline client reaches its size limit.
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxDropPolicy {
+    /// Remove the oldest stored messages to make space for the new one.
+    #[default]
+    DropOldest,
+
+    /// Keep the stored messages and reject the new one.
+    DropNewest,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 struct Debug {
@@ -456,6 +609,20 @@ struct Debug {
     /// Number of messages from offline client that can be pulled at once from the storage.
     message_retrieval_limit: i64,
 
+    /// Maximum duration for which messages for offline clients are kept in the storage.
+    #[serde(with = "humantime_serde")]
+    message_max_age: Duration,
+
+    /// Delay between subsequent runs of the task removing expired messages from the storage.
+    #[serde(with = "humantime_serde")]
+    inbox_pruning_interval: Duration,
+
+    /// Maximum number of bytes that can be stored for a single offline client.
+    max_client_inbox_size: u64,
+
+    /// Specifies which messages get dropped once the inbox of a client is full.
+    inbox_drop_policy: InboxDropPolicy,
+
     /// Specifies whether the mixnode should be using the legacy framing for the sphinx packets.
     // it's set to true by default. The reason for that decision is to preserve compatibility with the
     // existing nodes whilst everyone else is upgrading and getting the code for handling the new field.
@@ -473,6 +640,10 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
+            message_max_age: DEFAULT_MESSAGE_MAX_AGE,
+            inbox_pruning_interval: DEFAULT_INBOX_PRUNING_INTERVAL,
+            max_client_inbox_size: DEFAULT_MAX_CLIENT_INBOX_SIZE,
+            inbox_drop_policy: InboxDropPolicy::default(),
             // TODO: remember to change it in one of future releases!!
             use_legacy_framed_packet_version: true,
         }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
+use nym_pemstore::Passphrase;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
@@ -11,6 +12,10 @@ pub struct GatewayPathfinder {
     pub public_sphinx_key: PathBuf,
     pub private_identity_key: PathBuf,
     pub public_identity_key: PathBuf,
+
+    /// Passphrase used for encrypting the private keys. If not set, the keys are stored in
+    /// plaintext.
+    pub passphrase: Option<Passphrase>,
 }
 
 impl GatewayPathfinder {
@@ -21,9 +26,19 @@ impl GatewayPathfinder {
             public_sphinx_key: config.get_public_sphinx_key_file(),
             private_identity_key: config.get_private_identity_key_file(),
             public_identity_key: config.get_public_identity_key_file(),
+            passphrase: Passphrase::from_env(),
         }
     }
 
+    pub fn passphrase(&self) -> Option<&Passphrase> {
+        self.passphrase.as_ref()
+    }
+
+    /// Directory containing all the keys of the node.
+    pub fn keys_dir(&self) -> Option<&Path> {
+        self.private_identity_key().parent()
+    }
+
     pub fn private_identity_key(&self) -> &Path {
         &self.private_identity_key
     }
@@ -38,6 +38,9 @@ pub(crate) enum GatewayError {
         source: ValidatorClientError,
     },
 
+    #[error("the gateway is configured to use a PostgreSQL database, but it has been compiled without the 'postgres' feature")]
+    PostgresNotSupported,
+
     #[error("address {account} has an invalid bech32 prefix. it uses '{actual_prefix}' while '{expected_prefix}' was expected")]
     InvalidBech32AccountPrefix {
         account: AccountId,
@@ -2,17 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::node::client_handling::websocket::message_receiver::MixMessageSender;
-use dashmap::DashMap;
+use crate::node::statistics::metrics;
+use dashmap::{DashMap, DashSet};
 use nym_sphinx::DestinationAddressBytes;
 use std::sync::Arc;
 
 #[derive(Clone)]
-pub(crate) struct ActiveClientsStore(Arc<DashMap<DestinationAddressBytes, MixMessageSender>>);
+pub(crate) struct ActiveClientsStore {
+    clients: Arc<DashMap<DestinationAddressBytes, MixMessageSender>>,
+
+    /// Clients that got banned by the operator and are not allowed to (re)connect to the gateway
+    /// until the ban is lifted. It mirrors the bans persisted in the storage.
+    banned: Arc<DashSet<DestinationAddressBytes>>,
+}
 
 impl ActiveClientsStore {
     /// Creates new instance of `ActiveClientsStore` to store in-memory handles to all currently connected clients.
     pub(crate) fn new() -> Self {
-        ActiveClientsStore(Arc::new(DashMap::new()))
+        ActiveClientsStore {
+            clients: Arc::new(DashMap::new()),
+            banned: Arc::new(DashSet::new()),
+        }
     }
 
     /// Tries to obtain sending channel to specified client. Note that if stale entry existed, it is
@@ -22,7 +32,7 @@ impl ActiveClientsStore {
     ///
     /// * `client`: address of the client for which to obtain the handle.
     pub(crate) fn get(&self, client: DestinationAddressBytes) -> Option<MixMessageSender> {
-        let entry = self.0.get(&client)?;
+        let entry = self.clients.get(&client)?;
         let handle = entry.value();
 
         // if the entry is stale, remove it from the map
@@ -32,7 +42,8 @@ impl ActiveClientsStore {
         } else {
             // drop the reference to the map to prevent deadlocks
             drop(entry);
-            self.0.remove(&client);
+            self.clients.remove(&client);
+            self.update_metrics();
             None
         }
     }
@@ -43,7 +54,65 @@ impl ActiveClientsStore {
     ///
     /// * `client`: address of the client for which to remove the handle.
     pub(crate) fn disconnect(&self, client: DestinationAddressBytes) {
-        self.0.remove(&client);
+        self.clients.remove(&client);
+        self.update_metrics();
+    }
+
+    /// Forcefully disconnects the particular client. Removing its handle from the store closes
+    /// the channel its connection handler is listening on, which causes the connection to get closed.
+    ///
+    /// Returns whether the client was connected in the first place.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client to disconnect.
+    pub(crate) fn force_disconnect(&self, client: DestinationAddressBytes) -> bool {
+        let removed = self.clients.remove(&client).is_some();
+        self.update_metrics();
+        removed
+    }
+
+    /// Bans the particular client, preventing it from (re)connecting, and disconnects it if it's
+    /// currently connected.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client to ban.
+    pub(crate) fn ban(&self, client: DestinationAddressBytes) {
+        self.banned.insert(client);
+        self.force_disconnect(client);
+    }
+
+    /// Lifts the ban of the particular client. Returns whether the client was banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client to unban.
+    pub(crate) fn unban(&self, client: DestinationAddressBytes) -> bool {
+        self.banned.remove(&client).is_some()
+    }
+
+    /// Checks whether the particular client is currently banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client to check.
+    pub(crate) fn is_banned(&self, client: DestinationAddressBytes) -> bool {
+        self.banned.contains(&client)
+    }
+
+    /// Get addresses of all currently banned clients.
+    pub(crate) fn banned_clients(&self) -> Vec<DestinationAddressBytes> {
+        self.banned.iter().map(|client| *client).collect()
+    }
+
+    /// Get addresses of all currently connected clients.
+    pub(crate) fn connected_clients(&self) -> Vec<DestinationAddressBytes> {
+        self.clients
+            .iter()
+            .filter(|entry| !entry.value().is_closed())
+            .map(|entry| *entry.key())
+            .collect()
     }
 
     /// Insert new client handle into the store.
@@ -53,11 +122,40 @@ impl ActiveClientsStore {
     /// * `client`: address of the client for which to insert the handle.
     /// * `handle`: the sender channel for all mix packets to be pushed back onto the websocket
     pub(crate) fn insert(&self, client: DestinationAddressBytes, handle: MixMessageSender) {
-        self.0.insert(client, handle);
+        self.clients.insert(client, handle);
+        self.update_metrics();
     }
 
     /// Get number of active clients in store
     pub(crate) fn size(&self) -> usize {
-        self.0.len()
+        self.clients.len()
+    }
+
+    fn update_metrics(&self) {
+        metrics::ACTIVE_WEBSOCKET_CLIENTS.set(self.size() as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+
+    #[test]
+    fn banning_disconnects_client() {
+        let store = ActiveClientsStore::new();
+        let client = DestinationAddressBytes::from_bytes([42; 32]);
+        let (sender, mut receiver) = mpsc::unbounded();
+        store.insert(client, sender);
+        assert_eq!(store.connected_clients(), vec![client]);
+
+        store.ban(client);
+        assert!(store.is_banned(client));
+        assert!(store.connected_clients().is_empty());
+        // the connection handler gets notified through its channel being closed
+        assert!(matches!(receiver.try_next(), Ok(None)));
+
+        assert!(store.unban(client));
+        assert!(!store.is_banned(client));
     }
 }
@@ -3,6 +3,7 @@
 
 use crate::node::client_handling::websocket::connection_handler::{ClientDetails, FreshHandler};
 use crate::node::client_handling::websocket::message_receiver::MixMessageReceiver;
+use crate::node::statistics::metrics;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::Storage;
 use futures::StreamExt;
@@ -46,6 +47,9 @@ pub(crate) enum RequestHandlingError {
     #[error("Provided bandwidth credential did not verify correctly on {0}")]
     InvalidBandwidthCredential(String),
 
+    #[error("Provided bandwidth credential has expired at {expiry_date}")]
+    ExpiredCredential { expiry_date: u64 },
+
     #[error("This gateway is only accepting coconut credentials for bandwidth")]
     OnlyCoconutCredentials,
 
@@ -172,6 +176,7 @@ where
             .storage
             .consume_bandwidth(self.client.address, amount)
             .await?;
+        metrics::BANDWIDTH_CONSUMED.inc_by(amount as u64);
         Ok(())
     }
 
@@ -181,6 +186,9 @@ where
     ///
     /// * `mix_packet`: packet received from the client that should get forwarded into the network.
     fn forward_packet(&self, mix_packet: MixPacket) {
+        metrics::PACKETS_SENT
+            .with_label_values(&[&mix_packet.next_hop().to_string()])
+            .inc();
         if let Err(err) = self.inner.outbound_mix_sender.unbounded_send(mix_packet) {
             error!("We failed to forward requested mix packet - {err}. Presumably our mix forwarder has crashed. We cannot continue.");
             process::exit(1);
@@ -206,6 +214,9 @@ where
             iv,
         )?;
 
+        // there's no point in doing any expensive verification if the credential is no longer valid
+        self.inner.coconut_verifier.check_expiry(&credential)?;
+
         // Get the latest coconut signers and their VK
         let credential_api_clients = self
             .inner
@@ -227,7 +238,12 @@ where
         let aggregated_verification_key =
             nym_credentials::obtain_aggregate_verification_key(&credential_api_clients).await?;
 
-        if !credential.verify(&aggregated_verification_key) {
+        if !self
+            .inner
+            .coconut_verifier
+            .verify_credential(&credential, aggregated_verification_key)
+            .await?
+        {
             return Err(RequestHandlingError::InvalidBandwidthCredential(
                 String::from("credential failed to verify on gateway"),
             ));
@@ -283,7 +299,7 @@ where
         &self,
         mix_packet: MixPacket,
     ) -> Result<ServerResponse, RequestHandlingError> {
-        let consumed_bandwidth = mix_packet.sphinx_packet().len() as i64;
+        let consumed_bandwidth = mix_packet.packet().len() as i64;
 
         let available_bandwidth = self.get_available_bandwidth().await?;
 
@@ -399,7 +415,12 @@ where
                     }
                 },
                 mix_messages = self.mix_receiver.next() => {
-                    let mix_messages = mix_messages.expect("sender was unexpectedly closed! this shouldn't have ever happened!");
+                    // the handle is only ever removed from the store if the client got forcefully
+                    // disconnected (or banned) by the gateway operator
+                    let Some(mix_messages) = mix_messages else {
+                        info!("{} got disconnected by the gateway operator", self.client.address);
+                        break;
+                    };
                     if let Err(err) = self.inner.push_packets_to_client(self.client.shared_keys, mix_messages).await {
                         warn!("failed to send the unwrapped sphinx packets back to the client - {err}, assuming the connection is dead");
                         break;
@@ -3,7 +3,8 @@
 
 use super::authenticated::RequestHandlingError;
 use log::*;
-use nym_coconut_interface::Credential;
+use nym_coconut_interface::{Credential, VerificationKey};
+use nym_credentials::coconut::batch_verifier::BatchCredentialVerifier;
 use nym_validator_client::nyxd::traits::DkgQueryClient;
 use nym_validator_client::{
     nyxd::{
@@ -13,7 +14,7 @@ use nym_validator_client::{
     },
     Client, CoconutApiClient,
 };
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const ONE_HOUR_SEC: u64 = 3600;
 const MAX_FEEGRANT_UNYM: u128 = 10000;
@@ -21,6 +22,7 @@ const MAX_FEEGRANT_UNYM: u128 = 10000;
 pub(crate) struct CoconutVerifier {
     nyxd_client: Client<DirectSigningNyxdClient>,
     mix_denom_base: String,
+    credential_verifier: BatchCredentialVerifier,
 }
 
 impl CoconutVerifier {
@@ -35,9 +37,39 @@ impl CoconutVerifier {
         CoconutVerifier {
             nyxd_client,
             mix_denom_base,
+            credential_verifier: BatchCredentialVerifier::default(),
         }
     }
 
+    /// Verifies the credential, batching it together with the ones received
+    /// by other client handlers at the same time.
+    pub async fn verify_credential(
+        &self,
+        credential: &Credential,
+        verification_key: VerificationKey,
+    ) -> Result<bool, RequestHandlingError> {
+        Ok(self
+            .credential_verifier
+            .verify(credential.clone(), verification_key)
+            .await?)
+    }
+
+    /// Makes sure the credential hasn't expired, as otherwise it would be rejected
+    /// by the bandwidth contract when trying to release its funds.
+    pub fn check_expiry(&self, credential: &Credential) -> Result<(), RequestHandlingError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        if credential.expiry_date() <= now {
+            return Err(RequestHandlingError::ExpiredCredential {
+                expiry_date: credential.expiry_date(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub async fn all_current_coconut_api_clients(
         &self,
     ) -> Result<Vec<CoconutApiClient>, RequestHandlingError> {
@@ -71,6 +103,7 @@ impl CoconutVerifier {
                 ),
                 credential.blinded_serial_number(),
                 self.nyxd_client.nyxd.address().to_string(),
+                credential.expiry_date(),
                 None,
             )
             .await?;
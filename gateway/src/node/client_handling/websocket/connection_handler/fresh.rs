@@ -46,6 +46,9 @@ pub(crate) enum InitialAuthenticationError {
     #[error("There is already an open connection to this client")]
     DuplicateConnection,
 
+    #[error("This client has been banned by the gateway operator")]
+    BannedClient,
+
     #[error("Provided authentication IV is malformed - {0}")]
     MalformedIV(#[from] IVConversionError),
 
@@ -418,6 +421,10 @@ where
         let encrypted_address = EncryptedAddressBytes::try_from_base58_string(enc_address)?;
         let iv = IV::try_from_base58_string(iv)?;
 
+        if self.active_clients_store.is_banned(address) {
+            return Err(InitialAuthenticationError::BannedClient);
+        }
+
         if self.active_clients_store.get(address).is_some() {
             return Err(InitialAuthenticationError::DuplicateConnection);
         }
@@ -503,6 +510,10 @@ where
         let remote_identity = Self::extract_remote_identity_from_register_init(&init_data)?;
         let remote_address = remote_identity.derive_destination_address();
 
+        if self.active_clients_store.is_banned(remote_address) {
+            return Err(InitialAuthenticationError::BannedClient);
+        }
+
         if self.active_clients_store.get(remote_address).is_some() {
             return Err(InitialAuthenticationError::DuplicateConnection);
         }
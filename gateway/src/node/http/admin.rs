@@ -0,0 +1,80 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::warn;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+#[derive(Debug)]
+pub(crate) enum AdminAuthError {
+    Disabled,
+    InvalidToken,
+}
+
+/// Token required by the administrative routes, if any was configured.
+pub(crate) struct AdminApiToken(Option<String>);
+
+impl AdminApiToken {
+    pub(crate) fn new(token: Option<String>) -> Self {
+        AdminApiToken(token)
+    }
+}
+
+/// Request guard that only allows requests carrying the configured admin token
+/// in the `Authorization: Bearer <token>` header.
+pub(crate) struct Admin;
+
+// compares the tokens without short-circuiting on the first mismatching byte
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = AdminAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(expected) = request
+            .rocket()
+            .state::<AdminApiToken>()
+            .and_then(|token| token.0.as_deref())
+        else {
+            return Outcome::Failure((Status::Forbidden, AdminAuthError::Disabled));
+        };
+
+        let provided = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Bearer "));
+
+        match provided {
+            Some(token) if tokens_match(token, expected) => Outcome::Success(Admin),
+            _ => {
+                warn!(
+                    "Received an unauthorized request from {:?} for an admin route",
+                    request.client_ip()
+                );
+                Outcome::Failure((Status::Unauthorized, AdminAuthError::InvalidToken))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_comparison() {
+        assert!(tokens_match("foomp", "foomp"));
+        assert!(!tokens_match("foomp", "foomP"));
+        assert!(!tokens_match("foomp", "foom"));
+        assert!(!tokens_match("", "foomp"));
+    }
+}
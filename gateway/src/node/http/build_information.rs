@@ -0,0 +1,11 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_bin_common::build_information::{BinaryBuildInformation, BinaryBuildInformationOwned};
+use rocket::serde::json::Json;
+
+/// Returns build information of the running gateway binary.
+#[get("/build-information")]
+pub(crate) fn build_information() -> Json<BinaryBuildInformationOwned> {
+    Json(BinaryBuildInformation::new(env!("CARGO_PKG_VERSION")).to_owned())
+}
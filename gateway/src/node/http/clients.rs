@@ -0,0 +1,128 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::http::admin::Admin;
+use crate::node::storage::Storage;
+use log::info;
+use nym_sphinx::DestinationAddressBytes;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub(crate) struct ConnectedClient {
+    address: String,
+    bandwidth_remaining: i64,
+}
+
+fn parse_address(address: &str) -> Result<DestinationAddressBytes, (Status, String)> {
+    DestinationAddressBytes::try_from_base58_string(address).map_err(|err| {
+        (
+            Status::BadRequest,
+            format!("provided client address is malformed - {err}"),
+        )
+    })
+}
+
+/// Returns the list of currently connected clients alongside their remaining bandwidth.
+/// Requires the admin token.
+#[get("/clients")]
+pub(crate) async fn clients(
+    _admin: Admin,
+    active_clients: &State<ActiveClientsStore>,
+    storage: &State<Arc<dyn Storage>>,
+) -> Result<Json<Vec<ConnectedClient>>, (Status, String)> {
+    let mut clients = Vec::new();
+    for address in active_clients.connected_clients() {
+        let bandwidth_remaining = storage
+            .get_available_bandwidth(address)
+            .await
+            .map_err(|err| (Status::InternalServerError, err.to_string()))?
+            .unwrap_or_default();
+
+        clients.push(ConnectedClient {
+            address: address.as_base58_string(),
+            bandwidth_remaining,
+        })
+    }
+
+    Ok(Json(clients))
+}
+
+/// Forcefully disconnects the specified client. Requires the admin token.
+#[post("/clients/<address>/disconnect")]
+pub(crate) fn disconnect_client(
+    _admin: Admin,
+    address: &str,
+    active_clients: &State<ActiveClientsStore>,
+) -> Result<(), (Status, String)> {
+    let address = parse_address(address)?;
+    if active_clients.force_disconnect(address) {
+        info!("Disconnected {address} on the operator's request");
+        Ok(())
+    } else {
+        Err((
+            Status::NotFound,
+            format!("client {address} is not connected"),
+        ))
+    }
+}
+
+/// Returns the list of banned clients. Requires the admin token.
+#[get("/clients/banned")]
+pub(crate) fn banned_clients(
+    _admin: Admin,
+    active_clients: &State<ActiveClientsStore>,
+) -> Json<Vec<String>> {
+    Json(
+        active_clients
+            .banned_clients()
+            .into_iter()
+            .map(|address| address.as_base58_string())
+            .collect(),
+    )
+}
+
+/// Bans the specified client, disconnecting it and preventing it from reconnecting until
+/// the ban is lifted. Requires the admin token.
+#[post("/clients/<address>/ban")]
+pub(crate) async fn ban_client(
+    _admin: Admin,
+    address: &str,
+    active_clients: &State<ActiveClientsStore>,
+    storage: &State<Arc<dyn Storage>>,
+) -> Result<(), (Status, String)> {
+    let address = parse_address(address)?;
+    storage
+        .insert_banned_client(address)
+        .await
+        .map_err(|err| (Status::InternalServerError, err.to_string()))?;
+    active_clients.ban(address);
+    info!("Banned {address} on the operator's request");
+    Ok(())
+}
+
+/// Lifts the ban of the specified client. Requires the admin token.
+#[delete("/clients/<address>/ban")]
+pub(crate) async fn unban_client(
+    _admin: Admin,
+    address: &str,
+    active_clients: &State<ActiveClientsStore>,
+    storage: &State<Arc<dyn Storage>>,
+) -> Result<(), (Status, String)> {
+    let address = parse_address(address)?;
+    let was_stored = storage
+        .remove_banned_client(address)
+        .await
+        .map_err(|err| (Status::InternalServerError, err.to_string()))?;
+    let was_active = active_clients.unban(address);
+    if was_stored || was_active {
+        info!("Lifted the ban of {address} on the operator's request");
+        Ok(())
+    } else {
+        Err((Status::NotFound, format!("client {address} is not banned")))
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::http::admin::Admin;
+use crate::node::storage::Storage;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub(crate) struct InboxResponse {
+    client_address: String,
+    messages: i64,
+    bytes: i64,
+}
+
+/// Returns the number of messages, and their total size, stored for every offline client.
+/// Requires the admin token.
+#[get("/inboxes")]
+pub(crate) async fn inboxes(
+    _admin: Admin,
+    storage: &State<Arc<dyn Storage>>,
+) -> Result<Json<Vec<InboxResponse>>, (Status, String)> {
+    let inboxes = storage
+        .get_inboxes()
+        .await
+        .map_err(|err| (Status::InternalServerError, err.to_string()))?;
+
+    Ok(Json(
+        inboxes
+            .into_iter()
+            .map(|inbox| InboxResponse {
+                client_address: inbox.client_address_bs58,
+                messages: inbox.messages,
+                bytes: inbox.size,
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,17 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rocket::Request;
+
+pub(crate) mod admin;
+pub(crate) mod build_information;
+pub(crate) mod clients;
+pub(crate) mod description;
+pub(crate) mod hardware;
+pub(crate) mod inboxes;
+pub(crate) mod stats;
+
+#[catch(404)]
+pub(crate) fn not_found(req: &Request<'_>) -> String {
+    format!("I couldn't find '{}'. Try something else?", req.uri())
+}
@@ -0,0 +1,13 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::statistics::mixnet::{MixnetStatistics, MixnetStatisticsReport};
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Returns statistics of the packets received from the mix network, both since the startup
+/// of the gateway and during the most recent interval.
+#[get("/stats")]
+pub(crate) fn stats(statistics: &State<MixnetStatistics>) -> Json<MixnetStatisticsReport> {
+    Json(statistics.report())
+}
@@ -3,12 +3,18 @@
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket::message_receiver::MixMessageSender;
-use crate::node::mixnet_handling::receiver::packet_processing::PacketProcessor;
+use crate::node::mixnet_handling::receiver::packet_processing::{
+    GatewayProcessingError, PacketProcessor,
+};
+use crate::node::statistics::inbox::InboxStatistics;
+use crate::node::statistics::metrics;
+use crate::node::statistics::mixnet::MixnetStatistics;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::Storage;
 use futures::StreamExt;
 use log::*;
 use nym_mixnet_client::forwarder::MixForwardingSender;
+use nym_mixnode_common::packet_processor::error::MixProcessingError;
 use nym_mixnode_common::packet_processor::processor::ProcessedFinalHop;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::framing::codec::SphinxCodec;
@@ -31,6 +37,8 @@ pub(crate) struct ConnectionHandler<St: Storage> {
     active_clients_store: ActiveClientsStore,
     storage: St,
     ack_sender: MixForwardingSender,
+    inbox_statistics: InboxStatistics,
+    mixnet_statistics: MixnetStatistics,
 }
 
 impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
@@ -49,6 +57,8 @@ impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
             active_clients_store: self.active_clients_store.clone(),
             storage: self.storage.clone(),
             ack_sender: self.ack_sender.clone(),
+            inbox_statistics: self.inbox_statistics.clone(),
+            mixnet_statistics: self.mixnet_statistics.clone(),
         }
     }
 }
@@ -59,6 +69,8 @@ impl<St: Storage> ConnectionHandler<St> {
         storage: St,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        inbox_statistics: InboxStatistics,
+        mixnet_statistics: MixnetStatistics,
     ) -> Self {
         ConnectionHandler {
             packet_processor,
@@ -66,6 +78,8 @@ impl<St: Storage> ConnectionHandler<St> {
             storage,
             active_clients_store,
             ack_sender,
+            inbox_statistics,
+            mixnet_statistics,
         }
     }
 
@@ -116,7 +130,23 @@ impl<St: Storage> ConnectionHandler<St> {
             client_address
         );
 
-        self.storage.store_message(client_address, message).await
+        match self.storage.store_message(client_address, message).await {
+            Ok(0) => Ok(()),
+            Ok(dropped) => {
+                debug!("Dropped {dropped} oldest stored messages of {client_address} to stay within the inbox size limit");
+                self.inbox_statistics.record_dropped(dropped);
+                Ok(())
+            }
+            Err(err @ StorageError::InboxFull { .. }) => {
+                self.inbox_statistics.record_dropped(1);
+                self.mixnet_statistics.record_dropped();
+                metrics::PACKETS_DROPPED
+                    .with_label_values(&[metrics::DROP_REASON_INBOX_FULL])
+                    .inc();
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
     }
 
     fn forward_ack(&self, forward_ack: Option<MixPacket>, client_address: DestinationAddressBytes) {
@@ -127,6 +157,9 @@ impl<St: Storage> ConnectionHandler<St> {
                 forward_ack.next_hop()
             );
 
+            metrics::PACKETS_SENT
+                .with_label_values(&[&forward_ack.next_hop().to_string()])
+                .inc();
             self.ack_sender.unbounded_send(forward_ack).unwrap();
         }
     }
@@ -143,10 +176,17 @@ impl<St: Storage> ConnectionHandler<St> {
                 .store_processed_packet_payload(client_address, unsent_plaintext)
                 .await
             {
+                Err(err @ StorageError::InboxFull { .. }) => debug!("Dropped client data - {err}"),
                 Err(err) => error!("Failed to store client data - {err}"),
-                Ok(_) => trace!("Stored packet for {}", client_address),
+                Ok(_) => {
+                    self.mixnet_statistics.record_stored();
+                    trace!("Stored packet for {}", client_address)
+                }
             },
-            Ok(_) => trace!("Pushed received packet to {}", client_address),
+            Ok(_) => {
+                self.mixnet_statistics.record_pushed();
+                trace!("Pushed received packet to {}", client_address)
+            }
         }
 
         // if we managed to either push message directly to the [online] client or store it at
@@ -156,15 +196,31 @@ impl<St: Storage> ConnectionHandler<St> {
     }
 
     async fn handle_received_packet(&mut self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
+        metrics::PACKETS_RECEIVED.inc();
+        self.mixnet_statistics.record_received();
 
+        // note: replay detection happens inside the packet processor with a cache shared between
+        // all connections, since a replayed packet can arrive through any of them
         let processed_final_hop = match self.packet_processor.process_received(framed_sphinx_packet)
         {
+            Err(GatewayProcessingError::PacketProcessingError(
+                MixProcessingError::ReplayedPacket,
+            )) => {
+                metrics::PACKETS_DROPPED
+                    .with_label_values(&[metrics::DROP_REASON_REPLAYED])
+                    .inc();
+                self.mixnet_statistics.record_dropped();
+                debug!(
+                    "Rejected a replayed sphinx packet ({} replays rejected since startup)",
+                    self.packet_processor.rejected_replays()
+                );
+                return;
+            }
             Err(err) => {
+                metrics::PACKETS_DROPPED
+                    .with_label_values(&[metrics::DROP_REASON_MALFORMED])
+                    .inc();
+                self.mixnet_statistics.record_dropped();
                 debug!("We failed to process received sphinx packet - {err}");
                 return;
             }
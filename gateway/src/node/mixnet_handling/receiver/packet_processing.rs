@@ -30,6 +30,10 @@ impl PacketProcessor {
         }
     }
 
+    pub(crate) fn rejected_replays(&self) -> u64 {
+        self.inner_processor.replay_cache().rejected_replays()
+    }
+
     pub(crate) fn process_received(
         &self,
         received: FramedSphinxPacket,
@@ -1,21 +1,42 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use self::storage::PersistentStorage;
+use self::storage::{InMemStorage, PersistentStorage};
 use crate::config::persistence::pathfinder::GatewayPathfinder;
 use crate::config::Config;
 use crate::error::GatewayError;
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket;
 use crate::node::client_handling::websocket::connection_handler::coconut::CoconutVerifier;
+use crate::node::http::{
+    admin::AdminApiToken,
+    build_information::build_information,
+    clients::{ban_client, banned_clients, clients, disconnect_client, unban_client},
+    description::description,
+    hardware::hardware,
+    inboxes::inboxes,
+    not_found,
+    stats::stats,
+};
 use crate::node::mixnet_handling::receiver::connection_handler::ConnectionHandler;
+use crate::node::node_description::NodeDescription;
 use crate::node::statistics::collector::GatewayStatisticsCollector;
+use crate::node::statistics::inbox::InboxStatistics;
+use crate::node::statistics::mixnet::{
+    MixnetStatistics, MixnetStatisticsUpdater, DEFAULT_MIXNET_STATISTICS_INTERVAL,
+};
+use crate::node::storage::error::StorageError;
+use crate::node::storage::inbox_pruner::InboxPruner;
+use crate::node::storage::inbox_watcher::InboxWatcher;
 use crate::node::storage::Storage;
 use log::*;
 use nym_bin_common::output_format::OutputFormat;
+use nym_config::NymConfig;
 use nym_crypto::asymmetric::{encryption, identity};
+use nym_metrics::MetricsServer;
 use nym_mixnet_client::forwarder::{MixForwardingSender, PacketForwarder};
 use nym_network_defaults::NymNetworkDetails;
+use nym_sphinx::DestinationAddressBytes;
 use nym_statistics_common::collector::StatisticsSender;
 use nym_task::{TaskClient, TaskManager};
 use nym_validator_client::Client;
@@ -26,7 +47,9 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 pub(crate) mod client_handling;
+mod http;
 pub(crate) mod mixnet_handling;
+pub(crate) mod node_description;
 pub(crate) mod statistics;
 pub(crate) mod storage;
 
@@ -36,10 +59,53 @@ pub(crate) async fn create_gateway(config: Config) -> Gateway<PersistentStorage>
     Gateway::new(config, storage).await
 }
 
+/// Wire up and create Gateway instance that keeps all of its client state only in memory
+pub(crate) async fn create_ephemeral_gateway(config: Config) -> Gateway<InMemStorage> {
+    let storage = InMemStorage::new(
+        config.get_message_retrieval_limit(),
+        config.get_max_client_inbox_size(),
+        config.get_inbox_drop_policy(),
+    );
+    Gateway::new(config, storage).await
+}
+
+/// Wire up and create Gateway instance using the PostgreSQL storage
+#[cfg(feature = "postgres")]
+pub(crate) async fn create_postgres_gateway(
+    config: Config,
+    database_url: &str,
+) -> Gateway<storage::postgres::PostgresStorage> {
+    let storage = initialise_postgres_storage(&config, database_url).await;
+    Gateway::new(config, storage).await
+}
+
+#[cfg(feature = "postgres")]
+async fn initialise_postgres_storage(
+    config: &Config,
+    database_url: &str,
+) -> storage::postgres::PostgresStorage {
+    let retrieval_limit = config.get_message_retrieval_limit();
+    let max_inbox_size = config.get_max_client_inbox_size();
+    let drop_policy = config.get_inbox_drop_policy();
+    match storage::postgres::PostgresStorage::init(
+        database_url,
+        retrieval_limit,
+        max_inbox_size,
+        drop_policy,
+    )
+    .await
+    {
+        Err(err) => panic!("failed to initialise gateway storage - {err}"),
+        Ok(storage) => storage,
+    }
+}
+
 async fn initialise_storage(config: &Config) -> PersistentStorage {
     let path = config.get_persistent_store_path();
     let retrieval_limit = config.get_message_retrieval_limit();
-    match PersistentStorage::init(path, retrieval_limit).await {
+    let max_inbox_size = config.get_max_client_inbox_size();
+    let drop_policy = config.get_inbox_drop_policy();
+    match PersistentStorage::init(path, retrieval_limit, max_inbox_size, drop_policy).await {
         Err(err) => panic!("failed to initialise gateway storage - {err}"),
         Ok(storage) => storage,
     }
@@ -47,11 +113,16 @@ async fn initialise_storage(config: &Config) -> PersistentStorage {
 
 pub(crate) struct Gateway<St: Storage> {
     config: Config,
+    descriptor: NodeDescription,
     /// ed25519 keypair used to assert one's identity.
     identity_keypair: Arc<identity::KeyPair>,
     /// x25519 keypair used for Diffie-Hellman. Currently only used for sphinx key derivation.
     sphinx_keypair: Arc<encryption::KeyPair>,
     storage: St,
+    /// Counters of messages for offline clients that got removed before being retrieved.
+    inbox_statistics: InboxStatistics,
+    /// Counters of packets received from the mix network.
+    mixnet_statistics: MixnetStatistics,
 }
 
 impl<St> Gateway<St>
@@ -64,10 +135,13 @@ where
         // let storage = Self::initialise_storage(&config).await;
 
         Gateway {
+            descriptor: Self::load_node_description(&config),
             config,
             identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder)),
             sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder)),
             storage,
+            inbox_statistics: InboxStatistics::new(),
+            mixnet_statistics: MixnetStatistics::new(),
         }
     }
 
@@ -80,29 +154,46 @@ where
     ) -> Self {
         Gateway {
             config,
+            descriptor: NodeDescription::default(),
             identity_keypair: Arc::new(identity_keypair),
             sphinx_keypair: Arc::new(sphinx_keypair),
             storage,
+            inbox_statistics: InboxStatistics::new(),
+            mixnet_statistics: MixnetStatistics::new(),
         }
     }
 
+    fn load_node_description(config: &Config) -> NodeDescription {
+        NodeDescription::load_from_file(config.config_directory()).unwrap_or_else(|err| {
+            // not having any description at all is perfectly fine
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to load the node description: {err} - using the default one");
+            }
+            NodeDescription::default()
+        })
+    }
+
     fn load_identity_keys(pathfinder: &GatewayPathfinder) -> identity::KeyPair {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .expect("Failed to read stored identity key files");
+            ),
+            pathfinder.passphrase(),
+        )
+        .expect("Failed to read stored identity key files");
         identity_keypair
     }
 
     fn load_sphinx_keys(pathfinder: &GatewayPathfinder) -> encryption::KeyPair {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .expect("Failed to read stored sphinx key files");
+            ),
+            pathfinder.passphrase(),
+        )
+        .expect("Failed to read stored sphinx key files");
         sphinx_keypair
     }
 
@@ -142,6 +233,8 @@ where
             self.storage.clone(),
             ack_sender,
             active_clients_store,
+            self.inbox_statistics.clone(),
+            self.mixnet_statistics.clone(),
         );
 
         let listening_address = SocketAddr::new(
@@ -180,6 +273,162 @@ where
         );
     }
 
+    fn start_inbox_pruner(&self, shutdown: TaskClient) {
+        info!("Starting inbox pruner...");
+
+        InboxPruner::new(
+            self.storage.clone(),
+            self.config.get_message_max_age(),
+            self.config.get_inbox_pruning_interval(),
+            self.inbox_statistics.clone(),
+            shutdown,
+        )
+        .start();
+    }
+
+    async fn start_inbox_watcher(
+        &self,
+        active_clients_store: ActiveClientsStore,
+        shutdown: TaskClient,
+    ) {
+        // only relevant if the storage is shared with other gateway processes
+        let stored_messages = match self.storage.subscribe_to_stored_messages().await {
+            Ok(Some(stored_messages)) => stored_messages,
+            Ok(None) => return,
+            Err(err) => {
+                // clients will still receive their messages once they reconnect
+                error!("Failed to subscribe to the stored messages notifications - {err}");
+                return;
+            }
+        };
+
+        InboxWatcher::new(
+            self.storage.clone(),
+            active_clients_store,
+            stored_messages,
+            shutdown,
+        )
+        .start();
+    }
+
+    fn start_metrics_server(&self, shutdown: TaskClient) {
+        info!("Starting metrics server...");
+
+        let listening_address = SocketAddr::new(
+            self.config.get_metrics_address(),
+            self.config.get_metrics_port(),
+        );
+
+        MetricsServer::new(listening_address).start(shutdown);
+    }
+
+    fn start_mixnet_statistics_updater(&self, shutdown: TaskClient) {
+        info!("Starting mixnet statistics updater...");
+
+        MixnetStatisticsUpdater::new(
+            self.mixnet_statistics.clone(),
+            DEFAULT_MIXNET_STATISTICS_INTERVAL,
+            shutdown,
+        )
+        .start();
+    }
+
+    fn start_http_api(&self) {
+        let mut config = rocket::config::Config::release_default();
+
+        // bind to the same address as we are using for the mixnet and client traffic
+        config.address = self.config.get_listening_address();
+        config.port = self.config.get_http_api_port();
+
+        info!(
+            "Starting HTTP API on http://{}:{}",
+            config.address, config.port
+        );
+
+        let descriptor = self.descriptor.clone();
+        let mixnet_statistics = self.mixnet_statistics.clone();
+
+        tokio::spawn(async move {
+            rocket::build()
+                .configure(config)
+                .mount(
+                    "/",
+                    routes![description, build_information, hardware, stats],
+                )
+                .register("/", catchers![not_found])
+                .manage(descriptor)
+                .manage(mixnet_statistics)
+                .launch()
+                .await
+        });
+    }
+
+    // the admin routes are served separately from the public ones, so that they could be bound
+    // to a different (by default, loopback) address as the token is sent over plain http
+    fn start_admin_http_api(&self, active_clients_store: ActiveClientsStore) {
+        let Some(admin_api_token) = self.config.get_admin_api_token() else {
+            info!("No admin api token has been set - the admin HTTP API is disabled");
+            return;
+        };
+        let admin_api_token = AdminApiToken::new(Some(admin_api_token.to_owned()));
+
+        let mut config = rocket::config::Config::release_default();
+        config.address = self.config.get_admin_api_address();
+        config.port = self.config.get_admin_api_port();
+
+        if !config.address.is_loopback() {
+            warn!(
+                "The admin HTTP API is exposed on a non-loopback address ({}). It does not use TLS, so make sure it's not reachable over untrusted networks",
+                config.address
+            );
+        }
+        info!(
+            "Starting admin HTTP API on http://{}:{}",
+            config.address, config.port
+        );
+
+        let storage: Arc<dyn Storage> = Arc::new(self.storage.clone());
+
+        tokio::spawn(async move {
+            rocket::build()
+                .configure(config)
+                .mount(
+                    "/",
+                    routes![
+                        clients,
+                        inboxes,
+                        disconnect_client,
+                        banned_clients,
+                        ban_client,
+                        unban_client
+                    ],
+                )
+                .register("/", catchers![not_found])
+                .manage(admin_api_token)
+                .manage(active_clients_store)
+                .manage(storage)
+                .launch()
+                .await
+        });
+    }
+
+    // bans are persisted in the storage, but checked against the in-memory copy
+    // whenever a client attempts to connect
+    async fn load_banned_clients(
+        &self,
+        active_clients_store: &ActiveClientsStore,
+    ) -> Result<(), StorageError> {
+        for client_address_bs58 in self.storage.get_banned_clients().await? {
+            match DestinationAddressBytes::try_from_base58_string(&client_address_bs58) {
+                Ok(client) => active_clients_store.ban(client),
+                Err(err) => {
+                    warn!("stored address of the banned client {client_address_bs58} is malformed - {err}")
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn start_packet_forwarder(&self, shutdown: TaskClient) -> MixForwardingSender {
         info!("Starting mix packet forwarder...");
 
@@ -289,17 +538,34 @@ where
         let mix_forwarding_channel = self.start_packet_forwarder(shutdown.subscribe());
 
         let active_clients_store = ActiveClientsStore::new();
+        self.load_banned_clients(&active_clients_store).await?;
+
         self.start_mix_socket_listener(
             mix_forwarding_channel.clone(),
             active_clients_store.clone(),
             shutdown.subscribe(),
         );
 
+        self.start_inbox_pruner(shutdown.subscribe());
+
+        self.start_inbox_watcher(active_clients_store.clone(), shutdown.subscribe())
+            .await;
+
+        if self.config.get_metrics_enabled() {
+            self.start_metrics_server(shutdown.subscribe());
+        }
+
+        self.start_mixnet_statistics_updater(shutdown.subscribe());
+
+        self.start_http_api();
+        self.start_admin_http_api(active_clients_store.clone());
+
         if self.config.get_enabled_statistics() {
             let statistics_service_url = self.config.get_statistics_service_url();
             let stats_collector = GatewayStatisticsCollector::new(
                 self.identity_keypair.public_key().to_base58_string(),
                 active_clients_store.clone(),
+                self.inbox_statistics.clone(),
                 statistics_service_url,
             );
             let mut stats_sender = StatisticsSender::new(stats_collector);
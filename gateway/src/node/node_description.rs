@@ -0,0 +1,38 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::{fs, io};
+
+pub(crate) const DESCRIPTION_FILE: &str = "description.toml";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct NodeDescription {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) link: String,
+    pub(crate) location: String,
+}
+
+impl Default for NodeDescription {
+    fn default() -> Self {
+        NodeDescription {
+            name: "This node has not yet set a name".to_string(),
+            description: "This node has not yet set a description".to_string(),
+            link: "https://nymtech.net".to_string(),
+            location: "This node has not yet set a location".to_string(),
+        }
+    }
+}
+
+impl NodeDescription {
+    /// Loads the description operators can put in the `description.toml` file located in the
+    /// config directory of the gateway.
+    pub(crate) fn load_from_file(config_path: PathBuf) -> io::Result<NodeDescription> {
+        let description_file_path = config_path.join(DESCRIPTION_FILE);
+        let toml = fs::read_to_string(description_file_path)?;
+        toml::from_str(&toml).map_err(|toml_err| io::Error::new(io::ErrorKind::Other, toml_err))
+    }
+}
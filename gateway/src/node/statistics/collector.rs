@@ -12,10 +12,12 @@ use nym_statistics_common::{
 };
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::statistics::inbox::InboxStatistics;
 
 pub(crate) struct GatewayStatisticsCollector {
     gateway_id: String,
     active_clients_store: ActiveClientsStore,
+    inbox_statistics: InboxStatistics,
     statistics_service_url: Url,
 }
 
@@ -23,11 +25,13 @@ impl GatewayStatisticsCollector {
     pub fn new(
         gateway_id: String,
         active_clients_store: ActiveClientsStore,
+        inbox_statistics: InboxStatistics,
         statistics_service_url: Url,
     ) -> Self {
         GatewayStatisticsCollector {
             gateway_id,
             active_clients_store,
+            inbox_statistics,
             statistics_service_url,
         }
     }
@@ -41,10 +45,11 @@ impl StatisticsCollector for GatewayStatisticsCollector {
         timestamp: DateTime<Utc>,
     ) -> StatsMessage {
         let inbox_count = self.active_clients_store.size() as u32;
-        let stats_data = vec![StatsData::Gateway(StatsGatewayData::new(
-            self.gateway_id.clone(),
-            inbox_count,
-        ))];
+        let (expired_messages, dropped_messages) = self.inbox_statistics.take();
+        let stats_data = vec![StatsData::Gateway(
+            StatsGatewayData::new(self.gateway_id.clone(), inbox_count)
+                .with_inbox_drops(expired_messages, dropped_messages),
+        )];
         StatsMessage {
             stats_data,
             interval_seconds: interval.as_secs() as u32,
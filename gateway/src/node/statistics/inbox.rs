@@ -0,0 +1,44 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Counters of messages for offline clients that got removed from the storage
+/// before they could have been retrieved.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InboxStatistics {
+    inner: Arc<InboxStatisticsInner>,
+}
+
+#[derive(Debug, Default)]
+struct InboxStatisticsInner {
+    /// Messages removed for exceeding the maximum age.
+    expired: AtomicU64,
+
+    /// Messages dropped, either stored or incoming, because the inbox of the client was full.
+    dropped: AtomicU64,
+}
+
+impl InboxStatistics {
+    pub(crate) fn new() -> Self {
+        InboxStatistics::default()
+    }
+
+    pub(crate) fn record_expired(&self, count: u64) {
+        self.inner.expired.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped(&self, count: u64) {
+        self.inner.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Resets the counters, returning the number of expired and dropped messages
+    /// recorded since the previous reset.
+    pub(crate) fn take(&self) -> (u64, u64) {
+        (
+            self.inner.expired.swap(0, Ordering::Relaxed),
+            self.inner.dropped.swap(0, Ordering::Relaxed),
+        )
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use lazy_static::lazy_static;
+use nym_metrics::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
+
+// reasons for which packets coming from the mixnet might not reach their destination client
+pub(crate) const DROP_REASON_REPLAYED: &str = "replayed";
+pub(crate) const DROP_REASON_MALFORMED: &str = "malformed";
+pub(crate) const DROP_REASON_INBOX_FULL: &str = "inbox_full";
+
+lazy_static! {
+    pub(crate) static ref PACKETS_RECEIVED: IntCounter = register_int_counter(
+        "gateway_packets_received_total",
+        "Number of sphinx packets received by the gateway from the mixnet"
+    );
+    pub(crate) static ref PACKETS_SENT: IntCounterVec = register_int_counter_vec(
+        "gateway_packets_sent_total",
+        "Number of sphinx packets (including acks) forwarded by the gateway into the mixnet, per destination",
+        &["destination"]
+    );
+    pub(crate) static ref PACKETS_DROPPED: IntCounterVec = register_int_counter_vec(
+        "gateway_packets_dropped_total",
+        "Number of sphinx packets received from the mixnet that never reached their client, per reason",
+        &["reason"]
+    );
+    pub(crate) static ref ACTIVE_WEBSOCKET_CLIENTS: IntGauge = register_int_gauge(
+        "gateway_active_websocket_clients",
+        "Number of clients currently connected to the gateway"
+    );
+    pub(crate) static ref BANDWIDTH_CONSUMED: IntCounter = register_int_counter(
+        "gateway_bandwidth_consumed_bytes_total",
+        "Amount of client bandwidth consumed by forwarding packets into the mixnet"
+    );
+}
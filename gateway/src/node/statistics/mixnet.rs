@@ -0,0 +1,221 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::trace;
+use nym_task::TaskClient;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+pub(crate) const DEFAULT_MIXNET_STATISTICS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counters of the packets received from the mix network, both since the startup of the gateway
+/// and during the most recent (completed) interval.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MixnetStatistics {
+    inner: Arc<MixnetStatisticsInner>,
+}
+
+#[derive(Debug, Default)]
+struct MixnetStatisticsInner {
+    since_startup: PacketCounters,
+    current_interval: PacketCounters,
+    last_interval: RwLock<Option<IntervalStatistics>>,
+}
+
+#[derive(Debug, Default)]
+struct PacketCounters {
+    /// Packets received from the mix network.
+    received: AtomicU64,
+
+    /// Packets pushed directly to a connected client.
+    pushed: AtomicU64,
+
+    /// Packets stored in the inbox of an offline client.
+    stored: AtomicU64,
+
+    /// Packets that were dropped, either as being malformed, replayed or due to full inboxes.
+    dropped: AtomicU64,
+}
+
+impl PacketCounters {
+    fn snapshot(&self) -> PacketCountersSnapshot {
+        PacketCountersSnapshot {
+            received: self.received.load(Ordering::Relaxed),
+            pushed: self.pushed.load(Ordering::Relaxed),
+            stored: self.stored.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn take(&self) -> PacketCountersSnapshot {
+        PacketCountersSnapshot {
+            received: self.received.swap(0, Ordering::Relaxed),
+            pushed: self.pushed.swap(0, Ordering::Relaxed),
+            stored: self.stored.swap(0, Ordering::Relaxed),
+            dropped: self.dropped.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(crate) struct PacketCountersSnapshot {
+    pub(crate) received: u64,
+    pub(crate) pushed: u64,
+    pub(crate) stored: u64,
+    pub(crate) dropped: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct IntervalStatistics {
+    /// Unix timestamp of the start of the interval.
+    pub(crate) interval_start: u64,
+
+    /// Unix timestamp of the end of the interval.
+    pub(crate) interval_end: u64,
+
+    #[serde(flatten)]
+    pub(crate) packets: PacketCountersSnapshot,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct MixnetStatisticsReport {
+    pub(crate) since_startup: PacketCountersSnapshot,
+    pub(crate) last_interval: Option<IntervalStatistics>,
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+impl MixnetStatistics {
+    pub(crate) fn new() -> Self {
+        MixnetStatistics::default()
+    }
+
+    fn record<F>(&self, counter: F)
+    where
+        F: Fn(&PacketCounters) -> &AtomicU64,
+    {
+        counter(&self.inner.since_startup).fetch_add(1, Ordering::Relaxed);
+        counter(&self.inner.current_interval).fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self) {
+        self.record(|counters| &counters.received);
+    }
+
+    pub(crate) fn record_pushed(&self) {
+        self.record(|counters| &counters.pushed);
+    }
+
+    pub(crate) fn record_stored(&self) {
+        self.record(|counters| &counters.stored);
+    }
+
+    pub(crate) fn record_dropped(&self) {
+        self.record(|counters| &counters.dropped);
+    }
+
+    pub(crate) fn report(&self) -> MixnetStatisticsReport {
+        MixnetStatisticsReport {
+            since_startup: self.inner.since_startup.snapshot(),
+            last_interval: *self
+                .inner
+                .last_interval
+                .read()
+                .expect("mixnet statistics lock got poisoned"),
+        }
+    }
+
+    /// Finishes the current interval, making its counters available as the most recent statistics.
+    fn complete_interval(&self, interval_start: SystemTime, interval_end: SystemTime) {
+        let packets = self.inner.current_interval.take();
+        *self
+            .inner
+            .last_interval
+            .write()
+            .expect("mixnet statistics lock got poisoned") = Some(IntervalStatistics {
+            interval_start: unix_timestamp(interval_start),
+            interval_end: unix_timestamp(interval_end),
+            packets,
+        });
+    }
+}
+
+/// Periodically rolls over the interval statistics of the received mixnet packets.
+pub(crate) struct MixnetStatisticsUpdater {
+    statistics: MixnetStatistics,
+    interval: Duration,
+    shutdown: TaskClient,
+}
+
+impl MixnetStatisticsUpdater {
+    pub(crate) fn new(
+        statistics: MixnetStatistics,
+        interval: Duration,
+        shutdown: TaskClient,
+    ) -> Self {
+        MixnetStatisticsUpdater {
+            statistics,
+            interval,
+            shutdown,
+        }
+    }
+
+    async fn run(&mut self) {
+        let mut interval_start = SystemTime::now();
+        let mut interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.interval, self.interval);
+
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown.recv() => {
+                    trace!("MixnetStatisticsUpdater: Received shutdown");
+                }
+                _ = interval.tick() => {
+                    let interval_end = SystemTime::now();
+                    self.statistics.complete_interval(interval_start, interval_end);
+                    interval_start = interval_end;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completing_interval_resets_only_current_counters() {
+        let statistics = MixnetStatistics::new();
+        statistics.record_received();
+        statistics.record_received();
+        statistics.record_stored();
+
+        let start = SystemTime::now();
+        statistics.complete_interval(start, start + Duration::from_secs(60));
+        statistics.record_received();
+
+        let report = statistics.report();
+        assert_eq!(report.since_startup.received, 3);
+        assert_eq!(report.since_startup.stored, 1);
+
+        let last_interval = report.last_interval.unwrap();
+        assert_eq!(last_interval.packets.received, 2);
+        assert_eq!(last_interval.packets.stored, 1);
+        assert_eq!(
+            last_interval.interval_end - last_interval.interval_start,
+            60
+        );
+    }
+}
@@ -2,3 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod collector;
+pub(crate) mod inbox;
+pub(crate) mod metrics;
+pub(crate) mod mixnet;
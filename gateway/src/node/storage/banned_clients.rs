@@ -0,0 +1,62 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+#[derive(Clone)]
+pub(crate) struct BannedClientsManager {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl BannedClientsManager {
+    /// Creates new instance of the `BannedClientsManager` with the provided sqlite connection pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_pool`: database connection pool to use.
+    pub(crate) fn new(connection_pool: sqlx::SqlitePool) -> Self {
+        BannedClientsManager { connection_pool }
+    }
+
+    /// Marks the particular client as banned. Banning an already banned client has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client.
+    pub(crate) async fn insert_banned_client(
+        &self,
+        client_address_bs58: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO banned_clients(client_address_bs58) VALUES (?)",
+            client_address_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Lifts the ban of the particular client. Returns whether the client was banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client.
+    pub(crate) async fn remove_banned_client(
+        &self,
+        client_address_bs58: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let affected = sqlx::query!(
+            "DELETE FROM banned_clients WHERE client_address_bs58 = ?",
+            client_address_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?
+        .rows_affected();
+        Ok(affected > 0)
+    }
+
+    /// Gets base58-encoded addresses of all the banned clients.
+    pub(crate) async fn get_banned_clients(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT client_address_bs58 FROM banned_clients")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+}
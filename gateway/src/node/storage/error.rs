@@ -10,4 +10,7 @@ pub(crate) enum StorageError {
 
     #[error("Failed to perform database migration - {0}")]
     MigrationError(#[from] sqlx::migrate::MigrateError),
+
+    #[error("The inbox of client {client_address_bs58} is full")]
+    InboxFull { client_address_bs58: String },
 }
@@ -0,0 +1,498 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::InboxDropPolicy;
+use crate::node::storage::error::StorageError;
+use crate::node::storage::models::{PersistedSharedKeys, StoredInbox, StoredMessage};
+use crate::node::storage::Storage;
+use async_trait::async_trait;
+use nym_gateway_requests::registration::handshake::SharedKeys;
+use nym_sphinx::DestinationAddressBytes;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct InMemMessage {
+    content: Vec<u8>,
+    timestamp: i64,
+}
+
+#[derive(Default)]
+struct InMemStorageInner {
+    shared_keys: HashMap<String, String>,
+
+    /// Messages stored for each client, ordered by their ids.
+    inboxes: HashMap<String, BTreeMap<i64, InMemMessage>>,
+
+    /// Owners of the stored messages, required to be able to remove messages by their ids.
+    message_owners: HashMap<i64, String>,
+
+    /// Id that is going to be assigned to the next stored message.
+    next_message_id: i64,
+
+    available_bandwidth: HashMap<String, i64>,
+
+    banned_clients: HashSet<String>,
+}
+
+impl InMemStorageInner {
+    fn remove_message(&mut self, id: i64) {
+        let Some(owner) = self.message_owners.remove(&id) else {
+            return;
+        };
+        if let Some(inbox) = self.inboxes.get_mut(&owner) {
+            inbox.remove(&id);
+            if inbox.is_empty() {
+                self.inboxes.remove(&owner);
+            }
+        }
+    }
+
+    fn inbox_size(&self, client_address_bs58: &str) -> i64 {
+        self.inboxes
+            .get(client_address_bs58)
+            .map(|inbox| {
+                inbox
+                    .values()
+                    .map(|message| message.content.len() as i64)
+                    .sum()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Removes the oldest messages of the particular client until its inbox fits within the size limit.
+    fn remove_oldest_over_limit(&mut self, client_address_bs58: &str, max_inbox_size: i64) -> u64 {
+        let Some(inbox) = self.inboxes.get(client_address_bs58) else {
+            return 0;
+        };
+
+        // keep the newest messages for as long as their cumulative size stays within the limit
+        let mut retained = 0;
+        let to_remove = inbox
+            .iter()
+            .rev()
+            .filter_map(|(id, message)| {
+                retained += message.content.len() as i64;
+                (retained > max_inbox_size).then_some(*id)
+            })
+            .collect::<Vec<_>>();
+
+        let removed = to_remove.len() as u64;
+        for id in to_remove {
+            self.remove_message(id)
+        }
+        removed
+    }
+}
+
+/// In-memory implementation of `Storage`, with the same semantics as the sqlite `PersistentStorage`.
+/// It is intended for testing environments and for ephemeral gateways that keep nothing on the disk.
+// note that clone here is fine as upon cloning the same underlying data will be used
+#[derive(Clone)]
+pub(crate) struct InMemStorage {
+    inner: Arc<Mutex<InMemStorageInner>>,
+
+    /// Maximum number of messages that can be obtained from the storage per operation.
+    retrieval_limit: i64,
+
+    /// Maximum number of bytes that can be stored for a single client.
+    max_inbox_size: i64,
+
+    /// Specifies which messages get dropped once the inbox of a client is full.
+    drop_policy: InboxDropPolicy,
+}
+
+impl InMemStorage {
+    /// Creates new, empty, instance of `InMemStorage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `max_inbox_size`: maximum number of bytes that can be stored for a single client.
+    /// * `drop_policy`: specifies which messages get dropped once the inbox of a client is full.
+    pub(crate) fn new(
+        message_retrieval_limit: i64,
+        max_inbox_size: u64,
+        drop_policy: InboxDropPolicy,
+    ) -> Self {
+        InMemStorage {
+            inner: Arc::new(Mutex::new(InMemStorageInner {
+                // ids start from 1, same as sqlite's AUTOINCREMENT
+                next_message_id: 1,
+                ..Default::default()
+            })),
+            retrieval_limit: message_retrieval_limit,
+            max_inbox_size: max_inbox_size.try_into().unwrap_or(i64::MAX),
+            drop_policy,
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, InMemStorageInner> {
+        // the lock is never held across an await point nor while anything could panic
+        self.inner
+            .lock()
+            .expect("in-memory storage lock got poisoned")
+    }
+}
+
+#[async_trait]
+impl Storage for InMemStorage {
+    async fn insert_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+        shared_keys: SharedKeys,
+    ) -> Result<(), StorageError> {
+        self.inner().shared_keys.insert(
+            client_address.as_base58_string(),
+            shared_keys.to_base58_string(),
+        );
+        Ok(())
+    }
+
+    async fn get_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<Option<PersistedSharedKeys>, StorageError> {
+        let client_address_bs58 = client_address.as_base58_string();
+        Ok(self
+            .inner()
+            .shared_keys
+            .get(&client_address_bs58)
+            .map(|keys| PersistedSharedKeys {
+                client_address_bs58,
+                derived_aes128_ctr_blake3_hmac_keys_bs58: keys.clone(),
+            }))
+    }
+
+    async fn remove_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        self.inner()
+            .shared_keys
+            .remove(&client_address.as_base58_string());
+        Ok(())
+    }
+
+    async fn store_message(
+        &self,
+        client_address: DestinationAddressBytes,
+        message: Vec<u8>,
+    ) -> Result<u64, StorageError> {
+        let client_address_bs58 = client_address.as_base58_string();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+
+        let mut inner = self.inner();
+        if self.drop_policy == InboxDropPolicy::DropNewest
+            && inner.inbox_size(&client_address_bs58) + message.len() as i64 > self.max_inbox_size
+        {
+            return Err(StorageError::InboxFull {
+                client_address_bs58,
+            });
+        }
+
+        let id = inner.next_message_id;
+        inner.next_message_id += 1;
+        inner.message_owners.insert(id, client_address_bs58.clone());
+        inner
+            .inboxes
+            .entry(client_address_bs58.clone())
+            .or_default()
+            .insert(
+                id,
+                InMemMessage {
+                    content: message,
+                    timestamp,
+                },
+            );
+
+        if self.drop_policy == InboxDropPolicy::DropOldest {
+            Ok(inner.remove_oldest_over_limit(&client_address_bs58, self.max_inbox_size))
+        } else {
+            Ok(0)
+        }
+    }
+
+    async fn retrieve_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+        start_after: Option<i64>,
+    ) -> Result<(Vec<StoredMessage>, Option<i64>), StorageError> {
+        let client_address_bs58 = client_address.as_base58_string();
+        let inner = self.inner();
+        let Some(inbox) = inner.inboxes.get(&client_address_bs58) else {
+            return Ok((Vec::new(), None));
+        };
+
+        // get 1 additional message to check whether there will be more to grab next time
+        let limit = (self.retrieval_limit + 1) as usize;
+        let lower_bound = start_after.map(|id| id + 1).unwrap_or(i64::MIN);
+        let mut res = inbox
+            .range(lower_bound..)
+            .take(limit)
+            .map(|(id, message)| StoredMessage {
+                id: *id,
+                client_address_bs58: client_address_bs58.clone(),
+                content: message.content.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        if res.len() > self.retrieval_limit as usize {
+            res.truncate(self.retrieval_limit as usize);
+            // assuming retrieval_limit > 0, unwrap will not fail
+            let start_after = res.last().unwrap().id;
+            Ok((res, Some(start_after)))
+        } else {
+            Ok((res, None))
+        }
+    }
+
+    async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError> {
+        let mut inner = self.inner();
+        for id in ids {
+            inner.remove_message(id)
+        }
+        Ok(())
+    }
+
+    async fn remove_messages_older_than(&self, cutoff: i64) -> Result<u64, StorageError> {
+        let mut inner = self.inner();
+        let expired = inner
+            .inboxes
+            .values()
+            .flat_map(|inbox| inbox.iter())
+            .filter(|(_, message)| message.timestamp < cutoff)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        let removed = expired.len() as u64;
+        for id in expired {
+            inner.remove_message(id)
+        }
+        Ok(removed)
+    }
+
+    async fn get_inboxes(&self) -> Result<Vec<StoredInbox>, StorageError> {
+        Ok(self
+            .inner()
+            .inboxes
+            .iter()
+            .map(|(client_address_bs58, inbox)| StoredInbox {
+                client_address_bs58: client_address_bs58.clone(),
+                messages: inbox.len() as i64,
+                size: inbox
+                    .values()
+                    .map(|message| message.content.len() as i64)
+                    .sum(),
+            })
+            .collect())
+    }
+
+    async fn create_bandwidth_entry(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        self.inner()
+            .available_bandwidth
+            .entry(client_address.as_base58_string())
+            .or_insert(0);
+        Ok(())
+    }
+
+    async fn get_available_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<Option<i64>, StorageError> {
+        Ok(self
+            .inner()
+            .available_bandwidth
+            .get(&client_address.as_base58_string())
+            .copied())
+    }
+
+    async fn increase_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+        amount: i64,
+    ) -> Result<(), StorageError> {
+        // same as with the sqlite `UPDATE`, nothing happens if the entry doesn't exist
+        if let Some(available) = self
+            .inner()
+            .available_bandwidth
+            .get_mut(&client_address.as_base58_string())
+        {
+            *available += amount;
+        }
+        Ok(())
+    }
+
+    async fn consume_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+        amount: i64,
+    ) -> Result<(), StorageError> {
+        if let Some(available) = self
+            .inner()
+            .available_bandwidth
+            .get_mut(&client_address.as_base58_string())
+        {
+            *available -= amount;
+        }
+        Ok(())
+    }
+
+    async fn insert_banned_client(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        self.inner()
+            .banned_clients
+            .insert(client_address.as_base58_string());
+        Ok(())
+    }
+
+    async fn remove_banned_client(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<bool, StorageError> {
+        Ok(self
+            .inner()
+            .banned_clients
+            .remove(&client_address.as_base58_string()))
+    }
+
+    async fn get_banned_clients(&self) -> Result<Vec<String>, StorageError> {
+        Ok(self.inner().banned_clients.iter().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(byte: u8) -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([byte; 32])
+    }
+
+    #[tokio::test]
+    async fn messages_are_retrieved_in_batches_respecting_the_limit() {
+        let storage = InMemStorage::new(2, u64::MAX, InboxDropPolicy::DropOldest);
+        for i in 0..5u8 {
+            storage.store_message(client(1), vec![i]).await.unwrap();
+        }
+        storage.store_message(client(2), vec![42]).await.unwrap();
+
+        let (first, start_after) = storage.retrieve_messages(client(1), None).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].content, vec![0]);
+        assert_eq!(start_after, Some(first[1].id));
+
+        let (second, start_after) = storage
+            .retrieve_messages(client(1), start_after)
+            .await
+            .unwrap();
+        assert_eq!(second.len(), 2);
+        assert_eq!(second[0].content, vec![2]);
+
+        let (last, start_after) = storage
+            .retrieve_messages(client(1), start_after)
+            .await
+            .unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].content, vec![4]);
+        assert!(start_after.is_none());
+
+        storage
+            .remove_messages(first.into_iter().chain(second).map(|m| m.id).collect())
+            .await
+            .unwrap();
+        let inboxes = storage.get_inboxes().await.unwrap();
+        assert_eq!(inboxes.len(), 2);
+        assert_eq!(inboxes.iter().map(|inbox| inbox.messages).sum::<i64>(), 2);
+    }
+
+    #[tokio::test]
+    async fn full_inboxes_respect_drop_policy() {
+        let drop_oldest = InMemStorage::new(100, 10, InboxDropPolicy::DropOldest);
+        assert_eq!(
+            drop_oldest
+                .store_message(client(1), vec![1; 6])
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            drop_oldest
+                .store_message(client(1), vec![2; 4])
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            drop_oldest
+                .store_message(client(1), vec![3; 4])
+                .await
+                .unwrap(),
+            1
+        );
+        let (messages, _) = drop_oldest
+            .retrieve_messages(client(1), None)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, vec![2; 4]);
+
+        let drop_newest = InMemStorage::new(100, 10, InboxDropPolicy::DropNewest);
+        drop_newest
+            .store_message(client(1), vec![1; 6])
+            .await
+            .unwrap();
+        drop_newest
+            .store_message(client(1), vec![2; 4])
+            .await
+            .unwrap();
+        assert!(matches!(
+            drop_newest.store_message(client(1), vec![3; 4]).await,
+            Err(StorageError::InboxFull { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn bandwidth_accounting() {
+        let storage = InMemStorage::new(100, u64::MAX, InboxDropPolicy::DropOldest);
+        // updating a non-existent entry does nothing
+        storage.increase_bandwidth(client(1), 100).await.unwrap();
+        assert!(storage
+            .get_available_bandwidth(client(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        storage.create_bandwidth_entry(client(1)).await.unwrap();
+        storage.increase_bandwidth(client(1), 100).await.unwrap();
+        storage.consume_bandwidth(client(1), 30).await.unwrap();
+        assert_eq!(
+            storage.get_available_bandwidth(client(1)).await.unwrap(),
+            Some(70)
+        );
+    }
+
+    #[tokio::test]
+    async fn banned_clients() {
+        let storage = InMemStorage::new(100, u64::MAX, InboxDropPolicy::DropOldest);
+        storage.insert_banned_client(client(1)).await.unwrap();
+        // banning the client again has no effect
+        storage.insert_banned_client(client(1)).await.unwrap();
+        assert_eq!(
+            storage.get_banned_clients().await.unwrap(),
+            vec![client(1).as_base58_string()]
+        );
+
+        assert!(storage.remove_banned_client(client(1)).await.unwrap());
+        assert!(!storage.remove_banned_client(client(1)).await.unwrap());
+        assert!(storage.get_banned_clients().await.unwrap().is_empty());
+    }
+}
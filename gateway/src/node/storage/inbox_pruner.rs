@@ -0,0 +1,79 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::statistics::inbox::InboxStatistics;
+use crate::node::storage::Storage;
+use log::*;
+use nym_task::TaskClient;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+/// Periodically removes messages for offline clients that have been stored for longer
+/// than the configured maximum age.
+pub(crate) struct InboxPruner<St> {
+    storage: St,
+    max_message_age: Duration,
+    pruning_interval: Duration,
+    inbox_statistics: InboxStatistics,
+    shutdown: TaskClient,
+}
+
+impl<St> InboxPruner<St>
+where
+    St: Storage + 'static,
+{
+    pub(crate) fn new(
+        storage: St,
+        max_message_age: Duration,
+        pruning_interval: Duration,
+        inbox_statistics: InboxStatistics,
+        shutdown: TaskClient,
+    ) -> Self {
+        InboxPruner {
+            storage,
+            max_message_age,
+            pruning_interval,
+            inbox_statistics,
+            shutdown,
+        }
+    }
+
+    async fn remove_expired_messages(&self) {
+        let cutoff = SystemTime::now()
+            .checked_sub(self.max_message_age)
+            .and_then(|cutoff| cutoff.duration_since(UNIX_EPOCH).ok())
+            .map(|cutoff| cutoff.as_secs() as i64)
+            .unwrap_or_default();
+
+        match self.storage.remove_messages_older_than(cutoff).await {
+            Err(err) => error!("Failed to remove expired client messages - {err}"),
+            Ok(0) => trace!("There were no expired client messages to remove"),
+            Ok(removed) => {
+                debug!("Removed {removed} expired client messages");
+                self.inbox_statistics.record_expired(removed);
+            }
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        let mut interval = tokio::time::interval(self.pruning_interval);
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown.recv() => {
+                    log::trace!("InboxPruner: Received shutdown");
+                }
+                _ = interval.tick() => self.remove_expired_messages().await,
+            }
+        }
+    }
+
+    pub(crate) fn start(mut self) -> JoinHandle<()> {
+        info!(
+            "Starting inbox pruner removing messages older than {:?}",
+            self.max_message_age
+        );
+
+        tokio::spawn(async move { self.run().await })
+    }
+}
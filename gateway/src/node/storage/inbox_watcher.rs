@@ -0,0 +1,102 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::storage::error::StorageError;
+use crate::node::storage::{Storage, StoredMessagesReceiver};
+use futures::StreamExt;
+use log::*;
+use nym_sphinx::DestinationAddressBytes;
+use nym_task::TaskClient;
+use tokio::task::JoinHandle;
+
+/// Pushes the messages stored by other gateway processes sharing the same database
+/// to the clients that are connected to this one.
+pub(crate) struct InboxWatcher<St> {
+    storage: St,
+    active_clients_store: ActiveClientsStore,
+    stored_messages: StoredMessagesReceiver,
+    shutdown: TaskClient,
+}
+
+impl<St> InboxWatcher<St>
+where
+    St: Storage + 'static,
+{
+    pub(crate) fn new(
+        storage: St,
+        active_clients_store: ActiveClientsStore,
+        stored_messages: StoredMessagesReceiver,
+        shutdown: TaskClient,
+    ) -> Self {
+        InboxWatcher {
+            storage,
+            active_clients_store,
+            stored_messages,
+            shutdown,
+        }
+    }
+
+    async fn push_stored_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        let mut start_next_after = None;
+        loop {
+            // the client might have disconnected in the meantime
+            let Some(client_sender) = self.active_clients_store.get(client_address) else {
+                return Ok(());
+            };
+
+            let (messages, new_start_next_after) = self
+                .storage
+                .retrieve_messages(client_address, start_next_after)
+                .await?;
+            if messages.is_empty() {
+                return Ok(());
+            }
+
+            let (messages, ids) = messages
+                .into_iter()
+                .map(|msg| (msg.content, msg.id))
+                .unzip();
+
+            // if the handler is gone, the messages stay in the store until the client reconnects
+            if client_sender.unbounded_send(messages).is_err() {
+                return Ok(());
+            }
+            self.storage.remove_messages(ids).await?;
+
+            match new_start_next_after {
+                None => return Ok(()),
+                Some(start_after) => start_next_after = Some(start_after),
+            }
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown.recv() => {
+                    log::trace!("InboxWatcher: Received shutdown");
+                }
+                client_address = self.stored_messages.next() => {
+                    let Some(client_address) = client_address else {
+                        warn!("The stored messages notifications have stopped");
+                        break;
+                    };
+                    if let Err(err) = self.push_stored_messages(client_address).await {
+                        error!("Failed to push stored messages to {client_address} - {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn start(mut self) -> JoinHandle<()> {
+        info!("Starting inbox watcher");
+
+        tokio::spawn(async move { self.run().await })
+    }
+}
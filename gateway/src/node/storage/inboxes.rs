@@ -1,7 +1,9 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node::storage::models::StoredMessage;
+use crate::config::InboxDropPolicy;
+use crate::node::storage::error::StorageError;
+use crate::node::storage::models::{StoredInbox, StoredMessage};
 
 #[derive(Clone)]
 pub(crate) struct InboxManager {
@@ -10,6 +12,12 @@ pub(crate) struct InboxManager {
     /// It is used to prevent out of memory errors in the case of client receiving a lot of data while
     /// offline and then loading it all at once when he comes back online.
     retrieval_limit: i64,
+
+    /// Maximum number of bytes that can be stored for a single client.
+    max_inbox_size: i64,
+
+    /// Specifies which messages get dropped once the inbox of a client is full.
+    drop_policy: InboxDropPolicy,
 }
 
 impl InboxManager {
@@ -18,32 +26,145 @@ impl InboxManager {
     /// # Arguments
     ///
     /// * `connection_pool`: database connection pool to use.
-    pub(crate) fn new(connection_pool: sqlx::SqlitePool, retrieval_limit: i64) -> Self {
+    /// * `retrieval_limit`: maximum number of messages that can be obtained per operation.
+    /// * `max_inbox_size`: maximum number of bytes that can be stored for a single client.
+    /// * `drop_policy`: specifies which messages get dropped once the inbox of a client is full.
+    pub(crate) fn new(
+        connection_pool: sqlx::SqlitePool,
+        retrieval_limit: i64,
+        max_inbox_size: u64,
+        drop_policy: InboxDropPolicy,
+    ) -> Self {
         InboxManager {
             connection_pool,
             retrieval_limit,
+            max_inbox_size: max_inbox_size.try_into().unwrap_or(i64::MAX),
+            drop_policy,
         }
     }
 
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// If the inbox of the client is full, messages are dropped according to the drop policy.
     ///
     /// # Arguments
     ///
     /// * `client_address_bs58`: base58-encoded address of the client
     /// * `content`: raw content of the message to store.
+    /// * `timestamp`: unix timestamp of when the message got received.
+    ///
+    /// returns the number of previously stored messages that got dropped to make space for the new one.
     pub(crate) async fn insert_message(
         &self,
         client_address_bs58: &str,
         content: Vec<u8>,
-    ) -> Result<(), sqlx::Error> {
+        timestamp: i64,
+    ) -> Result<u64, StorageError> {
+        if self.drop_policy == InboxDropPolicy::DropNewest {
+            // check the quota as part of the insertion itself so that concurrent inserts for the
+            // same client couldn't both pass the check and exceed it
+            let content_len = content.len() as i64;
+            let inserted = sqlx::query!(
+                r#"
+                    INSERT INTO message_store(client_address_bs58, content, timestamp)
+                    SELECT ?, ?, ?
+                    WHERE (
+                        SELECT COALESCE(SUM(LENGTH(content)), 0) FROM message_store
+                        WHERE client_address_bs58 = ?
+                    ) + ? <= ?
+                "#,
+                client_address_bs58,
+                content,
+                timestamp,
+                client_address_bs58,
+                content_len,
+                self.max_inbox_size,
+            )
+            .execute(&self.connection_pool)
+            .await?
+            .rows_affected();
+
+            if inserted == 0 {
+                return Err(StorageError::InboxFull {
+                    client_address_bs58: client_address_bs58.to_string(),
+                });
+            }
+            return Ok(0);
+        }
+
         sqlx::query!(
-            "INSERT INTO message_store(client_address_bs58, content) VALUES (?, ?)",
+            "INSERT INTO message_store(client_address_bs58, content, timestamp) VALUES (?, ?, ?)",
             client_address_bs58,
             content,
+            timestamp,
         )
         .execute(&self.connection_pool)
         .await?;
-        Ok(())
+
+        if self.drop_policy == InboxDropPolicy::DropOldest {
+            Ok(self.remove_oldest_over_limit(client_address_bs58).await?)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Gets the number of messages and bytes stored for each client with a non-empty inbox.
+    pub(crate) async fn get_inboxes(&self) -> Result<Vec<StoredInbox>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredInbox,
+            r#"
+                SELECT client_address_bs58, COUNT(*) as "messages!: i64", SUM(LENGTH(content)) as "size!: i64"
+                FROM message_store
+                GROUP BY client_address_bs58
+            "#
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Removes the oldest messages of the particular client until its inbox fits within the size limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client
+    ///
+    /// returns the number of removed messages.
+    async fn remove_oldest_over_limit(
+        &self,
+        client_address_bs58: &str,
+    ) -> Result<u64, sqlx::Error> {
+        // keep the newest messages for as long as their cumulative size stays within the limit
+        let removed = sqlx::query!(
+            r#"
+                DELETE FROM message_store WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, SUM(LENGTH(content)) OVER (ORDER BY id DESC) AS retained
+                        FROM message_store
+                        WHERE client_address_bs58 = ?
+                    ) WHERE retained > ?
+                )
+            "#,
+            client_address_bs58,
+            self.max_inbox_size
+        )
+        .execute(&self.connection_pool)
+        .await?
+        .rows_affected();
+        Ok(removed)
+    }
+
+    /// Removes all messages, of all clients, that got stored before the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff`: unix timestamp before which the messages are considered expired.
+    ///
+    /// returns the number of removed messages.
+    pub(crate) async fn remove_messages_older_than(&self, cutoff: i64) -> Result<u64, sqlx::Error> {
+        let removed = sqlx::query!("DELETE FROM message_store WHERE timestamp < ?", cutoff)
+            .execute(&self.connection_pool)
+            .await?
+            .rows_affected();
+        Ok(removed)
     }
 
     /// Retrieves messages stored for the particular client specified by the provided address.
@@ -70,7 +191,7 @@ impl InboxManager {
             sqlx::query_as!(
                 StoredMessage,
                 r#"
-                    SELECT * FROM message_store 
+                    SELECT id, client_address_bs58, content FROM message_store
                     WHERE client_address_bs58 = ? AND id > ?
                     ORDER BY id ASC
                     LIMIT ?;
@@ -85,7 +206,7 @@ impl InboxManager {
             sqlx::query_as!(
                 StoredMessage,
                 r#"
-                    SELECT * FROM message_store 
+                    SELECT id, client_address_bs58, content FROM message_store
                     WHERE client_address_bs58 = ?
                     ORDER BY id ASC
                     LIMIT ?;
@@ -120,3 +241,130 @@ impl InboxManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const CLIENT: &str = "client";
+
+    async fn inbox_manager(max_inbox_size: u64, drop_policy: InboxDropPolicy) -> InboxManager {
+        // every connection to an in-memory database creates a separate database
+        let connection_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations")
+            .run(&connection_pool)
+            .await
+            .unwrap();
+
+        InboxManager::new(connection_pool, 100, max_inbox_size, drop_policy)
+    }
+
+    async fn stored_contents(manager: &InboxManager) -> Vec<Vec<u8>> {
+        let (messages, _) = manager.get_messages(CLIENT, None).await.unwrap();
+        messages
+            .into_iter()
+            .map(|message| message.content)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_removes_old_messages_at_the_quota_boundary() {
+        let manager = inbox_manager(10, InboxDropPolicy::DropOldest).await;
+
+        assert_eq!(
+            manager.insert_message(CLIENT, vec![1; 6], 0).await.unwrap(),
+            0
+        );
+        // exactly at the limit
+        assert_eq!(
+            manager.insert_message(CLIENT, vec![2; 4], 0).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            stored_contents(&manager).await,
+            vec![vec![1; 6], vec![2; 4]]
+        );
+
+        // one byte over the limit
+        assert_eq!(
+            manager.insert_message(CLIENT, vec![3; 1], 0).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            stored_contents(&manager).await,
+            vec![vec![2; 4], vec![3; 1]]
+        );
+
+        // other clients are not affected
+        manager
+            .insert_message("other", vec![4; 10], 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            stored_contents(&manager).await,
+            vec![vec![2; 4], vec![3; 1]]
+        );
+    }
+
+    #[tokio::test]
+    async fn drop_newest_rejects_messages_at_the_quota_boundary() {
+        let manager = inbox_manager(10, InboxDropPolicy::DropNewest).await;
+
+        assert_eq!(
+            manager.insert_message(CLIENT, vec![1; 6], 0).await.unwrap(),
+            0
+        );
+        // exactly at the limit
+        assert_eq!(
+            manager.insert_message(CLIENT, vec![2; 4], 0).await.unwrap(),
+            0
+        );
+
+        // one byte over the limit
+        assert!(matches!(
+            manager.insert_message(CLIENT, vec![3; 1], 0).await,
+            Err(StorageError::InboxFull { .. })
+        ));
+        assert_eq!(
+            stored_contents(&manager).await,
+            vec![vec![1; 6], vec![2; 4]]
+        );
+    }
+
+    #[tokio::test]
+    async fn drop_newest_never_exceeds_the_quota() {
+        let manager = inbox_manager(10, InboxDropPolicy::DropNewest).await;
+
+        let inserts = (0..10).map(|i| manager.insert_message(CLIENT, vec![i; 4], 0));
+        let results = futures::future::join_all(inserts).await;
+
+        assert_eq!(results.iter().filter(|res| res.is_ok()).count(), 2);
+        let stored: usize = stored_contents(&manager).await.iter().map(Vec::len).sum();
+        assert!(stored <= 10);
+    }
+
+    #[tokio::test]
+    async fn removing_expired_messages() {
+        let manager = inbox_manager(u64::MAX, InboxDropPolicy::DropOldest).await;
+
+        manager.insert_message(CLIENT, vec![1], 100).await.unwrap();
+        manager.insert_message(CLIENT, vec![2], 200).await.unwrap();
+        manager.insert_message("other", vec![3], 150).await.unwrap();
+
+        // the cutoff itself is not considered expired
+        assert_eq!(manager.remove_messages_older_than(100).await.unwrap(), 0);
+        assert_eq!(manager.remove_messages_older_than(200).await.unwrap(), 2);
+        assert_eq!(stored_contents(&manager).await, vec![vec![2]]);
+        assert!(manager
+            .get_messages("other", None)
+            .await
+            .unwrap()
+            .0
+            .is_empty());
+    }
+}
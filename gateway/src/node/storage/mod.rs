@@ -1,24 +1,39 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::config::InboxDropPolicy;
 use crate::node::storage::bandwidth::BandwidthManager;
+use crate::node::storage::banned_clients::BannedClientsManager;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::inboxes::InboxManager;
-use crate::node::storage::models::{PersistedSharedKeys, StoredMessage};
+use crate::node::storage::models::{PersistedSharedKeys, StoredInbox, StoredMessage};
 use crate::node::storage::shared_keys::SharedKeysManager;
 use async_trait::async_trait;
+use futures::channel::mpsc;
 use log::{debug, error};
 use nym_gateway_requests::registration::handshake::SharedKeys;
 use nym_sphinx::DestinationAddressBytes;
 use sqlx::ConnectOptions;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 mod bandwidth;
+mod banned_clients;
 pub(crate) mod error;
+mod in_memory;
+pub(crate) mod inbox_pruner;
+pub(crate) mod inbox_watcher;
 mod inboxes;
 mod models;
+#[cfg(feature = "postgres")]
+pub(crate) mod postgres;
 mod shared_keys;
 
+pub(crate) use in_memory::InMemStorage;
+
+/// Receives addresses of the clients that got new messages stored for them.
+pub(crate) type StoredMessagesReceiver = mpsc::UnboundedReceiver<DestinationAddressBytes>;
+
 #[async_trait]
 pub(crate) trait Storage: Send + Sync {
     /// Inserts provided derived shared keys into the database.
@@ -58,16 +73,19 @@ pub(crate) trait Storage: Send + Sync {
     ) -> Result<(), StorageError>;
 
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// If the inbox of the client is full, messages are dropped according to the configured policy.
     ///
     /// # Arguments
     ///
     /// * `client_address`: address of the client
     /// * `message`: raw message to store.
+    ///
+    /// returns the number of previously stored messages that got dropped to make space for the new one.
     async fn store_message(
         &self,
         client_address: DestinationAddressBytes,
         message: Vec<u8>,
-    ) -> Result<(), StorageError>;
+    ) -> Result<u64, StorageError>;
 
     /// Retrieves messages stored for the particular client specified by the provided address.
     ///
@@ -91,6 +109,28 @@ pub(crate) trait Storage: Send + Sync {
     /// * `ids`: ids of the messages to remove
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError>;
 
+    /// Removes all messages that got stored before the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff`: unix timestamp before which the messages are considered expired.
+    ///
+    /// returns the number of removed messages.
+    async fn remove_messages_older_than(&self, cutoff: i64) -> Result<u64, StorageError>;
+
+    /// Gets the number of messages and bytes stored for each client with a non-empty inbox.
+    async fn get_inboxes(&self) -> Result<Vec<StoredInbox>, StorageError>;
+
+    /// Subscribes to the notifications about messages getting stored for any client,
+    /// including the ones stored by other gateway processes sharing the same database.
+    ///
+    /// returns `None` if the storage is not shared with any other process.
+    async fn subscribe_to_stored_messages(
+        &self,
+    ) -> Result<Option<StoredMessagesReceiver>, StorageError> {
+        Ok(None)
+    }
+
     /// Creates a new bandwidth entry for the particular client.
     ///
     /// # Arguments
@@ -134,6 +174,29 @@ pub(crate) trait Storage: Send + Sync {
         client_address: DestinationAddressBytes,
         amount: i64,
     ) -> Result<(), StorageError>;
+
+    /// Marks the particular client as banned by the gateway operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address`: address of the client
+    async fn insert_banned_client(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError>;
+
+    /// Lifts the ban of the particular client. Returns whether the client was banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address`: address of the client
+    async fn remove_banned_client(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<bool, StorageError>;
+
+    /// Gets base58-encoded addresses of all the clients banned by the gateway operator.
+    async fn get_banned_clients(&self) -> Result<Vec<String>, StorageError>;
 }
 
 // note that clone here is fine as upon cloning the same underlying pool will be used
@@ -142,6 +205,7 @@ pub(crate) struct PersistentStorage {
     shared_key_manager: SharedKeysManager,
     inbox_manager: InboxManager,
     bandwidth_manager: BandwidthManager,
+    banned_clients_manager: BannedClientsManager,
 }
 
 impl PersistentStorage {
@@ -151,9 +215,13 @@ impl PersistentStorage {
     ///
     /// * `database_path`: path to the database.
     /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `max_inbox_size`: maximum number of bytes that can be stored for a single client.
+    /// * `drop_policy`: specifies which messages get dropped once the inbox of a client is full.
     pub async fn init<P: AsRef<Path> + Send>(
         database_path: P,
         message_retrieval_limit: i64,
+        max_inbox_size: u64,
+        drop_policy: InboxDropPolicy,
     ) -> Result<Self, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
@@ -186,8 +254,14 @@ impl PersistentStorage {
         // the cloning here are cheap as connection pool is stored behind an Arc
         Ok(PersistentStorage {
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
-            inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
-            bandwidth_manager: BandwidthManager::new(connection_pool),
+            inbox_manager: InboxManager::new(
+                connection_pool.clone(),
+                message_retrieval_limit,
+                max_inbox_size,
+                drop_policy,
+            ),
+            bandwidth_manager: BandwidthManager::new(connection_pool.clone()),
+            banned_clients_manager: BannedClientsManager::new(connection_pool),
         })
     }
 }
@@ -235,11 +309,14 @@ impl Storage for PersistentStorage {
         &self,
         client_address: DestinationAddressBytes,
         message: Vec<u8>,
-    ) -> Result<(), StorageError> {
+    ) -> Result<u64, StorageError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
         self.inbox_manager
-            .insert_message(&client_address.as_base58_string(), message)
-            .await?;
-        Ok(())
+            .insert_message(&client_address.as_base58_string(), message, timestamp)
+            .await
     }
 
     async fn retrieve_messages(
@@ -261,6 +338,19 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 
+    async fn remove_messages_older_than(&self, cutoff: i64) -> Result<u64, StorageError> {
+        let removed = self
+            .inbox_manager
+            .remove_messages_older_than(cutoff)
+            .await?;
+        Ok(removed)
+    }
+
+    async fn get_inboxes(&self) -> Result<Vec<StoredInbox>, StorageError> {
+        let inboxes = self.inbox_manager.get_inboxes().await?;
+        Ok(inboxes)
+    }
+
     async fn create_bandwidth_entry(
         &self,
         client_address: DestinationAddressBytes,
@@ -304,93 +394,30 @@ impl Storage for PersistentStorage {
             .await?;
         Ok(())
     }
-}
-
-/// In-memory implementation of `Storage`. The intention is primarily in testing environments.
-#[cfg(test)]
-#[derive(Clone)]
-pub(crate) struct InMemStorage;
-
-//#[cfg(test)]
-//impl InMemStorage {
-//    #[allow(unused)]
-//    async fn init<P: AsRef<Path> + Send>() -> Result<Self, StorageError> {
-//        todo!()
-//    }
-//}
-
-#[cfg(test)]
-#[async_trait]
-impl Storage for InMemStorage {
-    async fn insert_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _shared_keys: SharedKeys,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn get_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<Option<PersistedSharedKeys>, StorageError> {
-        todo!()
-    }
-
-    async fn remove_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn store_message(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _message: Vec<u8>,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn retrieve_messages(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _start_after: Option<i64>,
-    ) -> Result<(Vec<StoredMessage>, Option<i64>), StorageError> {
-        todo!()
-    }
-
-    async fn remove_messages(&self, _ids: Vec<i64>) -> Result<(), StorageError> {
-        todo!()
-    }
 
-    async fn create_bandwidth_entry(
+    async fn insert_banned_client(
         &self,
-        _client_address: DestinationAddressBytes,
+        client_address: DestinationAddressBytes,
     ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn get_available_bandwidth(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<Option<i64>, StorageError> {
-        todo!()
+        self.banned_clients_manager
+            .insert_banned_client(&client_address.as_base58_string())
+            .await?;
+        Ok(())
     }
 
-    async fn increase_bandwidth(
+    async fn remove_banned_client(
         &self,
-        _client_address: DestinationAddressBytes,
-        _amount: i64,
-    ) -> Result<(), StorageError> {
-        todo!()
+        client_address: DestinationAddressBytes,
+    ) -> Result<bool, StorageError> {
+        let removed = self
+            .banned_clients_manager
+            .remove_banned_client(&client_address.as_base58_string())
+            .await?;
+        Ok(removed)
     }
 
-    async fn consume_bandwidth(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _amount: i64,
-    ) -> Result<(), StorageError> {
-        todo!()
+    async fn get_banned_clients(&self) -> Result<Vec<String>, StorageError> {
+        let banned = self.banned_clients_manager.get_banned_clients().await?;
+        Ok(banned)
     }
 }
@@ -1,11 +1,13 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg_attr(feature = "postgres", derive(sqlx::FromRow))]
 pub(crate) struct PersistedSharedKeys {
     pub(crate) client_address_bs58: String,
     pub(crate) derived_aes128_ctr_blake3_hmac_keys_bs58: String,
 }
 
+#[cfg_attr(feature = "postgres", derive(sqlx::FromRow))]
 pub(crate) struct StoredMessage {
     pub(crate) id: i64,
     #[allow(dead_code)]
@@ -13,8 +15,16 @@ pub(crate) struct StoredMessage {
     pub(crate) content: Vec<u8>,
 }
 
+#[cfg_attr(feature = "postgres", derive(sqlx::FromRow))]
 pub(crate) struct PersistedBandwidth {
     #[allow(dead_code)]
     pub(crate) client_address_bs58: String,
     pub(crate) available: i64,
 }
+
+#[cfg_attr(feature = "postgres", derive(sqlx::FromRow))]
+pub(crate) struct StoredInbox {
+    pub(crate) client_address_bs58: String,
+    pub(crate) messages: i64,
+    pub(crate) size: i64,
+}
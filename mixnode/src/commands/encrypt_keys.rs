@@ -0,0 +1,53 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::persistence::pathfinder::MixNodePathfinder;
+use crate::config::Config;
+use clap::Args;
+use nym_config::NymConfig;
+use nym_pemstore::PASSPHRASE_ENV_VAR;
+
+#[derive(Args)]
+pub(crate) struct EncryptKeys {
+    /// The id of the mixnode whose keys should be encrypted
+    #[clap(long)]
+    id: String,
+}
+
+pub(crate) fn execute(args: &EncryptKeys) {
+    let config = match Config::load_from_file(&args.id) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!(
+                "Failed to load config for {}. Are you sure you have run `init` before? (Error was: {})",
+                args.id,
+                err,
+            );
+            return;
+        }
+    };
+
+    let pathfinder = MixNodePathfinder::new_from_config(&config);
+    let Some(passphrase) = pathfinder.passphrase() else {
+        error!("The passphrase must be provided via the {PASSPHRASE_ENV_VAR} environment variable");
+        return;
+    };
+    let Some(keys_dir) = pathfinder.keys_dir() else {
+        error!("Could not determine the keys directory");
+        return;
+    };
+
+    match nym_pemstore::encrypt_pem_directory(keys_dir, passphrase) {
+        Ok(encrypted) => {
+            for path in &encrypted {
+                eprintln!("Encrypted {}", path.display());
+            }
+            eprintln!(
+                "Encrypted {} key(s) in {}. Remember to set {PASSPHRASE_ENV_VAR} whenever running the mixnode",
+                encrypted.len(),
+                keys_dir.display()
+            );
+        }
+        Err(err) => error!("Failed to encrypt the keys: {err}"),
+    }
+}
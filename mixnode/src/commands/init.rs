@@ -88,21 +88,23 @@ pub(crate) fn execute(args: &Init) {
         let identity_keys = identity::KeyPair::new(&mut rng);
         let sphinx_keys = encryption::KeyPair::new(&mut rng);
         let pathfinder = MixNodePathfinder::new_from_config(&config);
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &identity_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
             ),
+            pathfinder.passphrase(),
         )
         .expect("Failed to save identity keys");
 
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &sphinx_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
             ),
+            pathfinder.passphrase(),
         )
         .expect("Failed to save sphinx keys");
         eprintln!("Saved mixnet identity and sphinx keypairs");
@@ -15,6 +15,7 @@ use std::net::IpAddr;
 use std::process;
 
 mod describe;
+mod encrypt_keys;
 mod init;
 mod node_details;
 mod run;
@@ -26,6 +27,9 @@ pub(crate) enum Commands {
     /// Describe your mixnode and tell people why they should delegate state to you
     Describe(describe::Describe),
 
+    /// Encrypt, in place, the private keys of the mixnode using the passphrase from the environment
+    EncryptKeys(encrypt_keys::EncryptKeys),
+
     /// Initialise the mixnode
     Init(init::Init),
 
@@ -65,6 +69,7 @@ pub(crate) async fn execute(args: Cli) {
 
     match args.command {
         Commands::Describe(m) => describe::execute(m),
+        Commands::EncryptKeys(m) => encrypt_keys::execute(&m),
         Commands::Init(m) => init::execute(&m),
         Commands::Run(m) => run::execute(&m).await,
         Commands::Sign(m) => sign::execute(&m),
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
+use nym_pemstore::Passphrase;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
@@ -10,6 +11,10 @@ pub struct MixNodePathfinder {
     identity_public_key: PathBuf,
     private_sphinx_key: PathBuf,
     public_sphinx_key: PathBuf,
+
+    /// Passphrase used for encrypting the private keys. If not set, the keys are stored in
+    /// plaintext.
+    passphrase: Option<Passphrase>,
 }
 
 impl MixNodePathfinder {
@@ -19,9 +24,19 @@ impl MixNodePathfinder {
             identity_public_key: config.get_public_identity_key_file(),
             private_sphinx_key: config.get_private_sphinx_key_file(),
             public_sphinx_key: config.get_public_sphinx_key_file(),
+            passphrase: Passphrase::from_env(),
         }
     }
 
+    pub fn passphrase(&self) -> Option<&Passphrase> {
+        self.passphrase.as_ref()
+    }
+
+    /// Directory containing all the keys of the node.
+    pub fn keys_dir(&self) -> Option<&Path> {
+        self.private_identity_key().parent()
+    }
+
     pub fn private_identity_key(&self) -> &Path {
         &self.identity_private_key
     }
@@ -55,11 +55,8 @@ impl ConnectionHandler {
         instrument(skip(self, framed_sphinx_packet), fields(cpucycles))
     )]
     fn handle_received_packet(&self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
+        // note: replay detection happens inside the packet processor with a cache shared between
+        // all connections, since a replayed packet can arrive through any of them
 
         // all processing such, key caching, etc. was done.
         // however, if it was a forward hop, we still need to delay it
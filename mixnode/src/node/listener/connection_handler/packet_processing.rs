@@ -34,6 +34,10 @@ impl PacketProcessor {
         received: FramedSphinxPacket,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         self.node_stats_update_sender.report_received();
-        self.inner_processor.process_received(received)
+        let res = self.inner_processor.process_received(received);
+        if let Err(MixProcessingError::ReplayedPacket) = res {
+            self.node_stats_update_sender.report_replayed();
+        }
+        res
     }
 }
@@ -6,6 +6,7 @@ use crate::config::Config;
 use crate::node::http::{
     description::description,
     hardware::hardware,
+    metrics::metrics,
     not_found,
     stats::stats,
     verloc::{verloc as verlocRoute, VerlocState},
@@ -33,6 +34,7 @@ use tracing::{error, info, warn};
 mod http;
 mod listener;
 pub(crate) mod node_description;
+mod node_metrics;
 mod node_statistics;
 mod packet_delayforwarder;
 
@@ -63,23 +65,27 @@ impl MixNode {
 
     /// Loads identity keys stored on disk
     pub(crate) fn load_identity_keys(pathfinder: &MixNodePathfinder) -> identity::KeyPair {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .expect("Failed to read stored identity key files");
+            ),
+            pathfinder.passphrase(),
+        )
+        .expect("Failed to read stored identity key files");
         identity_keypair
     }
 
     /// Loads Sphinx keys stored on disk
     fn load_sphinx_keys(pathfinder: &MixNodePathfinder) -> encryption::KeyPair {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .expect("Failed to read stored sphinx key files");
+            ),
+            pathfinder.passphrase(),
+        )
+        .expect("Failed to read stored sphinx key files");
         sphinx_keypair
     }
 
@@ -119,7 +125,10 @@ impl MixNode {
         tokio::spawn(async move {
             rocket::build()
                 .configure(config)
-                .mount("/", routes![verlocRoute, description, stats, hardware])
+                .mount(
+                    "/",
+                    routes![verlocRoute, description, stats, hardware, metrics],
+                )
                 .register("/", catchers![not_found])
                 .manage(verloc_state)
                 .manage(descriptor)
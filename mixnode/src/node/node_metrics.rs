@@ -0,0 +1,33 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use lazy_static::lazy_static;
+use nym_metrics::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
+
+lazy_static! {
+    pub(crate) static ref PACKETS_RECEIVED: IntCounter = register_int_counter(
+        "mixnode_packets_received_total",
+        "Number of sphinx packets received by the mixnode"
+    );
+    pub(crate) static ref PACKETS_SENT: IntCounterVec = register_int_counter_vec(
+        "mixnode_packets_sent_total",
+        "Number of sphinx packets sent by the mixnode, per destination",
+        &["destination"]
+    );
+    pub(crate) static ref PACKETS_DROPPED: IntCounterVec = register_int_counter_vec(
+        "mixnode_packets_dropped_total",
+        "Number of sphinx packets the mixnode failed to forward, per destination",
+        &["destination"]
+    );
+    pub(crate) static ref PACKETS_REPLAYED: IntCounter = register_int_counter(
+        "mixnode_packets_replayed_total",
+        "Number of replayed sphinx packets rejected by the mixnode"
+    );
+    pub(crate) static ref DELAY_QUEUE_DEPTH: IntGauge = register_int_gauge(
+        "mixnode_delay_queue_depth",
+        "Number of sphinx packets currently being delayed by the mixnode"
+    );
+}
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, RwLockReadGuard};
 
+use super::node_metrics;
 use super::TaskClient;
 
 // convenience aliases
@@ -31,9 +32,11 @@ impl SharedNodeStats {
                 packets_received_since_startup: 0,
                 packets_sent_since_startup: HashMap::new(),
                 packets_explicitly_dropped_since_startup: HashMap::new(),
+                packets_replayed_since_startup: 0,
                 packets_received_since_last_update: 0,
                 packets_sent_since_last_update: HashMap::new(),
                 packets_explicitly_dropped_since_last_update: HashMap::new(),
+                packets_replayed_since_last_update: 0,
             })),
         }
     }
@@ -43,6 +46,7 @@ impl SharedNodeStats {
         new_received: u64,
         new_sent: PacketsMap,
         new_dropped: PacketsMap,
+        new_replayed: u64,
     ) {
         let mut guard = self.inner.write().await;
         let snapshot_time = SystemTime::now();
@@ -51,6 +55,7 @@ impl SharedNodeStats {
         guard.update_time = snapshot_time;
 
         guard.packets_received_since_startup += new_received;
+        guard.packets_replayed_since_startup += new_replayed;
         for (mix, count) in &new_sent {
             *guard
                 .packets_sent_since_startup
@@ -68,6 +73,7 @@ impl SharedNodeStats {
         guard.packets_received_since_last_update = new_received;
         guard.packets_sent_since_last_update = new_sent;
         guard.packets_explicitly_dropped_since_last_update = new_dropped;
+        guard.packets_replayed_since_last_update = new_replayed;
     }
 
     pub(crate) async fn clone_data(&self) -> NodeStats {
@@ -95,6 +101,9 @@ pub(crate) struct NodeStats {
     // we know for sure we dropped packets to those destinations
     packets_explicitly_dropped_since_startup: PacketsMap,
 
+    // packets we have rejected since we have already processed them before
+    packets_replayed_since_startup: u64,
+
     packets_received_since_last_update: u64,
 
     // note: sent does not imply forwarded. We don't know if it was delivered successfully
@@ -102,6 +111,9 @@ pub(crate) struct NodeStats {
 
     // we know for sure we dropped packets to those destinations
     packets_explicitly_dropped_since_last_update: PacketsMap,
+
+    // packets we have rejected since we have already processed them before
+    packets_replayed_since_last_update: u64,
 }
 
 impl NodeStats {
@@ -115,12 +127,14 @@ impl NodeStats {
                 .packets_explicitly_dropped_since_startup
                 .values()
                 .sum(),
+            packets_replayed_since_startup: self.packets_replayed_since_startup,
             packets_received_since_last_update: self.packets_received_since_last_update,
             packets_sent_since_last_update: self.packets_sent_since_last_update.values().sum(),
             packets_explicitly_dropped_since_last_update: self
                 .packets_explicitly_dropped_since_last_update
                 .values()
                 .sum(),
+            packets_replayed_since_last_update: self.packets_replayed_since_last_update,
         }
     }
 }
@@ -141,6 +155,9 @@ pub(crate) struct NodeStatsSimple {
     // we know for sure we dropped those packets
     packets_explicitly_dropped_since_startup: u64,
 
+    // packets we have rejected since we have already processed them before
+    packets_replayed_since_startup: u64,
+
     packets_received_since_last_update: u64,
 
     // note: sent does not imply forwarded. We don't know if it was delivered successfully
@@ -148,12 +165,16 @@ pub(crate) struct NodeStatsSimple {
 
     // we know for sure we dropped those packets
     packets_explicitly_dropped_since_last_update: u64,
+
+    // packets we have rejected since we have already processed them before
+    packets_replayed_since_last_update: u64,
 }
 
 pub(crate) enum PacketEvent {
     Sent(String),
     Received,
     Dropped(String),
+    Replayed,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +187,7 @@ struct PacketDataInner {
     received: AtomicU64,
     sent: Mutex<PacketsMap>,
     dropped: Mutex<PacketsMap>,
+    replayed: AtomicU64,
 }
 
 impl CurrentPacketData {
@@ -175,6 +197,7 @@ impl CurrentPacketData {
                 received: AtomicU64::new(0),
                 sent: Mutex::new(HashMap::new()),
                 dropped: Mutex::new(HashMap::new()),
+                replayed: AtomicU64::new(0),
             }),
         }
     }
@@ -183,6 +206,10 @@ impl CurrentPacketData {
         self.inner.received.fetch_add(1, Ordering::SeqCst);
     }
 
+    fn increment_replayed(&self) {
+        self.inner.replayed.fetch_add(1, Ordering::SeqCst);
+    }
+
     async fn increment_sent(&self, destination: String) {
         let mut unlocked = self.inner.sent.lock().await;
         let receiver_count = unlocked.entry(destination).or_insert(0);
@@ -195,15 +222,16 @@ impl CurrentPacketData {
         *dropped_count += 1;
     }
 
-    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap) {
+    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap, u64) {
         let mut unlocked_sent = self.inner.sent.lock().await;
         let mut unlocked_dropped = self.inner.dropped.lock().await;
         let received = self.inner.received.swap(0, Ordering::SeqCst);
+        let replayed = self.inner.replayed.swap(0, Ordering::SeqCst);
 
         let sent = std::mem::take(unlocked_sent.deref_mut());
         let dropped = std::mem::take(unlocked_dropped.deref_mut());
 
-        (received, sent, dropped)
+        (received, sent, dropped, replayed)
     }
 }
 
@@ -233,13 +261,22 @@ impl UpdateHandler {
             tokio::select! {
                 Some(packet_data) = self.update_receiver.next() => {
                     match packet_data {
-                        PacketEvent::Received => self.current_data.increment_received(),
+                        PacketEvent::Received => {
+                            node_metrics::PACKETS_RECEIVED.inc();
+                            self.current_data.increment_received()
+                        }
                         PacketEvent::Sent(destination) => {
+                            node_metrics::PACKETS_SENT.with_label_values(&[&destination]).inc();
                             self.current_data.increment_sent(destination).await
                         }
                         PacketEvent::Dropped(destination) => {
+                            node_metrics::PACKETS_DROPPED.with_label_values(&[&destination]).inc();
                             self.current_data.increment_dropped(destination).await
                         }
+                        PacketEvent::Replayed => {
+                            node_metrics::PACKETS_REPLAYED.inc();
+                            self.current_data.increment_replayed()
+                        }
                     }
                 }
                 _ = self.shutdown.recv() => {
@@ -285,6 +322,12 @@ impl UpdateSender {
             .unbounded_send(PacketEvent::Dropped(destination))
             .unwrap()
     }
+
+    pub(crate) fn report_replayed(&self) {
+        // in unbounded_send() failed it means that the receiver channel was disconnected
+        // and hence something weird must have happened without a way of recovering
+        self.0.unbounded_send(PacketEvent::Replayed).unwrap()
+    }
 }
 
 // Worker that periodically updates the shared node stats from the current packet data buffer that
@@ -313,8 +356,11 @@ impl StatsUpdater {
 
     async fn update_stats(&self) {
         // grab new data since last update
-        let (received, sent, dropped) = self.current_packet_data.acquire_and_reset().await;
-        self.current_stats.update(received, sent, dropped).await;
+        let (received, sent, dropped, replayed) =
+            self.current_packet_data.acquire_and_reset().await;
+        self.current_stats
+            .update(received, sent, dropped, replayed)
+            .await;
     }
 
     async fn run(&mut self) {
@@ -376,6 +422,14 @@ impl PacketStatsConsoleLogger {
                     difference_secs,
                 );
             }
+            if stats.packets_replayed_since_startup > 0 {
+                info!(
+                    "Since startup rejected {} replayed packets! ({} in last {} seconds)",
+                    stats.packets_replayed_since_startup,
+                    stats.packets_replayed_since_last_update,
+                    difference_secs,
+                );
+            }
 
             debug!(
                 "Since startup received {} packets ({} in last {} seconds)",
@@ -403,6 +457,12 @@ impl PacketStatsConsoleLogger {
                         .sum::<u64>(),
                 );
             }
+            if stats.packets_replayed_since_startup > 0 {
+                info!(
+                    "Since startup rejected {} replayed packets!",
+                    stats.packets_replayed_since_startup
+                );
+            }
 
             debug!(
                 "Since startup received {} packets",
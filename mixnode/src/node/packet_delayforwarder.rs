@@ -1,6 +1,7 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::node::node_metrics;
 use crate::node::node_statistics::UpdateSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
@@ -59,11 +60,11 @@ where
     fn forward_packet(&mut self, packet: MixPacket) {
         let next_hop = packet.next_hop();
         let packet_mode = packet.packet_mode();
-        let sphinx_packet = packet.into_sphinx_packet();
+        let packet = packet.into_packet();
 
-        if let Err(err) =
-            self.mixnet_client
-                .send_without_response(next_hop, sphinx_packet, packet_mode)
+        if let Err(err) = self
+            .mixnet_client
+            .send_without_response(next_hop, packet, packet_mode)
         {
             if err.kind() == io::ErrorKind::WouldBlock {
                 // we only know for sure if we dropped a packet if our sending queue was full
@@ -121,6 +122,7 @@ where
                     break;
                 }
             }
+            node_metrics::DELAY_QUEUE_DEPTH.set(self.delay_queue.len() as i64);
         }
         log::trace!("DelayForwarder: Exiting");
     }
@@ -142,19 +144,20 @@ mod tests {
     use nym_sphinx_types::builder::SphinxPacketBuilder;
     use nym_sphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
-        SphinxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        NymPacket, SphinxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+        NODE_ADDRESS_LENGTH,
     };
 
     #[derive(Default)]
     struct TestClient {
-        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, SphinxPacket, PacketMode)>>>,
+        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, NymPacket, PacketMode)>>>,
     }
 
     impl nym_mixnet_client::SendWithoutResponse for TestClient {
         fn send_without_response(
             &mut self,
             address: NymNodeRoutingAddress,
-            packet: SphinxPacket,
+            packet: NymPacket,
             packet_mode: PacketMode,
         ) -> io::Result<()> {
             self.packets_sent
@@ -218,7 +221,7 @@ mod tests {
             NymNodeRoutingAddress::from(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 42));
         let mix_packet = MixPacket::new(
             next_hop,
-            make_valid_sphinx_packet(PacketSize::default()),
+            make_valid_sphinx_packet(PacketSize::default()).into(),
             PacketMode::default(),
         );
         let forward_instant = None;
@@ -0,0 +1,26 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- a single deposit can now be split into multiple (smaller) vouchers, each signed separately
+ALTER TABLE signed_deposit RENAME TO signed_deposit_old;
+
+CREATE TABLE signed_deposit
+(
+    id                         INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    tx_hash                    VARCHAR NOT NULL,
+    -- signature on the blind sign request, identifying a particular voucher issued for the deposit
+    request_signature          VARCHAR NOT NULL,
+    -- NULL for deposits that got signed as a whole, before the splitting was possible
+    voucher_value              VARCHAR,
+    blinded_signature_response VARCHAR NOT NULL,
+
+    UNIQUE (tx_hash, request_signature)
+);
+
+INSERT INTO signed_deposit (tx_hash, request_signature, voucher_value, blinded_signature_response)
+SELECT tx_hash, '', NULL, blinded_signature_response
+FROM signed_deposit_old;
+
+DROP TABLE signed_deposit_old;
@@ -5,6 +5,7 @@ use crate::coconut::error::Result;
 use cw3::ProposalResponse;
 use cw4::MemberResponse;
 use nym_coconut_bandwidth_contract_common::spend_credential::SpendCredentialResponse;
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason, DisqualifiedDealer};
 use nym_coconut_dkg_common::dealer::{ContractDealing, DealerDetails, DealerDetailsResponse};
 use nym_coconut_dkg_common::types::{
     EncodedBTEPublicKeyWithProof, Epoch, EpochId, InitialReplacementData,
@@ -33,6 +34,8 @@ pub trait Client {
     async fn get_current_dealers(&self) -> Result<Vec<DealerDetails>>;
     async fn get_dealings(&self, idx: usize) -> Result<Vec<ContractDealing>>;
     async fn get_verification_key_shares(&self, epoch_id: EpochId) -> Result<Vec<ContractVKShare>>;
+    async fn get_complaints(&self, epoch_id: EpochId, dealer: String) -> Result<Vec<Complaint>>;
+    async fn get_disqualified_dealers(&self, epoch_id: EpochId) -> Result<Vec<DisqualifiedDealer>>;
     async fn vote_proposal(&self, proposal_id: u64, vote_yes: bool, fee: Option<Fee>)
         -> Result<()>;
     async fn execute_proposal(&self, proposal_id: u64) -> Result<()>;
@@ -48,6 +51,12 @@ pub trait Client {
         dealing_bytes: ContractSafeBytes,
         resharing: bool,
     ) -> Result<ExecuteResult>;
+    async fn submit_complaint(
+        &self,
+        dealer: String,
+        reason: ComplaintReason,
+        resharing: bool,
+    ) -> Result<ExecuteResult>;
     async fn submit_verification_key_share(
         &self,
         share: VerificationKeyShare,
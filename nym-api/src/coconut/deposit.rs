@@ -6,10 +6,11 @@ use nym_coconut_bandwidth_contract_common::events::{
     DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
     DEPOSIT_VALUE,
 };
-use nym_credentials::coconut::bandwidth::BandwidthVoucher;
+use nym_credentials::coconut::bandwidth::{is_valid_expiry_date, BandwidthVoucher};
 use nym_crypto::asymmetric::encryption;
 use nym_crypto::asymmetric::identity::{self, Signature};
-use nym_validator_client::nyxd::TxResponse;
+use nym_validator_client::nyxd::{Tag, TxResponse};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::error::{CoconutError, Result};
 
@@ -31,27 +32,23 @@ pub async fn extract_encryption_key(
 
     let signature = Signature::from_base58_string(blind_sign_request_body.signature())?;
 
-    let attributes: &Vec<_> = tx
-        .tx_result
-        .events
-        .iter()
-        .find(|event| event.type_str == format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE))
-        .ok_or(CoconutError::DepositEventNotFound)?
-        .attributes
-        .as_ref();
+    let attributes = deposit_attributes(&tx)?;
 
-    let deposit_value = attributes
-        .iter()
-        .find(|tag| tag.key.as_ref() == DEPOSIT_VALUE)
-        .ok_or(CoconutError::DepositValueNotFound)?
-        .value
-        .as_ref();
-    let deposit_value_plain = public_attributes_plain.get(0).cloned().unwrap_or_default();
-    if deposit_value != deposit_value_plain {
-        return Err(CoconutError::DifferentPublicAttributes(
+    // the deposit might be split into multiple vouchers, so each of them can't be worth more
+    // than the whole deposit (whether all of them together are is checked by the caller)
+    let deposit_value = parse_deposit_value(attributes)?;
+    let voucher_value_plain = public_attributes_plain.get(0).cloned().unwrap_or_default();
+    let voucher_value = voucher_value_plain.parse::<u128>().map_err(|_| {
+        CoconutError::DifferentPublicAttributes(
             deposit_value.to_string(),
-            deposit_value_plain,
-        ));
+            voucher_value_plain.clone(),
+        )
+    })?;
+    if voucher_value == 0 || voucher_value > deposit_value {
+        return Err(CoconutError::VoucherValueExceedsDeposit {
+            voucher_value: voucher_value_plain,
+            deposit_value: deposit_value.to_string(),
+        });
     }
 
     let deposit_info = attributes
@@ -68,6 +65,19 @@ pub async fn extract_encryption_key(
         ));
     }
 
+    // the credential can't be made valid for longer than the allowed period
+    let expiry_date_plain = public_attributes_plain.get(2).cloned().unwrap_or_default();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    if !expiry_date_plain
+        .parse()
+        .map_or(false, |expiry_date| is_valid_expiry_date(expiry_date, now))
+    {
+        return Err(CoconutError::InvalidExpiryDate(expiry_date_plain));
+    }
+
     let verification_key = identity::PublicKey::from_base58_string(
         attributes
             .iter()
@@ -91,14 +101,44 @@ pub async fn extract_encryption_key(
     Ok(encryption_key)
 }
 
+/// Extracts the total amount of tokens deposited in the provided transaction.
+pub fn extract_deposit_value(tx: &TxResponse) -> Result<u128> {
+    parse_deposit_value(deposit_attributes(tx)?)
+}
+
+fn deposit_attributes(tx: &TxResponse) -> Result<&[Tag]> {
+    Ok(tx
+        .tx_result
+        .events
+        .iter()
+        .find(|event| event.type_str == format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE))
+        .ok_or(CoconutError::DepositEventNotFound)?
+        .attributes
+        .as_ref())
+}
+
+fn parse_deposit_value(attributes: &[Tag]) -> Result<u128> {
+    let deposit_value = attributes
+        .iter()
+        .find(|tag| tag.key.as_ref() == DEPOSIT_VALUE)
+        .ok_or(CoconutError::DepositValueNotFound)?
+        .value
+        .as_ref();
+
+    deposit_value
+        .parse()
+        .map_err(|_| CoconutError::InvalidDepositValue(deposit_value.to_string()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::coconut::tests::tx_entry_fixture;
     use nym_coconut::{prepare_blind_sign, BlindSignRequest, Parameters};
     use nym_config::defaults::VOUCHER_INFO;
+    use nym_credentials::coconut::bandwidth::{expiry_date_for, TOTAL_ATTRIBUTES};
     use nym_validator_client::nyxd::tx::Hash;
-    use nym_validator_client::nyxd::{Event, Tag};
+    use nym_validator_client::nyxd::Event;
     use rand_07::rngs::OsRng;
     use std::str::FromStr;
 
@@ -108,24 +148,33 @@ mod test {
             Hash::from_str("6B27412050B823E58BB38447D7870BBC8CBE3C51C905BEA89D459ACCDA80A00E")
                 .unwrap();
         let mut tx_entry = tx_entry_fixture(&tx_hash.to_string());
-        let params = Parameters::new(4).unwrap();
-        let mut rng = OsRng;
-        let voucher = BandwidthVoucher::new(
-            &params,
-            "1234".to_string(),
-            VOUCHER_INFO.to_string(),
-            tx_hash,
-            identity::PrivateKey::from_base58_string(
-                identity::KeyPair::new(&mut rng)
-                    .private_key()
-                    .to_base58_string(),
-            )
-            .unwrap(),
-            encryption::PrivateKey::from_bytes(
-                &encryption::KeyPair::new(&mut rng).private_key().to_bytes(),
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let voucher_with_expiry = |expiry_date| {
+            BandwidthVoucher::new(
+                &params,
+                "1234".to_string(),
+                VOUCHER_INFO.to_string(),
+                expiry_date,
+                tx_hash,
+                identity::PrivateKey::from_base58_string(
+                    identity::KeyPair::new(&mut OsRng)
+                        .private_key()
+                        .to_base58_string(),
+                )
+                .unwrap(),
+                encryption::PrivateKey::from_bytes(
+                    &encryption::KeyPair::new(&mut OsRng)
+                        .private_key()
+                        .to_bytes(),
+                )
+                .unwrap(),
             )
-            .unwrap(),
-        );
+        };
+        let voucher = voucher_with_expiry(expiry_date_for(now));
         let (_, blind_sign_req) = prepare_blind_sign(
             &params,
             &voucher.get_private_attributes(),
@@ -143,7 +192,7 @@ mod test {
                 String::from("First wrong plain"),
                 String::from("Second wrong plain"),
             ],
-            4,
+            TOTAL_ATTRIBUTES,
         );
         let err = extract_encryption_key(&req, tx_entry.clone())
             .await
@@ -159,7 +208,7 @@ mod test {
             String::from("Invalid signature"),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            TOTAL_ATTRIBUTES,
         );
         let err = extract_encryption_key(&req, tx_entry.clone())
             .await
@@ -178,7 +227,7 @@ mod test {
             signature.clone(),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            TOTAL_ATTRIBUTES,
         );
 
         tx_entry.tx_result.events.push(Event {
@@ -202,10 +251,27 @@ mod test {
             .unwrap_err();
         assert_eq!(
             err.to_string(),
-            CoconutError::DifferentPublicAttributes("10".to_string(), "1234".to_string())
-                .to_string(),
+            CoconutError::VoucherValueExceedsDeposit {
+                voucher_value: "1234".to_string(),
+                deposit_value: "10".to_string(),
+            }
+            .to_string(),
         );
 
+        // a voucher can be worth less than the whole deposit
+        tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![Tag {
+            key: DEPOSIT_VALUE.parse().unwrap(),
+            value: "2000".parse().unwrap(),
+        }];
+        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CoconutError::DepositInfoNotFound.to_string(),
+        );
+        assert_eq!(extract_deposit_value(&tx_entry).unwrap(), 2000);
+
         tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![Tag {
             key: DEPOSIT_VALUE.parse().unwrap(),
             value: "1234".parse().unwrap(),
@@ -240,6 +306,39 @@ mod test {
             .to_string(),
         );
 
+        let expired_voucher = voucher_with_expiry(expiry_date_for(now - 2 * 24 * 60 * 60));
+        let (_, expired_blind_sign_req) = prepare_blind_sign(
+            &params,
+            &expired_voucher.get_private_attributes(),
+            &expired_voucher.get_public_attributes(),
+        )
+        .unwrap();
+        let expired_request = BlindSignRequestBody::new(
+            &expired_blind_sign_req,
+            tx_hash.to_string(),
+            signature.clone(),
+            &expired_voucher.get_public_attributes(),
+            expired_voucher.get_public_attributes_plain(),
+            TOTAL_ATTRIBUTES,
+        );
+        tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
+            Tag {
+                key: DEPOSIT_VALUE.parse().unwrap(),
+                value: "1234".parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_INFO.parse().unwrap(),
+                value: VOUCHER_INFO.parse().unwrap(),
+            },
+        ];
+        let err = extract_encryption_key(&expired_request, tx_entry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CoconutError::InvalidExpiryDate(expired_voucher.expiry_date().to_string()).to_string(),
+        );
+
         tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
             Tag {
                 key: DEPOSIT_VALUE.parse().unwrap(),
@@ -402,7 +501,7 @@ mod test {
             "gSFgpma5GAVMcsmZwKieqGNHNd3dPzcfa8eT2Qn2LoBccSeyiJdphREbNrkuh5XWxMe2hUsranaYzLro48L9Qhd".to_string(),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            TOTAL_ATTRIBUTES,
         );
         tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
             Tag {
@@ -5,6 +5,7 @@ use crate::coconut::client::Client;
 use crate::coconut::error::CoconutError;
 use cw3::ProposalResponse;
 use cw4::MemberResponse;
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason, DisqualifiedDealer};
 use nym_coconut_dkg_common::dealer::{ContractDealing, DealerDetails, DealerDetailsResponse};
 use nym_coconut_dkg_common::types::{
     EncodedBTEPublicKeyWithProof, Epoch, EpochId, InitialReplacementData, NodeIndex,
@@ -98,6 +99,21 @@ impl DkgClient {
         self.inner.get_verification_key_shares(epoch_id).await
     }
 
+    pub(crate) async fn get_complaints(
+        &self,
+        epoch_id: EpochId,
+        dealer: String,
+    ) -> Result<Vec<Complaint>, CoconutError> {
+        self.inner.get_complaints(epoch_id, dealer).await
+    }
+
+    pub(crate) async fn get_disqualified_dealers(
+        &self,
+        epoch_id: EpochId,
+    ) -> Result<Vec<DisqualifiedDealer>, CoconutError> {
+        self.inner.get_disqualified_dealers(epoch_id).await
+    }
+
     pub(crate) async fn list_proposals(&self) -> Result<Vec<ProposalResponse>, CoconutError> {
         self.inner.list_proposals().await
     }
@@ -138,6 +154,18 @@ impl DkgClient {
         Ok(())
     }
 
+    pub(crate) async fn submit_complaint(
+        &self,
+        dealer: String,
+        reason: ComplaintReason,
+        resharing: bool,
+    ) -> Result<(), CoconutError> {
+        self.inner
+            .submit_complaint(dealer, reason, resharing)
+            .await?;
+        Ok(())
+    }
+
     pub(crate) async fn submit_verification_key_share(
         &self,
         share: VerificationKeyShare,
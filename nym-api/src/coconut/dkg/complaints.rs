@@ -1,13 +1,50 @@
-// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// Copyright 2022-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use serde::{Deserialize, Serialize};
+use crate::coconut::dkg::client::DkgClient;
+use crate::coconut::dkg::state::{ConsistentState, State};
+use crate::coconut::dkg::verification_key::deterministic_filter_dealers;
+use crate::coconut::error::CoconutError;
+use log::debug;
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub(crate) enum ComplaintReason {
-    MalformedBTEPublicKey,
-    InvalidBTEPublicKey,
-    MissingDealing,
-    MalformedDealing,
-    DealingVerificationError,
+pub(crate) async fn complaint_submission(
+    dkg_client: &DkgClient,
+    state: &mut State,
+    resharing: bool,
+) -> Result<(), CoconutError> {
+    if state.submitted_complaints() {
+        debug!("Complaints were submitted previously, nothing to do");
+        return Ok(());
+    }
+
+    // mark all the dealers that misbehaved during the dealing exchange
+    let threshold = state.threshold()?;
+    deterministic_filter_dealers(dkg_client, state, threshold, resharing).await?;
+
+    let own_address = dkg_client.get_address().await.to_string();
+    let epoch_id = dkg_client.get_current_epoch().await?.epoch_id;
+    for (dealer, reason) in state.misbehaving_dealers() {
+        if dealer.as_str() == own_address {
+            continue;
+        }
+        // in case we crashed in the middle of submitting the complaints
+        let already_complained = dkg_client
+            .get_complaints(epoch_id, dealer.to_string())
+            .await?
+            .iter()
+            .any(|complaint| complaint.complainer.as_str() == own_address);
+        if already_complained {
+            continue;
+        }
+
+        debug!("Complaining about dealer {dealer} due to {reason:?}");
+        dkg_client
+            .submit_complaint(dealer.to_string(), reason, resharing)
+            .await?;
+    }
+
+    state.set_submitted_complaints();
+    info!("DKG: Submitted complaints about misbehaving dealers");
+
+    Ok(())
 }
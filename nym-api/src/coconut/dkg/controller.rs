@@ -7,7 +7,7 @@ use crate::coconut::dkg::verification_key::{
     verification_key_finalization, verification_key_validation,
 };
 use crate::coconut::dkg::{
-    dealing::dealing_exchange, public_key::public_key_submission,
+    complaints::complaint_submission, dealing::dealing_exchange, public_key::public_key_submission,
     verification_key::verification_key_submission,
 };
 use crate::coconut::keypair::KeyPair as CoconutKeyPair;
@@ -127,6 +127,9 @@ impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
                             )
                             .await
                         }
+                        EpochState::ComplaintSubmission { resharing } => {
+                            complaint_submission(&self.dkg_client, &mut self.state, resharing).await
+                        }
                         EpochState::VerificationKeySubmission { resharing } => {
                             let keypair_path = nym_pemstore::KeyPairPath::new(
                                 self.secret_key_path.clone(),
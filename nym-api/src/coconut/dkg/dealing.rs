@@ -92,12 +92,12 @@ pub(crate) async fn dealing_exchange(
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use crate::coconut::dkg::complaints::ComplaintReason;
     use crate::coconut::dkg::state::PersistentState;
     use crate::coconut::tests::DummyClient;
     use crate::coconut::KeyPair;
     use cosmwasm_std::Addr;
     use nym_coconut::{ttp_keygen, Parameters};
+    use nym_coconut_dkg_common::complaint::ComplaintReason;
     use nym_coconut_dkg_common::dealer::DealerDetails;
     use nym_coconut_dkg_common::types::InitialReplacementData;
     use nym_dkg::bte::keys::KeyPair as DkgKeyPair;
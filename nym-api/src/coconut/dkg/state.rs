@@ -1,12 +1,12 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::coconut::dkg::complaints::ComplaintReason;
 use crate::coconut::error::CoconutError;
 use crate::coconut::keypair::KeyPair as CoconutKeyPair;
 use cosmwasm_std::Addr;
-use log::debug;
+use log::{debug, warn};
 use nym_coconut::SecretKey;
+use nym_coconut_dkg_common::complaint::{ComplaintReason, DisqualifiedDealer};
 use nym_coconut_dkg_common::dealer::DealerDetails;
 use nym_coconut_dkg_common::types::EpochState;
 use nym_dkg::bte::{keys::KeyPair as DkgKeyPair, PublicKey, PublicKeyWithProof};
@@ -77,6 +77,10 @@ pub(crate) trait ConsistentState {
             EpochState::DealingExchange { .. } => {
                 self.node_index_value()?;
             }
+            EpochState::ComplaintSubmission { .. } => {
+                self.receiver_index_value()?;
+                self.threshold()?;
+            }
             EpochState::VerificationKeySubmission { .. } => {
                 self.receiver_index_value()?;
                 self.threshold()?;
@@ -164,12 +168,16 @@ where
 pub(crate) struct PersistentState {
     node_index: Option<NodeIndex>,
     dealers: BTreeMap<Addr, Result<DkgParticipant, ComplaintReason>>,
+    #[serde(default)]
+    dealing_receivers: BTreeMap<Addr, DkgParticipant>,
     receiver_index: Option<usize>,
     threshold: Option<Threshold>,
     #[serde(serialize_with = "vks_serialize")]
     #[serde(deserialize_with = "vks_deserialize")]
     recovered_vks: Vec<RecoveredVerificationKeys>,
     proposal_id: Option<u64>,
+    #[serde(default)]
+    submitted_complaints: bool,
     voted_vks: bool,
     executed_proposal: bool,
     was_in_progress: bool,
@@ -180,10 +188,12 @@ impl From<&State> for PersistentState {
         PersistentState {
             node_index: s.node_index,
             dealers: s.dealers.clone(),
+            dealing_receivers: s.dealing_receivers.clone(),
             receiver_index: s.receiver_index,
             threshold: s.threshold,
             recovered_vks: s.recovered_vks.clone(),
             proposal_id: s.proposal_id,
+            submitted_complaints: s.submitted_complaints,
             voted_vks: s.voted_vks,
             executed_proposal: s.executed_proposal,
             was_in_progress: s.was_in_progress,
@@ -209,10 +219,14 @@ pub(crate) struct State {
     coconut_keypair: CoconutKeyPair,
     node_index: Option<NodeIndex>,
     dealers: BTreeMap<Addr, Result<DkgParticipant, ComplaintReason>>,
+    // the receivers the dealings were created for; unlike `dealers`, it doesn't change
+    // when dealers get marked as bad later on
+    dealing_receivers: BTreeMap<Addr, DkgParticipant>,
     receiver_index: Option<usize>,
     threshold: Option<Threshold>,
     recovered_vks: Vec<RecoveredVerificationKeys>,
     proposal_id: Option<u64>,
+    submitted_complaints: bool,
     voted_vks: bool,
     executed_proposal: bool,
     was_in_progress: bool,
@@ -233,10 +247,12 @@ impl State {
             coconut_keypair,
             node_index: persistent_state.node_index,
             dealers: persistent_state.dealers,
+            dealing_receivers: persistent_state.dealing_receivers,
             receiver_index: persistent_state.receiver_index,
             threshold: persistent_state.threshold,
             recovered_vks: persistent_state.recovered_vks,
             proposal_id: persistent_state.proposal_id,
+            submitted_complaints: persistent_state.submitted_complaints,
             voted_vks: persistent_state.voted_vks,
             executed_proposal: persistent_state.executed_proposal,
             was_in_progress: persistent_state.was_in_progress,
@@ -249,10 +265,12 @@ impl State {
         }
         self.node_index = Default::default();
         self.dealers = Default::default();
+        self.dealing_receivers = Default::default();
         self.receiver_index = Default::default();
         self.threshold = Default::default();
         self.recovered_vks = Default::default();
         self.proposal_id = Default::default();
+        self.submitted_complaints = Default::default();
         self.voted_vks = Default::default();
         self.executed_proposal = Default::default();
         self.was_in_progress = Default::default();
@@ -316,10 +334,35 @@ impl State {
             .collect()
     }
 
+    pub fn dealing_receivers_by_idx(&self) -> BTreeMap<NodeIndex, PublicKey> {
+        self.dealing_receivers
+            .values()
+            .map(|participant| {
+                (
+                    participant.assigned_index,
+                    *participant.bte_public_key_with_proof.public_key(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn misbehaving_dealers(&self) -> BTreeMap<Addr, ComplaintReason> {
+        self.dealers
+            .iter()
+            .filter_map(|(addr, dealer)| {
+                dealer.as_ref().err().map(|reason| (addr.clone(), *reason))
+            })
+            .collect()
+    }
+
     pub fn recovered_vks(&self) -> &Vec<RecoveredVerificationKeys> {
         &self.recovered_vks
     }
 
+    pub fn submitted_complaints(&self) -> bool {
+        self.submitted_complaints
+    }
+
     pub fn voted_vks(&self) -> bool {
         self.voted_vks
     }
@@ -352,14 +395,29 @@ impl State {
             dealers
                 .into_iter()
                 .map(|details| (details.address.clone(), DkgParticipant::try_from(details))),
-        )
+        );
+        self.dealing_receivers = self
+            .dealers
+            .iter()
+            .filter_map(|(addr, dealer)| {
+                dealer
+                    .as_ref()
+                    .ok()
+                    .map(|participant| (addr.clone(), participant.clone()))
+            })
+            .collect();
     }
 
+    pub fn is_bad_dealer(&self, dealer_addr: &Addr) -> bool {
+        matches!(self.dealers.get(dealer_addr), Some(Err(_)))
+    }
+
+    // the first reason a dealer got marked for is kept, as that's the one we complain about
     pub fn mark_bad_dealer(&mut self, dealer_addr: &Addr, reason: ComplaintReason) {
         if let Some((_, value)) = self
             .dealers
             .iter_mut()
-            .find(|(addr, _)| *addr == dealer_addr)
+            .find(|(addr, dealer)| *addr == dealer_addr && dealer.is_ok())
         {
             debug!(
                 "Dealer {} misbehaved: {:?}. It will be marked locally as bad dealer and ignored",
@@ -369,6 +427,17 @@ impl State {
         }
     }
 
+    /// Excludes the dealer that got disqualified on chain, so that all signers end up with the same dealer set.
+    pub fn mark_disqualified_dealer(&mut self, disqualified: &DisqualifiedDealer) {
+        if let Some(Ok(_)) = self.dealers.get(&disqualified.dealer) {
+            warn!(
+                "Dealer {} got disqualified by {} other dealers, even though it seemed to behave correctly",
+                disqualified.dealer, disqualified.complaints
+            );
+        }
+        self.mark_bad_dealer(&disqualified.dealer, disqualified.reason);
+    }
+
     pub fn set_receiver_index(&mut self, receiver_index: Option<usize>) {
         self.receiver_index = receiver_index;
     }
@@ -381,6 +450,10 @@ impl State {
         self.proposal_id = Some(proposal_id);
     }
 
+    pub fn set_submitted_complaints(&mut self) {
+        self.submitted_complaints = true;
+    }
+
     pub fn set_voted_vks(&mut self) {
         self.voted_vks = true;
     }
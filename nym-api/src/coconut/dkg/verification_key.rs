@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::coconut::dkg::client::DkgClient;
-use crate::coconut::dkg::complaints::ComplaintReason;
 use crate::coconut::dkg::state::{ConsistentState, State};
 use crate::coconut::error::CoconutError;
 use crate::coconut::helpers::accepted_vote_err;
@@ -11,6 +10,7 @@ use cw3::{ProposalResponse, Status};
 use log::debug;
 use nym_coconut::tests::helpers::transpose_matrix;
 use nym_coconut::{check_vk_pairing, Base58, KeyPair, Parameters, SecretKey, VerificationKey};
+use nym_coconut_dkg_common::complaint::ComplaintReason;
 use nym_coconut_dkg_common::event_attributes::DKG_PROPOSAL_ID;
 use nym_coconut_dkg_common::types::{NodeIndex, TOTAL_DEALINGS};
 use nym_coconut_dkg_common::verification_key::owner_from_cosmos_msgs;
@@ -24,7 +24,7 @@ use nym_validator_client::nyxd::cosmwasm_client::logs::find_attribute;
 use std::collections::BTreeMap;
 
 // Filter the dealers based on what dealing they posted (or not) in the contract
-async fn deterministic_filter_dealers(
+pub(crate) async fn deterministic_filter_dealers(
     dkg_client: &DkgClient,
     state: &mut State,
     threshold: Threshold,
@@ -32,7 +32,9 @@ async fn deterministic_filter_dealers(
 ) -> Result<Vec<BTreeMap<NodeIndex, (Addr, Dealing)>>, CoconutError> {
     let mut dealings_maps = vec![];
     let initial_dealers_by_addr = state.current_dealers_by_addr();
-    let initial_receivers = state.current_dealers_by_idx();
+    // dealings have to be verified against the receivers they were created for,
+    // not against the set that's left after some dealers got marked as bad
+    let initial_receivers = state.dealing_receivers_by_idx();
     let initial_resharing_dealers = if resharing {
         dkg_client
             .get_initial_dealers()
@@ -82,7 +84,7 @@ async fn deterministic_filter_dealers(
 
     for (addr, _) in initial_dealers_by_addr.iter() {
         // in resharing mode, we don't commit dealings from dealers outside the initial set
-        if !resharing || initial_resharing_dealers.contains(addr) {
+        if (!resharing || initial_resharing_dealers.contains(addr)) && !state.is_bad_dealer(addr) {
             for dealings_map in dealings_maps.iter() {
                 if !dealings_map.iter().any(|(_, (address, _))| address == addr) {
                     state.mark_bad_dealer(addr, ComplaintReason::MissingDealing);
@@ -167,6 +169,12 @@ pub(crate) async fn verification_key_submission(
     let threshold = state.threshold()?;
     let dealings_maps =
         deterministic_filter_dealers(dkg_client, state, threshold, resharing).await?;
+    // exclude the dealers disqualified during the complaint round, as the final key
+    // has to be derived from exactly the same dealer set by all the signers
+    let epoch_id = dkg_client.get_current_epoch().await?.epoch_id;
+    for disqualified in dkg_client.get_disqualified_dealers(epoch_id).await? {
+        state.mark_disqualified_dealer(&disqualified);
+    }
     debug!(
         "Filtered dealers to {:?}",
         dealings_maps[0].keys().collect::<Vec<_>>()
@@ -301,12 +309,14 @@ pub(crate) async fn verification_key_finalization(
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
+    use crate::coconut::dkg::complaints::complaint_submission;
     use crate::coconut::dkg::dealing::dealing_exchange;
     use crate::coconut::dkg::public_key::public_key_submission;
     use crate::coconut::dkg::state::PersistentState;
     use crate::coconut::tests::DummyClient;
     use crate::coconut::KeyPair;
     use nym_coconut::aggregate_verification_keys;
+    use nym_coconut_dkg_common::complaint::Complaint;
     use nym_coconut_dkg_common::dealer::DealerDetails;
     use nym_coconut_dkg_common::types::InitialReplacementData;
     use nym_coconut_dkg_common::verification_key::ContractVKShare;
@@ -327,6 +337,7 @@ pub(crate) mod tests {
         dealings_db: Arc<RwLock<HashMap<String, Vec<ContractSafeBytes>>>>,
         proposal_db: Arc<RwLock<HashMap<u64, ProposalResponse>>>,
         verification_share_db: Arc<RwLock<HashMap<String, ContractVKShare>>>,
+        complaints_db: Arc<RwLock<HashMap<String, Vec<Complaint>>>>,
         threshold_db: Arc<RwLock<Option<Threshold>>>,
         initial_dealers_db: Arc<RwLock<Option<InitialReplacementData>>>,
     }
@@ -338,6 +349,7 @@ pub(crate) mod tests {
                 dealings_db: Arc::new(Default::default()),
                 proposal_db: Arc::new(Default::default()),
                 verification_share_db: Arc::new(Default::default()),
+                complaints_db: Arc::new(Default::default()),
                 threshold_db: Arc::new(RwLock::new(Some(2))),
                 initial_dealers_db: Arc::new(RwLock::new(Default::default())),
             }
@@ -362,6 +374,7 @@ pub(crate) mod tests {
                     .with_dealings(&db.dealings_db)
                     .with_proposal_db(&db.proposal_db)
                     .with_verification_share(&db.verification_share_db)
+                    .with_complaints(&db.complaints_db)
                     .with_threshold(&db.threshold_db)
                     .with_initial_dealers_db(&db.initial_dealers_db),
             );
@@ -481,7 +494,7 @@ pub(crate) mod tests {
                 .unwrap()
                 .as_ref()
                 .unwrap_err();
-            assert_eq!(*corrupted_status, ComplaintReason::MissingDealing);
+            assert_eq!(*corrupted_status, ComplaintReason::MalformedDealing);
         }
     }
 
@@ -580,7 +593,7 @@ pub(crate) mod tests {
                 .unwrap()
                 .as_ref()
                 .unwrap_err();
-            assert_eq!(*corrupted_status, ComplaintReason::MissingDealing);
+            assert_eq!(*corrupted_status, ComplaintReason::MalformedDealing);
         }
     }
 
@@ -602,11 +615,6 @@ pub(crate) mod tests {
             });
 
         for (dkg_client, state) in clients_and_states.iter_mut().skip(1) {
-            deterministic_filter_dealers(dkg_client, state, 2, false)
-                .await
-                .unwrap();
-            // second filter will leave behind the bad dealer and surface why it was left out
-            // in the first place
             let filtered = deterministic_filter_dealers(dkg_client, state, 2, false)
                 .await
                 .unwrap();
@@ -644,11 +652,6 @@ pub(crate) mod tests {
             });
 
         for (dkg_client, state) in clients_and_states.iter_mut().skip(1) {
-            deterministic_filter_dealers(dkg_client, state, 2, false)
-                .await
-                .unwrap();
-            // second filter will leave behind the bad dealer and surface why it was left out
-            // in the first place
             let filtered = deterministic_filter_dealers(dkg_client, state, 2, false)
                 .await
                 .unwrap();
@@ -663,6 +666,63 @@ pub(crate) mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn complaints_disqualify_bad_dealer() {
+        let db = MockContractDb::new();
+        let mut clients_and_states = prepare_clients_and_states_with_dealing(&db).await;
+
+        // corrupt just one dealing
+        db.dealings_db
+            .write()
+            .unwrap()
+            .entry(TEST_VALIDATORS_ADDRESS[0].to_string())
+            .and_modify(|dealings| {
+                let mut last = dealings.pop().unwrap();
+                last.0.pop();
+                dealings.push(last);
+            });
+
+        for (dkg_client, state) in clients_and_states.iter_mut() {
+            complaint_submission(dkg_client, state, false)
+                .await
+                .unwrap();
+            assert!(state.submitted_complaints());
+        }
+
+        let complaints = db
+            .complaints_db
+            .read()
+            .unwrap()
+            .get(TEST_VALIDATORS_ADDRESS[0])
+            .cloned()
+            .unwrap();
+        assert_eq!(complaints.len(), 3);
+        assert!(complaints
+            .iter()
+            .all(|complaint| complaint.reason == ComplaintReason::MalformedDealing));
+        assert_eq!(db.complaints_db.read().unwrap().len(), 1);
+
+        // submitting again doesn't result in duplicate complaints
+        for (dkg_client, state) in clients_and_states.iter_mut() {
+            complaint_submission(dkg_client, state, false)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            db.complaints_db.read().unwrap()[TEST_VALIDATORS_ADDRESS[0]].len(),
+            3
+        );
+
+        let (dkg_client, _) = clients_and_states.first().unwrap();
+        let disqualified = dkg_client.get_disqualified_dealers(0).await.unwrap();
+        assert_eq!(disqualified.len(), 1);
+        assert_eq!(
+            disqualified[0].dealer,
+            Addr::unchecked(TEST_VALIDATORS_ADDRESS[0])
+        );
+        assert_eq!(disqualified[0].reason, ComplaintReason::MalformedDealing);
+    }
+
     #[tokio::test]
     #[ignore] // expensive test
     async fn partial_keypair_derivation() {
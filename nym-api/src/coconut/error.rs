@@ -70,6 +70,21 @@ pub enum CoconutError {
     )]
     DifferentPublicAttributes(String, String),
 
+    #[error("Could not parse the deposit value {0}")]
+    InvalidDepositValue(String),
+
+    #[error("The voucher value of {voucher_value} is invalid for the deposit of {deposit_value}")]
+    VoucherValueExceedsDeposit {
+        voucher_value: String,
+        deposit_value: String,
+    },
+
+    #[error("The credential expiry date {0} is not within the allowed validity period")]
+    InvalidExpiryDate(String),
+
+    #[error("The deposit {tx_hash} has already been used up for the previously issued vouchers")]
+    DepositExhausted { tx_hash: String },
+
     #[error("Error in coconut interface - {0}")]
     CoconutInterfaceError(#[from] nym_coconut_interface::error::CoconutInterfaceError),
 
@@ -3,7 +3,7 @@
 
 use self::comm::APICommunicationChannel;
 use crate::coconut::client::Client as LocalClient;
-use crate::coconut::deposit::extract_encryption_key;
+use crate::coconut::deposit::{extract_deposit_value, extract_encryption_key};
 use crate::coconut::error::{CoconutError, Result};
 use crate::coconut::helpers::accepted_vote_err;
 use crate::support::storage::NymApiStorage;
@@ -21,6 +21,7 @@ use nym_coconut_interface::{
     Attribute, BlindSignRequest, BlindedSignature, Parameters, VerificationKey,
 };
 use nym_config::defaults::NYM_API_VERSION;
+use nym_credentials::coconut::batch_verifier::BatchCredentialVerifier;
 use nym_credentials::coconut::params::{
     NymApiCredentialEncryptionAlgorithm, NymApiCredentialHkdfAlgorithm,
 };
@@ -53,6 +54,9 @@ pub struct State {
     comm_channel: Arc<dyn APICommunicationChannel + Send + Sync>,
     storage: NymApiStorage,
     rng: Arc<Mutex<OsRng>>,
+    credential_verifier: BatchCredentialVerifier,
+    // makes sure the vouchers issued for the same deposit never exceed its value
+    issuance_lock: Arc<Mutex<()>>,
 }
 
 impl State {
@@ -77,11 +81,22 @@ impl State {
             comm_channel,
             storage,
             rng,
+            credential_verifier: BatchCredentialVerifier::default(),
+            issuance_lock: Arc::new(Mutex::new(())),
         }
     }
 
-    pub async fn signed_before(&self, tx_hash: &str) -> Result<Option<BlindedSignatureResponse>> {
-        let ret = self.storage.get_blinded_signature_response(tx_hash).await?;
+    /// Checks whether the particular voucher request for the deposit has already been signed.
+    /// Requests are identified by their (deterministic) signatures.
+    pub async fn signed_before(
+        &self,
+        tx_hash: &str,
+        request_signature: &str,
+    ) -> Result<Option<BlindedSignatureResponse>> {
+        let ret = self
+            .storage
+            .get_blinded_signature_response(tx_hash, request_signature)
+            .await?;
         if let Some(blinded_signature_reponse) = ret {
             Ok(Some(BlindedSignatureResponse::from_base58_string(
                 blinded_signature_reponse,
@@ -91,9 +106,29 @@ impl State {
         }
     }
 
+    /// Returns the total value of the vouchers signed so far for the deposit.
+    pub async fn issued_value(&self, tx_hash: &str, deposit_value: u128) -> Result<u128> {
+        let mut issued: u128 = 0;
+        for value in self.storage.get_signed_voucher_values(tx_hash).await? {
+            match value {
+                // the deposit was signed as a whole
+                None => return Ok(deposit_value),
+                Some(value) => {
+                    let value = value
+                        .parse::<u128>()
+                        .map_err(|_| CoconutError::InvalidDepositValue(value))?;
+                    issued = issued.saturating_add(value);
+                }
+            }
+        }
+        Ok(issued)
+    }
+
     pub async fn encrypt_and_store(
         &self,
         tx_hash: &str,
+        request_signature: &str,
+        voucher_value: &str,
         remote_key: &encryption::PublicKey,
         signature: &BlindedSignature,
     ) -> Result<BlindedSignatureResponse> {
@@ -119,15 +154,20 @@ impl State {
             BlindedSignatureResponse::new(encrypted_data, keypair.public_key().to_bytes());
 
         // Atomically insert data, only if there is no signature stored in the meantime
-        // This prevents race conditions on storing two signatures for the same deposit transaction
+        // This prevents race conditions on storing two signatures for the same voucher request
         if self
             .storage
-            .insert_blinded_signature_response(tx_hash, &response.to_base58_string())
+            .insert_blinded_signature_response(
+                tx_hash,
+                request_signature,
+                voucher_value,
+                &response.to_base58_string(),
+            )
             .await
             .is_err()
         {
             Ok(self
-                .signed_before(tx_hash)
+                .signed_before(tx_hash, request_signature)
                 .await?
                 .expect("The signature was expected to be there"))
         } else {
@@ -205,17 +245,32 @@ pub async fn post_blind_sign(
     state: &RocketState<State>,
 ) -> Result<Json<BlindedSignatureResponse>> {
     debug!("{:?}", blind_sign_request_body);
-    if let Some(response) = state
-        .signed_before(blind_sign_request_body.tx_hash())
-        .await?
-    {
+    let tx_hash = blind_sign_request_body.tx_hash();
+    let request_signature = blind_sign_request_body.signature();
+    if let Some(response) = state.signed_before(tx_hash, request_signature).await? {
         return Ok(Json(response));
     }
-    let tx = state
-        .client
-        .get_tx(blind_sign_request_body.tx_hash())
-        .await?;
+    let tx = state.client.get_tx(tx_hash).await?;
+    let deposit_value = extract_deposit_value(&tx)?;
     let encryption_key = extract_encryption_key(&blind_sign_request_body, tx).await?;
+    // this has already been validated when extracting the encryption key
+    let voucher_value = blind_sign_request_body
+        .public_attributes_plain()
+        .get(0)
+        .cloned()
+        .unwrap_or_default();
+
+    let _issuance_guard = state.issuance_lock.lock().await;
+    if let Some(response) = state.signed_before(tx_hash, request_signature).await? {
+        return Ok(Json(response));
+    }
+    let issued_value = state.issued_value(tx_hash, deposit_value).await?;
+    let remaining_value = deposit_value.saturating_sub(issued_value);
+    if voucher_value.parse::<u128>().unwrap_or(u128::MAX) > remaining_value {
+        return Err(CoconutError::DepositExhausted {
+            tx_hash: tx_hash.to_string(),
+        });
+    }
     let internal_request = InternalSignRequest::new(
         *blind_sign_request_body.total_params(),
         blind_sign_request_body.public_attributes(),
@@ -229,7 +284,9 @@ pub async fn post_blind_sign(
 
     let response = state
         .encrypt_and_store(
-            blind_sign_request_body.tx_hash(),
+            tx_hash,
+            request_signature,
+            &voucher_value,
             &encryption_key,
             &blinded_signature,
         )
@@ -259,15 +316,15 @@ pub async fn verify_bandwidth_credential(
             reason: String::from("action is not to release funds"),
         })?;
     // Credential has not been spent before, and is on its way of being spent
-    let credential_status = state
+    let spent_credential = state
         .client
         .get_spent_credential(verify_credential_body.credential().blinded_serial_number())
         .await?
         .spend_credential
         .ok_or(CoconutError::InvalidCredentialStatus {
             status: String::from("Inexistent"),
-        })?
-        .status();
+        })?;
+    let credential_status = spent_credential.status();
     if credential_status != SpendCredentialStatus::InProgress {
         return Err(CoconutError::InvalidCredentialStatus {
             status: format!("{:?}", credential_status),
@@ -276,9 +333,14 @@ pub async fn verify_bandwidth_credential(
     let verification_key = state
         .verification_key(*verify_credential_body.credential().epoch_id())
         .await?;
-    let mut vote_yes = verify_credential_body
-        .credential()
-        .verify(&verification_key);
+    // under load, the credential is going to be verified alongside the ones from other requests
+    let mut vote_yes = state
+        .credential_verifier
+        .verify(
+            verify_credential_body.credential().clone(),
+            verification_key,
+        )
+        .await?;
 
     vote_yes &= Coin::from(proposed_release_funds)
         == Coin::new(
@@ -286,6 +348,10 @@ pub async fn verify_bandwidth_credential(
             state.mix_denom.clone(),
         );
 
+    // the contract only checked the expiry date it was told about, so make sure it's the one
+    // the credential is actually bound to, as otherwise it could be pruned while still spendable
+    vote_yes &= spent_credential.expiry_date() == verify_credential_body.credential().expiry_date();
+
     // Vote yes or no on the proposal based on the verification result
     let ret = state
         .client
@@ -18,7 +18,7 @@ use nym_coconut_bandwidth_contract_common::spend_credential::{
 };
 use nym_coconut_interface::{hash_to_scalar, Credential, VerificationKey};
 use nym_config::defaults::VOUCHER_INFO;
-use nym_credentials::coconut::bandwidth::BandwidthVoucher;
+use nym_credentials::coconut::bandwidth::{expiry_date_for, BandwidthVoucher, TOTAL_ATTRIBUTES};
 use nym_credentials::coconut::params::{
     NymApiCredentialEncryptionAlgorithm, NymApiCredentialHkdfAlgorithm,
 };
@@ -35,6 +35,7 @@ use crate::support::storage::NymApiStorage;
 use async_trait::async_trait;
 use cw3::ProposalResponse;
 use cw4::MemberResponse;
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason, DisqualifiedDealer};
 use nym_coconut_dkg_common::dealer::{
     ContractDealing, DealerDetails, DealerDetailsResponse, DealerType,
 };
@@ -71,6 +72,7 @@ pub(crate) struct DummyClient {
     threshold: Arc<RwLock<Option<Threshold>>>,
     dealings: Arc<RwLock<HashMap<String, Vec<ContractSafeBytes>>>>,
     verification_share: Arc<RwLock<HashMap<String, ContractVKShare>>>,
+    complaints: Arc<RwLock<HashMap<String, Vec<Complaint>>>>,
     group_db: Arc<RwLock<HashMap<String, MemberResponse>>>,
     initial_dealers_db: Arc<RwLock<Option<InitialReplacementData>>>,
 }
@@ -87,6 +89,7 @@ impl DummyClient {
             threshold: Arc::new(RwLock::new(None)),
             dealings: Arc::new(RwLock::new(HashMap::new())),
             verification_share: Arc::new(RwLock::new(HashMap::new())),
+            complaints: Arc::new(RwLock::new(HashMap::new())),
             group_db: Arc::new(RwLock::new(HashMap::new())),
             initial_dealers_db: Arc::new(RwLock::new(None)),
         }
@@ -147,6 +150,14 @@ impl DummyClient {
         self
     }
 
+    pub fn with_complaints(
+        mut self,
+        complaints: &Arc<RwLock<HashMap<String, Vec<Complaint>>>>,
+    ) -> Self {
+        self.complaints = Arc::clone(complaints);
+        self
+    }
+
     pub fn _with_group_db(
         mut self,
         group_db: &Arc<RwLock<HashMap<String, MemberResponse>>>,
@@ -290,6 +301,36 @@ impl super::client::Client for DummyClient {
             .collect())
     }
 
+    async fn get_complaints(&self, _epoch_id: EpochId, dealer: String) -> Result<Vec<Complaint>> {
+        Ok(self
+            .complaints
+            .read()
+            .unwrap()
+            .get(&dealer)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_disqualified_dealers(&self, epoch_id: EpochId) -> Result<Vec<DisqualifiedDealer>> {
+        let threshold = match *self.threshold.read().unwrap() {
+            Some(threshold) => threshold,
+            None => return Ok(vec![]),
+        };
+        Ok(self
+            .complaints
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, complaints)| complaints.len() as u64 >= threshold)
+            .map(|(dealer, complaints)| DisqualifiedDealer {
+                dealer: Addr::unchecked(dealer),
+                epoch_id,
+                complaints: complaints.len() as u32,
+                reason: complaints[0].reason,
+            })
+            .collect())
+    }
+
     async fn vote_proposal(
         &self,
         proposal_id: u64,
@@ -394,6 +435,33 @@ impl super::client::Client for DummyClient {
         })
     }
 
+    async fn submit_complaint(
+        &self,
+        dealer: String,
+        reason: ComplaintReason,
+        _resharing: bool,
+    ) -> Result<ExecuteResult> {
+        let complaint = Complaint {
+            complainer: Addr::unchecked(self.validator_address.to_string()),
+            dealer: Addr::unchecked(&dealer),
+            epoch_id: self.epoch.read().unwrap().epoch_id,
+            reason,
+        };
+        self.complaints
+            .write()
+            .unwrap()
+            .entry(dealer)
+            .or_default()
+            .push(complaint);
+
+        Ok(ExecuteResult {
+            logs: vec![],
+            data: Default::default(),
+            transaction_hash: Hash::new([0; 32]),
+            gas_info: Default::default(),
+        })
+    }
+
     async fn submit_verification_key_share(
         &self,
         share: VerificationKeyShare,
@@ -481,6 +549,13 @@ impl super::comm::APICommunicationChannel for DummyCommunicationChannel {
     }
 }
 
+fn current_unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 pub fn tx_entry_fixture(tx_hash: &str) -> TxResponse {
     TxResponse {
         hash: Hash::from_str(tx_hash).unwrap(),
@@ -510,12 +585,13 @@ async fn signed_before() {
         "2DHbEZ6pzToGpsAXJrqJi7Wj1pAXeT18283q2YEEyNH5gTymwRozWBdja6SMAVt1dyYmUnM4ZNhsJ4wxZyGh4Z6J",
     );
 
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
     let mut rng = OsRng;
     let voucher = BandwidthVoucher::new(
         &params,
         "1234".to_string(),
         VOUCHER_INFO.to_string(),
+        expiry_date_for(current_unix_timestamp()),
         tx_hash,
         identity::PrivateKey::from_base58_string(
             identity::KeyPair::new(&mut rng)
@@ -568,7 +644,7 @@ async fn signed_before() {
         signature.clone(),
         &voucher.get_public_attributes(),
         voucher.get_public_attributes_plain(),
-        4,
+        TOTAL_ATTRIBUTES,
     );
 
     let encrypted_signature = vec![1, 2, 3, 4];
@@ -577,6 +653,8 @@ async fn signed_before() {
     storage
         .insert_blinded_signature_response(
             &tx_hash.to_string(),
+            &signature,
+            "1234",
             &expected_response.to_base58_string(),
         )
         .await
@@ -610,7 +688,7 @@ async fn signed_before() {
 async fn state_functions() {
     let nyxd_client =
         DummyClient::new(AccountId::from_str(TEST_REWARDING_VALIDATOR_ADDRESS).unwrap());
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
     let key_pair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
     let mut db_dir = std::env::temp_dir();
     db_dir.push(&key_pair.verification_key().to_bs58()[..8]);
@@ -627,18 +705,35 @@ async fn state_functions() {
     );
 
     let tx_hash = String::from("6B27412050B823E58BB38447D7870BBC8CBE3C51C905BEA89D459ACCDA80A00E");
-    assert!(state.signed_before(&tx_hash).await.unwrap().is_none());
+    let request_signature = String::from("first request signature");
+    assert!(state
+        .signed_before(&tx_hash, &request_signature)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(state.issued_value(&tx_hash, 1000).await.unwrap(), 0);
 
     let encrypted_signature = vec![1, 2, 3, 4];
     let remote_key = [42; 32];
     let expected_response = BlindedSignatureResponse::new(encrypted_signature, remote_key);
     storage
-        .insert_blinded_signature_response(&tx_hash, &expected_response.to_base58_string())
+        .insert_blinded_signature_response(
+            &tx_hash,
+            &request_signature,
+            "400",
+            &expected_response.to_base58_string(),
+        )
         .await
         .unwrap();
+    assert_eq!(state.issued_value(&tx_hash, 1000).await.unwrap(), 400);
+    assert!(state
+        .signed_before(&tx_hash, "second request signature")
+        .await
+        .unwrap()
+        .is_none());
     assert_eq!(
         state
-            .signed_before(&tx_hash)
+            .signed_before(&tx_hash, &request_signature)
             .await
             .unwrap()
             .unwrap()
@@ -655,11 +750,13 @@ async fn state_functions() {
         222, 119, 93, 146, 116, 229, 0, 152, 51, 232, 2, 102, 204, 147, 202, 254, 243,
     ])
     .unwrap();
-    // Check that the new payload is not stored if there was already something signed for tx_hash
+    // Check that the new payload is not stored if there was already something signed for the request
     assert_eq!(
         state
             .encrypt_and_store(
                 &tx_hash,
+                &request_signature,
+                "400",
                 encryption_keypair.public_key(),
                 &blinded_signature,
             )
@@ -674,6 +771,8 @@ async fn state_functions() {
     let response = state
         .encrypt_and_store(
             &tx_hash,
+            &request_signature,
+            "1000",
             encryption_keypair.public_key(),
             &blinded_signature,
         )
@@ -712,12 +811,19 @@ async fn state_functions() {
     .unwrap();
     assert_eq!(
         state
-            .encrypt_and_store(&tx_hash, encryption_keypair.public_key(), &other_signature,)
+            .encrypt_and_store(
+                &tx_hash,
+                &request_signature,
+                "1000",
+                encryption_keypair.public_key(),
+                &other_signature,
+            )
             .await
             .unwrap()
             .to_bytes(),
         response.to_bytes()
     );
+    assert_eq!(state.issued_value(&tx_hash, 1000).await.unwrap(), 1000);
 }
 
 #[tokio::test]
@@ -725,7 +831,7 @@ async fn blind_sign_correct() {
     let tx_hash =
         Hash::from_str("7C41AF8266D91DE55E1C8F4712E6A952A165ED3D8C27C7B00428CBD0DE00A52B").unwrap();
 
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
     let mut rng = OsRng;
     let identity_keypair = identity::KeyPair::new(&mut rng);
     let encryption_keypair = encryption::KeyPair::new(&mut rng);
@@ -733,6 +839,7 @@ async fn blind_sign_correct() {
         &params,
         "1234".to_string(),
         VOUCHER_INFO.to_string(),
+        expiry_date_for(current_unix_timestamp()),
         tx_hash,
         identity::PrivateKey::from_base58_string(identity_keypair.private_key().to_base58_string())
             .unwrap(),
@@ -806,7 +913,7 @@ async fn blind_sign_correct() {
             .to_base58_string(),
         &voucher.get_public_attributes(),
         voucher.get_public_attributes_plain(),
-        4,
+        TOTAL_ATTRIBUTES,
     );
 
     let response = client
@@ -835,13 +942,15 @@ async fn verification_of_bandwidth_credential() {
         .with_proposal_db(&proposal_db)
         .with_spent_credential_db(&spent_credential_db);
     let mut db_dir = std::env::temp_dir();
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
     let mut key_pairs = ttp_keygen(&params, 1, 1).unwrap();
     let voucher_value = 1234u64;
     let voucher_info = "voucher info";
+    let expiry_date = expiry_date_for(current_unix_timestamp());
     let public_attributes = vec![
         hash_to_scalar(voucher_value.to_string()),
         hash_to_scalar(voucher_info),
+        hash_to_scalar(expiry_date.to_string()),
     ];
     let indices: Vec<u64> = key_pairs
         .iter()
@@ -868,7 +977,14 @@ async fn verification_of_bandwidth_credential() {
         .await
         .expect("valid rocket instance");
 
-    let credential = Credential::new(4, theta.clone(), voucher_value, voucher_info.to_string(), 0);
+    let credential = Credential::new(
+        TOTAL_ATTRIBUTES,
+        theta.clone(),
+        voucher_value,
+        voucher_info.to_string(),
+        expiry_date,
+        0,
+    );
     let proposal_id = 42;
     // The address is not used, so we can use a duplicate
     let gateway_cosmos_addr = validator_address.clone();
@@ -1009,16 +1125,18 @@ async fn verification_of_bandwidth_credential() {
         funds.clone().into(),
         credential.blinded_serial_number(),
         Addr::unchecked("unimportant"),
+        expiry_date,
     );
     spent_credential_db.write().unwrap().insert(
         credential.blinded_serial_number(),
         SpendCredentialResponse::new(Some(spent_credential.clone())),
     );
     let bad_credential = Credential::new(
-        4,
+        TOTAL_ATTRIBUTES,
         theta.clone(),
         voucher_value,
         String::from("bad voucher info"),
+        expiry_date,
         0,
     );
     let bad_req =
@@ -1085,6 +1203,50 @@ async fn verification_of_bandwidth_credential() {
             .status
     );
 
+    // Test the endpoint with the credential recorded in the contract with a different expiry date
+    // than the one the credential is bound to
+    let funds = Coin::new(voucher_value as u128, TEST_COIN_DENOM);
+    let msg = nym_coconut_bandwidth_contract_common::msg::ExecuteMsg::ReleaseFunds {
+        funds: funds.clone().into(),
+    };
+    let cosmos_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: String::new(),
+        msg: to_binary(&msg).unwrap(),
+        funds: vec![],
+    });
+    proposal.msgs = vec![cosmos_msg];
+    proposal_db
+        .write()
+        .unwrap()
+        .insert(proposal_id, proposal.clone());
+    let forged_spent_credential = SpendCredential::new(
+        funds.clone().into(),
+        credential.blinded_serial_number(),
+        Addr::unchecked("unimportant"),
+        expiry_date + 1,
+    );
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        SpendCredentialResponse::new(Some(forged_spent_credential)),
+    );
+    let response = client
+        .post(format!(
+            "/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_VERIFY_BANDWIDTH_CREDENTIAL
+        ))
+        .json(&req)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let verify_credential_response =
+        serde_json::from_str::<VerifyCredentialResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert!(!verify_credential_response.verification_result);
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        SpendCredentialResponse::new(Some(spent_credential.clone())),
+    );
+
     // Test the endpoint with every dependency met
     let funds = Coin::new(voucher_value as u128, TEST_COIN_DENOM);
     let msg = nym_coconut_bandwidth_contract_common::msg::ExecuteMsg::ReleaseFunds {
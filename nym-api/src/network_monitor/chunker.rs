@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use nym_sphinx::forwarding::packet::MixPacket;
-use nym_sphinx::message::NymMessage;
+use nym_sphinx::message::{MessageCompression, MessageFraming, NymMessage};
 use nym_sphinx::params::PacketSize;
 use nym_sphinx::{
     acknowledgements::AckKey, addressing::clients::Recipient, preparer::MessagePreparer,
@@ -59,9 +59,12 @@ impl Chunker {
     ) -> Vec<MixPacket> {
         let ack_key: AckKey = AckKey::new(&mut self.rng);
 
-        let split_message = self
-            .message_preparer
-            .pad_and_split_message(NymMessage::new_plain(message), self.packet_size);
+        let split_message = self.message_preparer.pad_and_split_message(
+            NymMessage::new_plain(message),
+            self.packet_size,
+            MessageCompression::None,
+            MessageFraming::Standalone,
+        );
 
         let mut mix_packets = Vec::with_capacity(split_message.len());
         for message_chunk in split_message {
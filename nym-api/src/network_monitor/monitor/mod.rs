@@ -9,6 +9,7 @@ use crate::network_monitor::test_packet::TestPacket;
 use crate::network_monitor::test_route::TestRoute;
 use crate::storage::NymApiStorage;
 use crate::support::config::Config;
+use crate::support::metrics;
 use log::{debug, error, info};
 use nym_sphinx::receiver::MessageReceiver;
 use nym_task::TaskClient;
@@ -260,6 +261,8 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         let total_received = received.len();
         info!("Test routes: {:?}", routes);
         info!("Received {}/{} packets", total_received, total_sent);
+        metrics::NETWORK_MONITOR_PACKETS_SENT.inc_by(total_sent as u64);
+        metrics::NETWORK_MONITOR_PACKETS_RECEIVED.inc_by(total_received as u64);
 
         let summary = self.summary_producer.produce_summary(
             prepared_packets.tested_mixnodes,
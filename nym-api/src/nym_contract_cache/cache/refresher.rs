@@ -1,6 +1,7 @@
 use super::NymContractCache;
 use crate::nyxd::Client;
 use crate::support::caching::CacheNotification;
+use crate::support::metrics;
 use anyhow::Result;
 use nym_mixnet_contract_common::{MixId, MixNodeDetails, RewardedSetNodeStatus};
 use nym_task::TaskClient;
@@ -114,6 +115,7 @@ impl NymContractCacheRefresher {
                         }
                         ret = self.refresh() => {
                             if let Err(err) = ret {
+                                metrics::CONTRACT_CACHE_REFRESH_FAILURES.inc();
                                 error!("Failed to refresh validator cache - {err}");
                             } else {
                                 // relaxed memory ordering is fine here. worst case scenario network monitor
@@ -0,0 +1,12 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rocket::http::ContentType;
+
+/// Returns the metrics of the nym-api in the Prometheus text format.
+#[get("/metrics")]
+pub(crate) fn metrics() -> (ContentType, String) {
+    let content_type = ContentType::parse_flexible(&nym_metrics::metrics_content_type())
+        .unwrap_or(ContentType::Plain);
+    (content_type, nym_metrics::encode_metrics())
+}
@@ -15,6 +15,7 @@ use rocket_cors::{AllowedHeaders, AllowedOrigins, Cors};
 use rocket_okapi::mount_endpoints_and_merged_docs;
 use rocket_okapi::swagger_ui::make_swagger_ui;
 
+pub(crate) mod metrics;
 pub(crate) mod openapi;
 
 pub(crate) async fn setup_rocket(
@@ -38,6 +39,7 @@ pub(crate) async fn setup_rocket(
 
     let rocket = rocket
         .mount("/swagger", make_swagger_ui(&openapi::get_docs()))
+        .mount("/", routes![metrics::metrics])
         .attach(setup_cors()?)
         .attach(NymContractCache::stage())
         .attach(NodeStatusCache::stage())
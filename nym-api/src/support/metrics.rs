@@ -0,0 +1,20 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use lazy_static::lazy_static;
+use nym_metrics::{register_int_counter, IntCounter};
+
+lazy_static! {
+    pub(crate) static ref NETWORK_MONITOR_PACKETS_SENT: IntCounter = register_int_counter(
+        "api_network_monitor_packets_sent_total",
+        "Number of test packets sent by the network monitor"
+    );
+    pub(crate) static ref NETWORK_MONITOR_PACKETS_RECEIVED: IntCounter = register_int_counter(
+        "api_network_monitor_packets_received_total",
+        "Number of test packets that made it back to the network monitor"
+    );
+    pub(crate) static ref CONTRACT_CACHE_REFRESH_FAILURES: IntCounter = register_int_counter(
+        "api_contract_cache_refresh_failures_total",
+        "Number of failed attempts to refresh the cached contract data, including the network topology"
+    );
+}
@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use cw3::ProposalResponse;
 use cw4::MemberResponse;
 use nym_coconut_bandwidth_contract_common::spend_credential::SpendCredentialResponse;
+use nym_coconut_dkg_common::complaint::{Complaint, ComplaintReason, DisqualifiedDealer};
 use nym_coconut_dkg_common::msg::QueryMsg as DkgQueryMsg;
 use nym_coconut_dkg_common::types::InitialReplacementData;
 use nym_coconut_dkg_common::{
@@ -409,6 +410,31 @@ impl crate::coconut::client::Client for Client {
             .await?)
     }
 
+    async fn get_complaints(
+        &self,
+        epoch_id: EpochId,
+        dealer: String,
+    ) -> crate::coconut::error::Result<Vec<Complaint>> {
+        Ok(self
+            .0
+            .read()
+            .await
+            .get_all_complaints(epoch_id, dealer)
+            .await?)
+    }
+
+    async fn get_disqualified_dealers(
+        &self,
+        epoch_id: EpochId,
+    ) -> crate::coconut::error::Result<Vec<DisqualifiedDealer>> {
+        Ok(self
+            .0
+            .read()
+            .await
+            .get_all_disqualified_dealers(epoch_id)
+            .await?)
+    }
+
     async fn vote_proposal(
         &self,
         proposal_id: u64,
@@ -473,6 +499,21 @@ impl crate::coconut::client::Client for Client {
             .await?)
     }
 
+    async fn submit_complaint(
+        &self,
+        dealer: String,
+        reason: ComplaintReason,
+        resharing: bool,
+    ) -> crate::coconut::error::Result<ExecuteResult> {
+        Ok(self
+            .0
+            .write()
+            .await
+            .nyxd
+            .submit_complaint(dealer, reason, resharing, None)
+            .await?)
+    }
+
     async fn submit_verification_key_share(
         &self,
         share: VerificationKeyShare,
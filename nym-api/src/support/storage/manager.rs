@@ -973,20 +973,27 @@ impl StorageManager {
         Ok(active_day_statuses)
     }
 
-    /// Creates new encrypted blinded signature response entry for a given deposit tx hash.
+    /// Creates new encrypted blinded signature response entry for a voucher issued for
+    /// a given deposit tx hash.
     ///
     /// # Arguments
     ///
     /// * `tx_hash`: hash of the deposit transaction.
+    /// * `request_signature`: signature on the blind sign request of the voucher.
+    /// * `voucher_value`: the value of the signed voucher.
     /// * `blinded_signature_response`: the encrypted blinded signature response.
     pub(crate) async fn insert_blinded_signature_response(
         &self,
         tx_hash: &str,
+        request_signature: &str,
+        voucher_value: &str,
         blinded_signature_response: &str,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "INSERT INTO signed_deposit(tx_hash, blinded_signature_response) VALUES (?, ?)",
+            "INSERT INTO signed_deposit(tx_hash, request_signature, voucher_value, blinded_signature_response) VALUES (?, ?, ?, ?)",
             tx_hash,
+            request_signature,
+            voucher_value,
             blinded_signature_response
         )
         .execute(&self.connection_pool)
@@ -994,18 +1001,24 @@ impl StorageManager {
         Ok(())
     }
 
-    /// Tries to obtain encrypted blinded signature response for a given transaction hash.
+    /// Tries to obtain encrypted blinded signature response for a given voucher request
+    /// of the deposit transaction.
     ///
     /// # Arguments
     ///
     /// * `tx_hash`: transaction hash of the deposit.
+    /// * `request_signature`: signature on the blind sign request of the voucher.
     pub(crate) async fn get_blinded_signature_response(
         &self,
         tx_hash: &str,
+        request_signature: &str,
     ) -> Result<Option<String>, sqlx::Error> {
+        // deposits signed before they could be split have no request signature stored,
+        // but they could have only ever been signed as a whole anyway
         let blinded_signature_response = sqlx::query!(
-            "SELECT blinded_signature_response FROM signed_deposit WHERE tx_hash = ?",
-            tx_hash
+            "SELECT blinded_signature_response FROM signed_deposit WHERE tx_hash = ? AND (request_signature = ? OR request_signature = '')",
+            tx_hash,
+            request_signature
         )
         .fetch_optional(&self.connection_pool)
         .await?
@@ -1013,4 +1026,27 @@ impl StorageManager {
 
         Ok(blinded_signature_response)
     }
+
+    /// Gets values of all the vouchers signed so far for a given deposit transaction.
+    /// `None` indicates the deposit has been signed as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash`: transaction hash of the deposit.
+    pub(crate) async fn get_signed_voucher_values(
+        &self,
+        tx_hash: &str,
+    ) -> Result<Vec<Option<String>>, sqlx::Error> {
+        let values = sqlx::query!(
+            "SELECT voucher_value FROM signed_deposit WHERE tx_hash = ?",
+            tx_hash
+        )
+        .fetch_all(&self.connection_pool)
+        .await?
+        .into_iter()
+        .map(|row| row.voucher_value)
+        .collect();
+
+        Ok(values)
+    }
 }
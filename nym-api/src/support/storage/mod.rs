@@ -720,9 +720,10 @@ impl NymApiStorage {
     pub(crate) async fn get_blinded_signature_response(
         &self,
         tx_hash: &str,
+        request_signature: &str,
     ) -> Result<Option<String>, NymApiStorageError> {
         self.manager
-            .get_blinded_signature_response(tx_hash)
+            .get_blinded_signature_response(tx_hash, request_signature)
             .await
             .map_err(|err| err.into())
     }
@@ -730,10 +731,27 @@ impl NymApiStorage {
     pub(crate) async fn insert_blinded_signature_response(
         &self,
         tx_hash: &str,
+        request_signature: &str,
+        voucher_value: &str,
         blinded_signature_response: &str,
     ) -> Result<(), NymApiStorageError> {
         self.manager
-            .insert_blinded_signature_response(tx_hash, blinded_signature_response)
+            .insert_blinded_signature_response(
+                tx_hash,
+                request_signature,
+                voucher_value,
+                blinded_signature_response,
+            )
+            .await
+            .map_err(|err| err.into())
+    }
+
+    pub(crate) async fn get_signed_voucher_values(
+        &self,
+        tx_hash: &str,
+    ) -> Result<Vec<Option<String>>, NymApiStorageError> {
+        self.manager
+            .get_signed_voucher_values(tx_hash)
             .await
             .map_err(|err| err.into())
     }
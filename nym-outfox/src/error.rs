@@ -19,6 +19,10 @@ pub enum OutfoxError {
     InvalidKeyLength,
     #[error("Message length must be greater then {MIN_MESSAGE_LEN} bytes")]
     InvalidMessageLength,
+    #[error("Route must consist of between 1 and {max} hops, got {got}")]
+    InvalidRouteLength { max: usize, got: usize },
+    #[error("Packet of {len} bytes is too short to contain {layers} layers")]
+    MalformedPacket { len: usize, layers: usize },
     #[error("{source}")]
     TryFromSluce {
         #[from]
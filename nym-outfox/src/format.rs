@@ -66,8 +66,8 @@ use sphinx_packet::route::Node;
 
 use std::convert::TryInto;
 
-const GROUPELEMENTBYTES: usize = 32;
-const TAGBYTES: usize = 16;
+pub const GROUPELEMENTBYTES: usize = 32;
+pub const TAGBYTES: usize = 16;
 
 use std::ops::Range;
 use std::u8;
@@ -186,8 +186,24 @@ impl MixStageParameters {
         user_secret_key: &[u8],
         node: &Node,
     ) -> Result<MontgomeryPoint, OutfoxError> {
-        let routing_data = node.address.as_bytes().to_vec();
-        let mix_public_key = MontgomeryPoint(*node.pub_key.as_bytes());
+        self.encode_mix_layer_with_routing_data(
+            buffer,
+            user_secret_key,
+            node.pub_key.as_bytes(),
+            node.address.as_bytes_ref(),
+        )
+    }
+
+    /// Encodes a layer of mixing for the mix identified by `mix_public_key`, such that
+    /// decoding it reveals the provided `routing_data`, i.e. the address of the next hop.
+    pub fn encode_mix_layer_with_routing_data(
+        &self,
+        buffer: &mut [u8],
+        user_secret_key: &[u8],
+        mix_public_key: &[u8; 32],
+        routing_data: &[u8],
+    ) -> Result<MontgomeryPoint, OutfoxError> {
+        let mix_public_key = MontgomeryPoint(*mix_public_key);
         let user_secret_key = Scalar::from_bytes_mod_order(user_secret_key.try_into()?);
 
         if buffer.len() != self.incoming_packet_length() {
@@ -208,7 +224,7 @@ impl MixStageParameters {
         let shared_key = user_secret_key * mix_public_key;
 
         // Copy rounting data into buffer
-        buffer[self.routing_data_range()].copy_from_slice(&routing_data);
+        buffer[self.routing_data_range()].copy_from_slice(routing_data);
 
         // Perform the AEAD
         let header_aead_key = ChaCha20Poly1305::new_from_slice(&shared_key.0[..])?;
@@ -1,11 +1,15 @@
+use std::convert::{TryFrom, TryInto};
 use std::ops::Range;
 
 use crate::{
     error::OutfoxError,
-    format::{MixCreationParameters, MixStageParameters},
+    format::{MixCreationParameters, MixStageParameters, GROUPELEMENTBYTES, TAGBYTES},
+    lion::MIN_MESSAGE_LEN,
 };
 
+use rand_core::{CryptoRng, RngCore};
 use sphinx_packet::{packet::builder::DEFAULT_PAYLOAD_SIZE, route::Node};
+use zeroize::Zeroize;
 
 pub struct OutfoxPacket {
     mix_params: MixCreationParameters,
@@ -14,6 +18,19 @@ pub struct OutfoxPacket {
 
 pub const DEFAULT_ROUTING_INFO_SIZE: usize = 32;
 
+/// Number of bytes added to the packet by every layer of encoding.
+pub const LAYER_OVERHEAD: usize = GROUPELEMENTBYTES + TAGBYTES + DEFAULT_ROUTING_INFO_SIZE;
+
+/// A single hop on the route of the packet.
+pub struct OutfoxHop {
+    /// Public key of the mix that is going to decode this layer.
+    pub public_key: [u8; 32],
+
+    /// Routing information revealed to the mix, i.e. the address of the next hop
+    /// or of the final destination.
+    pub routing_information: [u8; DEFAULT_ROUTING_INFO_SIZE],
+}
+
 impl OutfoxPacket {
     pub fn build(
         payload: &[u8],
@@ -41,6 +58,59 @@ impl OutfoxPacket {
         })
     }
 
+    /// Builds a packet carrying exactly the provided payload through the specified route.
+    /// Every layer is encoded with a fresh ephemeral key.
+    pub fn build_for_route<R>(
+        rng: &mut R,
+        payload: &[u8],
+        route: &[OutfoxHop],
+    ) -> Result<OutfoxPacket, OutfoxError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        if route.is_empty() || route.len() > u8::MAX as usize {
+            return Err(OutfoxError::InvalidRouteLength {
+                max: u8::MAX as usize,
+                got: route.len(),
+            });
+        }
+
+        if payload.len() < MIN_MESSAGE_LEN {
+            return Err(OutfoxError::InvalidMessageLength);
+        }
+
+        let mut mix_params = MixCreationParameters::new(payload.len());
+        for _ in route {
+            mix_params.add_outer_layer(DEFAULT_ROUTING_INFO_SIZE);
+        }
+
+        let mut buffer = vec![0; mix_params.total_packet_length() - payload.len()];
+        buffer.extend_from_slice(payload);
+
+        let mut user_secret_key = [0u8; 32];
+        for (idx, hop) in route.iter().rev().enumerate() {
+            rng.fill_bytes(&mut user_secret_key);
+            let (range, stage_params) = mix_params.get_stage_params(idx);
+            stage_params.encode_mix_layer_with_routing_data(
+                &mut buffer[range],
+                &user_secret_key,
+                &hop.public_key,
+                &hop.routing_information,
+            )?;
+        }
+        user_secret_key.zeroize();
+
+        Ok(OutfoxPacket {
+            mix_params,
+            payload: buffer,
+        })
+    }
+
+    /// The length of a serialized packet with the given payload and number of layers.
+    pub const fn expected_serialized_len(payload_length: usize, layers: usize) -> usize {
+        1 + layers * LAYER_OVERHEAD + payload_length
+    }
+
     pub fn stage_params(&self, layer_number: usize) -> (Range<usize>, MixStageParameters) {
         self.mix_params().get_stage_params(layer_number)
     }
@@ -49,6 +119,11 @@ impl OutfoxPacket {
         &self.mix_params
     }
 
+    /// The number of layers that are yet to be decoded.
+    pub fn layers(&self) -> usize {
+        self.mix_params.routing_information_length_by_stage.len()
+    }
+
     pub fn payload(&self) -> &[u8] {
         &self.payload
     }
@@ -61,6 +136,14 @@ impl OutfoxPacket {
         &mut self.payload
     }
 
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+
+    pub fn serialized_len(&self) -> usize {
+        1 + self.payload.len()
+    }
+
     pub fn decode_mix_layer(
         &mut self,
         layer: usize,
@@ -70,4 +153,100 @@ impl OutfoxPacket {
         params.decode_mix_layer(&mut self.payload_mut()[range], mix_secret_key)?;
         Ok(())
     }
+
+    /// Decodes the outermost layer of the packet and strips it, so that the remainder
+    /// could be forwarded to the hop indicated by the revealed routing information.
+    pub fn decode_next_layer(
+        mut self,
+        mix_secret_key: &[u8; 32],
+    ) -> Result<OutfoxProcessedPacket, OutfoxError> {
+        let layers = self.layers();
+        if layers == 0 {
+            return Err(OutfoxError::MalformedPacket {
+                len: self.serialized_len(),
+                layers,
+            });
+        }
+
+        let (range, params) = self.stage_params(layers - 1);
+        let shared_secret = params.decode_mix_layer(&mut self.payload[range], mix_secret_key)?;
+        let routing_information = self.payload[params.routing_data_range()].try_into()?;
+
+        let remaining = self.payload.split_off(params.routing_data_range().end);
+        self.mix_params.routing_information_length_by_stage.pop();
+
+        Ok(OutfoxProcessedPacket {
+            packet: OutfoxPacket {
+                mix_params: self.mix_params,
+                payload: remaining,
+            },
+            routing_information,
+            shared_secret: shared_secret.0,
+        })
+    }
+
+    // the packet is serialized as follows:
+    // LAYERS || ENCODED_LAYERS || PAYLOAD
+    pub fn to_bytes(&self) -> Vec<u8> {
+        std::iter::once(self.layers() as u8)
+            .chain(self.payload.iter().copied())
+            .collect()
+    }
+}
+
+impl TryFrom<&[u8]> for OutfoxPacket {
+    type Error = OutfoxError;
+
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        let (layers, packet) = match b.split_first() {
+            Some((layers, packet)) => (*layers as usize, packet),
+            None => return Err(OutfoxError::MalformedPacket { len: 0, layers: 0 }),
+        };
+
+        let header_length = layers * LAYER_OVERHEAD;
+        if layers == 0 || packet.len() < header_length + MIN_MESSAGE_LEN {
+            return Err(OutfoxError::MalformedPacket {
+                len: b.len(),
+                layers,
+            });
+        }
+
+        let mut mix_params = MixCreationParameters::new(packet.len() - header_length);
+        for _ in 0..layers {
+            mix_params.add_outer_layer(DEFAULT_ROUTING_INFO_SIZE);
+        }
+
+        Ok(OutfoxPacket {
+            mix_params,
+            payload: packet.to_vec(),
+        })
+    }
+}
+
+/// Result of decoding a single layer of an [`OutfoxPacket`].
+pub struct OutfoxProcessedPacket {
+    packet: OutfoxPacket,
+    routing_information: [u8; DEFAULT_ROUTING_INFO_SIZE],
+    shared_secret: [u8; 32],
+}
+
+impl OutfoxProcessedPacket {
+    /// Address of the next hop or, if this was the final layer, of the destination.
+    pub fn routing_information(&self) -> &[u8; DEFAULT_ROUTING_INFO_SIZE] {
+        &self.routing_information
+    }
+
+    /// Secret shared between the sender and this mix. It is unique for every packet at every hop.
+    pub fn shared_secret(&self) -> &[u8; 32] {
+        &self.shared_secret
+    }
+
+    /// Whether all layers have been decoded, in which case the packet only contains the plaintext payload.
+    pub fn is_final_hop(&self) -> bool {
+        self.packet.layers() == 0
+    }
+
+    pub fn into_packet(self) -> OutfoxPacket {
+        self.packet
+    }
 }
@@ -5,13 +5,13 @@ mod tests {
 
     use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
     use curve25519_dalek::scalar::Scalar;
-    use nym_outfox::packet::OutfoxPacket;
+    use nym_outfox::packet::{OutfoxHop, OutfoxPacket, DEFAULT_ROUTING_INFO_SIZE};
     use sphinx_packet::constants::NODE_ADDRESS_LENGTH;
     use sphinx_packet::crypto::PublicKey;
     use sphinx_packet::packet::builder::DEFAULT_PAYLOAD_SIZE;
     use sphinx_packet::route::Node;
     use sphinx_packet::route::NodeAddressBytes;
-    use std::convert::TryInto;
+    use std::convert::{TryFrom, TryInto};
 
     use nym_outfox::format::*;
     use nym_outfox::lion::*;
@@ -111,4 +111,71 @@ mod tests {
 
         assert_eq!(payload, &packet.payload()[packet.payload_range()]);
     }
+
+    #[test]
+    fn test_routed_packet() {
+        let mut rng = rand::rngs::OsRng;
+
+        let keys = (0..4)
+            .map(|_| sphinx_packet::crypto::keygen())
+            .collect::<Vec<_>>();
+        let destination = [42u8; DEFAULT_ROUTING_INFO_SIZE];
+        let route = keys
+            .iter()
+            .enumerate()
+            .map(|(i, (_, public_key))| OutfoxHop {
+                public_key: *public_key.as_bytes(),
+                routing_information: if i + 1 < keys.len() {
+                    [i as u8 + 1; DEFAULT_ROUTING_INFO_SIZE]
+                } else {
+                    destination
+                },
+            })
+            .collect::<Vec<_>>();
+
+        let payload = randombytes(1024);
+        let packet = OutfoxPacket::build_for_route(&mut rng, &payload, &route).unwrap();
+
+        let serialized = packet.to_bytes();
+        assert_eq!(
+            serialized.len(),
+            OutfoxPacket::expected_serialized_len(payload.len(), route.len())
+        );
+        let mut packet = OutfoxPacket::try_from(serialized.as_slice()).unwrap();
+
+        for (i, (private_key, _)) in keys.iter().enumerate() {
+            let processed = packet.decode_next_layer(&private_key.to_bytes()).unwrap();
+            assert_eq!(
+                processed.routing_information(),
+                &route[i].routing_information
+            );
+            assert_eq!(processed.is_final_hop(), i + 1 == keys.len());
+
+            // every hop forwards the serialized remainder of the packet
+            let remaining = processed.into_packet();
+            if remaining.layers() == 0 {
+                assert_eq!(payload, remaining.into_payload());
+                return;
+            }
+            packet = OutfoxPacket::try_from(remaining.to_bytes().as_slice()).unwrap();
+        }
+        unreachable!("the final hop should have revealed the payload")
+    }
+
+    #[test]
+    fn test_routed_packet_rejects_wrong_key() {
+        let mut rng = rand::rngs::OsRng;
+
+        let (_, public_key) = sphinx_packet::crypto::keygen();
+        let (other_private_key, _) = sphinx_packet::crypto::keygen();
+        let route = [OutfoxHop {
+            public_key: *public_key.as_bytes(),
+            routing_information: [0u8; DEFAULT_ROUTING_INFO_SIZE],
+        }];
+
+        let packet = OutfoxPacket::build_for_route(&mut rng, &randombytes(1024), &route).unwrap();
+        assert!(packet
+            .decode_next_layer(&other_private_key.to_bytes())
+            .is_err());
+    }
 }
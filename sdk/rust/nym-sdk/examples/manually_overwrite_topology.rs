@@ -31,6 +31,8 @@ async fn main() {
                 .unwrap(),
             layer: Layer::One,
             version: "1.1.0".to_string(),
+            total_stake: 0,
+            performance: None,
         }],
     );
     mixnodes.insert(
@@ -48,6 +50,8 @@ async fn main() {
                 .unwrap(),
             layer: Layer::Two,
             version: "1.1.0".to_string(),
+            total_stake: 0,
+            performance: None,
         }],
     );
     mixnodes.insert(
@@ -65,6 +69,8 @@ async fn main() {
                 .unwrap(),
             layer: Layer::Three,
             version: "1.1.0".to_string(),
+            total_stake: 0,
+            performance: None,
         }],
     );
 
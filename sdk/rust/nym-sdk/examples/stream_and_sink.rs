@@ -0,0 +1,38 @@
+use futures::{SinkExt, StreamExt};
+use nym_sdk::mixnet::{self, InputMessage};
+use nym_task::connections::TransmissionLane;
+
+#[tokio::main]
+async fn main() {
+    nym_bin_common::logging::setup_logging();
+
+    let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    let our_address = *client.nym_address();
+    println!("Our client nym address is: {our_address}");
+
+    // The sink applies backpressure based on the number of packets the client still has to send
+    // out, so it's safe to push as many messages as we want into it
+    let mut sink = client.sink();
+    tokio::spawn(async move {
+        for i in 0..10 {
+            let message = InputMessage::new_regular(
+                our_address,
+                format!("hello there #{i}").into_bytes(),
+                TransmissionLane::General,
+            );
+            if let Err(err) = sink.send(message).await {
+                println!("failed to send the message: {err}");
+                return;
+            }
+        }
+    });
+
+    // Received messages can be consumed one by one as a regular stream
+    println!("Waiting for messages");
+    let mut received = client.by_ref().take(10);
+    while let Some(msg) = received.next().await {
+        println!("Received: {}", String::from_utf8_lossy(&msg.message));
+    }
+
+    client.disconnect().await;
+}
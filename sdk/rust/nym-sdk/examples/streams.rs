@@ -0,0 +1,41 @@
+use nym_sdk::mixnet::{self, IncludedSurbs};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::main]
+async fn main() {
+    nym_bin_common::logging::setup_logging();
+
+    let mut server = mixnet::MixnetClient::connect_new().await.unwrap();
+    let server_address = *server.nym_address();
+    println!("Server nym address is: {server_address}");
+
+    // Echo everything back to whoever opened the stream
+    let mut listener = server.listen().unwrap();
+    tokio::spawn(async move {
+        while let Some(mut stream) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut received = Vec::new();
+                stream.read_to_end(&mut received).await.unwrap();
+                stream.write_all(&received).await.unwrap();
+                stream.shutdown().await.unwrap();
+            });
+        }
+    });
+
+    // The server never learns the address of the client, it responds using reply SURBs
+    let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    let mut stream = client
+        .open_stream(server_address, IncludedSurbs::default())
+        .await
+        .unwrap();
+
+    stream.write_all(b"hello there").await.unwrap();
+    stream.shutdown().await.unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+    println!("Received: {}", String::from_utf8_lossy(&response));
+
+    client.disconnect().await;
+    server.disconnect().await;
+}
@@ -72,6 +72,18 @@ pub enum Error {
     #[error("invalid mnemonic: {0}")]
     InvalidMnemonic(#[from] bip39::Error),
 
+    #[error("the client is no longer accepting any input messages")]
+    ClientInputClosed,
+
+    #[error("the stream multiplexer is no longer running")]
+    StreamMultiplexerStopped,
+
+    #[error("the message was not delivered: {0}")]
+    MessageNotDelivered(#[from] nym_client_core::client::delivery_tracker::DeliveryError),
+
+    #[error("the delivery of the message was not confirmed within {0:?}")]
+    DeliveryTimeout(std::time::Duration),
+
     #[error("failed to create reply storage backend: {source}")]
     StorageError {
         source: Box<dyn std::error::Error + Send + Sync>,
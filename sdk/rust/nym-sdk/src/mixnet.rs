@@ -36,26 +36,33 @@ mod connection_state;
 mod keys;
 mod native_client;
 mod paths;
+mod sink;
 mod socks5_client;
+mod stream;
 
 pub use client::{DisconnectedMixnetClient, IncludedSurbs, MixnetClientBuilder};
 pub use config::Config;
 pub use keys::{Keys, KeysArc};
-pub use native_client::MixnetClient;
 pub use native_client::MixnetClientSender;
+pub use native_client::{MixnetClient, DEFAULT_MAX_RETRANSMISSIONS};
 pub use nym_client_core::{
     client::{
+        delivery_tracker::{DeliveryConfirmation, DeliveryError},
         inbound_messages::InputMessage,
         replies::reply_storage::{fs_backend::Backend as ReplyStorage, Empty as EmptyReplyStorage},
     },
     config::GatewayEndpointConfig,
 };
 pub use nym_network_defaults::NymNetworkDetails;
+pub use nym_pemstore::Passphrase;
 pub use nym_socks5_client_core::config::Socks5;
 pub use nym_sphinx::{
     addressing::clients::{ClientIdentity, Recipient},
+    message::MessageCompression,
     receiver::ReconstructedMessage,
 };
 pub use nym_topology::{provider_trait::TopologyProvider, NymTopology};
 pub use paths::{GatewayKeyMode, KeyMode, StoragePaths};
+pub use sink::{MixnetMessageSink, DEFAULT_MAX_QUEUED_PACKETS};
 pub use socks5_client::Socks5MixnetClient;
+pub use stream::{MixnetStream, MixnetStreamListener};
@@ -1,6 +1,6 @@
 use futures::channel::mpsc;
 use futures::StreamExt;
-use std::{path::Path, sync::Arc};
+use std::{collections::VecDeque, path::Path, sync::Arc};
 use url::Url;
 
 use nym_bandwidth_controller::BandwidthController;
@@ -26,7 +26,8 @@ use nym_validator_client::nyxd::QueryNyxdClient;
 use nym_validator_client::Client;
 
 use crate::bandwidth::BandwidthAcquireClient;
-use crate::mixnet::native_client::MixnetClient;
+use crate::mixnet::native_client::{MixnetClient, ReceivedMessages};
+use crate::mixnet::sink::MixnetMessageSink;
 use crate::mixnet::socks5_client::Socks5MixnetClient;
 use crate::mixnet::Recipient;
 use crate::{Error, Result};
@@ -42,6 +43,7 @@ pub struct MixnetClientBuilder {
     storage_paths: Option<StoragePaths>,
     keys: Option<Keys>,
     gateway_config: Option<GatewayEndpointConfig>,
+    fallback_gateways: Vec<GatewayEndpointConfig>,
     socks5_config: Option<Socks5>,
     custom_topology_provider: Option<Box<dyn TopologyProvider>>,
 }
@@ -101,6 +103,14 @@ impl MixnetClientBuilder {
         self
     }
 
+    /// Use a ranked list of gateways to fail over to if the main gateway becomes unreachable.
+    /// If storage is enabled, previously derived keys of those gateways are going to be reused.
+    #[must_use]
+    pub fn fallback_gateways(mut self, fallback_gateways: Vec<GatewayEndpointConfig>) -> Self {
+        self.fallback_gateways = fallback_gateways;
+        self
+    }
+
     /// Configure the SOCKS5 mode.
     #[must_use]
     pub fn socks5_config(mut self, socks5_config: Socks5) -> Self {
@@ -138,6 +148,8 @@ impl MixnetClientBuilder {
             client.set_keys(keys);
         }
 
+        client.fallback_gateways = self.fallback_gateways;
+
         // If we have a gateway config, we can move the client into a registered state. This will
         // fail if no gateway key is set.
         if let Some(gateway_config) = self.gateway_config {
@@ -173,6 +185,9 @@ where
     /// connected to the mixnet.
     state: BuilderState,
 
+    /// Ranked list of gateways the client is going to fail over to.
+    fallback_gateways: Vec<GatewayEndpointConfig>,
+
     /// Controller of bandwidth credentials that the mixnet client can use to connect
     bandwidth_controller: BandwidthController<Client<QueryNyxdClient>, EphemeralStorage>,
 
@@ -259,6 +274,7 @@ where
             socks5_config,
             storage_paths: paths,
             state: BuilderState::New,
+            fallback_gateways: Vec::new(),
             reply_storage_backend,
             bandwidth_controller,
             custom_topology_provider,
@@ -452,6 +468,16 @@ where
         let nym_address =
             nym_client_core::init::get_client_address(&self.key_manager, &gateway_endpoint_config);
 
+        let key_pathfinder = self.storage_paths.map(ClientKeyPathfinder::from);
+        if let Some(key_pathfinder) = &key_pathfinder {
+            self.key_manager.load_fallback_gateway_keys(
+                key_pathfinder,
+                self.fallback_gateways
+                    .iter()
+                    .map(|gateway| gateway.gateway_id.as_str()),
+            )?;
+        }
+
         let mut base_builder: BaseClientBuilder<'_, _, Client<QueryNyxdClient>, EphemeralStorage> =
             BaseClientBuilder::new(
                 &gateway_endpoint_config,
@@ -467,6 +493,13 @@ where
             base_builder = base_builder.with_topology_provider(topology_provider);
         }
 
+        if !self.fallback_gateways.is_empty() {
+            if let Some(key_pathfinder) = key_pathfinder {
+                base_builder = base_builder.with_key_pathfinder(key_pathfinder);
+            }
+            base_builder = base_builder.with_fallback_gateways(self.fallback_gateways);
+        }
+
         let started_client = base_builder.start_base().await?;
 
         Ok((started_client, nym_address))
@@ -518,7 +551,6 @@ where
             client_input,
             client_output,
             client_state.clone(),
-            nym_address,
             started_client.task_manager.subscribe(),
         );
         started_client
@@ -581,6 +613,10 @@ where
         let client_state = started_client.client_state;
 
         let reconstructed_receiver = client_output.register_receiver()?;
+        let sink = MixnetMessageSink::new(
+            client_input.input_sender.clone(),
+            client_state.shared_lane_queue_lengths.clone(),
+        );
 
         Ok(MixnetClient {
             nym_address,
@@ -588,7 +624,10 @@ where
             client_input,
             client_output,
             client_state,
-            reconstructed_receiver,
+            reconstructed_receiver: ReceivedMessages::Direct(reconstructed_receiver),
+            buffered_messages: VecDeque::new(),
+            sink,
+            stream_multiplexer: None,
             task_manager: started_client.task_manager,
         })
     }
@@ -1,11 +1,13 @@
 use nym_client_core::client::{
     base_client::{ClientInput, ClientOutput, ClientState},
+    delivery_tracker::{DeliveryConfirmation, DeliveryTracker},
     inbound_messages::InputMessage,
     key_manager::KeyManager,
     received_buffer::ReconstructedMessagesReceiver,
 };
 use nym_sphinx::{
     addressing::clients::{ClientIdentity, Recipient},
+    message::MessageCompression,
     receiver::ReconstructedMessage,
 };
 use nym_task::{
@@ -13,11 +15,44 @@ use nym_task::{
     TaskManager,
 };
 
-use futures::StreamExt;
+use futures::channel::mpsc;
+use futures::{ready, Sink, Stream, StreamExt};
 use nym_topology::NymTopology;
+use rand::RngCore;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 use crate::mixnet::client::{IncludedSurbs, MixnetClientBuilder};
-use crate::Result;
+use crate::mixnet::sink::MixnetMessageSink;
+use crate::mixnet::stream::{
+    inbound_channel, MixnetStream, MixnetStreamListener, StreamMultiplexer,
+    StreamMultiplexerHandle, StreamTarget, INCOMING_STREAMS_CAPACITY, PASSTHROUGH_CHANNEL_CAPACITY,
+};
+use crate::{Error, Result};
+
+/// The default number of times each fragment of a message sent with [`MixnetClient::send_wait`]
+/// is going to be retransmitted before its delivery is considered to have failed.
+pub const DEFAULT_MAX_RETRANSMISSIONS: u32 = 5;
+
+/// Source of the messages received from the mixnet. Once the client starts using streams, all the
+/// messages go through the stream multiplexer, which only passes through the regular ones.
+pub(crate) enum ReceivedMessages {
+    Direct(ReconstructedMessagesReceiver),
+    Multiplexed(mpsc::Receiver<Vec<ReconstructedMessage>>),
+}
+
+impl Stream for ReceivedMessages {
+    type Item = Vec<ReconstructedMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.get_mut() {
+            ReceivedMessages::Direct(receiver) => receiver.poll_next_unpin(cx),
+            ReceivedMessages::Multiplexed(receiver) => receiver.poll_next_unpin(cx),
+        }
+    }
+}
 
 /// Client connected to the Nym mixnet.
 pub struct MixnetClient {
@@ -41,7 +76,18 @@ pub struct MixnetClient {
     pub(crate) client_state: ClientState,
 
     /// A channel for messages arriving from the mixnet after they have been reconstructed.
-    pub(crate) reconstructed_receiver: ReconstructedMessagesReceiver,
+    pub(crate) reconstructed_receiver: ReceivedMessages,
+
+    /// Messages that have already been received from the `reconstructed_receiver`, but that
+    /// haven't yet been returned by the [`Stream`] implementation.
+    pub(crate) buffered_messages: VecDeque<ReconstructedMessage>,
+
+    /// The [`Sink`] used when sending messages via the `Sink` implementation of the client.
+    pub(crate) sink: MixnetMessageSink,
+
+    /// Handle to the multiplexer routing the data of all the [`MixnetStream`]s. It's only
+    /// started once the client opens or starts listening for its first stream.
+    pub(crate) stream_multiplexer: Option<StreamMultiplexerHandle>,
 
     /// The task manager that controlls all the spawned tasks that the clients uses to do it's job.
     pub(crate) task_manager: TaskManager,
@@ -81,6 +127,12 @@ impl MixnetClient {
         &self.nym_address
     }
 
+    /// Get the nym address this client is currently reachable at. It only differs from
+    /// [`MixnetClient::nym_address`] if the client has failed over to one of its fallback gateways.
+    pub fn current_nym_address(&self) -> Recipient {
+        self.client_state.self_address.current()
+    }
+
     /// Get a shallow clone of [`MixnetClientSender`]. Useful if you want split the send and
     /// receive logic in different locations.
     pub fn sender(&self) -> MixnetClientSender {
@@ -102,6 +154,16 @@ impl MixnetClient {
         self.client_state.shared_lane_queue_lengths.clone()
     }
 
+    /// Get a new [`MixnetMessageSink`] for sending messages to the mixnet. Unlike the
+    /// [`Sink`] implementation of the client itself, it does not borrow the client and thus
+    /// could be moved to a different task.
+    pub fn sink(&self) -> MixnetMessageSink {
+        MixnetMessageSink::new(
+            self.client_input.input_sender.clone(),
+            self.client_state.shared_lane_queue_lengths.clone(),
+        )
+    }
+
     /// Change the network topology used by this client for constructing sphinx packets into the
     /// provided one.
     pub async fn manually_overwrite_topology(&self, new_topology: NymTopology) {
@@ -160,6 +222,36 @@ impl MixnetClient {
     /// }
     /// ```
     pub async fn send_bytes(&self, address: Recipient, message: Vec<u8>, surbs: IncludedSurbs) {
+        self.send_bytes_with_compression(address, message, surbs, MessageCompression::None)
+            .await
+    }
+
+    /// Sends bytes to the supplied Nym address after compressing them with the specified
+    /// algorithm. The recipient is going to transparently decompress them, however, note that
+    /// clients running older versions are going to be unable to read such messages.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let address = "foobar";
+    ///     let recipient = mixnet::Recipient::try_from_base58_string(address).unwrap();
+    ///     let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let surbs = mixnet::IncludedSurbs::default();
+    ///     let compression = mixnet::MessageCompression::Deflate;
+    ///     client.send_bytes_with_compression(recipient, "hi".repeat(100).into_bytes(), surbs, compression).await;
+    /// }
+    /// ```
+    pub async fn send_bytes_with_compression(
+        &self,
+        address: Recipient,
+        message: Vec<u8>,
+        surbs: IncludedSurbs,
+        compression: MessageCompression,
+    ) {
         let lane = TransmissionLane::General;
         let input_msg = match surbs {
             IncludedSurbs::Amount(surbs) => {
@@ -167,30 +259,92 @@ impl MixnetClient {
             }
             IncludedSurbs::ExposeSelfAddress => InputMessage::new_regular(address, message, lane),
         };
-        self.send(input_msg).await
+        self.send_input_message(input_msg.with_compression(compression))
+            .await
     }
 
     /// Sends a [`InputMessage`] to the mixnet. This is the most low-level sending function, for
     /// full customization.
-    async fn send(&self, message: InputMessage) {
+    async fn send_input_message(&self, message: InputMessage) {
         if self.client_input.send(message).await.is_err() {
             log::error!("Failed to send message");
         }
     }
 
-    /// Sends a [`InputMessage`] to the mixnet. This is the most low-level sending function, for
-    /// full customization.
+    /// Sends a [`InputMessage`] to the mixnet and returns a [`DeliveryConfirmation`] that resolves
+    /// once every fragment of the message got acknowledged by the recipient's gateway, or once any
+    /// of them was not acknowledged after `max_retransmissions` retransmissions.
+    ///
+    /// Note that the delivery of replies is currently not being tracked.
     ///
-    /// Waits until the message is actually sent, or close to being sent, until returning.
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet::{self, InputMessage};
+    /// use nym_task::connections::TransmissionLane;
     ///
-    /// NOTE: this not yet implemented.
-    #[allow(unused)]
-    async fn send_wait(&self, _message: InputMessage) {
-        todo!();
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let address = "foobar";
+    ///     let recipient = mixnet::Recipient::try_from_base58_string(address).unwrap();
+    ///     let client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let message = InputMessage::new_regular(recipient, b"hi".to_vec(), TransmissionLane::General);
+    ///     let confirmation = client.send_tracked(message, 3).await.unwrap();
+    ///     if let Err(err) = confirmation.await {
+    ///         println!("the message was not delivered: {err}");
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_tracked(
+        &self,
+        message: InputMessage,
+        max_retransmissions: u32,
+    ) -> Result<DeliveryConfirmation> {
+        let (tracker, confirmation) = DeliveryTracker::new(max_retransmissions);
+        let message = InputMessage::new_tracked(message, tracker);
+        if self.client_input.send(message).await.is_err() {
+            return Err(Error::ClientInputClosed);
+        }
+        Ok(confirmation)
+    }
+
+    /// Sends a [`InputMessage`] to the mixnet and waits until every fragment of the message got
+    /// acknowledged. It fails if any fragment was not acknowledged after
+    /// [`DEFAULT_MAX_RETRANSMISSIONS`] retransmissions or if the delivery was not confirmed
+    /// within the specified `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet::{self, InputMessage};
+    /// use nym_task::connections::TransmissionLane;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let address = "foobar";
+    ///     let recipient = mixnet::Recipient::try_from_base58_string(address).unwrap();
+    ///     let client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let message = InputMessage::new_regular(recipient, b"hi".to_vec(), TransmissionLane::General);
+    ///     client.send_wait(message, Duration::from_secs(60)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn send_wait(&self, message: InputMessage, timeout: Duration) -> Result<()> {
+        let confirmation = self
+            .send_tracked(message, DEFAULT_MAX_RETRANSMISSIONS)
+            .await?;
+        match tokio::time::timeout(timeout, confirmation).await {
+            Ok(delivery_result) => Ok(delivery_result?),
+            Err(_) => Err(Error::DeliveryTimeout(timeout)),
+        }
     }
 
     /// Wait for messages from the mixnet
     pub async fn wait_for_messages(&mut self) -> Option<Vec<ReconstructedMessage>> {
+        // make sure to not lose anything that was partially consumed via the `Stream` implementation
+        if !self.buffered_messages.is_empty() {
+            return Some(self.buffered_messages.drain(..).collect());
+        }
         self.reconstructed_receiver.next().await
     }
 
@@ -206,6 +360,119 @@ impl MixnetClient {
         }
     }
 
+    fn stream_multiplexer(&mut self) -> StreamMultiplexerHandle {
+        if let Some(handle) = &self.stream_multiplexer {
+            return handle.clone();
+        }
+
+        // from now on, all received messages go through the multiplexer, which passes through
+        // anything that is not a part of a stream
+        let (passthrough_sender, passthrough_receiver) =
+            mpsc::channel(PASSTHROUGH_CHANNEL_CAPACITY);
+        let reconstructed_receiver = std::mem::replace(
+            &mut self.reconstructed_receiver,
+            ReceivedMessages::Multiplexed(passthrough_receiver),
+        );
+
+        let (multiplexer, handle) = StreamMultiplexer::new(
+            reconstructed_receiver,
+            passthrough_sender,
+            self.client_input.input_sender.clone(),
+            self.client_state.shared_lane_queue_lengths.clone(),
+        );
+        multiplexer.start(self.task_manager.subscribe());
+        self.stream_multiplexer = Some(handle.clone());
+        handle
+    }
+
+    /// Opens an ordered, reliable and bidirectional byte stream to the specified Nym address.
+    /// The recipient has to be [listening](MixnetClient::listen) for incoming streams.
+    ///
+    /// With [`IncludedSurbs::Amount`], the specified number of reply SURBs is sent alongside
+    /// the request to open the stream and the recipient never learns our address. Note that
+    /// it's going to request more SURBs whenever it runs low on them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let address = "foobar";
+    ///     let recipient = mixnet::Recipient::try_from_base58_string(address).unwrap();
+    ///     let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let surbs = mixnet::IncludedSurbs::default();
+    ///     let mut stream = client.open_stream(recipient, surbs).await.unwrap();
+    ///     stream.write_all(b"hello").await.unwrap();
+    ///     stream.shutdown().await.unwrap();
+    ///
+    ///     let mut response = Vec::new();
+    ///     stream.read_to_end(&mut response).await.unwrap();
+    /// }
+    /// ```
+    pub async fn open_stream(
+        &mut self,
+        recipient: Recipient,
+        surbs: IncludedSurbs,
+    ) -> Result<MixnetStream> {
+        let multiplexer = self.stream_multiplexer();
+
+        let id = rand::thread_rng().next_u64();
+        let (inbound_sender, inbound) = inbound_channel();
+        multiplexer.register_stream(id, inbound_sender)?;
+
+        let (target, own_address) = match surbs {
+            IncludedSurbs::Amount(reply_surbs) => (
+                StreamTarget::Anonymous {
+                    recipient,
+                    reply_surbs,
+                },
+                None,
+            ),
+            IncludedSurbs::ExposeSelfAddress => (
+                StreamTarget::Address(recipient),
+                Some(self.current_nym_address()),
+            ),
+        };
+
+        let stream = MixnetStream::new(id, true, target, self.sink(), inbound);
+        if self
+            .client_input
+            .send(stream.open_message(own_address))
+            .await
+            .is_err()
+        {
+            return Err(Error::ClientInputClosed);
+        }
+        Ok(stream)
+    }
+
+    /// Starts accepting the streams opened towards this client by other Nym clients. Calling it
+    /// again replaces the previous listener, which will stop receiving any new streams.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let mut listener = client.listen().unwrap();
+    ///     while let Some(mut stream) = listener.accept().await {
+    ///         tokio::spawn(async move { stream.write_all(b"hello there").await });
+    ///     }
+    /// }
+    /// ```
+    pub fn listen(&mut self) -> Result<MixnetStreamListener> {
+        let (incoming_sender, incoming) = mpsc::channel(INCOMING_STREAMS_CAPACITY);
+        self.stream_multiplexer().listen(incoming_sender)?;
+        Ok(MixnetStreamListener::new(incoming))
+    }
+
     /// Disconnect from the mixnet. Currently it is not supported to reconnect a disconnected
     /// client.
     pub async fn disconnect(&mut self) {
@@ -214,6 +481,57 @@ impl MixnetClient {
     }
 }
 
+impl Stream for MixnetClient {
+    type Item = ReconstructedMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        poll_next_message(
+            &mut this.buffered_messages,
+            &mut this.reconstructed_receiver,
+            cx,
+        )
+    }
+}
+
+/// Yields the received messages one by one, buffering the rest of any batch that arrives at once.
+fn poll_next_message(
+    buffered_messages: &mut VecDeque<ReconstructedMessage>,
+    reconstructed_receiver: &mut ReceivedMessages,
+    cx: &mut Context<'_>,
+) -> Poll<Option<ReconstructedMessage>> {
+    loop {
+        if let Some(message) = buffered_messages.pop_front() {
+            return Poll::Ready(Some(message));
+        }
+
+        match ready!(reconstructed_receiver.poll_next_unpin(cx)) {
+            Some(messages) => buffered_messages.extend(messages),
+            None => return Poll::Ready(None),
+        }
+    }
+}
+
+impl Sink<InputMessage> for MixnetClient {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.sink).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: InputMessage) -> Result<()> {
+        Pin::new(&mut self.sink).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.sink).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.sink).poll_close(cx)
+    }
+}
+
 pub struct MixnetClientSender {
     client_input: ClientInput,
 }
@@ -225,3 +543,64 @@ impl MixnetClientSender {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_sphinx::message::MessageFraming;
+
+    fn message(data: &[u8]) -> ReconstructedMessage {
+        ReconstructedMessage {
+            message: data.to_vec(),
+            sender_tag: None,
+            framing: MessageFraming::Standalone,
+        }
+    }
+
+    #[test]
+    fn batched_messages_are_yielded_one_by_one() {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut receiver = ReceivedMessages::Direct(receiver);
+        let mut buffered = VecDeque::new();
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(poll_next_message(&mut buffered, &mut receiver, &mut cx).is_pending());
+
+        sender
+            .unbounded_send(vec![message(b"foo"), message(b"bar")])
+            .unwrap();
+        sender.unbounded_send(vec![message(b"baz")]).unwrap();
+        drop(sender);
+
+        for expected in [b"foo", b"bar", b"baz"] {
+            match poll_next_message(&mut buffered, &mut receiver, &mut cx) {
+                Poll::Ready(Some(received)) => assert_eq!(received.message, expected),
+                _ => panic!("expected a message to be ready"),
+            }
+        }
+        assert!(matches!(
+            poll_next_message(&mut buffered, &mut receiver, &mut cx),
+            Poll::Ready(None)
+        ));
+    }
+
+    #[test]
+    fn buffered_messages_are_yielded_before_closing() {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut receiver = ReceivedMessages::Direct(receiver);
+        let mut buffered = VecDeque::from([message(b"foo")]);
+        drop(sender);
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(matches!(
+            poll_next_message(&mut buffered, &mut receiver, &mut cx),
+            Poll::Ready(Some(received)) if received.message == b"foo"
+        ));
+        assert!(matches!(
+            poll_next_message(&mut buffered, &mut receiver, &mut cx),
+            Poll::Ready(None)
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use nym_client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use nym_pemstore::Passphrase;
 use std::path::{Path, PathBuf};
 
 use crate::error::{Error, Result};
@@ -61,6 +62,10 @@ pub struct StoragePaths {
 
     /// The database storing reply surbs in-between sessions
     pub reply_surb_database_path: PathBuf,
+
+    /// Passphrase used for encrypting the private keys. If not set, the keys are stored in
+    /// plaintext.
+    pub keys_passphrase: Option<Passphrase>,
 }
 
 impl StoragePaths {
@@ -88,8 +93,16 @@ impl StoragePaths {
             gateway_endpoint_config: dir.join("gateway_endpoint_config.toml"),
             credential_database_path: dir.join("db.sqlite"),
             reply_surb_database_path: dir.join("persistent_reply_store.sqlite"),
+            keys_passphrase: None,
         })
     }
+
+    /// Encrypt all the private keys with the provided passphrase.
+    #[must_use]
+    pub fn with_keys_passphrase(mut self, passphrase: Passphrase) -> Self {
+        self.keys_passphrase = Some(passphrase);
+        self
+    }
 }
 
 impl From<StoragePaths> for ClientKeyPathfinder {
@@ -101,6 +114,7 @@ impl From<StoragePaths> for ClientKeyPathfinder {
             encryption_public_key: paths.public_encryption,
             gateway_shared_key: paths.gateway_shared_key,
             ack_key: paths.ack_key,
+            passphrase: paths.keys_passphrase,
         }
     }
 }
@@ -118,6 +132,7 @@ impl<T> From<&nym_client_core::config::Config<T>> for StoragePaths {
             gateway_endpoint_config: Default::default(),
             credential_database_path: value.get_database_path(),
             reply_surb_database_path: value.get_reply_surb_database_path(),
+            keys_passphrase: Passphrase::from_env(),
         }
     }
 }
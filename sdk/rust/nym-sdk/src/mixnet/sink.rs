@@ -0,0 +1,180 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::{ready, Sink};
+use nym_client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use nym_task::connections::LaneQueueLengths;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Sleep;
+use tokio_util::sync::PollSender;
+
+use crate::{Error, Result};
+
+/// The default number of packets that might be waiting to be sent out to the mixnet, across all
+/// transmission lanes, before the sink stops accepting new messages.
+pub const DEFAULT_MAX_QUEUED_PACKETS: usize = 30;
+
+/// How often the sink re-checks the queue lengths whilst it's applying backpressure.
+const QUEUE_LENGTH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A [`Sink`] of [`InputMessage`]s sent to the mixnet.
+///
+/// The sink only becomes ready once the number of packets queued for sending by the client
+/// drops below the configured limit, which paces the producer to the rate at which the client
+/// is actually capable of pushing out the traffic.
+pub struct MixnetMessageSink {
+    sender: PollSender<InputMessage>,
+    lane_queue_lengths: LaneQueueLengths,
+    max_queued_packets: usize,
+    backoff: Option<Pin<Box<Sleep>>>,
+}
+
+impl MixnetMessageSink {
+    pub(crate) fn new(sender: InputMessageSender, lane_queue_lengths: LaneQueueLengths) -> Self {
+        MixnetMessageSink {
+            sender: PollSender::new(sender),
+            lane_queue_lengths,
+            max_queued_packets: DEFAULT_MAX_QUEUED_PACKETS,
+            backoff: None,
+        }
+    }
+
+    /// Change the number of queued packets above which the sink is going to stop accepting
+    /// new messages.
+    #[must_use]
+    pub fn with_max_queued_packets(mut self, max_queued_packets: usize) -> Self {
+        self.max_queued_packets = max_queued_packets;
+        self
+    }
+
+    /// Attempts to immediately send the message, bypassing any backpressure. It is meant to be
+    /// used outside of async contexts, e.g. when dropping the owner of the sink.
+    pub(crate) fn try_send(&self, message: InputMessage) -> bool {
+        self.sender
+            .get_ref()
+            .map(|sender| sender.try_send(message).is_ok())
+            .unwrap_or_default()
+    }
+
+    fn queued_packets(&self) -> usize {
+        match self.lane_queue_lengths.lock() {
+            Ok(inner) => inner.values().sum(),
+            Err(err) => {
+                log::warn!("Failed to get lane queue lengths: {err}");
+                0
+            }
+        }
+    }
+}
+
+impl Sink<InputMessage> for MixnetMessageSink {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        loop {
+            if let Some(backoff) = self.backoff.as_mut() {
+                ready!(backoff.as_mut().poll(cx));
+                self.backoff = None;
+            }
+
+            if self.queued_packets() <= self.max_queued_packets {
+                break;
+            }
+            self.backoff = Some(Box::pin(tokio::time::sleep(QUEUE_LENGTH_POLL_INTERVAL)));
+        }
+
+        self.sender
+            .poll_reserve(cx)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: InputMessage) -> Result<()> {
+        self.sender
+            .send_item(item)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // once the message is in the channel, it's up to the client to get it out to the mixnet
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.sender.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::poll_fn;
+    use futures::{FutureExt, SinkExt};
+    use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+    use nym_task::connections::TransmissionLane;
+
+    fn message(data: &[u8]) -> InputMessage {
+        InputMessage::new_reply(
+            AnonymousSenderTag::from_bytes([42; 16]),
+            data.to_vec(),
+            TransmissionLane::General,
+        )
+    }
+
+    fn received_data(message: InputMessage) -> Vec<u8> {
+        match message {
+            InputMessage::Reply { data, .. } => data,
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sink_forwards_messages_to_the_client() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+        let mut sink = MixnetMessageSink::new(sender, LaneQueueLengths::new());
+
+        sink.send(message(b"foo")).await.unwrap();
+        sink.send(message(b"bar")).await.unwrap();
+
+        assert_eq!(received_data(receiver.recv().await.unwrap()), b"foo");
+        assert_eq!(received_data(receiver.recv().await.unwrap()), b"bar");
+    }
+
+    #[tokio::test]
+    async fn sink_applies_backpressure_while_too_many_packets_are_queued() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(4);
+        let mut lane_queue_lengths = LaneQueueLengths::new();
+        let mut sink =
+            MixnetMessageSink::new(sender, lane_queue_lengths.clone()).with_max_queued_packets(5);
+
+        lane_queue_lengths.set(&TransmissionLane::General, Some(3));
+        lane_queue_lengths.set(&TransmissionLane::ConnectionId(1), Some(3));
+        assert!(poll_fn(|cx| Pin::new(&mut sink).poll_ready(cx))
+            .now_or_never()
+            .is_none());
+
+        lane_queue_lengths.set(&TransmissionLane::ConnectionId(1), Some(2));
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            poll_fn(|cx| Pin::new(&mut sink).poll_ready(cx)),
+        )
+        .await
+        .expect("the sink did not become ready")
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sink_errors_once_the_client_stops_accepting_input() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(4);
+        let mut sink = MixnetMessageSink::new(sender, LaneQueueLengths::new());
+        drop(receiver);
+
+        assert!(matches!(
+            sink.send(message(b"foo")).await,
+            Err(Error::ClientInputClosed)
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Ordered, reliable and bidirectional byte streams between two Nym clients.
+//!
+//! Streams are opened with [`MixnetClient::open_stream`](crate::mixnet::MixnetClient::open_stream)
+//! and accepted via the [`MixnetStreamListener`] obtained from
+//! [`MixnetClient::listen`](crate::mixnet::MixnetClient::listen). Both sides get a
+//! [`MixnetStream`] implementing [`AsyncRead`] and [`AsyncWrite`].
+
+use futures::channel::mpsc;
+use futures::{ready, Sink, Stream, StreamExt};
+use nym_client_core::client::inbound_messages::InputMessage;
+use nym_ordered_buffer::OrderedMessageSender;
+use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::message::MessageFraming;
+use nym_task::connections::TransmissionLane;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::mixnet::sink::MixnetMessageSink;
+use frame::{FramePayload, StreamFrame, StreamId};
+
+pub(crate) use multiplexer::{
+    inbound_channel, InboundReceiver, StreamMultiplexer, StreamMultiplexerHandle,
+    INCOMING_STREAMS_CAPACITY, PASSTHROUGH_CHANNEL_CAPACITY,
+};
+
+mod frame;
+mod multiplexer;
+
+/// Maximum amount of data put in a single mixnet message. Larger writes are split.
+const MAX_FRAME_DATA_SIZE: usize = 32 * 1024;
+
+/// Where the data written to the stream is sent to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum StreamTarget {
+    /// The peer is reached directly at its address. This is the case for the streams we opened
+    /// whilst revealing our own address and for streams opened by peers that revealed theirs.
+    Address(Recipient),
+
+    /// The peer is reached directly at its address, but it doesn't know ours and thus has to use
+    /// the reply SURBs we send it in order to respond.
+    Anonymous {
+        recipient: Recipient,
+        reply_surbs: u32,
+    },
+
+    /// The peer has not revealed its address, so we have to use the reply SURBs it sent us.
+    Reply(AnonymousSenderTag),
+}
+
+impl StreamTarget {
+    fn input_message(&self, data: Vec<u8>, lane: TransmissionLane) -> InputMessage {
+        let message = match *self {
+            StreamTarget::Address(recipient) => InputMessage::new_regular(recipient, data, lane),
+            // the initial batch of SURBs is sent alongside the stream opening frame. after that,
+            // the recipient is going to explicitly request more whenever it runs low
+            StreamTarget::Anonymous { recipient, .. } => {
+                InputMessage::new_anonymous(recipient, data, 0, lane)
+            }
+            StreamTarget::Reply(sender_tag) => InputMessage::new_reply(sender_tag, data, lane),
+        };
+        message.with_framing(MessageFraming::Stream)
+    }
+}
+
+/// An ordered, reliable and bidirectional byte stream to another Nym client.
+///
+/// Every write is sent as a separate mixnet message (or several, if it exceeds the maximum frame
+/// size) and the data is re-ordered on the receiving side. Shutting down the writing half
+/// lets the peer observe the end of the stream once it has received all preceding data.
+pub struct MixnetStream {
+    id: StreamId,
+    initiator: bool,
+    target: StreamTarget,
+
+    sink: MixnetMessageSink,
+    ordered_sender: OrderedMessageSender,
+    closed: bool,
+
+    inbound: InboundReceiver,
+    read_buffer: Vec<u8>,
+    read_offset: usize,
+}
+
+impl MixnetStream {
+    pub(crate) fn new(
+        id: StreamId,
+        initiator: bool,
+        target: StreamTarget,
+        sink: MixnetMessageSink,
+        inbound: InboundReceiver,
+    ) -> Self {
+        MixnetStream {
+            id,
+            initiator,
+            target,
+            sink,
+            ordered_sender: OrderedMessageSender::new(),
+            closed: false,
+            inbound,
+            read_buffer: Vec::new(),
+            read_offset: 0,
+        }
+    }
+
+    /// The address of the peer, if it's known.
+    pub fn peer_address(&self) -> Option<Recipient> {
+        match self.target {
+            StreamTarget::Address(recipient) | StreamTarget::Anonymous { recipient, .. } => {
+                Some(recipient)
+            }
+            StreamTarget::Reply(_) => None,
+        }
+    }
+
+    /// The anonymous tag of the peer if it hasn't revealed its address.
+    pub fn peer_sender_tag(&self) -> Option<AnonymousSenderTag> {
+        match self.target {
+            StreamTarget::Reply(sender_tag) => Some(sender_tag),
+            _ => None,
+        }
+    }
+
+    fn lane(&self) -> TransmissionLane {
+        TransmissionLane::ConnectionId(self.id)
+    }
+
+    pub(crate) fn open_message(&self, own_address: Option<Recipient>) -> InputMessage {
+        let frame = StreamFrame {
+            stream_id: self.id,
+            from_initiator: self.initiator,
+            payload: FramePayload::Open {
+                reply_address: own_address.map(|address| address.to_bytes().to_vec()),
+            },
+        };
+
+        match self.target {
+            StreamTarget::Anonymous {
+                recipient,
+                reply_surbs,
+            } => {
+                InputMessage::new_anonymous(recipient, frame.into_bytes(), reply_surbs, self.lane())
+                    .with_framing(MessageFraming::Stream)
+            }
+            target => target.input_message(frame.into_bytes(), self.lane()),
+        }
+    }
+
+    fn frame_message(&mut self, data: Vec<u8>, last: bool) -> InputMessage {
+        let message = self.ordered_sender.wrap_message(data);
+        let payload = if last {
+            FramePayload::Close(message)
+        } else {
+            FramePayload::Data(message)
+        };
+        let frame = StreamFrame {
+            stream_id: self.id,
+            from_initiator: self.initiator,
+            payload,
+        };
+        self.target.input_message(frame.into_bytes(), self.lane())
+    }
+}
+
+fn broken_pipe(err: crate::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, err)
+}
+
+impl AsyncRead for MixnetStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.read_offset == self.read_buffer.len() {
+            match ready!(self.inbound.poll_recv(cx))? {
+                Some(data) => {
+                    self.read_buffer = data;
+                    self.read_offset = 0;
+                }
+                // the peer has closed its writing half
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let available = &self.read_buffer[self.read_offset..];
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        self.read_offset += n;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for MixnetStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        ready!(Pin::new(&mut self.sink).poll_ready(cx)).map_err(broken_pipe)?;
+
+        let n = buf.len().min(MAX_FRAME_DATA_SIZE);
+        let message = self.frame_message(buf[..n].to_vec(), false);
+        Pin::new(&mut self.sink)
+            .start_send(message)
+            .map_err(broken_pipe)?;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.sink).poll_flush(cx).map_err(broken_pipe)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.closed {
+            return Poll::Ready(Ok(()));
+        }
+
+        ready!(Pin::new(&mut self.sink).poll_ready(cx)).map_err(broken_pipe)?;
+        let message = self.frame_message(Vec::new(), true);
+        Pin::new(&mut self.sink)
+            .start_send(message)
+            .map_err(broken_pipe)?;
+        self.closed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for MixnetStream {
+    fn drop(&mut self) {
+        // make sure the peer doesn't wait for our data forever
+        if !self.closed {
+            let message = self.frame_message(Vec::new(), true);
+            if !self.sink.try_send(message) {
+                log::debug!("failed to notify the peer about closing stream {}", self.id)
+            }
+        }
+    }
+}
+
+/// Accepts the streams opened towards this client by other Nym clients.
+pub struct MixnetStreamListener {
+    incoming: mpsc::Receiver<MixnetStream>,
+}
+
+impl MixnetStreamListener {
+    pub(crate) fn new(incoming: mpsc::Receiver<MixnetStream>) -> Self {
+        MixnetStreamListener { incoming }
+    }
+
+    /// Wait for the next incoming stream. It returns `None` once the client has shut down.
+    pub async fn accept(&mut self) -> Option<MixnetStream> {
+        self.incoming.next().await
+    }
+}
+
+impl Stream for MixnetStreamListener {
+    type Item = MixnetStream;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.incoming.poll_next_unpin(cx)
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_ordered_buffer::{MessageError, OrderedMessage};
+use thiserror::Error;
+
+pub(crate) type StreamId = u64;
+
+const CURRENT_VERSION: u8 = 1;
+
+/// | version (1) | kind (1) | from_initiator (1) | stream id (8) |
+const HEADER_LEN: usize = 1 + 1 + 1 + 8;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum FrameError {
+    #[error("the frame is too short. Got {received} bytes, but expected at least {expected}")]
+    TooShort { received: usize, expected: usize },
+
+    #[error("the frame has an unsupported version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("the frame has an unknown kind {0}")]
+    UnknownKind(u8),
+
+    #[error("the frame contains malformed ordered message: {0}")]
+    MalformedOrderedMessage(#[from] MessageError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum FrameKind {
+    Open = 0,
+    Data = 1,
+    Close = 2,
+}
+
+impl TryFrom<u8> for FrameKind {
+    type Error = FrameError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FrameKind::Open),
+            1 => Ok(FrameKind::Data),
+            2 => Ok(FrameKind::Close),
+            other => Err(FrameError::UnknownKind(other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FramePayload {
+    /// Announces a new stream. If the initiator has decided to reveal its address, it's attached
+    /// so that the other side would know where to send its own data.
+    Open { reply_address: Option<Vec<u8>> },
+
+    /// Next chunk of the stream data.
+    Data(OrderedMessage),
+
+    /// Indicates that the sender is not going to write any more data. The index of the message
+    /// is the one following the last data chunk.
+    Close(OrderedMessage),
+}
+
+/// A single unit of stream communication sent as a mixnet message. Such messages are explicitly
+/// tagged with [`MessageFraming::Stream`](nym_sphinx::message::MessageFraming::Stream) so that
+/// they could be told apart from any other messages the client might be receiving.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StreamFrame {
+    pub(crate) stream_id: StreamId,
+
+    /// Whether this frame was sent by the party that opened the stream. Since both sides choose
+    /// the ids of the streams they open, this is required to tell them apart.
+    pub(crate) from_initiator: bool,
+
+    pub(crate) payload: FramePayload,
+}
+
+impl StreamFrame {
+    fn kind(&self) -> FrameKind {
+        match self.payload {
+            FramePayload::Open { .. } => FrameKind::Open,
+            FramePayload::Data(_) => FrameKind::Data,
+            FramePayload::Close(_) => FrameKind::Close,
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.push(CURRENT_VERSION);
+        bytes.push(self.kind() as u8);
+        bytes.push(self.from_initiator as u8);
+        bytes.extend_from_slice(&self.stream_id.to_be_bytes());
+
+        match self.payload {
+            FramePayload::Open { reply_address } => {
+                if let Some(address) = reply_address {
+                    bytes.extend_from_slice(&address)
+                }
+            }
+            FramePayload::Data(message) | FramePayload::Close(message) => {
+                bytes.extend_from_slice(&message.into_bytes())
+            }
+        }
+        bytes
+    }
+
+    pub(crate) fn try_from_bytes(b: &[u8]) -> Result<Self, FrameError> {
+        if b.len() < HEADER_LEN {
+            return Err(FrameError::TooShort {
+                received: b.len(),
+                expected: HEADER_LEN,
+            });
+        }
+
+        let version = b[0];
+        if version != CURRENT_VERSION {
+            return Err(FrameError::UnsupportedVersion(version));
+        }
+        let kind = FrameKind::try_from(b[1])?;
+        let from_initiator = b[2] != 0;
+
+        let mut stream_id = [0u8; 8];
+        stream_id.copy_from_slice(&b[3..HEADER_LEN]);
+        let stream_id = StreamId::from_be_bytes(stream_id);
+
+        let payload = &b[HEADER_LEN..];
+        let payload = match kind {
+            FrameKind::Open => FramePayload::Open {
+                reply_address: (!payload.is_empty()).then(|| payload.to_vec()),
+            },
+            FrameKind::Data => {
+                FramePayload::Data(OrderedMessage::try_from_bytes(payload.to_vec())?)
+            }
+            FrameKind::Close => {
+                FramePayload::Close(OrderedMessage::try_from_bytes(payload.to_vec())?)
+            }
+        };
+
+        Ok(StreamFrame {
+            stream_id,
+            from_initiator,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_can_be_recovered_from_bytes() {
+        let frames = vec![
+            StreamFrame {
+                stream_id: 42,
+                from_initiator: true,
+                payload: FramePayload::Open {
+                    reply_address: None,
+                },
+            },
+            StreamFrame {
+                stream_id: 42,
+                from_initiator: true,
+                payload: FramePayload::Open {
+                    reply_address: Some(vec![1, 2, 3]),
+                },
+            },
+            StreamFrame {
+                stream_id: u64::MAX,
+                from_initiator: false,
+                payload: FramePayload::Data(OrderedMessage {
+                    data: vec![4, 5, 6],
+                    index: 123,
+                }),
+            },
+            StreamFrame {
+                stream_id: 0,
+                from_initiator: false,
+                payload: FramePayload::Close(OrderedMessage {
+                    data: vec![],
+                    index: 124,
+                }),
+            },
+        ];
+
+        for frame in frames {
+            let bytes = frame.clone().into_bytes();
+            assert_eq!(frame, StreamFrame::try_from_bytes(&bytes).unwrap())
+        }
+    }
+
+    #[test]
+    fn malformed_frames_are_rejected() {
+        let valid = StreamFrame {
+            stream_id: 42,
+            from_initiator: true,
+            payload: FramePayload::Data(OrderedMessage {
+                data: vec![1],
+                index: 0,
+            }),
+        }
+        .into_bytes();
+
+        assert!(StreamFrame::try_from_bytes(&valid[..HEADER_LEN - 1]).is_err());
+        // missing the ordered message index
+        assert!(StreamFrame::try_from_bytes(&valid[..HEADER_LEN + 4]).is_err());
+
+        let mut bad_version = valid.clone();
+        bad_version[0] = 42;
+        assert_eq!(
+            StreamFrame::try_from_bytes(&bad_version),
+            Err(FrameError::UnsupportedVersion(42))
+        );
+
+        let mut bad_kind = valid;
+        bad_kind[1] = 42;
+        assert_eq!(
+            StreamFrame::try_from_bytes(&bad_kind),
+            Err(FrameError::UnknownKind(42))
+        );
+    }
+}
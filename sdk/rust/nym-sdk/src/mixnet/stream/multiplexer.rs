@@ -0,0 +1,662 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::frame::{FramePayload, StreamFrame, StreamId};
+use super::{MixnetStream, StreamTarget};
+use crate::mixnet::native_client::ReceivedMessages;
+use crate::mixnet::sink::MixnetMessageSink;
+use crate::{Error, Result};
+use futures::channel::mpsc;
+use futures::{ready, StreamExt};
+use log::*;
+use nym_client_core::client::inbound_messages::InputMessageSender;
+use nym_ordered_buffer::{OrderedMessage, OrderedMessageBuffer};
+use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::receiver::ReconstructedMessage;
+use nym_task::connections::LaneQueueLengths;
+use nym_task::TaskClient;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Maximum number of received data chunks of a single stream that haven't been read yet.
+/// If the application doesn't keep up, the stream gets reset.
+const INBOUND_CHANNEL_CAPACITY: usize = 256;
+
+/// Maximum number of frames of a single stream that arrived out of order and are waiting for
+/// the preceding data. If the gap doesn't get filled in time, the stream gets reset.
+const MAX_BUFFERED_FRAMES: usize = 256;
+
+/// Maximum number of batches of regular messages waiting to be received by the client.
+/// If the application doesn't keep up, any further messages get dropped.
+pub(crate) const PASSTHROUGH_CHANNEL_CAPACITY: usize = 64;
+
+/// Maximum number of remotely opened streams waiting to be accepted by the listener.
+pub(crate) const INCOMING_STREAMS_CAPACITY: usize = 32;
+
+/// Maximum number of remote streams for which we keep the data that arrived ahead of their
+/// opening frame.
+const MAX_EARLY_STREAMS: usize = 64;
+
+/// Maximum number of frames kept for a single stream whose opening frame hasn't arrived yet.
+const MAX_EARLY_FRAMES: usize = 32;
+
+/// How long we wait for the opening frame of a stream after receiving some of its data.
+const EARLY_FRAMES_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) fn inbound_channel() -> (InboundSender, InboundReceiver) {
+    let (sender, receiver) = mpsc::channel(INBOUND_CHANNEL_CAPACITY);
+    let reset = Arc::new(AtomicBool::new(false));
+    (
+        InboundSender {
+            sender,
+            reset: Arc::clone(&reset),
+        },
+        InboundReceiver { receiver, reset },
+    )
+}
+
+/// Sending half of the channel forwarding the ordered data of a stream to its [`MixnetStream`].
+pub(crate) struct InboundSender {
+    sender: mpsc::Sender<Vec<u8>>,
+    reset: Arc<AtomicBool>,
+}
+
+impl InboundSender {
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    // we can't apply any backpressure on the remote, so rather than silently
+    // losing data, let the reader know the stream got broken
+    fn reset(&mut self) {
+        self.reset.store(true, Ordering::Release);
+        self.sender.close_channel();
+    }
+
+    // returns `false` if the stream can no longer receive any data
+    fn send(&mut self, data: Vec<u8>) -> bool {
+        match self.sender.try_send(data) {
+            Ok(_) => true,
+            Err(err) => {
+                if err.is_full() {
+                    self.reset();
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Receiving half of the channel forwarding the ordered data of a stream to its [`MixnetStream`].
+pub(crate) struct InboundReceiver {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    reset: Arc<AtomicBool>,
+}
+
+impl InboundReceiver {
+    /// Returns `None` once the peer has finished writing.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<Vec<u8>>>> {
+        match ready!(self.receiver.poll_next_unpin(cx)) {
+            Some(data) => Poll::Ready(Ok(Some(data))),
+            None if self.reset.load(Ordering::Acquire) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "the stream has been reset as too much of its data had to be buffered",
+            ))),
+            None => Poll::Ready(Ok(None)),
+        }
+    }
+}
+
+pub(crate) enum MultiplexerCommand {
+    /// Start forwarding the data of the stream we have just opened.
+    Register {
+        id: StreamId,
+        inbound: InboundSender,
+    },
+
+    /// Start accepting the streams opened by other clients. It replaces any previous listener.
+    Listen(mpsc::Sender<MixnetStream>),
+}
+
+#[derive(Clone)]
+pub(crate) struct StreamMultiplexerHandle {
+    commands: mpsc::UnboundedSender<MultiplexerCommand>,
+}
+
+impl StreamMultiplexerHandle {
+    pub(crate) fn register_stream(&self, id: StreamId, inbound: InboundSender) -> Result<()> {
+        self.commands
+            .unbounded_send(MultiplexerCommand::Register { id, inbound })
+            .map_err(|_| Error::StreamMultiplexerStopped)
+    }
+
+    pub(crate) fn listen(&self, incoming: mpsc::Sender<MixnetStream>) -> Result<()> {
+        self.commands
+            .unbounded_send(MultiplexerCommand::Listen(incoming))
+            .map_err(|_| Error::StreamMultiplexerStopped)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct StreamKey {
+    id: StreamId,
+    opened_locally: bool,
+}
+
+struct StreamState {
+    buffer: OrderedMessageBuffer,
+    close_index: Option<u64>,
+    inbound: InboundSender,
+
+    /// Whether all the data of the stream has already been forwarded.
+    finished: bool,
+}
+
+impl StreamState {
+    fn new(inbound: InboundSender) -> Self {
+        StreamState {
+            buffer: OrderedMessageBuffer::new(),
+            close_index: None,
+            inbound,
+            finished: false,
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.inbound.is_closed()
+    }
+
+    // returns `false` if the stream should no longer receive any data
+    fn write(&mut self, message: OrderedMessage, last: bool) -> bool {
+        if last {
+            self.close_index = Some(message.index);
+        }
+        self.buffer.write(message);
+
+        let Some(read) = self.buffer.read() else {
+            if self.buffer.len() > MAX_BUFFERED_FRAMES {
+                // the remote keeps on sending data without ever filling the gap
+                self.inbound.reset();
+                return false;
+            }
+            return true;
+        };
+        if !read.data.is_empty() && !self.inbound.send(read.data) {
+            // the stream got dropped or it is not being read
+            return false;
+        }
+
+        // note: `last_index` is the index following the last read message
+        if matches!(self.close_index, Some(close_index) if read.last_index > close_index) {
+            self.finished = true;
+        }
+        !self.finished
+    }
+}
+
+/// Frames of a remotely opened stream that arrived ahead of its opening frame.
+struct EarlyFrames {
+    first_received: Instant,
+    frames: Vec<(OrderedMessage, bool)>,
+}
+
+impl EarlyFrames {
+    fn new() -> Self {
+        EarlyFrames {
+            first_received: Instant::now(),
+            frames: Vec::new(),
+        }
+    }
+}
+
+/// Routes the frames of all the streams to the [`MixnetStream`]s they belong to. Any other
+/// messages are passed through unchanged to the [`MixnetClient`](crate::mixnet::MixnetClient).
+pub(crate) struct StreamMultiplexer {
+    reconstructed_receiver: ReceivedMessages,
+    passthrough: mpsc::Sender<Vec<ReconstructedMessage>>,
+    commands: mpsc::UnboundedReceiver<MultiplexerCommand>,
+
+    input_sender: InputMessageSender,
+    lane_queue_lengths: LaneQueueLengths,
+
+    incoming: Option<mpsc::Sender<MixnetStream>>,
+    streams: HashMap<StreamKey, StreamState>,
+    early_frames: HashMap<StreamKey, EarlyFrames>,
+}
+
+impl StreamMultiplexer {
+    pub(crate) fn new(
+        reconstructed_receiver: ReceivedMessages,
+        passthrough: mpsc::Sender<Vec<ReconstructedMessage>>,
+        input_sender: InputMessageSender,
+        lane_queue_lengths: LaneQueueLengths,
+    ) -> (Self, StreamMultiplexerHandle) {
+        let (commands_sender, commands) = mpsc::unbounded();
+        (
+            StreamMultiplexer {
+                reconstructed_receiver,
+                passthrough,
+                commands,
+                input_sender,
+                lane_queue_lengths,
+                incoming: None,
+                streams: HashMap::new(),
+                early_frames: HashMap::new(),
+            },
+            StreamMultiplexerHandle {
+                commands: commands_sender,
+            },
+        )
+    }
+
+    fn handle_command(&mut self, command: MultiplexerCommand) {
+        match command {
+            MultiplexerCommand::Register { id, inbound } => {
+                let key = StreamKey {
+                    id,
+                    opened_locally: true,
+                };
+                self.streams.insert(key, StreamState::new(inbound));
+            }
+            MultiplexerCommand::Listen(incoming) => self.incoming = Some(incoming),
+        }
+    }
+
+    fn handle_messages(&mut self, messages: Vec<ReconstructedMessage>) {
+        let mut passthrough = Vec::new();
+        for message in messages {
+            if !message.framing.is_stream() {
+                passthrough.push(message);
+                continue;
+            }
+
+            match StreamFrame::try_from_bytes(&message.message) {
+                Ok(frame) => self.handle_frame(frame, message.sender_tag),
+                Err(err) => warn!("received a malformed stream frame: {err}"),
+            }
+        }
+
+        if !passthrough.is_empty() {
+            if let Err(err) = self.passthrough.try_send(passthrough) {
+                // the client might not care about regular messages anymore
+                if err.is_full() {
+                    warn!(
+                        "the received messages are not being read fast enough - dropping {} of them",
+                        err.into_inner().len()
+                    )
+                }
+            }
+        }
+
+        self.streams.retain(|_, state| !state.is_stale());
+        self.early_frames
+            .retain(|_, early| early.first_received.elapsed() < EARLY_FRAMES_TIMEOUT);
+    }
+
+    fn handle_frame(&mut self, frame: StreamFrame, sender_tag: Option<AnonymousSenderTag>) {
+        let key = StreamKey {
+            id: frame.stream_id,
+            opened_locally: !frame.from_initiator,
+        };
+
+        match frame.payload {
+            FramePayload::Open { reply_address } => {
+                self.handle_open(key, reply_address, sender_tag)
+            }
+            FramePayload::Data(message) => self.handle_data(key, message, false),
+            FramePayload::Close(message) => self.handle_data(key, message, true),
+        }
+    }
+
+    fn handle_open(
+        &mut self,
+        key: StreamKey,
+        reply_address: Option<Vec<u8>>,
+        sender_tag: Option<AnonymousSenderTag>,
+    ) {
+        if key.opened_locally {
+            warn!("received an opening frame for our own stream {}", key.id);
+            return;
+        }
+        if self.incoming.is_none() {
+            debug!("ignoring stream {} as we're not listening", key.id);
+            return;
+        }
+        if self.streams.contains_key(&key) {
+            debug!("received a duplicate opening frame for stream {}", key.id);
+            return;
+        }
+
+        let target = match (reply_address, sender_tag) {
+            (Some(address), _) => {
+                let recipient = address
+                    .try_into()
+                    .ok()
+                    .and_then(|bytes| Recipient::try_from_bytes(bytes).ok());
+                match recipient {
+                    Some(recipient) => StreamTarget::Address(recipient),
+                    None => {
+                        warn!("stream {} has a malformed reply address", key.id);
+                        return;
+                    }
+                }
+            }
+            (None, Some(sender_tag)) => StreamTarget::Reply(sender_tag),
+            (None, None) => {
+                warn!("stream {} can't be responded to", key.id);
+                return;
+            }
+        };
+
+        let (inbound_sender, inbound) = inbound_channel();
+        let mut state = StreamState::new(inbound_sender);
+
+        // forward any data that got to us before the opening frame
+        let mut receiving = true;
+        if let Some(early) = self.early_frames.remove(&key) {
+            for (message, last) in early.frames {
+                receiving = state.write(message, last);
+                if !receiving {
+                    break;
+                }
+            }
+        }
+
+        let sink =
+            MixnetMessageSink::new(self.input_sender.clone(), self.lane_queue_lengths.clone());
+        let stream = MixnetStream::new(key.id, false, target, sink, inbound);
+        let Some(incoming) = self.incoming.as_mut() else {
+            return;
+        };
+        match incoming.try_send(stream) {
+            Ok(_) => {
+                // if we have already received all the data of the stream, there's nothing to keep
+                if receiving {
+                    self.streams.insert(key, state);
+                }
+            }
+            Err(err) if err.is_full() => {
+                warn!(
+                    "too many streams are waiting to be accepted - rejecting stream {}",
+                    key.id
+                )
+            }
+            Err(_) => {
+                debug!("the stream listener got dropped");
+                self.incoming = None;
+            }
+        }
+    }
+
+    fn handle_data(&mut self, key: StreamKey, message: OrderedMessage, last: bool) {
+        if let Some(state) = self.streams.get_mut(&key) {
+            if !state.write(message, last) {
+                self.streams.remove(&key);
+            }
+            return;
+        }
+
+        // the remote might have started sending data before its opening frame got to us,
+        // but we only keep a limited amount of it for a limited time
+        if key.opened_locally || self.incoming.is_none() {
+            trace!("received data for an unknown stream {}", key.id);
+            return;
+        }
+        let early_streams = self.early_frames.len();
+        let early = match self.early_frames.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) if early_streams < MAX_EARLY_STREAMS => {
+                entry.insert(EarlyFrames::new())
+            }
+            Entry::Vacant(_) => {
+                debug!("dropping data of unknown stream {}", key.id);
+                return;
+            }
+        };
+        if early.frames.len() >= MAX_EARLY_FRAMES {
+            debug!("dropping data of stream {} that hasn't been opened", key.id);
+            return;
+        }
+        early.frames.push((message, last));
+    }
+
+    pub(crate) async fn run(&mut self, mut shutdown: TaskClient) {
+        debug!("Started StreamMultiplexer with graceful shutdown support");
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("StreamMultiplexer: Received shutdown");
+                }
+                command = self.commands.next() => match command {
+                    Some(command) => self.handle_command(command),
+                    None => {
+                        trace!("StreamMultiplexer: Stopping since the client got dropped");
+                        break;
+                    }
+                },
+                messages = self.reconstructed_receiver.next() => match messages {
+                    Some(messages) => self.handle_messages(messages),
+                    None => {
+                        trace!("StreamMultiplexer: Stopping since channel closed");
+                        break;
+                    }
+                },
+            }
+        }
+        shutdown.recv_timeout().await;
+        debug!("StreamMultiplexer: Exiting");
+    }
+
+    pub(crate) fn start(mut self, shutdown: TaskClient) {
+        tokio::spawn(async move { self.run(shutdown).await });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_sphinx::message::MessageFraming;
+
+    fn message(index: u64, data: &[u8]) -> OrderedMessage {
+        OrderedMessage {
+            data: data.to_vec(),
+            index,
+        }
+    }
+
+    fn test_multiplexer() -> (
+        StreamMultiplexer,
+        mpsc::Receiver<MixnetStream>,
+        mpsc::Receiver<Vec<ReconstructedMessage>>,
+    ) {
+        let (_, reconstructed_receiver) = mpsc::unbounded();
+        let (passthrough, passthrough_receiver) = mpsc::channel(PASSTHROUGH_CHANNEL_CAPACITY);
+        let (input_sender, _) = tokio::sync::mpsc::channel(1);
+        let (mut multiplexer, _) = StreamMultiplexer::new(
+            ReceivedMessages::Direct(reconstructed_receiver),
+            passthrough,
+            input_sender,
+            LaneQueueLengths::new(),
+        );
+
+        let (incoming_sender, incoming) = mpsc::channel(INCOMING_STREAMS_CAPACITY);
+        multiplexer.handle_command(MultiplexerCommand::Listen(incoming_sender));
+        (multiplexer, incoming, passthrough_receiver)
+    }
+
+    fn remote_key(id: StreamId) -> StreamKey {
+        StreamKey {
+            id,
+            opened_locally: false,
+        }
+    }
+
+    #[test]
+    fn stream_state_forwards_data_in_order() {
+        let (inbound, mut receiver) = inbound_channel();
+        let mut state = StreamState::new(inbound);
+
+        assert!(state.write(message(1, b"world"), false));
+        assert!(receiver.receiver.try_next().is_err());
+
+        assert!(state.write(message(0, b"hello "), false));
+        assert_eq!(
+            receiver.receiver.try_next().unwrap().unwrap(),
+            b"hello world"
+        );
+    }
+
+    #[test]
+    fn stream_state_finishes_once_all_data_preceding_close_is_forwarded() {
+        let (inbound, mut receiver) = inbound_channel();
+        let mut state = StreamState::new(inbound);
+
+        assert!(state.write(message(2, &[]), true));
+        assert!(state.write(message(0, b"foo"), false));
+        assert!(!state.write(message(1, b"bar"), false));
+        assert!(state.finished);
+        assert_eq!(receiver.receiver.try_next().unwrap().unwrap(), b"foo");
+        assert_eq!(receiver.receiver.try_next().unwrap().unwrap(), b"bar");
+    }
+
+    #[test]
+    fn stream_state_is_reset_if_not_read() {
+        let (inbound, mut receiver) = inbound_channel();
+        let mut state = StreamState::new(inbound);
+
+        let mut index = 0;
+        while state.write(message(index, b"foo"), false) {
+            index += 1;
+        }
+        assert!(state.is_stale());
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..index {
+            assert!(matches!(
+                receiver.poll_recv(&mut cx),
+                Poll::Ready(Ok(Some(_)))
+            ));
+        }
+        assert!(matches!(receiver.poll_recv(&mut cx), Poll::Ready(Err(_))));
+    }
+
+    #[test]
+    fn stream_state_is_reset_if_gap_is_never_filled() {
+        let (inbound, mut receiver) = inbound_channel();
+        let mut state = StreamState::new(inbound);
+
+        // the very first chunk never arrives
+        let mut index = 1;
+        while state.write(message(index, b"foo"), false) {
+            index += 1;
+        }
+        assert_eq!(index as usize, MAX_BUFFERED_FRAMES + 1);
+        assert!(state.is_stale());
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(matches!(receiver.poll_recv(&mut cx), Poll::Ready(Err(_))));
+    }
+
+    #[test]
+    fn only_explicitly_tagged_messages_are_treated_as_stream_frames() {
+        let (mut multiplexer, mut incoming, mut passthrough) = test_multiplexer();
+        let sender_tag = AnonymousSenderTag::from_bytes(Default::default());
+        let open_frame = StreamFrame {
+            stream_id: 1,
+            from_initiator: true,
+            payload: FramePayload::Open {
+                reply_address: None,
+            },
+        }
+        .into_bytes();
+
+        // a regular message that just happens to look like a stream frame
+        multiplexer.handle_messages(vec![ReconstructedMessage::new(
+            open_frame.clone(),
+            sender_tag,
+        )]);
+        assert!(incoming.try_next().is_err());
+        let received = passthrough.try_next().unwrap().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].message, open_frame);
+
+        multiplexer
+            .handle_messages(vec![ReconstructedMessage::new(open_frame, sender_tag)
+                .with_framing(MessageFraming::Stream)]);
+        assert!(incoming.try_next().unwrap().is_some());
+        assert!(passthrough.try_next().is_err());
+    }
+
+    #[test]
+    fn passthrough_messages_are_bounded() {
+        let (mut multiplexer, _incoming, mut passthrough) = test_multiplexer();
+        let sender_tag = AnonymousSenderTag::from_bytes(Default::default());
+
+        for _ in 0..(PASSTHROUGH_CHANNEL_CAPACITY + 10) {
+            multiplexer
+                .handle_messages(vec![ReconstructedMessage::new(b"foo".to_vec(), sender_tag)]);
+        }
+
+        let mut received = 0;
+        while let Ok(Some(_)) = passthrough.try_next() {
+            received += 1;
+        }
+        // note: every sender is guaranteed a single extra slot in the channel
+        assert!(received <= PASSTHROUGH_CHANNEL_CAPACITY + 1);
+    }
+
+    #[test]
+    fn data_for_unopened_streams_is_bounded() {
+        let (mut multiplexer, _incoming, _passthrough) = test_multiplexer();
+
+        for id in 0..(MAX_EARLY_STREAMS as u64 + 10) {
+            multiplexer.handle_data(remote_key(id), message(0, b"foo"), false);
+        }
+        assert!(multiplexer.streams.is_empty());
+        assert_eq!(multiplexer.early_frames.len(), MAX_EARLY_STREAMS);
+
+        for index in 1..(MAX_EARLY_FRAMES as u64 + 10) {
+            multiplexer.handle_data(remote_key(0), message(index, b"foo"), false);
+        }
+        assert_eq!(
+            multiplexer.early_frames[&remote_key(0)].frames.len(),
+            MAX_EARLY_FRAMES
+        );
+
+        // nothing is kept for the streams we have supposedly opened ourselves
+        let local = StreamKey {
+            id: 42,
+            opened_locally: true,
+        };
+        multiplexer.handle_data(local, message(0, b"foo"), false);
+        assert!(!multiplexer.early_frames.contains_key(&local));
+    }
+
+    #[test]
+    fn early_data_is_forwarded_once_stream_is_opened() {
+        let (mut multiplexer, mut incoming, _passthrough) = test_multiplexer();
+        let sender_tag = AnonymousSenderTag::from_bytes(Default::default());
+
+        multiplexer.handle_data(remote_key(1), message(1, b"bar"), false);
+        multiplexer.handle_data(remote_key(1), message(0, b"foo"), false);
+        multiplexer.handle_open(remote_key(1), None, Some(sender_tag));
+
+        assert!(multiplexer.early_frames.is_empty());
+        assert!(multiplexer.streams.contains_key(&remote_key(1)));
+
+        let mut stream = incoming.try_next().unwrap().unwrap();
+        assert_eq!(
+            stream.inbound.receiver.try_next().unwrap().unwrap(),
+            b"foobar"
+        );
+    }
+}
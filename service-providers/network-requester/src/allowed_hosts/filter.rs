@@ -3,8 +3,10 @@
 
 use super::HostsStore;
 use crate::allowed_hosts::group::HostsGroup;
+use crate::allowed_hosts::policy::{Action, Protocol, RequestedHost, StoredPolicy};
 use crate::allowed_hosts::standard_list::StandardList;
 use crate::allowed_hosts::stored_allowed_hosts::StoredAllowedHosts;
+use std::fmt::{self, Display, Formatter};
 use std::net::{IpAddr, SocketAddr};
 
 #[derive(Debug)]
@@ -14,7 +16,42 @@ enum RequestHost {
     RootDomain(String),
 }
 
-/// Filters outbound requests based on what's in an `allowed_hosts` list.
+/// The reason for which an outbound request got blocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DenialReason {
+    /// The request matched a deny rule of the policy.
+    PolicyRule(String),
+
+    /// The request didn't match any policy rule and the host is not on any allow list.
+    NotAllowed,
+
+    /// The requested host couldn't be parsed.
+    InvalidHost,
+
+    /// The requested host couldn't be resolved to any address.
+    UnresolvableHost,
+}
+
+impl Display for DenialReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DenialReason::PolicyRule(rule) => write!(f, "denied by the policy rule {rule}"),
+            DenialReason::NotAllowed => write!(f, "the host is not on the allow list"),
+            DenialReason::InvalidHost => write!(f, "the host could not be parsed"),
+            DenialReason::UnresolvableHost => write!(f, "the host could not be resolved"),
+        }
+    }
+}
+
+/// Filters outbound requests based on the operator-defined policy and what's in an
+/// `allowed_hosts` list.
+///
+/// The policy rules are evaluated first, in order, and the first matching one decides whether
+/// the request is allowed. If none of them matches, the `allowed_hosts` and the standard lists
+/// are consulted.
+///
+/// In the open proxy mode, the allow lists are not consulted at all, i.e. anything that's not
+/// explicitly denied by the policy is allowed.
 ///
 /// Requests to unknown hosts are automatically written to an `unknown_hosts`
 /// list so that they can be copy/pasted into the `allowed_hosts` list if desired.
@@ -28,10 +65,12 @@ enum RequestHost {
 /// domains as allowed. That list is loaded once at startup from Mozilla's canonical
 /// publicsuffix list.
 pub(crate) struct OutboundRequestFilter {
+    pub(super) policy: StoredPolicy,
     pub(super) allowed_hosts: StoredAllowedHosts,
     pub(super) standard_list: StandardList,
     root_domain_list: publicsuffix::List,
     unknown_hosts: HostsStore,
+    open_proxy: bool,
 }
 
 impl OutboundRequestFilter {
@@ -43,6 +82,7 @@ impl OutboundRequestFilter {
     /// Automatcially fetches the latest standard allowed list from the Nym website, so that all
     /// requesters are able to support the same minimal functionality out of the box.
     pub(crate) fn new(
+        policy: StoredPolicy,
         allowed_hosts: StoredAllowedHosts,
         standard_list: StandardList,
         unknown_hosts: HostsStore,
@@ -53,10 +93,53 @@ impl OutboundRequestFilter {
         };
 
         OutboundRequestFilter {
+            policy,
             allowed_hosts,
             standard_list,
             root_domain_list: domain_list,
             unknown_hosts,
+            open_proxy: false,
+        }
+    }
+
+    /// Allow all requests that are not explicitly denied by the policy.
+    #[must_use]
+    pub(crate) fn with_open_proxy(mut self, open_proxy: bool) -> Self {
+        self.open_proxy = open_proxy;
+        self
+    }
+
+    pub(crate) fn policy(&self) -> &StoredPolicy {
+        &self.policy
+    }
+
+    /// Returns the decision of the first policy rule matching the request, if any.
+    async fn check_policy(
+        &self,
+        host: &str,
+        protocol: Protocol,
+    ) -> Option<Result<(), DenialReason>> {
+        let (requested_host, port) = Self::policy_target(host);
+        let policy = self.policy.get().await;
+        let rule = policy.evaluate(&requested_host, port, protocol)?;
+        match rule.action() {
+            Action::Allow => Some(Ok(())),
+            Action::Deny => Some(Err(DenialReason::PolicyRule(rule.to_string()))),
+        }
+    }
+
+    /// Splits the requested host into the form understood by the policy, i.e. the full address
+    /// (rather than just the root domain) and the port, if specified.
+    fn policy_target(host: &str) -> (RequestedHost<'_>, Option<u16>) {
+        if let Ok(socketaddr) = host.parse::<SocketAddr>() {
+            (RequestedHost::Ip(socketaddr.ip()), Some(socketaddr.port()))
+        } else if let Ok(ipaddr) = host.parse::<IpAddr>() {
+            (RequestedHost::Ip(ipaddr), None)
+        } else {
+            match host.rsplit_once(':') {
+                Some((domain, port)) => (RequestedHost::Domain(domain), port.parse().ok()),
+                None => (RequestedHost::Domain(host), None),
+            }
         }
     }
 
@@ -117,26 +200,49 @@ impl OutboundRequestFilter {
         self.check_standard_list(request_host).await
     }
 
-    /// Returns `true` if a host's root domain is in the `allowed_hosts` list.
-    ///
-    /// If it's not in the list, return `false` and write it to the `unknown_hosts` storefile.
-    pub(crate) async fn check(&mut self, host: &str) -> bool {
-        let allowed = match self.parse_request_host(host) {
-            Some(request_host) => {
-                let res = self.check_request_host(&request_host).await;
-                if !res {
-                    self.add_to_unknown_hosts(request_host)
-                }
-                res
-            }
-            None => false,
+    async fn evaluate(&mut self, host: &str, protocol: Protocol) -> Result<(), DenialReason> {
+        // the explicit policy always takes precedence over the allow lists
+        if let Some(decision) = self.check_policy(host, protocol).await {
+            return decision;
+        }
+        if self.open_proxy {
+            return Ok(());
+        }
+
+        let Some(request_host) = self.parse_request_host(host) else {
+            return Err(DenialReason::InvalidHost);
         };
+        if self.check_request_host(&request_host).await {
+            Ok(())
+        } else {
+            self.add_to_unknown_hosts(request_host);
+            Err(DenialReason::NotAllowed)
+        }
+    }
 
-        if !allowed {
-            log::warn!("Blocked outbound connection to {host}, add it to allowed.list if needed",);
+    /// Returns `Ok` if the request is allowed by the policy or, if none of its rules applies,
+    /// if the host's root domain is in the `allowed_hosts` list (or if we're running as an open proxy).
+    ///
+    /// Note that the ip-based rules can only be applied to domains once they are resolved,
+    /// so the addresses to connect to have to be obtained with [`resolve_allowed`].
+    ///
+    /// Otherwise returns the reason for blocking the request. If the host wasn't explicitly
+    /// denied by the policy and is not on the allow lists, it's written to the `unknown_hosts` storefile.
+    pub(crate) async fn check(
+        &mut self,
+        host: &str,
+        protocol: Protocol,
+    ) -> Result<(), DenialReason> {
+        let decision = self.evaluate(host, protocol).await;
+        match &decision {
+            Err(DenialReason::NotAllowed) => log::warn!(
+                "Blocked outbound {protocol} connection to {host}, add it to allowed.list if needed"
+            ),
+            Err(reason) => log::warn!("Blocked outbound {protocol} connection to {host}: {reason}"),
+            Ok(_) => (),
         }
 
-        allowed
+        decision
     }
 
     fn trim_port(host: &str) -> String {
@@ -167,9 +273,54 @@ impl OutboundRequestFilter {
     }
 }
 
+/// Resolves the host and returns the addresses it can be reached at that are not denied by
+/// the policy. It is meant to be called for the requests that have already passed the
+/// [`OutboundRequestFilter::check`], so the addresses not matching any rule are allowed.
+pub(crate) async fn resolve_allowed(
+    policy: &StoredPolicy,
+    host: &str,
+    protocol: Protocol,
+) -> Result<Vec<SocketAddr>, DenialReason> {
+    let (requested_host, _) = OutboundRequestFilter::policy_target(host);
+    let addresses = match tokio::net::lookup_host(host).await {
+        Ok(addresses) => addresses.collect::<Vec<_>>(),
+        Err(err) => {
+            log::warn!("Failed to resolve {host}: {err}");
+            return Err(DenialReason::UnresolvableHost);
+        }
+    };
+
+    let policy = policy.get().await;
+    let mut denial = None;
+    let mut allowed = Vec::new();
+    for address in addresses {
+        let resolved = match requested_host {
+            RequestedHost::Domain(domain) => RequestedHost::Resolved {
+                domain,
+                address: address.ip(),
+            },
+            requested => requested,
+        };
+        match policy.evaluate(&resolved, Some(address.port()), protocol) {
+            Some(rule) if rule.action() == Action::Deny => {
+                denial.get_or_insert_with(|| DenialReason::PolicyRule(rule.to_string()));
+            }
+            _ => allowed.push(address),
+        }
+    }
+
+    if allowed.is_empty() {
+        let reason = denial.unwrap_or(DenialReason::UnresolvableHost);
+        log::warn!("Blocked outbound {protocol} connection to {host}: {reason}");
+        return Err(reason);
+    }
+    Ok(allowed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::allowed_hosts::policy::Policy;
     use std::ops::{Deref, DerefMut};
 
     struct HostsStoreFixture {
@@ -232,7 +383,12 @@ mod tests {
             let unknown = HostsStore::new(&unknown_tmp_file);
             let standard = StandardList::new();
 
-            let inner = OutboundRequestFilter::new(allowed.into(), standard, unknown);
+            let inner = OutboundRequestFilter::new(
+                Policy::default().into(),
+                allowed.into(),
+                standard,
+                unknown,
+            );
             OutboundRequestFilterFixture {
                 inner,
                 _allow_tmp_file: allow_tmp_file,
@@ -256,7 +412,12 @@ mod tests {
             allowed_store.add_host(allow)
         }
 
-        let inner = OutboundRequestFilter::new(allowed_store.into(), standard, unknown);
+        let inner = OutboundRequestFilter::new(
+            Policy::default().into(),
+            allowed_store.into(),
+            standard,
+            unknown,
+        );
         OutboundRequestFilterFixture {
             inner,
             _allow_tmp_file: allow_tmp_file,
@@ -345,17 +506,17 @@ mod tests {
         async fn are_not_allowed() {
             let host = "unknown.com";
             let mut filter = setup_empty();
-            assert!(!filter.check(host).await);
+            assert!(filter.check(host, Protocol::Tcp).await.is_err());
         }
 
         #[tokio::test]
         async fn get_appended_once_to_the_unknown_hosts_list() {
             let host = "unknown.com";
             let mut filter = setup_empty();
-            filter.check(host).await;
+            let _ = filter.check(host, Protocol::Tcp).await;
             assert_eq!(1, filter.unknown_hosts.data.domains.len());
             assert!(filter.unknown_hosts.data.domains.contains("unknown.com"));
-            filter.check(host).await;
+            let _ = filter.check(host, Protocol::Tcp).await;
             assert_eq!(1, filter.unknown_hosts.data.domains.len());
             assert!(filter.unknown_hosts.data.domains.contains("unknown.com"));
         }
@@ -370,7 +531,7 @@ mod tests {
             let host = "nymtech.net";
 
             let mut filter = setup_with_allowed(&["nymtech.net"]);
-            assert!(filter.check(host).await);
+            assert!(filter.check(host, Protocol::Tcp).await.is_ok());
         }
 
         #[tokio::test]
@@ -378,7 +539,7 @@ mod tests {
             let host = "foomp.nymtech.net";
 
             let mut filter = setup_with_allowed(&["nymtech.net"]);
-            assert!(filter.check(host).await);
+            assert!(filter.check(host, Protocol::Tcp).await.is_ok());
         }
 
         #[tokio::test]
@@ -391,7 +552,7 @@ mod tests {
                     .unwrap();
             assert_eq!(1, lines.len());
 
-            assert!(filter.check("nymtech.net").await);
+            assert!(filter.check("nymtech.net", Protocol::Tcp).await.is_ok());
 
             // test state after we've checked to make sure no unexpected changes
             let lines =
@@ -407,9 +568,9 @@ mod tests {
             let address_bad = "1.1.1.2";
 
             let mut filter = setup_with_allowed(&["1.1.1.1"]);
-            assert!(filter.check(address_good).await);
-            assert!(filter.check(address_good_port).await);
-            assert!(!filter.check(address_bad).await);
+            assert!(filter.check(address_good, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(address_good_port, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(address_bad, Protocol::Tcp).await.is_err());
         }
 
         #[tokio::test]
@@ -427,19 +588,37 @@ mod tests {
             let mut filter2 =
                 setup_with_allowed(&[ip_v6_full_rendered, ip_v6_semi_rendered, "::1"]);
 
-            assert!(filter1.check(ip_v6_full).await);
-            assert!(filter1.check(ip_v6_full_rendered).await);
-            assert!(filter1.check(ip_v6_full_port).await);
-            assert!(filter1.check(ip_v6_semi).await);
-            assert!(filter1.check(ip_v6_semi_rendered).await);
-            assert!(filter1.check(ip_v6_loopback_port).await);
-
-            assert!(filter2.check(ip_v6_full).await);
-            assert!(filter2.check(ip_v6_full_rendered).await);
-            assert!(filter2.check(ip_v6_full_port).await);
-            assert!(filter2.check(ip_v6_semi).await);
-            assert!(filter2.check(ip_v6_semi_rendered).await);
-            assert!(filter2.check(ip_v6_loopback_port).await);
+            assert!(filter1.check(ip_v6_full, Protocol::Tcp).await.is_ok());
+            assert!(filter1
+                .check(ip_v6_full_rendered, Protocol::Tcp)
+                .await
+                .is_ok());
+            assert!(filter1.check(ip_v6_full_port, Protocol::Tcp).await.is_ok());
+            assert!(filter1.check(ip_v6_semi, Protocol::Tcp).await.is_ok());
+            assert!(filter1
+                .check(ip_v6_semi_rendered, Protocol::Tcp)
+                .await
+                .is_ok());
+            assert!(filter1
+                .check(ip_v6_loopback_port, Protocol::Tcp)
+                .await
+                .is_ok());
+
+            assert!(filter2.check(ip_v6_full, Protocol::Tcp).await.is_ok());
+            assert!(filter2
+                .check(ip_v6_full_rendered, Protocol::Tcp)
+                .await
+                .is_ok());
+            assert!(filter2.check(ip_v6_full_port, Protocol::Tcp).await.is_ok());
+            assert!(filter2.check(ip_v6_semi, Protocol::Tcp).await.is_ok());
+            assert!(filter2
+                .check(ip_v6_semi_rendered, Protocol::Tcp)
+                .await
+                .is_ok());
+            assert!(filter2
+                .check(ip_v6_loopback_port, Protocol::Tcp)
+                .await
+                .is_ok());
         }
 
         #[tokio::test]
@@ -453,11 +632,11 @@ mod tests {
             let outside_range2 = "1.2.2.4";
 
             let mut filter = setup_with_allowed(&[range1, range2]);
-            assert!(filter.check("127.0.0.1").await);
-            assert!(filter.check("127.0.0.1:1234").await);
-            assert!(filter.check(bottom_range2).await);
-            assert!(filter.check(top_range2).await);
-            assert!(!filter.check(outside_range2).await);
+            assert!(filter.check("127.0.0.1", Protocol::Tcp).await.is_ok());
+            assert!(filter.check("127.0.0.1:1234", Protocol::Tcp).await.is_ok());
+            assert!(filter.check(bottom_range2, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(top_range2, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(outside_range2, Protocol::Tcp).await.is_err());
         }
 
         #[tokio::test]
@@ -471,10 +650,104 @@ mod tests {
             let mid = "2620:0:42::42";
 
             let mut filter = setup_with_allowed(&[range]);
-            assert!(filter.check(bottom1).await);
-            assert!(filter.check(bottom2).await);
-            assert!(filter.check(top).await);
-            assert!(filter.check(mid).await);
+            assert!(filter.check(bottom1, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(bottom2, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(top, Protocol::Tcp).await.is_ok());
+            assert!(filter.check(mid, Protocol::Tcp).await.is_ok());
+        }
+    }
+
+    #[cfg(test)]
+    mod requests_matching_policy_rules {
+        use super::*;
+
+        fn setup_with_policy(policy: &str, allowed: &[&str]) -> OutboundRequestFilterFixture {
+            let mut filter = setup_with_allowed(allowed);
+            filter.policy = Policy::parse(policy).unwrap().into();
+            filter
+        }
+
+        #[tokio::test]
+        async fn are_denied_even_if_allow_listed() {
+            let mut filter = setup_with_policy("deny * ports=25 proto=tcp", &["nymtech.net"]);
+
+            let reason = filter
+                .check("smtp.nymtech.net:25", Protocol::Tcp)
+                .await
+                .unwrap_err();
+            assert_eq!(
+                reason,
+                DenialReason::PolicyRule("'deny * ports=25 proto=tcp' (line 1)".to_string())
+            );
+
+            assert!(filter.check("nymtech.net:25", Protocol::Udp).await.is_ok());
+            assert!(filter.check("nymtech.net:443", Protocol::Tcp).await.is_ok());
+        }
+
+        #[tokio::test]
+        async fn are_allowed_without_being_allow_listed() {
+            let mut filter = setup_with_policy("deny 10.0.0.0/8\nallow *", &[]);
+
+            assert!(filter.check("unknown.com:443", Protocol::Tcp).await.is_ok());
+            assert!(filter.check("1.1.1.1:53", Protocol::Udp).await.is_ok());
+            assert!(filter.unknown_hosts.data.domains.is_empty());
+
+            let reason = filter.check("10.1.1.1", Protocol::Tcp).await.unwrap_err();
+            assert_eq!(
+                reason,
+                DenialReason::PolicyRule("'deny 10.0.0.0/8' (line 1)".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn fall_back_to_the_allow_list_if_no_rule_matches() {
+            let mut filter = setup_with_policy("deny *.evil.com", &["nymtech.net"]);
+
+            assert!(filter.check("nymtech.net", Protocol::Tcp).await.is_ok());
+            assert_eq!(
+                filter.check("unknown.com", Protocol::Tcp).await,
+                Err(DenialReason::NotAllowed)
+            );
+            assert!(matches!(
+                filter.check("foo.evil.com", Protocol::Tcp).await,
+                Err(DenialReason::PolicyRule(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn are_denied_in_open_proxy_mode() {
+            let mut filter = setup_with_policy("deny *.evil.com", &[]);
+            filter.open_proxy = true;
+
+            assert!(filter.check("unknown.com:443", Protocol::Tcp).await.is_ok());
+            assert!(filter.unknown_hosts.data.domains.is_empty());
+            assert!(matches!(
+                filter.check("foo.evil.com:443", Protocol::Tcp).await,
+                Err(DenialReason::PolicyRule(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn ip_rules_apply_to_resolved_domains() {
+            let mut filter = setup_with_policy("deny 127.0.0.0/8\ndeny ::1/128\nallow *", &[]);
+
+            // the domain itself is fine, but it resolves to a denied range
+            assert!(filter.check("localhost:80", Protocol::Tcp).await.is_ok());
+            assert!(matches!(
+                resolve_allowed(filter.policy(), "localhost:80", Protocol::Tcp).await,
+                Err(DenialReason::PolicyRule(_))
+            ));
+            assert!(matches!(
+                resolve_allowed(filter.policy(), "127.0.0.1:80", Protocol::Tcp).await,
+                Err(DenialReason::PolicyRule(_))
+            ));
+
+            let filter = setup_with_policy("deny 10.0.0.0/8\nallow *", &[]);
+            let resolved = resolve_allowed(filter.policy(), "localhost:80", Protocol::Tcp)
+                .await
+                .unwrap();
+            assert!(!resolved.is_empty());
+            assert!(resolved.iter().all(|address| address.ip().is_loopback()));
         }
     }
 
@@ -5,9 +5,11 @@ mod filter;
 mod group;
 mod host;
 mod hosts;
+pub(crate) mod policy;
 pub(crate) mod standard_list;
 pub(crate) mod stored_allowed_hosts;
 
-pub(crate) use filter::OutboundRequestFilter;
+pub(crate) use filter::{resolve_allowed, DenialReason, OutboundRequestFilter};
 pub(crate) use hosts::HostsStore;
+pub(crate) use policy::{Protocol, StoredPolicy};
 pub(crate) use standard_list::StandardList;
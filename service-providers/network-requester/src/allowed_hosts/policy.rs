@@ -0,0 +1,736 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured allow / deny rules for the outbound requests.
+//!
+//! The policy file consists of rules, one per line, evaluated from top to bottom.
+//! The first rule matching the request decides its fate. If none of them matches,
+//! the request is checked against the `allowed.list` and the standard list as before.
+//!
+//! Each rule has the form `<allow|deny> <host> [ports=<ports>] [proto=<tcp|udp>]`, where:
+//! - `host` is either `*` (any host), an ip address, a CIDR range (e.g. `10.0.0.0/8`) or
+//!   a domain glob (e.g. `*.nymtech.net`), where `*` matches any sequence of characters,
+//! - `ports` is a comma-separated list of ports or inclusive port ranges (e.g. `25,465,587`
+//!   or `1-1023`). If omitted, the rule applies to all ports,
+//! - `proto` restricts the rule to either tcp or udp traffic. If omitted, it applies to both.
+//!
+//! Empty lines and anything following a `#` are ignored. For example:
+//! ```text
+//! # don't let anyone send spam through us
+//! deny * ports=25,465,587 proto=tcp
+//! deny 10.0.0.0/8
+//! deny 192.168.0.0/16
+//! allow *
+//! ```
+//!
+//! Requests made to domains are evaluated twice: first by their name and then, once resolved,
+//! against each of the addresses they resolve to, in which case both the domain and the ip-based
+//! rules apply. The connection is only ever made to an address that passed the policy,
+//! so for example `deny 10.0.0.0/8` also applies to any domain resolving into that range.
+
+use async_file_watcher::{AsyncFileWatcher, FileWatcherEventReceiver};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use ipnetwork::IpNetwork;
+use nym_task::TaskClient;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+/// How often the policy file gets checked for changes if it can't be watched.
+const POLICY_POLLING_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("failed to access the policy file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid policy rule at line {line}: {reason}")]
+    InvalidRule { line: usize, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tcp" => Ok(Protocol::Tcp),
+            "udp" => Ok(Protocol::Udp),
+            other => Err(format!("unknown protocol '{other}'")),
+        }
+    }
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    Allow,
+    Deny,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "allow" => Ok(Action::Allow),
+            "deny" => Ok(Action::Deny),
+            other => Err(format!("unknown action '{other}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PortRange {
+    start: u16,
+    end: u16,
+}
+
+impl PortRange {
+    fn contains(&self, port: u16) -> bool {
+        self.start <= port && port <= self.end
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_port = |raw: &str| {
+            raw.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("invalid port '{raw}'"))
+        };
+
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (parse_port(start)?, parse_port(end)?),
+            None => {
+                let port = parse_port(s)?;
+                (port, port)
+            }
+        };
+        if start > end {
+            return Err(format!("invalid port range '{s}'"));
+        }
+
+        Ok(PortRange { start, end })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    Any,
+    IpNetwork(IpNetwork),
+    DomainGlob(String),
+}
+
+impl HostPattern {
+    fn matches(&self, host: &RequestedHost<'_>) -> bool {
+        match (self, host) {
+            (HostPattern::Any, _) => true,
+            (HostPattern::IpNetwork(network), RequestedHost::Ip(address))
+            | (HostPattern::IpNetwork(network), RequestedHost::Resolved { address, .. }) => {
+                network.contains(*address)
+            }
+            (HostPattern::DomainGlob(glob), RequestedHost::Domain(domain))
+            | (HostPattern::DomainGlob(glob), RequestedHost::Resolved { domain, .. }) => {
+                glob_matches(glob, &domain.to_ascii_lowercase())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for HostPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "*" {
+            Ok(HostPattern::Any)
+        } else if let Ok(network) = s.parse() {
+            Ok(HostPattern::IpNetwork(network))
+        } else if s.contains('/') || s.contains(':') {
+            Err(format!("invalid host '{s}'"))
+        } else {
+            Ok(HostPattern::DomainGlob(s.to_ascii_lowercase()))
+        }
+    }
+}
+
+/// Checks whether the value matches the glob, where `*` stands for any, possibly empty,
+/// sequence of characters.
+fn glob_matches(glob: &str, value: &str) -> bool {
+    let mut parts = glob.split('*');
+    // SAFETY: split always returns at least a single element
+    let first = parts.next().unwrap();
+    let Some(mut remaining) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // there were no wildcards, so the whole value had to match exactly
+        return remaining.is_empty();
+    };
+
+    for part in middle {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+/// The host the request is made to, as seen by the policy.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum RequestedHost<'a> {
+    Ip(IpAddr),
+    Domain(&'a str),
+    /// Domain alongside one of the addresses it resolved to.
+    Resolved {
+        domain: &'a str,
+        address: IpAddr,
+    },
+}
+
+/// Single allow or deny rule of the policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PolicyRule {
+    action: Action,
+    host: HostPattern,
+    ports: Option<Vec<PortRange>>,
+    protocol: Option<Protocol>,
+
+    // used for explaining the decisions
+    line: usize,
+    raw: String,
+}
+
+impl PolicyRule {
+    fn parse(line: usize, raw: &str) -> Result<Self, PolicyError> {
+        let invalid = |reason: String| PolicyError::InvalidRule { line, reason };
+
+        let mut tokens = raw.split_whitespace();
+        let action = tokens
+            .next()
+            .ok_or_else(|| invalid("missing action".to_string()))?
+            .parse()
+            .map_err(invalid)?;
+        let host = tokens
+            .next()
+            .ok_or_else(|| invalid("missing host".to_string()))?
+            .parse()
+            .map_err(invalid)?;
+
+        let mut ports = None;
+        let mut protocol = None;
+        for token in tokens {
+            match token.split_once('=') {
+                Some(("ports" | "port", value)) => {
+                    let ranges = value
+                        .split(',')
+                        .map(PortRange::from_str)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(invalid)?;
+                    ports = Some(ranges);
+                }
+                Some(("proto" | "protocol", value)) => {
+                    protocol = Some(value.parse().map_err(invalid)?);
+                }
+                _ => return Err(invalid(format!("unexpected token '{token}'"))),
+            }
+        }
+
+        Ok(PolicyRule {
+            action,
+            host,
+            ports,
+            protocol,
+            line,
+            raw: raw.to_string(),
+        })
+    }
+
+    fn matches(&self, host: &RequestedHost<'_>, port: Option<u16>, protocol: Protocol) -> bool {
+        if let Some(rule_protocol) = self.protocol {
+            if rule_protocol != protocol {
+                return false;
+            }
+        }
+
+        if let Some(ports) = &self.ports {
+            // if we don't know the port, we can't claim the port-specific rule applies
+            let Some(port) = port else {
+                return false;
+            };
+            if !ports.iter().any(|range| range.contains(port)) {
+                return false;
+            }
+        }
+
+        self.host.matches(host)
+    }
+
+    pub(crate) fn action(&self) -> Action {
+        self.action
+    }
+}
+
+impl Display for PolicyRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' (line {})", self.raw, self.line)
+    }
+}
+
+/// Ordered list of allow and deny rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Policy {
+    rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    pub(crate) fn parse(raw: &str) -> Result<Self, PolicyError> {
+        let mut rules = Vec::new();
+        for (idx, line) in raw.lines().enumerate() {
+            // strip the comments
+            let rule = line.split('#').next().unwrap_or_default().trim();
+            if rule.is_empty() {
+                continue;
+            }
+            rules.push(PolicyRule::parse(idx + 1, rule)?);
+        }
+
+        Ok(Policy { rules })
+    }
+
+    pub(crate) fn load<P: AsRef<Path>>(path: P) -> Result<Self, PolicyError> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path).map_err(|source| PolicyError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&raw)
+    }
+
+    /// Returns the first rule matching the request, if any.
+    pub(crate) fn evaluate(
+        &self,
+        host: &RequestedHost<'_>,
+        port: Option<u16>,
+        protocol: Protocol,
+    ) -> Option<&PolicyRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(host, port, protocol))
+    }
+}
+
+/// File-backed policy that can be reloaded whenever the underlying file changes.
+#[derive(Debug, Clone)]
+pub(crate) struct StoredPolicy {
+    path: PathBuf,
+    inner: Arc<RwLock<Policy>>,
+
+    /// Incremented on every successful reload so that any cached decisions could be invalidated.
+    generation: Arc<AtomicU64>,
+}
+
+impl StoredPolicy {
+    /// Loads the policy from the provided file. If the file does not exist, an empty one
+    /// is created, i.e. one that leaves all the decisions to the allow lists.
+    pub(crate) fn new<P: AsRef<Path>>(path: P) -> Result<Self, PolicyError> {
+        let path = path.as_ref().to_path_buf();
+        let io_err = |source| PolicyError::Io {
+            path: path.clone(),
+            source,
+        };
+
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(io_err)?;
+            }
+            fs::write(&path, "").map_err(io_err)?;
+        }
+
+        let policy = Policy::load(&path)?;
+        Ok(StoredPolicy {
+            path,
+            inner: Arc::new(RwLock::new(policy)),
+            generation: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Attempts to reload the policy. If the new content is invalid, the old policy is kept.
+    pub(crate) async fn reload(&self) -> Result<(), PolicyError> {
+        log::debug!("reloading the outbound request policy");
+        let policy = Policy::load(&self.path)?;
+        *self.inner.write().await = policy;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    /// Returns the number of times the policy got reloaded.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub(crate) async fn get(&self) -> RwLockReadGuard<'_, Policy> {
+        self.inner.read().await
+    }
+}
+
+impl From<Policy> for StoredPolicy {
+    fn from(policy: Policy) -> Self {
+        StoredPolicy {
+            path: PathBuf::new(),
+            inner: Arc::new(RwLock::new(policy)),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+pub(crate) struct StoredPolicyReloader {
+    stored_policy: StoredPolicy,
+    events_receiver: FileWatcherEventReceiver,
+
+    // Listens to shutdown commands from higher up
+    shutdown_listener: TaskClient,
+}
+
+impl StoredPolicyReloader {
+    pub(crate) fn new(
+        stored_policy: StoredPolicy,
+        events_receiver: FileWatcherEventReceiver,
+        shutdown_listener: TaskClient,
+    ) -> Self {
+        StoredPolicyReloader {
+            stored_policy,
+            events_receiver,
+            shutdown_listener,
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        while !self.shutdown_listener.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown_listener.recv() => {
+                    log::trace!("StoredPolicyReloader: Received shutdown");
+                }
+                event = self.events_receiver.next() => {
+                    let Some(event) = event else {
+                        log::trace!("StoredPolicyReloader: sender channel has terminated");
+                        break
+                    };
+                    log::debug!("the policy file has changed - {event:?}");
+                    if let Err(err) = self.stored_policy.reload().await {
+                        log::error!("failed to reload the policy, the previous one is still in use: {err}")
+                    }
+                }
+            }
+        }
+
+        log::debug!("StoredPolicyReloader: Exiting");
+    }
+
+    pub(crate) fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+}
+
+/// Reloads the policy whenever the modification time of its file changes. Used in place of
+/// the `StoredPolicyReloader` if the file can't be watched for changes.
+pub(crate) struct PeriodicPolicyReloader {
+    stored_policy: StoredPolicy,
+    polling_interval: Duration,
+    last_modified: Option<SystemTime>,
+
+    // Listens to shutdown commands from higher up
+    shutdown_listener: TaskClient,
+}
+
+impl PeriodicPolicyReloader {
+    pub(crate) fn new(
+        stored_policy: StoredPolicy,
+        polling_interval: Duration,
+        shutdown_listener: TaskClient,
+    ) -> Self {
+        let last_modified = file_modified(&stored_policy.path);
+        PeriodicPolicyReloader {
+            stored_policy,
+            polling_interval,
+            last_modified,
+            shutdown_listener,
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        // the policy has just been loaded, so there's no point in checking it straight away
+        let start = tokio::time::Instant::now() + self.polling_interval;
+        let mut polling_interval = tokio::time::interval_at(start, self.polling_interval);
+
+        while !self.shutdown_listener.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown_listener.recv() => {
+                    log::trace!("PeriodicPolicyReloader: Received shutdown");
+                }
+                _ = polling_interval.tick() => {
+                    let modified = file_modified(&self.stored_policy.path);
+                    if modified == self.last_modified {
+                        continue;
+                    }
+                    self.last_modified = modified;
+                    log::debug!("the policy file has changed");
+                    if let Err(err) = self.stored_policy.reload().await {
+                        log::error!("failed to reload the policy, the previous one is still in use: {err}")
+                    }
+                }
+            }
+        }
+
+        log::debug!("PeriodicPolicyReloader: Exiting");
+    }
+
+    pub(crate) fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub(crate) fn start_policy_reloader(stored_policy: StoredPolicy, shutdown_listener: TaskClient) {
+    let (events_sender, events_receiver) = mpsc::unbounded();
+
+    let watcher = match AsyncFileWatcher::new_file_changes_watcher(
+        stored_policy.path.clone(),
+        events_sender,
+    ) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            log::error!(
+                "failed to watch the policy file for changes: {err}. It's going to be checked every {}s instead",
+                POLICY_POLLING_INTERVAL.as_secs()
+            );
+            PeriodicPolicyReloader::new(stored_policy, POLICY_POLLING_INTERVAL, shutdown_listener)
+                .start();
+            return;
+        }
+    };
+    let reloader =
+        StoredPolicyReloader::new(stored_policy, events_receiver, shutdown_listener.clone());
+
+    super::stored_allowed_hosts::start_watcher(watcher, shutdown_listener);
+    reloader.start()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(
+        policy: &Policy,
+        host: &str,
+        port: Option<u16>,
+        protocol: Protocol,
+    ) -> Option<usize> {
+        let requested = match host.parse() {
+            Ok(ip) => RequestedHost::Ip(ip),
+            Err(_) => RequestedHost::Domain(host),
+        };
+        policy
+            .evaluate(&requested, port, protocol)
+            .map(|rule| rule.line)
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches("nymtech.net", "nymtech.net"));
+        assert!(!glob_matches("nymtech.net", "foo.nymtech.net"));
+        assert!(glob_matches("*.nymtech.net", "foo.nymtech.net"));
+        assert!(glob_matches("*.nymtech.net", "foo.bar.nymtech.net"));
+        assert!(!glob_matches("*.nymtech.net", "nymtech.net"));
+        assert!(glob_matches("mail.*.com", "mail.example.com"));
+        assert!(!glob_matches("mail.*.com", "mail.example.org"));
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("a*b*c", "abc"));
+        assert!(!glob_matches("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn parsing_rules() {
+        let policy = Policy::parse(
+            r#"
+            # comment
+            deny * ports=25,465,587 proto=tcp # no smtp
+            deny 10.0.0.0/8
+
+            allow *.nymtech.net port=1-1024
+            allow *
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.rules.len(), 4);
+        assert_eq!(policy.rules[0].line, 3);
+        assert_eq!(policy.rules[0].action(), Action::Deny);
+        assert_eq!(policy.rules[0].host, HostPattern::Any);
+        assert_eq!(policy.rules[0].protocol, Some(Protocol::Tcp));
+        assert_eq!(policy.rules[0].ports.as_ref().unwrap().len(), 3);
+        assert_eq!(
+            policy.rules[1].host,
+            HostPattern::IpNetwork("10.0.0.0/8".parse().unwrap())
+        );
+        assert_eq!(
+            policy.rules[2].host,
+            HostPattern::DomainGlob("*.nymtech.net".to_string())
+        );
+
+        for invalid in [
+            "reject *",
+            "allow",
+            "allow * ports=",
+            "allow * ports=100-10",
+            "allow * ports=70000",
+            "allow * proto=icmp",
+            "allow * foo",
+            "deny 10.0.0.0/33",
+        ] {
+            assert!(
+                matches!(
+                    Policy::parse(invalid),
+                    Err(PolicyError::InvalidRule { line: 1, .. })
+                ),
+                "{invalid} should have been rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let policy = Policy::parse(
+            r#"
+            allow smtp.nymtech.net ports=25
+            deny * ports=25,465,587 proto=tcp
+            deny 10.0.0.0/8
+            deny 192.168.0.0/16 proto=udp
+            allow *
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            decision(&policy, "smtp.nymtech.net", Some(25), Protocol::Tcp),
+            Some(2)
+        );
+        assert_eq!(
+            decision(&policy, "mail.example.com", Some(587), Protocol::Tcp),
+            Some(3)
+        );
+        assert_eq!(
+            decision(&policy, "mail.example.com", Some(587), Protocol::Udp),
+            Some(6)
+        );
+        assert_eq!(
+            decision(&policy, "10.1.2.3", Some(443), Protocol::Tcp),
+            Some(4)
+        );
+        assert_eq!(
+            decision(&policy, "192.168.1.1", Some(53), Protocol::Tcp),
+            Some(6)
+        );
+        assert_eq!(
+            decision(&policy, "192.168.1.1", Some(53), Protocol::Udp),
+            Some(5)
+        );
+        // port-specific rules don't apply if the port is unknown
+        assert_eq!(
+            decision(&policy, "mail.example.com", None, Protocol::Tcp),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn resolved_domains_are_matched_by_both_domain_and_ip_rules() {
+        let policy = Policy::parse(
+            r#"
+            allow *.nymtech.net
+            deny 10.0.0.0/8
+            allow *
+            "#,
+        )
+        .unwrap();
+        let resolved = |domain, address: &str| RequestedHost::Resolved {
+            domain,
+            address: address.parse().unwrap(),
+        };
+
+        let decision = |host| {
+            policy
+                .evaluate(&host, Some(443), Protocol::Tcp)
+                .map(|rule| rule.line)
+        };
+        assert_eq!(decision(resolved("foo.nymtech.net", "10.0.0.1")), Some(2));
+        assert_eq!(decision(resolved("10.0.0.1.nip.io", "10.0.0.1")), Some(3));
+        assert_eq!(decision(resolved("10.0.0.1.nip.io", "1.1.1.1")), Some(4));
+        // before the domain is resolved, the ip rules do not apply
+        assert_eq!(decision(RequestedHost::Domain("10.0.0.1.nip.io")), Some(4));
+    }
+
+    #[test]
+    fn empty_policy_matches_nothing() {
+        let policy = Policy::parse("# nothing to see here").unwrap();
+        assert_eq!(
+            decision(&policy, "nymtech.net", Some(443), Protocol::Tcp),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn stored_policy_keeps_old_rules_on_invalid_reload() {
+        let tmp_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(tmp_file.path(), "deny *").unwrap();
+
+        let stored = StoredPolicy::new(tmp_file.path()).unwrap();
+        assert_eq!(stored.get().await.rules.len(), 1);
+        assert_eq!(stored.generation(), 0);
+
+        fs::write(tmp_file.path(), "deny *\nallow nymtech.net").unwrap();
+        stored.reload().await.unwrap();
+        assert_eq!(stored.get().await.rules.len(), 2);
+        assert_eq!(stored.generation(), 1);
+
+        fs::write(tmp_file.path(), "nonsense").unwrap();
+        assert!(stored.reload().await.is_err());
+        assert_eq!(stored.get().await.rules.len(), 2);
+        assert_eq!(stored.generation(), 1);
+    }
+}
@@ -106,7 +106,7 @@ async fn run_watcher(mut watcher: AsyncFileWatcher, mut shutdown: TaskClient) {
     log::debug!("AsyncFileWatcher: Exiting");
 }
 
-fn start_watcher(watcher: AsyncFileWatcher, shutdown: TaskClient) {
+pub(super) fn start_watcher(watcher: AsyncFileWatcher, shutdown: TaskClient) {
     tokio::spawn(async move { run_watcher(watcher, shutdown).await });
 }
 
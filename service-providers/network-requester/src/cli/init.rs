@@ -37,6 +37,11 @@ pub(crate) struct Init {
     #[clap(long)]
     force_register_gateway: bool,
 
+    /// Number of additional gateways to register with that the client is going to fail over to
+    /// if its main gateway becomes unreachable.
+    #[clap(long, default_value_t = 0)]
+    fallback_gateways: usize,
+
     /// Comma separated list of rest endpoints of the nyxd validators
     #[clap(long, alias = "nymd_validators", value_delimiter = ',')]
     nyxd_urls: Option<Vec<url::Url>>,
@@ -142,6 +147,24 @@ pub(crate) async fn execute(args: &Init) -> Result<(), NetworkRequesterError> {
 
     config.get_base_mut().set_gateway_endpoint(gateway);
 
+    // Fallback gateways are only (re-)registered with alongside the main gateway
+    if register_gateway && args.fallback_gateways > 0 {
+        let fallback_gateways =
+            nym_client_core::init::setup_fallback_gateways::<_, PersistentStorage>(
+                config.get_base(),
+                args.fallback_gateways,
+                args.latency_based_selection,
+            )
+            .await
+            .map_err(|source| {
+                eprintln!("Failed to setup fallback gateways\nError: {source}");
+                NetworkRequesterError::FailedToSetupGateway { source }
+            })?;
+        config
+            .get_base_mut()
+            .set_fallback_gateway_endpoints(fallback_gateways);
+    }
+
     config.save_to_file(None).tap_err(|_| {
         log::error!("Failed to save the config file");
     })?;
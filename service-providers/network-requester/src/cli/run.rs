@@ -162,6 +162,6 @@ pub(crate) async fn execute(args: &Run) -> Result<(), NetworkRequesterError> {
         args.enable_statistics,
         stats_provider_addr,
     )
-    .await;
+    .await?;
     server.run_service_provider().await
 }
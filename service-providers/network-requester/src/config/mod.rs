@@ -15,6 +15,10 @@ pub use nym_client_core::config::{DebugConfig, GatewayEndpointConfig};
 
 pub const DEFAULT_STANDARD_LIST_UPDATE_INTERVAL: Duration = Duration::from_secs(30 * 60);
 
+pub const DEFAULT_MAX_CONCURRENT_CONNECTIONS: u32 = 64;
+pub const DEFAULT_MAX_NEW_CONNECTIONS_PER_MINUTE: u32 = 120;
+pub const DEFAULT_MAX_BYTES_PER_SECOND: u64 = 1024 * 1024;
+
 pub mod old_config_v1_1_13;
 mod template;
 
@@ -77,6 +81,13 @@ pub struct NetworkRequster {
 
     /// Location of the file containing our unknown.list
     pub unknown_list_location: PathBuf,
+
+    /// Location of the file containing the allow / deny rules for the outbound requests.
+    /// They take precedence over the allow.list
+    pub policy_location: PathBuf,
+
+    /// Limits imposed on each individual client of this network requester.
+    pub rate_limits: RateLimits,
 }
 
 impl Default for NetworkRequster {
@@ -87,6 +98,33 @@ impl Default for NetworkRequster {
                 .join("allowed.list"),
             unknown_list_location: <Config as NymConfig>::default_root_directory()
                 .join("unknown.list"),
+            policy_location: <Config as NymConfig>::default_root_directory().join("policy.list"),
+            rate_limits: RateLimits::default(),
+        }
+    }
+}
+
+/// Per-client limits, where a client is identified by either its explicit return address
+/// or its anonymous sender tag. A value of 0 disables the particular limit.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimits {
+    /// Maximum number of connections (and udp associations) a single client can have open at once.
+    pub max_concurrent_connections: u32,
+
+    /// Maximum number of new connections a single client can open within a minute.
+    pub max_new_connections_per_minute: u32,
+
+    /// Maximum number of bytes per second a single client can send and receive through this network requester.
+    pub max_bytes_per_second: u64,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        RateLimits {
+            max_concurrent_connections: DEFAULT_MAX_CONCURRENT_CONNECTIONS,
+            max_new_connections_per_minute: DEFAULT_MAX_NEW_CONNECTIONS_PER_MINUTE,
+            max_bytes_per_second: DEFAULT_MAX_BYTES_PER_SECOND,
         }
     }
 }
@@ -116,6 +154,7 @@ impl Config {
 
         cfg.network_requester.allowed_list_location = cfg.data_directory().join("allowed.list");
         cfg.network_requester.unknown_list_location = cfg.data_directory().join("unknown.list");
+        cfg.network_requester.policy_location = cfg.data_directory().join("policy.list");
         cfg
     }
 
@@ -137,6 +176,14 @@ impl Config {
         self.network_requester.unknown_list_location.clone()
     }
 
+    pub fn policy_file_location(&self) -> PathBuf {
+        self.network_requester.policy_location.clone()
+    }
+
+    pub fn rate_limits(&self) -> RateLimits {
+        self.network_requester.rate_limits
+    }
+
     pub fn get_base(&self) -> &BaseConfig<Self> {
         &self.base
     }
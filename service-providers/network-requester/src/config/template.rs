@@ -76,6 +76,15 @@ gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
 # Address of the gateway listener to which all client requests should be sent.
 gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
 
+# Ranked list of additional gateways the client has registered with. If the client fails
+# to reconnect to its current gateway, it switches to the first reachable one out of those.
+{{#each client.fallback_gateway_endpoints }}
+[[client.fallback_gateway_endpoints]]
+gateway_id = '{{this.gateway_id}}'
+gateway_owner = '{{this.gateway_owner}}'
+gateway_listener = '{{this.gateway_listener}}'
+{{/each}}
+
 ##### network requester specific config options #####
 
 [network_requester]
@@ -85,6 +94,17 @@ allowed_list_location = '{{ network_requester.allowed_list_location }}'
 # Location of the file containing our unknown.list
 unknown_list_location = '{{ network_requester.unknown_list_location }}'
 
+# Location of the file containing the allow / deny rules for the outbound requests.
+# They take precedence over the allow.list
+policy_location = '{{ network_requester.policy_location }}'
+
+# Limits imposed on each individual client of this network requester.
+# Setting any of the values to 0 disables the particular limit.
+[network_requester.rate_limits]
+max_concurrent_connections = {{ network_requester.rate_limits.max_concurrent_connections }}
+max_new_connections_per_minute = {{ network_requester.rate_limits.max_new_connections_per_minute }}
+max_bytes_per_second = {{ network_requester.rate_limits.max_bytes_per_second }}
+
 ##### logging configuration options #####
 
 [logging]
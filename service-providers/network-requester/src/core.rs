@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::allowed_hosts;
+use crate::allowed_hosts::policy::start_policy_reloader;
 use crate::allowed_hosts::standard_list::StandardListUpdater;
 use crate::allowed_hosts::stored_allowed_hosts::{start_allowed_list_reloader, StoredAllowedHosts};
-use crate::allowed_hosts::{OutboundRequestFilter, StandardList};
+use crate::allowed_hosts::{OutboundRequestFilter, Protocol, StandardList, StoredPolicy};
 use crate::config::Config;
 use crate::error::NetworkRequesterError;
+use crate::rate_limit::{RateLimiter, Throttled};
 use crate::reply::MixnetMessage;
+use crate::socks5::udp::AssociationHandle;
 use crate::statistics::ServiceStatisticsCollector;
 use crate::{reply, socks5};
 use async_trait::async_trait;
@@ -22,17 +25,20 @@ use nym_service_providers_common::ServiceProvider;
 use nym_socks5_proxy_helpers::connection_controller::{
     Controller, ControllerCommand, ControllerSender,
 };
-use nym_socks5_proxy_helpers::proxy_runner::{MixProxyReader, MixProxySender};
+use nym_socks5_proxy_helpers::proxy_runner::{MixProxyReader, MixProxySender, ReadThrottle};
 use nym_socks5_requests::{
-    ConnectRequest, ConnectionId, NetworkData, SendRequest, Socks5ProtocolVersion,
+    ConnectRequest, ConnectionId, DatagramRequest, NetworkData, SendRequest, Socks5ProtocolVersion,
     Socks5ProviderRequest, Socks5Request, Socks5RequestContent, Socks5Response,
+    UdpAssociateRequest,
 };
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_statistics_common::collector::StatisticsSender;
 use nym_task::connections::LaneQueueLengths;
 use nym_task::{TaskClient, TaskManager};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 // Since it's an atomic, it's safe to be kept static and shared across threads
 static ACTIVE_PROXIES: AtomicUsize = AtomicUsize::new(0);
@@ -49,20 +55,21 @@ pub(crate) fn new_legacy_request_version() -> RequestVersion<Socks5Request> {
 pub struct NRServiceProviderBuilder {
     config: Config,
     outbound_request_filter: OutboundRequestFilter,
-    open_proxy: bool,
     enable_statistics: bool,
     stats_provider_addr: Option<Recipient>,
     standard_list: StandardList,
     allowed_hosts: StoredAllowedHosts,
+    policy: StoredPolicy,
 }
 
 struct NRServiceProvider {
     outbound_request_filter: OutboundRequestFilter,
-    open_proxy: bool,
     mixnet_client: nym_sdk::mixnet::MixnetClient,
 
     controller_sender: ControllerSender,
     mix_input_sender: MixProxySender<MixnetMessage>,
+    udp_associations: HashMap<ConnectionId, AssociationHandle>,
+    rate_limiter: RateLimiter,
     //shared_lane_queue_lengths: LaneQueueLengths,
     stats_collector: Option<ServiceStatisticsCollector>,
     shutdown: TaskManager,
@@ -129,6 +136,25 @@ impl ServiceProvider<Socks5Request> for NRServiceProvider {
                     .await
             }
             Socks5RequestContent::Send(req) => {
+                let delay = match self
+                    .rate_limiter
+                    .try_send(sender, req.conn_id, req.data.len())
+                {
+                    Ok(delay) => delay,
+                    Err(throttled) => {
+                        // we can't just drop the data as it would corrupt the stream,
+                        // so close the connection instead
+                        self.close_throttled_connection(req.conn_id);
+                        self.reject_throttled_request(
+                            request_version,
+                            req.conn_id,
+                            None,
+                            throttled,
+                        )
+                        .await;
+                        return Ok(None);
+                    }
+                };
                 if let Some(stats_collector) = &self.stats_collector {
                     if let Some(remote_addr) = stats_collector
                         .connected_services
@@ -143,7 +169,41 @@ impl ServiceProvider<Socks5Request> for NRServiceProvider {
                             .processed(remote_addr, req.data.len() as u32);
                     }
                 }
-                self.handle_proxy_send(req)
+                self.handle_proxy_send(req, delay)
+            }
+            Socks5RequestContent::UdpAssociate(req) => {
+                self.handle_udp_associate(request_version, sender, req)
+                    .await
+            }
+            Socks5RequestContent::SendDatagram(req) => {
+                if let Err(throttled) =
+                    self.rate_limiter
+                        .try_send_datagram(sender, req.conn_id, req.data.len())
+                {
+                    self.reject_throttled_request(
+                        request_version,
+                        req.conn_id,
+                        Some(req.remote_addr.as_str()),
+                        throttled,
+                    )
+                    .await;
+                    return Ok(None);
+                }
+                let remote_addr = req.remote_addr.clone();
+                let data_len = req.data.len();
+                // only record the datagrams that actually got relayed
+                if self.handle_send_datagram(request_version, req).await {
+                    if let Some(stats_collector) = &self.stats_collector {
+                        stats_collector
+                            .request_stats_data
+                            .write()
+                            .await
+                            .processed(&remote_addr, data_len as u32);
+                    }
+                }
+            }
+            Socks5RequestContent::CloseAssociation(conn_id) => {
+                self.handle_close_association(conn_id)
             }
         }
 
@@ -157,24 +217,30 @@ impl NRServiceProviderBuilder {
         open_proxy: bool,
         enable_statistics: bool,
         stats_provider_addr: Option<Recipient>,
-    ) -> NRServiceProviderBuilder {
+    ) -> Result<NRServiceProviderBuilder, NetworkRequesterError> {
         let standard_list = StandardList::new();
 
+        let policy = StoredPolicy::new(config.policy_file_location())?;
         let allowed_hosts = StoredAllowedHosts::new(config.allow_list_file_location());
         let unknown_hosts = allowed_hosts::HostsStore::new(config.unknown_list_file_location());
 
-        let outbound_request_filter =
-            OutboundRequestFilter::new(allowed_hosts.clone(), standard_list.clone(), unknown_hosts);
+        let outbound_request_filter = OutboundRequestFilter::new(
+            policy.clone(),
+            allowed_hosts.clone(),
+            standard_list.clone(),
+            unknown_hosts,
+        )
+        .with_open_proxy(open_proxy);
 
-        NRServiceProviderBuilder {
+        Ok(NRServiceProviderBuilder {
             config,
             outbound_request_filter,
-            open_proxy,
             enable_statistics,
             stats_provider_addr,
             standard_list,
             allowed_hosts,
-        }
+            policy,
+        })
     }
 
     /// Start all subsystems
@@ -241,12 +307,16 @@ impl NRServiceProviderBuilder {
         // start the allowed.list watcher and updater
         start_allowed_list_reloader(self.allowed_hosts, shutdown.subscribe()).await;
 
+        // and the same for the policy file
+        start_policy_reloader(self.policy, shutdown.subscribe());
+
         let service_provider = NRServiceProvider {
             outbound_request_filter: self.outbound_request_filter,
-            open_proxy: self.open_proxy,
             mixnet_client,
             controller_sender,
             mix_input_sender,
+            udp_associations: HashMap::new(),
+            rate_limiter: RateLimiter::new(self.config.rate_limits()),
             //shared_lane_queue_lengths: mixnet_client.shared_lane_queue_lengths(),
             stats_collector,
             shutdown,
@@ -328,15 +398,38 @@ impl NRServiceProvider {
         remote_version: RequestVersion<Socks5Request>,
         connection_id: ConnectionId,
         remote_addr: String,
+        policy: StoredPolicy,
+        rate_limiter: RateLimiter,
+        sender_tag: Option<AnonymousSenderTag>,
         return_address: reply::MixnetAddress,
         controller_sender: ControllerSender,
         mix_input_sender: MixProxySender<MixnetMessage>,
         lane_queue_lengths: LaneQueueLengths,
         shutdown: TaskClient,
     ) {
+        // only ever connect to the addresses that are not denied by the policy
+        let addresses =
+            match allowed_hosts::resolve_allowed(&policy, &remote_addr, Protocol::Tcp).await {
+                Ok(addresses) => addresses,
+                Err(reason) => {
+                    let mixnet_message = MixnetMessage::new_connection_error(
+                        return_address,
+                        remote_version,
+                        connection_id,
+                        format!("Domain {remote_addr:?} failed filter check: {reason}"),
+                    );
+                    mix_input_sender
+                        .send(mixnet_message)
+                        .await
+                        .expect("InputMessageReceiver has stopped receiving!");
+                    return;
+                }
+            };
+
         let mut conn = match socks5::tcp::Connection::new(
             connection_id,
             remote_addr.clone(),
+            &addresses,
             return_address.clone(),
         )
         .await
@@ -382,12 +475,17 @@ impl NRServiceProvider {
             old_count + 1
         );
 
-        // run the proxy on the connection
+        // run the proxy on the connection, making sure the responses count towards
+        // the bandwidth allowance of the client
+        let read_throttle = ReadThrottle::new(move |conn_id, read| {
+            rate_limiter.record_response(sender_tag, conn_id, read)
+        });
         conn.run_proxy(
             remote_version,
             mix_receiver,
             mix_input_sender,
             lane_queue_lengths,
+            read_throttle,
             shutdown,
         )
         .await;
@@ -421,8 +519,13 @@ impl NRServiceProvider {
         let remote_addr = connect_req.remote_addr;
         let conn_id = connect_req.conn_id;
 
-        if !self.open_proxy && !self.outbound_request_filter.check(&remote_addr).await {
-            let log_msg = format!("Domain {remote_addr:?} failed filter check");
+        let filter_check = self
+            .outbound_request_filter
+            .check(&remote_addr, Protocol::Tcp)
+            .await;
+
+        if let Err(reason) = filter_check {
+            let log_msg = format!("Domain {remote_addr:?} failed filter check: {reason}");
             log::info!("{}", log_msg);
             let msg = MixnetMessage::new_connection_error(
                 return_address,
@@ -437,6 +540,30 @@ impl NRServiceProvider {
             return;
         }
 
+        let permit =
+            match self
+                .rate_limiter
+                .try_open_connection(&return_address, sender_tag, conn_id)
+            {
+                Ok(permit) => permit,
+                Err(reason) => {
+                    let throttled = Throttled {
+                        return_address,
+                        reason,
+                    };
+                    self.reject_throttled_request(
+                        remote_version,
+                        conn_id,
+                        Some(remote_addr.as_str()),
+                        throttled,
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+        let policy = self.outbound_request_filter.policy().clone();
+        let rate_limiter = self.rate_limiter.clone();
         let controller_sender_clone = self.controller_sender.clone();
         let mix_input_sender_clone = self.mix_input_sender.clone();
         let lane_queue_lengths_clone = self.mixnet_client.shared_lane_queue_lengths();
@@ -448,18 +575,234 @@ impl NRServiceProvider {
                 remote_version,
                 conn_id,
                 remote_addr,
+                policy,
+                rate_limiter,
+                sender_tag,
                 return_address,
                 controller_sender_clone,
                 mix_input_sender_clone,
                 lane_queue_lengths_clone,
                 shutdown,
             )
+            .await;
+
+            // the connection no longer counts towards the client's limits
+            drop(permit)
+        });
+    }
+
+    fn handle_proxy_send(&mut self, req: SendRequest, delay: Duration) {
+        if delay.is_zero() {
+            self.controller_sender.unbounded_send(req.into()).unwrap();
+            return;
+        }
+
+        // hold the data until the client's bandwidth allowance gets replenished.
+        // the controller puts everything back in order, so it's fine if any other data overtakes it
+        let controller_sender = self.controller_sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if controller_sender.unbounded_send(req.into()).is_err() {
+                log::debug!("failed to forward delayed data - the controller has stopped");
+            }
+        });
+    }
+
+    fn close_throttled_connection(&mut self, conn_id: ConnectionId) {
+        self.controller_sender
+            .unbounded_send(ControllerCommand::Close {
+                connection_id: conn_id,
+            })
+            .unwrap()
+    }
+
+    async fn reject_throttled_request(
+        &self,
+        remote_version: RequestVersion<Socks5Request>,
+        conn_id: ConnectionId,
+        remote_addr: Option<&str>,
+        throttled: Throttled,
+    ) {
+        if let Some(stats_collector) = &self.stats_collector {
+            let remote_addr = match remote_addr {
+                Some(remote_addr) => Some(remote_addr.to_string()),
+                None => stats_collector
+                    .connected_services
+                    .read()
+                    .await
+                    .get(&conn_id)
+                    .cloned(),
+            };
+            if let Some(remote_addr) = remote_addr {
+                stats_collector
+                    .request_stats_data
+                    .write()
+                    .await
+                    .throttled(&remote_addr);
+            }
+        }
+
+        let log_msg = format!(
+            "Request on connection {conn_id} got throttled: {}",
+            throttled.reason
+        );
+        log::info!("{}", log_msg);
+        self.send_connection_error(throttled.return_address, remote_version, conn_id, log_msg)
+            .await;
+    }
+
+    async fn send_connection_error(
+        &self,
+        return_address: reply::MixnetAddress,
+        remote_version: RequestVersion<Socks5Request>,
+        conn_id: ConnectionId,
+        error_message: String,
+    ) {
+        let msg = MixnetMessage::new_connection_error(
+            return_address,
+            remote_version,
+            conn_id,
+            error_message,
+        );
+        self.mix_input_sender
+            .send(msg)
             .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    async fn handle_udp_associate(
+        &mut self,
+        remote_version: RequestVersion<Socks5Request>,
+        sender_tag: Option<AnonymousSenderTag>,
+        associate_req: Box<UdpAssociateRequest>,
+    ) {
+        let Some(return_address) = reply::MixnetAddress::new(associate_req.return_address, sender_tag) else {
+            log::warn!(
+                "attempted to start udp association with no way of returning data back to the sender"
+            );
+            return;
+        };
+        let conn_id = associate_req.conn_id;
+
+        // get rid of any associations that have expired in the meantime
+        self.udp_associations
+            .retain(|_, handle| !handle.is_closed());
+
+        let permit =
+            match self
+                .rate_limiter
+                .try_open_connection(&return_address, sender_tag, conn_id)
+            {
+                Ok(permit) => permit,
+                Err(reason) => {
+                    let throttled = Throttled {
+                        return_address,
+                        reason,
+                    };
+                    self.reject_throttled_request(remote_version, conn_id, None, throttled)
+                        .await;
+                    return;
+                }
+            };
+
+        let (association, handle) = match socks5::udp::Association::new(
+            conn_id,
+            sender_tag,
+            return_address.clone(),
+            self.outbound_request_filter.policy().clone(),
+            self.rate_limiter.clone(),
+        )
+        .await
+        {
+            Ok(association) => association,
+            Err(err) => {
+                log::error!("failed to bind udp socket for association {conn_id}: {err}");
+                self.send_connection_error(
+                    return_address,
+                    remote_version,
+                    conn_id,
+                    format!("failed to start udp association: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        if self.udp_associations.insert(conn_id, handle).is_some() {
+            log::warn!("received a duplicate udp association request for {conn_id}");
+        }
+
+        let mix_input_sender_clone = self.mix_input_sender.clone();
+        let shutdown = self.shutdown.subscribe();
+
+        log::info!(
+            "Starting udp association {conn_id} (currently there are {} associations being handled)",
+            self.udp_associations.len()
+        );
+        tokio::spawn(async move {
+            association
+                .run(remote_version, mix_input_sender_clone, shutdown)
+                .await;
+            log::info!("Udp association {conn_id} is finished");
+
+            // the association no longer counts towards the client's limits
+            drop(permit)
         });
     }
 
-    fn handle_proxy_send(&mut self, req: SendRequest) {
-        self.controller_sender.unbounded_send(req.into()).unwrap()
+    async fn handle_send_datagram(
+        &mut self,
+        remote_version: RequestVersion<Socks5Request>,
+        datagram_req: DatagramRequest,
+    ) -> bool {
+        let conn_id = datagram_req.conn_id;
+        let remote_addr = datagram_req.remote_addr;
+
+        let Some(handle) = self.udp_associations.get_mut(&conn_id) else {
+            log::debug!("received a datagram for unknown udp association {conn_id}");
+            return false;
+        };
+
+        // every destination has to pass the filter check, but we only need to do it once
+        // (or rather once per version of the policy)
+        let policy_generation = self.outbound_request_filter.policy().generation();
+        if !handle.is_allowed(&remote_addr, policy_generation) {
+            let filter_check = self
+                .outbound_request_filter
+                .check(&remote_addr, Protocol::Udp)
+                .await;
+
+            if let Err(reason) = filter_check {
+                let log_msg = format!("Domain {remote_addr:?} failed filter check: {reason}");
+                log::info!("{}", log_msg);
+                let return_address = handle.return_address().clone();
+                self.send_connection_error(return_address, remote_version, conn_id, log_msg)
+                    .await;
+                return false;
+            }
+            handle.allow(remote_addr.clone(), policy_generation);
+        }
+
+        if !handle.send(remote_addr, datagram_req.data) {
+            let return_address = handle.return_address().clone();
+            self.udp_associations.remove(&conn_id);
+            self.send_connection_error(
+                return_address,
+                remote_version,
+                conn_id,
+                "the udp association has expired".to_string(),
+            )
+            .await;
+            return false;
+        }
+        true
+    }
+
+    fn handle_close_association(&mut self, conn_id: ConnectionId) {
+        // dropping the handle stops the association
+        if self.udp_associations.remove(&conn_id).is_none() {
+            log::debug!("attempted to close unknown udp association {conn_id}");
+        }
     }
 }
 
@@ -478,6 +821,7 @@ async fn create_mixnet_client<T>(
         .debug_config(debug_config)
         .enable_storage(storage_paths)
         .registered_gateway(config.get_gateway_endpoint_config().clone())
+        .fallback_gateways(config.get_fallback_gateway_endpoints().to_vec())
         .build::<nym_sdk::mixnet::ReplyStorage>()
         .await
         .map_err(|err| NetworkRequesterError::FailedToSetupMixnetClient { source: err })?;
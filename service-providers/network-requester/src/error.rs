@@ -1,3 +1,4 @@
+use crate::allowed_hosts::policy::PolicyError;
 use nym_client_core::error::ClientCoreError;
 use nym_socks5_requests::Socks5RequestError;
 
@@ -33,4 +34,7 @@ pub enum NetworkRequesterError {
 
     #[error("failed to connect to mixnet: {source}")]
     FailedToConnectToMixnet { source: nym_sdk::Error },
+
+    #[error("failed to load the outbound request policy: {0}")]
+    InvalidPolicy(#[from] PolicyError),
 }
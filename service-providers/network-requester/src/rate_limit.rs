@@ -0,0 +1,606 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-client limits on the traffic going through the network requester.
+//!
+//! Every client, identified either by its explicit return address or by its anonymous sender tag,
+//! gets its own budget of concurrent connections, new connections per minute and bytes per second,
+//! so that a single client can't starve all the others.
+//!
+//! The bandwidth budget covers the data going in both directions. Rather than dropping the data
+//! going over the budget (which would corrupt the stream), it gets delayed until the budget refills.
+
+use crate::config::RateLimits;
+use crate::reply::MixnetAddress;
+use nym_socks5_requests::ConnectionId;
+use nym_sphinx::addressing::clients::RecipientBytes;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const NEW_CONNECTIONS_WINDOW: Duration = Duration::from_secs(60);
+
+// allow clients to burst up to this many seconds worth of their bandwidth allowance
+const BANDWIDTH_BURST_SECS: u64 = 2;
+
+// allow clients to queue up to this many seconds worth of their bandwidth allowance
+// before their connections get closed
+const BANDWIDTH_BACKLOG_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitExceeded {
+    ConcurrentConnections { limit: u32 },
+    NewConnectionsPerMinute { limit: u32 },
+    BytesPerSecond { limit: u64 },
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::ConcurrentConnections { limit } => {
+                write!(f, "exceeded the limit of {limit} concurrent connections")
+            }
+            LimitExceeded::NewConnectionsPerMinute { limit } => {
+                write!(
+                    f,
+                    "exceeded the limit of {limit} new connections per minute"
+                )
+            }
+            LimitExceeded::BytesPerSecond { limit } => {
+                write!(f, "exceeded the limit of {limit} bytes per second")
+            }
+        }
+    }
+}
+
+/// Information about a request that went over the limits of its client.
+#[derive(Debug)]
+pub(crate) struct Throttled {
+    /// Address of the client that should be informed about the throttling.
+    pub(crate) return_address: MixnetAddress,
+    pub(crate) reason: LimitExceeded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientKey {
+    Known(RecipientBytes),
+    Anonymous(AnonymousSenderTag),
+}
+
+impl From<&MixnetAddress> for ClientKey {
+    fn from(address: &MixnetAddress) -> Self {
+        match address {
+            MixnetAddress::Known(recipient) => ClientKey::Known(recipient.to_bytes()),
+            MixnetAddress::Anonymous(sender_tag) => ClientKey::Anonymous(*sender_tag),
+        }
+    }
+}
+
+struct TokenBucket {
+    capacity: i64,
+    max_debt: i64,
+    refill_per_second: u64,
+    // goes negative when the data got accepted, but is yet to be paid off
+    tokens: i64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(refill_per_second: u64, now: Instant) -> Self {
+        let per_second = i64::try_from(refill_per_second).unwrap_or(i64::MAX);
+        let capacity = per_second.saturating_mul(BANDWIDTH_BURST_SECS as i64);
+        TokenBucket {
+            capacity,
+            max_debt: per_second.saturating_mul(BANDWIDTH_BACKLOG_SECS as i64),
+            refill_per_second,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled = (elapsed.as_secs_f64() * self.refill_per_second as f64) as i64;
+        if refilled > 0 {
+            self.tokens = self.tokens.saturating_add(refilled).min(self.capacity);
+            self.last_refill = now;
+        }
+    }
+
+    /// Consumes the tokens, going into debt if needed, and returns how long it will take
+    /// to pay it off.
+    fn consume(&mut self, amount: u64, now: Instant) -> Duration {
+        self.refill(now);
+        let amount = i64::try_from(amount).unwrap_or(i64::MAX);
+        self.tokens = self.tokens.saturating_sub(amount);
+        self.debt_duration()
+    }
+
+    /// Like `consume`, but refuses to go over the maximum allowed debt
+    /// (or into any debt at all if the backlog is not allowed).
+    fn try_consume(&mut self, amount: u64, allow_backlog: bool, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        let amount = i64::try_from(amount).unwrap_or(i64::MAX);
+        let remaining = self.tokens.saturating_sub(amount);
+        let max_debt = if allow_backlog { self.max_debt } else { 0 };
+        if remaining < -max_debt {
+            return None;
+        }
+        self.tokens = remaining;
+        Some(self.debt_duration())
+    }
+
+    fn debt_duration(&self) -> Duration {
+        if self.tokens >= 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.tokens.unsigned_abs() as f64 / self.refill_per_second as f64)
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens == self.capacity
+    }
+}
+
+struct ClientState {
+    active_connections: u32,
+    recent_connections: VecDeque<Instant>,
+    bandwidth: TokenBucket,
+}
+
+impl ClientState {
+    fn new(max_bytes_per_second: u64, now: Instant) -> Self {
+        ClientState {
+            active_connections: 0,
+            recent_connections: VecDeque::new(),
+            bandwidth: TokenBucket::new(max_bytes_per_second, now),
+        }
+    }
+
+    fn forget_old_connections(&mut self, now: Instant) {
+        while let Some(opened) = self.recent_connections.front() {
+            if now.saturating_duration_since(*opened) < NEW_CONNECTIONS_WINDOW {
+                break;
+            }
+            self.recent_connections.pop_front();
+        }
+    }
+
+    fn is_idle(&mut self, now: Instant) -> bool {
+        self.forget_old_connections(now);
+        self.active_connections == 0
+            && self.recent_connections.is_empty()
+            && self.bandwidth.is_full(now)
+    }
+}
+
+// connection ids are chosen by the clients themselves, so they're only unique per sender
+type ConnectionKey = (Option<AnonymousSenderTag>, ConnectionId);
+
+struct ConnectionOwner {
+    client: ClientKey,
+    return_address: MixnetAddress,
+}
+
+#[derive(Default)]
+struct RateLimiterInner {
+    clients: HashMap<ClientKey, ClientState>,
+    connection_owners: HashMap<ConnectionKey, ConnectionOwner>,
+}
+
+/// Tracks the usage of every client and decides whether their requests should be throttled.
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    inner: Arc<Mutex<RateLimiterInner>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Self {
+        RateLimiter {
+            limits,
+            inner: Arc::new(Mutex::new(RateLimiterInner::default())),
+        }
+    }
+
+    /// Attempts to open a new connection (or udp association) on behalf of the client.
+    /// The connection counts towards the client's limits for as long as the returned permit is alive.
+    pub(crate) fn try_open_connection(
+        &self,
+        client: &MixnetAddress,
+        sender: Option<AnonymousSenderTag>,
+        connection_id: ConnectionId,
+    ) -> Result<ConnectionPermit, LimitExceeded> {
+        self.try_open_connection_at(client, (sender, connection_id), Instant::now())
+    }
+
+    /// Attempts to spend the bandwidth allowance of the client owning the specified connection
+    /// on the data it wants to send. Returns how long the data has to be held for
+    /// before it could be forwarded.
+    pub(crate) fn try_send(
+        &self,
+        sender: Option<AnonymousSenderTag>,
+        connection_id: ConnectionId,
+        bytes: usize,
+    ) -> Result<Duration, Throttled> {
+        self.try_send_at((sender, connection_id), bytes as u64, true, Instant::now())
+    }
+
+    /// Attempts to spend the bandwidth allowance of the client owning the specified udp association
+    /// on a datagram relayed in either direction. Unlike stream data, datagrams are never delayed.
+    pub(crate) fn try_send_datagram(
+        &self,
+        sender: Option<AnonymousSenderTag>,
+        connection_id: ConnectionId,
+        bytes: usize,
+    ) -> Result<(), Throttled> {
+        self.try_send_at((sender, connection_id), bytes as u64, false, Instant::now())
+            .map(|_| ())
+    }
+
+    /// Spends the bandwidth allowance of the client owning the specified connection
+    /// on the data sent back to it. Returns how long we should wait before reading
+    /// any more data for that connection.
+    pub(crate) fn record_response(
+        &self,
+        sender: Option<AnonymousSenderTag>,
+        connection_id: ConnectionId,
+        bytes: usize,
+    ) -> Duration {
+        self.record_response_at((sender, connection_id), bytes as u64, Instant::now())
+    }
+
+    fn try_open_connection_at(
+        &self,
+        return_address: &MixnetAddress,
+        connection: ConnectionKey,
+        now: Instant,
+    ) -> Result<ConnectionPermit, LimitExceeded> {
+        let client = ClientKey::from(return_address);
+        let mut guard = self.inner.lock().expect("rate limiter lock got poisoned");
+        let inner = &mut *guard;
+
+        // get rid of state of clients that haven't been doing anything recently
+        inner.clients.retain(|_, state| !state.is_idle(now));
+
+        let state = inner
+            .clients
+            .entry(client)
+            .or_insert_with(|| ClientState::new(self.limits.max_bytes_per_second, now));
+        state.forget_old_connections(now);
+
+        let max_concurrent = self.limits.max_concurrent_connections;
+        if max_concurrent != 0 && state.active_connections >= max_concurrent {
+            return Err(LimitExceeded::ConcurrentConnections {
+                limit: max_concurrent,
+            });
+        }
+
+        let max_new = self.limits.max_new_connections_per_minute;
+        if max_new != 0 && state.recent_connections.len() >= max_new as usize {
+            return Err(LimitExceeded::NewConnectionsPerMinute { limit: max_new });
+        }
+
+        state.active_connections += 1;
+        state.recent_connections.push_back(now);
+        inner.connection_owners.insert(
+            connection,
+            ConnectionOwner {
+                client,
+                return_address: return_address.clone(),
+            },
+        );
+
+        Ok(ConnectionPermit {
+            client,
+            connection,
+            inner: Arc::clone(&self.inner),
+        })
+    }
+
+    fn try_send_at(
+        &self,
+        connection: ConnectionKey,
+        bytes: u64,
+        allow_backlog: bool,
+        now: Instant,
+    ) -> Result<Duration, Throttled> {
+        let limit = self.limits.max_bytes_per_second;
+        if limit == 0 {
+            return Ok(Duration::ZERO);
+        }
+
+        let mut guard = self.inner.lock().expect("rate limiter lock got poisoned");
+        let inner = &mut *guard;
+
+        // if we don't know about the connection (for example the data arrived before the connect request),
+        // we can't attribute it to anyone. It will get dropped anyway if the connection never gets established.
+        let Some(owner) = inner.connection_owners.get(&connection) else {
+            return Ok(Duration::ZERO);
+        };
+        let Some(state) = inner.clients.get_mut(&owner.client) else {
+            return Ok(Duration::ZERO);
+        };
+
+        state
+            .bandwidth
+            .try_consume(bytes, allow_backlog, now)
+            .ok_or_else(|| Throttled {
+                return_address: owner.return_address.clone(),
+                reason: LimitExceeded::BytesPerSecond { limit },
+            })
+    }
+
+    fn record_response_at(&self, connection: ConnectionKey, bytes: u64, now: Instant) -> Duration {
+        if self.limits.max_bytes_per_second == 0 {
+            return Duration::ZERO;
+        }
+
+        let mut guard = self.inner.lock().expect("rate limiter lock got poisoned");
+        let inner = &mut *guard;
+
+        let Some(owner) = inner.connection_owners.get(&connection) else {
+            return Duration::ZERO;
+        };
+        let Some(state) = inner.clients.get_mut(&owner.client) else {
+            return Duration::ZERO;
+        };
+
+        // reading of the responses is paused for as long as the client is in debt,
+        // so there's no need to bound it here
+        state.bandwidth.consume(bytes, now)
+    }
+}
+
+/// Represents an open connection of a client. Once dropped, the connection no longer counts
+/// towards the client's concurrent connections limit.
+pub(crate) struct ConnectionPermit {
+    client: ClientKey,
+    connection: ConnectionKey,
+    inner: Arc<Mutex<RateLimiterInner>>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+
+        // make sure we don't remove ownership information of a reused connection id
+        let is_owner = inner
+            .connection_owners
+            .get(&self.connection)
+            .map(|owner| owner.client == self.client)
+            .unwrap_or_default();
+        if is_owner {
+            inner.connection_owners.remove(&self.connection);
+        }
+        if let Some(state) = inner.clients.get_mut(&self.client) {
+            state.active_connections = state.active_connections.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(
+        max_concurrent_connections: u32,
+        max_new_connections_per_minute: u32,
+        max_bytes_per_second: u64,
+    ) -> RateLimiter {
+        RateLimiter::new(RateLimits {
+            max_concurrent_connections,
+            max_new_connections_per_minute,
+            max_bytes_per_second,
+        })
+    }
+
+    fn sender(id: u8) -> AnonymousSenderTag {
+        AnonymousSenderTag::from_bytes([id; 16])
+    }
+
+    fn client(id: u8) -> MixnetAddress {
+        MixnetAddress::Anonymous(sender(id))
+    }
+
+    fn conn(client: u8, connection_id: ConnectionId) -> ConnectionKey {
+        (Some(sender(client)), connection_id)
+    }
+
+    #[test]
+    fn concurrent_connections_are_limited_per_client() {
+        let limiter = limiter(2, 0, 0);
+        let now = Instant::now();
+
+        let first = limiter
+            .try_open_connection_at(&client(1), conn(1, 1), now)
+            .unwrap();
+        let _second = limiter
+            .try_open_connection_at(&client(1), conn(1, 2), now)
+            .unwrap();
+        assert_eq!(
+            limiter
+                .try_open_connection_at(&client(1), conn(1, 3), now)
+                .err(),
+            Some(LimitExceeded::ConcurrentConnections { limit: 2 })
+        );
+
+        // other clients are not affected
+        assert!(limiter
+            .try_open_connection_at(&client(2), conn(2, 4), now)
+            .is_ok());
+
+        // and closing a connection frees up the slot
+        drop(first);
+        assert!(limiter
+            .try_open_connection_at(&client(1), conn(1, 3), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn new_connections_are_limited_per_minute() {
+        let limiter = limiter(0, 2, 0);
+        let now = Instant::now();
+
+        drop(
+            limiter
+                .try_open_connection_at(&client(1), conn(1, 1), now)
+                .unwrap(),
+        );
+        drop(
+            limiter
+                .try_open_connection_at(&client(1), conn(1, 2), now)
+                .unwrap(),
+        );
+        assert_eq!(
+            limiter
+                .try_open_connection_at(&client(1), conn(1, 3), now)
+                .err(),
+            Some(LimitExceeded::NewConnectionsPerMinute { limit: 2 })
+        );
+
+        let later = now + NEW_CONNECTIONS_WINDOW;
+        assert!(limiter
+            .try_open_connection_at(&client(1), conn(1, 3), later)
+            .is_ok());
+    }
+
+    #[test]
+    fn bandwidth_is_limited_per_client() {
+        let limiter = limiter(0, 0, 100);
+        let now = Instant::now();
+
+        let _first = limiter
+            .try_open_connection_at(&client(1), conn(1, 1), now)
+            .unwrap();
+        let _second = limiter
+            .try_open_connection_at(&client(1), conn(1, 2), now)
+            .unwrap();
+        let _other = limiter
+            .try_open_connection_at(&client(2), conn(2, 3), now)
+            .unwrap();
+
+        // the allowance is shared between all connections of the same client
+        assert_eq!(
+            limiter.try_send_at(conn(1, 1), 150, true, now).unwrap(),
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.try_send_at(conn(1, 2), 100, true, now).unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            limiter.try_send_at(conn(2, 3), 200, true, now).unwrap(),
+            Duration::ZERO
+        );
+
+        // data over the backlog limit is rejected
+        assert_eq!(
+            limiter
+                .try_send_at(conn(1, 2), 1000, true, now)
+                .unwrap_err()
+                .reason,
+            LimitExceeded::BytesPerSecond { limit: 100 }
+        );
+
+        // datagrams can't be queued up at all
+        assert!(limiter.try_send_at(conn(2, 3), 1, false, now).is_err());
+
+        // the allowance gets replenished with time
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            limiter.try_send_at(conn(1, 2), 50, true, later).unwrap(),
+            Duration::ZERO
+        );
+
+        // and we don't throttle data we can't attribute to anyone
+        assert_eq!(
+            limiter.try_send_at(conn(1, 42), 1000, true, now).unwrap(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn responses_count_towards_the_bandwidth() {
+        let limiter = limiter(0, 0, 100);
+        let now = Instant::now();
+
+        let _conn = limiter
+            .try_open_connection_at(&client(1), conn(1, 1), now)
+            .unwrap();
+
+        assert_eq!(
+            limiter.record_response_at(conn(1, 1), 200, now),
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.record_response_at(conn(1, 1), 2000, now),
+            Duration::from_secs(20)
+        );
+
+        // so the client can't send anything else until the debt is paid off
+        assert!(limiter.try_send_at(conn(1, 1), 1, true, now).is_err());
+        let later = now + Duration::from_secs(20);
+        assert_eq!(
+            limiter.try_send_at(conn(1, 1), 100, true, later).unwrap(),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn connection_ids_are_scoped_to_their_sender() {
+        let limiter = limiter(0, 0, 100);
+        let now = Instant::now();
+
+        let first = limiter
+            .try_open_connection_at(&client(1), conn(1, 1), now)
+            .unwrap();
+        let _second = limiter
+            .try_open_connection_at(&client(2), conn(2, 1), now)
+            .unwrap();
+
+        // another client reusing the same connection id can't spend our allowance
+        assert_eq!(
+            limiter.try_send_at(conn(2, 1), 300, true, now).unwrap(),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            limiter.try_send_at(conn(1, 1), 200, true, now).unwrap(),
+            Duration::ZERO
+        );
+
+        // and closing the connection of the first client leaves the other one intact
+        drop(first);
+        assert!(limiter.try_send_at(conn(2, 1), 1000, true, now).is_err());
+    }
+
+    #[test]
+    fn zero_disables_limits() {
+        let limiter = limiter(0, 0, 0);
+        let now = Instant::now();
+
+        let mut permits = Vec::new();
+        for conn_id in 0..1000 {
+            permits.push(
+                limiter
+                    .try_open_connection_at(&client(1), conn(1, conn_id), now)
+                    .unwrap(),
+            );
+            assert_eq!(
+                limiter
+                    .try_send_at(conn(1, conn_id), 1_000_000, true, now)
+                    .unwrap(),
+                Duration::ZERO
+            );
+            assert_eq!(
+                limiter.record_response_at(conn(1, conn_id), 1_000_000, now),
+                Duration::ZERO
+            );
+        }
+    }
+}
@@ -143,6 +143,25 @@ impl MixnetMessage {
         Self::new_network_data_response(address, request_version, connection_id, response_content)
     }
 
+    pub(crate) fn new_datagram_response(
+        address: MixnetAddress,
+        request_version: RequestVersion<Socks5Request>,
+        connection_id: ConnectionId,
+        source_addr: String,
+        data: Vec<u8>,
+    ) -> Self {
+        let res = Socks5Response::new_datagram(
+            request_version.provider_protocol,
+            connection_id,
+            source_addr,
+            data,
+        );
+        let msg =
+            Socks5ProviderResponse::new_provider_data(request_version.provider_interface, res);
+
+        Self::new_provider_response(address, connection_id, msg)
+    }
+
     pub(crate) fn data_size(&self) -> usize {
         self.data.len()
     }
@@ -176,16 +195,16 @@ impl MixnetAddress {
 
     pub(super) fn send_back_to(self, message: Vec<u8>, connection_id: u64) -> InputMessage {
         match self {
-            MixnetAddress::Known(recipient) => InputMessage::Regular {
-                recipient: *recipient,
-                data: message,
-                lane: TransmissionLane::ConnectionId(connection_id),
-            },
-            MixnetAddress::Anonymous(sender_tag) => InputMessage::Reply {
-                recipient_tag: sender_tag,
-                data: message,
-                lane: TransmissionLane::ConnectionId(connection_id),
-            },
+            MixnetAddress::Known(recipient) => InputMessage::new_regular(
+                *recipient,
+                message,
+                TransmissionLane::ConnectionId(connection_id),
+            ),
+            MixnetAddress::Anonymous(sender_tag) => InputMessage::new_reply(
+                sender_tag,
+                message,
+                TransmissionLane::ConnectionId(connection_id),
+            ),
         }
     }
 }
@@ -5,11 +5,12 @@ use crate::reply;
 use crate::reply::MixnetMessage;
 use nym_service_providers_common::interface::RequestVersion;
 use nym_socks5_proxy_helpers::connection_controller::ConnectionReceiver;
-use nym_socks5_proxy_helpers::proxy_runner::{MixProxySender, ProxyRunner};
+use nym_socks5_proxy_helpers::proxy_runner::{MixProxySender, ProxyRunner, ReadThrottle};
 use nym_socks5_requests::{ConnectionId, RemoteAddress, Socks5Request};
 use nym_task::connections::LaneQueueLengths;
 use nym_task::TaskClient;
 use std::io;
+use std::net::SocketAddr;
 use tokio::net::TcpStream;
 
 /// An outbound TCP connection between the Socks5 service provider, which makes
@@ -24,12 +25,14 @@ pub(crate) struct Connection {
 }
 
 impl Connection {
+    /// Connects to the first reachable of the `resolved` addresses of the remote.
     pub(crate) async fn new(
         id: ConnectionId,
         address: RemoteAddress,
+        resolved: &[SocketAddr],
         return_address: reply::MixnetAddress,
     ) -> io::Result<Self> {
-        let conn = TcpStream::connect(&address).await?;
+        let conn = TcpStream::connect(resolved).await?;
 
         Ok(Connection {
             id,
@@ -45,6 +48,7 @@ impl Connection {
         mix_receiver: ConnectionReceiver,
         mix_sender: MixProxySender<MixnetMessage>,
         lane_queue_lengths: LaneQueueLengths,
+        read_throttle: ReadThrottle,
         shutdown: TaskClient,
     ) {
         let stream = self.conn.take().unwrap();
@@ -61,6 +65,7 @@ impl Connection {
             Some(lane_queue_lengths),
             shutdown,
         )
+        .with_read_throttle(read_throttle)
         .run(move |conn_id, read_data, socket_closed| {
             MixnetMessage::new_network_data_response_content(
                 return_address.clone(),
@@ -0,0 +1,530 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::allowed_hosts::{self, Protocol, StoredPolicy};
+use crate::rate_limit::RateLimiter;
+use crate::reply;
+use crate::reply::MixnetMessage;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use nym_service_providers_common::interface::RequestVersion;
+use nym_socks5_proxy_helpers::proxy_runner::MixProxySender;
+use nym_socks5_requests::{ConnectionId, RemoteAddress, Socks5Request};
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_task::TaskClient;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// Size of the largest possible UDP payload.
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Associations that haven't relayed any datagram in either direction for this long get closed.
+const ASSOCIATION_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Replies are only relayed from destinations the client has sent a datagram to within this period.
+const CONTACTED_EXPIRY: Duration = ASSOCIATION_IDLE_TIMEOUT;
+
+/// Maximum number of recently contacted destinations remembered by a single association.
+/// Once it's reached, the least recently contacted destination gets forgotten.
+const MAX_CONTACTED_DESTINATIONS: usize = 1024;
+
+type OutboundDatagramSender = mpsc::UnboundedSender<(RemoteAddress, Vec<u8>)>;
+type OutboundDatagramReceiver = mpsc::UnboundedReceiver<(RemoteAddress, Vec<u8>)>;
+
+/// Handle to a running [`Association`] kept by the service provider for forwarding datagrams
+/// received from the mixnet.
+pub(crate) struct AssociationHandle {
+    sender: OutboundDatagramSender,
+    return_address: reply::MixnetAddress,
+
+    /// Destinations that have already passed the outbound request filter check.
+    allowed_destinations: HashSet<RemoteAddress>,
+
+    /// Generation of the policy the destinations have been checked against.
+    policy_generation: u64,
+}
+
+impl AssociationHandle {
+    pub(crate) fn return_address(&self) -> &reply::MixnetAddress {
+        &self.return_address
+    }
+
+    /// Checks whether the destination has already been allowed by the specified version of the policy.
+    pub(crate) fn is_allowed(
+        &mut self,
+        destination: &RemoteAddress,
+        policy_generation: u64,
+    ) -> bool {
+        if self.policy_generation != policy_generation {
+            // the policy got reloaded, so everything has to be checked again
+            self.allowed_destinations.clear();
+            self.policy_generation = policy_generation;
+        }
+        self.allowed_destinations.contains(destination)
+    }
+
+    pub(crate) fn allow(&mut self, destination: RemoteAddress, policy_generation: u64) {
+        if self.policy_generation == policy_generation {
+            self.allowed_destinations.insert(destination);
+        }
+    }
+
+    /// Checks whether the underlying association is still running.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Forwards the datagram to the association. Returns `false` if it is no longer running.
+    pub(crate) fn send(&self, destination: RemoteAddress, data: Vec<u8>) -> bool {
+        self.sender.unbounded_send((destination, data)).is_ok()
+    }
+}
+
+/// An outbound UDP association between the Socks5 service provider and any number of
+/// remote hosts, relaying datagrams on behalf of a single client.
+#[derive(Debug)]
+pub(crate) struct Association {
+    id: ConnectionId,
+    sender_tag: Option<AnonymousSenderTag>,
+    socket_v4: UdpSocket,
+
+    /// Relay socket for IPv6 destinations, if IPv6 is available on this host.
+    socket_v6: Option<UdpSocket>,
+    return_address: reply::MixnetAddress,
+    outbound: OutboundDatagramReceiver,
+    policy: StoredPolicy,
+    rate_limiter: RateLimiter,
+
+    /// Resolved addresses of the destinations that are allowed by the policy
+    /// alongside the generation of the policy they've been checked against.
+    resolved: HashMap<RemoteAddress, SocketAddr>,
+    resolved_generation: u64,
+
+    /// Addresses the client has recently sent datagrams to alongside the time of the last one.
+    /// Only replies coming from them are relayed back.
+    contacted: HashMap<SocketAddr, Instant>,
+}
+
+impl Association {
+    pub(crate) async fn new(
+        id: ConnectionId,
+        sender_tag: Option<AnonymousSenderTag>,
+        return_address: reply::MixnetAddress,
+        policy: StoredPolicy,
+        rate_limiter: RateLimiter,
+    ) -> io::Result<(Self, AssociationHandle)> {
+        let socket_v4 = UdpSocket::bind("0.0.0.0:0").await?;
+        let socket_v6 = match UdpSocket::bind("[::]:0").await {
+            Ok(socket) => Some(socket),
+            Err(err) => {
+                log::debug!(
+                    "UDP association {id}: IPv6 destinations are not going to be reachable: {err}"
+                );
+                None
+            }
+        };
+        let (sender, outbound) = mpsc::unbounded();
+        let policy_generation = policy.generation();
+
+        let association = Association {
+            id,
+            sender_tag,
+            socket_v4,
+            socket_v6,
+            return_address: return_address.clone(),
+            outbound,
+            policy,
+            rate_limiter,
+            resolved: HashMap::new(),
+            resolved_generation: policy_generation,
+            contacted: HashMap::new(),
+        };
+        let handle = AssociationHandle {
+            sender,
+            return_address,
+            allowed_destinations: HashSet::new(),
+            policy_generation,
+        };
+        Ok((association, handle))
+    }
+
+    /// Resolves the destination into an address allowed by the policy, remembering the result
+    /// until the policy changes.
+    async fn resolve(
+        &mut self,
+        destination: &RemoteAddress,
+    ) -> Result<SocketAddr, allowed_hosts::DenialReason> {
+        let generation = self.policy.generation();
+        if generation != self.resolved_generation {
+            self.resolved.clear();
+            self.resolved_generation = generation;
+        }
+        if let Some(address) = self.resolved.get(destination) {
+            return Ok(*address);
+        }
+
+        let address = allowed_hosts::resolve_allowed(&self.policy, destination, Protocol::Udp)
+            .await?
+            .into_iter()
+            .find(|address| self.socket_for(address).is_some())
+            .ok_or(allowed_hosts::DenialReason::UnresolvableHost)?;
+        self.resolved.insert(destination.clone(), address);
+        Ok(address)
+    }
+
+    fn socket_for(&self, address: &SocketAddr) -> Option<&UdpSocket> {
+        match address {
+            SocketAddr::V4(_) => Some(&self.socket_v4),
+            SocketAddr::V6(_) => self.socket_v6.as_ref(),
+        }
+    }
+
+    fn mark_contacted(&mut self, address: SocketAddr) {
+        let now = Instant::now();
+        if !self.contacted.contains_key(&address)
+            && self.contacted.len() >= MAX_CONTACTED_DESTINATIONS
+        {
+            self.contacted
+                .retain(|_, last_contacted| now - *last_contacted < CONTACTED_EXPIRY);
+
+            if self.contacted.len() >= MAX_CONTACTED_DESTINATIONS {
+                let least_recent = self
+                    .contacted
+                    .iter()
+                    .min_by_key(|(_, last_contacted)| **last_contacted)
+                    .map(|(address, _)| *address);
+                if let Some(least_recent) = least_recent {
+                    self.contacted.remove(&least_recent);
+                }
+            }
+        }
+        self.contacted.insert(address, now);
+    }
+
+    fn was_recently_contacted(&self, address: &SocketAddr) -> bool {
+        self.contacted
+            .get(address)
+            .map(|last_contacted| last_contacted.elapsed() < CONTACTED_EXPIRY)
+            .unwrap_or_default()
+    }
+
+    async fn send_datagram(
+        &mut self,
+        destination: &RemoteAddress,
+        data: &[u8],
+        remote_version: &RequestVersion<Socks5Request>,
+        mix_sender: &MixProxySender<MixnetMessage>,
+    ) {
+        let address = match self.resolve(destination).await {
+            Ok(address) => address,
+            Err(reason) => {
+                let mixnet_message = MixnetMessage::new_connection_error(
+                    self.return_address.clone(),
+                    remote_version.clone(),
+                    self.id,
+                    format!("Domain {destination:?} failed filter check: {reason}"),
+                );
+                mix_sender
+                    .send(mixnet_message)
+                    .await
+                    .expect("InputMessageReceiver has stopped receiving!");
+                return;
+            }
+        };
+
+        // the address has only been resolved if we have a socket of matching family
+        let Some(socket) = self.socket_for(&address) else {
+            return;
+        };
+        match socket.send_to(data, address).await {
+            Ok(_) => self.mark_contacted(address),
+            Err(err) => log::debug!(
+                "UDP association {}: failed to send datagram to {destination}: {err}",
+                self.id
+            ),
+        }
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        remote_version: RequestVersion<Socks5Request>,
+        mix_sender: MixProxySender<MixnetMessage>,
+        mut shutdown: TaskClient,
+    ) {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let idle_timeout = tokio::time::sleep(ASSOCIATION_IDLE_TIMEOUT);
+        tokio::pin!(idle_timeout);
+
+        loop {
+            tokio::select! {
+                outbound = self.outbound.next() => {
+                    let Some((destination, data)) = outbound else {
+                        log::trace!("UDP association {}: closed by the client", self.id);
+                        break;
+                    };
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                    self.send_datagram(&destination, &data, &remote_version, &mix_sender).await;
+                }
+                received = recv_from_any(&self.socket_v4, self.socket_v6.as_ref(), &mut buf) => {
+                    let (n, source) = match received {
+                        Ok(received) => received,
+                        Err(err) => {
+                            log::debug!("UDP association {}: failed to receive datagram: {err}", self.id);
+                            continue;
+                        }
+                    };
+                    if !self.was_recently_contacted(&source) {
+                        log::debug!("UDP association {}: dropping datagram from unexpected source {source}", self.id);
+                        continue;
+                    }
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+
+                    // replies count towards the client's bandwidth allowance as well
+                    if let Err(throttled) = self.rate_limiter.try_send_datagram(self.sender_tag, self.id, n) {
+                        log::debug!("UDP association {}: dropping datagram from {source}: {}", self.id, throttled.reason);
+                        continue;
+                    }
+
+                    let mixnet_message = MixnetMessage::new_datagram_response(
+                        self.return_address.clone(),
+                        remote_version.clone(),
+                        self.id,
+                        source.to_string(),
+                        buf[..n].to_vec(),
+                    );
+                    mix_sender
+                        .send(mixnet_message)
+                        .await
+                        .expect("InputMessageReceiver has stopped receiving!");
+                }
+                _ = &mut idle_timeout => {
+                    log::debug!("UDP association {}: closing after being idle for too long", self.id);
+                    break;
+                }
+                _ = shutdown.recv() => {
+                    log::trace!("UDP association {}: Received shutdown", self.id);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Receives the next datagram arriving at either of the relay sockets.
+async fn recv_from_any(
+    socket_v4: &UdpSocket,
+    socket_v6: Option<&UdpSocket>,
+    buf: &mut [u8],
+) -> io::Result<(usize, SocketAddr)> {
+    loop {
+        let socket = match socket_v6 {
+            Some(socket_v6) => tokio::select! {
+                ready = socket_v4.readable() => ready.map(|_| socket_v4)?,
+                ready = socket_v6.readable() => ready.map(|_| socket_v6)?,
+            },
+            None => {
+                socket_v4.readable().await?;
+                socket_v4
+            }
+        };
+
+        match socket.try_recv_from(buf) {
+            // readiness events can be spurious
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => continue,
+            received => return received,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allowed_hosts::policy::Policy;
+    use crate::config::RateLimits;
+    use crate::core::new_legacy_request_version;
+
+    // long enough for a datagram to make it through the loopback interface
+    const DELIVERY_TIMEOUT: Duration = Duration::from_millis(500);
+
+    struct TestAssociation {
+        handle: AssociationHandle,
+        local_v4: SocketAddr,
+        local_v6: Option<SocketAddr>,
+        mix_receiver: tokio::sync::mpsc::Receiver<MixnetMessage>,
+    }
+
+    async fn start_association(policy: StoredPolicy) -> TestAssociation {
+        let return_address =
+            reply::MixnetAddress::Anonymous(AnonymousSenderTag::from_bytes([1; 16]));
+        let rate_limiter = RateLimiter::new(RateLimits {
+            max_concurrent_connections: 0,
+            max_new_connections_per_minute: 0,
+            max_bytes_per_second: 0,
+        });
+        let (association, handle) =
+            Association::new(42, None, return_address, policy, rate_limiter)
+                .await
+                .unwrap();
+        let local_v4 = association.socket_v4.local_addr().unwrap();
+        let local_v6 = association
+            .socket_v6
+            .as_ref()
+            .map(|socket| socket.local_addr().unwrap());
+
+        let (mix_sender, mix_receiver) = tokio::sync::mpsc::channel(16);
+        tokio::spawn(association.run(
+            new_legacy_request_version(),
+            mix_sender,
+            TaskClient::dummy(),
+        ));
+
+        TestAssociation {
+            handle,
+            local_v4,
+            local_v6,
+            mix_receiver,
+        }
+    }
+
+    async fn recv_datagram(socket: &UdpSocket) -> Option<(Vec<u8>, SocketAddr)> {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let (n, source) = tokio::time::timeout(DELIVERY_TIMEOUT, socket.recv_from(&mut buf))
+            .await
+            .ok()?
+            .unwrap();
+        Some((buf[..n].to_vec(), source))
+    }
+
+    async fn recv_mix_message(
+        receiver: &mut tokio::sync::mpsc::Receiver<MixnetMessage>,
+    ) -> Option<MixnetMessage> {
+        tokio::time::timeout(DELIVERY_TIMEOUT, receiver.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    fn relay_address(local: SocketAddr, destination: SocketAddr) -> SocketAddr {
+        SocketAddr::new(destination.ip(), local.port())
+    }
+
+    #[tokio::test]
+    async fn replies_are_only_relayed_from_contacted_destinations() {
+        let mut association = start_association(Policy::default().into()).await;
+        let destination = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let other_source = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let destination_addr = destination.local_addr().unwrap();
+        let relay = relay_address(association.local_v4, destination_addr);
+
+        // nothing has been contacted yet
+        destination.send_to(b"unsolicited", relay).await.unwrap();
+        assert!(recv_mix_message(&mut association.mix_receiver)
+            .await
+            .is_none());
+
+        assert!(association
+            .handle
+            .send(destination_addr.to_string(), b"ping".to_vec()));
+        let (data, source) = recv_datagram(&destination).await.unwrap();
+        assert_eq!(data, b"ping");
+        assert_eq!(source.port(), association.local_v4.port());
+
+        // a different source on the same host is not the contacted destination
+        other_source.send_to(b"spoofed", relay).await.unwrap();
+        assert!(recv_mix_message(&mut association.mix_receiver)
+            .await
+            .is_none());
+
+        destination.send_to(b"pong", relay).await.unwrap();
+        let reply = recv_mix_message(&mut association.mix_receiver)
+            .await
+            .unwrap();
+        assert_eq!(reply.connection_id, 42);
+    }
+
+    #[tokio::test]
+    async fn ipv6_destinations_are_relayed() {
+        let Ok(destination) = UdpSocket::bind("[::1]:0").await else {
+            // IPv6 is not available on this host
+            return;
+        };
+        let mut association = start_association(Policy::default().into()).await;
+        let Some(local_v6) = association.local_v6 else {
+            return;
+        };
+        let destination_addr = destination.local_addr().unwrap();
+
+        assert!(association
+            .handle
+            .send(destination_addr.to_string(), b"ping".to_vec()));
+        let (data, source) = recv_datagram(&destination).await.unwrap();
+        assert_eq!(data, b"ping");
+        assert_eq!(source.port(), local_v6.port());
+
+        destination
+            .send_to(b"pong", relay_address(local_v6, destination_addr))
+            .await
+            .unwrap();
+        assert!(recv_mix_message(&mut association.mix_receiver)
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn contacted_destinations_expire() {
+        let return_address =
+            reply::MixnetAddress::Anonymous(AnonymousSenderTag::from_bytes([1; 16]));
+        let (mut association, _handle) = Association::new(
+            42,
+            None,
+            return_address,
+            Policy::default().into(),
+            RateLimiter::new(RateLimits::default()),
+        )
+        .await
+        .unwrap();
+        let destination: SocketAddr = "127.0.0.1:1234".parse().unwrap();
+
+        assert!(!association.was_recently_contacted(&destination));
+        association.mark_contacted(destination);
+        assert!(association.was_recently_contacted(&destination));
+        assert!(!association.was_recently_contacted(&"127.0.0.1:1235".parse().unwrap()));
+
+        let expired = Instant::now().checked_sub(CONTACTED_EXPIRY).unwrap();
+        association.contacted.insert(destination, expired);
+        assert!(!association.was_recently_contacted(&destination));
+    }
+
+    #[tokio::test]
+    async fn destinations_denied_after_policy_reload_are_no_longer_relayed() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy_path = dir.path().join("policy.txt");
+        let policy = StoredPolicy::new(&policy_path).unwrap();
+        let mut association = start_association(policy.clone()).await;
+        let destination = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let destination_addr = destination.local_addr().unwrap();
+
+        assert!(association
+            .handle
+            .send(destination_addr.to_string(), b"allowed".to_vec()));
+        assert_eq!(recv_datagram(&destination).await.unwrap().0, b"allowed");
+
+        std::fs::write(&policy_path, "deny 127.0.0.0/8\n").unwrap();
+        policy.reload().await.unwrap();
+
+        assert!(association
+            .handle
+            .send(destination_addr.to_string(), b"denied".to_vec()));
+        assert!(recv_datagram(&destination).await.is_none());
+
+        // the client gets told about the denial instead
+        let error = recv_mix_message(&mut association.mix_receiver)
+            .await
+            .unwrap();
+        assert_eq!(error.connection_id, 42);
+    }
+}
@@ -33,15 +33,24 @@ const REMOTE_SOURCE_OF_STATS_PROVIDER_CONFIG: &str =
 #[derive(Clone, Debug)]
 pub struct StatsData {
     client_processed_bytes: HashMap<String, u32>,
+    client_throttled_requests: HashMap<String, u32>,
 }
 
 impl StatsData {
     pub fn new() -> Self {
         StatsData {
             client_processed_bytes: HashMap::new(),
+            client_throttled_requests: HashMap::new(),
         }
     }
 
+    pub fn throttled(&mut self, remote_addr: &str) {
+        *self
+            .client_throttled_requests
+            .entry(remote_addr.to_string())
+            .or_insert(0) += 1;
+    }
+
     pub fn processed(&mut self, remote_addr: &str, bytes: u32) {
         if let Some(curr_bytes) = self.client_processed_bytes.get_mut(remote_addr) {
             *curr_bytes += bytes;
@@ -132,6 +141,7 @@ impl StatisticsCollector for ServiceStatisticsCollector {
                 .client_processed_bytes
                 .keys()
                 .chain(response_data_bytes.client_processed_bytes.keys())
+                .chain(request_data_bytes.client_throttled_requests.keys())
                 .cloned()
                 .collect();
             services
@@ -147,11 +157,15 @@ impl StatisticsCollector for ServiceStatisticsCollector {
                         .get(&requested_service)
                         .copied()
                         .unwrap_or(0);
-                    nym_statistics_common::StatsData::Service(StatsServiceData::new(
-                        requested_service,
-                        request_bytes,
-                        response_bytes,
-                    ))
+                    let throttled_requests = request_data_bytes
+                        .client_throttled_requests
+                        .get(&requested_service)
+                        .copied()
+                        .unwrap_or(0);
+                    nym_statistics_common::StatsData::Service(
+                        StatsServiceData::new(requested_service, request_bytes, response_bytes)
+                            .with_throttled_requests(throttled_requests),
+                    )
                 })
                 .collect()
         };
@@ -210,7 +224,10 @@ impl StatisticsCollector for ServiceStatisticsCollector {
     }
 
     async fn reset_stats(&mut self) {
-        self.request_stats_data.write().await.client_processed_bytes = HashMap::new();
+        let mut request_stats_data = self.request_stats_data.write().await;
+        request_stats_data.client_processed_bytes = HashMap::new();
+        request_stats_data.client_throttled_requests = HashMap::new();
+        drop(request_stats_data);
         self.response_stats_data
             .write()
             .await
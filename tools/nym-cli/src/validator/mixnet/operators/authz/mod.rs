@@ -0,0 +1,21 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_cli_commands::context::{create_signing_client, ClientArgs};
+use nym_network_defaults::NymNetworkDetails;
+
+pub(crate) async fn execute(
+    global_args: ClientArgs,
+    authz: nym_cli_commands::validator::mixnet::operators::authz::MixnetOperatorsAuthz,
+    network_details: &NymNetworkDetails,
+) -> anyhow::Result<()> {
+    match authz.command {
+        nym_cli_commands::validator::mixnet::operators::authz::MixnetOperatorsAuthzCommands::Grant(args) => {
+            nym_cli_commands::validator::mixnet::operators::authz::grant::grant(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::operators::authz::MixnetOperatorsAuthzCommands::Revoke(args) => {
+            nym_cli_commands::validator::mixnet::operators::authz::revoke::revoke(args, create_signing_client(global_args, network_details)?).await
+        }
+    }
+    Ok(())
+}
@@ -4,6 +4,7 @@
 use nym_cli_commands::context::ClientArgs;
 use nym_network_defaults::NymNetworkDetails;
 
+pub(crate) mod authz;
 pub(crate) mod gateways;
 pub(crate) mod mixnodes;
 
@@ -19,6 +20,9 @@ pub(crate) async fn execute(
         nym_cli_commands::validator::mixnet::operators::MixnetOperatorsCommands::Mixnode(
             mixnode,
         ) => mixnodes::execute(global_args, mixnode, network_details).await?,
+        nym_cli_commands::validator::mixnet::operators::MixnetOperatorsCommands::Authz(authz) => {
+            authz::execute(global_args, authz, network_details).await?
+        }
     }
     Ok(())
 }
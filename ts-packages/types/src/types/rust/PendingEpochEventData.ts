@@ -4,6 +4,9 @@ import type { DecCoin } from './DecCoin';
 export type PendingEpochEventData =
   | { Delegate: { owner: string; mix_id: number; amount: DecCoin; proxy: string | null } }
   | { Undelegate: { owner: string; mix_id: number; proxy: string | null } }
+  | {
+      Redelegate: { owner: string; from_mix_id: number; to_mix_id: number; amount: DecCoin; proxy: string | null };
+    }
   | { PledgeMore: { mix_id: number; amount: DecCoin } }
   | { UnbondMixnode: { mix_id: number } }
   | { UpdateActiveSetSize: { new_size: number } };